@@ -19,6 +19,7 @@ import gzip
 import io
 import json
 import os
+import re
 import shutil
 import sys
 from datetime import datetime, timezone
@@ -254,6 +255,17 @@ def list_image_objects_for_colony(
     return sorted(keys)
 
 
+def _hist_key_value(key: str) -> float:
+    """
+    Numeric value of a distribution key. Bucketed metrics (age, food) use ranged keys like
+    "1024-2047"; those count as their lower bound, so percentiles are bucket lower bounds.
+    """
+    match = re.fullmatch(r"(-?\d+)-(-?\d+)", key)
+    if match:
+        return float(match.group(1))
+    return float(key)
+
+
 def _summarize_numeric_hist(
     hist: Dict[str, Any],
     prefix: str,
@@ -275,7 +287,7 @@ def _summarize_numeric_hist(
     items: List[Tuple[float, int]] = []
     for k, v in dist.items():
         try:
-            value = float(k)
+            value = _hist_key_value(k)
             count = int(v)
         except (TypeError, ValueError):
             continue
@@ -0,0 +1,38 @@
+//! Drives one shard through SimulationHandle, without a coordinator or any networking, and
+//! prints the population every 50 ticks:
+//!
+//!     cargo run --release -p backend --example embedded_simulation
+
+use backend::simulation::{SimulationConfig, SimulationHandle};
+use shared::be_api::{COLONY_LIFE_INITIAL_RULES, SeedingOptions, Shard, StatMetric};
+
+const TICKS: u32 = 1000;
+const SAMPLE_EVERY: u32 = 50;
+
+/// Living creatures in the shard, from the Occupancy histogram (1 for an occupied cell)
+fn population(simulation: &SimulationHandle, shard: &Shard) -> u64 {
+    let stats = simulation.stats(shard, &[StatMetric::Occupancy]).expect("shard was added");
+    stats.metrics.iter()
+        .flat_map(|(_, buckets)| buckets)
+        .filter(|bucket| bucket.value == 1)
+        .map(|bucket| bucket.occs)
+        .sum()
+}
+
+fn main() {
+    let shard = Shard { x: 0, y: 0, width: 200, height: 200 };
+    let mut simulation = SimulationHandle::init(SimulationConfig {
+        width: shard.width,
+        height: shard.height,
+        seeding: SeedingOptions { seed: Some(42), ..SeedingOptions::default() },
+    }).expect("valid colony config");
+    simulation.add_shard(shard, COLONY_LIFE_INITIAL_RULES, None).expect("valid shard");
+
+    println!("tick,population");
+    println!("0,{}", population(&simulation, &shard));
+    for _ in 0..TICKS / SAMPLE_EVERY {
+        simulation.tick(SAMPLE_EVERY);
+        let tick = simulation.current_tick(&shard).expect("shard was added");
+        println!("{},{}", tick, population(&simulation, &shard));
+    }
+}
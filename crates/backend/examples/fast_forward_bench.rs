@@ -0,0 +1,96 @@
+//! Tick throughput of one dense shard through the backend tick path, with the presentation
+//! buffers refreshed in the background as for a watched colony, then in fast-forward mode:
+//!
+//!     cargo run --release -p backend --example fast_forward_bench
+
+use backend::backend_config;
+use backend::be_server::dispatch_request;
+use backend::be_ticker::step_ticks;
+use backend::fast_forward::set_fast_forward;
+use backend::http_server::start_http_server;
+use backend::presentation_snapshots::{start_snapshot_refresher, SnapshotConfig};
+use backend::rate_limiter::RateLimitConfig;
+use shared::be_api::{BackendRequest, ColonyLifeRules, COLONY_LIFE_INITIAL_RULES, InitColonyRequest, InitColonyShardRequest, SeedingOptions, Shard};
+use shared::cluster_topology::{ClusterTopology, HostInfo};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+const HTTP_PORT: u16 = 18104;
+const PHASE: Duration = Duration::from_secs(10);
+const LAYERS: [&str; 2] = ["creature-size", "age"];
+
+const RULES: ColonyLifeRules = ColonyLifeRules {
+    color_drift_per_generation: 0,
+    color_mutation_chance: 0,
+    ..COLONY_LIFE_INITIAL_RULES
+};
+
+fn shard() -> Shard {
+    Shard { x: 0, y: 0, width: 400, height: 400 }
+}
+
+async fn init_colony() {
+    let this_backend = HostInfo::new("127.0.0.1".to_string(), 18105);
+    backend_config::set_backend_hostname(this_backend.hostname.clone());
+    backend_config::set_backend_port(this_backend.port);
+    backend_config::set_rate_limit_config(RateLimitConfig { enabled: false, ..RateLimitConfig::default() });
+    backend_config::set_snapshot_config(SnapshotConfig { enabled: true, refresh_hz: 10.0, ..SnapshotConfig::default() });
+
+    let topology = ClusterTopology {
+        coordinator_host: HostInfo::new("127.0.0.1".to_string(), 18106),
+        backend_hosts: vec![this_backend.clone()],
+        shard_to_host: HashMap::from([(shard(), this_backend)]),
+    };
+    dispatch_request(BackendRequest::InitColony(InitColonyRequest { width: shard().width, height: shard().height, colony_life_rules: RULES })).await;
+    dispatch_request(BackendRequest::InitColonyShard(InitColonyShardRequest {
+        shard: shard(),
+        colony_life_rules: RULES,
+        topology: Some(topology),
+        seeding: SeedingOptions { density: 0.8, seed: Some(11), ..SeedingOptions::default() },
+        topography_data: None,
+        awaiting_topography: false,
+        colony_instance_id: None,
+        lease_epoch: 1,
+    })).await;
+}
+
+/// A GET whose response is dropped; it only makes the layer one the refresher renders
+async fn touch(path: &str) {
+    if let Ok(mut stream) = TcpStream::connect(("127.0.0.1", HTTP_PORT)).await {
+        let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path);
+        if stream.write_all(request.as_bytes()).await.is_ok() {
+            let _ = stream.read_to_end(&mut Vec::new()).await;
+        }
+    }
+}
+
+/// Ticks per second over one phase, stepping as fast as the tick path allows
+async fn measure_ticks() -> f64 {
+    let start = Instant::now();
+    let mut ticks = 0;
+    while start.elapsed() < PHASE {
+        step_ticks(None, 1).await;
+        ticks += 1;
+    }
+    ticks as f64 / start.elapsed().as_secs_f64()
+}
+
+#[tokio::main(flavor = "multi_thread", worker_threads = 4)]
+async fn main() {
+    init_colony().await;
+    tokio::spawn(start_http_server(HTTP_PORT));
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    for layer in LAYERS {
+        touch(&format!("/api/shard/{}/layer/{}", shard().to_id(), layer)).await;
+    }
+    start_snapshot_refresher();
+
+    let normal = measure_ticks().await;
+    println!("Normal mode: {:.1} ticks/s", normal);
+
+    set_fast_forward(true).await;
+    let fast_forward = measure_ticks().await;
+    println!("Fast-forward: {:.1} ticks/s ({:+.0}%)", fast_forward, (fast_forward / normal - 1.0) * 100.0);
+}
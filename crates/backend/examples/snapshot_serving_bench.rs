@@ -0,0 +1,139 @@
+//! Tick throughput of one shard without HTTP traffic and under heavy image/layer load.
+//! Snapshot serving follows the backend's environment, so compare the two modes with
+//!
+//!     cargo run --release -p backend --example snapshot_serving_bench
+//!     SNAPSHOT_SERVING=true cargo run --release -p backend --example snapshot_serving_bench
+
+use backend::backend_config;
+use backend::be_server::dispatch_request;
+use backend::colony::Colony;
+use backend::http_server::start_http_server;
+use backend::presentation_snapshots::{start_snapshot_refresher, SnapshotConfig};
+use backend::rate_limiter::RateLimitConfig;
+use shared::be_api::{BackendRequest, ColonyLifeRules, COLONY_LIFE_INITIAL_RULES, InitColonyRequest, InitColonyShardRequest, SeedingOptions, Shard};
+use shared::cluster_topology::{ClusterTopology, HostInfo};
+use shared::utils::new_seeded_random_generator;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+const HTTP_PORT: u16 = 18098;
+const PHASE: Duration = Duration::from_secs(10);
+const CLIENTS: usize = 16;
+const LAYERS: [&str; 2] = ["creature-size", "age"];
+/// Pause between ticks, as in the AWS backend ticker
+const TICK_SLEEP: Duration = Duration::from_millis(5);
+
+const RULES: ColonyLifeRules = ColonyLifeRules {
+    color_drift_per_generation: 0,
+    color_mutation_chance: 0,
+    ..COLONY_LIFE_INITIAL_RULES
+};
+
+fn shard() -> Shard {
+    Shard { x: 0, y: 0, width: 250, height: 250 }
+}
+
+async fn init_colony(snapshots: SnapshotConfig) {
+    let this_backend = HostInfo::new("127.0.0.1".to_string(), 18099);
+    backend_config::set_backend_hostname(this_backend.hostname.clone());
+    backend_config::set_backend_port(this_backend.port);
+    backend_config::set_rate_limit_config(RateLimitConfig { enabled: false, ..RateLimitConfig::default() });
+    backend_config::set_snapshot_config(snapshots);
+
+    let topology = ClusterTopology {
+        coordinator_host: HostInfo::new("127.0.0.1".to_string(), 18100),
+        backend_hosts: vec![this_backend.clone()],
+        shard_to_host: HashMap::from([(shard(), this_backend)]),
+    };
+    dispatch_request(BackendRequest::InitColony(InitColonyRequest { width: shard().width, height: shard().height, colony_life_rules: RULES })).await;
+    dispatch_request(BackendRequest::InitColonyShard(InitColonyShardRequest {
+        shard: shard(),
+        colony_life_rules: RULES,
+        topology: Some(topology),
+        seeding: SeedingOptions::default(),
+        topography_data: None,
+        awaiting_topography: false,
+        colony_instance_id: None,
+        lease_epoch: 1,
+    })).await;
+}
+
+/// True for a 200 response
+async fn fetch(path: &str) -> bool {
+    let Ok(mut stream) = TcpStream::connect(("127.0.0.1", HTTP_PORT)).await else {
+        return false;
+    };
+    let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path);
+    if stream.write_all(request.as_bytes()).await.is_err() {
+        return false;
+    }
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).await.is_ok() && response.starts_with(b"HTTP/1.1 200")
+}
+
+/// Ticks counted over one phase, per second
+async fn measure_ticks(ticks: &AtomicU64) -> f64 {
+    let start_ticks = ticks.load(Ordering::Relaxed);
+    let start = Instant::now();
+    tokio::time::sleep(PHASE).await;
+    (ticks.load(Ordering::Relaxed) - start_ticks) as f64 / start.elapsed().as_secs_f64()
+}
+
+#[tokio::main(flavor = "multi_thread", worker_threads = 4)]
+async fn main() {
+    let snapshots = SnapshotConfig::from_env();
+    println!("Snapshot serving: {:?}", snapshots);
+    init_colony(snapshots).await;
+    tokio::spawn(start_http_server(HTTP_PORT));
+    start_snapshot_refresher();
+
+    // Ticks the shard at the backend ticker's pace, contending with the HTTP handlers for the shard lock
+    let ticks = Arc::new(AtomicU64::new(0));
+    let stop = Arc::new(AtomicBool::new(false));
+    let ticker = {
+        let (ticks, stop) = (Arc::clone(&ticks), Arc::clone(&stop));
+        let shard_arc = Colony::instance().get_hosted_colony_shard_arc(&shard()).expect("shard hosted");
+        std::thread::spawn(move || {
+            let mut rng = new_seeded_random_generator(3);
+            while !stop.load(Ordering::Relaxed) {
+                shard_arc.lock().unwrap().tick(&mut rng);
+                ticks.fetch_add(1, Ordering::Relaxed);
+                std::thread::sleep(TICK_SLEEP);
+            }
+        })
+    };
+
+    let idle = measure_ticks(&ticks).await;
+    println!("Without HTTP load: {:.1} ticks/s", idle);
+
+    let responses = Arc::new(AtomicU64::new(0));
+    let failures = Arc::new(AtomicU64::new(0));
+    let clients: Vec<_> = (0..CLIENTS).map(|_| {
+        let (responses, failures, stop) = (Arc::clone(&responses), Arc::clone(&failures), Arc::clone(&stop));
+        tokio::spawn(async move {
+            let paths: Vec<String> = std::iter::once(format!("/api/shard/{}/image", shard().to_id()))
+                .chain(LAYERS.iter().map(|layer| format!("/api/shard/{}/layer/{}", shard().to_id(), layer)))
+                .collect();
+            while !stop.load(Ordering::Relaxed) {
+                for path in &paths {
+                    let counter = if fetch(path).await { &responses } else { &failures };
+                    counter.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        })
+    }).collect();
+    let loaded = measure_ticks(&ticks).await;
+    let served = responses.load(Ordering::Relaxed);
+    println!("With {} HTTP clients: {:.1} ticks/s ({:.0}% of unloaded), {:.0} responses/s, {} not served",
+             CLIENTS, loaded, loaded / idle * 100.0, served as f64 / PHASE.as_secs_f64(), failures.load(Ordering::Relaxed));
+
+    stop.store(true, Ordering::Relaxed);
+    for client in clients {
+        let _ = client.await;
+    }
+    ticker.join().unwrap();
+}
@@ -1,4 +1,4 @@
-use shared::be_api::{BackendRequest, BackendResponse, UpdatedShardContentsRequest};
+use shared::be_api::{BackendRequest, BackendResponse, UpdatedShardContentsRequest, UpdatedShardContentsResponse};
 use shared::cluster_topology::HostInfo;
 use shared::backend_communication::send_request_with_pool;
 
@@ -10,7 +10,10 @@ pub async fn send_updated_shard_contents_to_host_async(
     let response: BackendResponse = send_request_with_pool(host, &request).await?;
     
     match response {
-        BackendResponse::UpdatedShardContents(_) => Ok(()),
+        BackendResponse::UpdatedShardContents(UpdatedShardContentsResponse::Ok) => Ok(()),
+        BackendResponse::UpdatedShardContents(UpdatedShardContentsResponse::Rejected(reason)) => {
+            Err(format!("Border update of shard {} rejected: {}", req.updated_shard.to_id(), reason).into())
+        }
         _ => Err("Unexpected response type".into()),
     }
 }
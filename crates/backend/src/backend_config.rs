@@ -1,9 +1,25 @@
 use std::sync::OnceLock;
+use crate::image_qos::QosConfig;
+use crate::presentation_snapshots::SnapshotConfig;
+use crate::rate_limiter::RateLimitConfig;
 
 // Global variables for backend configuration
 static BACKEND_HOSTNAME: OnceLock<String> = OnceLock::new();
 static BACKEND_PORT: OnceLock<u16> = OnceLock::new();
 static DEPLOYMENT_MODE: OnceLock<String> = OnceLock::new();
+static RATE_LIMIT_CONFIG: OnceLock<RateLimitConfig> = OnceLock::new();
+static QOS_CONFIG: OnceLock<QosConfig> = OnceLock::new();
+static SNAPSHOT_CONFIG: OnceLock<SnapshotConfig> = OnceLock::new();
+static SHARD_EVENT_LOG_CAPACITY: OnceLock<usize> = OnceLock::new();
+static BORDER_VALIDATION_WARN_ONLY: OnceLock<bool> = OnceLock::new();
+static DETERMINISM_AUDIT_TICKS: OnceLock<usize> = OnceLock::new();
+
+pub const SHARD_EVENT_LOG_CAPACITY_ENV: &str = "SHARD_EVENT_LOG_CAPACITY";
+const DEFAULT_SHARD_EVENT_LOG_CAPACITY: usize = 256;
+/// "true" logs border updates from unexpected senders but still applies them, for rolling out validation
+pub const BORDER_VALIDATION_WARN_ONLY_ENV: &str = "BORDER_VALIDATION_WARN_ONLY";
+/// Ticks of state hashes each shard keeps for the determinism audit; unset or 0 leaves the audit off
+pub const DETERMINISM_AUDIT_TICKS_ENV: &str = "DETERMINISM_AUDIT_TICKS";
 
 pub fn set_backend_hostname(hostname: String) {
     BACKEND_HOSTNAME.set(hostname).expect("Failed to set hostname");
@@ -17,6 +33,18 @@ pub fn set_deployment_mode(mode: String) {
     DEPLOYMENT_MODE.set(mode).expect("Failed to set deployment mode");
 }
 
+pub fn set_rate_limit_config(config: RateLimitConfig) {
+    RATE_LIMIT_CONFIG.set(config).expect("Failed to set rate limit config");
+}
+
+pub fn set_qos_config(config: QosConfig) {
+    QOS_CONFIG.set(config).expect("Failed to set QoS config");
+}
+
+pub fn set_snapshot_config(config: SnapshotConfig) {
+    SNAPSHOT_CONFIG.set(config).expect("Failed to set snapshot config");
+}
+
 pub fn get_backend_hostname() -> &'static str {
     BACKEND_HOSTNAME.get().expect("Backend hostname not initialized")
 }
@@ -30,3 +58,49 @@ pub fn is_aws_deployment() -> bool {
         .map(|mode| mode.as_str() == "aws")
         .unwrap_or(false)
 }
+
+/// Defaults when the backend was started without run_backend
+pub fn get_rate_limit_config() -> RateLimitConfig {
+    RATE_LIMIT_CONFIG.get().cloned().unwrap_or_default()
+}
+
+/// Defaults when the backend was started without run_backend
+pub fn get_qos_config() -> QosConfig {
+    QOS_CONFIG.get().cloned().unwrap_or_default()
+}
+
+/// Defaults (snapshot serving off) when the backend was started without run_backend
+pub fn get_snapshot_config() -> SnapshotConfig {
+    SNAPSHOT_CONFIG.get().cloned().unwrap_or_default()
+}
+
+/// Entries kept per shard event log; SHARD_EVENT_LOG_CAPACITY overrides the default
+pub fn get_shard_event_log_capacity() -> usize {
+    *SHARD_EVENT_LOG_CAPACITY.get_or_init(|| {
+        std::env::var(SHARD_EVENT_LOG_CAPACITY_ENV)
+            .ok()
+            .and_then(|v| v.trim().parse::<usize>().ok())
+            .filter(|capacity| *capacity > 0)
+            .unwrap_or(DEFAULT_SHARD_EVENT_LOG_CAPACITY)
+    })
+}
+
+/// Whether border updates failing validation are applied anyway; off unless BORDER_VALIDATION_WARN_ONLY is true
+pub fn is_border_validation_warn_only() -> bool {
+    *BORDER_VALIDATION_WARN_ONLY.get_or_init(|| {
+        std::env::var(BORDER_VALIDATION_WARN_ONLY_ENV)
+            .ok()
+            .and_then(|v| v.trim().parse::<bool>().ok())
+            .unwrap_or(false)
+    })
+}
+
+/// State hashes kept per shard, 0 when the determinism audit is off
+pub fn get_determinism_audit_ticks() -> usize {
+    *DETERMINISM_AUDIT_TICKS.get_or_init(|| {
+        std::env::var(DETERMINISM_AUDIT_TICKS_ENV)
+            .ok()
+            .and_then(|v| v.trim().parse::<usize>().ok())
+            .unwrap_or(0)
+    })
+}
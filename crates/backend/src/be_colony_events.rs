@@ -1,13 +1,18 @@
-use crate::{colony::Colony, colony_shard::WHITE_COLOR, shard_utils::ShardUtils};
+use crate::{colony::Colony, colony_shard::{is_blank, ColonyShard, WHITE_COLOR}, shard_utils::ShardUtils};
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
 
 use rand::{rngs::SmallRng, Rng};
-use shared::{be_api::{Shard, ColonyLifeRules}, colony_events::{ColonyEvent, Region, ColonyRuleChange}, log};
+use shared::{be_api::{Biome, Shard, ShardEventEffect, ShardEventRecord, ColonyLifeRules}, colony_events::{ColonyEvent, Region, ColonyRuleChange}, colony_event_shared::event_type_name, colony_model::{validate_biomes, GlobalPos, LocalPos}, log};
+use crate::backend_config::get_shard_event_log_capacity;
+use crate::shard_lock::lock_shard;
 
-fn point_inside_region(x: i32, y: i32, region: &Region) -> bool {
+fn point_inside_region(pos: GlobalPos, region: &Region) -> bool {
     match region {
         Region::Ellipse(ellipse) => {
-            let dx = x - ellipse.x;
-            let dy = y - ellipse.y;
+            let center = ellipse.center();
+            let dx = pos.x - center.x;
+            let dy = pos.y - center.y;
             // Use saturating operations to prevent overflow
             let dx2 = dx.saturating_mul(dx);
             let dy2 = dy.saturating_mul(dy);
@@ -31,105 +36,204 @@ fn region_overlaps_shard(region: &Region, shard: &Shard) -> bool {
     
     match region {
         Region::Ellipse(ellipse) => {
-            let closest_x = ellipse.x.max(shard.x).min(shard_right);
-            let closest_y = ellipse.y.max(shard.y).min(shard_bottom);
-            point_inside_region(closest_x, closest_y, region)
+            let center = ellipse.center();
+            let closest = GlobalPos::new(center.x.max(shard.x).min(shard_right), center.y.max(shard.y).min(shard_bottom));
+            point_inside_region(closest, region)
         }
     }
 }
 
+/// Runs cell_fn on every grid cell inside the region; returns how many of them are interior cells
 fn apply_region_to_shard<F>(
     shard: &mut crate::colony_shard::ColonyShard,
     region: &Region,
     mut cell_fn: F,
-) 
+) -> u64
 where
     F: FnMut(&mut shared::be_api::Cell),
 {
-    let width = shard.shard.width as usize;
-    let height = shard.shard.height as usize;
-    let row_size = width + 2;
-
-    for y in 0..height + 2 {
-        for x in 0..width + 2 {
-            let global_x = shard.shard.x + x as i32;
-            let global_y = shard.shard.y + y as i32;
-
-            if point_inside_region(global_x, global_y, region) {
-                let idx = y * row_size + x;
-                cell_fn(shard.grid.get_mut(idx).unwrap());
+    let bounds = shard.shard;
+    let mut interior_cells = 0;
+
+    for (idx, cell) in shard.grid.iter_mut().enumerate() {
+        let pos = LocalPos::from_grid_index(idx, &bounds);
+        if point_inside_region(pos.to_global(&bounds), region) {
+            cell_fn(cell);
+            if pos.is_interior(&bounds) {
+                interior_cells += 1;
             }
         }
-    }    
+    }
+    interior_cells
+}
+
+fn is_interior(shard: &ColonyShard, idx: usize) -> bool {
+    LocalPos::from_grid_index(idx, &shard.shard).is_interior(&shard.shard)
+}
+
+fn interior_creature_count(shard: &ColonyShard) -> u64 {
+    shard.grid.iter().enumerate()
+        .filter(|(idx, cell)| !is_blank(cell) && is_interior(shard, *idx))
+        .count() as u64
+}
+
+fn no_effect(shard: &ColonyShard) -> ShardEventEffect {
+    ShardEventEffect { shard: shard.shard, cells_affected: 0, creatures_affected: 0, tick_applied: shard.current_tick }
+}
+
+/// Marks event_id on every hosted shard and returns the shards that had not seen it yet.
+/// None means all hosted shards already applied it (a retried delivery).
+fn claim_event_on_hosted_shards(colony: &Colony, event_id: Uuid) -> Option<Vec<Arc<Mutex<ColonyShard>>>> {
+    let (_, shard_arcs) = colony.get_hosted_shards();
+    let hosted_count = shard_arcs.len();
+    let claimed: Vec<Arc<Mutex<ColonyShard>>> = shard_arcs.into_iter()
+        .filter(|shard_arc| lock_shard(shard_arc).record_applied_event(event_id))
+        .collect();
+    if hosted_count > 0 && claimed.is_empty() {
+        None
+    } else {
+        Some(claimed)
+    }
 }
 
+/// Applies the event to hosted shards that have not applied event_id yet.
+/// Returns the effects on the shards it touched, or None if the event was a duplicate for every hosted shard.
+pub fn apply_event(rng: &mut SmallRng, colony: &Colony, event_id: Uuid, event: &ColonyEvent) -> Option<Vec<ShardEventEffect>> {
+    let shard_arcs = claim_event_on_hosted_shards(colony, event_id)?;
+    let effects = apply_event_to_shards(rng, &shard_arcs, event);
+    record_event_on_shards(&shard_arcs, event_id, event, &effects);
+    Some(effects)
+}
 
+/// Adds the event to the log of every shard it affected; untouched shards do not log it
+pub fn record_event_on_shards(shard_arcs: &[Arc<Mutex<ColonyShard>>], event_id: Uuid, event: &ColonyEvent, effects: &[ShardEventEffect]) {
+    let capacity = get_shard_event_log_capacity();
+    for shard_arc in shard_arcs {
+        let mut shard = lock_shard(shard_arc);
+        let Some(effect) = effects.iter().find(|effect| effect.shard == shard.shard) else {
+            continue;
+        };
+        let record = ShardEventRecord {
+            tick_applied: effect.tick_applied,
+            event_id,
+            event_type: event_type_name(event).to_string(),
+            cells_affected: effect.cells_affected,
+        };
+        shard.record_event(record, capacity);
+    }
+}
 
-pub fn apply_event(rng: &mut SmallRng, colony: &Colony, event: &ColonyEvent) {
-    match event {
+/// Applies the event to the given shards; the result only lists shards with affected cells
+pub fn apply_event_to_shards(rng: &mut SmallRng, shard_arcs: &[Arc<Mutex<ColonyShard>>], event: &ColonyEvent) -> Vec<ShardEventEffect> {
+    let effects = match event {
         ColonyEvent::CreateCreature(region, _params) => {
-                apply_local_event(colony, event, region);
+                apply_local_event(shard_arcs, event, region)
             }
         ColonyEvent::ChangeExtraFoodPerTick(amount) => {
-                let (_, shard_arcs) = colony.get_hosted_shards();
-                for shard_arc in shard_arcs {
-                    let mut shard = shard_arc.lock().unwrap();
-                    shard.grid.iter_mut().for_each(|cell| {
+                shard_arcs.iter().map(|shard_arc| {
+                    let mut shard = lock_shard(shard_arc);
+                    let mut effect = no_effect(&shard);
+                    for idx in 0..shard.grid.len() {
+                        let cell = &mut shard.grid[idx];
+                        let before = cell.extra_food_per_tick;
                         if *amount >= 0 {
                             cell.extra_food_per_tick = cell.extra_food_per_tick.saturating_add(*amount as u8);
                         } else {
-                            cell.extra_food_per_tick = cell.extra_food_per_tick.saturating_sub((-*amount) as u8);
+                            cell.extra_food_per_tick = cell.extra_food_per_tick.saturating_sub(amount.unsigned_abs());
                         }
-                    });
-                }            
+                        let changed = cell.extra_food_per_tick != before;
+                        let has_creature = !is_blank(cell);
+                        if changed && is_interior(&shard, idx) {
+                            effect.cells_affected += 1;
+                            effect.creatures_affected += has_creature as u64;
+                        }
+                    }
+                    effect
+                }).collect()
             },
         ColonyEvent::Extinction() => {
-                let (_, shard_arcs) = colony.get_hosted_shards();
-                for shard_arc in shard_arcs {
-                    if rng.gen_bool(0.5) {
-                        let mut shard = shard_arc.lock().unwrap();
-                        shard.grid.iter_mut().for_each(|cell| {
-                            cell.color = WHITE_COLOR;
-                            cell.original_color = WHITE_COLOR;
-                            cell.health = 0;
-                            cell.age = 0;
-                        });
-                    }
-                }
+                shard_arcs.iter().filter(|_| rng.gen_bool(0.5)).map(|shard_arc| {
+                    let mut shard = lock_shard(shard_arc);
+                    let effect = ShardEventEffect {
+                        shard: shard.shard,
+                        cells_affected: (shard.shard.width * shard.shard.height) as u64,
+                        creatures_affected: interior_creature_count(&shard),
+                        tick_applied: shard.current_tick,
+                    };
+                    shard.grid.iter_mut().for_each(|cell| {
+                        cell.color = WHITE_COLOR;
+                        cell.original_color = WHITE_COLOR;
+                        cell.health = 0;
+                        cell.age = 0;
+                        cell.hunger = 0;
+                    });
+                    effect
+                }).collect()
             }
         ColonyEvent::NewTopography() => {
             panic!("NewTopography should not be applied to the backend");
         },
         ColonyEvent::ChangeColonyRules(rule_change) => {
             log!("Colony rules change: {}", rule_change.description);
-            apply_colony_rule_change(colony, &rule_change);
+            apply_colony_rule_change(shard_arcs, rule_change);
+            // The rules apply to every cell of the shard
+            shard_arcs.iter().map(|shard_arc| {
+                let shard = lock_shard(shard_arc);
+                ShardEventEffect {
+                    shard: shard.shard,
+                    cells_affected: (shard.shard.width * shard.shard.height) as u64,
+                    creatures_affected: interior_creature_count(&shard),
+                    tick_applied: shard.current_tick,
+                }
+            }).collect()
+        },
+        ColonyEvent::ChangeBiomes(biome_change) => {
+            log!("Biomes change: {}", biome_change.description);
+            set_biomes_on_shards(shard_arcs, &biome_change.biomes);
+            // Only the cells inside a biome live by different rules now
+            shard_arcs.iter().map(|shard_arc| {
+                let shard = lock_shard(shard_arc);
+                let biome_cells: i32 = shard.biomes.iter().map(|biome| biome.width * biome.height).sum();
+                ShardEventEffect {
+                    shard: shard.shard,
+                    cells_affected: biome_cells.min(shard.shard.width * shard.shard.height) as u64,
+                    creatures_affected: interior_creature_count(&shard),
+                    tick_applied: shard.current_tick,
+                }
+            }).collect()
         },
-    } 
+    };
+    effects.into_iter().filter(|effect: &ShardEventEffect| effect.cells_affected > 0).collect()
 }
 
-pub fn apply_local_event(colony: &Colony, event: &ColonyEvent, region: &Region) {
-    let (_, shard_arcs) = colony.get_hosted_shards();
+pub fn apply_local_event(shard_arcs: &[Arc<Mutex<ColonyShard>>], event: &ColonyEvent, region: &Region) -> Vec<ShardEventEffect> {
+    let mut effects = Vec::new();
     for shard_arc in shard_arcs {
-        let mut shard = shard_arc.lock().unwrap();
+        let mut shard = lock_shard(shard_arc);
         if !region_overlaps_shard(region, &shard.shard) {
             continue;
         }
         
         match event {
             ColonyEvent::CreateCreature(_region, params) => {
-                apply_region_to_shard(&mut shard, region, |cell| {
+                let cells_affected = apply_region_to_shard(&mut shard, region, |cell| {
                     cell.color = params.color;
                     cell.original_color = params.color;
                     cell.traits = params.traits;
                     cell.health = params.starting_health;
                     cell.age = 1;
+                    cell.hunger = 0;
                 });
+                // Every affected cell now holds one of the new creatures
+                let creatures_affected = if params.starting_health > 0 { cells_affected } else { 0 };
+                effects.push(ShardEventEffect { shard: shard.shard, cells_affected, creatures_affected, tick_applied: shard.current_tick });
             },
             _ => {
                 panic!("should not be called");
             }
         }
     }
+    effects
 }
 
 fn log_colony_rules(rules: &ColonyLifeRules, prefix: &str) {
@@ -143,17 +247,16 @@ fn log_colony_rules(rules: &ColonyLifeRules, prefix: &str) {
     );
 }
 
-fn apply_colony_rule_change(colony: &Colony, rule_change: &ColonyRuleChange) {
-    let (_, shard_arcs) = colony.get_hosted_shards();
-    
+fn apply_colony_rule_change(shard_arcs: &[Arc<Mutex<ColonyShard>>], rule_change: &ColonyRuleChange) {
     for shard_arc in shard_arcs {
-        let mut shard = shard_arc.lock().unwrap();
+        let mut shard = lock_shard(shard_arc);
         
         // Store the old rules for logging
         let old_rules = shard.colony_life_rules;
         
         // Apply the new rules object
         shard.colony_life_rules = rule_change.new_rules;
+        shard.refresh_biome_rules();
         
         log!("Updated shard {} rules: {}", shard.shard.to_id(), rule_change.description);
         
@@ -164,3 +267,25 @@ fn apply_colony_rule_change(colony: &Colony, rule_change: &ColonyRuleChange) {
         ShardUtils::store_shard(&*shard);
     }
 }
+
+/// Checks the biomes against the rules of the hosted shards, which all share the colony rules
+pub fn validate_biomes_for_hosted_shards(colony: &Colony, biomes: &[Biome]) -> Result<(), String> {
+    let (_, shard_arcs) = colony.get_hosted_shards();
+    match shard_arcs.first() {
+        Some(shard_arc) => {
+            let rules = lock_shard(shard_arc).colony_life_rules;
+            validate_biomes(biomes, &rules)
+        }
+        None => Ok(()),
+    }
+}
+
+/// Gives every shard its clipped share of the biomes and stores it
+pub fn set_biomes_on_shards(shard_arcs: &[Arc<Mutex<ColonyShard>>], biomes: &[Biome]) {
+    for shard_arc in shard_arcs {
+        let mut shard = lock_shard(shard_arc);
+        shard.set_biomes(biomes);
+        log!("Shard {} has {} of {} biomes", shard.shard.to_id(), shard.biomes.len(), biomes.len());
+        ShardUtils::store_shard(&shard);
+    }
+}
@@ -0,0 +1,803 @@
+use shared::log;
+use tokio::net::TcpListener;
+use tokio::net::TcpStream;
+use tokio_util::codec::{Framed, LengthDelimitedCodec};
+use tokio_stream::StreamExt;
+use futures_util::SinkExt;
+use shared::be_api::{BackendRequest, BackendResponse, InitColonyShardResponse, InitColonyRequest, InitColonyShardRequest, InitColonyResponse, GetColonyInfoRequest, GetColonyInfoResponse, UpdatedShardContentsRequest, UpdatedShardContentsResponse, InitShardTopographyRequest, InitShardTopographyResponse, GetShardCurrentTickRequest, GetShardCurrentTickResponse, ApplyEventRequest, ApplyEventResponse, ColonyEvent, GetShardStatsRequest, GetShardStatsResponse, StartTickingRequest, StartTickingResponse, UpdateTopologyRequest, UpdateTopologyResponse, SetTickerPausedRequest, SetTickerPausedResponse, StepTicksRequest, StepTicksResponse, SetShardFrozenRequest, SetShardFrozenResponse, Shard, UpdateBiomesRequest, UpdateBiomesResponse, RefreshTopologyRequest, RefreshTopologyResponse, GetEventLogRequest, GetEventLogResponse, ShardEventLog, GetStateHashesResponse, ShardStateHashes, GetShardDensityRequest, GetShardDensityResponse};
+use shared::logging::{log_startup, init_logging, set_panic_hook};
+use shared::output_paths::OutputPaths;
+use shared::density::MAX_DENSITY_CELLS;
+use shared::backend_communication::accept_hello;
+use shared::rpc_decode_failures::{decode_failure_limit, DecodedFrame, FrameDecoder};
+use shared::{log_error};
+use shared::cluster_topology::{DiscoveredTopology, NodeType, NodeAddress, start_periodic_discovery, ClusterTopology};
+use shared::cluster_registry::{ClusterRegistry, create_cluster_registry, get_instance};
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::Mutex;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeploymentMode {
+    Localhost,
+    Aws,
+}
+
+impl FromStr for DeploymentMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "localhost" => Ok(DeploymentMode::Localhost),
+            "aws" => Ok(DeploymentMode::Aws),
+            _ => Err(format!("Invalid deployment mode: {}. Must be 'localhost' or 'aws'", s)),
+        }
+    }
+}
+
+use crate::{backend_config, be_ticker, fast_forward, rpc_metrics, shard_stats};
+use crate::border_validation::{check_border_source, log_rejection, record_border_sources};
+use crate::be_colony_events::{apply_event, set_biomes_on_shards, validate_biomes_for_hosted_shards};
+use crate::colony::Colony;
+use crate::topology_subscription::start_topology_subscription;
+use crate::shard_lease::{check_border_epoch, check_coordinator_lease, start_lease_renewal, LeaseState};
+use crate::shard_lock::lock_shard;
+use crate::shard_utils::ShardUtils;
+use crate::shard_stats::ShardStatsSnapshot;
+use crate::shard_topography::ShardTopography;
+use crate::http_server::start_http_server;
+use crate::backend_config::{get_backend_hostname, get_backend_port, get_determinism_audit_ticks};
+use crate::topology_refresh::{refresh_topology, set_colony_instance_id, topology_includes_this_backend};
+use crate::rate_limiter::RateLimitConfig;
+use crate::image_qos::QosConfig;
+use crate::presentation_snapshots::{start_snapshot_refresher, SnapshotConfig};
+
+type FramedStream = Framed<TcpStream, LengthDelimitedCodec>;
+
+pub use shared::be_api::BUILD_VERSION;
+
+/// Name of the RPC a response answers, the same as its request's name in rpc_metrics
+pub fn call_label(response: &BackendResponse) -> &'static str {
+    match response {
+        BackendResponse::Ping => "Ping",
+        BackendResponse::InitColony(_) => "InitColony",
+        BackendResponse::GetShardStats(_) => "GetShardStats",
+        BackendResponse::InitColonyShard(_) => "InitColonyShard",
+        BackendResponse::GetColonyInfo(_) => "GetColonyInfo",
+        BackendResponse::UpdatedShardContents(_) => "UpdatedShardContents",
+        BackendResponse::InitShardTopography(_) => "InitShardTopography",
+        BackendResponse::GetShardCurrentTick(_) => "GetShardCurrentTick",
+        BackendResponse::ApplyEvent(_) => "ApplyEvent",
+        BackendResponse::StartTicking(_) => "StartTicking",
+        BackendResponse::UpdateTopology(_) => "UpdateTopology",
+        BackendResponse::SetTickerPaused(_) => "SetTickerPaused",
+        BackendResponse::StepTicks(_) => "StepTicks",
+        BackendResponse::SetShardFrozen(_) => "SetShardFrozen",
+        BackendResponse::UpdateBiomes(_) => "UpdateBiomes",
+        BackendResponse::RefreshTopology(_) => "RefreshTopology",
+        BackendResponse::GetEventLog(_) => "GetEventLog",
+        BackendResponse::GetStateHashes(_) => "GetStateHashes",
+        BackendResponse::GetShardDensity(_) => "GetShardDensity",
+    }
+}
+
+async fn send_response(framed: &mut FramedStream, response: BackendResponse) {
+    let encoded = bincode::serialize(&response).expect("Failed to serialize BackendResponse");
+    let label = call_label(&response);
+    if let Err(e) = framed.send(encoded.into()).await {
+        log_error!("Failed to send {} response: {}", label, e);
+    }
+}
+
+/// Runs one request through its handler; public so tests can drive the handlers without a socket
+#[allow(dead_code)]
+pub async fn dispatch_request(request: BackendRequest) -> BackendResponse {
+    dispatch_request_from(request, None).await
+}
+
+/// dispatch_request for a request that arrived from peer, when its address is known
+pub async fn dispatch_request_from(request: BackendRequest, peer: Option<IpAddr>) -> BackendResponse {
+    match request {
+        BackendRequest::Ping => handle_ping().await,
+        BackendRequest::InitColony(req) => handle_init_colony(req).await,
+        BackendRequest::InitColonyShard(req) => handle_init_colony_shard(req).await,
+        BackendRequest::GetColonyInfo(req) => handle_get_colony_info(req).await,
+        BackendRequest::UpdatedShardContents(req) => handle_updated_shard_contents(req, peer).await,
+        BackendRequest::InitShardTopography(req) => handle_init_shard_topography(req).await,
+        BackendRequest::GetShardCurrentTick(req) => handle_get_shard_current_tick(req).await,
+        BackendRequest::GetShardStats(req) => handle_get_shard_stats(req).await,
+        BackendRequest::ApplyEvent(req) => handle_apply_event(req).await,
+        BackendRequest::StartTicking(req) => handle_start_ticking(req).await,
+        BackendRequest::UpdateTopology(req) => handle_update_topology(req).await,
+        BackendRequest::SetTickerPaused(req) => handle_set_ticker_paused(req).await,
+        BackendRequest::StepTicks(req) => handle_step_ticks(req).await,
+        BackendRequest::SetShardFrozen(req) => handle_set_shard_frozen(req).await,
+        BackendRequest::UpdateBiomes(req) => handle_update_biomes(req).await,
+        BackendRequest::RefreshTopology(req) => handle_refresh_topology(req).await,
+        BackendRequest::GetEventLog(req) => handle_get_event_log(req).await,
+        BackendRequest::GetStateHashes(_) => handle_get_state_hashes().await,
+        BackendRequest::GetShardDensity(req) => handle_get_shard_density(req).await,
+    }
+}
+
+async fn handle_client(socket: TcpStream) {
+    let peer_ip = socket.peer_addr().ok().map(|addr| addr.ip());
+    let peer = socket.peer_addr().map(|addr| addr.to_string()).unwrap_or_else(|_| "unknown".to_string());
+    let mut framed = Framed::new(socket, LengthDelimitedCodec::new());
+    let Some(hello) = accept_hello(&mut framed, &peer).await else {
+        return;
+    };
+    let peer_key = peer_ip.map(|ip| ip.to_string()).unwrap_or_else(|| peer.clone());
+    let mut decoder = FrameDecoder::new(peer_key, decode_failure_limit());
+    loop {
+        match framed.next().await {
+            Some(Ok(bytes)) => {
+                let response = match decoder.decode::<BackendRequest>(&bytes, &rpc_metrics::DECODE_FAILURES) {
+                    DecodedFrame::Request(request) => {
+                        let kind = rpc_metrics::rpc_kind(&request);
+                        let started = Instant::now();
+                        let response = dispatch_request_from(request, peer_ip).await;
+                        rpc_metrics::record_rpc(kind, started.elapsed());
+                        response
+                    }
+                    DecodedFrame::Rejected { error, log_count, disconnect } => {
+                        if let Some(count) = log_count {
+                            log_error!("Failed to deserialize BackendRequest from {} (protocol {}, build {}), {} failures from this peer{}: {}",
+                                       peer, hello.protocol_version, hello.build_version, count,
+                                       if disconnect { ", closing the connection" } else { "" }, error);
+                        }
+                        if disconnect {
+                            break;
+                        }
+                        continue;
+                    }
+                };
+                send_response(&mut framed, response).await;
+            }
+            Some(Err(e)) => {
+                log_error!("handle_client: error reading from connection: {}", e);
+                break;
+            }
+            None => {
+                break;
+            }
+        }
+    }
+}
+
+async fn handle_ping() -> BackendResponse {
+    BackendResponse::Ping
+}
+
+async fn handle_init_colony(req: InitColonyRequest) -> BackendResponse {
+    if Colony::is_initialized() {
+        let colony = Colony::instance();
+        if (colony.width(), colony.height()) == (req.width, req.height) {
+            return BackendResponse::InitColony(InitColonyResponse::ColonyAlreadyInitialized);
+        }
+        let e = format!("colony is already {}x{}, not {}x{}", colony.width(), colony.height(), req.width, req.height);
+        log_error!("Rejecting InitColony: {}", e);
+        BackendResponse::InitColony(InitColonyResponse::DimensionMismatch(e))
+    } else {
+        Colony::init(&req);
+        BackendResponse::InitColony(InitColonyResponse::Ok)
+    }
+}
+
+async fn handle_init_colony_shard(req: InitColonyShardRequest) -> BackendResponse {
+    // Initialize topology from ClusterTopology object on first call, and after it was cleared
+    if !ClusterTopology::is_initialized() {
+        // Extract ClusterTopology from request
+        let topology = match req.topology {
+            Some(t) => t,
+            None => {
+                log_error!("ClusterTopology missing from InitColonyShardRequest");
+                return BackendResponse::InitColonyShard(InitColonyShardResponse::Error);
+            }
+        };
+
+        // A coordinator bug could size the colony differently than the topology it sends
+        if Colony::is_initialized() {
+            let colony = Colony::instance();
+            if let Err(e) = Colony::validate_topology_dimensions(colony.width(), colony.height(), &topology) {
+                log_error!("Rejecting InitColonyShard for {:?}: {}", req.shard, e);
+                return BackendResponse::InitColonyShard(InitColonyShardResponse::DimensionMismatch(e));
+            }
+        }
+        
+        // Initialize topology from ClusterTopology object
+        if let Err(e) = ClusterTopology::initialize_from_topology(topology.clone()) {
+            log_error!("Failed to initialize topology: {}", e);
+            return BackendResponse::InitColonyShard(InitColonyShardResponse::Error);
+        }
+        
+        // Validate that this backend's host info exists in the topology's backend hosts
+        if !topology_includes_this_backend(&topology) {
+            log_error!("Backend host {}:{} not found in topology backend hosts", 
+                      get_backend_hostname(), get_backend_port());
+            return BackendResponse::InitColonyShard(InitColonyShardResponse::Error);
+        }
+        
+        set_colony_instance_id(req.colony_instance_id.clone());
+        log!("Topology initialized from ClusterTopology object");
+    } else if let Some(topology) = req.topology.clone() {
+        // A replacement coordinator sends its own topology; the stored one may name the old coordinator
+        if let Err(e) = refresh_topology(topology, req.colony_instance_id.clone()) {
+            log_error!("Rejecting InitColonyShard for {:?}: {}", req.shard, e);
+            return BackendResponse::InitColonyShard(InitColonyShardResponse::TopologyConflict(e));
+        }
+    }
+    
+    if !Colony::is_initialized() {
+        BackendResponse::InitColonyShard(InitColonyShardResponse::ColonyNotInitialized)
+    } else if let Some(shard_arc) = Colony::instance().get_hosted_colony_shard_arc(&req.shard) {
+        // Assigned here again under a newer epoch, e.g. back from another backend
+        let mut shard = lock_shard(&shard_arc);
+        if req.lease_epoch > shard.lease.epoch {
+            log!("Shard {} granted lease epoch {} (was {})", req.shard.to_id(), req.lease_epoch, shard.lease.epoch);
+            shard.lease = LeaseState::granted(req.lease_epoch, Instant::now());
+        }
+        BackendResponse::InitColonyShard(InitColonyShardResponse::ShardAlreadyInitialized)
+    } else if !Colony::instance().is_valid_shard_dimensions(&req.shard) {
+        BackendResponse::InitColonyShard(InitColonyShardResponse::InvalidShardDimensions)
+    } else if let Err(e) = req.colony_life_rules.validate() {
+        log_error!("Rejecting InitColonyShard for {:?}: {}", req.shard, e);
+        BackendResponse::InitColonyShard(InitColonyShardResponse::InvalidRules(e))
+    } else if let Err(e) = req.seeding.validate() {
+        log_error!("Rejecting InitColonyShard for {:?}: {}", req.shard, e);
+        BackendResponse::InitColonyShard(InitColonyShardResponse::InvalidSeeding(e))
+    } else {
+        let mut rng = match req.seeding.shard_seed(&req.shard) {
+            Some(seed) => shared::utils::new_seeded_random_generator(seed),
+            None => shared::utils::new_random_generator(),
+        };
+        let mut colony_shard = ShardUtils::new_colony_shard_in_chunks(Colony::instance(), &req.shard, &req.colony_life_rules, &req.seeding, &mut rng).await;
+        // The shard is only added once its terrain is in place, so it never ticks without it
+        match &req.topography_data {
+            Some(topography_data) => {
+                if let Err(e) = ShardTopography::init_shard_topography_from_data(&mut colony_shard, topography_data) {
+                    log_error!("Rejecting InitColonyShard for {:?}: {}", req.shard, e);
+                    Colony::instance().init_progress().clear(&req.shard);
+                    return BackendResponse::InitColonyShard(InitColonyShardResponse::InvalidTopography(e));
+                }
+            }
+            None => colony_shard.awaiting_topography = req.awaiting_topography,
+        }
+        colony_shard.lease = LeaseState::granted(req.lease_epoch, Instant::now());
+        Colony::instance().add_hosted_shard(colony_shard);
+        record_border_sources();
+        BackendResponse::InitColonyShard(InitColonyShardResponse::Ok)
+    }
+}
+
+async fn handle_get_colony_info(_req: GetColonyInfoRequest) -> BackendResponse {
+    if !Colony::is_initialized() {
+        BackendResponse::GetColonyInfo(GetColonyInfoResponse::ColonyNotInitialized)
+    } else {
+        let colony = Colony::instance();
+        let (shards, shard_arcs) = colony.get_hosted_shards();
+        
+        // Get ColonyLifeRules and current_tick from the first available shard
+        let (colony_life_rules, current_tick) = if let Some(first_shard_arc) = shard_arcs.first() {
+            let shard = lock_shard(first_shard_arc);
+            (Some(shard.colony_life_rules), Some(shard.current_tick))
+        } else {
+            (None, None)
+        };
+        let ticks: Vec<u64> = shard_arcs.iter().map(|shard_arc| lock_shard(shard_arc).current_tick).collect();
+        let tick_range = ticks.iter().min().zip(ticks.iter().max()).map(|(min, max)| (*min, *max));
+        let mut leases = Vec::new();
+        let mut suspended_shards = Vec::new();
+        for shard_arc in &shard_arcs {
+            let shard = lock_shard(shard_arc);
+            leases.push(shard.lease.to_lease(shard.shard));
+            if shard.lease.is_suspended() {
+                suspended_shards.push(shard.shard);
+            }
+        }
+        
+        BackendResponse::GetColonyInfo(GetColonyInfoResponse::Ok {
+            width: colony.width(),
+            height: colony.height(),
+            shards,
+            colony_life_rules: colony_life_rules.map(Box::new),
+            current_tick,
+            tick_range,
+            version: BUILD_VERSION.to_string(),
+            leases,
+            suspended_shards,
+        })
+    }
+}
+
+async fn handle_get_shard_stats(req: GetShardStatsRequest) -> BackendResponse {
+    if !Colony::is_initialized() {
+        return BackendResponse::GetShardStats(GetShardStatsResponse::ColonyNotInitialized);
+    }
+    let colony = Colony::instance();
+    if let Some(shard_arc) = colony.get_hosted_colony_shard_arc(&req.shard) {
+        // Only the copy runs under the lock, the histograms are built after it is released
+        let (snapshot, tick_count) = {
+            let shard = lock_shard(&shard_arc);
+            let started = Instant::now();
+            let snapshot = ShardStatsSnapshot::capture(&shard, &req.shard, req.region.as_ref());
+            shard_stats::record_lock_held(started.elapsed());
+            (snapshot, shard.get_current_tick())
+        };
+        match snapshot {
+            Some(snapshot) => BackendResponse::GetShardStats(GetShardStatsResponse::Ok { stats: snapshot.compute_stats(&req.metrics), tick_count }),
+            None => BackendResponse::GetShardStats(GetShardStatsResponse::ShardNotAvailable),
+        }
+    } else {
+        BackendResponse::GetShardStats(GetShardStatsResponse::ShardNotAvailable)
+    }
+}
+
+async fn handle_get_shard_density(req: GetShardDensityRequest) -> BackendResponse {
+    if !Colony::is_initialized() {
+        return BackendResponse::GetShardDensity(GetShardDensityResponse::ColonyNotInitialized);
+    }
+    let cells = (req.cells as usize).clamp(1, MAX_DENSITY_CELLS);
+    match Colony::instance().get_hosted_colony_shard_arc(&req.shard) {
+        Some(shard_arc) => {
+            let grid = lock_shard(&shard_arc).density(cells);
+            BackendResponse::GetShardDensity(GetShardDensityResponse::Ok((*grid).clone()))
+        }
+        None => BackendResponse::GetShardDensity(GetShardDensityResponse::ShardNotAvailable),
+    }
+}
+
+async fn handle_updated_shard_contents(req: UpdatedShardContentsRequest, peer: Option<IpAddr>) -> BackendResponse {   
+    if !Colony::is_initialized() {
+        return BackendResponse::UpdatedShardContents(UpdatedShardContentsResponse::Ok);
+    }
+    if let Err(reason) = check_border_source(&req.updated_shard, peer) {
+        let warn_only = backend_config::is_border_validation_warn_only();
+        log_rejection(&reason, warn_only);
+        if !warn_only {
+            return BackendResponse::UpdatedShardContents(UpdatedShardContentsResponse::Rejected(reason));
+        }
+    }
+    // The sender lost the shard to a newer lease; its copy has diverged from the owner's
+    if let Err(reason) = check_border_epoch(&req.updated_shard, req.epoch) {
+        log_rejection(&reason, false);
+        return BackendResponse::UpdatedShardContents(UpdatedShardContentsResponse::Rejected(reason));
+    }
+    
+    let colony = Colony::instance();    
+    let (_, shard_arcs) = colony.get_hosted_shards();
+    for shard_arc in shard_arcs {
+        let mut shard = lock_shard(&shard_arc);
+        if ShardUtils::is_adjacent_shard(&req.updated_shard, &shard.shard) && !shard.is_quarantined() {
+            ShardUtils::updated_shard_contents(&mut shard, &req);
+        }
+    }
+    
+    BackendResponse::UpdatedShardContents(UpdatedShardContentsResponse::Ok)
+}
+
+async fn handle_init_shard_topography(req: InitShardTopographyRequest) -> BackendResponse {   
+    if !Colony::is_initialized() {
+        return BackendResponse::InitShardTopography(InitShardTopographyResponse::ShardNotInitialized);
+    }
+    
+    let colony = Colony::instance();
+    if let Some(shard_arc) = colony.get_hosted_colony_shard_arc(&req.shard) {
+        let mut shard = lock_shard(&shard_arc);
+        // A ticking shard takes the new terrain between ticks, keeping its creatures
+        let applied = if shard.awaiting_topography || shard.current_tick == 0 {
+            ShardTopography::init_shard_topography_from_data(&mut shard, &req.topography_data)
+        } else {
+            ShardTopography::queue_topography(&mut shard, req.topography_data)
+        };
+        match applied {
+            Ok(()) => {
+                if shard.awaiting_topography {
+                    shard.awaiting_topography = false;
+                    log!("Shard {} received its topography and can tick", req.shard.to_id());
+                }
+                BackendResponse::InitShardTopography(InitShardTopographyResponse::Ok)
+            }
+            Err(_) => BackendResponse::InitShardTopography(InitShardTopographyResponse::InvalidTopographyData),
+        }
+    } else {
+        BackendResponse::InitShardTopography(InitShardTopographyResponse::ShardNotInitialized)
+    }
+}
+
+async fn handle_get_shard_current_tick(req: GetShardCurrentTickRequest) -> BackendResponse {
+    if !Colony::is_initialized() {
+        BackendResponse::GetShardCurrentTick(GetShardCurrentTickResponse::ColonyNotInitialized)
+    } else {
+        let colony = Colony::instance();
+        if let Some(shard_arc) = colony.get_hosted_colony_shard_arc(&req.shard) {
+            let shard = lock_shard(&shard_arc);
+            BackendResponse::GetShardCurrentTick(GetShardCurrentTickResponse::Ok {
+                current_tick: shard.get_current_tick(),
+            })
+        } else {
+            BackendResponse::GetShardCurrentTick(GetShardCurrentTickResponse::ShardNotAvailable)
+        }
+    }
+}
+
+async fn handle_apply_event(req: ApplyEventRequest) -> BackendResponse {
+    if !Colony::is_initialized() {
+        return BackendResponse::ApplyEvent(ApplyEventResponse::ColonyNotInitialized);
+    }
+    if let ColonyEvent::ChangeColonyRules(rule_change) = &req.event {
+        if let Err(e) = rule_change.new_rules.validate() {
+            log_error!("Rejecting event {}: {}", req.event_id, e);
+            return BackendResponse::ApplyEvent(ApplyEventResponse::InvalidRules(e));
+        }
+    }
+    if let ColonyEvent::ChangeBiomes(biome_change) = &req.event {
+        if let Err(e) = validate_biomes_for_hosted_shards(Colony::instance(), &biome_change.biomes) {
+            log_error!("Rejecting event {}: {}", req.event_id, e);
+            return BackendResponse::ApplyEvent(ApplyEventResponse::InvalidRules(e));
+        }
+    }
+
+    let colony = Colony::instance();
+    let mut rng = shared::utils::new_random_generator();
+    match apply_event(&mut rng, colony, req.event_id, &req.event) {
+        Some(effects) => BackendResponse::ApplyEvent(ApplyEventResponse::Ok { effects }),
+        None => {
+            log!("Event {} already applied on all hosted shards, skipping", req.event_id);
+            BackendResponse::ApplyEvent(ApplyEventResponse::AlreadyApplied)
+        }
+    }
+}
+
+async fn handle_start_ticking(req: StartTickingRequest) -> BackendResponse {
+    if !Colony::is_initialized() {
+        return BackendResponse::StartTicking(StartTickingResponse::ColonyNotInitialized);
+    }
+    if let Some(enabled) = req.fast_forward {
+        fast_forward::set_fast_forward(enabled).await;
+    }
+    
+    if !ClusterTopology::is_initialized() {
+        return BackendResponse::StartTicking(StartTickingResponse::TopologyNotInitialized);
+    }
+
+    let awaiting: Vec<Shard> = Colony::instance().get_hosted_shards().1.iter()
+        .map(|shard_arc| lock_shard(shard_arc))
+        .filter(|shard| shard.awaiting_topography)
+        .map(|shard| shard.shard)
+        .collect();
+    if !awaiting.is_empty() {
+        log_error!("Not starting to tick: {} shards still await their topography", awaiting.len());
+        return BackendResponse::StartTicking(StartTickingResponse::AwaitingTopography(awaiting));
+    }
+    
+    let now = Instant::now();
+    let stale: Vec<Shard> = Colony::instance().get_hosted_shards().1.iter()
+        .map(|shard_arc| lock_shard(shard_arc))
+        .filter_map(|mut shard| check_coordinator_lease(&mut shard, &req.leases, now).then_some(shard.shard))
+        .collect();
+    
+    // Start ticking (idempotent - start_be_ticker uses OnceLock to ensure only called once)
+    be_ticker::start_be_ticker();
+    
+    if stale.is_empty() {
+        BackendResponse::StartTicking(StartTickingResponse::Ok)
+    } else {
+        BackendResponse::StartTicking(StartTickingResponse::StaleLeases(stale))
+    }
+}
+
+async fn handle_update_topology(req: UpdateTopologyRequest) -> BackendResponse {
+    if !Colony::is_initialized() {
+        return BackendResponse::UpdateTopology(UpdateTopologyResponse::ColonyNotInitialized);
+    }
+    
+    // The ticker reads the topology on every tick, so border exchange picks up new neighbors right away
+    if let Err(e) = ClusterTopology::replace(req.topology) {
+        log_error!("Failed to replace topology: {}", e);
+        return BackendResponse::UpdateTopology(UpdateTopologyResponse::Error(e.to_string()));
+    }
+    Colony::instance().resize(req.width, req.height);
+    record_border_sources();
+    log!("Topology updated, colony is now {}x{}", req.width, req.height);
+    
+    BackendResponse::UpdateTopology(UpdateTopologyResponse::Ok)
+}
+
+async fn handle_set_ticker_paused(req: SetTickerPausedRequest) -> BackendResponse {
+    if !Colony::is_initialized() {
+        return BackendResponse::SetTickerPaused(SetTickerPausedResponse::ColonyNotInitialized);
+    }
+    
+    be_ticker::set_ticker_paused(req.paused).await;
+    BackendResponse::SetTickerPaused(SetTickerPausedResponse::Ok { current_tick: be_ticker::hosted_current_tick() })
+}
+
+async fn handle_step_ticks(req: StepTicksRequest) -> BackendResponse {
+    if !Colony::is_initialized() {
+        return BackendResponse::StepTicks(StepTicksResponse::ColonyNotInitialized);
+    }
+    if let Some(shard) = req.shard {
+        if !Colony::instance().is_hosting_shard(shard) {
+            return BackendResponse::StepTicks(StepTicksResponse::ShardNotAvailable);
+        }
+    }
+    
+    BackendResponse::StepTicks(be_ticker::step_ticks(req.shard, req.count).await)
+}
+
+async fn handle_set_shard_frozen(req: SetShardFrozenRequest) -> BackendResponse {
+    if !Colony::is_initialized() {
+        return BackendResponse::SetShardFrozen(SetShardFrozenResponse::ColonyNotInitialized);
+    }
+    
+    let Some(shard_arc) = Colony::instance().get_hosted_colony_shard_arc(&req.shard) else {
+        return BackendResponse::SetShardFrozen(SetShardFrozenResponse::ShardNotAvailable);
+    };
+    let mut shard = lock_shard(&shard_arc);
+    if shard.frozen != req.frozen {
+        shard.frozen = req.frozen;
+        log!("Shard {} {} at tick {}", req.shard.to_id(), if req.frozen { "frozen" } else { "unfrozen" }, shard.get_current_tick());
+    }
+    BackendResponse::SetShardFrozen(SetShardFrozenResponse::Ok { current_tick: shard.get_current_tick() })
+}
+
+async fn handle_refresh_topology(req: RefreshTopologyRequest) -> BackendResponse {
+    if !ClusterTopology::is_initialized() {
+        return BackendResponse::RefreshTopology(RefreshTopologyResponse::TopologyNotInitialized);
+    }
+    
+    match refresh_topology(req.topology, req.colony_instance_id) {
+        Ok(replaced) => BackendResponse::RefreshTopology(RefreshTopologyResponse::Ok { replaced }),
+        Err(e) => {
+            log_error!("Rejecting RefreshTopology: {}", e);
+            BackendResponse::RefreshTopology(RefreshTopologyResponse::TopologyConflict(e))
+        }
+    }
+}
+
+async fn handle_get_event_log(req: GetEventLogRequest) -> BackendResponse {
+    if !Colony::is_initialized() {
+        return BackendResponse::GetEventLog(GetEventLogResponse::ColonyNotInitialized);
+    }
+    let (shards, shard_arcs) = Colony::instance().get_hosted_shards();
+    let logs = shards.into_iter().zip(shard_arcs)
+        .map(|(shard, shard_arc)| ShardEventLog { shard, records: lock_shard(&shard_arc).event_log_records(req.event_id, req.limit) })
+        .filter(|log| !log.records.is_empty())
+        .collect();
+    BackendResponse::GetEventLog(GetEventLogResponse::Ok(logs))
+}
+
+async fn handle_get_state_hashes() -> BackendResponse {
+    if !Colony::is_initialized() {
+        return BackendResponse::GetStateHashes(GetStateHashesResponse::ColonyNotInitialized);
+    }
+    if get_determinism_audit_ticks() == 0 {
+        return BackendResponse::GetStateHashes(GetStateHashesResponse::AuditDisabled);
+    }
+    let (shards, shard_arcs) = Colony::instance().get_hosted_shards();
+    let hashes = shards.into_iter().zip(shard_arcs)
+        .map(|(shard, shard_arc)| ShardStateHashes { shard, hashes: lock_shard(&shard_arc).state_hashes.iter().copied().collect() })
+        .collect();
+    BackendResponse::GetStateHashes(GetStateHashesResponse::Ok(hashes))
+}
+
+async fn handle_update_biomes(req: UpdateBiomesRequest) -> BackendResponse {
+    if !Colony::is_initialized() {
+        return BackendResponse::UpdateBiomes(UpdateBiomesResponse::ColonyNotInitialized);
+    }
+    let colony = Colony::instance();
+    if let Err(e) = validate_biomes_for_hosted_shards(colony, &req.biomes) {
+        log_error!("Rejecting biomes: {}", e);
+        return BackendResponse::UpdateBiomes(UpdateBiomesResponse::InvalidBiomes(e));
+    }
+    
+    let (_, shard_arcs) = colony.get_hosted_shards();
+    set_biomes_on_shards(&shard_arcs, &req.biomes);
+    BackendResponse::UpdateBiomes(UpdateBiomesResponse::Ok)
+}
+
+async fn create_discovered_topology(hostname: &str, rpc_port: u16) -> DiscoveredTopology {
+    // In AWS mode, HTTP port comes from HTTP_PORT env var
+    let http_port = std::env::var("HTTP_PORT")
+        .ok()
+        .and_then(|v| v.parse::<u16>().ok())
+        .unwrap_or(8085); // Default fallback
+    let mut discovered_topology = DiscoveredTopology::new(
+        NodeType::Backend, 
+        NodeAddress::new(hostname.to_string(), hostname.to_string(), rpc_port, http_port), 
+        None, 
+        Vec::new()
+    );
+    discovered_topology.start_discovery().await;
+    discovered_topology
+}
+
+fn check_port_available(port: u16) -> Result<(), String> {
+    use std::net::TcpListener;
+    match TcpListener::bind(format!("127.0.0.1:{}", port)) {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            if e.kind() == std::io::ErrorKind::AddrInUse {
+                Err(format!("Port {} is already in use", port))
+            } else {
+                Err(format!("Failed to check port {}: {}", port, e))
+            }
+        }
+    }
+}
+
+/// Everything run_backend needs; be_main fills it from the command line or the environment
+#[derive(Debug, Clone)]
+pub struct BackendServerConfig {
+    pub hostname: String,
+    pub rpc_port: u16,
+    pub http_port: u16,
+    pub deployment_mode: DeploymentMode,
+    /// Limits on the HTTP endpoints, see rate_limiter
+    pub rate_limit: RateLimitConfig,
+    /// When image/layer requests get cached frames, see image_qos
+    pub qos: QosConfig,
+    /// Whether image/layer requests only read background-refreshed buffers, see presentation_snapshots
+    pub snapshots: SnapshotConfig,
+}
+
+/// Runs the backend RPC and HTTP servers; only returns if the ports are unavailable or the output directory is not writable.
+/// Backend state (colony, hostname, port) is process-global, so one backend per process.
+pub async fn run_backend(config: BackendServerConfig) -> Result<(), String> {
+    let BackendServerConfig { hostname, rpc_port, http_port, deployment_mode, rate_limit, qos, snapshots } = config;
+    
+    // Validate ports are available
+    check_port_available(rpc_port).map_err(|e| format!("RPC port validation failed: {}", e))?;
+    check_port_available(http_port).map_err(|e| format!("HTTP port validation failed: {}", e))?;
+    let output_paths = OutputPaths::get_instance();
+    output_paths.ensure_writable()?;
+    
+    // Initialize global variables
+    backend_config::set_backend_hostname(hostname.clone());
+    backend_config::set_backend_port(rpc_port);
+    backend_config::set_rate_limit_config(rate_limit);
+    backend_config::set_qos_config(qos);
+    backend_config::set_snapshot_config(snapshots);
+    
+    // When running in containers, services often bind on 0.0.0.0, but the cluster
+    // topology may list 127.0.0.1. Normalize just for validation.
+    let normalized_hostname_for_validation = if hostname == "0.0.0.0" {
+        "127.0.0.1".to_string()
+    } else {
+        hostname.clone()
+    };
+    
+    init_logging(output_paths.log_file(&format!("be_{}", rpc_port)));
+    log_startup("BE");
+    log!("Starting the backend in {:?} deployment mode, version {}", deployment_mode, BUILD_VERSION);
+    log!("RPC port: {}, HTTP port: {}", rpc_port, http_port);
+    set_panic_hook();
+    
+    let deployment_mode_str = match deployment_mode {
+        DeploymentMode::Aws => "aws",
+        DeploymentMode::Localhost => "localhost",
+    };
+    
+    // Store deployment mode globally
+    crate::backend_config::set_deployment_mode(deployment_mode_str.to_string());
+    
+    // Initialize ClusterRegistry early
+    let _registry = create_cluster_registry(deployment_mode_str);
+    
+    // Create DiscoveredTopology in AWS mode
+    if deployment_mode == DeploymentMode::Aws {
+        let discovered_topology = create_discovered_topology(&hostname, rpc_port).await;
+        discovered_topology.log_self();
+        start_periodic_discovery(Arc::new(Mutex::new(discovered_topology)));
+        
+        // Start HTTP server for debug endpoints (in both AWS and localhost modes)
+        tokio::spawn(start_http_server(http_port));
+    } else {
+        // Start HTTP server in localhost mode as well
+        tokio::spawn(start_http_server(http_port));
+    }
+    
+    rpc_metrics::start_window_rollover();
+    start_lease_renewal();
+    start_topology_subscription();
+    start_snapshot_refresher();
+    
+    // Note: Topology validation is now done during InitColonyShard processing using routing table from coordinator
+    // No static topology access needed at startup
+
+    // Backend ticker will be started by coordinator via StartTicking RPC after colony initialization
+    // Do NOT start ticker automatically here
+
+    let bind_host = match deployment_mode {
+        DeploymentMode::Aws => "0.0.0.0".to_string(),
+        DeploymentMode::Localhost => hostname.clone(),
+    };
+    let bind_addr = format!("{}:{}", bind_host, rpc_port);
+    let listener = match TcpListener::bind(&bind_addr).await {
+        Ok(listener) => listener,
+        Err(err) => {
+            log_error!("Failed to bind listener on {}: {}", bind_addr, err);
+            panic!("Could not bind listener on {}: {}", bind_addr, err);
+        }
+    };
+    log!("Listening on {} (advertised as {})", bind_addr, hostname);
+
+    // Register backend in ClusterRegistry
+    let (backend_private_ip, backend_public_ip, instance_id) = match deployment_mode {
+        DeploymentMode::Aws => {
+            // Get actual EC2 private IP
+            let private_ip = match shared::utils::get_ec2_private_ip().await {
+                Some(ip) => {
+                    log!("Discovered EC2 private IP: {}", ip);
+                    ip
+                }
+                None => {
+                    log_error!("Failed to get EC2 private IP, registration will fail");
+                    "0.0.0.0".to_string()
+                }
+            };
+            // Get actual EC2 public IP
+            let public_ip = match shared::utils::get_ec2_public_ip().await {
+                Some(ip) => {
+                    log!("Discovered EC2 public IP: {}", ip);
+                    ip
+                }
+                None => {
+                    log_error!("Failed to get EC2 public IP, registration will fail");
+                    "0.0.0.0".to_string()
+                }
+            };
+            let id = match shared::utils::get_ec2_instance_id().await {
+                Some(id) => {
+                    log!("Discovered EC2 instance ID: {}", id);
+                    id
+                }
+                None => {
+                    log_error!("Failed to get EC2 instance ID, using backend_{}", rpc_port);
+                    format!("backend_{}", rpc_port)
+                }
+            };
+            (private_ip, public_ip, id)
+        }
+        DeploymentMode::Localhost => (normalized_hostname_for_validation.clone(), normalized_hostname_for_validation.clone(), format!("backend_{}", rpc_port)),
+    };
+    // Use RPC port for internal communication and HTTP port for HTTP endpoints
+    let backend_address = NodeAddress::new(backend_private_ip.clone(), backend_public_ip.clone(), rpc_port, http_port);
+    let internal_addr = backend_address.to_internal_address();
+    let http_addr = backend_address.to_http_address();
+    if let Some(registry) = get_instance() {
+        if let Err(e) = registry.register_backend(instance_id.clone(), backend_address).await {
+            log_error!("Failed to register backend: {}", e);
+        } else {
+            log!("Registered backend {} in SSM ClusterRegistry: {} (internal), {} (http)", 
+                 instance_id, internal_addr, http_addr);
+        }
+    }
+
+    // Setup signal handlers for graceful shutdown
+    let registry_clone = get_instance();
+    let instance_id_clone = instance_id.clone();
+    tokio::spawn(async move {
+        use tokio::signal;
+        let _ = signal::ctrl_c().await;
+        log!("Received shutdown signal, unregistering backend...");
+        if let Some(registry) = registry_clone {
+            if let Err(e) = registry.unregister_backend(instance_id_clone).await {
+                log_error!("Failed to unregister backend: {}", e);
+            }
+        }
+        std::process::exit(0);
+    });
+
+    loop {
+        match listener.accept().await {
+            Ok((socket, _)) => {
+                tokio::spawn(handle_client(socket));
+            }
+            Err(e) => log_error!("Connection failed: {}", e),
+        }
+    }
+} 
\ No newline at end of file
@@ -1,15 +1,26 @@
 use futures::future::join_all;
 use crate::backend_client::send_updated_shard_contents_to_host_async;
+use crate::border_outbox::BorderOutbox;
 use crate::colony::Colony;
 use crate::shard_utils::ShardUtils;
+use crate::image_qos::ImageQos;
+use crate::fast_forward::is_fast_forward;
+use crate::shard_lock::lock_shard;
+use crate::shard_routing::border_update_hosts;
 use shared::utils::new_random_generator;
 use shared::cluster_topology::{ClusterTopology, HostInfo};
-use shared::log;
+use shared::be_api::{Shard, StepTicksResponse, UpdatedShardContentsRequest};
+use shared::{log, log_error};
+use shared::supervisor::spawn_supervised;
 use crate::backend_config::{get_backend_hostname, get_backend_port, is_aws_deployment};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, OnceLock};
 use std::time::Instant;
 
 static TICKER_STARTED: OnceLock<()> = OnceLock::new();
+static TICKER_PAUSED: AtomicBool = AtomicBool::new(false);
+/// Held for the duration of every tick, so pausing and stepping never overlap a running tick
+static TICK_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
 
 struct ShardTickLatencyStats {
     window_tick_count: u32,
@@ -50,89 +61,185 @@ impl ShardTickLatencyStats {
     }
 }
 
+/// Runs one tick of the hosted shards (or only `only_shard`) followed by border exchange.
+/// When `await_remote` is set, border updates to other backends are delivered before returning,
+/// so a stepping coordinator can rely on neighbors being up to date for the next tick.
+/// Returns (core_latency_ms, full_latency_ms), or None when the topology is not initialized.
+async fn run_tick(only_shard: Option<Shard>, await_remote: bool) -> Option<(f64, f64)> {
+    let start_full = Instant::now();
+    let colony = Colony::instance();
+
+    // Get a snapshot of shard keys and Arc handles (cheap clones)
+    let (hosted_shards, hosted_colony_shards) = colony.get_hosted_shards();
+    let ticked_shards: Vec<_> = match only_shard {
+        Some(shard) => hosted_shards.iter().zip(hosted_colony_shards.iter())
+            .filter(|(key, _)| **key == shard)
+            .map(|(_, shard_arc)| Arc::clone(shard_arc))
+            .collect(),
+        None => hosted_colony_shards,
+    };
+    // A shard whose lease expired or was superseded neither ticks nor sends borders, see shard_lease.
+    // Nor does a quarantined one, see shard_lock.
+    let ticked_shards: Vec<_> = ticked_shards.into_iter()
+        .filter(|shard_arc| {
+            let shard = lock_shard(shard_arc);
+            !shard.lease.is_suspended() && !shard.is_quarantined()
+        })
+        .collect();
+
+    let topology = match ClusterTopology::get_instance() {
+        Some(t) => t,
+        None => {
+            log!("Topology not initialized, skipping tick");
+            return None;
+        }
+    };
+
+    // Optional: read current tick from any shard
+    let current_tick = {
+        if let Some(first) = ticked_shards.first() {
+            lock_shard(first).get_current_tick()
+        } else { 0 }
+    };
+
+    let start_core = Instant::now();
+
+    let tasks = ticked_shards.iter().map(|shard_arc| {
+        let shard_arc = Arc::clone(shard_arc);
+        tokio::task::spawn_blocking(move || {
+            let mut rng = new_random_generator();
+            let mut shard = lock_shard(&shard_arc);
+            ShardUtils::tick_and_export(&mut shard, &mut rng)
+        })
+    });
+    // A panicking tick poisons its shard, which the next lock_shard recovers; the other shards go on
+    let exported = join_all(tasks).await
+        .into_iter()
+        .filter_map(|result| result.inspect_err(|e| log_error!("Shard tick panicked: {}", e)).ok())
+        .collect::<Vec<_>>();
+
+    let end_core = Instant::now();
+
+    let this_backend_host = HostInfo::new(get_backend_hostname().to_string(), get_backend_port());
+    let mut remote_sends = Vec::new();
+
+    exchange_local_borders(colony, &exported);
+    for req in &exported {
+        // external hosts (fire-and-forget unless stepping); unreachable ones get the update once they recover
+        for host in border_update_hosts(&topology, &req.updated_shard, &this_backend_host) {
+            let req_owned = req.clone();
+            let send = async move {
+                BorderOutbox::get_instance().deliver(&host, req_owned, Instant::now(), |req| {
+                    let host = host.clone();
+                    async move {
+                        send_updated_shard_contents_to_host_async(&host, &req).await.map_err(|e| e.to_string())
+                    }
+                }).await;
+            };
+            if await_remote {
+                remote_sends.push(send);
+            } else {
+                tokio::spawn(send);
+            }
+        }
+    }
+    join_all(remote_sends).await;
+
+    // optional persistence
+    if current_tick % 250 == 0 {
+        for shard_arc in &ticked_shards {
+            let shard = lock_shard(shard_arc);
+            ShardUtils::store_shard(&*shard);
+        }
+    }
+
+    let end_full = Instant::now();
+
+    let core_latency_ms = (end_core - start_core).as_secs_f64() * 1000.0;
+    let full_latency_ms = (end_full - start_full).as_secs_f64() * 1000.0;
+    // Nothing is rendered while fast-forwarding, so there is no image QoS to decide on
+    if !is_fast_forward() {
+        ImageQos::get_instance().record_tick(full_latency_ms, current_tick + 1);
+    }
+    Some((core_latency_ms, full_latency_ms))
+}
+
+/// Border phase of a tick: hands every exported border to the adjacent shards the colony hosts.
+/// Shards on other backends are not reached here, run_tick sends them the borders.
+pub fn exchange_local_borders(colony: &Colony, exported: &[UpdatedShardContentsRequest]) {
+    let (hosted_shards, _) = colony.get_hosted_shards();
+    for req in exported {
+        for shard_key in &hosted_shards {
+            if ShardUtils::is_adjacent_shard(&req.updated_shard, shard_key) {
+                let shard_arc = colony.get_hosted_colony_shard_arc(shard_key).unwrap();
+                let mut shard = lock_shard(&shard_arc);
+                if !shard.is_quarantined() {
+                    ShardUtils::updated_shard_contents(&mut shard, req);
+                }
+            }
+        }
+    }
+}
+
+/// Highest current tick across the hosted shards
+pub fn hosted_current_tick() -> u64 {
+    let (_, hosted_colony_shards) = Colony::instance().get_hosted_shards();
+    hosted_colony_shards.iter()
+        .map(|shard_arc| lock_shard(shard_arc).get_current_tick())
+        .max()
+        .unwrap_or(0)
+}
+
+/// Pausing waits for the in-flight tick, so no shard advances after this returns
+pub async fn set_ticker_paused(paused: bool) {
+    TICKER_PAUSED.store(paused, Ordering::SeqCst);
+    if paused {
+        drop(TICK_LOCK.lock().await);
+    }
+    log!("Ticker {}", if paused { "paused" } else { "resumed" });
+}
+
+/// Stepping is only allowed while the background ticker is not advancing the shards
+fn is_ticker_running() -> bool {
+    TICKER_STARTED.get().is_some() && !TICKER_PAUSED.load(Ordering::SeqCst)
+}
+
+pub async fn step_ticks(shard: Option<Shard>, count: u32) -> StepTicksResponse {
+    let _tick_guard = TICK_LOCK.lock().await;
+    if is_ticker_running() {
+        return StepTicksResponse::TickerNotPaused;
+    }
+    for _ in 0..count {
+        if run_tick(shard, true).await.is_none() {
+            break;
+        }
+    }
+    let current_tick = match shard {
+        Some(shard) => match Colony::instance().get_hosted_colony_shard_arc(&shard) {
+            Some(shard_arc) => lock_shard(&shard_arc).get_current_tick(),
+            None => return StepTicksResponse::ShardNotAvailable,
+        },
+        None => hosted_current_tick(),
+    };
+    StepTicksResponse::Ok { current_tick }
+}
+
 pub fn start_be_ticker() {
     // Ensure ticker is only started once (idempotent)
     TICKER_STARTED.get_or_init(|| {
-    tokio::spawn(async move {
+    spawn_supervised("be-ticker", || async {
         let mut latency_stats = ShardTickLatencyStats::new();
 
         loop {
-            if Colony::is_initialized() {
-                let start_full = Instant::now();
-                let colony = Colony::instance();
-
-                // Get a snapshot of shard keys and Arc handles (cheap clones)
-                let (hosted_shards, hosted_colony_shards) = colony.get_hosted_shards();
-
-                // Optional: read current tick from any shard
-                let current_tick = {
-                    if let Some(first) = hosted_colony_shards.first() {
-                        first.lock().unwrap().get_current_tick()
-                    } else { 0 }
-                };
-
-                let start_core = Instant::now();
-
-                let tasks = hosted_colony_shards.iter().map(|shard_arc| {
-                    let shard_arc = Arc::clone(shard_arc);
-                    tokio::task::spawn_blocking(move || {
-                        let mut rng = new_random_generator();
-                        let mut shard = shard_arc.lock().unwrap();
-                        shard.tick(&mut rng);
-                        ShardUtils::export_shard_contents(&shard)
-                    })
-                });
-                let exported = join_all(tasks).await
-                    .into_iter().map(|r| r.expect("tick task panicked")).collect::<Vec<_>>();
-
-                let end_core = Instant::now();
-
-                let topology = match ClusterTopology::get_instance() {
-                    Some(t) => t,
-                    None => {
-                        log!("Topology not initialized, skipping tick");
-                        continue;
-                    }
-                };
-                let this_backend_host = HostInfo::new(get_backend_hostname().to_string(), get_backend_port());
-
-                for req in &exported {
-                    for shard_key in &hosted_shards {
-                        if ShardUtils::is_adjacent_shard(&req.updated_shard, shard_key) {
-                            let shard_arc = colony.get_hosted_colony_shard_arc(shard_key).unwrap();
-                            let mut shard = shard_arc.lock().unwrap();
-                            ShardUtils::updated_shard_contents(&mut shard, req);
-                        }
-                    }
-
-                    // external hosts (fire-and-forget)
-                    let adj: std::collections::HashSet<_> =
-                        topology.get_adjacent_shards(&req.updated_shard).into_iter().collect();
-                    let hosts = topology.get_backend_hosts_for_shards(&adj.iter().cloned().collect::<Vec<_>>());
-                    for host in hosts {
-                        if host != this_backend_host {
-                            let req_owned = req.clone();
-                            tokio::spawn(async move {
-                                 let _ = send_updated_shard_contents_to_host_async(&host, &req_owned).await;
-                            });
-                        }
-                    }
-                }
-
-                // optional persistence
-                if current_tick % 250 == 0 {
-                    for shard_arc in &hosted_colony_shards {
-                        let shard = shard_arc.lock().unwrap();
-                        ShardUtils::store_shard(&*shard);
+            if Colony::is_initialized() && !TICKER_PAUSED.load(Ordering::SeqCst) {
+                let _tick_guard = TICK_LOCK.lock().await;
+                // A pause may have landed while waiting for a step to finish
+                if !TICKER_PAUSED.load(Ordering::SeqCst) {
+                    if let Some((core_latency_ms, full_latency_ms)) = run_tick(None, false).await {
+                        let shard_count = Colony::instance().get_hosted_shards().0.len();
+                        latency_stats.record_tick(core_latency_ms, full_latency_ms, shard_count);
                     }
                 }
-
-                let end_full = Instant::now();
-
-                let core_latency_ms = (end_core - start_core).as_secs_f64() * 1000.0;
-                let full_latency_ms = (end_full - start_full).as_secs_f64() * 1000.0;
-
-                latency_stats.record_tick(core_latency_ms, full_latency_ms, hosted_shards.len());
             }
 
             let sleep_duration = if is_aws_deployment() {
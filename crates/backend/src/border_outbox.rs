@@ -0,0 +1,220 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::future::Future;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use serde::Serialize;
+use shared::be_api::{Shard, UpdatedShardContentsRequest};
+use shared::cluster_topology::HostInfo;
+use shared::{log, log_error};
+
+/// First retry delay after a neighbor stops answering; doubles per failed retry
+const RETRY_BACKOFF_MIN: Duration = Duration::from_millis(200);
+const RETRY_BACKOFF_MAX: Duration = Duration::from_secs(10);
+
+/// Border updates a neighbor backend could not take yet. Only the latest update of each of
+/// our shards is kept, so an outage holds one border per shard however long it lasts.
+#[derive(Default)]
+struct NeighborOutbox {
+    /// Source shard -> (sequence number, latest undelivered update)
+    pending: HashMap<Shard, (u64, UpdatedShardContentsRequest)>,
+    next_seq: u64,
+    unreachable_since: Option<Instant>,
+    failed_retries: u32,
+    next_attempt: Option<Instant>,
+    retry_in_flight: bool,
+    /// Undelivered updates replaced by a newer border of the same shard
+    merged: u64,
+    failed_sends: u64,
+    recoveries: u64,
+}
+
+impl NeighborOutbox {
+    /// Keeps the update unless a newer one of the same shard is already waiting
+    fn hold(&mut self, seq: u64, req: UpdatedShardContentsRequest) {
+        match self.pending.get(&req.updated_shard) {
+            Some((held_seq, _)) if *held_seq > seq => self.merged += 1,
+            Some(_) => {
+                self.merged += 1;
+                self.pending.insert(req.updated_shard, (seq, req));
+            }
+            None => {
+                self.pending.insert(req.updated_shard, (seq, req));
+            }
+        }
+    }
+
+    fn backoff(&self) -> Duration {
+        RETRY_BACKOFF_MIN.saturating_mul(1 << self.failed_retries.min(16)).min(RETRY_BACKOFF_MAX)
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct NeighborOutboxStats {
+    pub neighbor: String,
+    pub reachable: bool,
+    pub unreachable_for_ms: Option<u64>,
+    pub pending: usize,
+    pub merged: u64,
+    pub failed_sends: u64,
+    pub recoveries: u64,
+}
+
+/// Per-neighbor outbox of border updates. While a neighbor is reachable updates go straight
+/// out; once a send fails they are held (latest wins) and retried with backoff, and the first
+/// successful retry delivers the freshest border of every shard.
+pub struct BorderOutbox {
+    neighbors: Mutex<HashMap<HostInfo, NeighborOutbox>>,
+}
+
+static INSTANCE: OnceLock<BorderOutbox> = OnceLock::new();
+
+impl BorderOutbox {
+    pub fn new() -> Self {
+        Self { neighbors: Mutex::new(HashMap::new()) }
+    }
+
+    pub fn get_instance() -> &'static BorderOutbox {
+        INSTANCE.get_or_init(BorderOutbox::new)
+    }
+
+    /// Delivers req to host with send, or holds it while the host is unreachable
+    pub async fn deliver<F, Fut>(&self, host: &HostInfo, req: UpdatedShardContentsRequest, now: Instant, send: F)
+    where
+        F: Fn(UpdatedShardContentsRequest) -> Fut,
+        Fut: Future<Output = Result<(), String>>,
+    {
+        let seq;
+        let retry = {
+            let mut neighbors = self.neighbors.lock().unwrap();
+            let outbox = neighbors.entry(host.clone()).or_default();
+            seq = outbox.next_seq;
+            outbox.next_seq += 1;
+            if outbox.unreachable_since.is_none() && outbox.pending.is_empty() {
+                None
+            } else {
+                outbox.hold(seq, req.clone());
+                let due = outbox.next_attempt.is_none_or(|at| now >= at);
+                if !due || outbox.retry_in_flight {
+                    return;
+                }
+                outbox.retry_in_flight = true;
+                Some(outbox.pending.drain().map(|(_, held)| held).collect::<Vec<_>>())
+            }
+        };
+
+        match retry {
+            None => {
+                if let Err(e) = send(req.clone()).await {
+                    // A later update of the shard may have been held while this send was in flight
+                    let mut neighbors = self.neighbors.lock().unwrap();
+                    let outbox = neighbors.entry(host.clone()).or_default();
+                    outbox.hold(seq, req);
+                    self.record_failure(host, outbox, now, &e);
+                }
+            }
+            Some(held) => self.retry(host, held, now, send).await,
+        }
+    }
+
+    async fn retry<F, Fut>(&self, host: &HostInfo, held: Vec<(u64, UpdatedShardContentsRequest)>, now: Instant, send: F)
+    where
+        F: Fn(UpdatedShardContentsRequest) -> Fut,
+        Fut: Future<Output = Result<(), String>>,
+    {
+        let mut undelivered = Vec::new();
+        let mut last_error = None;
+        for (seq, req) in held {
+            // Once one send fails the neighbor is still down; keep the rest for the next retry
+            if last_error.is_some() {
+                undelivered.push((seq, req));
+                continue;
+            }
+            if let Err(e) = send(req.clone()).await {
+                last_error = Some(e);
+                undelivered.push((seq, req));
+            }
+        }
+
+        let mut neighbors = self.neighbors.lock().unwrap();
+        let outbox = neighbors.entry(host.clone()).or_default();
+        outbox.retry_in_flight = false;
+        match last_error {
+            None => {
+                if let Some(since) = outbox.unreachable_since.take() {
+                    outbox.recoveries += 1;
+                    log!("Neighbor {} reachable again after {} ms; delivered the latest borders, {} updates merged so far",
+                        host.to_address(), now.saturating_duration_since(since).as_millis(), outbox.merged);
+                }
+                outbox.failed_retries = 0;
+                outbox.next_attempt = None;
+            }
+            Some(e) => {
+                for (seq, req) in undelivered {
+                    outbox.hold(seq, req);
+                }
+                self.record_failure(host, outbox, now, &e);
+            }
+        }
+    }
+
+    fn record_failure(&self, host: &HostInfo, outbox: &mut NeighborOutbox, now: Instant, error: &str) {
+        outbox.failed_sends += 1;
+        if outbox.unreachable_since.is_none() {
+            outbox.unreachable_since = Some(now);
+            outbox.failed_retries = 0;
+            log_error!("Neighbor {} unreachable, holding its border updates: {}", host.to_address(), error);
+        } else {
+            outbox.failed_retries += 1;
+        }
+        outbox.next_attempt = Some(now + outbox.backoff());
+    }
+
+    pub fn neighbor_stats(&self, host: &HostInfo, now: Instant) -> NeighborOutboxStats {
+        let neighbors = self.neighbors.lock().unwrap();
+        let default = NeighborOutbox::default();
+        let outbox = neighbors.get(host).unwrap_or(&default);
+        NeighborOutboxStats {
+            neighbor: host.to_address(),
+            reachable: outbox.unreachable_since.is_none(),
+            unreachable_for_ms: outbox.unreachable_since.map(|since| now.saturating_duration_since(since).as_millis() as u64),
+            pending: outbox.pending.len(),
+            merged: outbox.merged,
+            failed_sends: outbox.failed_sends,
+            recoveries: outbox.recoveries,
+        }
+    }
+
+    /// Outbox counters per neighbor in Prometheus text format, appended to /metrics
+    pub fn render_prometheus(&self) -> String {
+        let now = Instant::now();
+        let hosts: Vec<HostInfo> = self.neighbors.lock().unwrap().keys().cloned().collect();
+        let stats: BTreeMap<String, NeighborOutboxStats> = hosts.iter()
+            .map(|host| (host.to_address(), self.neighbor_stats(host, now)))
+            .collect();
+        let mut out = String::new();
+        let _ = writeln!(out, "# TYPE backend_border_neighbor_reachable gauge");
+        for (neighbor, stats) in &stats {
+            let _ = writeln!(out, "backend_border_neighbor_reachable{{neighbor=\"{}\"}} {}", neighbor, stats.reachable as u8);
+        }
+        let _ = writeln!(out, "# TYPE backend_border_pending_updates gauge");
+        for (neighbor, stats) in &stats {
+            let _ = writeln!(out, "backend_border_pending_updates{{neighbor=\"{}\"}} {}", neighbor, stats.pending);
+        }
+        let _ = writeln!(out, "# TYPE backend_border_merged_updates_total counter");
+        for (neighbor, stats) in &stats {
+            let _ = writeln!(out, "backend_border_merged_updates_total{{neighbor=\"{}\"}} {}", neighbor, stats.merged);
+        }
+        let _ = writeln!(out, "# TYPE backend_border_failed_sends_total counter");
+        for (neighbor, stats) in &stats {
+            let _ = writeln!(out, "backend_border_failed_sends_total{{neighbor=\"{}\"}} {}", neighbor, stats.failed_sends);
+        }
+        out
+    }
+}
+
+impl Default for BorderOutbox {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr};
+use std::time::{Duration, Instant};
+use shared::be_api::Shard;
+use shared::cluster_topology::{ClusterTopology, HostInfo};
+use shared::log_error;
+use crate::colony::Colony;
+use crate::shard_utils::ShardUtils;
+
+/// At most one rejection is logged per interval; the rest are counted into the next line
+const REJECTION_LOG_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Expected senders of border updates: for each hosted shard, its adjacent shards and the
+/// hosts that own them according to the topology
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BorderSources {
+    neighbors: HashMap<Shard, HashMap<Shard, HostInfo>>,
+}
+
+impl BorderSources {
+    pub fn from_topology(hosted_shards: &[Shard], topology: &ClusterTopology) -> Self {
+        let all_shards = topology.get_all_shards();
+        let neighbors = hosted_shards.iter()
+            .map(|hosted| {
+                let adjacent = all_shards.iter()
+                    .filter(|other| ShardUtils::is_adjacent_shard(other, hosted))
+                    .filter_map(|other| topology.get_host_for_shard(other).map(|host| (*other, host.clone())))
+                    .collect();
+                (*hosted, adjacent)
+            })
+            .collect();
+        Self { neighbors }
+    }
+
+    /// Ok when claimed borders a hosted shard and, if the peer IP is known, was sent from the
+    /// host that owns it. Owners given by a hostname that is not an IP are not compared.
+    pub fn check(&self, claimed: &Shard, peer: Option<IpAddr>) -> Result<(), String> {
+        let owner = self.neighbors.values()
+            .find_map(|adjacent| adjacent.get(claimed))
+            .ok_or_else(|| format!("shard {} is not adjacent to any hosted shard", claimed.to_id()))?;
+        match (peer, host_ip(owner)) {
+            (Some(peer), Some(owner_ip)) if peer.to_canonical() != owner_ip => Err(format!(
+                "shard {} is owned by {} but the update came from {}", claimed.to_id(), owner.to_address(), peer)),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn host_ip(host: &HostInfo) -> Option<IpAddr> {
+    match host.hostname.as_str() {
+        // Same normalization as topology_refresh: a backend bound to 0.0.0.0 connects from loopback locally
+        "localhost" | "0.0.0.0" => Some(IpAddr::V4(Ipv4Addr::LOCALHOST)),
+        hostname => hostname.parse::<IpAddr>().ok().map(|ip| ip.to_canonical()),
+    }
+}
+
+/// When a border rejection was last logged, and how many were left out since
+#[derive(Debug, Default)]
+pub struct RejectionLog {
+    last_logged: Option<Instant>,
+    suppressed: u64,
+}
+
+/// Rebuilds the expected senders from the hosted shards and the current topology; called
+/// whenever the hosted shards change, and by check_border_source after a topology swap
+pub fn record_border_sources() {
+    let (Some(topology), epoch) = ClusterTopology::get_instance_with_epoch() else {
+        return;
+    };
+    if !Colony::is_initialized() {
+        return;
+    }
+    let colony = Colony::instance();
+    let hosted_shards = colony.get_hosted_shards().0;
+    *colony.border_sources() = Some((epoch, BorderSources::from_topology(&hosted_shards, &topology)));
+}
+
+/// Checks an incoming border update against the recorded senders, rebuilt first when the
+/// topology was swapped since they were recorded
+pub fn check_border_source(claimed: &Shard, peer: Option<IpAddr>) -> Result<(), String> {
+    let colony = Colony::instance();
+    let recorded_epoch = colony.border_sources().as_ref().map(|(epoch, _)| *epoch);
+    if recorded_epoch.is_some_and(|epoch| epoch != ClusterTopology::topology_epoch()) {
+        record_border_sources();
+    }
+    match colony.border_sources().as_ref() {
+        Some((_, sources)) => sources.check(claimed, peer),
+        None => Err("no topology recorded yet".to_string()),
+    }
+}
+
+/// Logs a rejected border update at most once per REJECTION_LOG_INTERVAL, so a misbehaving
+/// peer sending every tick cannot flood the log
+pub fn log_rejection(reason: &str, warn_only: bool) {
+    let now = Instant::now();
+    let mut rejection_log = Colony::instance().border_rejections();
+    if rejection_log.last_logged.is_some_and(|at| now.duration_since(at) < REJECTION_LOG_INTERVAL) {
+        rejection_log.suppressed += 1;
+        return;
+    }
+    let suppressed = std::mem::take(&mut rejection_log.suppressed);
+    rejection_log.last_logged = Some(now);
+    let action = if warn_only { "Accepting (warn-only)" } else { "Rejecting" };
+    log_error!("{} border update: {} ({} more since the last report)", action, reason, suppressed);
+}
@@ -1,14 +1,24 @@
 use std::sync::{Arc, RwLock, Mutex};
 use std::sync::OnceLock;
+use std::sync::atomic::{AtomicI32, Ordering};
 use std::collections::HashMap;
 use shared::be_api::{InitColonyRequest, Shard};
+use shared::cluster_topology::ClusterTopology;
+use crate::border_validation::{BorderSources, RejectionLog};
 use crate::colony_shard::ColonyShard;
+use crate::shard_init_progress::InitProgress;
+use crate::shard_lease::BorderEpochs;
 
 #[derive(Debug)]
 pub struct Colony {
-    pub _width: i32,
-    pub _height: i32,
+    width: AtomicI32,  // grows when the coordinator expands the colony
+    height: AtomicI32,
     pub shards: RwLock<HashMap<Shard, Arc<Mutex<ColonyShard>>>>, // HashMap for easy lookup, Arc<Mutex> for parallelism
+    border_epochs: Mutex<BorderEpochs>,
+    /// Expected senders of border updates, with the topology epoch they were built from
+    border_sources: Mutex<Option<(u64, BorderSources)>>,
+    border_rejections: Mutex<RejectionLog>,
+    init_progress: Mutex<InitProgress>,
 }
 
 static COLONY: OnceLock<Colony> = OnceLock::new();
@@ -24,12 +34,33 @@ impl Colony {
 
     pub fn init(req: &InitColonyRequest) {
         if COLONY.get().is_some() { return; }
-        let colony = Colony {
-            _width: req.width,
-            _height: req.height,
-            shards: RwLock::new(HashMap::new())
-        };
-        COLONY.set(colony).expect("Failed to init Colony");
+        COLONY.set(Colony::new(req.width, req.height)).expect("Failed to init Colony");
+    }
+
+    /// A colony outside the process-wide instance, e.g. one owned by a SimulationHandle
+    pub fn new(width: i32, height: i32) -> Self {
+        Colony {
+            width: AtomicI32::new(width),
+            height: AtomicI32::new(height),
+            shards: RwLock::new(HashMap::new()),
+            border_epochs: Mutex::new(BorderEpochs::default()),
+            border_sources: Mutex::new(None),
+            border_rejections: Mutex::new(RejectionLog::default()),
+            init_progress: Mutex::new(InitProgress::default()),
+        }
+    }
+
+    pub fn width(&self) -> i32 {
+        self.width.load(Ordering::Relaxed)
+    }
+
+    pub fn height(&self) -> i32 {
+        self.height.load(Ordering::Relaxed)
+    }
+
+    pub fn resize(&self, width: i32, height: i32) {
+        self.width.store(width, Ordering::Relaxed);
+        self.height.store(height, Ordering::Relaxed);
     }
 
     pub fn add_hosted_shard(&self, colony_shard: ColonyShard) -> bool {
@@ -55,11 +86,47 @@ impl Colony {
         r.get(shard).cloned()
     }
 
+    /// Lease epochs seen in the border updates this colony received
+    pub fn border_epochs(&self) -> std::sync::MutexGuard<'_, BorderEpochs> {
+        self.border_epochs.lock().unwrap()
+    }
+
+    /// Senders accepted for border updates, see crate::border_validation
+    pub fn border_sources(&self) -> std::sync::MutexGuard<'_, Option<(u64, BorderSources)>> {
+        self.border_sources.lock().unwrap()
+    }
+
+    /// Throttling of the border rejection log lines
+    pub fn border_rejections(&self) -> std::sync::MutexGuard<'_, RejectionLog> {
+        self.border_rejections.lock().unwrap()
+    }
+
+    /// Progress of the shards whose grid is being generated, see ShardUtils::new_colony_shard_in_chunks
+    pub fn init_progress(&self) -> std::sync::MutexGuard<'_, InitProgress> {
+        self.init_progress.lock().unwrap()
+    }
+
     pub fn is_valid_shard_dimensions(&self, shard: &Shard) -> bool {
         shard.x >= 0 && shard.y >= 0 &&
         shard.width > 0 && shard.height > 0 &&
-        shard.x + shard.width <= self._width &&
-        shard.y + shard.height <= self._height
+        shard.x + shard.width <= self.width() &&
+        shard.y + shard.height <= self.height()
     }
 
+    /// The topology must tile exactly a width x height colony: every shard inside it and the
+    /// shards together reaching its right and bottom edges
+    pub fn validate_topology_dimensions(width: i32, height: i32, topology: &ClusterTopology) -> Result<(), String> {
+        let (mut extent_width, mut extent_height) = (0, 0);
+        for shard in topology.shard_to_host.keys() {
+            if shard.x < 0 || shard.y < 0 || shard.x + shard.width > width || shard.y + shard.height > height {
+                return Err(format!("shard {} is outside the {}x{} colony", shard.to_id(), width, height));
+            }
+            extent_width = extent_width.max(shard.x + shard.width);
+            extent_height = extent_height.max(shard.y + shard.height);
+        }
+        if (extent_width, extent_height) != (width, height) {
+            return Err(format!("topology covers {}x{} but the colony is {}x{}", extent_width, extent_height, width, height));
+        }
+        Ok(())
+    }
 }
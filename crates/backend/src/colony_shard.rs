@@ -1,15 +1,25 @@
 use serde::{Deserialize, Serialize};
-use shared::be_api::{Cell, ColonyLifeRules, Color, Shard, Traits};
+use shared::be_api::{Cell, ColonyLifeRules, Color, SeedingOptions, SeedingPattern, Shard, ShardEventRecord, TickStateHash, Traits};
+use shared::colony_model::{Biome, LocalPos};
+use shared::density::DensityGrid;
 use shared::log;
+use shared::state_hash::StateHasher;
 use shared::utils::{new_random_generator, random_chance, random_color};
 use rand::{Rng, rngs::SmallRng};
 use rand::seq::SliceRandom;
 use std::cmp::min;
-use std::sync::OnceLock;
+use std::collections::VecDeque;
+use std::ops::Range;
+use std::sync::{Arc, OnceLock};
+use uuid::Uuid;
+use crate::shard_lease::LeaseState;
 use crate::shard_utils::ShardUtils;
 
 pub const WHITE_COLOR: Color = Color { red: 255, green: 255, blue: 255 };
 const LOG_TICK_STATS: bool = false;
+const RECENT_EVENT_IDS_CAPACITY: usize = 256;
+// Enough ticks to cover the interval between two colony captures
+const DIRTY_PIXELS_JOURNAL_CAPACITY: usize = 8192;
 
 #[derive(Clone, Copy)]
 pub struct CreatureTemplate {
@@ -17,6 +27,95 @@ pub struct CreatureTemplate {
     pub traits: Traits,
 }
 
+/// The random creatures of a new shard and where they may go, chosen once so the grid can be
+/// seeded a range of cells at a time
+pub struct ShardSeeder {
+    templates: Vec<CreatureTemplate>,
+    region: SeedingRegion,
+    density: f64,
+}
+
+impl ShardSeeder {
+    pub fn new(shard: &Shard, seeding: &SeedingOptions, rng: &mut SmallRng) -> Self {
+        const NUM_RANDOM_CREATURES: usize = 3;
+        let templates: Vec<CreatureTemplate> = (0..NUM_RANDOM_CREATURES)
+            .map(|_| CreatureTemplate {
+                color: random_color(rng),
+                traits: Traits { 
+                    size: rng.gen_range(15..20),
+                    can_move: rng.gen_bool(0.5),
+                    can_kill: rng.gen_bool(0.5),
+                },
+            })
+            .collect();
+        let region = SeedingRegion::new(&seeding.pattern, shard.width, shard.height, rng);
+        Self { templates, region, density: seeding.density }
+    }
+
+    /// Seeds the grid cells in the range; seeding consecutive ranges from the same rng gives
+    /// the same grid as seeding them all at once
+    pub fn seed_cells(&self, colony_shard: &mut ColonyShard, cells: Range<usize>, rng: &mut SmallRng) {
+        for id in cells {
+            if self.region.contains(LocalPos::from_grid_index(id, &colony_shard.shard)) && rng.gen_bool(self.density) {
+                let template = self.templates[rng.gen_range(0..self.templates.len())];
+                let cell = &mut colony_shard.grid[id];
+                cell.color = template.color;
+                cell.original_color = template.color;
+                cell.health = 80;
+                cell.traits = template.traits;
+            }
+        }
+    }
+}
+
+/// Cells of a shard a SeedingPattern may place creatures on
+enum SeedingRegion {
+    /// Every grid cell, shadow margin included, as the uniform seeding always did
+    All,
+    Clusters { centers: Vec<(i32, i32)>, radius: i32 },
+    Edges { width: i32, height: i32, band: i32 },
+    Center { width: i32, height: i32 },
+}
+
+impl SeedingRegion {
+    fn new(pattern: &SeedingPattern, width: i32, height: i32, rng: &mut SmallRng) -> Self {
+        match *pattern {
+            SeedingPattern::Uniform => SeedingRegion::All,
+            SeedingPattern::Clustered { num_clusters, cluster_radius } => SeedingRegion::Clusters {
+                centers: (0..num_clusters).map(|_| (rng.gen_range(0..width), rng.gen_range(0..height))).collect(),
+                radius: cluster_radius as i32,
+            },
+            SeedingPattern::EdgeOnly => SeedingRegion::Edges { width, height, band: (width.min(height) / 10).max(1) },
+            SeedingPattern::CenterOnly => SeedingRegion::Center { width, height },
+        }
+    }
+
+    fn contains(&self, pos: LocalPos) -> bool {
+        let LocalPos { x, y } = pos;
+        match self {
+            SeedingRegion::All => true,
+            SeedingRegion::Clusters { centers, radius } => centers.iter()
+                .any(|&(cx, cy)| (x - cx).pow(2) + (y - cy).pow(2) <= radius * radius),
+            SeedingRegion::Edges { width, height, band } => {
+                let inside = x >= 0 && x < *width && y >= 0 && y < *height;
+                inside && (x < *band || y < *band || x >= width - band || y >= height - band)
+            }
+            SeedingRegion::Center { width, height } => {
+                x >= width / 4 && x < width - width / 4 && y >= height / 4 && y < height - height / 4
+            }
+        }
+    }
+}
+
+/// What came of a creature's turn to attack
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttackOutcome {
+    NoAttack,
+    Killed,
+    /// The defender survived and the attacker took counter damage
+    Repelled,
+}
+
 #[derive(Debug)]
 struct TickStats {
     #[allow(dead_code)]
@@ -26,10 +125,17 @@ struct TickStats {
     deaths: usize,
     moves: usize,
     breeds: usize,
-    kills: usize,
+    deaths_by_predation: usize,
+    failed_attacks: usize,
 }
 
 impl TickStats {
+    /// Estimate of the pixels whose color changed: moves and kills touch two cells. A pixel
+    /// changed twice counts twice, and repaints by colony events are not counted.
+    fn estimated_changed_pixels(&self) -> u32 {
+        (self.deaths + self.breeds + 2 * self.moves + 2 * self.deaths_by_predation) as u32
+    }
+
     fn new(tick_bit: bool) -> Self {
         Self {
             tick_bit,
@@ -38,7 +144,8 @@ impl TickStats {
             deaths: 0,
             moves: 0,
             breeds: 0,
-            kills: 0,
+            deaths_by_predation: 0,
+            failed_attacks: 0,
         }
     }
 }
@@ -70,6 +177,52 @@ pub struct ColonyShard {
     pub colony_life_rules: ColonyLifeRules,    
     pub grid: Vec<Cell>,
     pub current_tick: u64, 
+    pub recent_event_ids: VecDeque<Uuid>,
+    /// (tick, changed pixels) for recent ticks, oldest first
+    pub dirty_pixels_journal: VecDeque<(u64, u32)>,
+    /// Skips ticks and walls off its neighbors, see SetShardFrozenRequest
+    #[serde(default)]
+    pub frozen: bool,
+    /// Topography was promised at init but has not arrived; holds still like a frozen shard
+    #[serde(default)]
+    pub awaiting_topography: bool,
+    /// Per grid cell, true where random death does not apply; empty when the topography has no sanctuaries
+    #[serde(default)]
+    pub sanctuary: Vec<bool>,
+    /// Biomes clipped to this shard, see set_biomes
+    #[serde(default)]
+    pub biomes: Vec<Biome>,
+    /// Base rules with each biome's overrides applied, in the order of biomes
+    #[serde(skip)]
+    pub biome_rules: Vec<ColonyLifeRules>,
+    /// Per grid cell, 0 for the base rules or 1 + the index of the biome that owns the cell;
+    /// empty when no biome touches the shard
+    #[serde(skip)]
+    pub biome_index: Vec<u8>,
+    /// Events applied to this shard, oldest first, see record_event
+    #[serde(default)]
+    pub event_log: VecDeque<ShardEventRecord>,
+    /// Bumped every time terrain is applied; 0 until the first topography arrives
+    #[serde(default)]
+    pub topography_version: u64,
+    /// Terrain received while ticking, applied before the next tick, see ShardTopography::queue_topography
+    #[serde(skip)]
+    pub pending_topography: Option<Vec<u8>>,
+    /// End-of-tick state hashes, oldest first, kept only while the determinism audit is on
+    #[serde(skip)]
+    pub state_hashes: VecDeque<TickStateHash>,
+    /// Ownership lease from the coordinator; a suspended shard neither ticks nor sends borders
+    #[serde(skip)]
+    pub lease: LeaseState,
+    /// Last density grid served, with the cells it was asked for; valid for its tick only
+    #[serde(skip)]
+    pub density_cache: Option<(usize, Arc<DensityGrid>)>,
+    /// Times a panic poisoned the shard's lock and it was recovered, see crate::shard_lock
+    #[serde(skip)]
+    pub lock_recoveries: u64,
+    /// Invariant the state broke after such a recovery; a quarantined shard no longer ticks or renders
+    #[serde(skip)]
+    pub quarantine: Option<String>,
 }
 
 impl ColonyShard {
@@ -77,6 +230,159 @@ impl ColonyShard {
         self.current_tick
     }
 
+    pub fn is_quarantined(&self) -> bool {
+        self.quarantine.is_some()
+    }
+
+    /// Creature density over the shard's interior at cells buckets per side, computed at most
+    /// once per tick and cells value
+    pub fn density(&mut self, cells: usize) -> Arc<DensityGrid> {
+        if let Some((cached_cells, grid)) = &self.density_cache {
+            if *cached_cells == cells && grid.tick == self.current_tick {
+                return grid.clone();
+            }
+        }
+        let positions = self.grid.iter().enumerate()
+            .filter(|(_, cell)| cell.health > 0)
+            .map(|(idx, _)| LocalPos::from_grid_index(idx, &self.shard))
+            .filter(|pos| pos.is_interior(&self.shard))
+            .map(|pos| {
+                let global = pos.to_global(&self.shard);
+                (global.x, global.y)
+            });
+        let grid = Arc::new(DensityGrid::from_positions(self.shard, self.current_tick, cells, positions));
+        self.density_cache = Some((cells, grid.clone()));
+        grid
+    }
+
+    pub fn is_sanctuary(&self, cell_idx: usize) -> bool {
+        self.sanctuary.get(cell_idx).copied().unwrap_or(false)
+    }
+
+    /// Keeps the part of each biome inside the shard and rebuilds the per-cell biome index
+    pub fn set_biomes(&mut self, biomes: &[Biome]) {
+        self.biomes = biomes.iter().filter_map(|biome| biome.clip_to(&self.shard)).take(shared::colony_model::MAX_BIOMES).collect();
+        self.biome_index = Vec::new();
+        if !self.biomes.is_empty() {
+            self.biome_index = (0..self.grid.len()).map(|idx| {
+                // No clipped biome covers the shadow margin
+                let pos = LocalPos::from_grid_index(idx, &self.shard).to_global(&self.shard);
+                self.biomes.iter().rposition(|biome| biome.contains(pos)).map_or(0, |i| i as u8 + 1)
+            }).collect();
+        }
+        self.refresh_biome_rules();
+    }
+
+    /// Recomputes the biome rules from the base rules; call after colony_life_rules changes
+    pub fn refresh_biome_rules(&mut self) {
+        self.biome_rules = self.biomes.iter().map(|biome| biome.overrides.apply(&self.colony_life_rules)).collect();
+    }
+
+    /// Rules for the creature in cell_idx: its biome's, or the shard's base rules
+    #[inline(always)]
+    pub fn rules_at(&self, cell_idx: usize) -> ColonyLifeRules {
+        match self.biome_index.get(cell_idx) {
+            Some(&biome) if biome > 0 => self.biome_rules[biome as usize - 1],
+            _ => self.colony_life_rules,
+        }
+    }
+
+    /// Remembers event_id in a bounded window; returns false if it was already applied here
+    pub fn record_applied_event(&mut self, event_id: Uuid) -> bool {
+        if self.recent_event_ids.contains(&event_id) {
+            return false;
+        }
+        if self.recent_event_ids.len() >= RECENT_EVENT_IDS_CAPACITY {
+            self.recent_event_ids.pop_front();
+        }
+        self.recent_event_ids.push_back(event_id);
+        true
+    }
+
+    /// Appends to the event log, evicting the oldest records beyond capacity
+    pub fn record_event(&mut self, record: ShardEventRecord, capacity: usize) {
+        while self.event_log.len() >= capacity.max(1) {
+            self.event_log.pop_front();
+        }
+        self.event_log.push_back(record);
+    }
+
+    /// Up to limit event log records, newest first, optionally only those of one event
+    pub fn event_log_records(&self, event_id: Option<Uuid>, limit: usize) -> Vec<ShardEventRecord> {
+        self.event_log.iter().rev()
+            .filter(|record| event_id.is_none_or(|id| record.event_id == id))
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    fn record_dirty_pixels(&mut self, tick: u64, changed_pixels: u32) {
+        if self.dirty_pixels_journal.len() >= DIRTY_PIXELS_JOURNAL_CAPACITY {
+            self.dirty_pixels_journal.pop_front();
+        }
+        self.dirty_pixels_journal.push_back((tick, changed_pixels));
+    }
+
+    /// Estimated pixels changed by the ticks after since_tick, capped at the shard size.
+    /// None when the journal no longer reaches back to since_tick.
+    pub fn estimated_changed_pixels_since(&self, since_tick: u64) -> Option<u32> {
+        if since_tick >= self.current_tick {
+            return Some(0);
+        }
+        let oldest_tick = self.dirty_pixels_journal.front()?.0;
+        if oldest_tick > since_tick + 1 {
+            return None;
+        }
+        let changed: u64 = self.dirty_pixels_journal.iter()
+            .filter(|(tick, _)| *tick > since_tick)
+            .map(|(_, count)| *count as u64)
+            .sum();
+        let shard_pixels = (self.shard.width * self.shard.height) as u64;
+        Some(changed.min(shard_pixels) as u32)
+    }
+
+    /// Structural invariants that the tick and the renderers index by without checking;
+    /// a shard breaking one would panic again on its next tick
+    pub fn check_invariants(&self) -> Result<(), String> {
+        let cells = self.shard.grid_len();
+        if self.grid.len() != cells {
+            return Err(format!("grid has {} cells, expected {}", self.grid.len(), cells));
+        }
+        if !self.sanctuary.is_empty() && self.sanctuary.len() != cells {
+            return Err(format!("sanctuary mask has {} cells, expected {}", self.sanctuary.len(), cells));
+        }
+        if self.biome_rules.len() != self.biomes.len() {
+            return Err(format!("{} biome rules for {} biomes", self.biome_rules.len(), self.biomes.len()));
+        }
+        if !self.biome_index.is_empty() && self.biome_index.len() != cells {
+            return Err(format!("biome index has {} cells, expected {}", self.biome_index.len(), cells));
+        }
+        if let Some(&biome) = self.biome_index.iter().find(|&&biome| biome as usize > self.biomes.len()) {
+            return Err(format!("biome index names biome {} of {}", biome, self.biomes.len()));
+        }
+        self.colony_life_rules.validate()
+    }
+
+    /// Hash of the cells this shard owns; the shadow margin belongs to the neighbors
+    pub fn state_hash(&self) -> u64 {
+        let mut hasher = StateHasher::new();
+        for y in 0..self.shard.height {
+            hasher.write_cells(&self.grid[self.shard.interior_row(y)]);
+        }
+        hasher.finish()
+    }
+
+    /// Appends the hash of the current tick, keeping the last capacity ticks
+    pub fn record_state_hash(&mut self, capacity: usize) {
+        while self.state_hashes.len() >= capacity.max(1) {
+            self.state_hashes.pop_front();
+        }
+        let hash = self.state_hash();
+        self.state_hashes.push_back(TickStateHash { tick: self.current_tick, hash });
+    }
+
+    /// Takes raw grid coordinates, like the rest of the tick loop: it runs for every cell on
+    /// every tick, so it stays on plain indices rather than LocalPos
     #[inline(always)]
     fn get_neighbors(x: usize, y: usize, width: usize, height: usize, offsets: &[(isize, isize)], my_cell: usize, neighbors: &mut [usize]) -> usize {
         let mut count = 0;
@@ -102,13 +408,64 @@ impl ColonyShard {
             + can_kill_cost + can_move_cost
     }
 
+    /// A creature that finds nothing to eat skips its health cost until it has gone hungry for
+    /// more than starvation_grace_ticks in a row. Hunger is only counted under rules that use it.
     fn eat_food(&mut self, cell_idx: usize) {
+        let rules = self.rules_at(cell_idx);
         let size: u16 = self.grid[cell_idx].traits.size as u16;
-        let max_food_can_eat = size.saturating_mul(self.colony_life_rules.eat_capacity_per_size_unit as u16);
+        let max_food_can_eat = size.saturating_mul(rules.eat_capacity_per_size_unit as u16);
         let food_eaten: u16 = min(self.grid[cell_idx].food, max_food_can_eat);
-        let health_cost = Self::calculate_health_cost_for_cell(&self.grid[cell_idx], &self.colony_life_rules);
-        self.grid[cell_idx].health = self.grid[cell_idx].health.saturating_add(food_eaten).saturating_sub(health_cost);
-        self.grid[cell_idx].food = self.grid[cell_idx].food.saturating_sub(food_eaten);
+        let cell = &mut self.grid[cell_idx];
+        if food_eaten > 0 {
+            cell.hunger = 0;
+        } else if rules.starvation_grace_ticks > 0 || Self::shares_food(&rules) {
+            cell.hunger = cell.hunger.saturating_add(1);
+        }
+        let in_grace = food_eaten == 0 && rules.starvation_grace_ticks > 0 && cell.hunger as u32 <= rules.starvation_grace_ticks;
+        let health_cost = if in_grace { 0 } else { Self::calculate_health_cost_for_cell(cell, &rules) };
+        cell.health = cell.health.saturating_add(food_eaten).saturating_sub(health_cost);
+        cell.food = cell.food.saturating_sub(food_eaten);
+    }
+
+    fn shares_food(rules: &ColonyLifeRules) -> bool {
+        rules.food_sharing_radius > 0 && rules.food_sharing_percent > 0
+    }
+
+    /// Gives food_sharing_percent of the food left on my_cell after eating to the hungriest
+    /// creature within food_sharing_radius; the one with less health wins a tie, then the first
+    /// in row order. Food only moves between the shard's own cells, so the colony's total is kept.
+    fn share_food(&mut self, my_cell: usize, x: usize, y: usize, width: usize, height: usize) {
+        let rules = self.rules_at(my_cell);
+        if !Self::shares_food(&rules) || x == 0 || y == 0 || x == width - 1 || y == height - 1 {
+            return;
+        }
+        let shared = (self.grid[my_cell].food as u32 * rules.food_sharing_percent / 100) as u16;
+        if shared == 0 {
+            return;
+        }
+        let radius = rules.food_sharing_radius as usize;
+        let mut hungriest: Option<usize> = None;
+        for ny in y.saturating_sub(radius).max(1)..=(y + radius).min(height - 2) {
+            for nx in x.saturating_sub(radius).max(1)..=(x + radius).min(width - 2) {
+                let n = ny * width + nx;
+                let candidate = &self.grid[n];
+                if n == my_cell || is_blank(candidate) || candidate.hunger == 0 {
+                    continue;
+                }
+                let hungrier = hungriest.is_none_or(|best| {
+                    let best = &self.grid[best];
+                    (candidate.hunger, std::cmp::Reverse(candidate.health)) > (best.hunger, std::cmp::Reverse(best.health))
+                });
+                if hungrier {
+                    hungriest = Some(n);
+                }
+            }
+        }
+        if let Some(n) = hungriest {
+            let shared = shared.min(u16::MAX - self.grid[n].food);
+            self.grid[n].food += shared;
+            self.grid[my_cell].food -= shared;
+        }
     }
 
     #[inline(always)]
@@ -130,6 +487,7 @@ impl ColonyShard {
                 self.grid[n].original_color = self.grid[my_cell].original_color;
                 self.grid[n].health = self.grid[my_cell].health;
                 self.grid[n].age = self.grid[my_cell].age;
+                self.grid[n].hunger = self.grid[my_cell].hunger;
                 self.grid[n].traits = self.grid[my_cell].traits;
                 self.grid[n].tick_bit = next_bit;
                 set_blank(&mut self.grid[my_cell]);
@@ -139,32 +497,25 @@ impl ColonyShard {
         false
     }
 
-    pub fn randomize_at_start(&mut self, rng: &mut SmallRng) {
-        const NUM_RANDOM_CREATURES: usize = 3;
-        let creature_templates: Vec<CreatureTemplate> = (0..NUM_RANDOM_CREATURES)
-            .map(|_| CreatureTemplate {
-                color: random_color(rng),
-                traits: Traits { 
-                    size: rng.gen_range(15..20),
-                    can_move: rng.gen_bool(0.5),
-                    can_kill: rng.gen_bool(0.5),
-                },
-            })
-            .collect();
+    #[allow(dead_code)]
+    pub fn randomize_at_start(&mut self, seeding: &SeedingOptions, rng: &mut SmallRng) {
+        let seeder = ShardSeeder::new(&self.shard, seeding, rng);
+        seeder.seed_cells(self, 0..self.grid.len(), rng);
+    }
 
-        for id in 0..self.grid.len() {
-            if rng.gen_bool(0.1) {
-                let template = creature_templates[rng.gen_range(0..creature_templates.len())];
-                self.grid[id].color = template.color;
-                self.grid[id].original_color = template.color;
-                self.grid[id].health = 80;
-                self.grid[id].traits = template.traits;
-            }
-        }
+    pub fn tick(&mut self, rng: &mut SmallRng) {
+        self.tick_with_journal(rng, true);
+    }
+
+    /// A tick that leaves the dirty pixels journal empty, so estimated_changed_pixels_since never sums
+    /// across the ticks it did not record
+    pub fn tick_without_journal(&mut self, rng: &mut SmallRng) {
+        self.dirty_pixels_journal.clear();
+        self.tick_with_journal(rng, false);
     }
 
     #[inline(never)]
-    pub fn tick(&mut self, rng: &mut SmallRng) {
+    fn tick_with_journal(&mut self, rng: &mut SmallRng, journal: bool) {
         if self.grid.is_empty() { return; }        
         let width = (self.shard.width + 2) as usize;
         let height = (self.shard.height + 2) as usize;
@@ -196,7 +547,7 @@ impl ColonyShard {
                     continue;
                 }
                 self.grid[my_cell].age = self.grid[my_cell].age.saturating_add(1);
-                if random_chance(rng, self.colony_life_rules.random_death_chance) {
+                if !self.is_sanctuary(my_cell) && random_chance(rng, self.rules_at(my_cell).random_death_chance) {
                     set_blank(&mut self.grid[my_cell]);
                     stats.deaths += 1;
                     continue;
@@ -213,14 +564,23 @@ impl ColonyShard {
                     continue;
                 }
 
-                if self.kill_neighbour(my_cell, &neighbors, neighbor_count, next_bit, rng) {
-                    stats.kills += 1;
-                } else {
-                    if self.breed(my_cell, &neighbors, neighbor_count, next_bit, rng) {
-                        stats.breeds += 1;
-                    } else {
-                        if self.move_to_higher_food_neighbor(my_cell, &neighbors, neighbor_count, next_bit, rng) {
-                            stats.moves += 1;
+                self.share_food(my_cell, x, y, width, height);
+
+                match self.kill_neighbour(my_cell, &neighbors, neighbor_count, next_bit, rng) {
+                    AttackOutcome::Killed => stats.deaths_by_predation += 1,
+                    AttackOutcome::Repelled => {
+                        stats.failed_attacks += 1;
+                        if is_blank(&self.grid[my_cell]) {
+                            stats.deaths += 1;
+                        }
+                    }
+                    AttackOutcome::NoAttack => {
+                        if self.breed(my_cell, &neighbors, neighbor_count, next_bit, rng) {
+                            stats.breeds += 1;
+                        } else {
+                            if self.move_to_higher_food_neighbor(my_cell, &neighbors, neighbor_count, next_bit, rng) {
+                                stats.moves += 1;
+                            }
                         }
                     }
                 }
@@ -233,11 +593,17 @@ impl ColonyShard {
                 self.shard.x, self.shard.y, self.shard.width, self.shard.height, stats);
         }
         self.current_tick += 1;
+        if journal {
+            self.record_dirty_pixels(self.current_tick, stats.estimated_changed_pixels());
+        }
     }
     
     fn breed(&mut self, my_cell: usize, neighbors: &[usize], neighbor_count: usize, next_bit: bool, rng: &mut SmallRng) -> bool {
-        let cost_per_tick: u16 = (self.colony_life_rules.health_cost_per_size_unit as u16).saturating_mul(self.grid[my_cell].traits.size as u16);
-        if self.grid[my_cell].health <= cost_per_tick {
+        let rules = self.rules_at(my_cell);
+        let cost_per_tick: u16 = (rules.health_cost_per_size_unit as u16).saturating_mul(self.grid[my_cell].traits.size as u16);
+        let food_cost = rules.reproduction_food_cost.min(u16::MAX as u32) as u16;
+        let health = self.grid[my_cell].health;
+        if health <= cost_per_tick || (health as u32) < rules.reproduction_min_food || health <= food_cost {
             return false;
         }
         
@@ -245,17 +611,20 @@ impl ColonyShard {
             let neighbor = neighbors[i];
             if is_blank(&self.grid[neighbor]) {
                 if random_chance(rng, 5) { return false; }  
+                // The parent pays the cost, then splits what is left; the offspring also gets half the cost
+                self.grid[my_cell].health -= food_cost;
                 let half_health = self.grid[my_cell].health / 2;
 
                 self.grid[neighbor].color = self.grid[my_cell].color;
                 self.grid[neighbor].original_color = self.grid[my_cell].original_color;
-                self.grid[neighbor].health = half_health;
+                self.grid[neighbor].health = half_health.saturating_add(food_cost / 2);
                 self.grid[neighbor].age = 1;
                 self.grid[neighbor].traits = self.grid[my_cell].traits;
                 self.grid[neighbor].tick_bit = next_bit;
-                if random_chance(rng, self.colony_life_rules.mutation_chance) {
-                    self.grid[neighbor] = self.mutate_cell(&self.grid[neighbor], rng);
+                if random_chance(rng, rules.mutation_chance) {
+                    self.grid[neighbor] = Self::mutate_cell(&self.grid[neighbor], &rules, rng);
                 }
+                self.grid[neighbor].color = Self::inherit_color(self.grid[neighbor].color, &rules, rng);
                 self.grid[my_cell].health = self.grid[my_cell].health.saturating_sub(half_health);
                 
                 return true;
@@ -265,12 +634,24 @@ impl ColonyShard {
         false
     }
     
-    fn mutate_cell(&self, cell: &Cell, rng: &mut SmallRng) -> Cell {        
+    /// Offspring traits moved by the rules' mutation steps; a change of the health cost per tick
+    /// beyond mutation_cost_step keeps the parent's traits, so only the color mutates
+    fn mutate_cell(cell: &Cell, rules: &ColonyLifeRules, rng: &mut SmallRng) -> Cell {
         let mut new_cell = *cell;
-        let size_change = if rng.gen_bool(0.5) { 1 } else { -1 };
-        new_cell.traits.size = cell.traits.size.saturating_add_signed(size_change);
-        new_cell.traits.can_kill = if random_chance(rng, 100) { cell.traits.can_kill } else { !cell.traits.can_kill };
-        new_cell.traits.can_move = if random_chance(rng, 100) { cell.traits.can_move } else { !cell.traits.can_move };
+        let size_step = rules.mutation_size_step.min(u8::MAX as u32) as u8;
+        new_cell.traits.size = if rng.gen_bool(0.5) {
+            cell.traits.size.saturating_add(size_step)
+        } else {
+            cell.traits.size.saturating_sub(size_step)
+        };
+        let flip_chance = rules.boolean_trait_flip_chance;
+        new_cell.traits.can_kill = cell.traits.can_kill ^ (rng.gen_range(0..100) < flip_chance);
+        new_cell.traits.can_move = cell.traits.can_move ^ (rng.gen_range(0..100) < flip_chance);
+        let old_cost = Self::calculate_health_cost_for_cell(cell, rules);
+        let new_cost = Self::calculate_health_cost_for_cell(&new_cell, rules);
+        if old_cost.abs_diff(new_cost) as u32 > rules.mutation_cost_step {
+            new_cell.traits = cell.traits;
+        }
 
         let color_mutation_range = 3; 
         let red_change = rng.gen_range(-color_mutation_range..=color_mutation_range);
@@ -284,33 +665,73 @@ impl ColonyShard {
         new_cell   
     }
     
+    /// Offspring color after the rules' drift and, rarely, a full re-roll. Draws nothing from
+    /// the RNG for rules that keep the color, so runs without them replay as before.
+    fn inherit_color(color: Color, rules: &ColonyLifeRules, rng: &mut SmallRng) -> Color {
+        if rules.color_mutation_chance > 0 && random_chance(rng, rules.color_mutation_chance) {
+            return random_color(rng);
+        }
+        if rules.color_drift_per_generation == 0 {
+            return color;
+        }
+        let drift = rules.color_drift_per_generation.min(u8::MAX as u32) as i16;
+        let mut drift_channel = |channel: u8| (channel as i16 + rng.gen_range(-drift..=drift)).clamp(0, 255) as u8;
+        Color { red: drift_channel(color.red), green: drift_channel(color.green), blue: drift_channel(color.blue) }
+    }
+
     #[inline(always)]
     fn kill_neighbour(&mut self, my_cell: usize, neighbors: &[usize], neighbor_count: usize, 
-            next_bit: bool, rng: &mut SmallRng) -> bool 
+            next_bit: bool, rng: &mut SmallRng) -> AttackOutcome 
     {
         if !self.grid[my_cell].traits.can_kill {
-            return false;
+            return AttackOutcome::NoAttack;
         }
         let my_size  = self.grid[my_cell].traits.size;
         let my_color = self.grid[my_cell].color;
+        let rules = self.rules_at(my_cell);
 
         for i in 0..neighbor_count {
             let n = neighbors[i];
             let nref = &self.grid[n];
-            if my_size >= nref.traits.size && nref.health > 0 && !my_color.equals(&nref.color) {
-                if !random_chance(rng, 10) { continue }
-                self.grid[n].health = self.grid[my_cell].health.saturating_add(nref.health);
-                self.grid[n].age = self.grid[my_cell].age;
-                self.grid[n].color = self.grid[my_cell].color;
-                self.grid[n].original_color = self.grid[my_cell].original_color;
-                self.grid[n].traits = self.grid[my_cell].traits;
-                self.grid[n].tick_bit = next_bit;
+            if nref.health == 0 || my_color.equals(&nref.color) {
+                continue;
+            }
+            // Never picks a fight it cannot win
+            if rules.kill_success_percent(my_size, nref.traits.size) == 0 {
+                continue;
+            }
+            if !random_chance(rng, 10) { continue }
+            return self.resolve_attack(my_cell, n, next_bit, rng);
+        }
+        AttackOutcome::NoAttack
+    }
 
-                set_blank(&mut self.grid[my_cell]);
-                return true;
+    /// One attack of my_cell on neighbor n. The kill succeeds with the rules' size-based chance
+    /// and the attacker takes the defender's cell; otherwise the attacker takes counter damage
+    /// and dies if that uses up its health.
+    pub fn resolve_attack(&mut self, my_cell: usize, n: usize, next_bit: bool, rng: &mut SmallRng) -> AttackOutcome {
+        let rules = self.rules_at(my_cell);
+        let kill_percent = rules.kill_success_percent(self.grid[my_cell].traits.size, self.grid[n].traits.size);
+        if kill_percent < 100 && rng.gen_range(0..100) >= kill_percent {
+            let attacker = &mut self.grid[my_cell];
+            attacker.health = attacker.health.saturating_sub(rules.kill_counter_damage.min(u16::MAX as u32) as u16);
+            if attacker.health == 0 {
+                set_blank(attacker);
             }
+            return AttackOutcome::Repelled;
         }
-        false
+
+        let nref = self.grid[n];
+        self.grid[n].health = self.grid[my_cell].health.saturating_add(nref.health);
+        self.grid[n].age = self.grid[my_cell].age;
+        self.grid[n].hunger = self.grid[my_cell].hunger;
+        self.grid[n].color = self.grid[my_cell].color;
+        self.grid[n].original_color = self.grid[my_cell].original_color;
+        self.grid[n].traits = self.grid[my_cell].traits;
+        self.grid[n].tick_bit = next_bit;
+
+        set_blank(&mut self.grid[my_cell]);
+        AttackOutcome::Killed
     }
         
 }
@@ -332,11 +753,12 @@ fn assert_blank_consistency(cell: &Cell) {
 }
 
 #[inline(always)]
-fn set_blank(cell: &mut Cell) {
+pub fn set_blank(cell: &mut Cell) {
     cell.color = WHITE_COLOR;
     cell.original_color = WHITE_COLOR;
     cell.health = 0;
     cell.age = 0;
+    cell.hunger = 0;
     #[cfg(debug_assertions)]
     assert_blank_consistency(cell);
 }
@@ -0,0 +1,42 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use shared::{log, log_error};
+use crate::colony::Colony;
+use crate::image_qos::ImageQos;
+use crate::presentation_snapshots::PresentationSnapshots;
+
+/// Retry-After of the image and layer endpoints while fast-forwarding
+pub const FAST_FORWARD_RETRY_AFTER_SECS: u64 = 30;
+
+static FAST_FORWARD: AtomicBool = AtomicBool::new(false);
+
+/// While on, ticks run only the simulation and border exchange: no dirty pixels journal, no
+/// image QoS bookkeeping and no presentation buffers, and image and layer requests get a 503
+pub fn is_fast_forward() -> bool {
+    FAST_FORWARD.load(Ordering::SeqCst)
+}
+
+/// Entering fast-forward drops the cached frames, which would only go stale. Leaving it
+/// repopulates the presentation buffers right away, so they are there even while paused.
+pub async fn set_fast_forward(enabled: bool) {
+    if FAST_FORWARD.swap(enabled, Ordering::SeqCst) == enabled {
+        return;
+    }
+    if enabled {
+        ImageQos::get_instance().clear_frames();
+        PresentationSnapshots::get_instance().clear();
+        log!("Fast-forward on: images, layers and the change journal are off until it is turned off");
+        return;
+    }
+
+    log!("Fast-forward off");
+    let snapshots = PresentationSnapshots::get_instance();
+    if snapshots.is_enabled() && Colony::is_initialized() {
+        let refresh = tokio::task::spawn_blocking(|| {
+            let (_, shard_arcs) = Colony::instance().get_hosted_shards();
+            PresentationSnapshots::get_instance().refresh(&shard_arcs);
+        });
+        if let Err(e) = refresh.await {
+            log_error!("Snapshot refresh after fast-forward failed: {}", e);
+        }
+    }
+}
@@ -2,12 +2,32 @@ use shared::{log, log_error};
 use tokio::net::TcpListener;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use shared::ssm;
-use shared::be_api::{Shard, ColonyLifeRules, ShardLayer};
+use shared::supervisor;
+use shared::cluster_topology::{ClusterTopology, HostInfo};
+use shared::api_auth::{ApiAuthConfig, ApiScope};
+use shared::be_api::{Shard, ShardEventLog, ShardInitProgress, ShardRedirect, ShardStateHashes, ColonyLifeRules, ShardLayer, COLONY_TICK_HEADER, STALE_TICKS_HEADER};
+use shared::shard_render::{ImageBackground, BACKGROUND_QUERY_PARAM};
+use shared::density::{parse_density_cells, DEFAULT_SHARD_DENSITY_CELLS};
+use shared::layer_stats::{encode_layer, encode_layer_with_stats, ShardLayerData, LAYER_FORMAT_VERSION_WITH_STATS};
+use shared::utils::{is_root_page_request, parse_query_param};
+use shared::http_access_log::{AccessLog, AccessLoggedStream, AccessLoggedTcpStream};
+use shared::output_paths::OutputPaths;
+use crate::border_outbox::{BorderOutbox, NeighborOutboxStats};
 use crate::colony::Colony;
+use crate::colony_shard::ColonyShard;
+use crate::fast_forward::{is_fast_forward, FAST_FORWARD_RETRY_AFTER_SECS};
+use crate::image_qos::ImageQos;
+use crate::presentation_snapshots::{PresentationSnapshots, FOOD_TINTED_IMAGE_FRAME_KEY, IMAGE_FRAME_KEY};
+use crate::{rpc_metrics, shard_routing, shard_stats};
+use crate::shard_routing::{record_misdirected_request, route_shard_request, ShardRouting};
+use crate::topology_refresh::this_backend_host;
+use crate::rate_limiter::{too_many_requests_response, EndpointClass, RateLimitDecision, RateLimiter};
+use crate::shard_lock::{self, lock_shard, quarantined_shard_ids};
 use crate::shard_utils::ShardUtils;
-use crate::backend_config::{get_backend_hostname, get_backend_port};
+use crate::backend_config::{get_backend_hostname, get_backend_port, get_determinism_audit_ticks};
 use std::fmt::Write;
-use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
 use std::io::Write as IoWrite;
 use flate2::write::GzEncoder;
@@ -15,6 +35,11 @@ use flate2::Compression;
 
 const HTTP_BIND_HOST: &str = "0.0.0.0";
 const HTTP_LATENCY_WINDOW_SIZE: usize = 100;
+const DEFAULT_EVENT_LOG_LIMIT: usize = 50;
+/// Debug page served at GET /, polls /api/shards and draws each hosted shard
+const VIEWER_HTML: &str = include_str!("viewer.html");
+
+static TRUNCATED_RESPONSES: AtomicU64 = AtomicU64::new(0);
 
 #[derive(Debug, Clone)]
 struct HttpLatencyStats {
@@ -109,26 +134,78 @@ pub async fn start_http_server(http_port: u16) {
     let addr = build_http_bind_addr(http_port);
     let listener = TcpListener::bind(&addr).await.expect("Failed to bind HTTP server");
     log!("HTTP server listening on {}", addr);
+    let access_log = Arc::new(AccessLog::rotating(OutputPaths::get_instance().log_file(&format!("be_{}_access", http_port))));
     
     loop {
         match listener.accept().await {
-            Ok((mut stream, _)) => {
+            Ok((stream, peer_addr)) => {
+                // Responses are written in one piece, nothing is gained by Nagle batching
+                if let Err(e) = stream.set_nodelay(true) {
+                    log_error!("Failed to set TCP_NODELAY for {}: {}", peer_addr, e);
+                }
+                let mut stream = AccessLoggedStream::new(stream, access_log.clone(), peer_addr);
+                let access_log = access_log.clone();
                 tokio::spawn(async move {
                     let mut buffer = [0; 1024];
                     if let Ok(n) = stream.read(&mut buffer).await {
                         let request = String::from_utf8_lossy(&buffer[..n]);
+                        stream.set_request(&request);
+                        
+                        let auth = ApiAuthConfig::get_instance();
+                        let scope = match auth.authorize(&request) {
+                            Ok(scope) => scope,
+                            Err(rejection) => {
+                                let _ = stream.write_all(rejection.to_http_response().as_bytes()).await;
+                                return;
+                            }
+                        };
+                        
+                        // The coordinator calls with the admin token; without auth every request is admin, so nobody is trusted by token
+                        let trusted = auth.is_enabled() && scope == ApiScope::Admin;
+                        let class = EndpointClass::for_request(&request);
+                        if let RateLimitDecision::Throttled { retry_after } = RateLimiter::get_instance().check(peer_addr.ip(), class, trusted, Instant::now()) {
+                            let _ = stream.write_all(too_many_requests_response(retry_after).as_bytes()).await;
+                            return;
+                        }
                         
                         if request.starts_with("GET /api/colony-info") {
                             handle_get_colony_info(&mut stream).await;
+                        } else if request.starts_with("GET /api/shards") {
+                            handle_get_hosted_shards(&mut stream).await;
                         } else if request.starts_with("GET /api/shard/") {
-                            // Parse shard endpoints: /api/shard/{shard_id}/image or /api/shard/{shard_id}/layer/{layer_name}
-                            if request.find("/image").is_some() {
+                            // Parse shard endpoints: /api/shard/{shard_id}/image, /api/shard/{shard_id}/image-changed
+                            // /api/shard/{shard_id}/layer/{layer_name} /api/shard/{shard_id}/diagnostics, /api/shard/{shard_id}/event-log?limit=
+                            // /api/shard/{shard_id}/state-hashes, /api/shard/{shard_id}/init-progress or /api/shard/{shard_id}/density?cells=
+                            if request.find("/density").is_some() {
+                                let shard_id = extract_shard_id(&request, "/api/shard/", "/density");
+                                let cells = parse_query_param(&request, "cells");
+                                handle_get_shard_density(&mut stream, &shard_id, cells.as_deref()).await;
+                            } else if request.find("/init-progress").is_some() {
+                                let shard_id = extract_shard_id(&request, "/api/shard/", "/init-progress");
+                                handle_get_shard_init_progress(&mut stream, &shard_id).await;
+                            } else if request.find("/state-hashes").is_some() {
+                                let shard_id = extract_shard_id(&request, "/api/shard/", "/state-hashes");
+                                handle_get_shard_state_hashes(&mut stream, &shard_id).await;
+                            } else if request.find("/event-log").is_some() {
+                                let shard_id = extract_shard_id(&request, "/api/shard/", "/event-log");
+                                let limit = parse_query_param(&request, "limit");
+                                handle_get_shard_event_log(&mut stream, &shard_id, limit.as_deref()).await;
+                            } else if request.find("/diagnostics").is_some() {
+                                let shard_id = extract_shard_id(&request, "/api/shard/", "/diagnostics");
+                                handle_get_shard_diagnostics(&mut stream, &shard_id).await;
+                            } else if request.find("/image-changed").is_some() {
+                                let shard_id = extract_shard_id(&request, "/api/shard/", "/image-changed");
+                                let since_tick = parse_query_param(&request, "since_tick");
+                                handle_get_shard_image_changed(&mut stream, &shard_id, since_tick.as_deref()).await;
+                            } else if request.find("/image").is_some() {
                                 let shard_id = extract_shard_id(&request, "/api/shard/", "/image");
-                                handle_get_shard_image(&mut stream, &shard_id).await;
+                                let background = parse_query_param(&request, BACKGROUND_QUERY_PARAM);
+                                handle_get_shard_image(&mut stream, &shard_id, background.as_deref()).await;
                             } else if let Some(layer_start) = request.find("/layer/") {
                                 let shard_id = extract_shard_id(&request, "/api/shard/", "/layer/");
                                 let layer_name = extract_layer_name(&request, layer_start + "/layer/".len());
-                                handle_get_shard_layer(&mut stream, &shard_id, &layer_name).await;
+                                let format = LayerResponseFormat::from_request(&request);
+                                handle_get_shard_layer(&mut stream, &shard_id, &layer_name, format).await;
                             } else {
                                 let error_json = r#"{"error":"Invalid shard endpoint"}"#;
                                 let response = format!(
@@ -138,6 +215,23 @@ pub async fn start_http_server(http_port: u16) {
                                 );
                                 let _ = stream.write_all(response.as_bytes()).await;
                             }
+                        } else if request.starts_with("GET /api/rpc-stats") {
+                            handle_get_rpc_stats(&mut stream).await;
+                        } else if request.starts_with("GET /metrics") {
+                            let body = rpc_metrics::render_prometheus() + &RateLimiter::get_instance().render_prometheus()
+                                + &ImageQos::get_instance().render_prometheus() + &PresentationSnapshots::get_instance().render_prometheus()
+                                + &shard_stats::render_prometheus()
+                                + &BorderOutbox::get_instance().render_prometheus() + &shard_lock::render_prometheus()
+                                + &render_http_prometheus() + &shard_routing::render_prometheus()
+                                + &access_log.render_prometheus("backend");
+                            let response = format!(
+                                "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\n\r\n{}",
+                                body.len(),
+                                body
+                            );
+                            let _ = stream.write_all(response.as_bytes()).await;
+                        } else if request.starts_with("GET /health") {
+                            handle_get_health(&mut stream).await;
                         } else if request.starts_with("GET /debug-ssm") {
                             let body = render_ssm_state().await;
                             let response = format!(
@@ -146,13 +240,19 @@ pub async fn start_http_server(http_port: u16) {
                                 body
                             );
                             let _ = stream.write_all(response.as_bytes()).await;
-                        } else if request.starts_with("GET /") {
-                            let response = "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: 13\r\n\r\nBackend API";
+                        } else if is_root_page_request(&request) {
+                            let response = format!(
+                                "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\n\r\n{}",
+                                VIEWER_HTML.len(),
+                                VIEWER_HTML
+                            );
                             let _ = stream.write_all(response.as_bytes()).await;
                         } else {
                             let response = "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n";
                             let _ = stream.write_all(response.as_bytes()).await;
                         }
+                        // Close with a FIN so the client reads the whole body before the socket goes away
+                        let _ = stream.shutdown().await;
                     }
                 });
             }
@@ -186,7 +286,51 @@ async fn render_ssm_state() -> String {
     body
 }
 
-async fn handle_get_colony_info(stream: &mut tokio::net::TcpStream) {
+async fn handle_get_health(stream: &mut AccessLoggedTcpStream) {
+    let (hosted_shards, quarantined_shards) = if Colony::is_initialized() {
+        let colony = Colony::instance();
+        (colony.get_hosted_shards().0.len(), quarantined_shard_ids(colony))
+    } else {
+        (0, Vec::new())
+    };
+    let tasks = supervisor::supervised_tasks_health();
+    // Discovery saw another coordinator than the one in the topology
+    let topology_stale_reason = ClusterTopology::stale_reason();
+    let status = if topology_stale_reason.is_some() || !quarantined_shards.is_empty() {
+        "degraded"
+    } else {
+        supervisor::health_status(&tasks)
+    };
+    let body = format!(
+        r#"{{"status":"{}","colony_initialized":{},"hosted_shards":{},"fast_forward":{},"topology_stale":{},"topology_stale_reason":{},"quarantined_shards":{},"tasks":{}}}"#,
+        status,
+        Colony::is_initialized(),
+        hosted_shards,
+        is_fast_forward(),
+        topology_stale_reason.is_some(),
+        serde_json::to_string(&topology_stale_reason).unwrap_or_else(|_| "null".to_string()),
+        serde_json::to_string(&quarantined_shards).unwrap_or_else(|_| "[]".to_string()),
+        serde_json::to_string(&tasks).unwrap_or_else(|_| "[]".to_string())
+    );
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+}
+
+async fn handle_get_rpc_stats(stream: &mut AccessLoggedTcpStream) {
+    match serde_json::to_string(&rpc_metrics::snapshot()) {
+        Ok(json) => write_json(stream, "200 OK", &json).await,
+        Err(e) => {
+            log_error!("Failed to serialize RPC stats: {}", e);
+            write_json(stream, "500 Internal Server Error", r#"{"error":"Failed to serialize RPC stats"}"#).await;
+        }
+    }
+}
+
+async fn handle_get_colony_info(stream: &mut AccessLoggedTcpStream) {
     // Check if colony is initialized
     if !Colony::is_initialized() {
         let error_json = r#"{"error":"Colony not initialized"}"#;
@@ -205,7 +349,7 @@ async fn handle_get_colony_info(stream: &mut tokio::net::TcpStream) {
     
     // Get ColonyLifeRules and current_tick from the first available shard
     let (colony_life_rules, current_tick) = if let Some(first_shard_arc) = shard_arcs.first() {
-        let shard = first_shard_arc.lock().unwrap();
+        let shard = lock_shard(first_shard_arc);
         (Some(shard.colony_life_rules), Some(shard.current_tick))
     } else {
         (None, None)
@@ -222,8 +366,8 @@ async fn handle_get_colony_info(stream: &mut tokio::net::TcpStream) {
     }
     
     let response_data = Response {
-        width: colony._width,
-        height: colony._height,
+        width: colony.width(),
+        height: colony.height(),
         shards,
         colony_life_rules,
         current_tick,
@@ -253,6 +397,235 @@ async fn handle_get_colony_info(stream: &mut tokio::net::TcpStream) {
     }
 }
 
+/// Hosted shards with their ticks, for the viewer page
+async fn handle_get_hosted_shards(stream: &mut AccessLoggedTcpStream) {
+    if !Colony::is_initialized() {
+        write_json(stream, "404 Not Found", r#"{"error":"Colony not initialized"}"#).await;
+        return;
+    }
+
+    #[derive(serde::Serialize)]
+    struct HostedShard {
+        shard_id: String,
+        shard: Shard,
+        current_tick: u64,
+        frozen: bool,
+        awaiting_topography: bool,
+        topography_version: u64,
+        lease_epoch: u64,
+        /// The lease expired or was superseded, so the shard no longer ticks
+        suspended: bool,
+        /// Why the shard was quarantined after a poisoned lock, see crate::shard_lock
+        quarantined: Option<String>,
+    }
+
+    #[derive(serde::Serialize)]
+    struct Response {
+        width: i32,
+        height: i32,
+        /// Images and layers are not served while on, see crate::fast_forward
+        fast_forward: bool,
+        shards: Vec<HostedShard>,
+        /// Shards whose grid is still being generated, not hosted yet
+        initializing: Vec<ShardInitProgress>,
+    }
+
+    let colony = Colony::instance();
+    let (_, shard_arcs) = colony.get_hosted_shards();
+    let mut shards: Vec<HostedShard> = shard_arcs.iter()
+        .map(|shard_arc| {
+            let shard = lock_shard(shard_arc);
+            HostedShard {
+                shard_id: shard.shard.to_id(),
+                shard: shard.shard,
+                current_tick: shard.current_tick,
+                frozen: shard.frozen,
+                awaiting_topography: shard.awaiting_topography,
+                topography_version: shard.topography_version,
+                lease_epoch: shard.lease.epoch,
+                suspended: shard.lease.is_suspended(),
+                quarantined: shard.quarantine.clone(),
+            }
+        })
+        .collect();
+    shards.sort_by_key(|entry| (entry.shard.y, entry.shard.x));
+
+    let initializing = colony.init_progress().initializing_shards().into_iter()
+        .map(|(shard, progress)| ShardInitProgress { shard_id: shard.to_id(), progress })
+        .collect();
+    let response_data = Response { width: colony.width(), height: colony.height(), fast_forward: is_fast_forward(), shards, initializing };
+    match serde_json::to_string(&response_data) {
+        Ok(json) => write_json(stream, "200 OK", &json).await,
+        Err(e) => {
+            log_error!("Failed to serialize hosted shards: {}", e);
+            write_json(stream, "500 Internal Server Error", r#"{"error":"Failed to serialize hosted shards"}"#).await;
+        }
+    }
+}
+
+/// Progress of generating the shard's grid; shards hosted without going through
+/// InitColonyShard here, e.g. restored ones, report 1.0
+async fn handle_get_shard_init_progress(stream: &mut AccessLoggedTcpStream, shard_id: &str) {
+    let shard = match Shard::from_id(shard_id) {
+        Ok(shard) => shard,
+        Err(e) => {
+            write_json(stream, "400 Bad Request", &format!(r#"{{"error":"{}"}}"#, e)).await;
+            return;
+        }
+    };
+    if !Colony::is_initialized() {
+        write_json(stream, "404 Not Found", r#"{"error":"Shard not initialized by this backend"}"#).await;
+        return;
+    }
+    let colony = Colony::instance();
+    let hosted = colony.get_hosted_colony_shard_arc(&shard).is_some();
+    let recorded = colony.init_progress().get(&shard);
+    let progress = match recorded {
+        Some(progress) => progress,
+        None if hosted => 1.0,
+        None => {
+            write_json(stream, "404 Not Found", r#"{"error":"Shard not initialized by this backend"}"#).await;
+            return;
+        }
+    };
+    let json = serde_json::to_string(&ShardInitProgress { shard_id: shard.to_id(), progress }).expect("Failed to serialize init progress");
+    write_json(stream, "200 OK", &json).await;
+}
+
+/// Border delivery state of every other backend hosting a neighbor of the shard
+async fn handle_get_shard_diagnostics(stream: &mut AccessLoggedTcpStream, shard_id: &str) {
+    let shard = match Shard::from_id(shard_id) {
+        Ok(shard) => shard,
+        Err(e) => {
+            write_json(stream, "400 Bad Request", &format!(r#"{{"error":"{}"}}"#, e)).await;
+            return;
+        }
+    };
+    if !Colony::is_initialized() || Colony::instance().get_hosted_colony_shard_arc(&shard).is_none() {
+        write_json(stream, "404 Not Found", r#"{"error":"Shard not hosted by this backend"}"#).await;
+        return;
+    }
+    let Some(topology) = ClusterTopology::get_instance() else {
+        write_json(stream, "404 Not Found", r#"{"error":"Topology not initialized"}"#).await;
+        return;
+    };
+
+    #[derive(serde::Serialize)]
+    struct Response {
+        shard_id: String,
+        neighbors: Vec<NeighborOutboxStats>,
+    }
+
+    let this_backend_host = HostInfo::new(get_backend_hostname().to_string(), get_backend_port());
+    let now = Instant::now();
+    let mut neighbors: Vec<NeighborOutboxStats> = topology.get_backend_hosts_for_shards(&topology.get_adjacent_shards(&shard))
+        .into_iter()
+        .filter(|host| *host != this_backend_host)
+        .map(|host| BorderOutbox::get_instance().neighbor_stats(&host, now))
+        .collect();
+    neighbors.sort_by(|a, b| a.neighbor.cmp(&b.neighbor));
+
+    match serde_json::to_string(&Response { shard_id: shard.to_id(), neighbors }) {
+        Ok(json) => write_json(stream, "200 OK", &json).await,
+        Err(e) => {
+            log_error!("Failed to serialize shard diagnostics: {}", e);
+            write_json(stream, "500 Internal Server Error", r#"{"error":"Failed to serialize shard diagnostics"}"#).await;
+        }
+    }
+}
+
+async fn handle_get_shard_event_log(stream: &mut AccessLoggedTcpStream, shard_id: &str, limit: Option<&str>) {
+    let shard = match Shard::from_id(shard_id) {
+        Ok(shard) => shard,
+        Err(e) => {
+            write_json(stream, "400 Bad Request", &format!(r#"{{"error":"{}"}}"#, e)).await;
+            return;
+        }
+    };
+    let limit = match limit.map(|v| v.parse::<usize>()) {
+        None => DEFAULT_EVENT_LOG_LIMIT,
+        Some(Ok(limit)) => limit,
+        Some(Err(_)) => {
+            write_json(stream, "400 Bad Request", r#"{"error":"limit must be a non-negative integer"}"#).await;
+            return;
+        }
+    };
+    let shard_arc = if Colony::is_initialized() { Colony::instance().get_hosted_colony_shard_arc(&shard) } else { None };
+    let Some(shard_arc) = shard_arc else {
+        write_json(stream, "404 Not Found", r#"{"error":"Shard not hosted by this backend"}"#).await;
+        return;
+    };
+    let log = ShardEventLog { shard, records: lock_shard(&shard_arc).event_log_records(None, limit) };
+
+    match serde_json::to_string(&log) {
+        Ok(json) => write_json(stream, "200 OK", &json).await,
+        Err(e) => {
+            log_error!("Failed to serialize shard event log: {}", e);
+            write_json(stream, "500 Internal Server Error", r#"{"error":"Failed to serialize shard event log"}"#).await;
+        }
+    }
+}
+
+/// End-of-tick hashes of the shard's cells, recorded while DETERMINISM_AUDIT_TICKS is set
+async fn handle_get_shard_state_hashes(stream: &mut AccessLoggedTcpStream, shard_id: &str) {
+    let shard = match Shard::from_id(shard_id) {
+        Ok(shard) => shard,
+        Err(e) => {
+            write_json(stream, "400 Bad Request", &format!(r#"{{"error":"{}"}}"#, e)).await;
+            return;
+        }
+    };
+    if get_determinism_audit_ticks() == 0 {
+        write_json(stream, "409 Conflict", r#"{"error":"Determinism audit is off, start the backend with DETERMINISM_AUDIT_TICKS"}"#).await;
+        return;
+    }
+    let shard_arc = if Colony::is_initialized() { Colony::instance().get_hosted_colony_shard_arc(&shard) } else { None };
+    let Some(shard_arc) = shard_arc else {
+        write_json(stream, "404 Not Found", r#"{"error":"Shard not hosted by this backend"}"#).await;
+        return;
+    };
+    let hashes = ShardStateHashes { shard, hashes: lock_shard(&shard_arc).state_hashes.iter().copied().collect() };
+
+    match serde_json::to_string(&hashes) {
+        Ok(json) => write_json(stream, "200 OK", &json).await,
+        Err(e) => {
+            log_error!("Failed to serialize shard state hashes: {}", e);
+            write_json(stream, "500 Internal Server Error", r#"{"error":"Failed to serialize shard state hashes"}"#).await;
+        }
+    }
+}
+
+async fn handle_get_shard_density(stream: &mut AccessLoggedTcpStream, shard_id: &str, cells: Option<&str>) {
+    let shard = match Shard::from_id(shard_id) {
+        Ok(shard) => shard,
+        Err(e) => {
+            write_json(stream, "400 Bad Request", &format!(r#"{{"error":"{}"}}"#, e)).await;
+            return;
+        }
+    };
+    let cells = match parse_density_cells(cells, DEFAULT_SHARD_DENSITY_CELLS) {
+        Ok(cells) => cells,
+        Err(e) => {
+            write_json(stream, "400 Bad Request", &format!(r#"{{"error":"{}"}}"#, e)).await;
+            return;
+        }
+    };
+    let shard_arc = if Colony::is_initialized() { Colony::instance().get_hosted_colony_shard_arc(&shard) } else { None };
+    let Some(shard_arc) = shard_arc else {
+        write_json(stream, "404 Not Found", r#"{"error":"Shard not hosted by this backend"}"#).await;
+        return;
+    };
+    let grid = lock_shard(&shard_arc).density(cells);
+
+    match serde_json::to_string(&*grid) {
+        Ok(json) => write_json(stream, "200 OK", &json).await,
+        Err(e) => {
+            log_error!("Failed to serialize shard density: {}", e);
+            write_json(stream, "500 Internal Server Error", r#"{"error":"Failed to serialize shard density"}"#).await;
+        }
+    }
+}
+
 fn extract_shard_id(request: &str, prefix: &str, suffix: &str) -> String {
     if let Some(start) = request.find(prefix) {
         let start_idx = start + prefix.len();
@@ -264,16 +637,17 @@ fn extract_shard_id(request: &str, prefix: &str, suffix: &str) -> String {
 }
 
 fn extract_layer_name(request: &str, start_idx: usize) -> String {
-    // Extract layer name until space or newline (end of HTTP request line)
+    // Extract layer name until query string, space or newline (end of HTTP request line)
     let remaining = &request[start_idx..];
-    if let Some(end) = remaining.find(|c: char| c == ' ' || c == '\r' || c == '\n') {
+    if let Some(end) = remaining.find(|c: char| c == '?' || c == ' ' || c == '\r' || c == '\n') {
         remaining[..end].to_string()
     } else {
         remaining.to_string()
     }
 }
 
-fn layer_name_to_enum(layer_name: &str) -> Result<ShardLayer, String> {
+/// Layer names as they appear in /api/shard/{id}/layer/{name}
+pub fn layer_name_to_enum(layer_name: &str) -> Result<ShardLayer, String> {
     match layer_name {
         "creature-size" => Ok(ShardLayer::CreatureSize),
         "extra-food" => Ok(ShardLayer::ExtraFood),
@@ -283,11 +657,127 @@ fn layer_name_to_enum(layer_name: &str) -> Result<ShardLayer, String> {
         "food" => Ok(ShardLayer::Food),
         "health" => Ok(ShardLayer::Health),
         "age" => Ok(ShardLayer::Age),
+        "sanctuary" => Ok(ShardLayer::Sanctuary),
         _ => Err(format!("Invalid layer name: {}", layer_name)),
     }
 }
 
-async fn handle_get_shard_image(stream: &mut tokio::net::TcpStream, shard_id: &str) {
+/// A rendered shard image or layer body and the tick it shows
+struct PresentationFrame {
+    body: Arc<Vec<u8>>,
+    tick: u64,
+    /// Ticks behind the backend, when served from the QoS cache
+    stale_ticks: Option<u64>,
+}
+
+enum FrameUnavailable {
+    ShardNotHosted,
+    /// Snapshot serving is on and the refresher has not rendered this frame
+    NoSnapshot,
+    /// Nothing is rendered while fast-forwarding, see crate::fast_forward
+    FastForward,
+    /// The shard is quarantined and had no frame rendered before, see crate::shard_lock
+    Quarantined,
+}
+
+/// Uncompressed body for a shard endpoint. With snapshot serving on, only the buffers of the
+/// background refresher are read. While image QoS is active the last rendered frame is served
+/// without taking the shard lock, along with how many ticks stale it is; otherwise, or when
+/// nothing was cached yet, the frame is rendered fresh and cached. A quarantined shard is never
+/// rendered again; only its last frame is served. The shard lock is only taken for a frame that
+/// is neither in the snapshot buffers nor in the QoS cache, so its quarantine is checked there.
+fn presentation_frame<F>(shard: &Shard, key: &str, render: F) -> Result<PresentationFrame, FrameUnavailable>
+where
+    F: FnOnce(&ColonyShard) -> Option<Vec<u8>>,
+{
+    let shard_arc = Colony::instance().get_hosted_colony_shard_arc(shard).ok_or(FrameUnavailable::ShardNotHosted)?;
+    if is_fast_forward() {
+        return Err(FrameUnavailable::FastForward);
+    }
+    let snapshots = PresentationSnapshots::get_instance();
+    if snapshots.is_enabled() {
+        if let Some((body, tick)) = snapshots.frame(shard, key) {
+            return Ok(PresentationFrame { body, tick, stale_ticks: None });
+        }
+    }
+
+    let qos = ImageQos::get_instance();
+    if qos.is_active() {
+        if let Some((body, tick, stale_ticks)) = qos.stale_frame(shard, key) {
+            return Ok(PresentationFrame { body, tick, stale_ticks: Some(stale_ticks) });
+        }
+    }
+
+    let (body, tick) = {
+        let shard_guard = lock_shard(&shard_arc);
+        if shard_guard.is_quarantined() {
+            let (body, tick, stale_ticks) = qos.stale_frame(shard, key).ok_or(FrameUnavailable::Quarantined)?;
+            return Ok(PresentationFrame { body, tick, stale_ticks: Some(stale_ticks) });
+        }
+        if snapshots.is_enabled() {
+            return Err(FrameUnavailable::NoSnapshot);
+        }
+        (render(&shard_guard).ok_or(FrameUnavailable::ShardNotHosted)?, shard_guard.get_current_tick())
+    };
+    let body = Arc::new(body);
+    qos.store_frame(*shard, key, tick, Arc::clone(&body));
+    Ok(PresentationFrame { body, tick, stale_ticks: None })
+}
+
+/// The tick header, plus the stale header for frames served from the QoS cache
+fn frame_headers(frame: &PresentationFrame) -> String {
+    let stale = frame.stale_ticks.map(|ticks| format!("{}: {}\r\n", STALE_TICKS_HEADER, ticks)).unwrap_or_default();
+    format!("{}: {}\r\n{}", COLONY_TICK_HEADER, frame.tick, stale)
+}
+
+/// 421 or 404 for a shard this backend does not host, see write_shard_not_hosted; 503 for a
+/// frame the snapshot refresher has not rendered yet, while fast-forwarding or for a
+/// quarantined shard without a last frame
+async fn write_frame_unavailable(stream: &mut AccessLoggedTcpStream, shard: &Shard, reason: FrameUnavailable) {
+    match reason {
+        FrameUnavailable::FastForward => write_fast_forward_unavailable(stream).await,
+        FrameUnavailable::ShardNotHosted => write_shard_not_hosted(stream, shard).await,
+        FrameUnavailable::Quarantined => write_json(stream, "503 Service Unavailable",
+            r#"{"error":"Shard is quarantined and has no rendered frame","quarantined":true}"#).await,
+        FrameUnavailable::NoSnapshot => {
+            let error_json = r#"{"error":"No snapshot of this frame yet"}"#;
+            let response = format!(
+                "HTTP/1.1 503 Service Unavailable\r\nRetry-After: 1\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                error_json.len(),
+                error_json
+            );
+            let _ = stream.write_all(response.as_bytes()).await;
+        }
+    }
+}
+
+/// 421 Misdirected Request naming the backend that hosts the shard when the topology assigns
+/// it to another one, so the client can update its routing; 404 otherwise
+async fn write_shard_not_hosted(stream: &mut AccessLoggedTcpStream, shard: &Shard) {
+    let topology = ClusterTopology::get_instance();
+    match route_shard_request(shard, false, topology.as_deref(), &this_backend_host()) {
+        ShardRouting::Elsewhere(host) => {
+            record_misdirected_request();
+            let redirect = ShardRedirect { error: "Shard is hosted by another backend".to_string(), shard_id: shard.to_id(), host };
+            let json = serde_json::to_string(&redirect).expect("Failed to serialize shard redirect");
+            write_json(stream, "421 Misdirected Request", &json).await;
+        }
+        ShardRouting::Hosted | ShardRouting::Unknown => write_json(stream, "404 Not Found", r#"{"error":"Shard not available"}"#).await,
+    }
+}
+
+async fn write_fast_forward_unavailable(stream: &mut AccessLoggedTcpStream) {
+    let error_json = r#"{"error":"Fast-forward mode is on, shard images and layers are not rendered until it is turned off","fast_forward":true}"#;
+    let response = format!(
+        "HTTP/1.1 503 Service Unavailable\r\nRetry-After: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+        FAST_FORWARD_RETRY_AFTER_SECS,
+        error_json.len(),
+        error_json
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+}
+
+async fn handle_get_shard_image(stream: &mut AccessLoggedTcpStream, shard_id: &str, background: Option<&str>) {
     let start_total = Instant::now();
     let endpoint = "/api/shard/{id}/image";
     
@@ -324,62 +814,40 @@ async fn handle_get_shard_image(stream: &mut tokio::net::TcpStream, shard_id: &s
         return;
     }
     
-    // Colony Access
-    let colony = Colony::instance();
-    
-    // Shard Lookup
-    let rgb_bytes = if let Some(shard_arc) = colony.get_hosted_colony_shard_arc(&shard) {
-        // Lock Acquisition + Image Generation
-        let image = {
-            let shard_guard = shard_arc.lock().unwrap();
-            ShardUtils::get_shard_image(&shard_guard, &shard)
-        };
-        
-        if let Some(image) = image {
-            // RGB Conversion
-            let width = shard.width as usize;
-            let height = shard.height as usize;
-            let mut rgb_bytes = Vec::with_capacity(width * height * 3);
-            for color in &image {
-                rgb_bytes.push(color.red);
-                rgb_bytes.push(color.green);
-                rgb_bytes.push(color.blue);
-            }
-            Some(rgb_bytes)
-        } else {
-            None
+    let background = match ImageBackground::from_query(background) {
+        Ok(background) => background,
+        Err(e) => {
+            write_json(stream, "400 Bad Request", &format!(r#"{{"error":"{}"}}"#, e)).await;
+            record_http_latency(endpoint, start_total.elapsed().as_secs_f64() * 1000.0);
+            return;
         }
-    } else {
-        None
     };
+    let frame_key = match background {
+        ImageBackground::Plain => IMAGE_FRAME_KEY,
+        ImageBackground::FoodTinted => FOOD_TINTED_IMAGE_FRAME_KEY,
+    };
+    // The plain image is always refreshed; the tinted one only while it is requested
+    let snapshots = PresentationSnapshots::get_instance();
+    if snapshots.is_enabled() && background != ImageBackground::Plain {
+        snapshots.record_layer_request(frame_key, || {
+            Arc::new(move |shard_guard: &ColonyShard| ShardUtils::get_shard_rgb_image(shard_guard, &shard_guard.shard, background))
+        });
+    }
+
+    // Shard Lookup and RGB Conversion
+    let frame = presentation_frame(&shard, frame_key, |shard_guard| ShardUtils::get_shard_rgb_image(shard_guard, &shard, background));
     
     // Network Write (with gzip compression)
     // let start_network = Instant::now();
-    if let Some(rgb_bytes) = rgb_bytes {
-        // Compress rgb_bytes with gzip
-        let uncompressed_len = rgb_bytes.len();
-        let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
-        if let Err(e) = IoWrite::write_all(&mut encoder, &rgb_bytes) {
-            log_error!(
-                "Failed to gzip-compress shard image {}: {} (uncompressed_len={})",
-                shard_id,
-                e,
-                uncompressed_len
-            );
-            let error_json = r#"{"error":"Failed to compress shard image"}"#;
-            let response = format!(
-                "HTTP/1.1 500 Internal Server Error\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
-                error_json.len(),
-                error_json
-            );
-            let _ = stream.write_all(response.as_bytes()).await;
-            return;
-        }
-        let compressed_bytes = match encoder.finish() {
-            Ok(bytes) => bytes,
-            Err(e) => {
+    match frame {
+        Ok(frame) => {
+            let rgb_bytes = frame.body.as_slice();
+            // Compress rgb_bytes with gzip
+            let uncompressed_len = rgb_bytes.len();
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+            if let Err(e) = IoWrite::write_all(&mut encoder, rgb_bytes) {
                 log_error!(
-                    "Failed to finish gzip compression for shard image {}: {} (uncompressed_len={})",
+                    "Failed to gzip-compress shard image {}: {} (uncompressed_len={})",
                     shard_id,
                     e,
                     uncompressed_len
@@ -393,58 +861,37 @@ async fn handle_get_shard_image(stream: &mut tokio::net::TcpStream, shard_id: &s
                 let _ = stream.write_all(response.as_bytes()).await;
                 return;
             }
-        };
-
-        // let compressed_len = compressed_bytes.len();
-        let body_bytes = &compressed_bytes[..];
-        let response = format!(
-            "HTTP/1.1 200 OK\r\nContent-Type: application/octet-stream\r\nContent-Encoding: gzip\r\nContent-Length: {}\r\n\r\n",
-            body_bytes.len()
-        );
-        let header_bytes = response.as_bytes();
-        if let Err(e) = stream.write_all(header_bytes).await {
-            log_error!("Failed to write shard image response header: {}", e);
-            // let network_write_ms = start_network.elapsed().as_secs_f64() * 1000.0;
-            // let bytes_sent = header_bytes.len();
-            let latency = start_total.elapsed();
-            let latency_ms = latency.as_secs_f64() * 1000.0;
-            record_http_latency(endpoint, latency_ms);
+            let compressed_bytes = match encoder.finish() {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    log_error!(
+                        "Failed to finish gzip compression for shard image {}: {} (uncompressed_len={})",
+                        shard_id,
+                        e,
+                        uncompressed_len
+                    );
+                    let error_json = r#"{"error":"Failed to compress shard image"}"#;
+                    let response = format!(
+                        "HTTP/1.1 500 Internal Server Error\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                        error_json.len(),
+                        error_json
+                    );
+                    let _ = stream.write_all(response.as_bytes()).await;
+                    return;
+                }
+            };
 
-            // Log breakdown for this request even on error
-            // let backend_host = format!("{}:{}", get_backend_hostname(), get_backend_port());
-            // let content_encoding = "gzip";
-            // log!(
-            //     "Backend HTTP request breakdown: endpoint={}, shard_id={}, host={}, total_ms={:.2}, network_write_ms={:.2}, bytes_sent={}, uncompressed_bytes={}, compressed_bytes={}, content_encoding={}",
-            //     endpoint,
-            //     shard_id,
-            //     backend_host,
-            //     latency_ms,
-            //     network_write_ms,
-            //     bytes_sent,
-            //     uncompressed_len,
-            //     compressed_len,
-            //     content_encoding
-            // );
-            return;
-        }
-        let mut bytes_sent = header_bytes.len();
-        if let Err(e) = stream.write_all(body_bytes).await {
-            log_error!("Failed to write shard image response body: {}", e);
-        } else {
-            bytes_sent += body_bytes.len();
+            // let compressed_len = compressed_bytes.len();
+            let body_bytes = &compressed_bytes[..];
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/octet-stream\r\nContent-Encoding: gzip\r\n{}Content-Length: {}\r\n\r\n",
+                frame_headers(&frame),
+                body_bytes.len()
+            );
+            write_full_response(stream, &response, body_bytes, endpoint).await;
         }
-        bytes_sent
-    } else {
-        let error_json = r#"{"error":"Shard not available"}"#;
-        let response = format!(
-            "HTTP/1.1 404 Not Found\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
-            error_json.len(),
-            error_json
-        );
-        let response_bytes = response.as_bytes();
-        let _ = stream.write_all(response_bytes).await;
-        response_bytes.len()
-    };
+        Err(reason) => write_frame_unavailable(stream, &shard, reason).await,
+    }
     // let network_write_ms = start_network.elapsed().as_secs_f64() * 1000.0;
     
     // Record latency (existing behavior)
@@ -466,7 +913,147 @@ async fn handle_get_shard_image(stream: &mut tokio::net::TcpStream, shard_id: &s
     // );
 }
 
-async fn handle_get_shard_layer(stream: &mut tokio::net::TcpStream, shard_id: &str, layer_name: &str) {
+async fn write_json(stream: &mut AccessLoggedTcpStream, status_line: &str, body: &str) {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+        status_line,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+}
+
+/// Writes the header and body as one buffer, so a client never sees a header whose body was
+/// cut off between two writes, and checks the byte count against the full response.
+/// Returns the bytes written.
+async fn write_full_response(stream: &mut AccessLoggedTcpStream, header: &str, body: &[u8], endpoint: &str) -> usize {
+    let mut response = Vec::with_capacity(header.len() + body.len());
+    response.extend_from_slice(header.as_bytes());
+    response.extend_from_slice(body);
+
+    let mut written = 0;
+    while written < response.len() {
+        match stream.write(&response[written..]).await {
+            Ok(0) => break,
+            Ok(n) => written += n,
+            Err(e) => {
+                log_error!("Failed to write {} response: {}", endpoint, e);
+                break;
+            }
+        }
+    }
+    if written < response.len() {
+        TRUNCATED_RESPONSES.fetch_add(1, Ordering::Relaxed);
+        log_error!("Truncated {} response: wrote {} of {} bytes", endpoint, written, response.len());
+    } else if let Err(e) = stream.flush().await {
+        log_error!("Failed to flush {} response: {}", endpoint, e);
+    }
+    written
+}
+
+/// HTTP write counters in Prometheus text format, appended to /metrics
+fn render_http_prometheus() -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# TYPE backend_http_truncated_responses_total counter");
+    let _ = writeln!(out, "backend_http_truncated_responses_total {}", TRUNCATED_RESPONSES.load(Ordering::Relaxed));
+    out
+}
+
+/// Reports an estimate of how many pixels changed since a tick, summed from the shard's per-tick
+/// dirty journal of deaths, births and moves.
+/// When the journal no longer covers since_tick the whole shard counts as changed. The journal
+/// is off while fast-forwarding, so is this endpoint.
+async fn handle_get_shard_image_changed(stream: &mut AccessLoggedTcpStream, shard_id: &str, since_tick: Option<&str>) {
+    let start_total = Instant::now();
+    let endpoint = "/api/shard/{id}/image-changed";
+
+    let shard = match Shard::from_id(shard_id) {
+        Ok(s) => s,
+        Err(e) => {
+            write_json(stream, "400 Bad Request", &format!(r#"{{"error":"{}"}}"#, e)).await;
+            return;
+        }
+    };
+    let since_tick = match since_tick.map(|v| v.parse::<u64>()) {
+        Some(Ok(tick)) => tick,
+        _ => {
+            write_json(stream, "400 Bad Request", r#"{"error":"Missing or invalid since_tick"}"#).await;
+            return;
+        }
+    };
+
+    if !Colony::is_initialized() {
+        write_json(stream, "404 Not Found", r#"{"error":"Colony not initialized"}"#).await;
+        return;
+    }
+    if is_fast_forward() {
+        write_fast_forward_unavailable(stream).await;
+        return;
+    }
+
+    let result = Colony::instance().get_hosted_colony_shard_arc(&shard).map(|shard_arc| {
+        let shard_guard = lock_shard(&shard_arc);
+        let changed_pixels = shard_guard.estimated_changed_pixels_since(since_tick)
+            .unwrap_or((shard.width * shard.height) as u32);
+        (changed_pixels, shard_guard.current_tick)
+    });
+
+    match result {
+        Some((changed_pixels, current_tick)) => {
+            let body = format!(
+                r#"{{"changed":{},"changed_pixels":{},"current_tick":{}}}"#,
+                changed_pixels > 0,
+                changed_pixels,
+                current_tick
+            );
+            write_json(stream, "200 OK", &body).await;
+        }
+        None => write_shard_not_hosted(stream, &shard).await,
+    }
+
+    record_http_latency(endpoint, start_total.elapsed().as_secs_f64() * 1000.0);
+}
+
+/// Layer body selected by query parameters; the original binary format stays the default
+/// until all GUIs request version 2
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LayerResponseFormat {
+    Binary,
+    BinaryWithStats,
+    Json,
+}
+
+impl LayerResponseFormat {
+    fn from_request(request: &str) -> Self {
+        if parse_query_param(request, "format").as_deref() == Some("json") {
+            LayerResponseFormat::Json
+        } else if parse_query_param(request, "version").and_then(|v| v.parse::<u32>().ok()) == Some(LAYER_FORMAT_VERSION_WITH_STATS) {
+            LayerResponseFormat::BinaryWithStats
+        } else {
+            LayerResponseFormat::Binary
+        }
+    }
+
+    fn content_type(&self) -> &'static str {
+        match self {
+            LayerResponseFormat::Json => "application/json",
+            _ => "application/octet-stream",
+        }
+    }
+}
+
+/// Uncompressed layer body in the requested format
+fn render_layer(shard: &ColonyShard, req_shard: &Shard, layer: &ShardLayer, format: LayerResponseFormat) -> Option<Vec<u8>> {
+    let (values, stats) = ShardUtils::get_shard_layer(shard, req_shard, layer)?;
+    Some(match format {
+        LayerResponseFormat::Binary => encode_layer(&values),
+        LayerResponseFormat::BinaryWithStats => encode_layer_with_stats(&values, &stats),
+        LayerResponseFormat::Json => serde_json::to_vec(&ShardLayerData { stats, values })
+            .expect("Failed to serialize shard layer"),
+    })
+}
+
+async fn handle_get_shard_layer(stream: &mut AccessLoggedTcpStream, shard_id: &str, layer_name: &str, format: LayerResponseFormat) {
     let start = Instant::now();
     let endpoint = format!("/api/shard/{{id}}/layer/{}", layer_name);
     
@@ -522,56 +1109,24 @@ async fn handle_get_shard_layer(stream: &mut tokio::net::TcpStream, shard_id: &s
     }
     
     // Get shard layer using existing handler logic
-    let colony = Colony::instance();
-    let binary_data = if let Some(shard_arc) = colony.get_hosted_colony_shard_arc(&shard) {
-        let data = {
-            let shard_guard = shard_arc.lock().unwrap();
-            ShardUtils::get_shard_layer(&shard_guard, &shard, &layer)
-        };
-        if let Some(data) = data {
-            // Convert to binary format: length (u32 LE) + i32 values (LE)
-            let count = data.len() as u32;
-            let mut binary_data = Vec::with_capacity(4 + data.len() * 4);
-            binary_data.extend_from_slice(&count.to_le_bytes());
-            for &value in &data {
-                binary_data.extend_from_slice(&value.to_le_bytes());
-            }
-            Some(binary_data)
-        } else {
-            None
-        }
-    } else {
-        None
-    };
+    let frame_key = format!("layer/{}/{:?}", layer_name, format);
+    let snapshots = PresentationSnapshots::get_instance();
+    if snapshots.is_enabled() {
+        snapshots.record_layer_request(&frame_key, || {
+            Arc::new(move |shard_guard: &ColonyShard| render_layer(shard_guard, &shard_guard.shard, &layer, format))
+        });
+    }
+    let frame = presentation_frame(&shard, &frame_key, |shard_guard| render_layer(shard_guard, &shard, &layer, format));
     
-    if let Some(binary_data) = binary_data {
-        // Compress binary layer data with gzip, but keep the same binary format
-        // (length prefix + i32 values) as the uncompressed representation.
-        let uncompressed_len = binary_data.len();
-        let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
-        if let Err(e) = IoWrite::write_all(&mut encoder, &binary_data) {
-            log_error!(
-                "Failed to gzip-compress shard layer {} for shard {}: {} (uncompressed_len={})",
-                layer_name,
-                shard_id,
-                e,
-                uncompressed_len
-            );
-            let error_json = r#"{"error":"Failed to compress shard layer"}"#;
-            let response = format!(
-                "HTTP/1.1 500 Internal Server Error\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
-                error_json.len(),
-                error_json
-            );
-            let _ = stream.write_all(response.as_bytes()).await;
-            return;
-        }
-
-        let compressed_bytes = match encoder.finish() {
-            Ok(bytes) => bytes,
-            Err(e) => {
+    match frame {
+        Ok(frame) => {
+            let layer_body = frame.body.as_slice();
+            // Compress layer data with gzip, but keep the same format as the uncompressed representation
+            let uncompressed_len = layer_body.len();
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+            if let Err(e) = IoWrite::write_all(&mut encoder, layer_body) {
                 log_error!(
-                    "Failed to finish gzip compression for shard layer {} for shard {}: {} (uncompressed_len={})",
+                    "Failed to gzip-compress shard layer {} for shard {}: {} (uncompressed_len={})",
                     layer_name,
                     shard_id,
                     e,
@@ -586,28 +1141,38 @@ async fn handle_get_shard_layer(stream: &mut tokio::net::TcpStream, shard_id: &s
                 let _ = stream.write_all(response.as_bytes()).await;
                 return;
             }
-        };
 
-        let body_bytes = &compressed_bytes[..];
-        let response = format!(
-            "HTTP/1.1 200 OK\r\nContent-Type: application/octet-stream\r\nContent-Encoding: gzip\r\nContent-Length: {}\r\n\r\n",
-            body_bytes.len()
-        );
-        if let Err(e) = stream.write_all(response.as_bytes()).await {
-            log_error!("Failed to write shard layer response header: {}", e);
-            return;
-        }
-        if let Err(e) = stream.write_all(body_bytes).await {
-            log_error!("Failed to write shard layer response body: {}", e);
+            let compressed_bytes = match encoder.finish() {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    log_error!(
+                        "Failed to finish gzip compression for shard layer {} for shard {}: {} (uncompressed_len={})",
+                        layer_name,
+                        shard_id,
+                        e,
+                        uncompressed_len
+                    );
+                    let error_json = r#"{"error":"Failed to compress shard layer"}"#;
+                    let response = format!(
+                        "HTTP/1.1 500 Internal Server Error\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                        error_json.len(),
+                        error_json
+                    );
+                    let _ = stream.write_all(response.as_bytes()).await;
+                    return;
+                }
+            };
+
+            let body_bytes = &compressed_bytes[..];
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Encoding: gzip\r\n{}Content-Length: {}\r\n\r\n",
+                format.content_type(),
+                frame_headers(&frame),
+                body_bytes.len()
+            );
+            write_full_response(stream, &response, body_bytes, &endpoint).await;
         }
-    } else {
-        let error_json = r#"{"error":"Shard not available"}"#;
-        let response = format!(
-            "HTTP/1.1 404 Not Found\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
-            error_json.len(),
-            error_json
-        );
-        let _ = stream.write_all(response.as_bytes()).await;
+        Err(reason) => write_frame_unavailable(stream, &shard, reason).await,
     }
     
     // Record latency
@@ -0,0 +1,194 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use shared::be_api::Shard;
+use shared::log;
+use crate::backend_config::get_qos_config;
+
+pub const QOS_ENABLED_ENV: &str = "QOS_ENABLED";
+pub const QOS_TICK_MS_THRESHOLD_ENV: &str = "QOS_TICK_MS_THRESHOLD";
+
+/// Ticks in the rolling average
+const TICK_WINDOW: usize = 20;
+/// QoS mode is left only once the average drops this far below the threshold, so it does not flap
+const RECOVERY_FRACTION: f64 = 0.8;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QosConfig {
+    pub enabled: bool,
+    /// Rolling average tick duration above which image/layer requests get cached frames
+    pub tick_ms_threshold: f64,
+}
+
+impl Default for QosConfig {
+    fn default() -> Self {
+        Self { enabled: true, tick_ms_threshold: 500.0 }
+    }
+}
+
+impl QosConfig {
+    /// Defaults overridden by QOS_ENABLED and QOS_TICK_MS_THRESHOLD; malformed values are ignored
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        if let Ok(enabled) = std::env::var(QOS_ENABLED_ENV) {
+            config.enabled = !matches!(enabled.trim(), "false" | "0");
+        }
+        if let Some(threshold) = std::env::var(QOS_TICK_MS_THRESHOLD_ENV).ok()
+            .and_then(|v| v.trim().parse::<f64>().ok())
+            .filter(|v| *v > 0.0)
+        {
+            config.tick_ms_threshold = threshold;
+        }
+        config
+    }
+}
+
+/// Rolling average of tick durations with hysteresis between normal and QoS mode
+#[derive(Debug)]
+pub struct TickLoadMonitor {
+    config: QosConfig,
+    durations_ms: VecDeque<f64>,
+    active: bool,
+    engaged_count: u64,
+}
+
+impl TickLoadMonitor {
+    pub fn new(config: QosConfig) -> Self {
+        Self { config, durations_ms: VecDeque::with_capacity(TICK_WINDOW), active: false, engaged_count: 0 }
+    }
+
+    /// Records one tick; returns whether QoS mode is active afterwards
+    pub fn record_tick(&mut self, duration_ms: f64) -> bool {
+        if self.durations_ms.len() == TICK_WINDOW {
+            self.durations_ms.pop_front();
+        }
+        self.durations_ms.push_back(duration_ms);
+        if !self.config.enabled {
+            return false;
+        }
+
+        let average_ms = self.average_ms().unwrap_or(0.0);
+        if !self.active && average_ms > self.config.tick_ms_threshold {
+            self.active = true;
+            self.engaged_count += 1;
+        } else if self.active && average_ms < self.config.tick_ms_threshold * RECOVERY_FRACTION {
+            self.active = false;
+        }
+        self.active
+    }
+
+    pub fn average_ms(&self) -> Option<f64> {
+        if self.durations_ms.is_empty() {
+            None
+        } else {
+            Some(self.durations_ms.iter().sum::<f64>() / self.durations_ms.len() as f64)
+        }
+    }
+
+    /// Times QoS mode was entered
+    pub fn engaged_count(&self) -> u64 {
+        self.engaged_count
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CachedFrame {
+    tick: u64,
+    body: Arc<Vec<u8>>,
+}
+
+/// Last rendered body per shard and endpoint (the image, or a layer in one format)
+#[derive(Debug, Default)]
+pub struct FrameCache {
+    frames: Mutex<HashMap<(Shard, String), CachedFrame>>,
+}
+
+impl FrameCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn store(&self, shard: Shard, key: &str, tick: u64, body: Arc<Vec<u8>>) {
+        self.frames.lock().unwrap().insert((shard, key.to_string()), CachedFrame { tick, body });
+    }
+
+    /// The cached body and the tick it was rendered at
+    pub fn get(&self, shard: &Shard, key: &str) -> Option<(Arc<Vec<u8>>, u64)> {
+        self.frames.lock().unwrap()
+            .get(&(*shard, key.to_string()))
+            .map(|frame| (Arc::clone(&frame.body), frame.tick))
+    }
+
+    /// Drops every frame for which keep returns false
+    pub fn retain(&self, keep: impl Fn(&Shard, &str) -> bool) {
+        self.frames.lock().unwrap().retain(|(shard, key), _| keep(shard, key));
+    }
+}
+
+/// Process-wide QoS state shared by the ticker and the HTTP handlers
+pub struct ImageQos {
+    monitor: Mutex<TickLoadMonitor>,
+    // Mirrors the monitor so HTTP handlers never wait on it
+    active: AtomicBool,
+    latest_tick: AtomicU64,
+    frames: FrameCache,
+    stale_responses: AtomicU64,
+}
+
+static INSTANCE: OnceLock<ImageQos> = OnceLock::new();
+
+impl ImageQos {
+    pub fn get_instance() -> &'static ImageQos {
+        INSTANCE.get_or_init(|| ImageQos {
+            monitor: Mutex::new(TickLoadMonitor::new(get_qos_config())),
+            active: AtomicBool::new(false),
+            latest_tick: AtomicU64::new(0),
+            frames: FrameCache::new(),
+            stale_responses: AtomicU64::new(0),
+        })
+    }
+
+    /// Called by the ticker after every tick with the highest tick of the ticked shards
+    pub fn record_tick(&self, duration_ms: f64, latest_tick: u64) {
+        self.latest_tick.fetch_max(latest_tick, Ordering::SeqCst);
+        let mut monitor = self.monitor.lock().unwrap();
+        let active = monitor.record_tick(duration_ms);
+        if self.active.swap(active, Ordering::SeqCst) != active {
+            log!("Image QoS {}: average tick {:.1} ms", if active { "engaged" } else { "released" }, monitor.average_ms().unwrap_or(0.0));
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active.load(Ordering::SeqCst)
+    }
+
+    pub fn store_frame(&self, shard: Shard, key: &str, tick: u64, body: Arc<Vec<u8>>) {
+        self.frames.store(shard, key, tick, body);
+    }
+
+    /// Drops every cached frame, see crate::fast_forward
+    pub fn clear_frames(&self) {
+        self.frames.retain(|_, _| false);
+    }
+
+    /// Cached body, its tick and how many ticks behind the backend it is; counted as a stale response
+    pub fn stale_frame(&self, shard: &Shard, key: &str) -> Option<(Arc<Vec<u8>>, u64, u64)> {
+        let (body, tick) = self.frames.get(shard, key)?;
+        self.stale_responses.fetch_add(1, Ordering::Relaxed);
+        Some((body, tick, self.latest_tick.load(Ordering::SeqCst).saturating_sub(tick)))
+    }
+
+    /// QoS counters in Prometheus text format, appended to /metrics
+    pub fn render_prometheus(&self) -> String {
+        let engaged_count = self.monitor.lock().unwrap().engaged_count();
+        let mut out = String::new();
+        let _ = writeln!(out, "# TYPE backend_image_qos_active gauge");
+        let _ = writeln!(out, "backend_image_qos_active {}", self.is_active() as u8);
+        let _ = writeln!(out, "# TYPE backend_image_qos_engaged_total counter");
+        let _ = writeln!(out, "backend_image_qos_engaged_total {}", engaged_count);
+        let _ = writeln!(out, "# TYPE backend_image_qos_stale_responses_total counter");
+        let _ = writeln!(out, "backend_image_qos_stale_responses_total {}", self.stale_responses.load(Ordering::Relaxed));
+        out
+    }
+}
@@ -0,0 +1,25 @@
+use backend::shard_storage::ShardStorage;
+use backend::snapshot_inspect::{parse_args, run, USAGE};
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (snapshot_path, command) = match parse_args(&args) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
+    };
+    let snapshot = match ShardStorage::load_snapshot(&snapshot_path) {
+        Ok(snapshot) => snapshot,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    if let Err(e) = run(&snapshot, &command, &mut std::io::stdout().lock()) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
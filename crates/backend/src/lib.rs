@@ -0,0 +1,27 @@
+pub mod colony;
+pub mod be_ticker;
+pub mod colony_shard;
+pub mod shard_utils;
+pub mod shard_init_progress;
+pub mod shard_stats;
+pub mod shard_storage;
+pub mod snapshot_inspect;
+pub mod be_colony_events;
+pub mod shard_topography;
+pub mod backend_config;
+pub mod backend_client;
+pub mod border_outbox;
+pub mod border_validation;
+pub mod http_server;
+pub mod rpc_metrics;
+pub mod rate_limiter;
+pub mod image_qos;
+pub mod presentation_snapshots;
+pub mod fast_forward;
+pub mod topology_refresh;
+pub mod topology_subscription;
+pub mod shard_routing;
+pub mod shard_lease;
+pub mod shard_lock;
+pub mod be_server;
+pub mod simulation;
@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use shared::be_api::Shard;
+use shared::shard_render::ImageBackground;
+use shared::supervisor::spawn_supervised;
+use shared::{log, log_error};
+use crate::backend_config::get_snapshot_config;
+use crate::colony::Colony;
+use crate::colony_shard::ColonyShard;
+use crate::fast_forward::is_fast_forward;
+use crate::image_qos::FrameCache;
+use crate::shard_lock::lock_shard;
+use crate::shard_utils::ShardUtils;
+
+pub const SNAPSHOT_SERVING_ENV: &str = "SNAPSHOT_SERVING";
+pub const SNAPSHOT_REFRESH_HZ_ENV: &str = "SNAPSHOT_REFRESH_HZ";
+pub const SNAPSHOT_LAYERS_ENV: &str = "SNAPSHOT_LAYERS";
+
+/// Frame key of the shard image, always refreshed
+pub const IMAGE_FRAME_KEY: &str = "image";
+/// Frame key of the food-tinted shard image, refreshed like a layer while it is among the most requested
+pub const FOOD_TINTED_IMAGE_FRAME_KEY: &str = "image/food-tinted";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotConfig {
+    /// Image and layer requests only read the buffers of the background refresher, never render
+    pub enabled: bool,
+    /// Buffer refreshes per second
+    pub refresh_hz: f64,
+    /// Most requested layer frames refreshed besides the image
+    pub max_layers: usize,
+}
+
+impl Default for SnapshotConfig {
+    fn default() -> Self {
+        Self { enabled: false, refresh_hz: 2.0, max_layers: 4 }
+    }
+}
+
+impl SnapshotConfig {
+    /// Defaults overridden by SNAPSHOT_SERVING, SNAPSHOT_REFRESH_HZ and SNAPSHOT_LAYERS; malformed values are ignored
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        if let Ok(enabled) = std::env::var(SNAPSHOT_SERVING_ENV) {
+            config.enabled = matches!(enabled.trim(), "true" | "1");
+        }
+        if let Some(refresh_hz) = std::env::var(SNAPSHOT_REFRESH_HZ_ENV).ok()
+            .and_then(|v| v.trim().parse::<f64>().ok())
+            .filter(|v| v.is_finite() && *v > 0.0)
+        {
+            config.refresh_hz = refresh_hz;
+        }
+        if let Some(max_layers) = std::env::var(SNAPSHOT_LAYERS_ENV).ok().and_then(|v| v.trim().parse::<usize>().ok()) {
+            config.max_layers = max_layers;
+        }
+        config
+    }
+
+    pub fn refresh_interval(&self) -> Duration {
+        Duration::from_secs_f64(1.0 / self.refresh_hz)
+    }
+}
+
+/// Renders one frame body from a locked shard
+pub type FrameRenderer = Arc<dyn Fn(&ColonyShard) -> Option<Vec<u8>> + Send + Sync>;
+
+/// Requests per layer frame key, to pick the layers worth refreshing
+#[derive(Debug, Default)]
+pub struct RequestCounts {
+    counts: HashMap<String, u64>,
+}
+
+impl RequestCounts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, key: &str) {
+        *self.counts.entry(key.to_string()).or_insert(0) += 1;
+    }
+
+    /// The n most requested keys, ties broken by key so the selection is stable
+    pub fn top(&self, n: usize) -> Vec<String> {
+        let mut keys: Vec<(&String, &u64)> = self.counts.iter().collect();
+        keys.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        keys.into_iter().take(n).map(|(key, _)| key.clone()).collect()
+    }
+}
+
+/// Presentation buffers kept by a background refresher while snapshot serving is on, so the
+/// HTTP handlers never take a shard lock
+pub struct PresentationSnapshots {
+    config: SnapshotConfig,
+    requests: Mutex<RequestCounts>,
+    renderers: Mutex<HashMap<String, FrameRenderer>>,
+    frames: FrameCache,
+    refreshes: AtomicU64,
+    misses: AtomicU64,
+}
+
+static INSTANCE: OnceLock<PresentationSnapshots> = OnceLock::new();
+
+impl PresentationSnapshots {
+    pub fn get_instance() -> &'static PresentationSnapshots {
+        INSTANCE.get_or_init(|| PresentationSnapshots {
+            config: get_snapshot_config(),
+            requests: Mutex::new(RequestCounts::new()),
+            renderers: Mutex::new(HashMap::new()),
+            frames: FrameCache::new(),
+            refreshes: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Counts a request for a layer frame; renderer is only built the first time the key is seen
+    pub fn record_layer_request(&self, key: &str, renderer: impl FnOnce() -> FrameRenderer) {
+        self.requests.lock().unwrap().record(key);
+        self.renderers.lock().unwrap().entry(key.to_string()).or_insert_with(renderer);
+    }
+
+    /// The buffered body and its tick; None, counted as a miss, until the refresher rendered it
+    pub fn frame(&self, shard: &Shard, key: &str) -> Option<(Arc<Vec<u8>>, u64)> {
+        let frame = self.frames.get(shard, key);
+        if frame.is_none() {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+        }
+        frame
+    }
+
+    /// The image plus the most requested layers
+    fn frames_to_refresh(&self) -> Vec<(String, FrameRenderer)> {
+        let keys = self.requests.lock().unwrap().top(self.config.max_layers);
+        let renderers = self.renderers.lock().unwrap();
+        let image: FrameRenderer = Arc::new(|shard: &ColonyShard| ShardUtils::get_shard_rgb_image(shard, &shard.shard, ImageBackground::Plain));
+        std::iter::once((IMAGE_FRAME_KEY.to_string(), image))
+            .chain(keys.into_iter().filter_map(|key| renderers.get(&key).map(|renderer| (key.clone(), Arc::clone(renderer)))))
+            .collect()
+    }
+
+    /// Re-renders the buffers of every given shard, locking one shard at a time, and drops the
+    /// buffers of shards and layers no longer refreshed
+    pub fn refresh(&self, shard_arcs: &[Arc<Mutex<ColonyShard>>]) {
+        let frames = self.frames_to_refresh();
+        let mut refreshed = Vec::with_capacity(shard_arcs.len());
+        for shard_arc in shard_arcs {
+            let (shard, tick, bodies) = {
+                let shard_guard = lock_shard(shard_arc);
+                // A quarantined shard keeps the buffers rendered before it was quarantined
+                if shard_guard.is_quarantined() {
+                    refreshed.push(shard_guard.shard);
+                    continue;
+                }
+                let bodies: Vec<(&str, Vec<u8>)> = frames.iter()
+                    .filter_map(|(key, render)| render(&shard_guard).map(|body| (key.as_str(), body)))
+                    .collect();
+                (shard_guard.shard, shard_guard.get_current_tick(), bodies)
+            };
+            for (key, body) in bodies {
+                self.frames.store(shard, key, tick, Arc::new(body));
+            }
+            refreshed.push(shard);
+        }
+        self.frames.retain(|shard, key| refreshed.contains(shard) && frames.iter().any(|(refreshed_key, _)| refreshed_key == key));
+        self.refreshes.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Drops every buffer until the next refresh, see crate::fast_forward
+    pub fn clear(&self) {
+        self.frames.retain(|_, _| false);
+    }
+
+    /// Snapshot counters in Prometheus text format, appended to /metrics
+    pub fn render_prometheus(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# TYPE backend_snapshot_serving_enabled gauge");
+        let _ = writeln!(out, "backend_snapshot_serving_enabled {}", self.is_enabled() as u8);
+        let _ = writeln!(out, "# TYPE backend_snapshot_refreshes_total counter");
+        let _ = writeln!(out, "backend_snapshot_refreshes_total {}", self.refreshes.load(Ordering::Relaxed));
+        let _ = writeln!(out, "# TYPE backend_snapshot_misses_total counter");
+        let _ = writeln!(out, "backend_snapshot_misses_total {}", self.misses.load(Ordering::Relaxed));
+        out
+    }
+}
+
+/// Refreshes the presentation buffers at the configured rate when snapshot serving is on.
+/// Rendering runs on the blocking pool, so it never holds up the RPC and HTTP tasks.
+pub fn start_snapshot_refresher() {
+    let config = get_snapshot_config();
+    if !config.enabled {
+        return;
+    }
+    let interval = config.refresh_interval();
+    let max_layers = config.max_layers;
+    spawn_supervised("snapshot-refresh", move || async move {
+        log!("Serving images and layers from snapshots refreshed every {:?}, up to {} layers", interval, max_layers);
+        let mut timer = tokio::time::interval(interval);
+        // A slow refresh is not made up for with a burst of refreshes
+        timer.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            timer.tick().await;
+            if !Colony::is_initialized() || is_fast_forward() {
+                continue;
+            }
+            let refresh = tokio::task::spawn_blocking(|| {
+                let (_, shard_arcs) = Colony::instance().get_hosted_shards();
+                PresentationSnapshots::get_instance().refresh(&shard_arcs);
+            });
+            if let Err(e) = refresh.await {
+                log_error!("Snapshot refresh failed: {}", e);
+            }
+        }
+    });
+}
@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::net::IpAddr;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use crate::backend_config::get_rate_limit_config;
+
+pub const RATE_LIMIT_ENABLED_ENV: &str = "RATE_LIMIT_ENABLED";
+pub const RATE_LIMIT_SHARD_DATA_PER_SEC_ENV: &str = "RATE_LIMIT_SHARD_DATA_PER_SEC";
+pub const RATE_LIMIT_SHARD_DATA_BURST_ENV: &str = "RATE_LIMIT_SHARD_DATA_BURST";
+pub const RATE_LIMIT_ADMIN_PER_SEC_ENV: &str = "RATE_LIMIT_ADMIN_PER_SEC";
+pub const RATE_LIMIT_ADMIN_BURST_ENV: &str = "RATE_LIMIT_ADMIN_BURST";
+/// Comma separated IPs that are never limited, e.g. the coordinator when no API token is set
+pub const RATE_LIMIT_ALLOWLIST_ENV: &str = "RATE_LIMIT_ALLOWLIST";
+
+// Idle buckets are dropped once this many clients are tracked
+const MAX_TRACKED_BUCKETS: usize = 1024;
+
+/// Endpoints are limited per class, so image polling cannot use up the budget of /health
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EndpointClass {
+    /// /api/shard/{id}/image, image-changed and layer
+    ShardData,
+    /// Everything else: colony info, stats, metrics, health and debug pages
+    Admin,
+}
+
+impl EndpointClass {
+    pub fn for_request(request: &str) -> Self {
+        if request.starts_with("GET /api/shard/") {
+            EndpointClass::ShardData
+        } else {
+            EndpointClass::Admin
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            EndpointClass::ShardData => "shard_data",
+            EndpointClass::Admin => "admin",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BucketConfig {
+    /// Requests allowed back to back from a full bucket
+    pub burst: f64,
+    pub refill_per_sec: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimitConfig {
+    pub enabled: bool,
+    pub shard_data: BucketConfig,
+    pub admin: BucketConfig,
+    pub allowlist: Vec<IpAddr>,
+}
+
+impl Default for RateLimitConfig {
+    /// Generous enough for the GUI polling every shard of a backend at localhost speed
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            shard_data: BucketConfig { burst: 400.0, refill_per_sec: 200.0 },
+            admin: BucketConfig { burst: 40.0, refill_per_sec: 20.0 },
+            allowlist: Vec::new(),
+        }
+    }
+}
+
+impl RateLimitConfig {
+    /// Defaults overridden by the RATE_LIMIT_* environment variables; malformed values are ignored
+    pub fn from_env() -> Self {
+        let env_f64 = |name: &str| std::env::var(name).ok().and_then(|v| v.trim().parse::<f64>().ok()).filter(|v| *v > 0.0);
+        let mut config = Self::default();
+        if let Ok(enabled) = std::env::var(RATE_LIMIT_ENABLED_ENV) {
+            config.enabled = !matches!(enabled.trim(), "false" | "0");
+        }
+        if let Some(value) = env_f64(RATE_LIMIT_SHARD_DATA_PER_SEC_ENV) {
+            config.shard_data.refill_per_sec = value;
+        }
+        if let Some(value) = env_f64(RATE_LIMIT_SHARD_DATA_BURST_ENV) {
+            config.shard_data.burst = value;
+        }
+        if let Some(value) = env_f64(RATE_LIMIT_ADMIN_PER_SEC_ENV) {
+            config.admin.refill_per_sec = value;
+        }
+        if let Some(value) = env_f64(RATE_LIMIT_ADMIN_BURST_ENV) {
+            config.admin.burst = value;
+        }
+        if let Ok(allowlist) = std::env::var(RATE_LIMIT_ALLOWLIST_ENV) {
+            config.allowlist = allowlist.split(',')
+                .filter_map(|ip| ip.trim().parse::<IpAddr>().ok())
+                .collect();
+        }
+        config
+    }
+
+    fn bucket(&self, class: EndpointClass) -> BucketConfig {
+        match class {
+            EndpointClass::ShardData => self.shard_data,
+            EndpointClass::Admin => self.admin,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn full(config: BucketConfig, now: Instant) -> Self {
+        Self { tokens: config.burst, last_refill: now }
+    }
+
+    fn refill(&mut self, config: BucketConfig, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * config.refill_per_sec).min(config.burst);
+        self.last_refill = now;
+    }
+
+    /// Takes one token, or returns how long until one is available
+    fn try_take(&mut self, config: BucketConfig, now: Instant) -> Result<(), Duration> {
+        self.refill(config, now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / config.refill_per_sec))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RateLimitDecision {
+    Allowed,
+    /// Trusted client (coordinator token or allowlisted IP), not counted
+    Bypassed,
+    Throttled { retry_after: Duration },
+}
+
+/// Token buckets per client IP and endpoint class
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Mutex<HashMap<(IpAddr, EndpointClass), TokenBucket>>,
+    throttled: Mutex<HashMap<EndpointClass, u64>>,
+}
+
+static INSTANCE: OnceLock<RateLimiter> = OnceLock::new();
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self { config, buckets: Mutex::new(HashMap::new()), throttled: Mutex::new(HashMap::new()) }
+    }
+
+    /// Process-wide limiter, built from the backend config on first use
+    pub fn get_instance() -> &'static RateLimiter {
+        INSTANCE.get_or_init(|| RateLimiter::new(get_rate_limit_config()))
+    }
+
+    /// `trusted` is set when the request carries the coordinator's (admin) API token
+    pub fn check(&self, ip: IpAddr, class: EndpointClass, trusted: bool, now: Instant) -> RateLimitDecision {
+        if !self.config.enabled {
+            return RateLimitDecision::Allowed;
+        }
+        if trusted || self.config.allowlist.contains(&ip) {
+            return RateLimitDecision::Bypassed;
+        }
+
+        let config = self.config.bucket(class);
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.len() >= MAX_TRACKED_BUCKETS {
+            // A bucket that refilled completely carries no state worth keeping
+            buckets.retain(|(_, class), bucket| {
+                let config = self.config.bucket(*class);
+                bucket.refill(config, now);
+                bucket.tokens < config.burst
+            });
+        }
+        let bucket = buckets.entry((ip, class)).or_insert_with(|| TokenBucket::full(config, now));
+        match bucket.try_take(config, now) {
+            Ok(()) => RateLimitDecision::Allowed,
+            Err(retry_after) => {
+                drop(buckets);
+                *self.throttled.lock().unwrap().entry(class).or_insert(0) += 1;
+                RateLimitDecision::Throttled { retry_after }
+            }
+        }
+    }
+
+    pub fn throttled_count(&self, class: EndpointClass) -> u64 {
+        self.throttled.lock().unwrap().get(&class).copied().unwrap_or(0)
+    }
+
+    /// Throttle counters in Prometheus text format, appended to /metrics
+    pub fn render_prometheus(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# TYPE backend_http_throttled_total counter");
+        for class in [EndpointClass::ShardData, EndpointClass::Admin] {
+            let _ = writeln!(out, "backend_http_throttled_total{{class=\"{}\"}} {}", class.label(), self.throttled_count(class));
+        }
+        out
+    }
+}
+
+/// 429 response; Retry-After is in whole seconds, rounded up
+pub fn too_many_requests_response(retry_after: Duration) -> String {
+    let retry_after_secs = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+    let error_json = r#"{"error":"Rate limit exceeded"}"#;
+    format!(
+        "HTTP/1.1 429 Too Many Requests\r\nRetry-After: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+        retry_after_secs,
+        error_json.len(),
+        error_json
+    )
+}
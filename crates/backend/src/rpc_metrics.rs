@@ -0,0 +1,201 @@
+use serde::Serialize;
+use shared::be_api::BackendRequest;
+use shared::rpc_decode_failures::{DecodeFailureTracker, PeerDecodeFailures, DECODE_FAILURE_LOG_INTERVAL};
+use shared::supervisor::spawn_supervised;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+pub const RPC_STATS_WINDOW: Duration = Duration::from_secs(60);
+
+/// Names of the BackendRequest variants, indexed by rpc_kind
+pub const RPC_KIND_NAMES: [&str; 19] = [
+    "Ping",
+    "InitColony",
+    "GetShardStats",
+    "InitColonyShard",
+    "GetColonyInfo",
+    "UpdatedShardContents",
+    "InitShardTopography",
+    "GetShardCurrentTick",
+    "ApplyEvent",
+    "StartTicking",
+    "UpdateTopology",
+    "SetTickerPaused",
+    "StepTicks",
+    "SetShardFrozen",
+    "UpdateBiomes",
+    "RefreshTopology",
+    "GetEventLog",
+    "GetStateHashes",
+    "GetShardDensity",
+];
+
+pub fn rpc_kind(request: &BackendRequest) -> usize {
+    match request {
+        BackendRequest::Ping => 0,
+        BackendRequest::InitColony(_) => 1,
+        BackendRequest::GetShardStats(_) => 2,
+        BackendRequest::InitColonyShard(_) => 3,
+        BackendRequest::GetColonyInfo(_) => 4,
+        BackendRequest::UpdatedShardContents(_) => 5,
+        BackendRequest::InitShardTopography(_) => 6,
+        BackendRequest::GetShardCurrentTick(_) => 7,
+        BackendRequest::ApplyEvent(_) => 8,
+        BackendRequest::StartTicking(_) => 9,
+        BackendRequest::UpdateTopology(_) => 10,
+        BackendRequest::SetTickerPaused(_) => 11,
+        BackendRequest::StepTicks(_) => 12,
+        BackendRequest::SetShardFrozen(_) => 13,
+        BackendRequest::UpdateBiomes(_) => 14,
+        BackendRequest::RefreshTopology(_) => 15,
+        BackendRequest::GetEventLog(_) => 16,
+        BackendRequest::GetStateHashes(_) => 17,
+        BackendRequest::GetShardDensity(_) => 18,
+    }
+}
+
+/// Requests only touch the current window; roll_window folds it into the totals
+struct RpcCounters {
+    window_count: AtomicU64,
+    window_total_us: AtomicU64,
+    window_max_us: AtomicU64,
+    last_count: AtomicU64,
+    last_total_us: AtomicU64,
+    last_max_us: AtomicU64,
+    rolled_count: AtomicU64,
+    rolled_total_us: AtomicU64,
+    rolled_max_us: AtomicU64,
+}
+
+impl RpcCounters {
+    const fn new() -> Self {
+        Self {
+            window_count: AtomicU64::new(0),
+            window_total_us: AtomicU64::new(0),
+            window_max_us: AtomicU64::new(0),
+            last_count: AtomicU64::new(0),
+            last_total_us: AtomicU64::new(0),
+            last_max_us: AtomicU64::new(0),
+            rolled_count: AtomicU64::new(0),
+            rolled_total_us: AtomicU64::new(0),
+            rolled_max_us: AtomicU64::new(0),
+        }
+    }
+}
+
+static RPC_COUNTERS: [RpcCounters; RPC_KIND_NAMES.len()] = [const { RpcCounters::new() }; RPC_KIND_NAMES.len()];
+/// Undecodable BackendRequests per peer IP, fed by be_server::handle_client
+pub static DECODE_FAILURES: Mutex<DecodeFailureTracker> = Mutex::new(DecodeFailureTracker::new(DECODE_FAILURE_LOG_INTERVAL));
+
+pub fn record_rpc(kind: usize, elapsed: Duration) {
+    let counters = &RPC_COUNTERS[kind];
+    let elapsed_us = elapsed.as_micros() as u64;
+    counters.window_count.fetch_add(1, Ordering::Relaxed);
+    counters.window_total_us.fetch_add(elapsed_us, Ordering::Relaxed);
+    counters.window_max_us.fetch_max(elapsed_us, Ordering::Relaxed);
+}
+
+/// Closes the current window: it becomes the "last window" and is added to the totals
+fn roll_window() {
+    for counters in &RPC_COUNTERS {
+        let count = counters.window_count.swap(0, Ordering::Relaxed);
+        let total_us = counters.window_total_us.swap(0, Ordering::Relaxed);
+        let max_us = counters.window_max_us.swap(0, Ordering::Relaxed);
+        counters.last_count.store(count, Ordering::Relaxed);
+        counters.last_total_us.store(total_us, Ordering::Relaxed);
+        counters.last_max_us.store(max_us, Ordering::Relaxed);
+        counters.rolled_count.fetch_add(count, Ordering::Relaxed);
+        counters.rolled_total_us.fetch_add(total_us, Ordering::Relaxed);
+        counters.rolled_max_us.fetch_max(max_us, Ordering::Relaxed);
+    }
+}
+
+pub fn start_window_rollover() {
+    spawn_supervised("rpc-window-rollover", || async {
+        let mut window_interval = tokio::time::interval(RPC_STATS_WINDOW);
+        window_interval.tick().await;
+        loop {
+            window_interval.tick().await;
+            roll_window();
+        }
+    });
+}
+
+#[derive(Serialize, Debug)]
+pub struct RpcLatencySummary {
+    pub count: u64,
+    pub mean_ms: f64,
+    pub max_ms: f64,
+}
+
+impl RpcLatencySummary {
+    fn new(count: u64, total_us: u64, max_us: u64) -> Self {
+        let mean_ms = if count > 0 { total_us as f64 / count as f64 / 1000.0 } else { 0.0 };
+        Self { count, mean_ms, max_ms: max_us as f64 / 1000.0 }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct RpcRequestStats {
+    pub request: &'static str,
+    pub since_startup: RpcLatencySummary,
+    /// The last completed window of RPC_STATS_WINDOW
+    pub last_window: RpcLatencySummary,
+}
+
+#[derive(Serialize, Debug)]
+pub struct RpcStatsSnapshot {
+    pub window_secs: u64,
+    pub requests: Vec<RpcRequestStats>,
+    /// Undecodable requests per peer IP
+    pub deserialize_failures: BTreeMap<String, PeerDecodeFailures>,
+}
+
+pub fn snapshot() -> RpcStatsSnapshot {
+    let requests = RPC_KIND_NAMES.iter().zip(RPC_COUNTERS.iter()).map(|(name, counters)| {
+        let window_count = counters.window_count.load(Ordering::Relaxed);
+        let window_total_us = counters.window_total_us.load(Ordering::Relaxed);
+        let window_max_us = counters.window_max_us.load(Ordering::Relaxed);
+        RpcRequestStats {
+            request: name,
+            since_startup: RpcLatencySummary::new(
+                counters.rolled_count.load(Ordering::Relaxed) + window_count,
+                counters.rolled_total_us.load(Ordering::Relaxed) + window_total_us,
+                counters.rolled_max_us.load(Ordering::Relaxed).max(window_max_us),
+            ),
+            last_window: RpcLatencySummary::new(
+                counters.last_count.load(Ordering::Relaxed),
+                counters.last_total_us.load(Ordering::Relaxed),
+                counters.last_max_us.load(Ordering::Relaxed),
+            ),
+        }
+    }).collect();
+
+    RpcStatsSnapshot {
+        window_secs: RPC_STATS_WINDOW.as_secs(),
+        requests,
+        deserialize_failures: DECODE_FAILURES.lock().unwrap().peers(),
+    }
+}
+
+/// Prometheus text exposition of the since-startup counters
+pub fn render_prometheus() -> String {
+    let snapshot = snapshot();
+    let mut out = String::new();
+    let _ = writeln!(out, "# TYPE backend_rpc_requests_total counter");
+    for stats in &snapshot.requests {
+        let _ = writeln!(out, "backend_rpc_requests_total{{request=\"{}\"}} {}", stats.request, stats.since_startup.count);
+    }
+    let _ = writeln!(out, "# TYPE backend_rpc_latency_mean_ms gauge");
+    for stats in &snapshot.requests {
+        let _ = writeln!(out, "backend_rpc_latency_mean_ms{{request=\"{}\"}} {:.3}", stats.request, stats.since_startup.mean_ms);
+    }
+    let _ = writeln!(out, "# TYPE backend_rpc_latency_max_ms gauge");
+    for stats in &snapshot.requests {
+        let _ = writeln!(out, "backend_rpc_latency_max_ms{{request=\"{}\"}} {:.3}", stats.request, stats.since_startup.max_ms);
+    }
+    out + &DECODE_FAILURES.lock().unwrap().render_prometheus("backend")
+}
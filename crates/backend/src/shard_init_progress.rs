@@ -0,0 +1,36 @@
+use std::collections::HashMap;
+use shared::be_api::Shard;
+
+/// How much of each shard's grid is generated, 0.0 to 1.0, for the shards initialized by this
+/// backend; kept on the Colony, see Colony::init_progress
+#[derive(Debug, Default)]
+pub struct InitProgress {
+    progress: HashMap<Shard, f32>,
+}
+
+impl InitProgress {
+    /// Records how much of a shard's grid is generated, 0.0 to 1.0
+    pub fn set(&mut self, shard: &Shard, progress: f32) {
+        self.progress.insert(*shard, progress.clamp(0.0, 1.0));
+    }
+
+    /// Progress of the shard's initialization, None if it was never initialized here
+    pub fn get(&self, shard: &Shard) -> Option<f32> {
+        self.progress.get(shard).copied()
+    }
+
+    /// Shards whose grid is still being generated, with their progress
+    pub fn initializing_shards(&self) -> Vec<(Shard, f32)> {
+        let mut shards: Vec<(Shard, f32)> = self.progress.iter()
+            .filter(|(_, progress)| **progress < 1.0)
+            .map(|(shard, progress)| (*shard, *progress))
+            .collect();
+        shards.sort_by_key(|(shard, _)| (shard.y, shard.x));
+        shards
+    }
+
+    /// Drops the progress of a shard that failed to initialize, so it does not look stuck
+    pub fn clear(&mut self, shard: &Shard) {
+        self.progress.remove(shard);
+    }
+}
@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use shared::backend_communication::send_request_with_pool;
+use shared::be_api::{Shard, ShardLease, SHARD_LEASE_DURATION, SHARD_LEASE_RENEW_INTERVAL};
+use shared::cluster_topology::ClusterTopology;
+use shared::coordinator_api::{CoordinatorRequest, CoordinatorResponse, LeaseRenewal};
+use shared::supervisor::spawn_supervised;
+use shared::{log, log_error};
+use crate::colony::Colony;
+use crate::colony_shard::ColonyShard;
+use crate::shard_lock::lock_shard;
+use crate::topology_refresh::this_backend_host;
+
+/// Why a hosted shard stopped ticking
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Suspension {
+    /// Not renewed within SHARD_LEASE_DURATION, e.g. cut off from the coordinator; a later renewal resumes the shard
+    Expired,
+    /// The coordinator issued this newer epoch to another backend; only a new assignment resumes the shard
+    Superseded { epoch: u64 },
+}
+
+/// This backend's lease on one hosted shard. Epoch 0 is a shard created without a lease, which never expires.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LeaseState {
+    pub epoch: u64,
+    pub renewed_at: Option<Instant>,
+    pub suspension: Option<Suspension>,
+}
+
+impl LeaseState {
+    pub fn granted(epoch: u64, now: Instant) -> Self {
+        Self { epoch, renewed_at: Some(now), suspension: None }
+    }
+
+    pub fn is_suspended(&self) -> bool {
+        self.suspension.is_some()
+    }
+
+    /// The coordinator confirmed the lease; a shard suspended for not renewing ticks again
+    pub fn renew(&mut self, now: Instant) {
+        self.renewed_at = Some(now);
+        if self.suspension == Some(Suspension::Expired) {
+            self.suspension = None;
+        }
+    }
+
+    /// Suspends the shard once duration has passed since the last renewal; true only on the call that suspended it
+    pub fn expire_if_due(&mut self, now: Instant, duration: Duration) -> bool {
+        let due = self.suspension.is_none()
+            && self.renewed_at.is_some_and(|renewed_at| now.saturating_duration_since(renewed_at) >= duration);
+        if due {
+            self.suspension = Some(Suspension::Expired);
+        }
+        due
+    }
+
+    /// Another backend holds the shard under epoch; true unless it was already superseded
+    pub fn supersede(&mut self, epoch: u64) -> bool {
+        let newly = !matches!(self.suspension, Some(Suspension::Superseded { .. }));
+        self.suspension = Some(Suspension::Superseded { epoch });
+        newly
+    }
+
+    pub fn to_lease(self, shard: Shard) -> ShardLease {
+        ShardLease { shard, epoch: self.epoch }
+    }
+}
+
+/// Applies the coordinator's answer for one of this shard's renewals
+pub fn apply_renewal(shard: &mut ColonyShard, renewal: &LeaseRenewal, now: Instant) {
+    match renewal {
+        LeaseRenewal::Renewed(_) => shard.lease.renew(now),
+        LeaseRenewal::Superseded { epoch, owner, .. } => {
+            if shard.lease.supersede(*epoch) {
+                log_error!("Suspending shard {}: {} holds it under lease epoch {}, this backend has epoch {}",
+                           shard.shard.to_id(), owner, epoch, shard.lease.epoch);
+            }
+        }
+    }
+}
+
+/// Checks the shard against the coordinator's leases sent with StartTicking; true when the
+/// coordinator issued a newer epoch, which suspends the shard
+pub fn check_coordinator_lease(shard: &mut ColonyShard, leases: &[ShardLease], now: Instant) -> bool {
+    let Some(lease) = leases.iter().find(|lease| lease.shard == shard.shard) else {
+        return false;
+    };
+    if lease.epoch > shard.lease.epoch {
+        if shard.lease.supersede(lease.epoch) {
+            log_error!("Suspending shard {}: the coordinator issued lease epoch {}, this backend has epoch {}",
+                       shard.shard.to_id(), lease.epoch, shard.lease.epoch);
+        }
+        return true;
+    }
+    if lease.epoch == shard.lease.epoch {
+        shard.lease.renew(now);
+    }
+    false
+}
+
+/// Newest lease epoch seen in border updates, per sending shard
+#[derive(Debug, Default)]
+pub struct BorderEpochs {
+    newest: HashMap<Shard, u64>,
+}
+
+impl BorderEpochs {
+    /// Err when epoch is older than one already seen for the shard: the sender lost the shard to another backend
+    pub fn check(&mut self, shard: &Shard, epoch: u64) -> Result<(), String> {
+        let newest = self.newest.entry(*shard).or_insert(epoch);
+        if epoch < *newest {
+            return Err(format!("border update of shard {} carries lease epoch {}, epoch {} has taken over",
+                               shard.to_id(), epoch, newest));
+        }
+        *newest = epoch;
+        Ok(())
+    }
+}
+
+/// BorderEpochs::check against the border updates the colony has received
+pub fn check_border_epoch(shard: &Shard, epoch: u64) -> Result<(), String> {
+    Colony::instance().border_epochs().check(shard, epoch)
+}
+
+async fn renew_leases() {
+    if !Colony::is_initialized() {
+        return;
+    }
+    let Some(topology) = ClusterTopology::get_instance() else {
+        return;
+    };
+    let (_, shard_arcs) = Colony::instance().get_hosted_shards();
+    let leases: Vec<ShardLease> = shard_arcs.iter()
+        .map(|shard_arc| lock_shard(shard_arc))
+        .filter(|shard| shard.lease.epoch > 0 && !matches!(shard.lease.suspension, Some(Suspension::Superseded { .. })))
+        .map(|shard| shard.lease.to_lease(shard.shard))
+        .collect();
+
+    if !leases.is_empty() {
+        let request = CoordinatorRequest::RenewShardLeases { backend: this_backend_host(), leases };
+        let coordinator = &topology.coordinator_host;
+        match tokio::time::timeout(SHARD_LEASE_RENEW_INTERVAL, send_request_with_pool(coordinator, &request)).await {
+            Ok(Ok(CoordinatorResponse::RenewShardLeasesResponse { renewals })) => {
+                let now = Instant::now();
+                for shard_arc in &shard_arcs {
+                    let mut shard = lock_shard(shard_arc);
+                    let key = shard.shard;
+                    let renewal = renewals.iter().find(|renewal| match renewal {
+                        LeaseRenewal::Renewed(shard) | LeaseRenewal::Superseded { shard, .. } => *shard == key,
+                    });
+                    if let Some(renewal) = renewal {
+                        apply_renewal(&mut shard, renewal, now);
+                    }
+                }
+            }
+            Ok(Ok(_)) => log_error!("Unexpected response to RenewShardLeases from {}", coordinator.to_address()),
+            Ok(Err(e)) => log_error!("Failed to renew shard leases with {}: {}", coordinator.to_address(), e),
+            Err(_) => log_error!("Renewing shard leases with {} timed out", coordinator.to_address()),
+        }
+    }
+
+    let now = Instant::now();
+    for shard_arc in &shard_arcs {
+        let mut shard = lock_shard(shard_arc);
+        if shard.lease.expire_if_due(now, SHARD_LEASE_DURATION) {
+            log_error!("Suspending shard {}: lease epoch {} not renewed for {:?}",
+                       shard.shard.to_id(), shard.lease.epoch, SHARD_LEASE_DURATION);
+        }
+    }
+}
+
+/// Renews the leases of the hosted shards every SHARD_LEASE_RENEW_INTERVAL and suspends the
+/// shards whose lease ran out
+pub fn start_lease_renewal() {
+    spawn_supervised("lease-renewal", || async {
+        log!("Renewing shard leases every {:?}", SHARD_LEASE_RENEW_INTERVAL);
+        let mut timer = tokio::time::interval(SHARD_LEASE_RENEW_INTERVAL);
+        loop {
+            timer.tick().await;
+            renew_leases().await;
+        }
+    });
+}
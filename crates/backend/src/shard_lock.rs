@@ -0,0 +1,68 @@
+use std::fmt::Write;
+use std::sync::{Mutex, MutexGuard};
+use shared::log_error;
+use crate::colony::Colony;
+use crate::colony_shard::ColonyShard;
+
+/// Locks a shard. A panic while holding the lock poisons it; instead of panicking in turn, the
+/// guard is recovered, its state checked with ColonyShard::check_invariants, and the shard
+/// quarantined if the check fails.
+pub fn lock_shard(shard_arc: &Mutex<ColonyShard>) -> MutexGuard<'_, ColonyShard> {
+    match shard_arc.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => {
+            let mut guard = poisoned.into_inner();
+            recover(&mut guard);
+            shard_arc.clear_poison();
+            guard
+        }
+    }
+}
+
+/// Counts the recovery on the shard, logging its first one, and quarantines it on a broken invariant
+fn recover(shard: &mut ColonyShard) {
+    shard.lock_recoveries += 1;
+    if shard.lock_recoveries == 1 {
+        log_error!("Shard {} lock was poisoned by a panic at tick {}, recovering it", shard.shard.to_id(), shard.current_tick);
+    }
+    if let Err(violation) = shard.check_invariants() {
+        log_error!("Shard {} quarantined: {}", shard.shard.to_id(), violation);
+        shard.quarantine = Some(violation);
+    }
+}
+
+/// Ids of the colony's quarantined shards, sorted
+pub fn quarantined_shard_ids(colony: &Colony) -> Vec<String> {
+    let (_, shard_arcs) = colony.get_hosted_shards();
+    let mut ids: Vec<String> = shard_arcs.iter()
+        .filter_map(|shard_arc| {
+            let shard = lock_shard(shard_arc);
+            shard.is_quarantined().then(|| shard.shard.to_id())
+        })
+        .collect();
+    ids.sort();
+    ids
+}
+
+/// Lock recoveries over all of the colony's shards
+pub fn poisoned_recoveries(colony: &Colony) -> u64 {
+    let (_, shard_arcs) = colony.get_hosted_shards();
+    shard_arcs.iter().map(|shard_arc| lock_shard(shard_arc).lock_recoveries).sum()
+}
+
+/// Recovery counters of the process-wide colony in Prometheus text format, appended to /metrics;
+/// both are 0 until the colony is initialized
+pub fn render_prometheus() -> String {
+    let (recoveries, quarantined) = if Colony::is_initialized() {
+        let colony = Colony::instance();
+        (poisoned_recoveries(colony), quarantined_shard_ids(colony).len())
+    } else {
+        (0, 0)
+    };
+    let mut out = String::new();
+    let _ = writeln!(out, "# TYPE backend_shard_lock_poisoned_recoveries_total counter");
+    let _ = writeln!(out, "backend_shard_lock_poisoned_recoveries_total {}", recoveries);
+    let _ = writeln!(out, "# TYPE backend_quarantined_shards gauge");
+    let _ = writeln!(out, "backend_quarantined_shards {}", quarantined);
+    out
+}
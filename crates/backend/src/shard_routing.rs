@@ -0,0 +1,52 @@
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use shared::be_api::Shard;
+use shared::cluster_topology::{ClusterTopology, HostInfo};
+
+/// Requests for shards the topology assigns to another backend, see /metrics
+static MISDIRECTED_REQUESTS: AtomicU64 = AtomicU64::new(0);
+
+/// Where a request for a shard belongs, judged by this backend's copy of the topology
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShardRouting {
+    Hosted,
+    /// Another backend hosts it; the client's routing is stale
+    Elsewhere(HostInfo),
+    /// Not hosted here and not assigned elsewhere, or not a shard of the colony at all
+    Unknown,
+}
+
+pub fn route_shard_request(shard: &Shard, hosted: bool, topology: Option<&ClusterTopology>, this_backend: &HostInfo) -> ShardRouting {
+    if hosted {
+        return ShardRouting::Hosted;
+    }
+    match topology.and_then(|topology| topology.get_host_for_shard(shard)) {
+        Some(host) if host != this_backend => ShardRouting::Elsewhere(host.clone()),
+        _ => ShardRouting::Unknown,
+    }
+}
+
+/// Other backends that host a neighbor of shard and so get its border updates
+pub fn border_update_hosts(topology: &ClusterTopology, shard: &Shard, this_backend: &HostInfo) -> HashSet<HostInfo> {
+    let adjacent: Vec<Shard> = topology.get_adjacent_shards(shard);
+    let mut hosts = topology.get_backend_hosts_for_shards(&adjacent);
+    hosts.remove(this_backend);
+    hosts
+}
+
+pub fn record_misdirected_request() {
+    MISDIRECTED_REQUESTS.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn misdirected_requests() -> u64 {
+    MISDIRECTED_REQUESTS.load(Ordering::Relaxed)
+}
+
+/// The misdirected request counter in Prometheus text format, appended to /metrics
+pub fn render_prometheus() -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# TYPE backend_http_misdirected_shard_requests_total counter");
+    let _ = writeln!(out, "backend_http_misdirected_shard_requests_total {}", misdirected_requests());
+    out
+}
@@ -0,0 +1,144 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use shared::be_api::{Color, Shard, ShardStatResult, StatBucket, StatMetric, StringStatBucket, FOOD_COVERAGE_MIN_FOOD};
+use crate::colony_shard::ColonyShard;
+
+static SNAPSHOTS_TOTAL: AtomicU64 = AtomicU64::new(0);
+static LOCK_HELD_US_TOTAL: AtomicU64 = AtomicU64::new(0);
+static LOCK_HELD_US_MAX: AtomicU64 = AtomicU64::new(0);
+
+/// The fields of a cell the stats read, about half the size of a Cell
+#[derive(Debug, Clone, Copy)]
+struct StatCell {
+    health: u16,
+    food: u16,
+    age: u16,
+    size: u8,
+    can_kill: bool,
+    can_move: bool,
+    original_color: Color,
+    sanctuary: bool,
+}
+
+/// Interior cells of a shard copied out of the grid, so the histograms can be built
+/// after the shard lock is released instead of delaying the ticker
+pub struct ShardStatsSnapshot {
+    shard: Shard,
+    cells: Vec<StatCell>,
+}
+
+impl ShardStatsSnapshot {
+    /// The only part of a stats request that needs the shard lock; None when the shard differs.
+    /// With a region (colony coordinates) only the cells inside it are copied; a region that
+    /// misses the shard gives a snapshot without cells, whose histograms are empty.
+    pub fn capture(shard: &ColonyShard, req_shard: &Shard, region: Option<&Shard>) -> Option<Self> {
+        if shard.shard != *req_shard {
+            return None;
+        }
+        let row_size = shard.shard.width as usize + 2;
+        let area = match region {
+            Some(region) => shard.shard.intersection(region),
+            None => Some(shard.shard),
+        };
+        let Some(area) = area else {
+            return Some(Self { shard: shard.shard, cells: Vec::new() });
+        };
+        // Grid rows and columns of the area, past the shadow margin
+        let first_col = (area.x - shard.shard.x) as usize + 1;
+        let first_row = (area.y - shard.shard.y) as usize + 1;
+        let width = area.width as usize;
+        let height = area.height as usize;
+        let mut cells = Vec::with_capacity(width * height);
+        for row_iter in first_row..first_row + height {
+            let start = row_iter * row_size + first_col;
+            cells.extend(shard.grid[start..start + width].iter().enumerate().map(|(offset, cell)| StatCell {
+                health: cell.health,
+                food: cell.food,
+                age: cell.age,
+                size: cell.traits.size,
+                can_kill: cell.traits.can_kill,
+                can_move: cell.traits.can_move,
+                original_color: cell.original_color,
+                sanctuary: shard.is_sanctuary(start + offset),
+            }));
+        }
+        Some(Self { shard: shard.shard, cells })
+    }
+
+    pub fn compute_stats(&self, stats: &[StatMetric]) -> Vec<ShardStatResult> {
+        let mut metric_buckets: Vec<(StatMetric, Vec<StatBucket>)> = Vec::new();
+        let mut string_metric_buckets: Vec<(StatMetric, Vec<StringStatBucket>)> = Vec::new();
+
+        for stat in stats.iter().copied() {
+            match stat {
+                StatMetric::Health => metric_buckets.push((stat, self.accumulate_counts(|c| c.health as i32, false))),
+                StatMetric::Size => metric_buckets.push((stat, self.accumulate_counts(|c| c.size as i32, false))),
+                StatMetric::CanKill => metric_buckets.push((stat, self.accumulate_counts(|c| c.can_kill as i32, false))),
+                StatMetric::CanMove => metric_buckets.push((stat, self.accumulate_counts(|c| c.can_move as i32, false))),
+                StatMetric::Food => metric_buckets.push((stat, self.accumulate_counts(|c| c.food as i32, true))),
+                StatMetric::Age => metric_buckets.push((stat, self.accumulate_counts(|c| c.age as i32, false))),
+                StatMetric::Sanctuary => metric_buckets.push((stat, self.accumulate_counts(|c| c.sanctuary as i32, false))),
+                StatMetric::Occupancy => metric_buckets.push((stat, self.accumulate_counts(|c| (c.health > 0) as i32, true))),
+                StatMetric::FoodCoverage => metric_buckets.push((stat, self.accumulate_counts(|c| (c.food > FOOD_COVERAGE_MIN_FOOD) as i32, true))),
+                StatMetric::OriginalColor => {
+                    let buckets = self.accumulate_string_counts(|c| {
+                        format!("{}_{}_{}", c.original_color.red, c.original_color.green, c.original_color.blue)
+                    }, false);
+                    string_metric_buckets.push((stat, buckets));
+                }
+            }
+        }
+
+        vec![ShardStatResult {
+            shard: self.shard,
+            metrics: metric_buckets,
+            string_metrics: string_metric_buckets,
+        }]
+    }
+
+    fn accumulate_counts<F>(&self, mapper: F, include_blank_cells: bool) -> Vec<StatBucket>
+    where
+        F: Fn(&StatCell) -> i32,
+    {
+        let mut counts: BTreeMap<i32, u64> = BTreeMap::new();
+        for cell in &self.cells {
+            if !include_blank_cells && cell.health == 0 { continue; }
+            *counts.entry(mapper(cell)).or_insert(0) += 1;
+        }
+        counts.into_iter().map(|(value, occs)| StatBucket { value, occs }).collect()
+    }
+
+    fn accumulate_string_counts<F>(&self, mapper: F, include_blank_cells: bool) -> Vec<StringStatBucket>
+    where
+        F: Fn(&StatCell) -> String,
+    {
+        let mut counts: BTreeMap<String, u64> = BTreeMap::new();
+        for cell in &self.cells {
+            if !include_blank_cells && cell.health == 0 { continue; }
+            *counts.entry(mapper(cell)).or_insert(0) += 1;
+        }
+        counts.into_iter().map(|(value, occs)| StringStatBucket { value, occs }).collect()
+    }
+}
+
+/// How long a stats request held the shard lock
+pub fn record_lock_held(held: Duration) {
+    let held_us = held.as_micros() as u64;
+    SNAPSHOTS_TOTAL.fetch_add(1, Ordering::Relaxed);
+    LOCK_HELD_US_TOTAL.fetch_add(held_us, Ordering::Relaxed);
+    LOCK_HELD_US_MAX.fetch_max(held_us, Ordering::Relaxed);
+}
+
+/// Lock-held counters in Prometheus text format, appended to /metrics
+pub fn render_prometheus() -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# TYPE backend_stats_snapshots_total counter");
+    let _ = writeln!(out, "backend_stats_snapshots_total {}", SNAPSHOTS_TOTAL.load(Ordering::Relaxed));
+    let _ = writeln!(out, "# TYPE backend_stats_lock_held_us_total counter");
+    let _ = writeln!(out, "backend_stats_lock_held_us_total {}", LOCK_HELD_US_TOTAL.load(Ordering::Relaxed));
+    let _ = writeln!(out, "# TYPE backend_stats_lock_held_us_max gauge");
+    let _ = writeln!(out, "backend_stats_lock_held_us_max {}", LOCK_HELD_US_MAX.load(Ordering::Relaxed));
+    out
+}
@@ -1,26 +1,252 @@
-use crate::{colony_shard::ColonyShard};
+use crate::{colony_shard::ColonyShard, shard_topography::ShardTopography};
+use shared::be_api::ColonyLifeRules;
+use shared::log_error;
 use shared::storage::StorageUtils;
 use serde::{Serialize, Deserialize};
 
+/// Leads every snapshot since format version 1. A version 0 snapshot is the bare bincode
+/// ColonyShard; its first bytes are the shard's x, which never spells this magic.
+pub const SNAPSHOT_MAGIC: [u8; 4] = *b"CSNP";
+/// Bump when the snapshot layout changes and teach load_snapshot the old one.
+/// Version 2 appends a SnapshotTopography and a SnapshotRules to the shard.
+/// Version 3 adds the hunger counter to every cell and the starvation and food-sharing rules.
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 3;
+
 #[derive(Serialize, Deserialize)]
 pub struct ShardStorage;
 
+/// The shard's terrain as InitShardTopography payloads, so a run can be replayed on the same ground
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SnapshotTopography {
+    pub version: u64,
+    /// Rebuilds the current terrain, see ShardTopography::export_topography
+    pub current: Vec<u8>,
+    /// Terrain received while ticking and not applied yet
+    pub pending: Option<Vec<u8>>,
+}
+
+impl SnapshotTopography {
+    fn of(shard: &ColonyShard) -> Self {
+        Self {
+            version: shard.topography_version,
+            current: ShardTopography::export_topography(shard),
+            pending: shard.pending_topography.clone(),
+        }
+    }
+}
+
+/// The rules the shard ticked with. Biome rules are rebuilt from the overrides on load;
+/// the stored ones tell whether this build still derives the same.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SnapshotRules {
+    pub base: ColonyLifeRules,
+    /// Base rules with each biome's overrides applied, in the order of the shard's biomes
+    pub biome_rules: Vec<ColonyLifeRules>,
+}
+
+/// A shard read back from disk, with the format version it was written in
+#[allow(dead_code)] // format_version and rules are only read by colony-inspect
+pub struct ShardSnapshot {
+    pub format_version: u32,
+    pub shard: ColonyShard,
+    /// Stored since version 2; for older snapshots taken from the cells, with nothing pending
+    pub topography: SnapshotTopography,
+    /// None before version 2
+    pub rules: Option<SnapshotRules>,
+}
+
 #[allow(dead_code)]
 impl ShardStorage {
     pub fn store_shard(shard: &ColonyShard, filename: &str) -> Result<(), String> {
-        StorageUtils::store_with_checksum(shard, filename)
+        let mut data = SNAPSHOT_MAGIC.to_vec();
+        data.extend_from_slice(&SNAPSHOT_FORMAT_VERSION.to_le_bytes());
+        let rules = SnapshotRules { base: shard.colony_life_rules, biome_rules: shard.biome_rules.clone() };
+        bincode::serialize_into(&mut data, &(shard, SnapshotTopography::of(shard), rules))
+            .map_err(|e| format!("Failed to serialize shard {}: {}", shard.shard.to_id(), e))?;
+        StorageUtils::store_bytes_with_checksum(data, filename)
+    }
+
+    /// Reads a snapshot of any format version up to SNAPSHOT_FORMAT_VERSION
+    pub fn load_snapshot(filename: &str) -> Result<ShardSnapshot, String> {
+        let data = StorageUtils::retrieve_bytes_with_checksum(filename)?;
+        let (format_version, body) = match data.strip_prefix(&SNAPSHOT_MAGIC) {
+            Some(rest) => {
+                let version_bytes: [u8; 4] = rest.get(..4).and_then(|bytes| bytes.try_into().ok())
+                    .ok_or_else(|| format!("{} ends inside the snapshot header", filename))?;
+                (u32::from_le_bytes(version_bytes), &rest[4..])
+            }
+            None => (0, data.as_slice()),
+        };
+        if format_version > SNAPSHOT_FORMAT_VERSION {
+            return Err(format!("{} is a format version {} snapshot; this build reads versions up to {}, use a newer build",
+                               filename, format_version, SNAPSHOT_FORMAT_VERSION));
+        }
+        let unreadable = |e: bincode::Error| format!("{} is not a readable format version {} snapshot: {}", filename, format_version, e);
+        let (mut shard, topography, rules) = match format_version {
+            3.. => {
+                let (shard, topography, rules): (ColonyShard, SnapshotTopography, SnapshotRules) = bincode::deserialize(body).map_err(unreadable)?;
+                (shard, Some(topography), Some(rules))
+            }
+            2 => {
+                let (shard, topography, rules): (v2::ColonyShard, SnapshotTopography, v2::SnapshotRules) = bincode::deserialize(body).map_err(unreadable)?;
+                (shard.into(), Some(topography), Some(rules.into()))
+            }
+            _ => (bincode::deserialize::<v2::ColonyShard>(body).map_err(unreadable)?.into(), None, None),
+        };
+        // The per-cell biome index and the biome rules are not stored
+        let biomes = std::mem::take(&mut shard.biomes);
+        shard.set_biomes(&biomes);
+        let topography = match topography {
+            Some(topography) => {
+                ShardTopography::restore_topography(&mut shard, &topography.current)
+                    .map_err(|e| format!("{} holds topography that does not fit its shard: {}", filename, e))?;
+                shard.topography_version = topography.version;
+                shard.pending_topography = topography.pending.clone();
+                topography
+            }
+            None => SnapshotTopography::of(&shard),
+        };
+        Ok(ShardSnapshot { format_version, shard, topography, rules })
     }
 
     pub fn retrieve_shard(shard: &mut ColonyShard, filename: &str) -> bool {
-        if let Some(loaded_shard) = StorageUtils::retrieve_with_checksum::<ColonyShard>(filename) {
-            shard.grid = loaded_shard.grid;
-            shard.colony_life_rules = loaded_shard.colony_life_rules;
-            shard.current_tick = loaded_shard.current_tick;
-            assert_eq!(shard.shard, loaded_shard.shard);
-            true
-        } else {
-            false
+        match Self::load_snapshot(filename) {
+            Ok(ShardSnapshot { shard: loaded_shard, .. }) => {
+                shard.grid = loaded_shard.grid;
+                shard.colony_life_rules = loaded_shard.colony_life_rules;
+                shard.current_tick = loaded_shard.current_tick;
+                shard.set_biomes(&loaded_shard.biomes);
+                shard.event_log = loaded_shard.event_log;
+                shard.sanctuary = loaded_shard.sanctuary;
+                shard.topography_version = loaded_shard.topography_version;
+                shard.pending_topography = loaded_shard.pending_topography;
+                assert_eq!(shard.shard, loaded_shard.shard);
+                true
+            }
+            Err(e) => {
+                // No snapshot yet is the normal case, anything else is worth a log line
+                if std::path::Path::new(filename).exists() {
+                    log_error!("{}", e);
+                }
+                false
+            }
         }
     }
 }
 
+/// Layout of format versions 0 to 2, before the hunger counter and the starvation and
+/// food-sharing rules. Rules were the first 16 fields of COLONY_LIFE_RULE_RANGES, in that order.
+mod v2 {
+    use crate::shard_utils::ShardUtils;
+    use serde::Deserialize;
+    use shared::be_api::{ColonyLifeRules, Color, Shard, ShardEventRecord, Traits, COLONY_LIFE_RULE_RANGES};
+    use shared::colony_model::{Biome as CurrentBiome, ColonyLifeRulesOverride};
+    use std::collections::VecDeque;
+    use uuid::Uuid;
+
+    type Rules = [u32; 16];
+    type RulesOverride = [Option<u32>; 16];
+
+    /// Rules fields added since all default to what the simulation did without them
+    fn rules(values: &Rules) -> ColonyLifeRules {
+        let fields = COLONY_LIFE_RULE_RANGES.iter().zip(values)
+            .map(|(range, value)| (range.field.to_string(), (*value).into()))
+            .collect();
+        serde_json::from_value(serde_json::Value::Object(fields)).expect("rules fields added since version 2 have defaults")
+    }
+
+    fn rules_override(values: &RulesOverride) -> ColonyLifeRulesOverride {
+        let fields = COLONY_LIFE_RULE_RANGES.iter().zip(values)
+            .filter_map(|(range, value)| value.map(|value| (range.field.to_string(), value.into())))
+            .collect();
+        serde_json::from_value(serde_json::Value::Object(fields)).expect("override fields are all optional")
+    }
+
+    #[derive(Deserialize)]
+    struct Cell {
+        tick_bit: bool,
+        food: u16,
+        extra_food_per_tick: u8,
+        color: Color,
+        original_color: Color,
+        health: u16,
+        age: u16,
+        traits: Traits,
+    }
+
+    #[derive(Deserialize)]
+    struct Biome {
+        name: String,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        overrides: RulesOverride,
+    }
+
+    #[derive(Deserialize)]
+    pub struct ColonyShard {
+        shard: Shard,
+        colony_life_rules: Rules,
+        grid: Vec<Cell>,
+        current_tick: u64,
+        recent_event_ids: VecDeque<Uuid>,
+        dirty_pixels_journal: VecDeque<(u64, u32)>,
+        frozen: bool,
+        awaiting_topography: bool,
+        sanctuary: Vec<bool>,
+        biomes: Vec<Biome>,
+        event_log: VecDeque<ShardEventRecord>,
+        topography_version: u64,
+    }
+
+    impl From<ColonyShard> for crate::colony_shard::ColonyShard {
+        fn from(stored: ColonyShard) -> Self {
+            let mut shard = ShardUtils::blank_colony_shard(&stored.shard, &rules(&stored.colony_life_rules));
+            shard.grid = stored.grid.into_iter()
+                .map(|cell| shared::be_api::Cell {
+                    tick_bit: cell.tick_bit,
+                    food: cell.food,
+                    extra_food_per_tick: cell.extra_food_per_tick,
+                    color: cell.color,
+                    original_color: cell.original_color,
+                    health: cell.health,
+                    age: cell.age,
+                    hunger: 0,
+                    traits: cell.traits,
+                })
+                .collect();
+            shard.current_tick = stored.current_tick;
+            shard.recent_event_ids = stored.recent_event_ids;
+            shard.dirty_pixels_journal = stored.dirty_pixels_journal;
+            shard.frozen = stored.frozen;
+            shard.awaiting_topography = stored.awaiting_topography;
+            shard.sanctuary = stored.sanctuary;
+            shard.biomes = stored.biomes.into_iter()
+                .map(|biome| CurrentBiome {
+                    name: biome.name,
+                    x: biome.x,
+                    y: biome.y,
+                    width: biome.width,
+                    height: biome.height,
+                    overrides: rules_override(&biome.overrides),
+                })
+                .collect();
+            shard.event_log = stored.event_log;
+            shard.topography_version = stored.topography_version;
+            shard
+        }
+    }
+
+    #[derive(Deserialize)]
+    pub struct SnapshotRules {
+        base: Rules,
+        biome_rules: Vec<Rules>,
+    }
+
+    impl From<SnapshotRules> for super::SnapshotRules {
+        fn from(stored: SnapshotRules) -> Self {
+            Self { base: rules(&stored.base), biome_rules: stored.biome_rules.iter().map(rules).collect() }
+        }
+    }
+}
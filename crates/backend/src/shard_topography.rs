@@ -1,43 +1,134 @@
-use crate::{colony_shard::ColonyShard, shard_utils::ShardUtils};
+use crate::{colony_shard::{is_blank, set_blank, ColonyShard}, shard_utils::ShardUtils};
+use shared::be_api::{pack_sanctuary_mask, sanctuary_bit, sanctuary_mask_len, WATER_TOPOGRAPHY_VALUE};
+use shared::colony_model::{LocalPos, Shard};
 use shared::log;
 pub struct ShardTopography;
 
+/// Interior cells of the shard in the row-major order of the topography data
+fn interior_cells(bounds: Shard) -> impl Iterator<Item = LocalPos> + Clone {
+    (0..bounds.height).flat_map(move |y| (0..bounds.width).map(move |x| LocalPos::new(x, y)))
+}
+
 impl ShardTopography {
-    pub fn init_shard_topography_from_data(shard: &mut ColonyShard, topography_data: &[u8]) -> Result<(), ()> {
-        log!("Initializing shard topography from data for shard ({},{},{},{})", 
-            shard.shard.x, shard.shard.y, shard.shard.width, shard.shard.height);
-        
+    /// Checks the payload size; returns the extra food of every interior cell and the sanctuary mask
+    fn split_topography_data<'a>(shard: &ColonyShard, topography_data: &'a [u8]) -> Result<(&'a [u8], &'a [u8]), String> {
         let expected_size = (shard.shard.width * shard.shard.height) as usize;
-        if topography_data.len() != expected_size {
-            log!("Topography data size mismatch: expected {}, got {}", expected_size, topography_data.len());
-            return Err(());
+        let with_sanctuaries = expected_size + sanctuary_mask_len(expected_size);
+        if topography_data.len() != expected_size && topography_data.len() != with_sanctuaries {
+            let error = format!("Topography data size mismatch: expected {} or {} with sanctuaries, got {}",
+                expected_size, with_sanctuaries, topography_data.len());
+            log!("{}", error);
+            return Err(error);
         }
-        
-        // Apply the topography data directly to the shard's interior cells (excluding shadow margins)
-        let width = (shard.shard.width + 2) as usize;
-        
+        Ok(topography_data.split_at(expected_size))
+    }
+
+    pub fn init_shard_topography_from_data(shard: &mut ColonyShard, topography_data: &[u8]) -> Result<(), String> {
+        log!("Initializing shard topography from data for shard ({},{},{},{})",
+            shard.shard.x, shard.shard.y, shard.shard.width, shard.shard.height);
+
+        let (topography_data, sanctuary_mask) = Self::split_topography_data(shard, topography_data)?;
+
         // Initialize all cells with default value (0)
-        for idx in 0..shard.grid.len() {
-            shard.grid[idx].food = 0;
-            shard.grid[idx].extra_food_per_tick = 0;
+        for cell in shard.grid.iter_mut() {
+            cell.food = 0;
+            cell.extra_food_per_tick = 0;
         }
-        
-        // Apply topography data to interior cells (skip shadow margins)
-        for y in 0..shard.shard.height as usize {
-            for x in 0..shard.shard.width as usize {
-                let data_idx = y * shard.shard.width as usize + x;
-                let grid_idx = (y + 1) * width + (x + 1); // +1 for shadow margin
-                
-                if data_idx < topography_data.len() && grid_idx < shard.grid.len() {
-                    let value = topography_data[data_idx];
-                    shard.grid[grid_idx].food = 200;
-                    shard.grid[grid_idx].extra_food_per_tick = value;
-                }
+
+        // The data covers the interior cells only, in row-major order
+        let bounds = shard.shard;
+        for (pos, &value) in interior_cells(bounds).zip(topography_data) {
+            if let Some(grid_idx) = pos.grid_index(&bounds) {
+                shard.grid[grid_idx].food = if value == WATER_TOPOGRAPHY_VALUE { 0 } else { 200 };
+                shard.grid[grid_idx].extra_food_per_tick = value;
             }
         }
-        
+
+        Self::apply_sanctuary_mask(shard, sanctuary_mask);
+        shard.topography_version += 1;
         ShardUtils::store_shard(shard);
         Ok(())
     }
-    
+
+    /// New terrain replaces the sanctuaries too; a payload without a mask leaves none
+    fn apply_sanctuary_mask(shard: &mut ColonyShard, sanctuary_mask: &[u8]) {
+        shard.sanctuary.clear();
+        if !sanctuary_mask.is_empty() {
+            let bounds = shard.shard;
+            shard.sanctuary = vec![false; shard.grid.len()];
+            for (data_idx, pos) in interior_cells(bounds).enumerate() {
+                if let Some(grid_idx) = pos.grid_index(&bounds) {
+                    shard.sanctuary[grid_idx] = sanctuary_bit(sanctuary_mask, data_idx);
+                }
+            }
+        }
+    }
+
+    /// The current terrain as a payload laid out like InitShardTopographyRequest::topography_data,
+    /// with the sanctuary mask when the shard has sanctuaries
+    pub fn export_topography(shard: &ColonyShard) -> Vec<u8> {
+        let bounds = shard.shard;
+        let cells = || interior_cells(bounds).filter_map(move |pos| pos.grid_index(&bounds));
+        let mut data: Vec<u8> = cells().map(|grid_idx| shard.grid[grid_idx].extra_food_per_tick).collect();
+        if !shard.sanctuary.is_empty() {
+            data.extend(pack_sanctuary_mask(cells().map(|grid_idx| shard.is_sanctuary(grid_idx))));
+        }
+        data
+    }
+
+    /// Puts back terrain from export_topography; food and creatures are left as they are
+    pub fn restore_topography(shard: &mut ColonyShard, topography_data: &[u8]) -> Result<(), String> {
+        let (topography_data, sanctuary_mask) = Self::split_topography_data(shard, topography_data)?;
+        let bounds = shard.shard;
+        for (pos, &value) in interior_cells(bounds).zip(topography_data) {
+            if let Some(grid_idx) = pos.grid_index(&bounds) {
+                shard.grid[grid_idx].extra_food_per_tick = value;
+            }
+        }
+        Self::apply_sanctuary_mask(shard, sanctuary_mask);
+        Ok(())
+    }
+
+    /// Terrain for a shard that is already ticking: validated now, applied by
+    /// apply_pending_topography right before the next tick. A newer payload replaces one
+    /// still queued. A paused ticker holds it until the next step or resume.
+    pub fn queue_topography(shard: &mut ColonyShard, topography_data: Vec<u8>) -> Result<(), String> {
+        Self::split_topography_data(shard, &topography_data)?;
+        shard.pending_topography = Some(topography_data);
+        Ok(())
+    }
+
+    /// Applies queued terrain. Unlike the initial load, food and creatures are kept; only
+    /// creatures on cells that just turned to water die. Returns how many did, None when
+    /// nothing was queued.
+    pub fn apply_pending_topography(shard: &mut ColonyShard) -> Option<u64> {
+        let pending = shard.pending_topography.take()?;
+        let Ok((topography_data, sanctuary_mask)) = Self::split_topography_data(shard, &pending) else {
+            return None;
+        };
+
+        let bounds = shard.shard;
+        let mut drowned = 0;
+        for (pos, &value) in interior_cells(bounds).zip(topography_data) {
+            let Some(grid_idx) = pos.grid_index(&bounds) else {
+                continue;
+            };
+            let cell = &mut shard.grid[grid_idx];
+            if value == WATER_TOPOGRAPHY_VALUE {
+                if cell.extra_food_per_tick != WATER_TOPOGRAPHY_VALUE && !is_blank(cell) {
+                    set_blank(cell);
+                    drowned += 1;
+                }
+                cell.food = 0;
+            }
+            cell.extra_food_per_tick = value;
+        }
+
+        Self::apply_sanctuary_mask(shard, sanctuary_mask);
+        shard.topography_version += 1;
+        log!("Shard {} reloaded its topography at tick {} (version {}), {} creatures drowned",
+            shard.shard.to_id(), shard.current_tick, shard.topography_version, drowned);
+        ShardUtils::store_shard(shard);
+        Some(drowned)
+    }
 }
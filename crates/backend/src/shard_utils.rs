@@ -1,123 +1,105 @@
-use std::collections::BTreeMap;
+use std::collections::VecDeque;
 
-use crate::colony_shard::{ColonyShard, is_blank};
-use shared::{be_api::{Cell, ColonyLifeRules, Color, Shard, Traits, UpdatedShardContentsRequest, ShardLayer, StatMetric, ShardStatResult, StatBucket, StringStatBucket}};
+use crate::colony::Colony;
+use crate::colony_shard::{ColonyShard, ShardSeeder, is_blank, WHITE_COLOR};
+use crate::shard_lease::LeaseState;
+use crate::shard_topography::ShardTopography;
+use shared::{be_api::{Cell, ColonyLifeRules, Color, SeedingOptions, Shard, Traits, UpdatedShardContentsRequest, ShardLayer}};
 use shared::log;
+use crate::backend_config::get_determinism_audit_ticks;
+use crate::fast_forward::is_fast_forward;
+use shared::output_paths::OutputPaths;
+use std::path::PathBuf;
+use shared::layer_stats::LayerStats;
+use shared::colony_model::LocalPos;
+use shared::shard_render::{cell_color, ImageBackground};
 use rand::rngs::SmallRng;
 
+/// Grid rows seeded between two progress updates of new_colony_shard_in_chunks
+pub const INIT_ROWS_PER_CHUNK: usize = 64;
+
 pub struct ShardUtils;
 
-impl ShardUtils {
-    #[inline]
-    fn accumulate_counts<F>(shard: &ColonyShard, mapper: F, include_blank_cells: bool) -> Vec<StatBucket>
-    where
-        F: Fn(&Cell) -> i32,
-    {
-        let width = shard.shard.width as usize;
-        let height = shard.shard.height as usize;
-        let row_size = width + 2;
-        let mut counts: BTreeMap<i32, u64> = BTreeMap::new();
-        for row_iter in 1..=height {
-            let start = row_iter * row_size + 1;
-            let end = start + width;
-            for cell in &shard.grid[start..end] {
-                if !include_blank_cells && cell.health == 0 { continue; }
-                let value = mapper(cell);
-                *counts.entry(value).or_insert(0) += 1;
-            }
-        }
-        counts.into_iter().map(|(value, occs)| StatBucket { value, occs }).collect()
-    }
+/// Side of a shard on which a direct neighbor sits
+#[derive(Clone, Copy)]
+enum NeighborSide {
+    Above,
+    Below,
+    Left,
+    Right,
+}
 
-    #[inline]
-    fn accumulate_string_counts<F>(shard: &ColonyShard, mapper: F, include_blank_cells: bool) -> Vec<StringStatBucket>
-    where
-        F: Fn(&Cell) -> String,
-    {
-        let width = shard.shard.width as usize;
-        let height = shard.shard.height as usize;
-        let row_size = width + 2;
-        let mut counts: BTreeMap<String, u64> = BTreeMap::new();
-        for row_iter in 1..=height {
-            let start = row_iter * row_size + 1;
-            let end = start + width;
-            for cell in &shard.grid[start..end] {
-                if !include_blank_cells && cell.health == 0 { continue; }
-                let value = mapper(cell);
-                *counts.entry(value).or_insert(0) += 1;
-            }
-        }
-        counts.into_iter().map(|(value, occs)| StringStatBucket { value, occs }).collect()
-    }
+impl ShardUtils {
     fn copy_cell_creature_data(dst: &mut Cell, src: &Cell, tick_bit: bool) {
         if dst.health > 0 && src.health == 0 { return; } // don't remove creatures from another shard
         dst.color = src.color;
         dst.original_color = src.original_color;
         dst.health = src.health;
         dst.age = src.age;
+        dst.hunger = src.hunger;
         dst.traits = src.traits;
         dst.food = src.food;
         dst.extra_food_per_tick = src.extra_food_per_tick;
         dst.tick_bit = tick_bit;        
     }
 
-    pub fn compute_stats(shard: &ColonyShard, req_shard: &Shard, stats: &[StatMetric]) -> Option<Vec<ShardStatResult>> {
-        if shard.shard.x != req_shard.x || shard.shard.y != req_shard.y || shard.shard.width != req_shard.width || shard.shard.height != req_shard.height {
-            return None;
-        }
+    #[allow(dead_code)] // the backend binary initializes shards in chunks, crate::simulation does not
+    pub fn new_colony_shard(shard: &Shard, colony_life_rules: &ColonyLifeRules, seeding: &SeedingOptions, rng: &mut SmallRng) -> ColonyShard {
+        let mut colony_shard = Self::blank_colony_shard(shard, colony_life_rules);
 
-        let mut metric_buckets: Vec<(StatMetric, Vec<StatBucket>)> = Vec::new();
-        let mut string_metric_buckets: Vec<(StatMetric, Vec<StringStatBucket>)> = Vec::new();
-        
-        for stat in stats.iter().copied() {
-            match stat {
-                StatMetric::Health => {
-                    let buckets = Self::accumulate_counts(shard, |c| c.health as i32, false);
-                    metric_buckets.push((stat, buckets));
-                }
-                StatMetric::Size => {
-                    let buckets = Self::accumulate_counts(shard, |c| c.traits.size as i32, false);
-                    metric_buckets.push((stat, buckets));
-                }
-                StatMetric::CanKill => {
-                    let buckets = Self::accumulate_counts(shard, |c| if c.traits.can_kill { 1 } else { 0 }, false);
-                    metric_buckets.push((stat, buckets));
-                }
-                StatMetric::CanMove => {
-                    let buckets = Self::accumulate_counts(shard, |c| if c.traits.can_move { 1 } else { 0 }, false);
-                    metric_buckets.push((stat, buckets));
-                }
-                StatMetric::Food => {
-                    let buckets = Self::accumulate_counts(shard, |c| c.food as i32, true);
-                    metric_buckets.push((stat, buckets));
-                }
-                StatMetric::Age => {
-                    let buckets = Self::accumulate_counts(shard, |c| c.age as i32, false);
-                    metric_buckets.push((stat, buckets));
-                }
-                StatMetric::OriginalColor => {
-                    let buckets = Self::accumulate_string_counts(shard, |c| {
-                        format!("{}_{}_{}", c.original_color.red, c.original_color.green, c.original_color.blue)
-                    }, false);
-                    string_metric_buckets.push((stat, buckets));
-                }
-            }
+        // State persistence removed - always start with randomized shard
+        log!("Randomizing shard: {} ({:?}, density {})", shard.to_id(), seeding.pattern, seeding.density);
+        colony_shard.randomize_at_start(seeding, rng);
+
+        colony_shard
+    }
+
+    /// Same shard as new_colony_shard, seeded INIT_ROWS_PER_CHUNK rows at a time. The progress
+    /// goes to the colony's init_progress after each chunk, and other tasks get to run between
+    /// chunks so Ping and /health answer while a large shard is generated.
+    pub async fn new_colony_shard_in_chunks(colony: &Colony, shard: &Shard, colony_life_rules: &ColonyLifeRules, seeding: &SeedingOptions, rng: &mut SmallRng) -> ColonyShard {
+        colony.init_progress().set(shard, 0.0);
+        let mut colony_shard = Self::blank_colony_shard(shard, colony_life_rules);
+
+        log!("Randomizing shard: {} ({:?}, density {})", shard.to_id(), seeding.pattern, seeding.density);
+        let seeder = ShardSeeder::new(shard, seeding, rng);
+        let grid_len = colony_shard.grid.len();
+        let chunk_len = (shard.width as usize + 2) * INIT_ROWS_PER_CHUNK;
+        let mut start = 0;
+        while start < grid_len {
+            let end = (start + chunk_len).min(grid_len);
+            seeder.seed_cells(&mut colony_shard, start..end, rng);
+            colony.init_progress().set(shard, end as f32 / grid_len as f32);
+            tokio::task::yield_now().await;
+            start = end;
         }
 
-        Some(vec![ShardStatResult { 
-            shard: shard.shard.clone(), 
-            metrics: metric_buckets,
-            string_metrics: string_metric_buckets,
-        }])
+        colony_shard
     }
 
-    pub fn new_colony_shard(shard: &Shard, colony_life_rules: &ColonyLifeRules, rng: &mut SmallRng) -> ColonyShard {
+    pub fn blank_colony_shard(shard: &Shard, colony_life_rules: &ColonyLifeRules) -> ColonyShard {
         let white_color = Color { red: 255, green: 255, blue: 255 };
-        let mut colony_shard = ColonyShard {
+        ColonyShard {
             shard: shard.clone(),
             colony_life_rules: colony_life_rules.clone(),
             current_tick: 0,
-            grid: (0..((shard.width as usize + 2) * (shard.height as usize + 2))).map(|_| {
+            recent_event_ids: VecDeque::new(),
+            dirty_pixels_journal: VecDeque::new(),
+            frozen: false,
+            awaiting_topography: false,
+            sanctuary: Vec::new(),
+            biomes: Vec::new(),
+            biome_rules: Vec::new(),
+            biome_index: Vec::new(),
+            event_log: VecDeque::new(),
+            topography_version: 0,
+            pending_topography: None,
+            state_hashes: VecDeque::new(),
+            lease: LeaseState::default(),
+            density_cache: None,
+            lock_recoveries: 0,
+            quarantine: None,
+            grid: (0..shard.grid_len()).map(|_| {
                 Cell { 
                     color: white_color, 
                     original_color: white_color,
@@ -126,28 +108,19 @@ impl ShardUtils {
                     extra_food_per_tick: 50,
                     health: 0,
                     age: 1,
+                    hunger: 0,
                     traits: Traits { size: 1, can_kill: true, can_move: true },
                 }
             }).collect(),
-        };
-
-        // State persistence removed - always start with randomized shard
-        log!("Randomizing shard: {}", shard.to_id());
-        colony_shard.randomize_at_start(rng);
-
-        colony_shard
+        }
     }
 
-    pub fn get_shard_image(shard: &ColonyShard, req_shard: &Shard) -> Option<Vec<Color>> {
+    /// Cell colors in row-major order, empty cells drawn as background says
+    pub fn get_shard_image(shard: &ColonyShard, req_shard: &Shard, background: ImageBackground) -> Option<Vec<Color>> {
         if shard.shard.x == req_shard.x && shard.shard.y == req_shard.y && shard.shard.width == req_shard.width && shard.shard.height == req_shard.height {
-            let width = shard.shard.width as usize;
-            let height = shard.shard.height as usize;
-            let row_size = width + 2;
-            let mut image = Vec::with_capacity(width * height);
-            for row_iter in 1..=height {
-                let start = row_iter * row_size + 1;
-                let end = start + width;
-                image.extend(shard.grid[start..end].iter().map(|cell| cell.color));
+            let mut image = Vec::with_capacity((shard.shard.width * shard.shard.height) as usize);
+            for y in 0..shard.shard.height {
+                image.extend(shard.grid[shard.shard.interior_row(y)].iter().map(|cell| cell_color(cell, background)));
             }
             Some(image)
         } else {
@@ -155,15 +128,25 @@ impl ShardUtils {
         }
     }
 
-    pub fn get_shard_layer(shard: &ColonyShard, req_shard: &Shard, layer: &ShardLayer) -> Option<Vec<i32>> {
+    /// The shard image as packed RGB bytes, as served by the image endpoint before compression
+    pub fn get_shard_rgb_image(shard: &ColonyShard, req_shard: &Shard, background: ImageBackground) -> Option<Vec<u8>> {
+        let image = Self::get_shard_image(shard, req_shard, background)?;
+        let mut rgb_bytes = Vec::with_capacity(image.len() * 3);
+        for color in &image {
+            rgb_bytes.push(color.red);
+            rgb_bytes.push(color.green);
+            rgb_bytes.push(color.blue);
+        }
+        Some(rgb_bytes)
+    }
+
+    /// Layer values in row-major order, plus min/max/mean/histogram so clients need not scan them
+    pub fn get_shard_layer(shard: &ColonyShard, req_shard: &Shard, layer: &ShardLayer) -> Option<(Vec<i32>, LayerStats)> {
         if shard.shard.x == req_shard.x && shard.shard.y == req_shard.y && shard.shard.width == req_shard.width && shard.shard.height == req_shard.height {
-            let width = shard.shard.width as usize;
-            let height = shard.shard.height as usize;
-            let row_size = width + 2;
-            let mut data = Vec::with_capacity(width * height);
-            for row_iter in 1..=height {
-                let start = row_iter * row_size + 1;
-                let end = start + width;
+            let mut data = Vec::with_capacity((shard.shard.width * shard.shard.height) as usize);
+            for y in 0..shard.shard.height {
+                let row = shard.shard.interior_row(y);
+                let (start, end) = (row.start, row.end);
                 match layer {
                     ShardLayer::CreatureSize => {
                         data.extend(shard.grid[start..end].iter().map(|cell| if is_blank(cell) { 0 } else { cell.traits.size as i32 }));
@@ -197,11 +180,11 @@ impl ShardUtils {
                         }));
                     }
                     ShardLayer::CostPerTurn => {
-                        data.extend(shard.grid[start..end].iter().map(|cell| {
+                        data.extend(shard.grid[start..end].iter().enumerate().map(|(offset, cell)| {
                             if is_blank(cell) {
                                 0 // blank
                             } else {
-                                ColonyShard::calculate_health_cost_for_cell(cell, &shard.colony_life_rules) as i32
+                                ColonyShard::calculate_health_cost_for_cell(cell, &shard.rules_at(start + offset)) as i32
                             }
                         }));
                     }
@@ -211,56 +194,117 @@ impl ShardUtils {
                     ShardLayer::Health => {
                         data.extend(shard.grid[start..end].iter().map(|cell| cell.health as i32));
                     }
+                    ShardLayer::Sanctuary => {
+                        data.extend((start..end).map(|idx| shard.is_sanctuary(idx) as i32));
+                    }
                 }
             }
-            Some(data)
+            let stats = LayerStats::compute(&data);
+            Some((data, stats))
         } else {
             None
         }
     }
 
     pub fn updated_shard_contents(my_shard: &mut ColonyShard, updated_shard_req: &UpdatedShardContentsRequest) {
-        let my = &my_shard.shard;
-        let other = &updated_shard_req.updated_shard;
-        let width = my.width as usize;
-        let height = my.height as usize;
-        let row_size = width + 2;
+        // A frozen shard keeps its shadow lanes as they were when it froze
+        if my_shard.frozen {
+            return;
+        }
+        let Some(side) = Self::neighbor_side(&my_shard.shard, &updated_shard_req.updated_shard) else {
+            return;
+        };
+        let lane = Self::shadow_lane(&my_shard.shard, side);
         // Use a cell from the grid to get the current tick_bit value
-        let tick_bit = my_shard.grid[width+4].tick_bit;
+        let tick_bit = my_shard.grid[my_shard.shard.width as usize + 4].tick_bit;
 
-        // Check if the other shard is directly above
-        if other.x == my.x && other.y + other.height == my.y && other.width == my.width {
-            // Update top shadow lane (row 0, columns 1..=width) with the bottom border of the above shard
-            let start = 1;
-            for i in 0..width {
-                let idx = start + i;
-                Self::copy_cell_creature_data(&mut my_shard.grid[idx], &updated_shard_req.bottom[i], tick_bit);
+        if updated_shard_req.frozen {
+            for idx in lane {
+                Self::set_wall_cell(&mut my_shard.grid[idx], tick_bit);
             }
+            return;
         }
-        // Check if the other shard is directly below
-        else if other.x == my.x && my.y + my.height == other.y && other.width == my.width {
-            // Update bottom shadow lane (row height+1, columns 1..=width) with the top border of the below shard
-            let start = (height + 1) * row_size + 1;
-            for i in 0..width {
-                let idx = start + i;
-                Self::copy_cell_creature_data(&mut my_shard.grid[idx], &updated_shard_req.top[i], tick_bit);
-            }
+
+        // The facing border of the other shard: above sends its bottom row, left its right column, ...
+        let border = match side {
+            NeighborSide::Above => &updated_shard_req.bottom,
+            NeighborSide::Below => &updated_shard_req.top,
+            NeighborSide::Left => &updated_shard_req.right,
+            NeighborSide::Right => &updated_shard_req.left,
+        };
+        for (idx, cell) in lane.into_iter().zip(border) {
+            Self::copy_cell_creature_data(&mut my_shard.grid[idx], cell, tick_bit);
         }
-        // Check if the other shard is directly to the left
-        else if other.y == my.y && other.x + other.width == my.x && other.height == my.height {
-            // Update left shadow lane (col 0, rows 1..=height) with the right border of the left shard
-            for row in 1..=height {
-                let idx = row * row_size;
-                Self::copy_cell_creature_data(&mut my_shard.grid[idx], &updated_shard_req.right[row - 1], tick_bit);
-            }
+    }
+
+    /// Where other sits relative to my, None when it is not a direct neighbor
+    fn neighbor_side(my: &Shard, other: &Shard) -> Option<NeighborSide> {
+        if other.x == my.x && other.y + other.height == my.y && other.width == my.width {
+            Some(NeighborSide::Above)
+        } else if other.x == my.x && my.y + my.height == other.y && other.width == my.width {
+            Some(NeighborSide::Below)
+        } else if other.y == my.y && other.x + other.width == my.x && other.height == my.height {
+            Some(NeighborSide::Left)
+        } else if other.y == my.y && my.x + my.width == other.x && other.height == my.height {
+            Some(NeighborSide::Right)
+        } else {
+            None
         }
-        // Check if the other shard is directly to the right
-        else if other.y == my.y && my.x + my.width == other.x && other.height == my.height {
-            // Update right shadow lane (col width+1, rows 1..=height) with the left border of the right shard
-            for row in 1..=height {
-                let idx = row * row_size + (width + 1);
-                Self::copy_cell_creature_data(&mut my_shard.grid[idx], &updated_shard_req.left[row - 1], tick_bit);
-            }
+    }
+
+    /// Grid indices of the shadow lane facing side, in border order
+    fn shadow_lane(shard: &Shard, side: NeighborSide) -> Vec<usize> {
+        let lane: Vec<LocalPos> = match side {
+            NeighborSide::Above => (0..shard.width).map(|x| LocalPos::new(x, -1)).collect(),
+            NeighborSide::Below => (0..shard.width).map(|x| LocalPos::new(x, shard.height)).collect(),
+            NeighborSide::Left => (0..shard.height).map(|y| LocalPos::new(-1, y)).collect(),
+            NeighborSide::Right => (0..shard.height).map(|y| LocalPos::new(shard.width, y)).collect(),
+        };
+        lane.into_iter().map(|pos| pos.grid_index(shard).expect("shadow lane outside the shard grid")).collect()
+    }
+
+    /// An empty cell without food: nothing moves into it, and whatever breeds into it is
+    /// wiped by the next update from the frozen neighbor
+    fn set_wall_cell(cell: &mut Cell, tick_bit: bool) {
+        cell.color = WHITE_COLOR;
+        cell.original_color = WHITE_COLOR;
+        cell.health = 0;
+        cell.age = 0;
+        cell.hunger = 0;
+        cell.food = 0;
+        cell.extra_food_per_tick = 0;
+        cell.tick_bit = tick_bit;
+    }
+
+    /// Ticks the shard and exports its borders; a frozen shard, or one still awaiting its
+    /// topography, only announces that it is frozen
+    pub fn tick_and_export(colony_shard: &mut ColonyShard, rng: &mut SmallRng) -> UpdatedShardContentsRequest {
+        // Between ticks, so a tick sees either the old terrain or the new one
+        ShardTopography::apply_pending_topography(colony_shard);
+        if colony_shard.frozen || colony_shard.awaiting_topography {
+            return Self::export_frozen_shard_contents(colony_shard);
+        }
+        if is_fast_forward() {
+            colony_shard.tick_without_journal(rng);
+        } else {
+            colony_shard.tick(rng);
+        }
+        let audit_ticks = get_determinism_audit_ticks();
+        if audit_ticks > 0 {
+            colony_shard.record_state_hash(audit_ticks);
+        }
+        Self::export_shard_contents(colony_shard)
+    }
+
+    pub fn export_frozen_shard_contents(colony_shard: &ColonyShard) -> UpdatedShardContentsRequest {
+        UpdatedShardContentsRequest {
+            updated_shard: colony_shard.shard,
+            top: Vec::new(),
+            bottom: Vec::new(),
+            left: Vec::new(),
+            right: Vec::new(),
+            frozen: true,
+            epoch: colony_shard.lease.epoch,
         }
     }
     
@@ -296,6 +340,8 @@ impl ShardUtils {
             bottom,
             left,
             right,
+            frozen: false,
+            epoch: colony_shard.lease.epoch,
         }
     }
     
@@ -305,13 +351,13 @@ impl ShardUtils {
     }
 
     #[allow(dead_code)]
-    fn get_shard_filename(shard: &Shard) -> String {
-        format!("output/storage/{}.dat", shard.to_id())
+    fn get_shard_filename(instance_id: &str, shard: &Shard) -> PathBuf {
+        OutputPaths::get_instance().shard_snapshots_dir(instance_id).join(format!("{}.dat", shard.to_id()))
     }
 
     #[allow(dead_code)]
-    fn get_shard_temp_filename(shard: &Shard) -> String {
-        format!("{}.tmp", Self::get_shard_filename(shard))
+    fn get_shard_temp_filename(instance_id: &str, shard: &Shard) -> PathBuf {
+        Self::get_shard_filename(instance_id, shard).with_extension("dat.tmp")
     }
 
     pub fn set_shadow_margin_tick_bits(colony_shard: &mut ColonyShard, tick_bit: bool) {
@@ -346,23 +392,7 @@ impl ShardUtils {
     }
 
     pub fn is_adjacent_shard(shard1: &Shard, shard2: &Shard) -> bool {
-        // Check if shard2 is directly above shard1
-        if shard2.x == shard1.x && shard2.y + shard2.height == shard1.y && shard2.width == shard1.width {
-            return true;
-        }
-        // Check if shard2 is directly below shard1
-        if shard2.x == shard1.x && shard1.y + shard1.height == shard2.y && shard2.width == shard1.width {
-            return true;
-        }
-        // Check if shard2 is directly to the left of shard1
-        if shard2.y == shard1.y && shard2.x + shard2.width == shard1.x && shard2.height == shard1.height {
-            return true;
-        }
-        // Check if shard2 is directly to the right of shard1
-        if shard2.y == shard1.y && shard1.x + shard1.width == shard2.x && shard2.height == shard1.height {
-            return true;
-        }
-        false
+        Self::neighbor_side(shard1, shard2).is_some()
     }
 
 } 
\ No newline at end of file
@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+use rand::rngs::SmallRng;
+use shared::be_api::{Color, ColonyLifeRules, SeedingOptions, Shard, ShardStatResult, StatMetric};
+use shared::shard_render::ImageBackground;
+use shared::utils::{new_random_generator, new_seeded_random_generator};
+use crate::be_ticker::exchange_local_borders;
+use crate::colony::Colony;
+use crate::shard_lock::lock_shard;
+use crate::shard_stats::ShardStatsSnapshot;
+use crate::shard_topography::ShardTopography;
+use crate::shard_utils::ShardUtils;
+
+/// What InitColony and the seeding of InitColonyShard carry for a networked colony
+#[derive(Debug, Clone)]
+pub struct SimulationConfig {
+    pub width: i32,
+    pub height: i32,
+    /// With a seed, every shard gets its own RNG derived from it and the run is reproducible
+    pub seeding: SeedingOptions,
+}
+
+/// The simulation as a library: a Colony of its own, ticked in the calling thread with the same
+/// tick and border phases as the backend ticker, without RPC, HTTP or the process-wide Colony.
+/// See examples/embedded_simulation.rs.
+pub struct SimulationHandle {
+    colony: Colony,
+    seeding: SeedingOptions,
+    rngs: HashMap<Shard, SmallRng>,
+}
+
+impl SimulationHandle {
+    pub fn init(config: SimulationConfig) -> Result<Self, String> {
+        if config.width <= 0 || config.height <= 0 {
+            return Err(format!("Invalid colony size {}x{}", config.width, config.height));
+        }
+        config.seeding.validate()?;
+        Ok(Self { colony: Colony::new(config.width, config.height), seeding: config.seeding, rngs: HashMap::new() })
+    }
+
+    /// Seeds a shard like InitColonyShard does; with topography, the shard's food growth comes from it
+    pub fn add_shard(&mut self, shard: Shard, rules: ColonyLifeRules, topography: Option<&[u8]>) -> Result<(), String> {
+        if !self.colony.is_valid_shard_dimensions(&shard) {
+            return Err(format!("Shard {} is outside the {}x{} colony", shard.to_id(), self.colony.width(), self.colony.height()));
+        }
+        if self.colony.is_hosting_shard(shard) {
+            return Err(format!("Shard {} was already added", shard.to_id()));
+        }
+        rules.validate()?;
+        let mut rng = match self.seeding.shard_seed(&shard) {
+            Some(seed) => new_seeded_random_generator(seed),
+            None => new_random_generator(),
+        };
+        let mut colony_shard = ShardUtils::new_colony_shard(&shard, &rules, &self.seeding, &mut rng);
+        if let Some(topography_data) = topography {
+            ShardTopography::init_shard_topography_from_data(&mut colony_shard, topography_data)?;
+        }
+        self.colony.add_hosted_shard(colony_shard);
+        self.rngs.insert(shard, rng);
+        Ok(())
+    }
+
+    /// Runs count ticks of every shard; shards tick in row-major order, each with its own RNG
+    pub fn tick(&mut self, count: u32) {
+        let shards = self.shards();
+        for _ in 0..count {
+            let exported: Vec<_> = shards.iter()
+                .filter_map(|shard| {
+                    let shard_arc = self.colony.get_hosted_colony_shard_arc(shard)?;
+                    let rng = self.rngs.get_mut(shard)?;
+                    let mut colony_shard = lock_shard(&shard_arc);
+                    Some(ShardUtils::tick_and_export(&mut colony_shard, rng))
+                })
+                .collect();
+            exchange_local_borders(&self.colony, &exported);
+        }
+    }
+
+    /// The added shards in row-major order
+    pub fn shards(&self) -> Vec<Shard> {
+        let (mut shards, _) = self.colony.get_hosted_shards();
+        shards.sort_by_key(|shard| (shard.y, shard.x));
+        shards
+    }
+
+    pub fn current_tick(&self, shard: &Shard) -> Option<u64> {
+        let shard_arc = self.colony.get_hosted_colony_shard_arc(shard)?;
+        let tick = lock_shard(&shard_arc).get_current_tick();
+        Some(tick)
+    }
+
+    /// The shard's histograms, as GetShardStats returns them
+    pub fn stats(&self, shard: &Shard, metrics: &[StatMetric]) -> Option<ShardStatResult> {
+        let shard_arc = self.colony.get_hosted_colony_shard_arc(shard)?;
+        let snapshot = ShardStatsSnapshot::capture(&lock_shard(&shard_arc), shard, None)?;
+        snapshot.compute_stats(metrics).into_iter().next()
+    }
+
+    /// Cell colors of the shard in row-major order, as the shard image endpoint renders them
+    pub fn image(&self, shard: &Shard) -> Option<Vec<Color>> {
+        let shard_arc = self.colony.get_hosted_colony_shard_arc(shard)?;
+        let image = ShardUtils::get_shard_image(&lock_shard(&shard_arc), shard, ImageBackground::Plain);
+        image
+    }
+
+    /// The underlying colony, for direct access to its ColonyShards
+    pub fn colony(&self) -> &Colony {
+        &self.colony
+    }
+}
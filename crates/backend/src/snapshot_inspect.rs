@@ -0,0 +1,150 @@
+//! colony-inspect: looks into a shard snapshot written by ShardStorage without a running backend.
+//! Everything is here rather than in inspect_main.rs, so tests drive the commands directly.
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use shared::be_api::{GlobalPos, ShardLayer};
+use shared::colony_model::LocalPos;
+use shared::shard_render::ImageBackground;
+use crate::colony_shard::is_blank;
+use crate::http_server::layer_name_to_enum;
+use crate::shard_storage::ShardSnapshot;
+use crate::shard_utils::ShardUtils;
+
+pub const USAGE: &str = "Usage: colony-inspect <snapshot.dat> <command>
+Commands:
+  info                          tick, dimensions, rules and creature count
+  render --out <file.png>       the creatures image
+  layer <name> [--out <file>]   a layer as CSV, one line per row; stdout without --out
+                                (creature-size, extra-food, can-kill, can-move, cost-per-turn, food, health, age, sanctuary)
+  cell <x> <y>                  the full record of the cell at colony coordinates x,y";
+
+#[derive(Debug)]
+pub enum InspectCommand {
+    Info,
+    Render { out: PathBuf },
+    Layer { layer: ShardLayer, out: Option<PathBuf> },
+    Cell { pos: GlobalPos },
+}
+
+/// Splits the arguments after the program name into the snapshot path and the command
+pub fn parse_args(args: &[String]) -> Result<(String, InspectCommand), String> {
+    let [snapshot, command, rest @ ..] = args else {
+        return Err("Expected a snapshot file and a command".to_string());
+    };
+    let out = || match rest.iter().position(|arg| arg == "--out") {
+        Some(idx) => rest.get(idx + 1).map(|path| Some(PathBuf::from(path))).ok_or_else(|| "--out needs a file".to_string()),
+        None => Ok(None),
+    };
+    let command = match command.as_str() {
+        "info" => InspectCommand::Info,
+        "render" => InspectCommand::Render { out: out()?.ok_or("render needs --out <file.png>")? },
+        "layer" => {
+            let name = rest.first().filter(|name| !name.starts_with("--")).ok_or("layer needs a layer name")?;
+            InspectCommand::Layer { layer: layer_name_to_enum(name)?, out: out()? }
+        }
+        "cell" => {
+            let [x, y, ..] = rest else {
+                return Err("cell needs x and y".to_string());
+            };
+            let parse = |value: &String| value.parse::<i32>().map_err(|_| format!("'{}' is not a whole number", value));
+            InspectCommand::Cell { pos: GlobalPos::new(parse(x)?, parse(y)?) }
+        }
+        other => return Err(format!("Unknown command '{}'", other)),
+    };
+    Ok((snapshot.clone(), command))
+}
+
+/// Runs a command against a loaded snapshot; text goes to out, files to the paths in the command
+pub fn run(snapshot: &ShardSnapshot, command: &InspectCommand, out: &mut dyn Write) -> Result<(), String> {
+    match command {
+        InspectCommand::Info => write_info(snapshot, out),
+        InspectCommand::Render { out: path } => render_png(snapshot, path),
+        InspectCommand::Layer { layer, out: Some(path) } => {
+            let mut file = std::fs::File::create(path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+            write_layer_csv(snapshot, layer, &mut file)
+        }
+        InspectCommand::Layer { layer, out: None } => write_layer_csv(snapshot, layer, out),
+        InspectCommand::Cell { pos } => write_cell(snapshot, *pos, out),
+    }
+}
+
+fn io_error(e: std::io::Error) -> String {
+    format!("Failed to write output: {}", e)
+}
+
+/// Creatures among the interior cells; the shadow margin holds the neighbors' cells
+pub fn creature_count(snapshot: &ShardSnapshot) -> usize {
+    let shard = &snapshot.shard;
+    shard.grid.iter().enumerate()
+        .filter(|(idx, cell)| LocalPos::from_grid_index(*idx, &shard.shard).is_interior(&shard.shard) && !is_blank(cell))
+        .count()
+}
+
+fn write_info(snapshot: &ShardSnapshot, out: &mut dyn Write) -> Result<(), String> {
+    let shard = &snapshot.shard;
+    let rules = serde_json::to_string_pretty(&shard.colony_life_rules).map_err(|e| e.to_string())?;
+    writeln!(out, "Format version: {}", snapshot.format_version).map_err(io_error)?;
+    writeln!(out, "Shard: {} ({}x{} at {},{})", shard.shard.to_id(), shard.shard.width, shard.shard.height, shard.shard.x, shard.shard.y).map_err(io_error)?;
+    writeln!(out, "Tick: {}", shard.current_tick).map_err(io_error)?;
+    writeln!(out, "Creatures: {} of {} cells", creature_count(snapshot), shard.shard.width * shard.shard.height).map_err(io_error)?;
+    writeln!(out, "Frozen: {}", shard.frozen).map_err(io_error)?;
+    writeln!(out, "Biomes: {}", shard.biomes.iter().map(|biome| biome.name.as_str()).collect::<Vec<_>>().join(", ")).map_err(io_error)?;
+    writeln!(out, "Events logged: {}", shard.event_log.len()).map_err(io_error)?;
+    write_topography_summary(snapshot, out)?;
+    if let Some(stored) = &snapshot.rules {
+        let matches = if stored.biome_rules == shard.biome_rules { "matches this build" } else { "differs from this build" };
+        writeln!(out, "Biome rules: {} stored, {}", stored.biome_rules.len(), matches).map_err(io_error)?;
+    }
+    writeln!(out, "Rules: {}", rules).map_err(io_error)
+}
+
+/// Extra food range over the interior cells, water being the cells without any
+fn write_topography_summary(snapshot: &ShardSnapshot, out: &mut dyn Write) -> Result<(), String> {
+    let shard = &snapshot.shard;
+    let interior: Vec<usize> = (0..shard.grid.len())
+        .filter(|idx| LocalPos::from_grid_index(*idx, &shard.shard).is_interior(&shard.shard))
+        .collect();
+    let extra_food = || interior.iter().map(|&idx| shard.grid[idx].extra_food_per_tick);
+    let (min, max) = (extra_food().min().unwrap_or(0), extra_food().max().unwrap_or(0));
+    let mean = extra_food().map(|food| food as f64).sum::<f64>() / interior.len().max(1) as f64;
+    let water = extra_food().filter(|food| *food == 0).count();
+    let sanctuary = interior.iter().filter(|&&idx| shard.is_sanctuary(idx)).count();
+    writeln!(out, "Topography: version {}, extra food {}..{} (mean {:.1}), {} water cells, {} sanctuary cells, reload {}",
+        snapshot.topography.version, min, max, mean, water, sanctuary,
+        if snapshot.topography.pending.is_some() { "pending" } else { "none" }).map_err(io_error)
+}
+
+fn render_png(snapshot: &ShardSnapshot, path: &Path) -> Result<(), String> {
+    let shard = &snapshot.shard;
+    let colors = ShardUtils::get_shard_image(shard, &shard.shard, ImageBackground::Plain).ok_or("The snapshot holds no image")?;
+    let width = shard.shard.width as u32;
+    let image = image::RgbImage::from_fn(width, shard.shard.height as u32, |x, y| {
+        let color = colors[(y * width + x) as usize];
+        image::Rgb([color.red, color.green, color.blue])
+    });
+    image.save(path).map_err(|e| format!("Failed to save {}: {}", path.display(), e))
+}
+
+fn write_layer_csv(snapshot: &ShardSnapshot, layer: &ShardLayer, out: &mut dyn Write) -> Result<(), String> {
+    let shard = &snapshot.shard;
+    let (values, _) = ShardUtils::get_shard_layer(shard, &shard.shard, layer).ok_or("The snapshot holds no layer data")?;
+    for row in values.chunks(shard.shard.width as usize) {
+        let line: Vec<String> = row.iter().map(|value| value.to_string()).collect();
+        writeln!(out, "{}", line.join(",")).map_err(io_error)?;
+    }
+    Ok(())
+}
+
+fn write_cell(snapshot: &ShardSnapshot, pos: GlobalPos, out: &mut dyn Write) -> Result<(), String> {
+    let shard = &snapshot.shard;
+    let local = pos.to_local(&shard.shard);
+    let idx = Some(local).filter(|local| local.is_interior(&shard.shard))
+        .and_then(|local| local.grid_index(&shard.shard))
+        .ok_or_else(|| format!("{},{} is outside shard {}", pos.x, pos.y, shard.shard.to_id()))?;
+    let cell = &shard.grid[idx];
+    let record = serde_json::to_string_pretty(cell).map_err(|e| e.to_string())?;
+    writeln!(out, "Cell {},{} (local {},{}) of shard {}", pos.x, pos.y, local.x, local.y, shard.shard.to_id()).map_err(io_error)?;
+    writeln!(out, "Creature: {}", if is_blank(cell) { "none" } else { "alive" }).map_err(io_error)?;
+    writeln!(out, "Sanctuary: {}", shard.is_sanctuary(idx)).map_err(io_error)?;
+    writeln!(out, "{}", record).map_err(io_error)
+}
@@ -0,0 +1,90 @@
+use shared::cluster_topology::{ClusterTopology, HostInfo};
+use shared::log;
+use std::sync::Mutex;
+use crate::backend_config::{get_backend_hostname, get_backend_port};
+use crate::border_validation::record_border_sources;
+use crate::colony::Colony;
+
+/// Colony instance of the coordinator that sent the stored topology, when it said
+static COLONY_INSTANCE_ID: Mutex<Option<String>> = Mutex::new(None);
+
+pub fn colony_instance_id() -> Option<String> {
+    COLONY_INSTANCE_ID.lock().unwrap().clone()
+}
+
+pub fn set_colony_instance_id(colony_instance_id: Option<String>) {
+    if colony_instance_id.is_some() {
+        *COLONY_INSTANCE_ID.lock().unwrap() = colony_instance_id;
+    }
+}
+
+fn normalize_host(host: &HostInfo) -> HostInfo {
+    if host.hostname == "0.0.0.0" {
+        HostInfo::new("127.0.0.1".to_string(), host.port)
+    } else {
+        host.clone()
+    }
+}
+
+/// This backend as the topology names it
+pub fn this_backend_host() -> HostInfo {
+    normalize_host(&HostInfo::new(get_backend_hostname().to_string(), get_backend_port()))
+}
+
+/// Whether this backend's host info is one of the topology's backend hosts
+pub fn topology_includes_this_backend(topology: &ClusterTopology) -> bool {
+    let this_backend_host = this_backend_host();
+    topology.backend_hosts.iter().any(|host| normalize_host(host) == this_backend_host)
+}
+
+/// Decides whether `incoming` may replace `stored`. Ok(false) when the coordinator is the same,
+/// Ok(true) when a new coordinator may take over, Err with the reason when it may not.
+pub fn check_coordinator_change(
+    stored: &ClusterTopology,
+    stored_instance_id: Option<&str>,
+    incoming: &ClusterTopology,
+    incoming_instance_id: Option<&str>,
+    hosted_shards: usize,
+) -> Result<bool, String> {
+    if stored.coordinator_host == incoming.coordinator_host {
+        return Ok(false);
+    }
+    let same_colony = matches!((stored_instance_id, incoming_instance_id), (Some(stored), Some(incoming)) if stored == incoming);
+    if same_colony || hosted_shards == 0 {
+        return Ok(true);
+    }
+    Err(format!(
+        "topology from coordinator {} (colony {}) conflicts with coordinator {} (colony {}) while hosting {} shards",
+        incoming.coordinator_host.to_address(),
+        incoming_instance_id.unwrap_or("unknown"),
+        stored.coordinator_host.to_address(),
+        stored_instance_id.unwrap_or("unknown"),
+        hosted_shards,
+    ))
+}
+
+/// Replaces the stored topology with one from a new coordinator when check_coordinator_change
+/// allows it. Returns whether it was replaced.
+pub fn refresh_topology(incoming: ClusterTopology, incoming_instance_id: Option<String>) -> Result<bool, String> {
+    let stored = ClusterTopology::get_instance().ok_or_else(|| "topology not initialized".to_string())?;
+    let hosted_shards = if Colony::is_initialized() {
+        Colony::instance().get_hosted_shards().0.len()
+    } else {
+        0
+    };
+    let stored_instance_id = colony_instance_id();
+    if !check_coordinator_change(&stored, stored_instance_id.as_deref(), &incoming, incoming_instance_id.as_deref(), hosted_shards)? {
+        return Ok(false);
+    }
+    if !topology_includes_this_backend(&incoming) {
+        return Err(format!("backend {}:{} is not in the topology from coordinator {}",
+            get_backend_hostname(), get_backend_port(), incoming.coordinator_host.to_address()));
+    }
+
+    let new_coordinator = incoming.coordinator_host.to_address();
+    ClusterTopology::replace(incoming).map_err(|e| e.to_string())?;
+    set_colony_instance_id(incoming_instance_id);
+    record_border_sources();
+    log!("Topology replaced: coordinator {} -> {}", stored.coordinator_host.to_address(), new_coordinator);
+    Ok(true)
+}
@@ -0,0 +1,69 @@
+//! Follows the topology the coordinator pushes on a SubscribeTopology connection, so border
+//! updates go to a reassigned shard's new owner from the next tick on.
+use shared::backend_communication::{connect_with_handshake_async, receive_response_async, send_request_async};
+use shared::cluster_topology::{ClusterTopology, HostInfo};
+use shared::coordinator_api::{CoordinatorRequest, CoordinatorResponse};
+use shared::supervisor::spawn_supervised;
+use shared::{log, log_error};
+use std::time::Duration;
+use crate::border_validation::record_border_sources;
+use crate::colony::Colony;
+use crate::topology_refresh::this_backend_host;
+
+/// Wait before subscribing again after the connection to the coordinator ended
+const TOPOLOGY_RESUBSCRIBE_DELAY: Duration = Duration::from_secs(5);
+
+/// Swaps in a topology pushed by the stored topology's coordinator. Ok(false) when the shard
+/// assignment did not change. A topology from another coordinator goes through RefreshTopology,
+/// and one for other colony dimensions waits for UpdateTopology to resize the colony.
+pub fn apply_topology_push(incoming: ClusterTopology) -> Result<bool, String> {
+    let stored = ClusterTopology::get_instance().ok_or_else(|| "topology not initialized".to_string())?;
+    if stored.coordinator_host != incoming.coordinator_host {
+        return Err(format!("topology pushed by {} but the coordinator is {}",
+            incoming.coordinator_host.to_address(), stored.coordinator_host.to_address()));
+    }
+    if stored.shard_to_host == incoming.shard_to_host && stored.backend_hosts == incoming.backend_hosts {
+        return Ok(false);
+    }
+    if Colony::is_initialized() {
+        let colony = Colony::instance();
+        Colony::validate_topology_dimensions(colony.width(), colony.height(), &incoming)?;
+    }
+    // The ticker reads the topology once per tick, so a tick in progress finishes on the old one
+    ClusterTopology::replace(incoming).map_err(|e| e.to_string())?;
+    record_border_sources();
+    Ok(true)
+}
+
+/// Subscribes with coordinator and applies its pushes until the connection ends
+async fn follow_topology(coordinator: &HostInfo) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let mut stream = connect_with_handshake_async(&coordinator.to_address()).await?;
+    send_request_async(&mut stream, &CoordinatorRequest::SubscribeTopology { backend: this_backend_host() }).await?;
+    log!("Subscribed to topology updates from {}", coordinator.to_address());
+    loop {
+        match receive_response_async::<CoordinatorResponse>(&mut stream).await? {
+            CoordinatorResponse::TopologyUpdate { topology } => match apply_topology_push(topology) {
+                Ok(true) => log!("Topology swapped after a push from {}", coordinator.to_address()),
+                Ok(false) => {}
+                Err(e) => log_error!("Ignoring topology push: {}", e),
+            },
+            _ => return Err("unexpected response on a topology subscription".into()),
+        }
+    }
+}
+
+/// Keeps a topology subscription open with the coordinator of the stored topology. A dropped
+/// subscription is renewed, and the coordinator starts it with the full table.
+pub fn start_topology_subscription() {
+    spawn_supervised("topology-subscription", || async {
+        loop {
+            if let Some(topology) = ClusterTopology::get_instance() {
+                let coordinator = topology.coordinator_host.clone();
+                if let Err(e) = follow_topology(&coordinator).await {
+                    log_error!("Topology subscription with {} ended: {}", coordinator.to_address(), e);
+                }
+            }
+            tokio::time::sleep(TOPOLOGY_RESUBSCRIBE_DELAY).await;
+        }
+    });
+}
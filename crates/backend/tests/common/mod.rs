@@ -0,0 +1,99 @@
+//! Fixtures shared by the backend integration tests
+use backend::backend_config;
+use backend::be_server::dispatch_request;
+use backend::colony_shard::ColonyShard;
+use backend::presentation_snapshots::SnapshotConfig;
+use backend::rate_limiter::RateLimitConfig;
+use shared::be_api::{BackendRequest, ColonyLifeRules, InitColonyRequest, InitColonyShardRequest, SeedingOptions, Shard, COLONY_LIFE_INITIAL_RULES, COLONY_LIFE_RULE_RANGES};
+use shared::cluster_topology::{ClusterTopology, HostInfo};
+use shared::output_paths::OUTPUT_DIR_ENV;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The initial rules with colors that never drift or mutate
+pub const RULES: ColonyLifeRules = ColonyLifeRules {
+    color_drift_per_generation: 0,
+    color_mutation_chance: 0,
+    ..COLONY_LIFE_INITIAL_RULES
+};
+
+/// Points COLONY_OUTPUT_DIR at a new temp dir, so the HTTP server a test starts writes its
+/// access log there rather than into the crate. Call it before anything reads OutputPaths.
+#[allow(dead_code)]
+pub fn use_temp_output_dir() {
+    static OUTPUT_DIR: OnceLock<PathBuf> = OnceLock::new();
+    OUTPUT_DIR.get_or_init(|| {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).expect("Clock before epoch").as_nanos();
+        let dir = std::env::temp_dir().join(format!("colony_output_{}_{}", std::process::id(), nanos));
+        std::env::set_var(OUTPUT_DIR_ENV, &dir);
+        dir
+    });
+}
+
+/// Makes this process a rate-unlimited backend on backend_port hosting the shards, which tile a
+/// colony from 0,0, all seeded with RULES; the coordinator in the topology is on backend_port + 1
+#[allow(dead_code)]
+pub async fn init_colony(backend_port: u16, shards: &[Shard], snapshot_config: SnapshotConfig) {
+    let this_backend = HostInfo::new("127.0.0.1".to_string(), backend_port);
+    backend_config::set_backend_hostname(this_backend.hostname.clone());
+    backend_config::set_backend_port(this_backend.port);
+    backend_config::set_rate_limit_config(RateLimitConfig { enabled: false, ..RateLimitConfig::default() });
+    backend_config::set_snapshot_config(snapshot_config);
+
+    let topology = ClusterTopology {
+        coordinator_host: HostInfo::new("127.0.0.1".to_string(), backend_port + 1),
+        backend_hosts: vec![this_backend.clone()],
+        shard_to_host: shards.iter().map(|shard| (*shard, this_backend.clone())).collect::<HashMap<_, _>>(),
+    };
+    let width = shards.iter().map(|shard| shard.x + shard.width).max().unwrap_or(0);
+    let height = shards.iter().map(|shard| shard.y + shard.height).max().unwrap_or(0);
+    dispatch_request(BackendRequest::InitColony(InitColonyRequest { width, height, colony_life_rules: RULES })).await;
+    for shard in shards {
+        dispatch_request(BackendRequest::InitColonyShard(InitColonyShardRequest {
+            shard: *shard,
+            colony_life_rules: RULES,
+            topology: Some(topology.clone()),
+            seeding: SeedingOptions::default(),
+            topography_data: None,
+            awaiting_topography: false,
+            colony_instance_id: None,
+            lease_epoch: 1,
+        })).await;
+    }
+}
+
+#[allow(dead_code)]
+fn legacy_rules(rules: &ColonyLifeRules) -> [u32; 16] {
+    std::array::from_fn(|idx| rules.field_values()[idx].1)
+}
+
+/// The shard as snapshot format versions 0 to 2 laid it out: cells without the hunger counter
+/// and rules of the first 16 fields only
+#[allow(dead_code)]
+pub fn legacy_shard_body(shard: &ColonyShard) -> Vec<u8> {
+    let grid: Vec<_> = shard.grid.iter()
+        .map(|cell| (cell.tick_bit, cell.food, cell.extra_food_per_tick, cell.color, cell.original_color, cell.health, cell.age, cell.traits))
+        .collect();
+    let biomes: Vec<_> = shard.biomes.iter()
+        .map(|biome| {
+            let overridden = biome.overrides.overridden_values();
+            let overrides: [Option<u32>; 16] = std::array::from_fn(|idx| {
+                overridden.iter().find(|(field, _)| *field == COLONY_LIFE_RULE_RANGES[idx].field).map(|(_, value)| *value)
+            });
+            (&biome.name, biome.x, biome.y, biome.width, biome.height, overrides)
+        })
+        .collect();
+    bincode::serialize(&(
+        shard.shard, legacy_rules(&shard.colony_life_rules), grid, shard.current_tick, &shard.recent_event_ids,
+        &shard.dirty_pixels_journal, shard.frozen, shard.awaiting_topography, &shard.sanctuary, biomes,
+        &shard.event_log, shard.topography_version,
+    )).unwrap()
+}
+
+/// The rules as format version 2 stored them next to the shard
+#[allow(dead_code)]
+pub fn legacy_rules_body(shard: &ColonyShard) -> Vec<u8> {
+    bincode::serialize(&(legacy_rules(&shard.colony_life_rules), shard.biome_rules.iter().map(legacy_rules).collect::<Vec<_>>())).unwrap()
+}
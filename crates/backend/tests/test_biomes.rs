@@ -0,0 +1,128 @@
+mod common;
+
+use backend::be_colony_events::apply_event_to_shards;
+use backend::colony_shard::ColonyShard;
+use backend::shard_utils::ShardUtils;
+use shared::be_api::{Biome, ColonyLifeRules, Color, SeedingOptions, Shard, Traits};
+use shared::colony_events::{BiomeChange, ColonyEvent, ColonyRuleChange};
+use shared::colony_model::ColonyLifeRulesOverride;
+use shared::utils::new_seeded_random_generator;
+use std::sync::{Arc, Mutex};
+
+const SHARD_SIZE: i32 = 8;
+
+/// Random death never happens and nothing breeds
+const RULES: ColonyLifeRules = ColonyLifeRules {
+    random_death_chance: 1_000_000,
+    reproduction_min_food: 10_000,
+    ..common::RULES
+};
+
+fn grid_idx(x: i32, y: i32) -> usize {
+    ((y + 1) * (SHARD_SIZE + 2) + x + 1) as usize
+}
+
+/// Every creature in the biome dies at random on its first tick
+fn deadly_biome(x: i32, y: i32, width: i32, height: i32) -> Biome {
+    Biome {
+        name: "deadly".to_string(),
+        x,
+        y,
+        width,
+        height,
+        overrides: ColonyLifeRulesOverride { random_death_chance: Some(1), ..Default::default() },
+    }
+}
+
+/// SHARD_SIZE shard at (x, y) with a creature on every interior cell
+fn populated_shard(x: i32, y: i32) -> ColonyShard {
+    let shard = Shard { x, y, width: SHARD_SIZE, height: SHARD_SIZE };
+    let mut colony_shard = ShardUtils::new_colony_shard(&shard, &RULES, &SeedingOptions::default(), &mut new_seeded_random_generator(1));
+    for cell in colony_shard.grid.iter_mut() {
+        cell.health = 0;
+    }
+    let color = Color { red: 200, green: 0, blue: 0 };
+    for y in 0..SHARD_SIZE {
+        for x in 0..SHARD_SIZE {
+            let cell = &mut colony_shard.grid[grid_idx(x, y)];
+            cell.health = 500;
+            cell.color = color;
+            cell.original_color = color;
+            cell.traits = Traits { size: 5, can_kill: false, can_move: false };
+        }
+    }
+    colony_shard
+}
+
+#[test]
+fn test_north_biome_rules_apply_only_to_its_cells() {
+    let mut colony_shard = populated_shard(0, 0);
+    colony_shard.set_biomes(&[deadly_biome(0, 0, SHARD_SIZE, SHARD_SIZE / 2)]);
+    colony_shard.tick(&mut new_seeded_random_generator(2));
+
+    for y in 0..SHARD_SIZE {
+        for x in 0..SHARD_SIZE {
+            let alive = colony_shard.grid[grid_idx(x, y)].health > 0;
+            assert_eq!(alive, y >= SHARD_SIZE / 2, "creature at ({}, {})", x, y);
+        }
+    }
+}
+
+#[test]
+fn test_biomes_are_clipped_to_the_shard() {
+    // The biome covers the right half of the left shard and the left half of the right one
+    let biome = deadly_biome(SHARD_SIZE / 2, 0, SHARD_SIZE, SHARD_SIZE);
+    let mut left = populated_shard(0, 0);
+    let mut right = populated_shard(SHARD_SIZE, 0);
+    let mut far = populated_shard(0, SHARD_SIZE);
+    for colony_shard in [&mut left, &mut right, &mut far] {
+        colony_shard.set_biomes(std::slice::from_ref(&biome));
+    }
+
+    assert_eq!((left.biomes[0].x, left.biomes[0].width), (SHARD_SIZE / 2, SHARD_SIZE / 2));
+    assert_eq!((right.biomes[0].x, right.biomes[0].width), (SHARD_SIZE, SHARD_SIZE / 2));
+    assert!(far.biomes.is_empty());
+    assert!(far.biome_index.is_empty());
+
+    assert_eq!(left.rules_at(grid_idx(SHARD_SIZE / 2 - 1, 0)).random_death_chance, RULES.random_death_chance);
+    assert_eq!(left.rules_at(grid_idx(SHARD_SIZE / 2, 0)).random_death_chance, 1);
+    assert_eq!(right.rules_at(grid_idx(SHARD_SIZE / 2 - 1, 0)).random_death_chance, 1);
+    assert_eq!(right.rules_at(grid_idx(SHARD_SIZE / 2, 0)).random_death_chance, RULES.random_death_chance);
+    // The shadow margin belongs to the neighbor and keeps the base rules
+    assert_eq!(left.rules_at(0).random_death_chance, RULES.random_death_chance);
+}
+
+#[test]
+fn test_later_biome_wins_where_biomes_overlap() {
+    let mut colony_shard = populated_shard(0, 0);
+    let mild = Biome {
+        name: "mild".to_string(),
+        overrides: ColonyLifeRulesOverride { random_death_chance: Some(500), ..Default::default() },
+        ..deadly_biome(0, 0, 2, 2)
+    };
+    colony_shard.set_biomes(&[deadly_biome(0, 0, SHARD_SIZE, SHARD_SIZE), mild]);
+
+    assert_eq!(colony_shard.rules_at(grid_idx(1, 1)).random_death_chance, 500);
+    assert_eq!(colony_shard.rules_at(grid_idx(2, 2)).random_death_chance, 1);
+}
+
+#[test]
+fn test_biome_events_and_rule_changes_reach_the_shards() {
+    let shards = vec![Arc::new(Mutex::new(populated_shard(0, 0)))];
+    let mut rng = new_seeded_random_generator(5);
+    let event = ColonyEvent::ChangeBiomes(BiomeChange {
+        biomes: vec![deadly_biome(0, 0, SHARD_SIZE, 2)],
+        description: "test".to_string(),
+    });
+    let effects = apply_event_to_shards(&mut rng, &shards, &event);
+    assert_eq!(effects[0].cells_affected, (SHARD_SIZE * 2) as u64);
+    assert_eq!(shards[0].lock().unwrap().rules_at(grid_idx(0, 1)).random_death_chance, 1);
+    assert_eq!(shards[0].lock().unwrap().rules_at(grid_idx(0, 2)).random_death_chance, RULES.random_death_chance);
+
+    // A base rule change carries over into the biome, except for the overridden fields
+    let new_rules = ColonyLifeRules { mutation_chance: 7, random_death_chance: 900, ..RULES };
+    let event = ColonyEvent::ChangeColonyRules(ColonyRuleChange { new_rules, description: "test".to_string() });
+    apply_event_to_shards(&mut rng, &shards, &event);
+    let biome_rules = shards[0].lock().unwrap().rules_at(grid_idx(0, 0));
+    assert_eq!((biome_rules.mutation_chance, biome_rules.random_death_chance), (7, 1));
+}
@@ -0,0 +1,127 @@
+use backend::border_outbox::BorderOutbox;
+use shared::be_api::{Cell, Color, Shard, Traits, UpdatedShardContentsRequest};
+use shared::cluster_topology::HostInfo;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const TICK: Duration = Duration::from_millis(25);
+const SHARD_A: Shard = Shard { x: 0, y: 0, width: 10, height: 10 };
+const SHARD_B: Shard = Shard { x: 10, y: 0, width: 10, height: 10 };
+
+/// Neighbor backend that takes updates only while it is up
+#[derive(Clone, Default)]
+struct FakeNeighbor {
+    up: Arc<AtomicBool>,
+    attempts: Arc<AtomicU32>,
+    received: Arc<Mutex<Vec<(Shard, u16)>>>,
+}
+
+impl FakeNeighbor {
+    fn set_up(&self, up: bool) {
+        self.up.store(up, Ordering::SeqCst);
+    }
+
+    fn take_received(&self) -> Vec<(Shard, u16)> {
+        std::mem::take(&mut self.received.lock().unwrap())
+    }
+
+    async fn deliver(&self, outbox: &BorderOutbox, shard: Shard, tick: u16, now: Instant) {
+        let neighbor = self.clone();
+        outbox.deliver(&host(), border(shard, tick), now, move |req| {
+            let neighbor = neighbor.clone();
+            async move {
+                neighbor.attempts.fetch_add(1, Ordering::SeqCst);
+                if !neighbor.up.load(Ordering::SeqCst) {
+                    return Err("Connection refused".to_string());
+                }
+                neighbor.received.lock().unwrap().push((req.updated_shard, req.top[0].age));
+                Ok(())
+            }
+        }).await;
+    }
+}
+
+fn host() -> HostInfo {
+    HostInfo::new("127.0.0.1".to_string(), 9999)
+}
+
+/// Border update of shard whose cells carry tick as their age
+fn border(shard: Shard, tick: u16) -> UpdatedShardContentsRequest {
+    let white = Color { red: 255, green: 255, blue: 255 };
+    let cell = Cell {
+        tick_bit: false,
+        food: 0,
+        extra_food_per_tick: 0,
+        color: white,
+        original_color: white,
+        health: 0,
+        age: tick,
+        hunger: 0,
+        traits: Traits { size: 1, can_kill: false, can_move: false },
+    };
+    let side = vec![cell; shard.width as usize];
+    UpdatedShardContentsRequest { updated_shard: shard, top: side.clone(), bottom: side.clone(), left: side.clone(), right: side, frozen: false, epoch: 1 }
+}
+
+#[tokio::test]
+async fn test_outage_delivers_the_latest_border_once_on_recovery() {
+    let outbox = BorderOutbox::new();
+    let neighbor = FakeNeighbor::default();
+    let start = Instant::now();
+    let at = |tick: u16| start + TICK * tick as u32;
+
+    neighbor.set_up(true);
+    neighbor.deliver(&outbox, SHARD_A, 1, at(1)).await;
+    assert_eq!(neighbor.take_received(), vec![(SHARD_A, 1)]);
+
+    // A 100 tick outage: the first failed send marks the neighbor unreachable, later ones are held
+    neighbor.set_up(false);
+    let attempts_before = neighbor.attempts.load(Ordering::SeqCst);
+    for tick in 2..=101 {
+        neighbor.deliver(&outbox, SHARD_A, tick, at(tick)).await;
+    }
+    let stats = outbox.neighbor_stats(&host(), at(101));
+    assert!(!stats.reachable);
+    assert_eq!(stats.pending, 1);
+    assert_eq!(stats.merged, 99);
+    // Retries back off instead of hitting the neighbor every tick
+    let outage_attempts = neighbor.attempts.load(Ordering::SeqCst) - attempts_before;
+    assert!(outage_attempts < 10, "{} attempts during the outage", outage_attempts);
+    assert!(neighbor.take_received().is_empty());
+
+    // Once the neighbor is back the retry carries the freshest border, and nothing older follows
+    neighbor.set_up(true);
+    let recovered_at = at(101) + Duration::from_secs(11);
+    neighbor.deliver(&outbox, SHARD_A, 102, recovered_at).await;
+    neighbor.deliver(&outbox, SHARD_A, 103, recovered_at + TICK).await;
+    assert_eq!(neighbor.take_received(), vec![(SHARD_A, 102), (SHARD_A, 103)]);
+
+    let stats = outbox.neighbor_stats(&host(), recovered_at);
+    assert!(stats.reachable);
+    assert_eq!((stats.pending, stats.recoveries), (0, 1));
+}
+
+#[tokio::test]
+async fn test_recovery_flushes_borders_of_every_held_shard() {
+    let outbox = BorderOutbox::new();
+    let neighbor = FakeNeighbor::default();
+    let start = Instant::now();
+
+    neighbor.set_up(false);
+    neighbor.deliver(&outbox, SHARD_B, 1, start).await;
+    neighbor.deliver(&outbox, SHARD_A, 1, start).await;
+    neighbor.deliver(&outbox, SHARD_A, 2, start + TICK).await;
+    assert_eq!(outbox.neighbor_stats(&host(), start).pending, 2);
+
+    // Too early for a retry: the update is held and nothing is sent
+    neighbor.set_up(true);
+    neighbor.deliver(&outbox, SHARD_A, 3, start + TICK * 2).await;
+    assert!(neighbor.take_received().is_empty());
+
+    neighbor.deliver(&outbox, SHARD_A, 4, start + Duration::from_secs(1)).await;
+    let mut received = neighbor.take_received();
+    received.sort_by_key(|(shard, _)| shard.x);
+    assert_eq!(received, vec![(SHARD_A, 4), (SHARD_B, 1)]);
+    assert!(outbox.neighbor_stats(&host(), start + Duration::from_secs(1)).reachable);
+}
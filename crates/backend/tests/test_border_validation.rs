@@ -0,0 +1,144 @@
+mod common;
+
+use backend::backend_config;
+use backend::be_server::{dispatch_request, dispatch_request_from};
+use backend::border_validation::BorderSources;
+use shared::be_api::{
+    BackendRequest, BackendResponse, Cell, Color, InitColonyRequest, InitColonyShardRequest,
+    InitColonyShardResponse, SeedingOptions, Shard, Traits, UpdatedShardContentsRequest, UpdatedShardContentsResponse,
+};
+use shared::cluster_topology::{ClusterTopology, HostInfo};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use common::RULES;
+
+const SHARD_SIZE: i32 = 10;
+
+fn this_backend() -> HostInfo {
+    HostInfo::new("127.0.0.1".to_string(), 18282)
+}
+
+fn backend(hostname: &str) -> HostInfo {
+    HostInfo::new(hostname.to_string(), 8082)
+}
+
+fn shard(col: i32, row: i32) -> Shard {
+    Shard { x: col * SHARD_SIZE, y: row * SHARD_SIZE, width: SHARD_SIZE, height: SHARD_SIZE }
+}
+
+fn ip(address: &str) -> Option<IpAddr> {
+    Some(address.parse().unwrap())
+}
+
+/// 3x2 colony: this backend hosts (0,0); its neighbors (1,0) and (0,1) live on 10.0.0.2 and
+/// 10.0.0.3, and everything else on 10.0.0.4
+fn topology() -> ClusterTopology {
+    let mut shard_to_host = HashMap::new();
+    for row in 0..2 {
+        for col in 0..3 {
+            let host = match (col, row) {
+                (0, 0) => this_backend(),
+                (1, 0) => backend("10.0.0.2"),
+                (0, 1) => backend("10.0.0.3"),
+                _ => backend("10.0.0.4"),
+            };
+            shard_to_host.insert(shard(col, row), host);
+        }
+    }
+    ClusterTopology {
+        coordinator_host: HostInfo::new("127.0.0.1".to_string(), 18283),
+        backend_hosts: vec![this_backend(), backend("10.0.0.2"), backend("10.0.0.3"), backend("10.0.0.4")],
+        shard_to_host,
+    }
+}
+
+fn border(updated_shard: Shard) -> UpdatedShardContentsRequest {
+    let white = Color { red: 255, green: 255, blue: 255 };
+    let cell = Cell {
+        tick_bit: false,
+        food: 0,
+        extra_food_per_tick: 0,
+        color: white,
+        original_color: white,
+        health: 0,
+        age: 0,
+        hunger: 0,
+        traits: Traits { size: 1, can_kill: false, can_move: false },
+    };
+    let side = vec![cell; SHARD_SIZE as usize];
+    UpdatedShardContentsRequest { updated_shard, top: side.clone(), bottom: side.clone(), left: side.clone(), right: side, frozen: false, epoch: 1 }
+}
+
+#[test]
+fn test_legitimate_neighbors_are_accepted() {
+    let sources = BorderSources::from_topology(&[shard(0, 0)], &topology());
+    assert_eq!(sources.check(&shard(1, 0), ip("10.0.0.2")), Ok(()));
+    assert_eq!(sources.check(&shard(0, 1), ip("10.0.0.3")), Ok(()));
+    // IPv4 peers may show up mapped into IPv6 on a dual-stack listener
+    assert_eq!(sources.check(&shard(1, 0), ip("::ffff:10.0.0.2")), Ok(()));
+    // Without a peer address only adjacency is checked
+    assert_eq!(sources.check(&shard(1, 0), None), Ok(()));
+}
+
+#[test]
+fn test_non_adjacent_shards_are_rejected() {
+    let sources = BorderSources::from_topology(&[shard(0, 0)], &topology());
+    // Diagonal and two columns away
+    for claimed in [shard(1, 1), shard(2, 0)] {
+        let error = sources.check(&claimed, ip("10.0.0.4")).unwrap_err();
+        assert!(error.contains("not adjacent"), "{}", error);
+    }
+    // Shaped like a neighbor but not in the topology
+    let unknown = Shard { x: SHARD_SIZE, y: 0, width: SHARD_SIZE, height: 2 * SHARD_SIZE };
+    assert!(sources.check(&unknown, None).is_err());
+}
+
+#[test]
+fn test_spoofed_shard_claims_are_rejected() {
+    let sources = BorderSources::from_topology(&[shard(0, 0)], &topology());
+    let error = sources.check(&shard(1, 0), ip("10.0.0.4")).unwrap_err();
+    assert!(error.contains("10.0.0.2:8082") && error.contains("10.0.0.4"), "{}", error);
+    assert!(sources.check(&shard(0, 1), ip("10.0.0.2")).is_err());
+
+    // Owners named by hostname cannot be compared with the peer IP, only localhost can
+    let mut named = topology();
+    named.shard_to_host.insert(shard(1, 0), backend("backend-2"));
+    named.shard_to_host.insert(shard(0, 1), backend("localhost"));
+    let sources = BorderSources::from_topology(&[shard(0, 0)], &named);
+    assert_eq!(sources.check(&shard(1, 0), ip("10.0.0.4")), Ok(()));
+    assert_eq!(sources.check(&shard(0, 1), ip("127.0.0.1")), Ok(()));
+    assert!(sources.check(&shard(0, 1), ip("10.0.0.4")).is_err());
+}
+
+#[tokio::test]
+async fn test_rejected_updates_get_their_own_response() {
+    backend_config::set_backend_hostname(this_backend().hostname);
+    backend_config::set_backend_port(this_backend().port);
+    dispatch_request(BackendRequest::InitColony(InitColonyRequest { width: 3 * SHARD_SIZE, height: 2 * SHARD_SIZE, colony_life_rules: RULES })).await;
+    let init = dispatch_request(BackendRequest::InitColonyShard(InitColonyShardRequest {
+        shard: shard(0, 0),
+        colony_life_rules: RULES,
+        topology: Some(topology()),
+        seeding: SeedingOptions::default(),
+        topography_data: None,
+        awaiting_topography: false,
+        colony_instance_id: Some("colony-a".to_string()),
+        lease_epoch: 1,
+    })).await;
+    assert!(matches!(init, BackendResponse::InitColonyShard(InitColonyShardResponse::Ok)));
+
+    let update = |claimed: Shard, peer: &str| dispatch_request_from(BackendRequest::UpdatedShardContents(border(claimed)), ip(peer));
+    assert!(matches!(update(shard(1, 0), "10.0.0.2").await, BackendResponse::UpdatedShardContents(UpdatedShardContentsResponse::Ok)));
+    match update(shard(1, 0), "10.0.0.4").await {
+        BackendResponse::UpdatedShardContents(UpdatedShardContentsResponse::Rejected(reason)) => assert!(reason.contains("owned by"), "{}", reason),
+        other => panic!("Unexpected response {:?}", other),
+    }
+    assert!(matches!(update(shard(2, 1), "10.0.0.4").await, BackendResponse::UpdatedShardContents(UpdatedShardContentsResponse::Rejected(_))));
+
+    // Any topology swap is picked up on the next update, without rebuilding the senders by hand
+    let mut moved = topology();
+    moved.shard_to_host.insert(shard(1, 0), backend("10.0.0.4"));
+    ClusterTopology::replace(moved).unwrap();
+    assert!(matches!(update(shard(1, 0), "10.0.0.4").await, BackendResponse::UpdatedShardContents(UpdatedShardContentsResponse::Ok)));
+    assert!(matches!(update(shard(1, 0), "10.0.0.2").await, BackendResponse::UpdatedShardContents(UpdatedShardContentsResponse::Rejected(_))));
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use backend::be_server::dispatch_request;
+use backend::colony::Colony;
+use shared::be_api::{
+    BackendRequest, BackendResponse, InitColonyRequest, InitColonyResponse, InitColonyShardRequest,
+    InitColonyShardResponse, SeedingOptions, Shard,
+};
+use shared::cluster_topology::{ClusterTopology, HostInfo};
+use std::collections::HashMap;
+use common::RULES;
+
+const SHARD_SIZE: i32 = 10;
+
+fn this_backend() -> HostInfo {
+    HostInfo::new("127.0.0.1".to_string(), 18111)
+}
+
+fn shard(col: i32, row: i32) -> Shard {
+    Shard { x: col * SHARD_SIZE, y: row * SHARD_SIZE, width: SHARD_SIZE, height: SHARD_SIZE }
+}
+
+/// A cols x rows grid of shards, all on this backend
+fn topology(cols: i32, rows: i32) -> ClusterTopology {
+    let shards = (0..rows).flat_map(|row| (0..cols).map(move |col| shard(col, row)));
+    ClusterTopology {
+        coordinator_host: HostInfo::new("127.0.0.1".to_string(), 18112),
+        backend_hosts: vec![this_backend()],
+        shard_to_host: shards.map(|shard| (shard, this_backend())).collect::<HashMap<_, _>>(),
+    }
+}
+
+#[test]
+fn test_matching_topology_is_accepted() {
+    assert_eq!(Colony::validate_topology_dimensions(3 * SHARD_SIZE, 2 * SHARD_SIZE, &topology(3, 2)), Ok(()));
+}
+
+#[test]
+fn test_topology_larger_than_the_colony_is_rejected() {
+    let e = Colony::validate_topology_dimensions(2 * SHARD_SIZE, 2 * SHARD_SIZE, &topology(3, 2)).unwrap_err();
+    assert!(e.contains("outside the 20x20 colony"), "{}", e);
+}
+
+#[test]
+fn test_topology_smaller_than_the_colony_is_rejected() {
+    let e = Colony::validate_topology_dimensions(3 * SHARD_SIZE, 3 * SHARD_SIZE, &topology(3, 2)).unwrap_err();
+    assert!(e.contains("covers 30x20 but the colony is 30x30"), "{}", e);
+}
+
+#[tokio::test]
+async fn test_backend_rejects_mismatched_dimensions() {
+    let init_colony = |width: i32| BackendRequest::InitColony(InitColonyRequest { width, height: SHARD_SIZE, colony_life_rules: RULES });
+    assert!(matches!(dispatch_request(init_colony(2 * SHARD_SIZE)).await, BackendResponse::InitColony(InitColonyResponse::Ok)));
+
+    // The first shard brings a 3x1 topology to a 2x1 colony
+    let response = dispatch_request(BackendRequest::InitColonyShard(InitColonyShardRequest {
+        shard: shard(0, 0),
+        colony_life_rules: RULES,
+        topology: Some(topology(3, 1)),
+        seeding: SeedingOptions::default(),
+        topography_data: None,
+        awaiting_topography: false,
+        colony_instance_id: None,
+        lease_epoch: 1,
+    })).await;
+    assert!(matches!(response, BackendResponse::InitColonyShard(InitColonyShardResponse::DimensionMismatch(_))), "{:?}", response);
+    assert!(ClusterTopology::get_instance().is_none(), "a rejected topology is not installed");
+
+    assert!(matches!(dispatch_request(init_colony(2 * SHARD_SIZE)).await,
+                     BackendResponse::InitColony(InitColonyResponse::ColonyAlreadyInitialized)));
+    match dispatch_request(init_colony(3 * SHARD_SIZE)).await {
+        BackendResponse::InitColony(InitColonyResponse::DimensionMismatch(e)) => assert!(e.contains("already 20x10, not 30x10"), "{}", e),
+        other => panic!("Expected DimensionMismatch, got {:?}", other),
+    }
+}
@@ -0,0 +1,43 @@
+mod common;
+
+use backend::colony_shard::is_blank;
+use backend::shard_utils::ShardUtils;
+use shared::be_api::{ColonyLifeRules, SeedingOptions, Shard};
+use shared::utils::new_seeded_random_generator;
+use std::collections::HashSet;
+use common::RULES;
+
+const SHARD_SIZE: i32 = 32;
+const TICKS: usize = 50_000;
+const SEED: u64 = 7;
+
+/// Distinct colors of the living creatures after TICKS ticks, with the founders' colors
+fn colors_after_ticks(rules: ColonyLifeRules) -> (usize, usize) {
+    let shard = Shard { x: 0, y: 0, width: SHARD_SIZE, height: SHARD_SIZE };
+    let mut rng = new_seeded_random_generator(SEED);
+    let mut colony_shard = ShardUtils::new_colony_shard(&shard, &rules, &SeedingOptions::default(), &mut rng);
+    for cell in colony_shard.grid.iter_mut() {
+        cell.extra_food_per_tick = 10;
+    }
+    for _ in 0..TICKS {
+        colony_shard.tick(&mut rng);
+    }
+    let living: Vec<_> = colony_shard.grid.iter().filter(|cell| !is_blank(cell)).collect();
+    assert!(!living.is_empty(), "the colony died out");
+    let colors: HashSet<_> = living.iter().map(|cell| (cell.color.red, cell.color.green, cell.color.blue)).collect();
+    let founders: HashSet<_> = living.iter()
+        .map(|cell| (cell.original_color.red, cell.original_color.green, cell.original_color.blue))
+        .collect();
+    (colors.len(), founders.len())
+}
+
+#[test]
+fn test_color_drift_diversifies_the_displayed_colors() {
+    let (flat_colors, flat_founders) = colors_after_ticks(RULES);
+    let drifting = ColonyLifeRules { color_drift_per_generation: 2, color_mutation_chance: 10_000, ..RULES };
+    let (drifting_colors, drifting_founders) = colors_after_ticks(drifting);
+    assert!(drifting_colors >= 10 * flat_colors, "distinct colors: {} with drift, {} without", drifting_colors, flat_colors);
+    // Drifted and re-rolled offspring still count toward their founder's lineage
+    assert!(drifting_founders < drifting_colors / 10, "{} lineages for {} colors", drifting_founders, drifting_colors);
+    assert!(flat_founders <= flat_colors);
+}
@@ -0,0 +1,97 @@
+mod common;
+
+use backend::colony_shard::{AttackOutcome, ColonyShard};
+use backend::shard_utils::ShardUtils;
+use shared::be_api::{ColonyLifeRules, Color, SeedingOptions, Shard, Traits};
+use shared::utils::new_seeded_random_generator;
+
+const ENCOUNTERS: usize = 20_000;
+/// Five standard deviations of a kill rate measured over ENCOUNTERS at p = 0.5
+const TOLERANCE: f64 = 0.018;
+
+const RULES: ColonyLifeRules = ColonyLifeRules {
+    kill_success_base_chance: 50,
+    kill_size_advantage_percent: 15,
+    kill_counter_damage: 30,
+    reproduction_food_cost: 0,
+    reproduction_min_food: 0,
+    ..common::RULES
+};
+
+const ROW_SIZE: usize = 4 + 2;
+const ATTACKER: usize = ROW_SIZE + 1;
+const DEFENDER: usize = ROW_SIZE + 2;
+
+fn empty_shard(rules: ColonyLifeRules) -> ColonyShard {
+    let shard = Shard { x: 0, y: 0, width: 4, height: 4 };
+    let mut rng = new_seeded_random_generator(1);
+    let mut colony_shard = ShardUtils::new_colony_shard(&shard, &rules, &SeedingOptions::default(), &mut rng);
+    for cell in colony_shard.grid.iter_mut() {
+        cell.health = 0;
+    }
+    colony_shard
+}
+
+fn place(colony_shard: &mut ColonyShard, idx: usize, size: u8, health: u16, red: u8) {
+    let color = Color { red, green: 0, blue: 0 };
+    let cell = &mut colony_shard.grid[idx];
+    cell.health = health;
+    cell.color = color;
+    cell.original_color = color;
+    cell.traits = Traits { size, can_kill: true, can_move: false };
+}
+
+/// Fraction of ENCOUNTERS fresh attacks that killed the defender
+fn empirical_kill_rate(attacker_size: u8, defender_size: u8) -> f64 {
+    let mut colony_shard = empty_shard(RULES);
+    let mut rng = new_seeded_random_generator(attacker_size as u64 * 1000 + defender_size as u64);
+    let mut kills = 0;
+    for _ in 0..ENCOUNTERS {
+        place(&mut colony_shard, ATTACKER, attacker_size, 500, 200);
+        place(&mut colony_shard, DEFENDER, defender_size, 500, 100);
+        if colony_shard.resolve_attack(ATTACKER, DEFENDER, true, &mut rng) == AttackOutcome::Killed {
+            kills += 1;
+        }
+    }
+    kills as f64 / ENCOUNTERS as f64
+}
+
+#[test]
+fn test_kill_rate_follows_size_advantage() {
+    for (attacker_size, defender_size) in [(10, 10), (12, 10), (8, 10), (11, 10), (9, 12), (20, 10), (10, 20)] {
+        let expected = RULES.kill_success_percent(attacker_size, defender_size) as f64 / 100.0;
+        let measured = empirical_kill_rate(attacker_size, defender_size);
+        assert!((measured - expected).abs() <= TOLERANCE,
+                "size {} vs {}: measured {:.4}, expected {:.2}", attacker_size, defender_size, measured, expected);
+    }
+}
+
+#[test]
+fn test_kill_takes_the_defender_cell() {
+    let mut colony_shard = empty_shard(ColonyLifeRules { kill_success_base_chance: 100, ..RULES });
+    let mut rng = new_seeded_random_generator(2);
+    place(&mut colony_shard, ATTACKER, 10, 40, 200);
+    place(&mut colony_shard, DEFENDER, 10, 25, 100);
+
+    assert_eq!(colony_shard.resolve_attack(ATTACKER, DEFENDER, true, &mut rng), AttackOutcome::Killed);
+    assert_eq!(colony_shard.grid[ATTACKER].health, 0);
+    assert_eq!(colony_shard.grid[DEFENDER].health, 65);
+    assert_eq!(colony_shard.grid[DEFENDER].color.red, 200);
+}
+
+#[test]
+fn test_failed_attack_costs_counter_damage_and_can_kill_the_attacker() {
+    let mut colony_shard = empty_shard(ColonyLifeRules { kill_success_base_chance: 0, kill_size_advantage_percent: 0, ..RULES });
+    let mut rng = new_seeded_random_generator(3);
+    place(&mut colony_shard, ATTACKER, 10, 40, 200);
+    place(&mut colony_shard, DEFENDER, 10, 25, 100);
+
+    assert_eq!(colony_shard.resolve_attack(ATTACKER, DEFENDER, true, &mut rng), AttackOutcome::Repelled);
+    assert_eq!(colony_shard.grid[ATTACKER].health, 10);
+    assert_eq!(colony_shard.grid[DEFENDER].health, 25);
+
+    // The second failure uses up the attacker's health
+    assert_eq!(colony_shard.resolve_attack(ATTACKER, DEFENDER, true, &mut rng), AttackOutcome::Repelled);
+    assert_eq!(colony_shard.grid[ATTACKER].health, 0);
+    assert_eq!(colony_shard.grid[DEFENDER].health, 25);
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use backend::be_colony_events::apply_event_to_shards;
+use backend::colony_shard::{ColonyShard, WHITE_COLOR};
+use backend::shard_utils::ShardUtils;
+use shared::be_api::{Color, SeedingOptions, Shard, ShardEventEffect, Traits};
+use shared::colony_events::{ColonyEvent, ColonyRuleChange, CreateCreatureParams, Ellipse, Region};
+use shared::utils::new_seeded_random_generator;
+use std::sync::{Arc, Mutex};
+use common::RULES;
+
+const SHARD_SIZE: i32 = 10;
+
+/// Empty SHARD_SIZE shard at (x, 0) with creatures on the first `creatures` interior cells of row 1
+fn crafted_shard(x: i32, creatures: usize) -> Arc<Mutex<ColonyShard>> {
+    let shard = Shard { x, y: 0, width: SHARD_SIZE, height: SHARD_SIZE };
+    let seeding = SeedingOptions { density: 0.0, ..SeedingOptions::default() };
+    let mut colony_shard = ShardUtils::new_colony_shard(&shard, &RULES, &seeding, &mut new_seeded_random_generator(1));
+    let row_size = (SHARD_SIZE + 2) as usize;
+    for col in 1..=creatures {
+        let cell = &mut colony_shard.grid[row_size + col];
+        cell.color = Color { red: 0, green: 200, blue: 0 };
+        cell.health = 50;
+    }
+    Arc::new(Mutex::new(colony_shard))
+}
+
+fn apply(shards: &[Arc<Mutex<ColonyShard>>], event: &ColonyEvent) -> Vec<ShardEventEffect> {
+    apply_event_to_shards(&mut new_seeded_random_generator(5), shards, event)
+}
+
+fn create_creature(region: Region) -> ColonyEvent {
+    ColonyEvent::CreateCreature(region, CreateCreatureParams {
+        color: Color { red: 200, green: 0, blue: 0 },
+        traits: Traits { size: 15, can_kill: false, can_move: false },
+        starting_health: 80,
+    })
+}
+
+#[test]
+fn test_create_creature_counts_region_cells() {
+    let shards = vec![crafted_shard(0, 0), crafted_shard(100, 0)];
+    // A 1-cell ellipse fully inside the first shard's interior
+    let region = Region::Ellipse(Ellipse { x: 5, y: 5, radius_x: 1, radius_y: 1 });
+    let effects = apply(&shards, &create_creature(region));
+
+    // The ellipse covers the center and its 4 direct neighbors; the far shard is not listed
+    assert_eq!(effects, vec![ShardEventEffect { shard: shards[0].lock().unwrap().shard, cells_affected: 5, creatures_affected: 5, tick_applied: 0 }]);
+
+    let missed = Region::Ellipse(Ellipse { x: 500, y: 500, radius_x: 3, radius_y: 3 });
+    assert!(apply(&shards, &create_creature(missed)).is_empty());
+}
+
+#[test]
+fn test_extra_food_counts_changed_cells() {
+    let shards = vec![crafted_shard(0, 3)];
+    let interior = (SHARD_SIZE * SHARD_SIZE) as u64;
+
+    let effects = apply(&shards, &ColonyEvent::ChangeExtraFoodPerTick(10));
+    assert_eq!(effects.len(), 1);
+    assert_eq!((effects[0].cells_affected, effects[0].creatures_affected), (interior, 3));
+
+    // Saturated at zero: lowering again changes nothing
+    apply(&shards, &ColonyEvent::ChangeExtraFoodPerTick(-128));
+    assert!(apply(&shards, &ColonyEvent::ChangeExtraFoodPerTick(-1)).is_empty());
+}
+
+#[test]
+fn test_extinction_counts_wiped_creatures() {
+    let shards: Vec<_> = (0..8).map(|i| crafted_shard(i * SHARD_SIZE, 4)).collect();
+    let effects = apply(&shards, &ColonyEvent::Extinction());
+
+    // Each shard is wiped with probability one half
+    assert!(!effects.is_empty() && effects.len() < shards.len(), "{} shards wiped", effects.len());
+    for effect in &effects {
+        assert_eq!((effect.cells_affected, effect.creatures_affected), ((SHARD_SIZE * SHARD_SIZE) as u64, 4));
+        let wiped = shards.iter().find(|s| s.lock().unwrap().shard == effect.shard).unwrap();
+        assert!(wiped.lock().unwrap().grid.iter().all(|cell| cell.color.equals(&WHITE_COLOR)));
+    }
+}
+
+#[test]
+fn test_rules_change_covers_whole_shard() {
+    let shards = vec![crafted_shard(0, 2), crafted_shard(SHARD_SIZE, 0)];
+    let mut new_rules = RULES;
+    new_rules.mutation_chance = 300;
+    let event = ColonyEvent::ChangeColonyRules(ColonyRuleChange { new_rules, description: "test".to_string() });
+    let effects = apply(&shards, &event);
+
+    let counts: Vec<(u64, u64)> = effects.iter().map(|e| (e.cells_affected, e.creatures_affected)).collect();
+    assert_eq!(counts, vec![(100, 2), (100, 0)]);
+    assert_eq!(shards[1].lock().unwrap().colony_life_rules.mutation_chance, 300);
+}
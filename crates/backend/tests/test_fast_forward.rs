@@ -0,0 +1,84 @@
+mod common;
+
+use backend::colony::Colony;
+use backend::fast_forward::{is_fast_forward, set_fast_forward, FAST_FORWARD_RETRY_AFTER_SECS};
+use backend::http_server::start_http_server;
+use backend::presentation_snapshots::SnapshotConfig;
+use backend::shard_utils::ShardUtils;
+use shared::be_api::Shard;
+use shared::utils::new_seeded_random_generator;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+const HTTP_PORT: u16 = 18101;
+
+fn shard() -> Shard {
+    Shard { x: 0, y: 0, width: 40, height: 30 }
+}
+
+/// Status code, headers and body of a GET
+async fn get(path: &str) -> (u16, String, String) {
+    let mut stream = TcpStream::connect(("127.0.0.1", HTTP_PORT)).await.expect("connect");
+    let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path);
+    stream.write_all(request.as_bytes()).await.expect("write");
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).await.expect("read");
+    let response = String::from_utf8_lossy(&response);
+    let (header, body) = response.split_once("\r\n\r\n").expect("header terminator");
+    (header[9..12].parse().expect("status"), header.to_string(), body.to_string())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_fast_forward_skips_presentation_and_restores_it() {
+    common::use_temp_output_dir();
+    common::init_colony(18102, &[shard()], SnapshotConfig { enabled: true, ..SnapshotConfig::default() }).await;
+    tokio::spawn(start_http_server(HTTP_PORT));
+    for _ in 0..50 {
+        if TcpStream::connect(("127.0.0.1", HTTP_PORT)).await.is_ok() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    let shard_arc = Colony::instance().get_hosted_colony_shard_arc(&shard()).expect("shard hosted");
+    let mut rng = new_seeded_random_generator(9);
+    ShardUtils::tick_and_export(&mut shard_arc.lock().unwrap(), &mut rng);
+    let tick_before = shard_arc.lock().unwrap().get_current_tick();
+
+    set_fast_forward(true).await;
+    assert!(is_fast_forward());
+    for _ in 0..3 {
+        ShardUtils::tick_and_export(&mut shard_arc.lock().unwrap(), &mut rng);
+    }
+    {
+        let shard_guard = shard_arc.lock().unwrap();
+        assert_eq!(shard_guard.get_current_tick(), tick_before + 3);
+        assert!(shard_guard.dirty_pixels_journal.is_empty());
+        assert_eq!(shard_guard.estimated_changed_pixels_since(tick_before), None, "the skipped ticks are not summed");
+    }
+
+    let shard_id = shard().to_id();
+    for path in [
+        format!("/api/shard/{}/image", shard_id),
+        format!("/api/shard/{}/layer/age", shard_id),
+        format!("/api/shard/{}/image-changed?since_tick={}", shard_id, tick_before),
+    ] {
+        let (status, header, body) = get(&path).await;
+        assert_eq!(status, 503, "{}", path);
+        assert!(header.contains(&format!("Retry-After: {}", FAST_FORWARD_RETRY_AFTER_SECS)), "{}", header);
+        assert!(body.contains("Fast-forward"), "{}", body);
+    }
+    let (_, _, shards) = get("/api/shards").await;
+    assert!(shards.contains(r#""fast_forward":true"#), "{}", shards);
+    let (_, _, health) = get("/health").await;
+    assert!(health.contains(r#""fast_forward":true"#), "{}", health);
+
+    // Leaving fast-forward renders the snapshot buffers without waiting for the refresher
+    set_fast_forward(false).await;
+    let (status, _, _) = get(&format!("/api/shard/{}/image", shard_id)).await;
+    assert_eq!(status, 200);
+    ShardUtils::tick_and_export(&mut shard_arc.lock().unwrap(), &mut rng);
+    assert_eq!(shard_arc.lock().unwrap().dirty_pixels_journal.len(), 1);
+    let (_, _, health) = get("/health").await;
+    assert!(health.contains(r#""fast_forward":false"#), "{}", health);
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use backend::colony::Colony;
+use backend::http_server::start_http_server;
+use backend::presentation_snapshots::SnapshotConfig;
+use flate2::read::GzDecoder;
+use shared::be_api::Shard;
+use shared::utils::new_seeded_random_generator;
+use std::io::Read;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+const HTTP_PORT: u16 = 18092;
+const CLIENTS: usize = 32;
+const REQUESTS_PER_CLIENT: usize = 100;
+
+fn shard() -> Shard {
+    Shard { x: 0, y: 0, width: 60, height: 40 }
+}
+
+/// Fetches the shard image; Err describes a response whose body does not match its header
+async fn fetch_image() -> Result<(), String> {
+    let mut stream = TcpStream::connect(("127.0.0.1", HTTP_PORT)).await.map_err(|e| e.to_string())?;
+    let request = format!("GET /api/shard/{}/image HTTP/1.1\r\nHost: localhost\r\n\r\n", shard().to_id());
+    stream.write_all(request.as_bytes()).await.map_err(|e| e.to_string())?;
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).await.map_err(|e| e.to_string())?;
+
+    let header_end = response.windows(4).position(|w| w == b"\r\n\r\n").ok_or("No header terminator")? + 4;
+    let header = String::from_utf8_lossy(&response[..header_end]);
+    if !header.starts_with("HTTP/1.1 200") {
+        return Err(format!("Unexpected status: {}", header.lines().next().unwrap_or("")));
+    }
+    let content_length: usize = header.lines()
+        .find_map(|line| line.strip_prefix("Content-Length: "))
+        .and_then(|v| v.trim().parse().ok())
+        .ok_or("No Content-Length")?;
+    let body = &response[header_end..];
+    if body.len() != content_length {
+        return Err(format!("Body is {} bytes, Content-Length {}", body.len(), content_length));
+    }
+
+    let mut rgb_bytes = Vec::new();
+    GzDecoder::new(body).read_to_end(&mut rgb_bytes).map_err(|e| e.to_string())?;
+    let expected = (shard().width * shard().height * 3) as usize;
+    if rgb_bytes.len() != expected {
+        return Err(format!("Image is {} bytes, expected {}", rgb_bytes.len(), expected));
+    }
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_concurrent_image_requests_while_ticking_are_never_truncated() {
+    common::use_temp_output_dir();
+    common::init_colony(18093, &[shard()], SnapshotConfig::default()).await;
+    tokio::spawn(start_http_server(HTTP_PORT));
+
+    // Ticks the shard in a loop, so every image request contends with the ticker for the lock
+    let stop = Arc::new(AtomicBool::new(false));
+    let ticker = {
+        let stop = Arc::clone(&stop);
+        let shard_arc = Colony::instance().get_hosted_colony_shard_arc(&shard()).expect("shard hosted");
+        std::thread::spawn(move || {
+            let mut rng = new_seeded_random_generator(3);
+            while !stop.load(Ordering::Relaxed) {
+                shard_arc.lock().unwrap().tick(&mut rng);
+            }
+        })
+    };
+
+    // Wait for the listener
+    for _ in 0..50 {
+        if TcpStream::connect(("127.0.0.1", HTTP_PORT)).await.is_ok() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    let clients = (0..CLIENTS).map(|_| tokio::spawn(async {
+        let mut failures = Vec::new();
+        for _ in 0..REQUESTS_PER_CLIENT {
+            if let Err(e) = fetch_image().await {
+                failures.push(e);
+            }
+        }
+        failures
+    }));
+    let mut failures = Vec::new();
+    for client in clients {
+        failures.extend(client.await.unwrap());
+    }
+    stop.store(true, Ordering::Relaxed);
+    ticker.join().unwrap();
+
+    assert!(failures.is_empty(), "{} of {} responses were bad, first: {}",
+            failures.len(), CLIENTS * REQUESTS_PER_CLIENT, failures[0]);
+}
@@ -0,0 +1,129 @@
+mod common;
+
+use backend::colony_shard::ColonyShard;
+use backend::shard_utils::ShardUtils;
+use rand::rngs::SmallRng;
+use rand::Rng;
+use shared::be_api::{ColonyLifeRules, Color, SeedingOptions, Shard, Traits};
+use shared::utils::new_seeded_random_generator;
+
+const SHARD_SIZE: i32 = 16;
+const TICKS: usize = 60;
+
+/// Creatures neither die at random, breed, move nor fight, so every death is starvation
+const RULES: ColonyLifeRules = ColonyLifeRules {
+    random_death_chance: 1_000_000,
+    reproduction_min_food: 10_000,
+    ..common::RULES
+};
+
+fn grid_idx(x: i32, y: i32) -> usize {
+    ((y + 1) * (SHARD_SIZE + 2) + x + 1) as usize
+}
+
+fn interior() -> impl Iterator<Item = usize> {
+    (0..SHARD_SIZE).flat_map(|y| (0..SHARD_SIZE).map(move |x| grid_idx(x, y)))
+}
+
+/// A creature on every interior cell with the given health, on cells with food from food;
+/// the shadow margin holds neither creatures nor food
+fn populated_shard(rules: &ColonyLifeRules, size: u8, health: impl Fn(&mut SmallRng) -> u16, food: impl Fn(&mut SmallRng) -> u16) -> ColonyShard {
+    let shard = Shard { x: 0, y: 0, width: SHARD_SIZE, height: SHARD_SIZE };
+    let mut rng = new_seeded_random_generator(1);
+    let mut colony_shard = ShardUtils::new_colony_shard(&shard, rules, &SeedingOptions::default(), &mut rng);
+    for cell in colony_shard.grid.iter_mut() {
+        cell.health = 0;
+        cell.food = 0;
+        cell.extra_food_per_tick = 0;
+    }
+    let color = Color { red: 0, green: 120, blue: 200 };
+    for idx in interior() {
+        let cell = &mut colony_shard.grid[idx];
+        cell.health = health(&mut rng);
+        cell.food = food(&mut rng);
+        cell.color = color;
+        cell.original_color = color;
+        cell.traits = Traits { size, can_kill: false, can_move: false };
+    }
+    colony_shard
+}
+
+fn creatures(colony_shard: &ColonyShard) -> usize {
+    interior().filter(|idx| colony_shard.grid[*idx].health > 0).count()
+}
+
+/// Deaths per tick on cells that each get a meal on one tick in three, at random
+fn starvation_deaths(rules: &ColonyLifeRules) -> Vec<usize> {
+    let mut colony_shard = populated_shard(rules, 5, |rng| rng.gen_range(20..=80), |_| 0);
+    let mut food_rng = new_seeded_random_generator(3);
+    let mut rng = new_seeded_random_generator(4);
+    (0..TICKS).map(|_| {
+        for idx in interior() {
+            if food_rng.gen_range(0..3) == 0 {
+                colony_shard.grid[idx].food += 25;
+            }
+        }
+        let before = creatures(&colony_shard);
+        colony_shard.tick(&mut rng);
+        before - creatures(&colony_shard)
+    }).collect()
+}
+
+#[test]
+fn test_grace_period_smooths_starvation_deaths() {
+    let without_grace = starvation_deaths(&RULES);
+    let with_grace = starvation_deaths(&ColonyLifeRules { starvation_grace_ticks: 3, ..RULES });
+
+    // Every missed meal costs health without the grace period, so the unlucky die in bursts;
+    // with it only a streak of missed meals does
+    let peak = |deaths: &[usize]| deaths.iter().copied().max().unwrap_or(0);
+    let total = |deaths: &[usize]| deaths.iter().sum::<usize>();
+    assert!(total(&without_grace) > 50, "{:?}", without_grace);
+    assert!(total(&with_grace) * 2 < total(&without_grace), "{:?} vs {:?}", with_grace, without_grace);
+    assert!(peak(&with_grace) < peak(&without_grace), "{:?} vs {:?}", with_grace, without_grace);
+}
+
+#[test]
+fn test_rules_without_hunger_never_count_it() {
+    let mut colony_shard = populated_shard(&RULES, 5, |rng| rng.gen_range(20..=80), |_| 0);
+    let mut rng = new_seeded_random_generator(4);
+    for _ in 0..5 {
+        colony_shard.tick(&mut rng);
+    }
+    assert!(colony_shard.grid.iter().all(|cell| cell.hunger == 0));
+}
+
+#[test]
+fn test_food_sharing_conserves_total_food() {
+    // Each creature eats one food a tick and pays one health for it, so the health it kept
+    // tells how much it ate
+    let rules = ColonyLifeRules {
+        health_cost_per_size_unit: 1,
+        eat_capacity_per_size_unit: 1,
+        food_sharing_radius: 2,
+        food_sharing_percent: 50,
+        ..RULES
+    };
+    let mut colony_shard = populated_shard(&rules, 1, |_| 1000, |rng| if rng.gen_bool(0.5) { 0 } else { rng.gen_range(1..=40) });
+    let mut rng = new_seeded_random_generator(4);
+    let total_food = |colony_shard: &ColonyShard| colony_shard.grid.iter().map(|cell| cell.food as u64).sum::<u64>();
+    let started_empty: Vec<usize> = interior().filter(|idx| colony_shard.grid[*idx].food == 0).collect();
+
+    for tick in 0..TICKS {
+        let food_before = total_food(&colony_shard);
+        let health_before: Vec<u16> = interior().map(|idx| colony_shard.grid[idx].health).collect();
+        colony_shard.tick(&mut rng);
+        // A creature can still die 1 in 5000 after eating; what it ate is then unknown
+        let died = (SHARD_SIZE * SHARD_SIZE) as u64 - creatures(&colony_shard) as u64;
+        let eaten: u64 = interior().zip(&health_before)
+            .filter(|(idx, _)| colony_shard.grid[*idx].health > 0)
+            .map(|(idx, before)| (colony_shard.grid[idx].health + 1 - before) as u64)
+            .sum();
+        let food_after = total_food(&colony_shard) + eaten;
+        assert!(food_after <= food_before && food_before - food_after <= died,
+            "food went from {} to {} at tick {} with {} deaths", food_before, food_after, tick, died);
+    }
+    // Creatures that started without food ate what their neighbors shared
+    let fed = started_empty.iter().filter(|idx| colony_shard.grid[**idx].health > 1000 - TICKS as u16).count();
+    assert!(fed * 2 > started_empty.len(), "{} of {} fed", fed, started_empty.len());
+}
@@ -0,0 +1,73 @@
+use backend::image_qos::{FrameCache, QosConfig, TickLoadMonitor};
+use shared::be_api::Shard;
+use std::sync::Arc;
+
+fn monitor(threshold_ms: f64) -> TickLoadMonitor {
+    TickLoadMonitor::new(QosConfig { enabled: true, tick_ms_threshold: threshold_ms })
+}
+
+#[test]
+fn test_engages_when_average_exceeds_threshold() {
+    let mut monitor = monitor(100.0);
+    // A single slow tick among fast ones keeps the average below the threshold
+    for _ in 0..10 {
+        assert!(!monitor.record_tick(50.0));
+    }
+    assert!(!monitor.record_tick(300.0));
+
+    let mut engaged = false;
+    for _ in 0..10 {
+        engaged = monitor.record_tick(300.0);
+    }
+    assert!(engaged);
+    assert_eq!(monitor.engaged_count(), 1);
+}
+
+#[test]
+fn test_releases_only_well_below_threshold() {
+    let mut monitor = monitor(100.0);
+    for _ in 0..20 {
+        monitor.record_tick(200.0);
+    }
+    // Just below the threshold is not enough to leave QoS mode
+    for _ in 0..20 {
+        assert!(monitor.record_tick(90.0));
+    }
+    for _ in 0..20 {
+        monitor.record_tick(50.0);
+    }
+    assert!(!monitor.record_tick(50.0));
+    assert!((monitor.average_ms().unwrap() - 50.0).abs() < 1e-9);
+
+    // Engaging again is counted again
+    for _ in 0..20 {
+        monitor.record_tick(200.0);
+    }
+    assert_eq!(monitor.engaged_count(), 2);
+}
+
+#[test]
+fn test_disabled_never_engages() {
+    let mut monitor = TickLoadMonitor::new(QosConfig { enabled: false, tick_ms_threshold: 10.0 });
+    for _ in 0..50 {
+        assert!(!monitor.record_tick(1000.0));
+    }
+    assert_eq!(monitor.engaged_count(), 0);
+}
+
+#[test]
+fn test_frame_cache_per_shard_and_key() {
+    let cache = FrameCache::new();
+    let shard = Shard { x: 0, y: 0, width: 10, height: 10 };
+    let other_shard = Shard { x: 10, y: 0, width: 10, height: 10 };
+    assert!(cache.get(&shard, "image").is_none());
+
+    cache.store(shard, "image", 7, Arc::new(vec![1, 2, 3]));
+    cache.store(shard, "layer/food/Binary", 7, Arc::new(vec![4]));
+    cache.store(shard, "image", 9, Arc::new(vec![5, 6]));
+
+    let (body, tick) = cache.get(&shard, "image").unwrap();
+    assert_eq!((body.as_slice(), tick), (&[5u8, 6][..], 9));
+    assert_eq!(cache.get(&shard, "layer/food/Binary").unwrap().0.as_slice(), &[4]);
+    assert!(cache.get(&other_shard, "image").is_none());
+}
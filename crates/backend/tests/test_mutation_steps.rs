@@ -0,0 +1,72 @@
+mod common;
+
+use backend::shard_stats::ShardStatsSnapshot;
+use backend::shard_utils::ShardUtils;
+use shared::be_api::{ColonyLifeRules, SeedingOptions, Shard, StatBucket, StatMetric};
+use shared::utils::new_seeded_random_generator;
+
+const SHARD_SIZE: i32 = 64;
+const TICKS: usize = 300;
+const SEED: u64 = 42;
+
+/// Every offspring mutates, so a few hundred ticks spread the sizes
+const RULES: ColonyLifeRules = ColonyLifeRules {
+    mutation_chance: 1,
+    mutation_cost_step: 1000,
+    boolean_trait_flip_chance: 10,
+    ..common::RULES
+};
+
+/// Size histogram from the shard stats after TICKS ticks on a shard with 10 food per tick everywhere
+fn size_histogram(rules: ColonyLifeRules) -> Vec<StatBucket> {
+    let shard = Shard { x: 0, y: 0, width: SHARD_SIZE, height: SHARD_SIZE };
+    let mut rng = new_seeded_random_generator(SEED);
+    let mut colony_shard = ShardUtils::new_colony_shard(&shard, &rules, &SeedingOptions::default(), &mut rng);
+    for cell in colony_shard.grid.iter_mut() {
+        cell.extra_food_per_tick = 10;
+    }
+    for _ in 0..TICKS {
+        colony_shard.tick(&mut rng);
+    }
+    let stats = ShardStatsSnapshot::capture(&colony_shard, &shard, None).expect("snapshot").compute_stats(&[StatMetric::Size]);
+    let (_, buckets) = stats[0].metrics[0].clone();
+    assert!(!buckets.is_empty(), "the colony died out");
+    buckets
+}
+
+/// Standard deviation of the sizes in the histogram
+fn size_spread(buckets: &[StatBucket]) -> f64 {
+    let count: u64 = buckets.iter().map(|bucket| bucket.occs).sum();
+    let mean = buckets.iter().map(|bucket| bucket.value as f64 * bucket.occs as f64).sum::<f64>() / count as f64;
+    let variance = buckets.iter()
+        .map(|bucket| (bucket.value as f64 - mean).powi(2) * bucket.occs as f64)
+        .sum::<f64>() / count as f64;
+    variance.sqrt()
+}
+
+fn with_size_step(step: u32) -> ColonyLifeRules {
+    ColonyLifeRules { mutation_size_step: step, ..RULES }
+}
+
+#[test]
+fn test_larger_size_steps_widen_the_size_histogram() {
+    let spreads: Vec<f64> = [1, 3, 6].into_iter().map(|step| size_spread(&size_histogram(with_size_step(step)))).collect();
+    assert!(spreads[0] < spreads[1] && spreads[1] < spreads[2], "size spreads by step: {:?}", spreads);
+
+    // Without a size step only the seeded template sizes are left
+    let sizes: Vec<i32> = size_histogram(with_size_step(0)).iter().map(|bucket| bucket.value).collect();
+    assert!(sizes.iter().all(|size| (15..20).contains(size)), "sizes: {:?}", sizes);
+}
+
+#[test]
+fn test_cost_step_keeps_expensive_mutations_out() {
+    // A size step of 3 changes the cost by 6, over a cost step of 5, so sizes cannot move.
+    // No flips, as a flipped ability can offset the size's cost change.
+    let no_flips = ColonyLifeRules { boolean_trait_flip_chance: 0, ..with_size_step(3) };
+    let capped = ColonyLifeRules { mutation_cost_step: 5, ..no_flips };
+    let sizes: Vec<i32> = size_histogram(capped).iter().map(|bucket| bucket.value).collect();
+    assert!(sizes.iter().all(|size| (15..20).contains(size)), "sizes: {:?}", sizes);
+
+    let uncapped = size_histogram(no_flips);
+    assert!(uncapped.iter().any(|bucket| !(15..20).contains(&bucket.value)), "sizes: {:?}", uncapped);
+}
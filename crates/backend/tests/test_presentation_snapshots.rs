@@ -0,0 +1,103 @@
+mod common;
+
+use backend::colony::Colony;
+use backend::http_server::start_http_server;
+use backend::image_qos::FrameCache;
+use backend::presentation_snapshots::{PresentationSnapshots, RequestCounts, SnapshotConfig};
+use shared::be_api::{Shard, COLONY_TICK_HEADER};
+use shared::utils::new_seeded_random_generator;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+const HTTP_PORT: u16 = 18095;
+
+fn shard() -> Shard {
+    Shard { x: 0, y: 0, width: 40, height: 30 }
+}
+
+#[test]
+fn test_config_defaults_off_at_two_hz() {
+    let config = SnapshotConfig::default();
+    assert!(!config.enabled);
+    assert_eq!(config.refresh_interval(), Duration::from_millis(500));
+}
+
+#[test]
+fn test_most_requested_layers_win_ties_by_key() {
+    let mut counts = RequestCounts::new();
+    assert!(counts.top(2).is_empty());
+    for _ in 0..3 {
+        counts.record("layer/age/Binary");
+    }
+    counts.record("layer/extra-food/Json");
+    counts.record("layer/creature-size/Binary");
+    assert_eq!(counts.top(2), vec!["layer/age/Binary", "layer/creature-size/Binary"]);
+    assert_eq!(counts.top(10).len(), 3);
+    assert!(counts.top(0).is_empty());
+}
+
+#[test]
+fn test_frame_cache_retain() {
+    let cache = FrameCache::new();
+    let other = Shard { x: 40, y: 0, width: 40, height: 30 };
+    cache.store(shard(), "image", 1, Arc::new(vec![1]));
+    cache.store(shard(), "layer/age/Binary", 1, Arc::new(vec![2]));
+    cache.store(other, "image", 1, Arc::new(vec![3]));
+    cache.retain(|s, key| *s == shard() && key == "image");
+    assert!(cache.get(&shard(), "image").is_some());
+    assert!(cache.get(&shard(), "layer/age/Binary").is_none());
+    assert!(cache.get(&other, "image").is_none());
+}
+
+/// Status code and X-Colony-Tick of a GET
+async fn get(path: &str) -> (u16, Option<u64>) {
+    let mut stream = TcpStream::connect(("127.0.0.1", HTTP_PORT)).await.expect("connect");
+    let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path);
+    stream.write_all(request.as_bytes()).await.expect("write");
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).await.expect("read");
+    let response = String::from_utf8_lossy(&response);
+    let status = response[9..12].parse().expect("status");
+    let tick = response.lines()
+        .find_map(|line| line.strip_prefix(&format!("{}: ", COLONY_TICK_HEADER)))
+        .map(|v| v.trim().parse().expect("tick"));
+    (status, tick)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_handlers_only_read_refreshed_buffers() {
+    common::use_temp_output_dir();
+    common::init_colony(18096, &[shard()], SnapshotConfig { enabled: true, ..SnapshotConfig::default() }).await;
+    tokio::spawn(start_http_server(HTTP_PORT));
+    for _ in 0..50 {
+        if TcpStream::connect(("127.0.0.1", HTTP_PORT)).await.is_ok() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    let image_path = format!("/api/shard/{}/image", shard().to_id());
+    let layer_path = format!("/api/shard/{}/layer/age", shard().to_id());
+    assert_eq!(get(&image_path).await, (503, None), "nothing is rendered on demand");
+    // The request makes the layer one of the most requested, so the next refresh renders it
+    assert_eq!(get(&layer_path).await, (503, None));
+
+    let shard_arc = Colony::instance().get_hosted_colony_shard_arc(&shard()).expect("shard hosted");
+    let snapshots = PresentationSnapshots::get_instance();
+    snapshots.refresh(std::slice::from_ref(&shard_arc));
+    let tick = shard_arc.lock().unwrap().get_current_tick();
+    assert_eq!(get(&image_path).await, (200, Some(tick)));
+    assert_eq!(get(&layer_path).await, (200, Some(tick)));
+
+    // Ticking does not change what is served until the next refresh
+    let mut rng = new_seeded_random_generator(5);
+    shard_arc.lock().unwrap().tick(&mut rng);
+    assert_eq!(get(&image_path).await, (200, Some(tick)));
+    snapshots.refresh(std::slice::from_ref(&shard_arc));
+    assert_eq!(get(&image_path).await, (200, Some(tick + 1)));
+
+    let unknown = Shard { x: 40, y: 0, width: 40, height: 30 };
+    assert_eq!(get(&format!("/api/shard/{}/image", unknown.to_id())).await.0, 404);
+}
@@ -0,0 +1,106 @@
+use backend::rate_limiter::{BucketConfig, EndpointClass, RateLimitConfig, RateLimitDecision, RateLimiter};
+use std::net::{IpAddr, Ipv4Addr};
+use std::time::{Duration, Instant};
+
+const CLIENT: IpAddr = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 7));
+const OTHER_CLIENT: IpAddr = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 8));
+const COORDINATOR: IpAddr = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+
+/// Shard data: burst of 5, 10 per second; admin: burst of 2, 1 per second
+fn limiter() -> RateLimiter {
+    RateLimiter::new(RateLimitConfig {
+        enabled: true,
+        shard_data: BucketConfig { burst: 5.0, refill_per_sec: 10.0 },
+        admin: BucketConfig { burst: 2.0, refill_per_sec: 1.0 },
+        allowlist: vec![COORDINATOR],
+    })
+}
+
+fn allowed(limiter: &RateLimiter, ip: IpAddr, class: EndpointClass, now: Instant) -> bool {
+    limiter.check(ip, class, false, now) == RateLimitDecision::Allowed
+}
+
+#[test]
+fn test_burst_then_throttled() {
+    let limiter = limiter();
+    let start = Instant::now();
+    for i in 0..5 {
+        assert!(allowed(&limiter, CLIENT, EndpointClass::ShardData, start), "request {}", i);
+    }
+    match limiter.check(CLIENT, EndpointClass::ShardData, false, start) {
+        // One token is 100ms away at 10 per second
+        RateLimitDecision::Throttled { retry_after } => assert_eq!(retry_after, Duration::from_millis(100)),
+        other => panic!("Expected throttling, got {:?}", other),
+    }
+    assert_eq!(limiter.throttled_count(EndpointClass::ShardData), 1);
+    assert_eq!(limiter.throttled_count(EndpointClass::Admin), 0);
+}
+
+#[test]
+fn test_refill_boundaries() {
+    let limiter = limiter();
+    let start = Instant::now();
+    for _ in 0..5 {
+        assert!(allowed(&limiter, CLIENT, EndpointClass::ShardData, start));
+    }
+    // Just short of one token, then exactly one
+    assert!(!allowed(&limiter, CLIENT, EndpointClass::ShardData, start + Duration::from_millis(99)));
+    assert!(allowed(&limiter, CLIENT, EndpointClass::ShardData, start + Duration::from_millis(100)));
+    assert!(!allowed(&limiter, CLIENT, EndpointClass::ShardData, start + Duration::from_millis(100)));
+
+    // A long idle period refills up to the burst, not beyond it
+    let later = start + Duration::from_secs(60);
+    for _ in 0..5 {
+        assert!(allowed(&limiter, CLIENT, EndpointClass::ShardData, later));
+    }
+    assert!(!allowed(&limiter, CLIENT, EndpointClass::ShardData, later));
+}
+
+#[test]
+fn test_steady_rate_within_limit_is_never_throttled() {
+    let limiter = limiter();
+    let start = Instant::now();
+    // 10 per second for 3 seconds after emptying the burst
+    for _ in 0..5 {
+        assert!(allowed(&limiter, CLIENT, EndpointClass::ShardData, start));
+    }
+    for i in 1..=30 {
+        assert!(allowed(&limiter, CLIENT, EndpointClass::ShardData, start + Duration::from_millis(100 * i)), "request {}", i);
+    }
+    assert_eq!(limiter.throttled_count(EndpointClass::ShardData), 0);
+}
+
+#[test]
+fn test_buckets_are_per_client_and_class() {
+    let limiter = limiter();
+    let now = Instant::now();
+    for _ in 0..2 {
+        assert!(allowed(&limiter, CLIENT, EndpointClass::Admin, now));
+    }
+    assert!(!allowed(&limiter, CLIENT, EndpointClass::Admin, now));
+    // An exhausted admin bucket leaves shard data and other clients alone
+    assert!(allowed(&limiter, CLIENT, EndpointClass::ShardData, now));
+    assert!(allowed(&limiter, OTHER_CLIENT, EndpointClass::Admin, now));
+}
+
+#[test]
+fn test_trusted_clients_bypass() {
+    let limiter = limiter();
+    let now = Instant::now();
+    for _ in 0..100 {
+        assert_eq!(limiter.check(COORDINATOR, EndpointClass::ShardData, false, now), RateLimitDecision::Bypassed);
+        assert_eq!(limiter.check(CLIENT, EndpointClass::Admin, true, now), RateLimitDecision::Bypassed);
+    }
+    assert_eq!(limiter.throttled_count(EndpointClass::ShardData), 0);
+    assert_eq!(limiter.throttled_count(EndpointClass::Admin), 0);
+    // Bypassed requests did not use the client's own bucket
+    assert!(allowed(&limiter, CLIENT, EndpointClass::Admin, now));
+}
+
+#[test]
+fn test_endpoint_classes() {
+    assert_eq!(EndpointClass::for_request("GET /api/shard/0_0_10_10/image HTTP/1.1\r\n"), EndpointClass::ShardData);
+    assert_eq!(EndpointClass::for_request("GET /api/shard/0_0_10_10/layer/Food HTTP/1.1\r\n"), EndpointClass::ShardData);
+    assert_eq!(EndpointClass::for_request("GET /metrics HTTP/1.1\r\n"), EndpointClass::Admin);
+    assert_eq!(EndpointClass::for_request("GET /api/colony-info HTTP/1.1\r\n"), EndpointClass::Admin);
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use backend::colony_shard::ColonyShard;
+use backend::shard_utils::ShardUtils;
+use shared::be_api::{ColonyLifeRules, SeedingOptions, Shard};
+use shared::utils::new_seeded_random_generator;
+use common::RULES;
+
+const SHARD_SIZE: i32 = 64;
+const TICKS: usize = 1500;
+/// Ticks before the population settles into its cycle
+const WARMUP_TICKS: usize = 300;
+const MAX_LAG: usize = 300;
+const RUNS: u64 = 5;
+
+fn interior_creatures(colony_shard: &ColonyShard) -> usize {
+    let row_size = (SHARD_SIZE + 2) as usize;
+    (1..=SHARD_SIZE as usize)
+        .flat_map(|y| (1..=SHARD_SIZE as usize).map(move |x| y * row_size + x))
+        .filter(|idx| colony_shard.grid[*idx].health > 0)
+        .count()
+}
+
+/// creatures_count after every tick, on a shard with 10 food per tick everywhere
+fn creatures_count_series(rules: ColonyLifeRules, seed: u64) -> Vec<f64> {
+    let shard = Shard { x: 0, y: 0, width: SHARD_SIZE, height: SHARD_SIZE };
+    let mut rng = new_seeded_random_generator(seed);
+    let mut colony_shard = ShardUtils::new_colony_shard(&shard, &rules, &SeedingOptions::default(), &mut rng);
+    for cell in colony_shard.grid.iter_mut() {
+        cell.extra_food_per_tick = 10;
+    }
+    (0..TICKS)
+        .map(|_| {
+            colony_shard.tick(&mut rng);
+            interior_creatures(&colony_shard) as f64
+        })
+        .collect()
+}
+
+/// Boom/bust period: the first autocorrelation peak after the autocorrelation turns negative.
+/// None when the series does not oscillate, e.g. a population that only grows.
+fn oscillation_period(series: &[f64]) -> Option<usize> {
+    let series = &series[WARMUP_TICKS..];
+    let mean = series.iter().sum::<f64>() / series.len() as f64;
+    let centered: Vec<f64> = series.iter().map(|v| v - mean).collect();
+    let variance: f64 = centered.iter().map(|v| v * v).sum();
+    if variance == 0.0 {
+        return None;
+    }
+    let acf: Vec<f64> = (0..MAX_LAG)
+        .map(|lag| centered.iter().zip(&centered[lag..]).map(|(a, b)| a * b).sum::<f64>() / variance)
+        .collect();
+    let first_negative = acf.iter().position(|v| *v < 0.0)?;
+    (first_negative + 1..MAX_LAG - 1).find(|&lag| acf[lag] > 0.0 && acf[lag] >= acf[lag - 1] && acf[lag] >= acf[lag + 1])
+}
+
+/// Median period over RUNS seeds, ignoring runs that did not oscillate
+fn median_period(rules: ColonyLifeRules) -> usize {
+    let mut periods: Vec<usize> = (0..RUNS)
+        .filter_map(|seed| oscillation_period(&creatures_count_series(rules, seed)))
+        .collect();
+    assert!(periods.len() as u64 > RUNS / 2, "Too few runs oscillated: {:?}", periods);
+    periods.sort();
+    periods[periods.len() / 2]
+}
+
+#[test]
+fn test_higher_reproduction_cost_lengthens_boom_bust_period() {
+    let cheap = median_period(RULES);
+    let costly = median_period(ColonyLifeRules { reproduction_food_cost: 150, reproduction_min_food: 300, ..RULES });
+    // Around 24 vs 45 ticks
+    assert!(costly as f64 > cheap as f64 * 1.4, "period {} ticks at the higher cost vs {} ticks", costly, cheap);
+}
+
+#[test]
+fn test_no_reproduction_below_min_food() {
+    // Creatures start at 80 health and gain at most ~100 a tick, so in 30 ticks none reaches the threshold
+    let rules = ColonyLifeRules { reproduction_min_food: 10_000, ..RULES };
+    let shard = Shard { x: 0, y: 0, width: SHARD_SIZE, height: SHARD_SIZE };
+    let mut rng = new_seeded_random_generator(7);
+    let mut colony_shard = ShardUtils::new_colony_shard(&shard, &rules, &SeedingOptions::default(), &mut rng);
+    // The whole grid, as creatures in the shadow margin move into the interior
+    let creatures = |colony_shard: &ColonyShard| colony_shard.grid.iter().filter(|cell| cell.health > 0).count();
+    let mut count = creatures(&colony_shard);
+    assert!(count > 0);
+    for _ in 0..30 {
+        colony_shard.tick(&mut rng);
+        let next = creatures(&colony_shard);
+        assert!(next <= count, "creatures grew from {} to {} without reproduction", count, next);
+        count = next;
+    }
+}
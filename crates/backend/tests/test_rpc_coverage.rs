@@ -0,0 +1,34 @@
+use backend::be_server::{call_label, dispatch_request};
+use backend::rpc_metrics::{rpc_kind, RPC_KIND_NAMES};
+use shared::be_api::{enumerate_all_backend_request_variants, enumerate_all_backend_response_variants, BackendRequest};
+
+/// Every request variant has its own rpc_metrics slot, and the slots are all used
+#[test]
+fn test_every_request_has_a_metrics_kind() {
+    let requests = enumerate_all_backend_request_variants();
+    let mut kinds: Vec<usize> = requests.iter().map(rpc_kind).collect();
+    kinds.sort_unstable();
+    assert_eq!(kinds, (0..RPC_KIND_NAMES.len()).collect::<Vec<_>>(),
+        "rpc_kind must map the {} BackendRequest variants onto RPC_KIND_NAMES one to one", requests.len());
+}
+
+/// call_label names every response variant after its request, in the same order
+#[test]
+fn test_every_response_has_a_call_label() {
+    let labels: Vec<&str> = enumerate_all_backend_response_variants().iter().map(call_label).collect();
+    assert_eq!(labels, RPC_KIND_NAMES);
+}
+
+/// The dispatcher routes every request to the handler answering that RPC. Without a colony the
+/// handlers answer right away; InitColony creates one, so it goes last.
+#[tokio::test]
+async fn test_dispatcher_answers_every_request_with_its_response() {
+    let (init_colony, requests): (Vec<BackendRequest>, Vec<BackendRequest>) = enumerate_all_backend_request_variants()
+        .into_iter()
+        .partition(|request| matches!(request, BackendRequest::InitColony(_)));
+    for request in requests.into_iter().chain(init_colony) {
+        let expected = RPC_KIND_NAMES[rpc_kind(&request)];
+        let response = dispatch_request(request).await;
+        assert_eq!(call_label(&response), expected, "{:?}", response);
+    }
+}
@@ -0,0 +1,104 @@
+mod common;
+
+use backend::colony_shard::ColonyShard;
+use backend::shard_stats::ShardStatsSnapshot;
+use backend::shard_topography::ShardTopography;
+use backend::shard_utils::ShardUtils;
+use shared::be_api::{pack_sanctuary_mask, ColonyLifeRules, Color, SeedingOptions, Shard, ShardLayer, StatMetric, Traits};
+use shared::utils::new_seeded_random_generator;
+
+const SHARD_SIZE: i32 = 8;
+
+/// Every creature outside a sanctuary dies on its first tick, and nothing breeds
+const RULES: ColonyLifeRules = ColonyLifeRules {
+    random_death_chance: 1,
+    reproduction_min_food: 10_000,
+    ..common::RULES
+};
+
+fn shard() -> Shard {
+    Shard { x: 0, y: 0, width: SHARD_SIZE, height: SHARD_SIZE }
+}
+
+fn grid_idx(x: i32, y: i32) -> usize {
+    ((y + 1) * (SHARD_SIZE + 2) + x + 1) as usize
+}
+
+/// Extra food 10 everywhere, the left half of the shard a sanctuary
+fn topography_with_sanctuary() -> Vec<u8> {
+    let cells = (SHARD_SIZE * SHARD_SIZE) as usize;
+    let mut data = vec![10u8; cells];
+    data.extend(pack_sanctuary_mask((0..cells).map(|idx| (idx as i32 % SHARD_SIZE) < SHARD_SIZE / 2)));
+    data
+}
+
+/// A shard with the sanctuary topography and a creature on every interior cell
+fn populated_shard() -> ColonyShard {
+    let mut rng = new_seeded_random_generator(1);
+    let mut colony_shard = ShardUtils::new_colony_shard(&shard(), &RULES, &SeedingOptions::default(), &mut rng);
+    ShardTopography::init_shard_topography_from_data(&mut colony_shard, &topography_with_sanctuary()).expect("valid topography");
+    for cell in colony_shard.grid.iter_mut() {
+        cell.health = 0;
+    }
+    let color = Color { red: 200, green: 0, blue: 0 };
+    for y in 0..SHARD_SIZE {
+        for x in 0..SHARD_SIZE {
+            let cell = &mut colony_shard.grid[grid_idx(x, y)];
+            cell.health = 500;
+            cell.color = color;
+            cell.original_color = color;
+            cell.traits = Traits { size: 5, can_kill: false, can_move: false };
+        }
+    }
+    colony_shard
+}
+
+#[test]
+fn test_sanctuary_skips_random_death_only() {
+    let mut colony_shard = populated_shard();
+    let mut rng = new_seeded_random_generator(2);
+    colony_shard.tick(&mut rng);
+
+    for y in 0..SHARD_SIZE {
+        for x in 0..SHARD_SIZE {
+            let alive = colony_shard.grid[grid_idx(x, y)].health > 0;
+            assert_eq!(alive, x < SHARD_SIZE / 2, "creature at ({}, {})", x, y);
+        }
+    }
+
+    // Starvation still applies inside a sanctuary
+    for cell in colony_shard.grid.iter_mut() {
+        cell.food = 0;
+        cell.extra_food_per_tick = 0;
+        cell.health = cell.health.min(1);
+    }
+    colony_shard.tick(&mut rng);
+    assert!(colony_shard.grid.iter().all(|cell| cell.health == 0));
+}
+
+#[test]
+fn test_sanctuary_layer_and_stats() {
+    let colony_shard = populated_shard();
+    let (values, _) = ShardUtils::get_shard_layer(&colony_shard, &shard(), &ShardLayer::Sanctuary).expect("layer");
+    let expected: Vec<i32> = (0..SHARD_SIZE * SHARD_SIZE).map(|idx| (idx % SHARD_SIZE < SHARD_SIZE / 2) as i32).collect();
+    assert_eq!(values, expected);
+
+    let stats = ShardStatsSnapshot::capture(&colony_shard, &shard(), None).expect("snapshot").compute_stats(&[StatMetric::Sanctuary]);
+    let buckets: Vec<(i32, u64)> = stats[0].metrics[0].1.iter().map(|b| (b.value, b.occs)).collect();
+    assert_eq!(buckets, vec![(0, 32), (1, 32)]);
+}
+
+#[test]
+fn test_sanctuary_mask_survives_serialization_and_is_replaced_by_new_terrain() {
+    let mut colony_shard = populated_shard();
+    let bytes = bincode::serialize(&colony_shard).expect("serialize");
+    let restored: ColonyShard = bincode::deserialize(&bytes).expect("deserialize");
+    assert_eq!(restored.sanctuary, colony_shard.sanctuary);
+    assert!(restored.is_sanctuary(grid_idx(0, 0)) && !restored.is_sanctuary(grid_idx(SHARD_SIZE - 1, 0)));
+
+    // Terrain without a mask leaves no sanctuaries; a mask of the wrong size is rejected
+    let cells = (SHARD_SIZE * SHARD_SIZE) as usize;
+    ShardTopography::init_shard_topography_from_data(&mut colony_shard, &vec![10u8; cells]).expect("valid topography");
+    assert!(!colony_shard.is_sanctuary(grid_idx(0, 0)));
+    assert!(ShardTopography::init_shard_topography_from_data(&mut colony_shard, &vec![10u8; cells + 1]).is_err());
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use backend::be_colony_events::{apply_event_to_shards, record_event_on_shards};
+use backend::colony_shard::ColonyShard;
+use backend::shard_storage::ShardStorage;
+use backend::shard_utils::ShardUtils;
+use shared::be_api::{Color, SeedingOptions, Shard, ShardEventRecord, Traits};
+use shared::colony_events::{ColonyEvent, CreateCreatureParams, Ellipse, Region};
+use shared::utils::new_seeded_random_generator;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+use common::RULES;
+
+const SHARD_SIZE: i32 = 10;
+
+fn empty_shard(x: i32) -> ColonyShard {
+    let shard = Shard { x, y: 0, width: SHARD_SIZE, height: SHARD_SIZE };
+    let seeding = SeedingOptions { density: 0.0, ..SeedingOptions::default() };
+    ShardUtils::new_colony_shard(&shard, &RULES, &seeding, &mut new_seeded_random_generator(1))
+}
+
+fn record(tick_applied: u64, event_id: Uuid) -> ShardEventRecord {
+    ShardEventRecord { tick_applied, event_id, event_type: "Extinction".to_string(), cells_affected: 1 }
+}
+
+#[test]
+fn test_event_log_evicts_oldest_first() {
+    let mut shard = empty_shard(0);
+    let ids: Vec<Uuid> = (0..5).map(|_| Uuid::new_v4()).collect();
+    for (tick, id) in ids.iter().enumerate() {
+        shard.record_event(record(tick as u64, *id), 3);
+    }
+
+    let ticks: Vec<u64> = shard.event_log_records(None, 10).iter().map(|r| r.tick_applied).collect();
+    assert_eq!(ticks, vec![4, 3, 2]);
+    assert_eq!(shard.event_log_records(None, 1)[0].event_id, ids[4]);
+    assert_eq!(shard.event_log_records(Some(ids[3]), 10), vec![record(3, ids[3])]);
+    // Evicted records are gone
+    assert!(shard.event_log_records(Some(ids[0]), 10).is_empty());
+}
+
+#[test]
+fn test_only_affected_shards_log_the_event() {
+    let shards = vec![Arc::new(Mutex::new(empty_shard(0))), Arc::new(Mutex::new(empty_shard(100)))];
+    shards[0].lock().unwrap().current_tick = 42;
+    let event_id = Uuid::new_v4();
+    let event = ColonyEvent::CreateCreature(Region::Ellipse(Ellipse { x: 5, y: 5, radius_x: 1, radius_y: 1 }), CreateCreatureParams {
+        color: Color { red: 200, green: 0, blue: 0 },
+        traits: Traits { size: 15, can_kill: false, can_move: false },
+        starting_health: 80,
+    });
+    let effects = apply_event_to_shards(&mut new_seeded_random_generator(5), &shards, &event);
+    record_event_on_shards(&shards, event_id, &event, &effects);
+
+    let near = shards[0].lock().unwrap().event_log_records(None, 10);
+    assert_eq!(near, vec![ShardEventRecord { tick_applied: 42, event_id, event_type: "Create Creature".to_string(), cells_affected: 5 }]);
+    assert!(shards[1].lock().unwrap().event_log.is_empty());
+}
+
+#[test]
+fn test_event_log_survives_a_snapshot() {
+    let mut shard = empty_shard(0);
+    let event_id = Uuid::new_v4();
+    shard.record_event(record(7, event_id), 16);
+    let path = std::env::temp_dir().join(format!("shard_event_log_{}.dat", Uuid::new_v4()));
+    let filename = path.to_str().unwrap();
+    ShardStorage::store_shard(&shard, filename).unwrap();
+
+    let mut restored = empty_shard(0);
+    assert!(ShardStorage::retrieve_shard(&mut restored, filename));
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(restored.event_log_records(None, 10), vec![record(7, event_id)]);
+}
@@ -0,0 +1,141 @@
+mod common;
+
+use backend::colony_shard::{ColonyShard, WHITE_COLOR};
+use backend::shard_utils::ShardUtils;
+use rand::rngs::SmallRng;
+use shared::be_api::{Cell, Color, SeedingOptions, Shard, Traits};
+use shared::shard_render::ImageBackground;
+use shared::utils::new_seeded_random_generator;
+use common::RULES;
+
+const SHARD_SIZE: i32 = 10;
+
+/// Two side by side shards: left at x=0, right at x=SHARD_SIZE
+fn shard_pair(rng: &mut SmallRng) -> Vec<ColonyShard> {
+    [0, SHARD_SIZE].iter().map(|&x| {
+        let shard = Shard { x, y: 0, width: SHARD_SIZE, height: SHARD_SIZE };
+        ShardUtils::new_colony_shard(&shard, &RULES, &SeedingOptions::default(), rng)
+    }).collect()
+}
+
+fn interior_indices(shard: &ColonyShard) -> Vec<usize> {
+    let row_size = (shard.shard.width + 2) as usize;
+    (1..=shard.shard.height as usize)
+        .flat_map(|row| (1..=shard.shard.width as usize).map(move |col| row * row_size + col))
+        .collect()
+}
+
+fn clear(shard: &mut ColonyShard) {
+    for cell in shard.grid.iter_mut() {
+        cell.color = WHITE_COLOR;
+        cell.original_color = WHITE_COLOR;
+        cell.health = 0;
+        cell.age = 0;
+    }
+}
+
+/// Every interior cell holds a long-lived creature that only breeds
+fn populate(shard: &mut ColonyShard) {
+    clear(shard);
+    let red = Color { red: 200, green: 0, blue: 0 };
+    for idx in interior_indices(shard) {
+        let cell: &mut Cell = &mut shard.grid[idx];
+        cell.color = red;
+        cell.original_color = red;
+        cell.health = 500;
+        cell.age = 1;
+        cell.traits = Traits { size: 1, can_kill: false, can_move: false };
+    }
+}
+
+fn creature_count(shard: &ColonyShard) -> usize {
+    ShardUtils::get_shard_image(shard, &shard.shard, ImageBackground::Plain)
+        .expect("Shard image")
+        .iter()
+        .filter(|color| !color.equals(&WHITE_COLOR))
+        .count()
+}
+
+fn image_key(shard: &ColonyShard) -> Vec<(u8, u8, u8)> {
+    ShardUtils::get_shard_image(shard, &shard.shard, ImageBackground::Plain)
+        .expect("Shard image")
+        .iter()
+        .map(|color| (color.red, color.green, color.blue))
+        .collect()
+}
+
+/// One backend tick: every shard ticks (or stays frozen), then borders go to the neighbors
+fn tick_all(shards: &mut [ColonyShard], rng: &mut SmallRng) {
+    let exported: Vec<_> = shards.iter_mut().map(|shard| ShardUtils::tick_and_export(shard, rng)).collect();
+    for req in &exported {
+        for shard in shards.iter_mut() {
+            if ShardUtils::is_adjacent_shard(&req.updated_shard, &shard.shard) {
+                ShardUtils::updated_shard_contents(shard, req);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_freeze_unfreeze_cycles() {
+    let mut rng = new_seeded_random_generator(11);
+    let mut shards = shard_pair(&mut rng);
+    tick_all(&mut shards, &mut rng);
+
+    for cycle in 0..2 {
+        shards[1].frozen = true;
+        let frozen_tick = shards[1].current_tick;
+        let frozen_image = image_key(&shards[1]);
+        let active_tick = shards[0].current_tick;
+
+        for _ in 0..10 {
+            tick_all(&mut shards, &mut rng);
+        }
+        assert_eq!(shards[1].current_tick, frozen_tick, "cycle {}", cycle);
+        assert_eq!(image_key(&shards[1]), frozen_image, "cycle {}", cycle);
+        assert_eq!(shards[0].current_tick, active_tick + 10, "cycle {}", cycle);
+
+        // Unfreezing resumes from the stored tick
+        shards[1].frozen = false;
+        for _ in 0..5 {
+            tick_all(&mut shards, &mut rng);
+        }
+        assert_eq!(shards[1].current_tick, frozen_tick + 5, "cycle {}", cycle);
+    }
+}
+
+#[test]
+fn test_creatures_do_not_enter_frozen_shard() {
+    let mut rng = new_seeded_random_generator(12);
+    let mut shards = shard_pair(&mut rng);
+    populate(&mut shards[0]);
+    clear(&mut shards[1]);
+    shards[1].frozen = true;
+
+    for _ in 0..50 {
+        tick_all(&mut shards, &mut rng);
+    }
+    assert!(creature_count(&shards[0]) > 0);
+    assert_eq!(creature_count(&shards[1]), 0);
+}
+
+#[test]
+fn test_frozen_shard_borders_are_not_applied() {
+    let mut rng = new_seeded_random_generator(13);
+    let mut shards = shard_pair(&mut rng);
+    clear(&mut shards[0]);
+    populate(&mut shards[1]);
+    shards[1].frozen = true;
+
+    for _ in 0..50 {
+        tick_all(&mut shards, &mut rng);
+    }
+    assert_eq!(creature_count(&shards[0]), 0);
+
+    // Once unfrozen the border is live again and creatures cross it
+    shards[1].frozen = false;
+    for _ in 0..20 {
+        tick_all(&mut shards, &mut rng);
+    }
+    assert!(creature_count(&shards[0]) > 0);
+}
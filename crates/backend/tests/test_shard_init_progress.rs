@@ -0,0 +1,58 @@
+mod common;
+
+use backend::colony::Colony;
+use backend::shard_utils::ShardUtils;
+use shared::be_api::{SeedingOptions, Shard};
+use shared::shard_render::ImageBackground;
+use shared::utils::new_seeded_random_generator;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use common::RULES;
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_intermediate_progress_is_observable_while_a_large_shard_initializes() {
+    let shard = Shard { x: 0, y: 0, width: 1000, height: 1000 };
+    let colony = Arc::new(Colony::new(1000, 1000));
+    let observed = Arc::new(Mutex::new(Vec::new()));
+    let done = Arc::new(AtomicBool::new(false));
+
+    // Only runs when the initialization yields, on the same thread
+    let observer = {
+        let (colony, observed, done) = (Arc::clone(&colony), Arc::clone(&observed), Arc::clone(&done));
+        tokio::spawn(async move {
+            while !done.load(Ordering::SeqCst) {
+                let progress = colony.init_progress().get(&shard);
+                if let Some(progress) = progress {
+                    let initializing = colony.init_progress().initializing_shards().iter().any(|(initializing, _)| *initializing == shard);
+                    observed.lock().unwrap().push((progress, initializing));
+                }
+                tokio::task::yield_now().await;
+            }
+        })
+    };
+
+    let mut rng = new_seeded_random_generator(7);
+    ShardUtils::new_colony_shard_in_chunks(&colony, &shard, &RULES, &SeedingOptions::default(), &mut rng).await;
+    done.store(true, Ordering::SeqCst);
+    observer.await.unwrap();
+
+    let observed = observed.lock().unwrap();
+    let intermediate: Vec<f32> = observed.iter().map(|(progress, _)| *progress).filter(|progress| *progress > 0.0 && *progress < 1.0).collect();
+    assert!(intermediate.len() >= 10, "observed {:?}", *observed);
+    assert!(observed.windows(2).all(|pair| pair[0].0 <= pair[1].0), "progress went back: {:?}", *observed);
+    assert!(observed.iter().all(|(progress, initializing)| *initializing == (*progress < 1.0)));
+    assert_eq!(colony.init_progress().get(&shard), Some(1.0));
+}
+
+#[tokio::test]
+async fn test_chunked_initialization_seeds_the_same_shard() {
+    let shard = Shard { x: 1000, y: 0, width: 200, height: 300 };
+    let seeding = SeedingOptions::default();
+
+    let whole = ShardUtils::new_colony_shard(&shard, &RULES, &seeding, &mut new_seeded_random_generator(11));
+    let chunked = ShardUtils::new_colony_shard_in_chunks(&Colony::new(1200, 300), &shard, &RULES, &seeding, &mut new_seeded_random_generator(11)).await;
+
+    let image = |colony_shard| ShardUtils::get_shard_image(colony_shard, &shard, ImageBackground::Plain).unwrap();
+    assert!(image(&whole).iter().zip(image(&chunked).iter()).all(|(a, b)| a.equals(b)));
+    assert_eq!(whole.grid.iter().map(|cell| cell.health as u64).sum::<u64>(), chunked.grid.iter().map(|cell| cell.health as u64).sum::<u64>());
+}
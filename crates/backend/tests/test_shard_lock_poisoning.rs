@@ -0,0 +1,98 @@
+mod common;
+
+use backend::be_ticker::step_ticks;
+use backend::colony::Colony;
+use backend::colony_shard::ColonyShard;
+use backend::http_server::start_http_server;
+use backend::presentation_snapshots::SnapshotConfig;
+use backend::shard_lock::{lock_shard, poisoned_recoveries, quarantined_shard_ids};
+use shared::be_api::{Shard, StepTicksResponse};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+const HTTP_PORT: u16 = 18121;
+const SHARD_SIZE: i32 = 20;
+
+fn shard(col: i32) -> Shard {
+    Shard { x: col * SHARD_SIZE, y: 0, width: SHARD_SIZE, height: SHARD_SIZE }
+}
+
+/// Panics on another thread while holding the shard lock, after applying corrupt to the shard
+fn poison(shard_arc: &Arc<Mutex<ColonyShard>>, corrupt: fn(&mut ColonyShard)) {
+    let shard_arc = Arc::clone(shard_arc);
+    let result = std::thread::spawn(move || {
+        let mut shard = shard_arc.lock().unwrap();
+        corrupt(&mut shard);
+        panic!("injected panic while holding the shard lock");
+    }).join();
+    assert!(result.is_err());
+}
+
+/// Status code and body of a GET
+async fn get(path: &str) -> (u16, String) {
+    let mut stream = TcpStream::connect(("127.0.0.1", HTTP_PORT)).await.expect("connect");
+    let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path);
+    stream.write_all(request.as_bytes()).await.expect("write");
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).await.expect("read");
+    let response = String::from_utf8_lossy(&response);
+    let (header, body) = response.split_once("\r\n\r\n").expect("header terminator");
+    (header[9..12].parse().expect("status"), body.to_string())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_poisoned_shards_are_recovered_or_quarantined() {
+    common::use_temp_output_dir();
+    common::init_colony(18122, &[shard(0), shard(1)], SnapshotConfig::default()).await;
+    tokio::spawn(start_http_server(HTTP_PORT));
+    for _ in 0..50 {
+        if TcpStream::connect(("127.0.0.1", HTTP_PORT)).await.is_ok() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    let colony = Colony::instance();
+    let (consistent, corrupted) = (shard(0), shard(1));
+    let consistent_arc = colony.get_hosted_colony_shard_arc(&consistent).expect("shard hosted");
+    let corrupted_arc = colony.get_hosted_colony_shard_arc(&corrupted).expect("shard hosted");
+
+    // The corrupted shard had a frame rendered before its lock was poisoned
+    let (status, _) = get(&format!("/api/shard/{}/image", corrupted.to_id())).await;
+    assert_eq!(status, 200);
+
+    poison(&consistent_arc, |_| {});
+    poison(&corrupted_arc, |shard| shard.grid.truncate(10));
+    assert!(consistent_arc.is_poisoned() && corrupted_arc.is_poisoned());
+    let consistent_tick = consistent_arc.lock().unwrap_or_else(|e| e.into_inner()).get_current_tick();
+    let corrupted_tick = corrupted_arc.lock().unwrap_or_else(|e| e.into_inner()).get_current_tick();
+
+    assert!(matches!(step_ticks(None, 2).await, StepTicksResponse::Ok { .. }));
+    assert!(!consistent_arc.is_poisoned() && !corrupted_arc.is_poisoned());
+    assert_eq!(poisoned_recoveries(colony), 2);
+    assert!(!lock_shard(&consistent_arc).is_quarantined());
+    assert_eq!(lock_shard(&consistent_arc).get_current_tick(), consistent_tick + 2);
+    let reason = lock_shard(&corrupted_arc).quarantine.clone().expect("corrupted shard quarantined");
+    assert!(reason.contains("grid"), "{}", reason);
+    assert_eq!(lock_shard(&corrupted_arc).get_current_tick(), corrupted_tick, "a quarantined shard does not tick");
+    assert_eq!(quarantined_shard_ids(colony), [corrupted.to_id()]);
+
+    let (status, body) = get(&format!("/api/shard/{}/image", corrupted.to_id())).await;
+    assert_eq!(status, 200, "the last rendered frame is served: {}", body);
+    let (status, body) = get(&format!("/api/shard/{}/layer/age", corrupted.to_id())).await;
+    assert_eq!(status, 503);
+    assert!(body.contains(r#""quarantined":true"#), "{}", body);
+    let (status, _) = get(&format!("/api/shard/{}/image", consistent.to_id())).await;
+    assert_eq!(status, 200);
+
+    let (_, shards) = get("/api/shards").await;
+    assert!(shards.contains(&format!(r#""quarantined":{}"#, serde_json::to_string(&reason).unwrap())), "{}", shards);
+    assert!(shards.contains(r#""quarantined":null"#), "{}", shards);
+    let (_, health) = get("/health").await;
+    assert!(health.contains(r#""status":"degraded""#), "{}", health);
+    assert!(health.contains(&format!(r#""quarantined_shards":["{}"]"#, corrupted.to_id())), "{}", health);
+    let (_, metrics) = get("/metrics").await;
+    assert!(metrics.contains("backend_shard_lock_poisoned_recoveries_total 2"), "{}", metrics);
+    assert!(metrics.contains("backend_quarantined_shards 1"), "{}", metrics);
+}
@@ -0,0 +1,54 @@
+use backend::shard_routing::{misdirected_requests, record_misdirected_request, render_prometheus, route_shard_request, ShardRouting};
+use shared::be_api::Shard;
+use shared::cluster_topology::{ClusterTopology, HostInfo};
+use std::collections::HashMap;
+
+fn this_backend() -> HostInfo {
+    HostInfo::new("10.0.0.2".to_string(), 8084)
+}
+
+fn other_backend() -> HostInfo {
+    HostInfo::new("10.0.0.3".to_string(), 8084)
+}
+
+fn shard(col: i32) -> Shard {
+    Shard { x: col * 100, y: 0, width: 100, height: 100 }
+}
+
+/// Shard 0 on this backend, shard 1 on the other one
+fn topology() -> ClusterTopology {
+    ClusterTopology {
+        coordinator_host: HostInfo::new("10.0.0.1".to_string(), 8082),
+        backend_hosts: vec![this_backend(), other_backend()],
+        shard_to_host: HashMap::from([(shard(0), this_backend()), (shard(1), other_backend())]),
+    }
+}
+
+#[test]
+fn test_hosted_shard_is_served() {
+    assert_eq!(route_shard_request(&shard(0), true, Some(&topology()), &this_backend()), ShardRouting::Hosted);
+}
+
+#[test]
+fn test_shard_known_elsewhere_names_its_host() {
+    assert_eq!(route_shard_request(&shard(1), false, Some(&topology()), &this_backend()), ShardRouting::Elsewhere(other_backend()));
+}
+
+#[test]
+fn test_unknown_shards_get_no_hint() {
+    let topology = topology();
+    // Not a shard of the colony, or one of a different size at the same position
+    assert_eq!(route_shard_request(&shard(5), false, Some(&topology), &this_backend()), ShardRouting::Unknown);
+    assert_eq!(route_shard_request(&Shard { width: 50, ..shard(1) }, false, Some(&topology), &this_backend()), ShardRouting::Unknown);
+    // Assigned here but not initialized yet: pointing the client back here would loop
+    assert_eq!(route_shard_request(&shard(0), false, Some(&topology), &this_backend()), ShardRouting::Unknown);
+    assert_eq!(route_shard_request(&shard(1), false, None, &this_backend()), ShardRouting::Unknown);
+}
+
+#[test]
+fn test_misdirected_requests_are_counted() {
+    let before = misdirected_requests();
+    record_misdirected_request();
+    assert!(misdirected_requests() > before);
+    assert!(render_prometheus().contains("backend_http_misdirected_shard_requests_total"));
+}
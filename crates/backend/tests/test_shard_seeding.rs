@@ -0,0 +1,115 @@
+mod common;
+
+use backend::be_colony_events::apply_local_event;
+use backend::colony_shard::{ColonyShard, WHITE_COLOR};
+use backend::shard_utils::ShardUtils;
+use shared::be_api::{Color, SeedingOptions, SeedingPattern, Shard, Traits};
+use shared::colony_events::{ColonyEvent, CreateCreatureParams, Ellipse, Region};
+use shared::shard_render::ImageBackground;
+use shared::utils::new_seeded_random_generator;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use common::RULES;
+
+const SHARD_SIZE: i32 = 100;
+
+fn seeded_shard(shard: Shard, seeding: SeedingOptions) -> ColonyShard {
+    let mut rng = new_seeded_random_generator(seeding.shard_seed(&shard).expect("Seeding without seed"));
+    ShardUtils::new_colony_shard(&shard, &RULES, &seeding, &mut rng)
+}
+
+fn shard_at(x: i32) -> Shard {
+    Shard { x, y: 0, width: SHARD_SIZE, height: SHARD_SIZE }
+}
+
+/// Shard-relative positions of the creatures
+fn creature_positions(shard: &ColonyShard) -> HashSet<(i32, i32)> {
+    ShardUtils::get_shard_image(shard, &shard.shard, ImageBackground::Plain)
+        .expect("Shard image")
+        .iter()
+        .enumerate()
+        .filter(|(_, color)| !color.equals(&WHITE_COLOR))
+        .map(|(i, _)| ((i as i32) % SHARD_SIZE, (i as i32) / SHARD_SIZE))
+        .collect()
+}
+
+/// Number of 8-connected groups of creatures
+fn count_clusters(positions: &HashSet<(i32, i32)>) -> usize {
+    let mut seen = HashSet::new();
+    let mut clusters = 0;
+    for &start in positions {
+        if !seen.insert(start) {
+            continue;
+        }
+        clusters += 1;
+        let mut stack = vec![start];
+        while let Some((x, y)) = stack.pop() {
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let next = (x + dx, y + dy);
+                    if positions.contains(&next) && seen.insert(next) {
+                        stack.push(next);
+                    }
+                }
+            }
+        }
+    }
+    clusters
+}
+
+#[test]
+fn test_clustered_seeding_produces_requested_clusters() {
+    let seeding = SeedingOptions {
+        density: 1.0,
+        pattern: SeedingPattern::Clustered { num_clusters: 5, cluster_radius: 4 },
+        seed: Some(42),
+    };
+    let shard = seeded_shard(shard_at(0), seeding);
+    let positions = creature_positions(&shard);
+
+    // Overlapping clusters merge, so a few may show up as one
+    let clusters = count_clusters(&positions);
+    assert!((3..=5).contains(&clusters), "{} clusters", clusters);
+    // Each full disc of radius 4 has 49 cells; clipped or merged ones have fewer
+    assert!(positions.len() <= 5 * 49, "{} creatures", positions.len());
+    assert!(positions.len() >= 49, "{} creatures", positions.len());
+
+    // The seed reproduces the shard, and each shard derives its own layout from it
+    assert_eq!(creature_positions(&seeded_shard(shard_at(0), seeding)), positions);
+    assert_ne!(creature_positions(&seeded_shard(shard_at(SHARD_SIZE), seeding)), positions);
+}
+
+#[test]
+fn test_zero_density_shard_is_empty_until_populated() {
+    let seeding = SeedingOptions { density: 0.0, seed: Some(7), ..SeedingOptions::default() };
+    let shard = seeded_shard(shard_at(0), seeding);
+    assert!(creature_positions(&shard).is_empty());
+
+    let region = Region::Ellipse(Ellipse { x: 50, y: 50, radius_x: 10, radius_y: 10 });
+    let event = ColonyEvent::CreateCreature(region.clone(), CreateCreatureParams {
+        color: Color { red: 200, green: 0, blue: 0 },
+        traits: Traits { size: 15, can_kill: false, can_move: false },
+        starting_health: 80,
+    });
+    let shard_arcs = vec![Arc::new(Mutex::new(shard))];
+    apply_local_event(&shard_arcs, &event, &region);
+
+    let positions = creature_positions(&shard_arcs[0].lock().unwrap());
+    assert!(positions.contains(&(50, 50)));
+    assert!(positions.len() > 100, "{} creatures", positions.len());
+}
+
+#[test]
+fn test_edge_and_center_patterns_stay_in_their_region() {
+    let edge = seeded_shard(shard_at(0), SeedingOptions { density: 0.5, pattern: SeedingPattern::EdgeOnly, seed: Some(3) });
+    let band = SHARD_SIZE / 10;
+    let edge_positions = creature_positions(&edge);
+    assert!(!edge_positions.is_empty());
+    assert!(edge_positions.iter().all(|&(x, y)| x < band || y < band || x >= SHARD_SIZE - band || y >= SHARD_SIZE - band));
+
+    let center = seeded_shard(shard_at(0), SeedingOptions { density: 0.5, pattern: SeedingPattern::CenterOnly, seed: Some(3) });
+    let (low, high) = (SHARD_SIZE / 4, SHARD_SIZE - SHARD_SIZE / 4);
+    let center_positions = creature_positions(&center);
+    assert!(!center_positions.is_empty());
+    assert!(center_positions.iter().all(|&(x, y)| x >= low && x < high && y >= low && y < high));
+}
@@ -0,0 +1,125 @@
+mod common;
+
+use backend::colony_shard::{ColonyShard, WHITE_COLOR};
+use backend::shard_stats::ShardStatsSnapshot;
+use backend::shard_utils::ShardUtils;
+use shared::be_api::{Cell, Color, SeedingOptions, Shard, StatBucket, StatMetric, Traits, FOOD_COVERAGE_MIN_FOOD};
+use shared::utils::new_seeded_random_generator;
+use common::RULES;
+
+const SHARD_SIZE: i32 = 4;
+
+fn shard() -> Shard {
+    Shard { x: 0, y: 0, width: SHARD_SIZE, height: SHARD_SIZE }
+}
+
+/// Empty shard with food 3 everywhere and two creatures in the interior
+fn colony_shard() -> ColonyShard {
+    let mut rng = new_seeded_random_generator(7);
+    let mut colony_shard = ShardUtils::new_colony_shard(&shard(), &RULES, &SeedingOptions::default(), &mut rng);
+    for cell in colony_shard.grid.iter_mut() {
+        cell.color = WHITE_COLOR;
+        cell.original_color = WHITE_COLOR;
+        cell.health = 0;
+        cell.food = 3;
+    }
+    let row_size = (SHARD_SIZE + 2) as usize;
+    let red = Color { red: 200, green: 0, blue: 0 };
+    for (idx, health, size) in [(row_size + 1, 10, 2), (2 * row_size + 2, 20, 2)] {
+        let cell = &mut colony_shard.grid[idx];
+        cell.health = health;
+        cell.age = 5;
+        cell.color = red;
+        cell.original_color = red;
+        cell.traits = Traits { size, can_kill: true, can_move: false };
+    }
+    // A creature in the shadow margin belongs to the neighbor and is not counted
+    colony_shard.grid[0].health = 99;
+    colony_shard
+}
+
+fn values(buckets: &[StatBucket]) -> Vec<(i32, u64)> {
+    buckets.iter().map(|bucket| (bucket.value, bucket.occs)).collect()
+}
+
+#[test]
+fn test_stats_from_snapshot() {
+    let snapshot = ShardStatsSnapshot::capture(&colony_shard(), &shard(), None).expect("snapshot");
+    let stats = snapshot.compute_stats(&[
+        StatMetric::Health, StatMetric::Size, StatMetric::CanKill, StatMetric::CanMove,
+        StatMetric::Food, StatMetric::Age, StatMetric::OriginalColor,
+    ]);
+    assert_eq!(stats.len(), 1);
+    let result = &stats[0];
+    assert_eq!(result.shard, shard());
+
+    // Numeric metrics come back in the requested order
+    let metrics: Vec<Vec<(i32, u64)>> = result.metrics.iter().map(|(_, buckets)| values(buckets)).collect();
+    assert_eq!(metrics, vec![
+        vec![(10, 1), (20, 1)], // Health
+        vec![(2, 2)],           // Size
+        vec![(1, 2)],           // CanKill
+        vec![(0, 2)],           // CanMove
+        vec![(3, 16)],          // Food counts empty cells too, all 16 interior cells
+        vec![(5, 2)],           // Age
+    ]);
+
+    let colors = &result.string_metrics[0].1;
+    assert_eq!(colors.iter().map(|bucket| (bucket.value.as_str(), bucket.occs)).collect::<Vec<_>>(), vec![("200_0_0", 2)]);
+}
+
+#[test]
+fn test_snapshot_of_other_shard_is_none() {
+    let other = Shard { x: SHARD_SIZE, ..shard() };
+    assert!(ShardStatsSnapshot::capture(&colony_shard(), &other, None).is_none());
+}
+
+#[test]
+fn test_snapshot_of_region() {
+    // Overhangs the shard: clipped to the 3x3 cells from (1, 1), holding only the second creature
+    let region = Shard { x: 1, y: 1, width: 10, height: 10 };
+    let stats = ShardStatsSnapshot::capture(&colony_shard(), &shard(), Some(&region)).expect("snapshot")
+        .compute_stats(&[StatMetric::Health, StatMetric::Food]);
+    let metrics: Vec<Vec<(i32, u64)>> = stats[0].metrics.iter().map(|(_, buckets)| values(buckets)).collect();
+    assert_eq!(metrics, vec![vec![(20, 1)], vec![(3, 9)]]);
+
+    // Missing the shard is an empty result, not an unavailable shard
+    let outside = Shard { x: SHARD_SIZE, y: 0, width: 2, height: 2 };
+    let stats = ShardStatsSnapshot::capture(&colony_shard(), &shard(), Some(&outside)).expect("snapshot")
+        .compute_stats(&[StatMetric::Health, StatMetric::Food]);
+    assert!(stats[0].metrics.iter().all(|(_, buckets)| buckets.is_empty()));
+}
+
+#[test]
+fn test_occupancy_and_food_coverage_match_brute_force_counts() {
+    let mut rng = new_seeded_random_generator(11);
+    let shard = Shard { x: 0, y: 0, width: 30, height: 20 };
+    let mut colony_shard = ShardUtils::new_colony_shard(&shard, &RULES, &SeedingOptions::default(), &mut rng);
+    for _ in 0..10 {
+        colony_shard.tick(&mut rng);
+    }
+    // Spread the food around the threshold
+    for (idx, cell) in colony_shard.grid.iter_mut().enumerate() {
+        cell.food = (idx as u16 * 7) % (2 * FOOD_COVERAGE_MIN_FOOD);
+    }
+
+    let interior: Vec<&Cell> = (1..=shard.height as usize)
+        .flat_map(|row| {
+            let start = row * (shard.width as usize + 2) + 1;
+            colony_shard.grid[start..start + shard.width as usize].iter()
+        })
+        .collect();
+    let occupied = interior.iter().filter(|cell| cell.health > 0).count() as u64;
+    let covered = interior.iter().filter(|cell| cell.food > FOOD_COVERAGE_MIN_FOOD).count() as u64;
+    let total = interior.len() as u64;
+    assert!(occupied > 0 && occupied < total, "the seeded shard should be partly occupied");
+    assert!(covered > 0 && covered < total);
+
+    let stats = ShardStatsSnapshot::capture(&colony_shard, &shard, None).expect("snapshot")
+        .compute_stats(&[StatMetric::Occupancy, StatMetric::FoodCoverage]);
+    let metrics: Vec<Vec<(i32, u64)>> = stats[0].metrics.iter().map(|(_, buckets)| values(buckets)).collect();
+    assert_eq!(metrics, vec![
+        vec![(0, total - occupied), (1, occupied)],
+        vec![(0, total - covered), (1, covered)],
+    ]);
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use backend::backend_config;
+use backend::be_server::dispatch_request;
+use backend::colony::Colony;
+use shared::be_api::{
+    BackendRequest, BackendResponse, InitColonyRequest, InitColonyShardRequest, InitColonyShardResponse,
+    InitShardTopographyRequest, InitShardTopographyResponse, SeedingOptions, Shard, StartTickingRequest, StartTickingResponse,
+};
+use shared::cluster_topology::{ClusterTopology, HostInfo};
+use std::collections::HashMap;
+use std::sync::Once;
+use common::RULES;
+
+const SHARD_SIZE: i32 = 10;
+
+// The colony and topology are process-wide, so the tests take turns
+static BACKEND_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+static BACKEND_CONFIG: Once = Once::new();
+
+fn this_backend() -> HostInfo {
+    HostInfo::new("127.0.0.1".to_string(), 18082)
+}
+
+fn shard(col: i32) -> Shard {
+    Shard { x: col * SHARD_SIZE, y: 0, width: SHARD_SIZE, height: SHARD_SIZE }
+}
+
+/// A 3x1 colony, every shard on this backend
+fn topology() -> ClusterTopology {
+    ClusterTopology {
+        coordinator_host: HostInfo::new("127.0.0.1".to_string(), 18083),
+        backend_hosts: vec![this_backend()],
+        shard_to_host: (0..3).map(|col| (shard(col), this_backend())).collect::<HashMap<_, _>>(),
+    }
+}
+
+async fn init_colony() {
+    BACKEND_CONFIG.call_once(|| {
+        backend_config::set_backend_hostname(this_backend().hostname);
+        backend_config::set_backend_port(this_backend().port);
+    });
+    dispatch_request(BackendRequest::InitColony(InitColonyRequest { width: 3 * SHARD_SIZE, height: SHARD_SIZE, colony_life_rules: RULES })).await;
+}
+
+async fn init_shard(shard: Shard, topography_data: Option<Vec<u8>>, awaiting_topography: bool) -> InitColonyShardResponse {
+    let request = BackendRequest::InitColonyShard(InitColonyShardRequest {
+        shard,
+        colony_life_rules: RULES,
+        topology: Some(topology()),
+        seeding: SeedingOptions::default(),
+        topography_data,
+        awaiting_topography,
+        colony_instance_id: None,
+        lease_epoch: 1,
+    });
+    match dispatch_request(request).await {
+        BackendResponse::InitColonyShard(response) => response,
+        other => panic!("Unexpected response {:?}", other),
+    }
+}
+
+async fn start_ticking() -> StartTickingResponse {
+    match dispatch_request(BackendRequest::StartTicking(StartTickingRequest { leases: Vec::new(), fast_forward: None })).await {
+        BackendResponse::StartTicking(response) => response,
+        other => panic!("Unexpected response {:?}", other),
+    }
+}
+
+/// (awaiting_topography, extra food of the first interior cell)
+fn shard_state(shard: &Shard) -> Option<(bool, u8)> {
+    let shard_arc = Colony::instance().get_hosted_colony_shard_arc(shard)?;
+    let colony_shard = shard_arc.lock().unwrap();
+    let first_interior = (SHARD_SIZE + 2 + 1) as usize;
+    Some((colony_shard.awaiting_topography, colony_shard.grid[first_interior].extra_food_per_tick))
+}
+
+#[tokio::test]
+async fn test_single_call_init_with_topography() {
+    let _lock = BACKEND_LOCK.lock().await;
+    init_colony().await;
+
+    let payload = vec![7u8; (SHARD_SIZE * SHARD_SIZE) as usize];
+    assert!(matches!(init_shard(shard(0), Some(payload), false).await, InitColonyShardResponse::Ok));
+    assert_eq!(shard_state(&shard(0)), Some((false, 7)));
+}
+
+#[tokio::test]
+async fn test_single_call_rejects_mismatched_topography() {
+    let _lock = BACKEND_LOCK.lock().await;
+    init_colony().await;
+
+    match init_shard(shard(1), Some(vec![7u8; 5]), false).await {
+        InitColonyShardResponse::InvalidTopography(e) => assert!(e.contains("size mismatch"), "{}", e),
+        other => panic!("Expected InvalidTopography, got {:?}", other),
+    }
+    // The shard is not created with half its state
+    assert_eq!(shard_state(&shard(1)), None);
+}
+
+#[tokio::test]
+async fn test_two_phase_init_holds_ticking_until_topography_arrives() {
+    let _lock = BACKEND_LOCK.lock().await;
+    init_colony().await;
+
+    assert!(matches!(init_shard(shard(2), None, true).await, InitColonyShardResponse::Ok));
+    assert_eq!(shard_state(&shard(2)).map(|(awaiting, _)| awaiting), Some(true));
+    match start_ticking().await {
+        StartTickingResponse::AwaitingTopography(shards) => assert_eq!(shards, vec![shard(2)]),
+        other => panic!("Expected AwaitingTopography, got {:?}", other),
+    }
+
+    let request = BackendRequest::InitShardTopography(InitShardTopographyRequest {
+        shard: shard(2),
+        topography_data: vec![9u8; (SHARD_SIZE * SHARD_SIZE) as usize],
+    });
+    assert!(matches!(dispatch_request(request).await, BackendResponse::InitShardTopography(InitShardTopographyResponse::Ok)));
+    assert_eq!(shard_state(&shard(2)), Some((false, 9)));
+    assert!(matches!(start_ticking().await, StartTickingResponse::Ok));
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use backend::colony::Colony;
+use backend::simulation::{SimulationConfig, SimulationHandle};
+use shared::be_api::{SeedingOptions, Shard, StatMetric};
+use common::RULES;
+
+const SHARD_SIZE: i32 = 16;
+
+fn shard(col: i32) -> Shard {
+    Shard { x: col * SHARD_SIZE, y: 0, width: SHARD_SIZE, height: SHARD_SIZE }
+}
+
+/// Two shards side by side, seeded
+fn simulation(seed: u64) -> SimulationHandle {
+    let mut simulation = SimulationHandle::init(SimulationConfig {
+        width: 2 * SHARD_SIZE,
+        height: SHARD_SIZE,
+        seeding: SeedingOptions { seed: Some(seed), ..SeedingOptions::default() },
+    }).unwrap();
+    simulation.add_shard(shard(1), RULES, None).unwrap();
+    simulation.add_shard(shard(0), RULES, None).unwrap();
+    simulation
+}
+
+#[test]
+fn test_ticks_without_the_process_wide_colony() {
+    let mut simulation = simulation(3);
+    simulation.tick(30);
+    assert!(!Colony::is_initialized());
+    assert_eq!(simulation.shards(), [shard(0), shard(1)]);
+    assert_eq!(simulation.current_tick(&shard(0)), Some(30));
+    assert_eq!(simulation.current_tick(&shard(1)), Some(30));
+
+    let image = simulation.image(&shard(0)).unwrap();
+    assert_eq!(image.len(), (SHARD_SIZE * SHARD_SIZE) as usize);
+    let stats = simulation.stats(&shard(0), &[StatMetric::Occupancy]).unwrap();
+    let cells: u64 = stats.metrics[0].1.iter().map(|bucket| bucket.occs).sum();
+    assert_eq!(cells, (SHARD_SIZE * SHARD_SIZE) as u64);
+
+    let outside = Shard { x: 2 * SHARD_SIZE, y: 0, width: SHARD_SIZE, height: SHARD_SIZE };
+    assert!(simulation.image(&outside).is_none());
+    assert!(simulation.add_shard(outside, RULES, None).unwrap_err().contains("outside"));
+    assert!(simulation.add_shard(shard(0), RULES, None).unwrap_err().contains("already"));
+}
+
+fn rgb(simulation: &SimulationHandle, shard: &Shard) -> Vec<(u8, u8, u8)> {
+    simulation.image(shard).unwrap().iter().map(|color| (color.red, color.green, color.blue)).collect()
+}
+
+#[test]
+fn test_seeded_runs_repeat() {
+    let (mut first, mut second) = (simulation(5), simulation(5));
+    first.tick(40);
+    second.tick(40);
+    for shard in [shard(0), shard(1)] {
+        assert_eq!(rgb(&first, &shard), rgb(&second, &shard));
+    }
+}
@@ -0,0 +1,139 @@
+mod common;
+
+use backend::colony_shard::ColonyShard;
+use backend::shard_storage::{ShardStorage, SNAPSHOT_FORMAT_VERSION, SNAPSHOT_MAGIC};
+use backend::shard_utils::ShardUtils;
+use backend::snapshot_inspect::{creature_count, parse_args, run, InspectCommand};
+use shared::be_api::{SeedingOptions, Shard};
+use shared::storage::StorageUtils;
+use shared::utils::new_seeded_random_generator;
+use std::path::PathBuf;
+use uuid::Uuid;
+use common::{legacy_shard_body, RULES};
+
+fn seeded_shard() -> ColonyShard {
+    let shard = Shard { x: 20, y: 10, width: 12, height: 8 };
+    let mut colony_shard = ShardUtils::new_colony_shard(&shard, &RULES, &SeedingOptions::default(), &mut new_seeded_random_generator(3));
+    let mut rng = new_seeded_random_generator(3);
+    for _ in 0..5 {
+        colony_shard.tick(&mut rng);
+    }
+    colony_shard
+}
+
+fn temp_file(extension: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("snapshot_inspect_{}.{}", Uuid::new_v4(), extension))
+}
+
+fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+fn run_to_string(filename: &str, command: &InspectCommand) -> Result<String, String> {
+    let snapshot = ShardStorage::load_snapshot(filename)?;
+    let mut out = Vec::new();
+    run(&snapshot, command, &mut out)?;
+    Ok(String::from_utf8(out).unwrap())
+}
+
+#[test]
+fn test_reads_current_and_legacy_snapshots() {
+    let shard = seeded_shard();
+    let current = temp_file("dat");
+    let legacy = temp_file("dat");
+    ShardStorage::store_shard(&shard, current.to_str().unwrap()).unwrap();
+    StorageUtils::store_bytes_with_checksum(legacy_shard_body(&shard), legacy.to_str().unwrap()).unwrap();
+
+    let loaded_current = ShardStorage::load_snapshot(current.to_str().unwrap());
+    let loaded_legacy = ShardStorage::load_snapshot(legacy.to_str().unwrap());
+    let _ = std::fs::remove_file(&current);
+    let _ = std::fs::remove_file(&legacy);
+
+    for (snapshot, format_version) in [(loaded_current.unwrap(), SNAPSHOT_FORMAT_VERSION), (loaded_legacy.unwrap(), 0)] {
+        assert_eq!(snapshot.format_version, format_version);
+        assert_eq!(snapshot.shard.shard, shard.shard);
+        assert_eq!(snapshot.shard.current_tick, shard.current_tick);
+        assert_eq!(snapshot.shard.state_hash(), shard.state_hash());
+    }
+}
+
+#[test]
+fn test_refuses_newer_format_versions() {
+    let path = temp_file("dat");
+    let mut data = SNAPSHOT_MAGIC.to_vec();
+    data.extend_from_slice(&(SNAPSHOT_FORMAT_VERSION + 1).to_le_bytes());
+    data.extend_from_slice(&bincode::serialize(&seeded_shard()).unwrap());
+    StorageUtils::store_bytes_with_checksum(data, path.to_str().unwrap()).unwrap();
+
+    let result = ShardStorage::load_snapshot(path.to_str().unwrap());
+    let _ = std::fs::remove_file(&path);
+    let err = result.err().unwrap();
+    assert!(err.contains(&format!("format version {}", SNAPSHOT_FORMAT_VERSION + 1)) && err.contains("newer build"), "{}", err);
+}
+
+#[test]
+fn test_info_and_layer() {
+    let shard = seeded_shard();
+    let path = temp_file("dat");
+    let filename = path.to_str().unwrap().to_string();
+    ShardStorage::store_shard(&shard, &filename).unwrap();
+
+    let (snapshot_arg, info) = parse_args(&args(&[&filename, "info"])).unwrap();
+    assert_eq!(snapshot_arg, filename);
+    let info = run_to_string(&filename, &info);
+    let (_, layer) = parse_args(&args(&[&filename, "layer", "health"])).unwrap();
+    let layer = run_to_string(&filename, &layer);
+    let count = creature_count(&ShardStorage::load_snapshot(&filename).unwrap());
+    let _ = std::fs::remove_file(&path);
+
+    let info = info.unwrap();
+    assert!(info.contains("Tick: 5"), "{}", info);
+    assert!(info.contains(&format!("Creatures: {} of 96 cells", count)), "{}", info);
+    assert!(count > 0);
+    assert!(info.contains("Topography: version 0, extra food 50..50"), "{}", info);
+    assert!(info.contains("Biome rules: 0 stored, matches this build"), "{}", info);
+
+    let layer = layer.unwrap();
+    let rows: Vec<&str> = layer.lines().collect();
+    assert_eq!(rows.len(), 8);
+    assert!(rows.iter().all(|row| row.split(',').count() == 12), "{}", layer);
+}
+
+#[test]
+fn test_render_and_cell() {
+    let shard = seeded_shard();
+    let path = temp_file("dat");
+    let png = temp_file("png");
+    let filename = path.to_str().unwrap().to_string();
+    ShardStorage::store_shard(&shard, &filename).unwrap();
+
+    let (_, render) = parse_args(&args(&[&filename, "render", "--out", png.to_str().unwrap()])).unwrap();
+    let rendered = run_to_string(&filename, &render);
+    let (_, cell) = parse_args(&args(&[&filename, "cell", "25", "12"])).unwrap();
+    let cell = run_to_string(&filename, &cell);
+    let (_, outside) = parse_args(&args(&[&filename, "cell", "5", "12"])).unwrap();
+    let outside = run_to_string(&filename, &outside);
+    let image = image::open(&png).map(|image| (image.width(), image.height()));
+    let _ = std::fs::remove_file(&path);
+    let _ = std::fs::remove_file(&png);
+
+    rendered.unwrap();
+    assert_eq!(image.unwrap(), (12, 8));
+    let cell = cell.unwrap();
+    assert!(cell.starts_with("Cell 25,12 (local 5,2) of shard"), "{}", cell);
+    assert!(cell.contains("Sanctuary:"), "{}", cell);
+    assert!(outside.unwrap_err().contains("outside shard"));
+}
+
+#[test]
+fn test_bad_arguments() {
+    assert!(parse_args(&args(&["shard.dat"])).is_err());
+    assert!(parse_args(&args(&["shard.dat", "explode"])).unwrap_err().contains("explode"));
+    assert!(parse_args(&args(&["shard.dat", "render"])).is_err());
+    assert!(parse_args(&args(&["shard.dat", "layer", "weather"])).is_err());
+    assert!(parse_args(&args(&["shard.dat", "layer", "--out", "x.csv"])).is_err());
+    assert!(parse_args(&args(&["shard.dat", "cell", "3"])).is_err());
+    assert!(parse_args(&args(&["shard.dat", "cell", "3", "y"])).unwrap_err().contains("'y'"));
+    assert!(matches!(parse_args(&args(&["shard.dat", "layer", "food", "--out", "food.csv"])),
+                     Ok((_, InspectCommand::Layer { out: Some(_), .. }))));
+}
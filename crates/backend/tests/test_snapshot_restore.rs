@@ -0,0 +1,144 @@
+mod common;
+
+use backend::colony_shard::ColonyShard;
+use backend::shard_storage::{ShardStorage, SnapshotTopography, SNAPSHOT_FORMAT_VERSION, SNAPSHOT_MAGIC};
+use backend::shard_topography::ShardTopography;
+use backend::shard_utils::ShardUtils;
+use shared::be_api::{SeedingOptions, Shard};
+use shared::colony_model::{Biome, ColonyLifeRulesOverride};
+use shared::storage::StorageUtils;
+use shared::utils::new_seeded_random_generator;
+use std::path::PathBuf;
+use uuid::Uuid;
+use common::{legacy_rules_body, legacy_shard_body, RULES};
+
+const WIDTH: i32 = 16;
+const HEIGHT: i32 = 12;
+
+fn temp_file() -> PathBuf {
+    std::env::temp_dir().join(format!("snapshot_restore_{}.dat", Uuid::new_v4()))
+}
+
+/// Varied extra food with water in the first column, and a sanctuary mask over the top rows
+fn topography(seed: u8) -> Vec<u8> {
+    let cells = (WIDTH * HEIGHT) as usize;
+    let mut data: Vec<u8> = (0..cells).map(|idx| if idx % WIDTH as usize == 0 { 0 } else { (idx as u8).wrapping_mul(seed) % 40 + 1 }).collect();
+    data.extend((0..cells.div_ceil(8)).map(|byte| if byte < 4 { 0xff } else { 0 }));
+    data
+}
+
+/// A shard on terrain with sanctuaries and a harsh biome, with a terrain reload still queued
+fn shard_with_terrain() -> ColonyShard {
+    let shard = Shard { x: 32, y: 24, width: WIDTH, height: HEIGHT };
+    let mut colony_shard = ShardUtils::new_colony_shard(&shard, &RULES, &SeedingOptions::default(), &mut new_seeded_random_generator(5));
+    ShardTopography::init_shard_topography_from_data(&mut colony_shard, &topography(7)).expect("valid topography");
+    colony_shard.set_biomes(&[Biome {
+        name: "harsh".to_string(),
+        x: 40,
+        y: 24,
+        width: 8,
+        height: 12,
+        overrides: ColonyLifeRulesOverride { health_cost_per_size_unit: Some(4), ..Default::default() },
+    }]);
+    let mut rng = new_seeded_random_generator(5);
+    for _ in 0..5 {
+        colony_shard.tick(&mut rng);
+    }
+    ShardTopography::queue_topography(&mut colony_shard, topography(11)).expect("valid topography");
+    colony_shard
+}
+
+fn store_and_load(colony_shard: &ColonyShard) -> backend::shard_storage::ShardSnapshot {
+    let path = temp_file();
+    ShardStorage::store_shard(colony_shard, path.to_str().unwrap()).unwrap();
+    let snapshot = ShardStorage::load_snapshot(path.to_str().unwrap());
+    let _ = std::fs::remove_file(&path);
+    snapshot.unwrap()
+}
+
+#[test]
+fn test_restored_shard_ticks_identically() {
+    let mut original = shard_with_terrain();
+    let snapshot = store_and_load(&original);
+    assert_eq!(snapshot.format_version, SNAPSHOT_FORMAT_VERSION);
+    let mut restored = snapshot.shard;
+
+    assert_eq!(restored.sanctuary, original.sanctuary);
+    assert_eq!(restored.biome_rules, original.biome_rules);
+    assert_eq!(restored.topography_version, original.topography_version);
+    assert_eq!(restored.pending_topography, original.pending_topography);
+    assert_eq!(snapshot.rules.expect("stored since version 2").biome_rules, original.biome_rules);
+
+    // The queued terrain is applied on the first tick of both
+    let (mut original_rng, mut restored_rng) = (new_seeded_random_generator(9), new_seeded_random_generator(9));
+    for tick in 0..100 {
+        ShardUtils::tick_and_export(&mut original, &mut original_rng);
+        ShardUtils::tick_and_export(&mut restored, &mut restored_rng);
+        assert_eq!(restored.state_hash(), original.state_hash(), "diverged at tick {}", tick);
+    }
+    assert_eq!(restored.topography_version, original.topography_version);
+    assert_eq!(ShardTopography::export_topography(&restored), ShardTopography::export_topography(&original));
+}
+
+#[test]
+fn test_terrain_is_restored_from_the_snapshot_payload() {
+    let original = shard_with_terrain();
+    let snapshot = store_and_load(&original);
+    assert_eq!(snapshot.topography.current, ShardTopography::export_topography(&original));
+    assert_eq!(snapshot.topography.pending, Some(topography(11)));
+    assert!(snapshot.shard.grid.iter().zip(original.grid.iter()).all(|(a, b)| a.extra_food_per_tick == b.extra_food_per_tick));
+}
+
+#[test]
+fn test_version_1_snapshots_still_load() {
+    let original = shard_with_terrain();
+    let path = temp_file();
+    let mut data = SNAPSHOT_MAGIC.to_vec();
+    data.extend_from_slice(&1u32.to_le_bytes());
+    data.extend_from_slice(&legacy_shard_body(&original));
+    StorageUtils::store_bytes_with_checksum(data, path.to_str().unwrap()).unwrap();
+
+    let snapshot = ShardStorage::load_snapshot(path.to_str().unwrap());
+    let _ = std::fs::remove_file(&path);
+    let snapshot = snapshot.unwrap();
+    assert_eq!(snapshot.format_version, 1);
+    assert_eq!(snapshot.shard.state_hash(), original.state_hash());
+    assert_eq!(snapshot.shard.biome_rules, original.biome_rules);
+    // Version 1 kept neither the queued terrain nor the rules
+    assert_eq!(snapshot.topography.pending, None);
+    assert!(snapshot.rules.is_none());
+}
+
+#[test]
+fn test_version_2_snapshots_load_without_hunger_or_the_new_rules() {
+    let mut original = shard_with_terrain();
+    let path = temp_file();
+    let mut data = SNAPSHOT_MAGIC.to_vec();
+    data.extend_from_slice(&2u32.to_le_bytes());
+    data.extend_from_slice(&legacy_shard_body(&original));
+    let topography = SnapshotTopography {
+        version: original.topography_version,
+        current: ShardTopography::export_topography(&original),
+        pending: original.pending_topography.clone(),
+    };
+    data.extend_from_slice(&bincode::serialize(&topography).unwrap());
+    data.extend_from_slice(&legacy_rules_body(&original));
+    StorageUtils::store_bytes_with_checksum(data, path.to_str().unwrap()).unwrap();
+
+    let snapshot = ShardStorage::load_snapshot(path.to_str().unwrap());
+    let _ = std::fs::remove_file(&path);
+    let snapshot = snapshot.unwrap();
+    assert_eq!(snapshot.format_version, 2);
+    assert_eq!(snapshot.shard.colony_life_rules, original.colony_life_rules);
+    assert_eq!(snapshot.shard.biomes, original.biomes);
+    assert_eq!(snapshot.rules.expect("stored since version 2").biome_rules, original.biome_rules);
+
+    // Without the new rules the old shard never got hungry, so it ticks on as before
+    let mut restored = snapshot.shard;
+    let (mut original_rng, mut restored_rng) = (new_seeded_random_generator(9), new_seeded_random_generator(9));
+    for tick in 0..50 {
+        ShardUtils::tick_and_export(&mut original, &mut original_rng);
+        ShardUtils::tick_and_export(&mut restored, &mut restored_rng);
+        assert_eq!(restored.state_hash(), original.state_hash(), "diverged at tick {}", tick);
+    }
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use backend::colony_shard::ColonyShard;
+use backend::shard_utils::ShardUtils;
+use shared::be_api::{SeedingOptions, Shard};
+use shared::utils::new_seeded_random_generator;
+use common::RULES;
+
+const SHARD_SIZE: i32 = 20;
+
+fn seeded_shard(seed: u64) -> ColonyShard {
+    let shard = Shard { x: 0, y: 0, width: SHARD_SIZE, height: SHARD_SIZE };
+    ShardUtils::new_colony_shard(&shard, &RULES, &SeedingOptions::default(), &mut new_seeded_random_generator(seed))
+}
+
+/// Hashes recorded over ticks driven by a seeded generator
+fn run(seed: u64, ticks: usize) -> Vec<u64> {
+    let mut colony_shard = seeded_shard(seed);
+    let mut rng = new_seeded_random_generator(seed);
+    for _ in 0..ticks {
+        colony_shard.tick(&mut rng);
+        colony_shard.record_state_hash(ticks);
+    }
+    colony_shard.state_hashes.iter().map(|entry| entry.hash).collect()
+}
+
+#[test]
+fn test_replayed_runs_hash_identically_and_different_seeds_diverge() {
+    assert_eq!(run(7, 30), run(7, 30));
+    assert_ne!(run(7, 30), run(8, 30));
+}
+
+#[test]
+fn test_history_keeps_the_last_ticks() {
+    let mut colony_shard = seeded_shard(3);
+    let mut rng = new_seeded_random_generator(3);
+    for _ in 0..10 {
+        colony_shard.tick(&mut rng);
+        colony_shard.record_state_hash(4);
+    }
+    let ticks: Vec<u64> = colony_shard.state_hashes.iter().map(|entry| entry.tick).collect();
+    assert_eq!(ticks, vec![7, 8, 9, 10]);
+}
+
+#[test]
+fn test_hash_covers_owned_cells_only() {
+    let mut colony_shard = seeded_shard(5);
+    let before = colony_shard.state_hash();
+
+    // Shadow margin cells mirror the neighbors and must not change the hash
+    colony_shard.grid[0].food = colony_shard.grid[0].food.wrapping_add(1);
+    let last = colony_shard.grid.len() - 1;
+    colony_shard.grid[last].age = colony_shard.grid[last].age.wrapping_add(1);
+    assert_eq!(colony_shard.state_hash(), before);
+
+    // The first owned cell sits one row and one column into the grid
+    let owned = (SHARD_SIZE + 2) as usize + 1;
+    colony_shard.grid[owned].food = colony_shard.grid[owned].food.wrapping_add(1);
+    assert_ne!(colony_shard.state_hash(), before);
+}
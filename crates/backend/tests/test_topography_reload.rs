@@ -0,0 +1,189 @@
+mod common;
+
+use backend::backend_config;
+use backend::be_server::dispatch_request;
+use backend::colony::Colony;
+use backend::colony_shard::{is_blank, ColonyShard};
+use backend::shard_topography::ShardTopography;
+use backend::shard_utils::ShardUtils;
+use shared::be_api::{
+    pack_sanctuary_mask, BackendRequest, BackendResponse, ColonyLifeRules, Color, InitColonyRequest, InitColonyShardRequest,
+    InitColonyShardResponse, InitShardTopographyRequest, InitShardTopographyResponse, SeedingOptions, Shard, Traits,
+    WATER_TOPOGRAPHY_VALUE,
+};
+use shared::cluster_topology::{ClusterTopology, HostInfo};
+use shared::utils::{new_random_generator, new_seeded_random_generator};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+const SHARD_SIZE: i32 = 8;
+const FOOD: u16 = 77;
+
+/// Creatures barely die at random and never breed
+const RULES: ColonyLifeRules = ColonyLifeRules {
+    health_cost_per_size_unit: 1,
+    eat_capacity_per_size_unit: 1,
+    health_cost_if_can_kill: 0,
+    health_cost_if_can_move: 0,
+    random_death_chance: 1_000_000,
+    reproduction_min_food: 10_000,
+    ..common::RULES
+};
+
+fn shard() -> Shard {
+    Shard { x: 0, y: 0, width: SHARD_SIZE, height: SHARD_SIZE }
+}
+
+fn grid_idx(x: i32, y: i32) -> usize {
+    ((y + 1) * (SHARD_SIZE + 2) + x + 1) as usize
+}
+
+/// Extra food 10 everywhere except the first water_columns columns, which are water
+fn topography(water_columns: i32) -> Vec<u8> {
+    (0..SHARD_SIZE * SHARD_SIZE)
+        .map(|idx| if idx % SHARD_SIZE < water_columns { WATER_TOPOGRAPHY_VALUE } else { 10 })
+        .collect()
+}
+
+fn place_creature(colony_shard: &mut ColonyShard, x: i32, y: i32) {
+    let color = Color { red: 200, green: 0, blue: 0 };
+    let cell = &mut colony_shard.grid[grid_idx(x, y)];
+    cell.health = 500;
+    cell.color = color;
+    cell.original_color = color;
+    cell.traits = Traits { size: 1, can_kill: false, can_move: false };
+}
+
+/// Terrain without water, a sanctuary everywhere and a creature on every interior cell
+fn populated_shard() -> ColonyShard {
+    let mut rng = new_seeded_random_generator(1);
+    let mut colony_shard = ShardUtils::new_colony_shard(&shard(), &RULES, &SeedingOptions::default(), &mut rng);
+    let mut initial = topography(0);
+    initial.extend(pack_sanctuary_mask((0..SHARD_SIZE * SHARD_SIZE).map(|_| true)));
+    ShardTopography::init_shard_topography_from_data(&mut colony_shard, &initial).expect("valid topography");
+    for y in 0..SHARD_SIZE {
+        for x in 0..SHARD_SIZE {
+            place_creature(&mut colony_shard, x, y);
+            colony_shard.grid[grid_idx(x, y)].food = FOOD;
+        }
+    }
+    colony_shard.current_tick = 10;
+    colony_shard
+}
+
+#[test]
+fn test_reload_keeps_creatures_except_on_new_water() {
+    let mut colony_shard = populated_shard();
+    assert_eq!(colony_shard.topography_version, 1);
+
+    ShardTopography::queue_topography(&mut colony_shard, topography(1)).expect("valid topography");
+    // Nothing changes until the queued terrain is applied
+    assert_eq!(colony_shard.grid[grid_idx(0, 0)].extra_food_per_tick, 10);
+    assert_eq!(colony_shard.topography_version, 1);
+
+    assert_eq!(ShardTopography::apply_pending_topography(&mut colony_shard), Some(SHARD_SIZE as u64));
+    assert_eq!(ShardTopography::apply_pending_topography(&mut colony_shard), None);
+    assert_eq!(colony_shard.topography_version, 2);
+    // The payload had no mask, so the sanctuaries are gone
+    assert!(colony_shard.sanctuary.is_empty());
+    for y in 0..SHARD_SIZE {
+        let water = &colony_shard.grid[grid_idx(0, y)];
+        assert!(is_blank(water));
+        assert_eq!((water.food, water.extra_food_per_tick), (0, WATER_TOPOGRAPHY_VALUE));
+        for x in 1..SHARD_SIZE {
+            let land = &colony_shard.grid[grid_idx(x, y)];
+            assert_eq!((land.health, land.food, land.extra_food_per_tick), (500, FOOD, 10), "cell ({}, {})", x, y);
+        }
+    }
+
+    // Only cells that turn to water drown their creatures, not those that already were
+    place_creature(&mut colony_shard, 0, 0);
+    ShardTopography::queue_topography(&mut colony_shard, topography(2)).expect("valid topography");
+    assert_eq!(ShardTopography::apply_pending_topography(&mut colony_shard), Some(SHARD_SIZE as u64));
+    assert!(!is_blank(&colony_shard.grid[grid_idx(0, 0)]));
+
+    assert!(ShardTopography::queue_topography(&mut colony_shard, vec![10; 3]).is_err());
+    assert!(colony_shard.pending_topography.is_none());
+}
+
+/// Spins until the condition on the shard holds
+fn wait_for(shard_arc: &Mutex<ColonyShard>, condition: impl Fn(&ColonyShard) -> bool) {
+    while !condition(&shard_arc.lock().unwrap()) {
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    }
+}
+
+#[test]
+fn test_reload_while_ticking_lands_between_ticks() {
+    let shard_arc = Arc::new(Mutex::new(populated_shard()));
+    let stop = Arc::new(AtomicBool::new(false));
+    let ticker = {
+        let (shard_arc, stop) = (Arc::clone(&shard_arc), Arc::clone(&stop));
+        std::thread::spawn(move || {
+            let mut rng = new_random_generator();
+            while !stop.load(Ordering::SeqCst) {
+                ShardUtils::tick_and_export(&mut shard_arc.lock().unwrap(), &mut rng);
+            }
+        })
+    };
+
+    wait_for(&shard_arc, |colony_shard| colony_shard.current_tick >= 30);
+    let queued_at = {
+        let mut colony_shard = shard_arc.lock().unwrap();
+        ShardTopography::queue_topography(&mut colony_shard, topography(1)).expect("valid topography");
+        colony_shard.current_tick
+    };
+    wait_for(&shard_arc, |colony_shard| colony_shard.current_tick > queued_at + 5);
+    stop.store(true, Ordering::SeqCst);
+    ticker.join().unwrap();
+
+    let colony_shard = shard_arc.lock().unwrap();
+    assert!(colony_shard.pending_topography.is_none());
+    assert_eq!(colony_shard.topography_version, 2);
+    for y in 0..SHARD_SIZE {
+        assert!(is_blank(&colony_shard.grid[grid_idx(0, y)]));
+        assert_eq!(colony_shard.grid[grid_idx(0, y)].extra_food_per_tick, WATER_TOPOGRAPHY_VALUE);
+        assert_eq!(colony_shard.grid[grid_idx(SHARD_SIZE - 1, y)].extra_food_per_tick, 10);
+    }
+}
+
+#[tokio::test]
+async fn test_topography_request_queues_on_a_ticking_shard() {
+    let this_backend = HostInfo::new("127.0.0.1".to_string(), 18382);
+    backend_config::set_backend_hostname(this_backend.hostname.clone());
+    backend_config::set_backend_port(this_backend.port);
+    let topology = ClusterTopology {
+        coordinator_host: HostInfo::new("127.0.0.1".to_string(), 18383),
+        backend_hosts: vec![this_backend.clone()],
+        shard_to_host: HashMap::from([(shard(), this_backend)]),
+    };
+    dispatch_request(BackendRequest::InitColony(InitColonyRequest { width: SHARD_SIZE, height: SHARD_SIZE, colony_life_rules: RULES })).await;
+    let init = dispatch_request(BackendRequest::InitColonyShard(InitColonyShardRequest {
+        shard: shard(),
+        colony_life_rules: RULES,
+        topology: Some(topology),
+        seeding: SeedingOptions::default(),
+        topography_data: Some(topography(0)),
+        awaiting_topography: false,
+        colony_instance_id: None,
+        lease_epoch: 1,
+    })).await;
+    assert!(matches!(init, BackendResponse::InitColonyShard(InitColonyShardResponse::Ok)), "{:?}", init);
+    let shard_arc = Colony::instance().get_hosted_colony_shard_arc(&shard()).unwrap();
+    assert_eq!(shard_arc.lock().unwrap().topography_version, 1);
+
+    // Not ticked yet: applied right away
+    let reload = || dispatch_request(BackendRequest::InitShardTopography(InitShardTopographyRequest { shard: shard(), topography_data: topography(1) }));
+    assert!(matches!(reload().await, BackendResponse::InitShardTopography(InitShardTopographyResponse::Ok)));
+    assert_eq!(shard_arc.lock().unwrap().topography_version, 2);
+
+    // Ticking: queued for the next tick
+    shard_arc.lock().unwrap().current_tick = 5;
+    assert!(matches!(reload().await, BackendResponse::InitShardTopography(InitShardTopographyResponse::Ok)));
+    let mut colony_shard = shard_arc.lock().unwrap();
+    assert_eq!(colony_shard.topography_version, 2);
+    assert!(colony_shard.pending_topography.is_some());
+    ShardUtils::tick_and_export(&mut colony_shard, &mut new_random_generator());
+    assert_eq!(colony_shard.topography_version, 3);
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use backend::backend_config;
+use backend::be_server::dispatch_request;
+use backend::topology_refresh::check_coordinator_change;
+use shared::be_api::{
+    BackendRequest, BackendResponse, InitColonyRequest, InitColonyShardRequest, InitColonyShardResponse,
+    RefreshTopologyRequest, RefreshTopologyResponse, SeedingOptions, Shard,
+};
+use shared::cluster_topology::{ClusterTopology, HostInfo};
+use std::collections::HashMap;
+use common::RULES;
+
+const SHARD_SIZE: i32 = 10;
+
+fn this_backend() -> HostInfo {
+    HostInfo::new("127.0.0.1".to_string(), 18182)
+}
+
+fn shard(col: i32) -> Shard {
+    Shard { x: col * SHARD_SIZE, y: 0, width: SHARD_SIZE, height: SHARD_SIZE }
+}
+
+/// A 3x1 colony on this backend, run by the coordinator on coordinator_port
+fn topology(coordinator_port: u16) -> ClusterTopology {
+    ClusterTopology {
+        coordinator_host: HostInfo::new("127.0.0.1".to_string(), coordinator_port),
+        backend_hosts: vec![this_backend()],
+        shard_to_host: (0..3).map(|col| (shard(col), this_backend())).collect::<HashMap<_, _>>(),
+    }
+}
+
+async fn init_shard(shard: Shard, coordinator_port: u16, colony_instance_id: &str) -> InitColonyShardResponse {
+    let request = BackendRequest::InitColonyShard(InitColonyShardRequest {
+        shard,
+        colony_life_rules: RULES,
+        topology: Some(topology(coordinator_port)),
+        seeding: SeedingOptions::default(),
+        topography_data: None,
+        awaiting_topography: false,
+        colony_instance_id: Some(colony_instance_id.to_string()),
+        lease_epoch: 1,
+    });
+    match dispatch_request(request).await {
+        BackendResponse::InitColonyShard(response) => response,
+        other => panic!("Unexpected response {:?}", other),
+    }
+}
+
+#[test]
+fn test_coordinator_change_rules() {
+    let (old, new) = (topology(1), topology(2));
+    assert_eq!(check_coordinator_change(&old, Some("a"), &old, Some("b"), 5), Ok(false));
+    assert_eq!(check_coordinator_change(&old, Some("a"), &new, Some("a"), 5), Ok(true));
+    // With no shards there is nothing the new coordinator could clash with
+    assert_eq!(check_coordinator_change(&old, Some("a"), &new, Some("b"), 0), Ok(true));
+
+    let error = check_coordinator_change(&old, Some("a"), &new, Some("b"), 5).unwrap_err();
+    assert!(error.contains("127.0.0.1:2") && error.contains("colony a"), "{}", error);
+    assert!(check_coordinator_change(&old, None, &new, None, 5).is_err());
+}
+
+#[tokio::test]
+async fn test_replacement_coordinator_takes_over_the_same_colony() {
+    backend_config::set_backend_hostname(this_backend().hostname);
+    backend_config::set_backend_port(this_backend().port);
+    dispatch_request(BackendRequest::InitColony(InitColonyRequest { width: 3 * SHARD_SIZE, height: SHARD_SIZE, colony_life_rules: RULES })).await;
+
+    assert!(matches!(init_shard(shard(0), 1, "colony-a").await, InitColonyShardResponse::Ok));
+    ClusterTopology::mark_stale("coordinator moved".to_string());
+
+    // Another colony's coordinator may not take over a backend that hosts shards
+    match init_shard(shard(1), 2, "colony-b").await {
+        InitColonyShardResponse::TopologyConflict(e) => assert!(e.contains("colony-b"), "{}", e),
+        other => panic!("Expected a topology conflict, got {:?}", other),
+    }
+    assert_eq!(ClusterTopology::get_instance().unwrap().coordinator_host.port, 1);
+
+    // The same colony's new coordinator replaces the topology, which is then up to date
+    assert!(matches!(init_shard(shard(1), 2, "colony-a").await, InitColonyShardResponse::Ok));
+    assert_eq!(ClusterTopology::get_instance().unwrap().coordinator_host.port, 2);
+    assert_eq!(ClusterTopology::stale_reason(), None);
+
+    let refresh = BackendRequest::RefreshTopology(RefreshTopologyRequest {
+        topology: topology(2),
+        colony_instance_id: Some("colony-a".to_string()),
+    });
+    assert!(matches!(dispatch_request(refresh).await, BackendResponse::RefreshTopology(RefreshTopologyResponse::Ok { replaced: false })));
+}
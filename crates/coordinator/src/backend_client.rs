@@ -1,58 +1,179 @@
-use shared::log;
-use shared::be_api::{BackendRequest, BackendResponse, GetShardCurrentTickRequest, GetShardCurrentTickResponse, ApplyEventRequest, ApplyEventResponse, GetColonyInfoRequest, GetColonyInfoResponse, GetShardStatsRequest, GetShardStatsResponse, StatMetric, StringStatBucket};
+use shared::{log, log_error};
+use shared::be_api::{BackendRequest, BackendResponse, GetShardCurrentTickRequest, GetShardCurrentTickResponse, ApplyEventRequest, ApplyEventResponse, GetColonyInfoRequest, GetColonyInfoResponse, GetShardStatsRequest, GetShardStatsResponse, GetShardDensityRequest, GetShardDensityResponse, StatMetric, StatBucket, StringStatBucket, ColonyLifeRules, CLIENT_TIMEOUT};
+use shared::coordinator_api::EventDelivery;
 use shared::colony_events::ColonyEvent;
 use shared::colony_model::Shard as ColonyShard;
+use shared::density::DensityGrid;
 use shared::cluster_topology::ClusterTopology;
-use shared::backend_communication::{send_request, receive_response};
+use shared::backend_communication::{connect_with_handshake, send_request, receive_response};
+use shared::coordinator_api::BreakerState;
 use std::net::TcpStream;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+use crate::circuit_breaker::{BreakerConfig, CircuitBreaker};
+use crate::coordinator_context::CoordinatorContext;
+use crate::coordinator_error::CoordinatorError;
 
-pub fn call_backend_for_tick_count(shard: ColonyShard) -> Option<u64> {
-    let topology = ClusterTopology::get_instance()?;
-    let host_info = topology.get_host_for_shard(&shard)?;
-    let addr = host_info.to_address();
-    let mut stream = TcpStream::connect(&addr).ok()?;
-    
+const MAX_EVENT_DELIVERY_ATTEMPTS: u32 = 3;
+const EVENT_DELIVERY_RETRY_DELAY: Duration = Duration::from_millis(200);
+
+/// Opens a connection, performing the Hello handshake
+fn connect(addr: &str) -> Result<TcpStream, CoordinatorError> {
+    connect_with_handshake(addr).map_err(|e| CoordinatorError::Connect {
+        host: addr.to_string(),
+        source: e.downcast::<std::io::Error>().map(|e| *e).unwrap_or_else(|e| std::io::Error::other(e.to_string())),
+    })
+}
+
+fn with_breaker<T>(addr: &str, f: impl FnOnce(&mut CircuitBreaker) -> T) -> T {
+    let mut breakers = CoordinatorContext::get_instance().backend_breakers();
+    let breaker = breakers.entry(addr.to_string())
+        .or_insert_with(|| CircuitBreaker::new(BreakerConfig::default()));
+    f(breaker)
+}
+
+fn log_breaker_transition(addr: &str, state: Option<BreakerState>, breaker: &CircuitBreaker) {
+    match state {
+        Some(BreakerState::Open) => log_error!("Backend {} failed {} calls in a row, failing its calls fast for {:?}",
+                                               addr, breaker.consecutive_failures(), breaker.retry_in(Instant::now()).unwrap_or_default()),
+        Some(BreakerState::HalfOpen) => log!("Probing backend {} after its backoff", addr),
+        Some(BreakerState::Closed) => log!("Backend {} answers again, its calls are no longer failed fast", addr),
+        None => {}
+    }
+}
+
+/// Fails without a connection attempt while the backend's breaker is open
+pub fn acquire_backend_call(addr: &str) -> Result<(), CoordinatorError> {
+    with_breaker(addr, |breaker| {
+        let (allowed, transition) = breaker.try_acquire(Instant::now());
+        log_breaker_transition(addr, transition, breaker);
+        if allowed { Ok(()) } else { Err(CoordinatorError::CircuitOpen { host: addr.to_string() }) }
+    })
+}
+
+/// Feeds the outcome of a call allowed by acquire_backend_call into the backend's breaker;
+/// a backend that answered, even with a rejection, was reachable
+pub fn record_backend_call(addr: &str, reachable: bool) {
+    with_breaker(addr, |breaker| {
+        let transition = if reachable { breaker.record_success() } else { breaker.record_failure(Instant::now()) };
+        log_breaker_transition(addr, transition, breaker);
+    })
+}
+
+pub fn backend_breaker_state(addr: &str) -> BreakerState {
+    with_breaker(addr, |breaker| breaker.state())
+}
+
+/// Sends one request on a fresh connection and reads the reply, unless the backend's breaker is open
+fn call_backend(addr: &str, op: &'static str, request: &BackendRequest, timeout: Option<Duration>) -> Result<BackendResponse, CoordinatorError> {
+    acquire_backend_call(addr)?;
+    let result = call_backend_once(addr, op, request, timeout);
+    record_backend_call(addr, !result.as_ref().is_err_and(CoordinatorError::is_retryable));
+    result
+}
+
+fn call_backend_once(addr: &str, op: &'static str, request: &BackendRequest, timeout: Option<Duration>) -> Result<BackendResponse, CoordinatorError> {
+    let mut stream = connect(addr)?;
+    let _ = stream.set_read_timeout(timeout);
+    let _ = stream.set_write_timeout(timeout);
+    send_request(&mut stream, request).map_err(|e| CoordinatorError::from_transport(addr, op, e.as_ref()))?;
+    receive_response(&mut stream).map_err(|e| CoordinatorError::from_transport(addr, op, e.as_ref()))
+}
+
+fn host_for_shard(shard: ColonyShard) -> Result<String, CoordinatorError> {
+    let topology = ClusterTopology::get_instance().ok_or(CoordinatorError::TopologyMissing)?;
+    let host_info = topology.get_host_for_shard(&shard).ok_or(CoordinatorError::NoBackendForShard { shard })?;
+    Ok(host_info.to_address())
+}
+
+pub fn call_backend_for_tick_count(shard: ColonyShard) -> Result<u64, CoordinatorError> {
+    let addr = host_for_shard(shard)?;
     let request = BackendRequest::GetShardCurrentTick(GetShardCurrentTickRequest { shard });
-    send_request(&mut stream, &request).ok()?;
-    
-    let response: BackendResponse = receive_response(&mut stream).ok()?;
-    
-    match response {
-        BackendResponse::GetShardCurrentTick(GetShardCurrentTickResponse::Ok { current_tick }) => Some(current_tick),
-        BackendResponse::GetShardCurrentTick(GetShardCurrentTickResponse::ColonyNotInitialized) => {
-            log!("Backend colony not initialized");
-            None
-        }
-        BackendResponse::GetShardCurrentTick(GetShardCurrentTickResponse::ShardNotAvailable) => {
-            log!("Shard not available on backend");
-            None
+    let rejected = |response: &str| CoordinatorError::BackendRejected { host: addr.clone(), shard: Some(shard), response: response.to_string() };
+    match call_backend(&addr, "GetShardCurrentTick", &request, None)? {
+        BackendResponse::GetShardCurrentTick(GetShardCurrentTickResponse::Ok { current_tick }) => Ok(current_tick),
+        BackendResponse::GetShardCurrentTick(GetShardCurrentTickResponse::ColonyNotInitialized) => Err(rejected("colony not initialized")),
+        BackendResponse::GetShardCurrentTick(GetShardCurrentTickResponse::ShardNotAvailable) => Err(rejected("shard not available")),
+        _ => Err(CoordinatorError::UnexpectedResponse { host: addr, op: "GetShardCurrentTick" }),
+    }
+}
+
+/// The colony's current tick and the shard that reported it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColonyTick {
+    pub tick: u64,
+    pub shard: ColonyShard,
+    /// Shards asked before it that did not answer, in the order asked
+    pub skipped: Vec<ColonyShard>,
+}
+
+impl ColonyTick {
+    /// Log line naming the shards that did not answer, None when the first one asked did
+    pub fn fallback_note(&self) -> Option<String> {
+        if self.skipped.is_empty() {
+            return None;
         }
-        _ => {
-            log!("Unexpected response type");
-            None
+        let skipped: Vec<String> = self.skipped.iter().map(|shard| shard.to_id()).collect();
+        Some(format!("Tick {} came from shard {}, {} did not answer", self.tick, self.shard.to_id(), skipped.join(", ")))
+    }
+}
+
+/// Asks the shards for their tick in row-major order until one answers, so a single backend
+/// that is down does not fail the query. The error is the last shard's when none answers.
+pub fn get_colony_tick(shards: &[ColonyShard]) -> Result<ColonyTick, CoordinatorError> {
+    let mut ordered = shards.to_vec();
+    ordered.sort_by_key(|shard| (shard.y, shard.x));
+    let mut skipped = Vec::new();
+    let mut last_error = CoordinatorError::NoBackendsAvailable;
+    for shard in ordered {
+        match call_backend_for_tick_count(shard) {
+            Ok(tick) => return Ok(ColonyTick { tick, shard, skipped }),
+            Err(e) => {
+                skipped.push(shard);
+                last_error = e;
+            }
         }
     }
+    Err(last_error)
 }
 
-pub fn call_backend_get_shard_stats(shard: ColonyShard, metrics: Vec<StatMetric>) -> Option<(u64, Vec<(StatMetric, Vec<shared::be_api::StatBucket>)>, Vec<(StatMetric, Vec<StringStatBucket>)>)> {
-    let topology = ClusterTopology::get_instance()?;
-    let host_info = topology.get_host_for_shard(&shard)?;
-    let addr = host_info.to_address();
-    let mut stream = TcpStream::connect(&addr).ok()?;
+/// get_colony_tick over every shard of the topology
+pub fn get_topology_colony_tick() -> Result<ColonyTick, CoordinatorError> {
+    let topology = ClusterTopology::get_instance().ok_or(CoordinatorError::TopologyMissing)?;
+    get_colony_tick(&topology.get_all_shards())
+}
 
-    let request = BackendRequest::GetShardStats(GetShardStatsRequest { shard, metrics });
-    send_request(&mut stream, &request).ok()?;
+/// The tick of one shard's stats, with its numeric and its string histograms
+pub type ShardStats = (u64, Vec<(StatMetric, Vec<StatBucket>)>, Vec<(StatMetric, Vec<StringStatBucket>)>);
 
-    let response: BackendResponse = receive_response(&mut stream).ok()?;
-    match response {
+/// Stats of one shard; with a region, only of the shard's cells inside it
+pub fn call_backend_get_shard_stats(shard: ColonyShard, metrics: Vec<StatMetric>, region: Option<ColonyShard>) -> Result<ShardStats, CoordinatorError> {
+    let addr = host_for_shard(shard)?;
+    let request = BackendRequest::GetShardStats(GetShardStatsRequest { shard, metrics, region });
+    match call_backend(&addr, "GetShardStats", &request, None)? {
         BackendResponse::GetShardStats(GetShardStatsResponse::Ok { stats, tick_count }) => {
             // stats is Vec<ShardStatResult> for one shard; return (tick, metrics, string_metrics)
-            let shard_result = stats.first()?;
+            let shard_result = stats.first().ok_or_else(|| CoordinatorError::BackendRejected {
+                host: addr.clone(),
+                shard: Some(shard),
+                response: "no stats for the shard".to_string(),
+            })?;
             let metrics = shard_result.metrics.clone();
             let string_metrics = shard_result.string_metrics.clone();
-            Some((tick_count, metrics, string_metrics))
+            Ok((tick_count, metrics, string_metrics))
         }
-        _ => None,
+        BackendResponse::GetShardStats(other) => Err(CoordinatorError::BackendRejected { host: addr, shard: Some(shard), response: format!("{:?}", other) }),
+        _ => Err(CoordinatorError::UnexpectedResponse { host: addr, op: "GetShardStats" }),
+    }
+}
+
+pub fn call_backend_get_shard_density(shard: ColonyShard, cells: usize) -> Result<DensityGrid, CoordinatorError> {
+    let addr = host_for_shard(shard)?;
+    let request = BackendRequest::GetShardDensity(GetShardDensityRequest { shard, cells: cells as u32 });
+    match call_backend(&addr, "GetShardDensity", &request, None)? {
+        BackendResponse::GetShardDensity(GetShardDensityResponse::Ok(grid)) => Ok(grid),
+        BackendResponse::GetShardDensity(other) => Err(CoordinatorError::BackendRejected { host: addr, shard: Some(shard), response: format!("{:?}", other) }),
+        _ => Err(CoordinatorError::UnexpectedResponse { host: addr, op: "GetShardDensity" }),
     }
 }
 
@@ -67,75 +188,119 @@ fn get_unique_backends() -> Vec<(String, u16)> {
         .collect()
 }
 
-pub fn broadcast_event_to_backends(event: ColonyEvent) -> bool {
-    let backends = get_unique_backends();
-    let mut success_count = 0;
-    let _total_count = backends.len();
+fn send_apply_event(addr: &str, event_id: Uuid, event: &ColonyEvent) -> Result<ApplyEventResponse, CoordinatorError> {
+    let request = BackendRequest::ApplyEvent(ApplyEventRequest { event_id, event: event.clone() });
+    match call_backend(addr, "ApplyEvent", &request, Some(CLIENT_TIMEOUT))? {
+        BackendResponse::ApplyEvent(apply_response) => Ok(apply_response),
+        _ => Err(CoordinatorError::UnexpectedResponse { host: addr.to_string(), op: "ApplyEvent" }),
+    }
+}
+
+/// Sends an event to every backend and retries only the ones that failed, reusing the
+/// same event_id so a backend that applied it but timed out answers AlreadyApplied.
+/// Errors that are not retryable, like a response that does not decode, are not retried.
+pub fn deliver_event<F>(event_id: Uuid, backends: &[String], mut send_to_backend: F) -> EventDelivery
+where
+    F: FnMut(&str) -> Result<ApplyEventResponse, CoordinatorError>,
+{
+    let mut delivery = EventDelivery {
+        event_id,
+        applied_to: Vec::new(),
+        failed_on: backends.to_vec(),
+        effects: Vec::new(),
+    };
+    let mut rejected_by = Vec::new();
     
-    for (hostname, port) in backends {
-        let addr = format!("{}:{}", hostname, port);
-        let mut stream = match TcpStream::connect(&addr) {
-            Ok(stream) => stream,
-            Err(e) => {
-                log!("Failed to connect to backend {}: {}", addr, e);
-                continue;
-            }
-        };
-        
-        let request = BackendRequest::ApplyEvent(ApplyEventRequest { event: event.clone() });
-        if let Err(e) = send_request(&mut stream, &request) {
-            log!("Failed to send apply event request to {}: {}", addr, e);
-            continue;
+    for attempt in 1..=MAX_EVENT_DELIVERY_ATTEMPTS {
+        if delivery.failed_on.is_empty() {
+            break;
+        }
+        if attempt > 1 {
+            log!("Retrying event {} on {} backend(s), attempt {}", event_id, delivery.failed_on.len(), attempt);
+            std::thread::sleep(EVENT_DELIVERY_RETRY_DELAY);
         }
         
-        let response: BackendResponse = match receive_response(&mut stream) {
-            Ok(response) => response,
-            Err(e) => {
-                log!("Failed to receive apply event response from {}: {}", addr, e);
-                continue;
-            }
-        };
-        
-        match response {
-            BackendResponse::ApplyEvent(ApplyEventResponse::Ok) => {
-                success_count += 1;
-            },
-            BackendResponse::ApplyEvent(ApplyEventResponse::ColonyNotInitialized) => {
-                log!("Failed to apply event to {}: colony not initialized", addr);
-            },
-            _ => {
-                log!("Unexpected response type for apply event from {}", addr);
+        for addr in std::mem::take(&mut delivery.failed_on) {
+            match send_to_backend(&addr) {
+                Ok(ApplyEventResponse::Ok { effects }) => {
+                    delivery.effects.extend(effects);
+                    delivery.applied_to.push(addr);
+                }
+                Ok(ApplyEventResponse::AlreadyApplied) => {
+                    delivery.applied_to.push(addr);
+                }
+                Ok(ApplyEventResponse::ColonyNotInitialized) => {
+                    log!("Failed to apply event {} to {}: colony not initialized", event_id, addr);
+                    delivery.failed_on.push(addr);
+                }
+                Ok(ApplyEventResponse::InvalidRules(e)) => {
+                    // Retrying cannot help, the backend would reject the same rules again
+                    log_error!("Backend {} rejected event {}: {}", addr, event_id, e);
+                    rejected_by.push(addr);
+                }
+                Err(e) if e.is_retryable() => {
+                    log!("Failed to apply event {}: {}", event_id, e);
+                    delivery.failed_on.push(addr);
+                }
+                Err(e) => {
+                    log_error!("Failed to apply event {}: {}", event_id, e);
+                    rejected_by.push(addr);
+                }
             }
         }
     }
+    delivery.failed_on.extend(rejected_by);
     
-    success_count > 0
+    if !delivery.failed_on.is_empty() {
+        log_error!("Event {} not applied on {:?} after {} attempts", event_id, delivery.failed_on, MAX_EVENT_DELIVERY_ATTEMPTS);
+    }
+    delivery
+}
+
+pub fn broadcast_event_to_backends(event: ColonyEvent) -> EventDelivery {
+    let backends: Vec<String> = get_unique_backends()
+        .into_iter()
+        .map(|(hostname, port)| format!("{}:{}", hostname, port))
+        .collect();
+    send_event_to_backends(event, &backends)
 }
 
-pub fn call_backend_get_colony_info() -> Option<(i32, i32)> {
-    let topology = ClusterTopology::get_instance()?;
-    let backend_hosts = topology.get_all_backend_hosts();
-    if backend_hosts.is_empty() {
-        return None;
+/// Delivers an event to the given backends only, under a fresh event_id
+pub fn send_event_to_backends(event: ColonyEvent, backends: &[String]) -> EventDelivery {
+    let event_id = Uuid::new_v4();
+    deliver_event(event_id, backends, |addr| send_apply_event(addr, event_id, &event))
+}
+
+/// Colony dimensions from the first backend that answers, in topology order
+pub fn call_backend_get_colony_info() -> Result<(i32, i32), CoordinatorError> {
+    let topology = ClusterTopology::get_instance().ok_or(CoordinatorError::TopologyMissing)?;
+    let mut last_error = CoordinatorError::NoBackendsAvailable;
+    for host_info in topology.get_all_backend_hosts() {
+        match get_colony_info_from(host_info.to_address()) {
+            Ok(dimensions) => return Ok(dimensions),
+            Err(e) => last_error = e,
+        }
     }
-    let host_info = &backend_hosts[0];
-    let addr = host_info.to_address();
-    let mut stream = TcpStream::connect(&addr).ok()?;
-    
-    let request = BackendRequest::GetColonyInfo(GetColonyInfoRequest);
-    send_request(&mut stream, &request).ok()?;
-    
-    let response: BackendResponse = receive_response(&mut stream).ok()?;
-    
-    match response {
-        BackendResponse::GetColonyInfo(GetColonyInfoResponse::Ok { width, height, .. }) => Some((width, height)),
+    Err(last_error)
+}
+
+fn get_colony_info_from(addr: String) -> Result<(i32, i32), CoordinatorError> {
+    match call_backend(&addr, "GetColonyInfo", &BackendRequest::GetColonyInfo(GetColonyInfoRequest), None)? {
+        BackendResponse::GetColonyInfo(GetColonyInfoResponse::Ok { width, height, .. }) => Ok((width, height)),
         BackendResponse::GetColonyInfo(GetColonyInfoResponse::ColonyNotInitialized) => {
-            log!("Backend colony not initialized");
-            None
+            Err(CoordinatorError::BackendRejected { host: addr, shard: None, response: "colony not initialized".to_string() })
         }
-        _ => {
-            log!("Unexpected response type for get colony info");
-            None
+        _ => Err(CoordinatorError::UnexpectedResponse { host: addr, op: "GetColonyInfo" }),
+    }
+}
+
+/// The rules a backend currently applies; None when it runs without colony rules
+pub fn call_backend_get_colony_rules(addr: &str) -> Result<Option<ColonyLifeRules>, CoordinatorError> {
+    match call_backend(addr, "GetColonyInfo", &BackendRequest::GetColonyInfo(GetColonyInfoRequest), None)? {
+        BackendResponse::GetColonyInfo(GetColonyInfoResponse::Ok { colony_life_rules, .. }) => Ok(colony_life_rules.map(|rules| *rules)),
+        BackendResponse::GetColonyInfo(GetColonyInfoResponse::ColonyNotInitialized) => {
+            Err(CoordinatorError::BackendRejected { host: addr.to_string(), shard: None, response: "colony not initialized".to_string() })
         }
+        _ => Err(CoordinatorError::UnexpectedResponse { host: addr.to_string(), op: "GetColonyInfo" }),
     }
 }
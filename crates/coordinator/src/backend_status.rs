@@ -0,0 +1,124 @@
+use futures_util::future::join_all;
+use shared::be_api::{BackendRequest, BackendResponse, GetColonyInfoRequest, GetColonyInfoResponse, Shard, ShardLease};
+use shared::cluster_topology::{ClusterTopology, HostInfo};
+use shared::coordinator_api::{BackendStatus, BackendsResponse, BreakerState};
+use std::collections::HashSet;
+use std::time::Duration;
+use crate::backend_client::{acquire_backend_call, backend_breaker_state, record_backend_call};
+use crate::init_colony::{connect_to_backend, receive_message, send_message};
+use crate::shard_leases::with_lease_table;
+
+/// A down backend must not hold up the whole listing
+const BACKEND_QUERY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// What a backend reports about itself
+#[derive(Debug, Clone)]
+pub struct BackendReport {
+    pub hosted_shards: Vec<Shard>,
+    pub tick_range: Option<(u64, u64)>,
+    pub version: Option<String>,
+    pub leases: Vec<ShardLease>,
+    pub suspended_shards: Vec<Shard>,
+}
+
+pub async fn query_backend(backend_host: &HostInfo) -> Result<BackendReport, String> {
+    let mut stream = connect_to_backend(&backend_host.hostname, backend_host.port).await
+        .map_err(|e| format!("Connection failed: {}", e))?;
+
+    send_message(&mut stream, &BackendRequest::GetColonyInfo(GetColonyInfoRequest)).await;
+
+    match receive_message::<BackendResponse>(&mut stream).await {
+        Some(BackendResponse::GetColonyInfo(GetColonyInfoResponse::Ok { shards, tick_range, version, leases, suspended_shards, .. })) => {
+            Ok(BackendReport { hosted_shards: shards, tick_range, version: Some(version), leases, suspended_shards })
+        }
+        // Up but hosting nothing yet
+        Some(BackendResponse::GetColonyInfo(GetColonyInfoResponse::ColonyNotInitialized)) => {
+            Ok(BackendReport { hosted_shards: Vec::new(), tick_range: None, version: None, leases: Vec::new(), suspended_shards: Vec::new() })
+        }
+        Some(_) => Err("Unexpected response type".to_string()),
+        None => Err("Failed to receive response".to_string()),
+    }
+}
+
+fn sorted_ids<'a>(shards: impl Iterator<Item = &'a Shard>) -> Vec<String> {
+    let mut shards: Vec<&Shard> = shards.collect();
+    shards.sort_by_key(|shard| (shard.y, shard.x));
+    shards.iter().map(|shard| shard.to_id()).collect()
+}
+
+/// Hosted shards whose lease epoch is not the one the coordinator issued, e.g. "0_0_250_250: epoch 1, coordinator issued 2"
+pub fn lease_mismatches(leases: &[ShardLease], issued_epoch: impl Fn(&Shard) -> Option<u64>) -> Vec<String> {
+    let mut leases: Vec<&ShardLease> = leases.iter().collect();
+    leases.sort_by_key(|lease| (lease.shard.y, lease.shard.x));
+    leases.iter()
+        .filter_map(|lease| issued_epoch(&lease.shard)
+            .filter(|issued| *issued != lease.epoch)
+            .map(|issued| format!("{}: epoch {}, coordinator issued {}", lease.shard.to_id(), lease.epoch, issued)))
+        .collect()
+}
+
+/// Lines up a backend's topology assignment with its report. Discrepancies are only
+/// listed when the backend answered, since an unreachable one tells us nothing.
+/// issued_epoch is the coordinator's lease epoch of a shard, see shard_leases.
+pub fn merge_backend_status(backend: String, assigned: &[Shard], report: Result<BackendReport, String>, issued_epoch: impl Fn(&Shard) -> Option<u64>) -> BackendStatus {
+    let assigned_set: HashSet<Shard> = assigned.iter().copied().collect();
+    match report {
+        Ok(report) => {
+            let hosted_set: HashSet<Shard> = report.hosted_shards.iter().copied().collect();
+            BackendStatus {
+                backend,
+                assigned_shards: sorted_ids(assigned_set.iter()),
+                hosted_shards: Some(sorted_ids(hosted_set.iter())),
+                tick_range: report.tick_range,
+                healthy: true,
+                version: report.version,
+                error: None,
+                assigned_not_hosting: sorted_ids(assigned_set.difference(&hosted_set)),
+                hosting_not_assigned: sorted_ids(hosted_set.difference(&assigned_set)),
+                lease_mismatches: lease_mismatches(&report.leases, issued_epoch),
+                suspended_shards: sorted_ids(report.suspended_shards.iter()),
+                breaker: BreakerState::Closed,
+            }
+        }
+        Err(e) => BackendStatus {
+            backend,
+            assigned_shards: sorted_ids(assigned_set.iter()),
+            hosted_shards: None,
+            tick_range: None,
+            healthy: false,
+            version: None,
+            error: Some(e),
+            assigned_not_hosting: Vec::new(),
+            hosting_not_assigned: Vec::new(),
+            lease_mismatches: Vec::new(),
+            suspended_shards: Vec::new(),
+            breaker: BreakerState::Closed,
+        },
+    }
+}
+
+/// Queries every backend of the topology in parallel, in topology order
+pub async fn backend_statuses(topology: &ClusterTopology) -> BackendsResponse {
+    let backends = topology.get_all_backend_hosts();
+    let reports = join_all(backends.iter().map(|backend| async move {
+        let addr = backend.to_address();
+        acquire_backend_call(&addr).map_err(|e| e.to_string())?;
+        let report = tokio::time::timeout(BACKEND_QUERY_TIMEOUT, query_backend(backend)).await
+            .unwrap_or_else(|_| Err("Timed out".to_string()));
+        record_backend_call(&addr, report.is_ok());
+        report
+    })).await;
+
+    let backends = backends.iter().zip(reports)
+        .map(|(backend, report)| {
+            let assigned: Vec<Shard> = topology.shard_to_host.iter()
+                .filter(|(_, host)| *host == backend)
+                .map(|(shard, _)| *shard)
+                .collect();
+            let mut status = merge_backend_status(backend.to_address(), &assigned, report, |shard| with_lease_table(|table| table.epoch(shard)));
+            status.breaker = backend_breaker_state(&status.backend);
+            status
+        })
+        .collect();
+    BackendsResponse { backends }
+}
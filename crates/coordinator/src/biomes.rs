@@ -0,0 +1,118 @@
+use rand::{rngs::SmallRng, Rng};
+use shared::be_api::{BackendRequest, BackendResponse, Biome, UpdateBiomesRequest, UpdateBiomesResponse};
+use shared::cluster_topology::HostInfo;
+use shared::colony_events::{BiomeChange, ColonyEvent};
+use shared::colony_model::{validate_biomes, ColonyLifeRulesOverride};
+use shared::{log, log_error};
+use crate::coordinator_context::CoordinatorContext;
+use crate::init_colony::{connect_to_backend, receive_message, send_message};
+
+const BIOMES_ENABLED_ENV: &str = "BIOMES_ENABLED";
+/// A biome shift moves one edge by up to this fraction of the colony side it runs along
+const MAX_SHIFT_FRACTION: f64 = 0.1;
+
+/// The north is harsher: upkeep costs more and random death comes twice as often
+fn north_overrides() -> ColonyLifeRulesOverride {
+    ColonyLifeRulesOverride {
+        health_cost_per_size_unit: Some(3),
+        random_death_chance: Some(50),
+        ..Default::default()
+    }
+}
+
+/// Biomes of a new colony: the north half, unless BIOMES_ENABLED=false
+pub fn default_biomes(colony_width: i32, colony_height: i32) -> Vec<Biome> {
+    let enabled = std::env::var(BIOMES_ENABLED_ENV)
+        .ok()
+        .and_then(|v| v.parse::<bool>().ok())
+        .unwrap_or(true);
+    if !enabled {
+        return Vec::new();
+    }
+    vec![Biome {
+        name: "north".to_string(),
+        x: 0,
+        y: 0,
+        width: colony_width,
+        height: colony_height / 2,
+        overrides: north_overrides(),
+    }]
+}
+
+async fn send_update_biomes(backend_host: &HostInfo, biomes: &[Biome]) -> Result<(), String> {
+    let mut stream = connect_to_backend(&backend_host.hostname, backend_host.port).await
+        .map_err(|e| format!("Connection failed: {}", e))?;
+
+    send_message(&mut stream, &BackendRequest::UpdateBiomes(UpdateBiomesRequest { biomes: biomes.to_vec() })).await;
+
+    match receive_message::<BackendResponse>(&mut stream).await {
+        Some(BackendResponse::UpdateBiomes(UpdateBiomesResponse::Ok)) => Ok(()),
+        Some(BackendResponse::UpdateBiomes(UpdateBiomesResponse::ColonyNotInitialized)) => Err("colony not initialized".to_string()),
+        Some(BackendResponse::UpdateBiomes(UpdateBiomesResponse::InvalidBiomes(e))) => Err(format!("invalid biomes: {}", e)),
+        Some(_) => Err("Unexpected response type".to_string()),
+        None => Err("Failed to receive response".to_string()),
+    }
+}
+
+/// Sends the coordinator's biomes to the given backends, which clip them to their shards
+pub async fn send_biomes_to_backends(backend_hosts: &[HostInfo]) {
+    let biomes = CoordinatorContext::get_instance().get_biomes();
+    for backend_host in backend_hosts {
+        if let Err(e) = send_update_biomes(backend_host, &biomes).await {
+            log_error!("Failed to send {} biomes to {}: {}", biomes.len(), backend_host.to_address(), e);
+        }
+    }
+}
+
+/// Defines the biomes of a new colony and delivers them to every backend
+pub async fn init_biomes(backend_hosts: &[HostInfo], colony_width: i32, colony_height: i32) {
+    let context = CoordinatorContext::get_instance();
+    let biomes = default_biomes(colony_width, colony_height);
+    if let Err(e) = validate_biomes(&biomes, &context.get_colony_life_rules()) {
+        log_error!("Not using the default biomes: {}", e);
+        return;
+    }
+    for biome in &biomes {
+        log!("Biome '{}' at ({}, {}) {}x{}", biome.name, biome.x, biome.y, biome.width, biome.height);
+    }
+    context.set_biomes(biomes);
+    send_biomes_to_backends(backend_hosts).await;
+}
+
+/// Moves one edge of the biome by `delta`, keeping it inside the colony and at least
+/// one cell wide. Edges are 0 left, 1 right, 2 top, 3 bottom. Returns the old and new edge position.
+pub fn shift_biome_edge(biome: &mut Biome, edge: usize, delta: i32, colony_width: i32, colony_height: i32) -> (i32, i32) {
+    let (left, right) = (biome.x, biome.x + biome.width);
+    let (top, bottom) = (biome.y, biome.y + biome.height);
+    let (old, new) = match edge {
+        0 => (left, (left + delta).clamp(0, right - 1)),
+        1 => (right, (right + delta).clamp(left + 1, colony_width)),
+        2 => (top, (top + delta).clamp(0, bottom - 1)),
+        _ => (bottom, (bottom + delta).clamp(top + 1, colony_height)),
+    };
+    match edge {
+        0 => { biome.x = new; biome.width = right - new; }
+        1 => biome.width = new - left,
+        2 => { biome.y = new; biome.height = bottom - new; }
+        _ => biome.height = new - top,
+    }
+    (old, new)
+}
+
+/// Event that moves one edge of a random biome; with no biomes it re-sends the empty list
+pub fn randomize_biome_shift(biomes: &[Biome], colony_width: i32, colony_height: i32, rng: &mut SmallRng) -> ColonyEvent {
+    let mut biomes = biomes.to_vec();
+    if biomes.is_empty() {
+        return ColonyEvent::ChangeBiomes(BiomeChange { biomes, description: "No biomes to shift".to_string() });
+    }
+    let index = rng.gen_range(0..biomes.len());
+    let edge = rng.gen_range(0..4);
+    let side = if edge < 2 { colony_width } else { colony_height };
+    let max_shift = ((side as f64 * MAX_SHIFT_FRACTION) as i32).max(1);
+    let amount = rng.gen_range(1..=max_shift);
+    let delta = if rng.gen_bool(0.5) { amount } else { -amount };
+    let (old, new) = shift_biome_edge(&mut biomes[index], edge, delta, colony_width, colony_height);
+    let edge_name = ["left", "right", "top", "bottom"][edge];
+    let description = format!("Moved the {} edge of '{}' from {} to {}", edge_name, biomes[index].name, old, new);
+    ColonyEvent::ChangeBiomes(BiomeChange { biomes, description })
+}
@@ -0,0 +1,82 @@
+use shared::api_auth::ApiScope;
+use shared::be_api::BUILD_VERSION;
+use shared::cluster_topology::{ClusterTopology, NodeAddress};
+use shared::coordinator_api::{BackendStatus, BootstrapBackend, BootstrapResponse, TopologySummary};
+use shared::ssm;
+use crate::backend_status::backend_statuses;
+use crate::coordinator_context::CoordinatorContext;
+
+pub fn topology_summary(topology: &ClusterTopology) -> TopologySummary {
+    TopologySummary {
+        colony_width: topology.width_in_shards() * topology.shard_width(),
+        colony_height: topology.height_in_shards() * topology.shard_height(),
+        width_in_shards: topology.width_in_shards(),
+        height_in_shards: topology.height_in_shards(),
+        shard_width: topology.shard_width(),
+        shard_height: topology.shard_height(),
+    }
+}
+
+/// The GET /api/bootstrap body. addresses are the cluster registry entries, coordinator
+/// included; statuses are the /api/backends probe in topology order.
+pub fn build_bootstrap(
+    colony_status: String,
+    colony_instance_id: Option<String>,
+    coordinator: Option<&NodeAddress>,
+    topology: Option<&ClusterTopology>,
+    addresses: &[NodeAddress],
+    statuses: &[BackendStatus],
+    scope: ApiScope,
+) -> BootstrapResponse {
+    // Observers see hosts under their public address, like in /topology
+    let view = topology.map(|topology| match scope {
+        ApiScope::Admin => topology.clone(),
+        ApiScope::Observer => topology.to_observer_view(addresses),
+    });
+    let backends = match (topology, &view) {
+        (Some(topology), Some(view)) => topology.get_all_backend_hosts().iter()
+            .zip(view.get_all_backend_hosts())
+            .enumerate()
+            .map(|(index, (host, shown_host))| {
+                let address = addresses.iter().find(|address| host.matches_address(address));
+                let status = statuses.get(index);
+                BootstrapBackend {
+                    host: shown_host.clone(),
+                    public_ip: address.map(|address| address.public_ip.clone()),
+                    private_ip: (scope == ApiScope::Admin).then(|| host.hostname.clone()),
+                    rpc_port: host.port,
+                    http_port: address.map(|address| address.http_port),
+                    healthy: status.is_some_and(|status| status.healthy),
+                    error: status.and_then(|status| status.error.clone()),
+                }
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    BootstrapResponse {
+        coordinator_version: BUILD_VERSION.to_string(),
+        coordinator_http: coordinator.map(|address| format!("{}:{}", address.public_ip, address.http_port)),
+        colony_status,
+        colony_instance_id,
+        summary: topology.map(topology_summary),
+        topology: view,
+        backends,
+    }
+}
+
+/// Gathers GET /api/bootstrap: stored colony state, the registry and a probe of every backend
+pub async fn bootstrap(scope: ApiScope) -> BootstrapResponse {
+    let (colony_status, colony_instance_id) = {
+        let stored_info = CoordinatorContext::get_instance().get_coord_stored_info();
+        (format!("{:?}", stored_info.status), stored_info.colony_instance_id.clone())
+    };
+    let topology = ClusterTopology::get_instance();
+    let coordinator = ssm::discover_coordinator().await;
+    let mut addresses = ssm::discover_backends().await;
+    addresses.extend(coordinator.clone());
+    let statuses = match &topology {
+        Some(topology) => backend_statuses(topology).await.backends,
+        None => Vec::new(),
+    };
+    build_bootstrap(colony_status, colony_instance_id, coordinator.as_ref(), topology.as_deref(), &addresses, &statuses, scope)
+}
@@ -0,0 +1,123 @@
+use shared::coordinator_api::{CaptureConfig, ColonyEventDescription};
+use shared::log;
+use std::future::Future;
+use std::time::Duration;
+use tokio::time::Instant;
+use crate::backend_client;
+use crate::coordinator_context::CoordinatorContext;
+
+const CAPTURE_INTERVAL_ENV: &str = "CAPTURE_INTERVAL_SECS";
+const STATS_INTERVAL_ENV: &str = "STATS_INTERVAL_SECS";
+const CAPTURES_ENABLED_ENV: &str = "CAPTURES_ENABLED";
+const STATS_ENABLED_ENV: &str = "STATS_ENABLED";
+const DEFAULT_CAPTURE_INTERVAL_SECS: u64 = 60;
+const DEFAULT_STATS_INTERVAL_SECS: u64 = 10;
+pub const MIN_INTERVAL_SECS: u64 = 1;
+
+fn env_u64(name: &str, default: u64) -> u64 {
+    std::env::var(name)
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|secs| *secs >= MIN_INTERVAL_SECS)
+        .unwrap_or(default)
+}
+
+fn env_bool(name: &str, default: bool) -> bool {
+    std::env::var(name)
+        .ok()
+        .and_then(|v| v.parse::<bool>().ok())
+        .unwrap_or(default)
+}
+
+/// Startup settings, overridable through the environment
+pub fn capture_config_from_env() -> CaptureConfig {
+    CaptureConfig {
+        capture_interval_secs: env_u64(CAPTURE_INTERVAL_ENV, DEFAULT_CAPTURE_INTERVAL_SECS),
+        stats_interval_secs: env_u64(STATS_INTERVAL_ENV, DEFAULT_STATS_INTERVAL_SECS),
+        captures_enabled: env_bool(CAPTURES_ENABLED_ENV, true),
+        stats_enabled: env_bool(STATS_ENABLED_ENV, true),
+    }
+}
+
+pub fn validate_capture_config(config: &CaptureConfig) -> Result<(), String> {
+    if config.capture_interval_secs < MIN_INTERVAL_SECS {
+        return Err(format!("capture_interval_secs must be at least {}", MIN_INTERVAL_SECS));
+    }
+    if config.stats_interval_secs < MIN_INTERVAL_SECS {
+        return Err(format!("stats_interval_secs must be at least {}", MIN_INTERVAL_SECS));
+    }
+    Ok(())
+}
+
+/// e.g. "capture interval 60s -> 30s, stats disabled"
+pub fn describe_capture_config_change(old: &CaptureConfig, new: &CaptureConfig) -> String {
+    let on_off = |enabled: bool| if enabled { "enabled" } else { "disabled" };
+    let mut changes = Vec::new();
+    if old.capture_interval_secs != new.capture_interval_secs {
+        changes.push(format!("capture interval {}s -> {}s", old.capture_interval_secs, new.capture_interval_secs));
+    }
+    if old.stats_interval_secs != new.stats_interval_secs {
+        changes.push(format!("stats interval {}s -> {}s", old.stats_interval_secs, new.stats_interval_secs));
+    }
+    if old.captures_enabled != new.captures_enabled {
+        changes.push(format!("captures {}", on_off(new.captures_enabled)));
+    }
+    if old.stats_enabled != new.stats_enabled {
+        changes.push(format!("stats {}", on_off(new.stats_enabled)));
+    }
+    if changes.is_empty() {
+        "no change".to_string()
+    } else {
+        changes.join(", ")
+    }
+}
+
+/// Applies new settings to the running loops and records the change as a colony event
+pub async fn update_capture_config(config: CaptureConfig) -> Result<CaptureConfig, String> {
+    validate_capture_config(&config)?;
+    let context = CoordinatorContext::get_instance();
+    let old = context.set_capture_config(config);
+    context.capture_config_changed().notify_waiters();
+
+    let description = describe_capture_config_change(&old, &config);
+    log!("Capture config changed: {}", description);
+    let colony_tick = tokio::task::spawn_blocking(backend_client::get_topology_colony_tick).await.ok().and_then(Result::ok);
+    if let Some(note) = colony_tick.as_ref().and_then(|colony_tick| colony_tick.fallback_note()) {
+        log!("Capture config change: {}", note);
+    }
+    let current_tick = colony_tick.map_or(0, |colony_tick| colony_tick.tick);
+    context.add_colony_event(ColonyEventDescription {
+        tick: current_tick,
+        intended_tick: None,
+        event_type: "Capture Config Change".to_string(),
+        description,
+        delivery: None,
+        no_effect: false,
+        region: None,
+    });
+    Ok(config)
+}
+
+/// Runs `action` every interval picked from the current settings, re-read before each wait.
+/// A disabled iteration is skipped but keeps the cadence.
+pub async fn run_periodic<S, F, Fut>(settings: S, action: F)
+where
+    S: Fn(&CaptureConfig) -> (u64, bool),
+    F: Fn() -> Fut,
+    Fut: Future<Output = ()>,
+{
+    let mut last_run = Instant::now();
+    loop {
+        let (interval_secs, _) = settings(&CoordinatorContext::get_instance().get_capture_config());
+        let due = last_run + Duration::from_secs(interval_secs.max(MIN_INTERVAL_SECS));
+        tokio::select! {
+            _ = tokio::time::sleep_until(due) => {}
+            _ = CoordinatorContext::get_instance().capture_config_changed().notified() => continue,
+        }
+        last_run = Instant::now();
+        let (_, enabled) = settings(&CoordinatorContext::get_instance().get_capture_config());
+        if enabled {
+            action().await;
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Utc};
+use shared::coordinator_api::{CaptureFrameEntry, CaptureListResponse};
+use shared::log;
+use crate::colony_capture::{images_dir, MissingShardsSidecar};
+
+const FRAME_SUFFIX: &str = ".png";
+const SIDECAR_SUFFIX: &str = ".missing.json";
+
+/// Frames of the current colony instance in images_shots, listed by one directory scan.
+/// File names and sizes come from the scan itself; only frames that have a .missing.json
+/// sidecar cost a file read, and those are the rare frames with placeholder tiles.
+pub struct CaptureStore {
+    instance_id: String,
+    dir: PathBuf,
+}
+
+impl CaptureStore {
+    pub fn for_instance(instance_id: &str) -> Self {
+        Self::new(instance_id, images_dir(instance_id))
+    }
+
+    pub fn new(instance_id: &str, dir: PathBuf) -> Self {
+        Self { instance_id: instance_id.to_string(), dir }
+    }
+
+    pub fn instance_id(&self) -> &str {
+        &self.instance_id
+    }
+
+    pub fn frame_path(&self, tick: u64) -> PathBuf {
+        self.dir.join(frame_file_name(tick))
+    }
+
+    /// Frames within [from_tick, to_tick], in tick order; an instance that never captured has none
+    pub fn list(&self, from_tick: Option<u64>, to_tick: Option<u64>) -> Result<CaptureListResponse, String> {
+        let in_range = |tick: u64| from_tick.is_none_or(|from| tick >= from) && to_tick.is_none_or(|to| tick <= to);
+
+        let mut frames: BTreeMap<u64, CaptureFrameEntry> = BTreeMap::new();
+        let mut sidecars: HashMap<u64, PathBuf> = HashMap::new();
+        for (name, entry) in self.scan()? {
+            if let Some(tick) = parse_tick(&name, SIDECAR_SUFFIX).filter(|tick| in_range(*tick)) {
+                sidecars.insert(tick, entry.path());
+            } else if let Some(tick) = parse_tick(&name, FRAME_SUFFIX).filter(|tick| in_range(*tick)) {
+                let metadata = entry.metadata()
+                    .map_err(|e| format!("Failed to stat {}: {}", entry.path().display(), e))?;
+                let timestamp = metadata.modified()
+                    .map(|modified| DateTime::<Utc>::from(modified).to_rfc3339())
+                    .unwrap_or_default();
+                frames.insert(tick, CaptureFrameEntry {
+                    tick,
+                    timestamp,
+                    url: format!("/api/captures/{}", name),
+                    bytes: metadata.len(),
+                    missing_shards: Vec::new(),
+                });
+            }
+        }
+
+        for (tick, sidecar_path) in sidecars {
+            if let Some(frame) = frames.get_mut(&tick) {
+                frame.missing_shards = read_sidecar(&sidecar_path)?.missing_shards;
+            }
+        }
+        Ok(CaptureListResponse { colony_instance_id: self.instance_id.clone(), frames: frames.into_values().collect() })
+    }
+
+    /// Deletes every frame and sidecar below before_tick; returns how many frames were deleted
+    pub fn prune(&self, before_tick: u64) -> Result<usize, String> {
+        let mut frames_deleted = 0;
+        for (name, entry) in self.scan()? {
+            let frame_tick = parse_tick(&name, FRAME_SUFFIX);
+            let tick = parse_tick(&name, SIDECAR_SUFFIX).or(frame_tick);
+            if tick.is_some_and(|tick| tick < before_tick) {
+                std::fs::remove_file(entry.path())
+                    .map_err(|e| format!("Failed to delete {}: {}", entry.path().display(), e))?;
+                if frame_tick.is_some() {
+                    frames_deleted += 1;
+                }
+            }
+        }
+        log!("Pruned {} capture frame(s) before tick {} from {}", frames_deleted, before_tick, self.dir.display());
+        Ok(frames_deleted)
+    }
+
+    fn scan(&self) -> Result<Vec<(String, std::fs::DirEntry)>, String> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(format!("Failed to read {}: {}", self.dir.display(), e)),
+        };
+        Ok(entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| Some((entry.file_name().into_string().ok()?, entry)))
+            .collect())
+    }
+}
+
+fn read_sidecar(path: &Path) -> Result<MissingShardsSidecar, String> {
+    let json = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&json).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+/// Frames are named by zero-padded tick, e.g. 0000042.png
+pub fn frame_file_name(tick: u64) -> String {
+    format!("{:07}{}", tick, FRAME_SUFFIX)
+}
+
+/// Tick of a frame URL such as /api/captures/0000042.png
+pub fn parse_frame_tick(file_name: &str) -> Option<u64> {
+    parse_tick(file_name, FRAME_SUFFIX)
+}
+
+fn parse_tick(file_name: &str, suffix: &str) -> Option<u64> {
+    let digits = file_name.strip_suffix(suffix)?;
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
@@ -0,0 +1,125 @@
+use std::fmt;
+use shared::log;
+use crate::colony_step::{is_colony_paused, is_fast_forward};
+use crate::coordinator_context::CoordinatorContext;
+use crate::coordinator_storage::{ColonyStatus, CoordinatorStoredInfo};
+use crate::tick_monitor::{latest_max_tick, latest_min_tick};
+use crate::warmup::is_warming_up;
+
+/// Why a periodic capture does not fan out to the shards this cycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureSkip {
+    /// The colony is not initialized or StartTicking was not issued yet
+    NotTicking,
+    Paused,
+    /// Nobody is watching, see colony_step::set_fast_forward
+    FastForward,
+    /// The run reached its target_tick, see crate::run_summary
+    Completed,
+    /// The slowest shard has not passed warmup_ticks, see crate::warmup
+    WarmingUp,
+    /// The highest shard tick did not advance since the last capture
+    Stalled(u64),
+}
+
+impl fmt::Display for CaptureSkip {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CaptureSkip::NotTicking => write!(f, "colony not ticking yet"),
+            CaptureSkip::Paused => write!(f, "colony paused"),
+            CaptureSkip::FastForward => write!(f, "fast-forward on"),
+            CaptureSkip::Completed => write!(f, "run completed"),
+            CaptureSkip::WarmingUp => write!(f, "colony warming up"),
+            CaptureSkip::Stalled(tick) => write!(f, "tick {} already captured", tick),
+        }
+    }
+}
+
+/// The colony as the capture tasks see it, without querying any backend
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaptureState {
+    pub ticking: bool,
+    pub paused: bool,
+    pub fast_forward: bool,
+    pub completed: bool,
+    pub warming_up: bool,
+    pub colony_instance_id: Option<String>,
+    /// Highest shard tick of the latest tick-history sweep
+    pub max_tick: Option<u64>,
+}
+
+impl CaptureState {
+    pub fn from_stored_info(stored_info: &CoordinatorStoredInfo, paused: bool, max_tick: Option<u64>) -> Self {
+        Self {
+            ticking: stored_info.ticking_started && matches!(stored_info.status, ColonyStatus::TopographyInitialized),
+            paused,
+            fast_forward: false,
+            completed: matches!(stored_info.status, ColonyStatus::Completed),
+            warming_up: false,
+            colony_instance_id: stored_info.colony_instance_id.clone(),
+            max_tick,
+        }
+    }
+
+    pub fn current() -> Self {
+        let max_tick = latest_max_tick();
+        let stored_info = CoordinatorContext::get_instance().get_coord_stored_info();
+        let warming_up = is_warming_up(&stored_info, latest_min_tick());
+        let state = Self::from_stored_info(&stored_info, is_colony_paused(), max_tick);
+        Self { fast_forward: is_fast_forward(), warming_up, ..state }
+    }
+}
+
+/// Lets a periodic capture through while the colony ticks and its tick advanced since the
+/// last capture. A skip is logged once, until the reason changes or a capture goes through.
+#[derive(Debug)]
+pub struct CaptureGate {
+    task: &'static str,
+    /// Instance and max tick of the last capture
+    last_captured: Option<(Option<String>, u64)>,
+    last_skip: Option<CaptureSkip>,
+}
+
+impl CaptureGate {
+    pub const fn new(task: &'static str) -> Self {
+        Self { task, last_captured: None, last_skip: None }
+    }
+
+    pub fn check(&mut self, state: &CaptureState) -> Result<(), CaptureSkip> {
+        let skip = if state.completed {
+            Some(CaptureSkip::Completed)
+        } else if !state.ticking {
+            Some(CaptureSkip::NotTicking)
+        } else if state.paused {
+            Some(CaptureSkip::Paused)
+        } else if state.fast_forward {
+            Some(CaptureSkip::FastForward)
+        } else if state.warming_up {
+            Some(CaptureSkip::WarmingUp)
+        } else {
+            state.max_tick
+                .filter(|&tick| self.last_captured == Some((state.colony_instance_id.clone(), tick)))
+                .map(CaptureSkip::Stalled)
+        };
+        match skip {
+            None => {
+                self.last_skip = None;
+                Ok(())
+            }
+            Some(skip) => {
+                if self.last_skip != Some(skip) {
+                    log!("{} skipped: {}", self.task, skip);
+                    self.last_skip = Some(skip);
+                }
+                Err(skip)
+            }
+        }
+    }
+
+    /// Records a capture taken in the given state, so the same tick is not captured again
+    pub fn record_capture(&mut self, state: &CaptureState) {
+        if let Some(tick) = state.max_tick {
+            self.last_captured = Some((state.colony_instance_id.clone(), tick));
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use std::time::{Duration, Instant};
+use shared::coordinator_api::BreakerState;
+
+/// Consecutive failed calls that open the breaker of a backend
+pub const DEFAULT_FAILURE_THRESHOLD: u32 = 3;
+/// First wait before a half-open probe; doubled after every failed probe
+pub const DEFAULT_BASE_BACKOFF: Duration = Duration::from_secs(5);
+pub const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BreakerConfig {
+    pub failure_threshold: u32,
+    pub base_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for BreakerConfig {
+    fn default() -> Self {
+        Self { failure_threshold: DEFAULT_FAILURE_THRESHOLD, base_backoff: DEFAULT_BASE_BACKOFF, max_backoff: DEFAULT_MAX_BACKOFF }
+    }
+}
+
+/// Calls to one backend: closed while it answers, open after failure_threshold consecutive
+/// failures so calls fail fast, half-open once the backoff elapsed to let one probe through.
+/// The probe closes the breaker on success and reopens it with twice the backoff on failure.
+/// Transition methods return the new state when it changed, so only transitions are logged.
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker {
+    config: BreakerConfig,
+    state: BreakerState,
+    consecutive_failures: u32,
+    backoff: Duration,
+    retry_at: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    pub fn new(config: BreakerConfig) -> Self {
+        Self { config, state: BreakerState::Closed, consecutive_failures: 0, backoff: config.base_backoff, retry_at: None }
+    }
+
+    pub fn state(&self) -> BreakerState {
+        self.state
+    }
+
+    pub fn consecutive_failures(&self) -> u32 {
+        self.consecutive_failures
+    }
+
+    /// Whether a call may go out now. An open breaker whose backoff elapsed turns half-open and
+    /// lets this call through as the probe; further calls fail fast until the probe is recorded.
+    pub fn try_acquire(&mut self, now: Instant) -> (bool, Option<BreakerState>) {
+        match self.state {
+            BreakerState::Closed => (true, None),
+            BreakerState::HalfOpen => (false, None),
+            BreakerState::Open if self.retry_at.is_some_and(|retry_at| now >= retry_at) => {
+                self.state = BreakerState::HalfOpen;
+                (true, Some(BreakerState::HalfOpen))
+            }
+            BreakerState::Open => (false, None),
+        }
+    }
+
+    pub fn record_success(&mut self) -> Option<BreakerState> {
+        self.consecutive_failures = 0;
+        self.backoff = self.config.base_backoff;
+        self.retry_at = None;
+        self.transition(BreakerState::Closed)
+    }
+
+    pub fn record_failure(&mut self, now: Instant) -> Option<BreakerState> {
+        self.consecutive_failures += 1;
+        match self.state {
+            BreakerState::Closed if self.consecutive_failures >= self.config.failure_threshold => {
+                self.retry_at = Some(now + self.backoff);
+                self.transition(BreakerState::Open)
+            }
+            BreakerState::Closed | BreakerState::Open => None,
+            BreakerState::HalfOpen => {
+                self.backoff = (self.backoff * 2).min(self.config.max_backoff);
+                self.retry_at = Some(now + self.backoff);
+                self.transition(BreakerState::Open)
+            }
+        }
+    }
+
+    /// How long an open breaker still fails calls fast
+    pub fn retry_in(&self, now: Instant) -> Option<Duration> {
+        self.retry_at.filter(|_| self.state == BreakerState::Open).map(|retry_at| retry_at.saturating_duration_since(now))
+    }
+
+    fn transition(&mut self, state: BreakerState) -> Option<BreakerState> {
+        (self.state != state).then(|| {
+            self.state = state;
+            state
+        })
+    }
+}
@@ -2,16 +2,117 @@ use shared::cluster_topology::{ClusterTopology, HostInfo};
 use shared::colony_model::{Shard, Color};
 use shared::{log, log_error};
 use shared::ssm;
+use shared::shard_render::ImageBackground;
+use shared::api_auth::{bearer_header_value, ApiAuthConfig};
 use shared::cluster_registry::create_cluster_registry;
+use shared::http_port_probe::answers_health;
+use shared::output_paths::OutputPaths;
+use std::future::Future;
 use std::time::{Duration, Instant};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use futures_util::future::join_all;
 use image::{ImageBuffer, Rgb, RgbImage};
 use crate::backend_client;
+use crate::coordinator_context::CoordinatorContext;
+use crate::capture_gate::CaptureState;
 
-const BASE_BUCKET_DIR: &str = "output/s3/distributed-colony";
+/// Frames with fewer changed pixels than this fraction of the colony are skipped
+const DEFAULT_MIN_CHANGED_FRACTION: f64 = 0.001;
+const MIN_CHANGED_FRACTION_ENV: &str = "CAPTURE_MIN_CHANGED_FRACTION";
+/// Frames with more missing shards than this fraction of all shards are not written
+const DEFAULT_MAX_MISSING_FRACTION: f64 = 0.25;
+const MAX_MISSING_FRACTION_ENV: &str = "CAPTURE_MAX_MISSING_FRACTION";
+/// Upper bound for fetching one shard image, port discovery included
+const SHARD_FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+const PLACEHOLDER_GRAY: Rgb<u8> = Rgb([128, 128, 128]);
+const PLACEHOLDER_HATCH: Rgb<u8> = Rgb([96, 96, 96]);
+const PLACEHOLDER_HATCH_SPACING: u32 = 8;
 
-/// Main function to capture colony creature images and save to disk
+#[derive(serde::Serialize, Default, Clone, Debug)]
+pub(crate) struct CaptureSummary {
+    #[serde(skip)]
+    instance_id: String,
+    frames_written: u64,
+    frames_skipped: u64,
+    last_frame_tick: Option<u64>,
+    min_changed_fraction: f64,
+    frames_skipped_missing_shards: u64,
+    /// Shard ids missing from the last frame, written or not
+    last_missing_shards: Vec<String>,
+}
+
+/// A stitched colony frame; missing shards are drawn as placeholder tiles
+pub struct StitchedFrame {
+    pub image: RgbImage,
+    pub total_shards: usize,
+    pub missing_shards: Vec<Shard>,
+}
+
+impl StitchedFrame {
+    pub fn missing_fraction(&self) -> f64 {
+        if self.total_shards == 0 {
+            return 0.0;
+        }
+        self.missing_shards.len() as f64 / self.total_shards as f64
+    }
+
+    pub fn missing_shard_ids(&self) -> Vec<String> {
+        self.missing_shards.iter().map(|shard| shard.to_id()).collect()
+    }
+}
+
+/// Written as {tick}.missing.json next to a frame that has placeholder tiles
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+pub struct MissingShardsSidecar {
+    pub tick: u64,
+    pub total_shards: usize,
+    pub missing_shards: Vec<String>,
+}
+
+/// Where the frames of a colony instance are written
+pub fn images_dir(instance_id: &str) -> PathBuf {
+    OutputPaths::get_instance().captures_dir(instance_id)
+}
+
+fn min_changed_fraction() -> f64 {
+    std::env::var(MIN_CHANGED_FRACTION_ENV)
+        .ok()
+        .and_then(|v| v.parse::<f64>().ok())
+        .filter(|f| (0.0..=1.0).contains(f))
+        .unwrap_or(DEFAULT_MIN_CHANGED_FRACTION)
+}
+
+fn max_missing_fraction() -> f64 {
+    std::env::var(MAX_MISSING_FRACTION_ENV)
+        .ok()
+        .and_then(|v| v.parse::<f64>().ok())
+        .filter(|f| (0.0..=1.0).contains(f))
+        .unwrap_or(DEFAULT_MAX_MISSING_FRACTION)
+}
+
+/// Periodic frame capture, skipped like the statistics capture while the colony does not tick
 pub async fn capture_colony() {
+    let state = CaptureState::current();
+    if CoordinatorContext::get_instance().image_capture_gate().check(&state).is_err() {
+        return;
+    }
+    if capture_colony_frame(true).await.is_some() {
+        CoordinatorContext::get_instance().image_capture_gate().record_capture(&state);
+    }
+}
+
+/// The last frame of a completed run, written however little changed since the previous one
+pub async fn capture_final_colony_frame() {
+    capture_colony_frame(false).await;
+}
+
+/// A frame requested through POST /api/captures, written however little changed; its tick if written
+pub async fn capture_colony_on_demand() -> Option<u64> {
+    capture_colony_frame(false).await
+}
+
+/// Tick of the frame written, None when the capture was skipped or failed
+async fn capture_colony_frame(skip_unchanged: bool) -> Option<u64> {
     log!("Starting creature image capture");
     
     // Get topology
@@ -19,7 +120,7 @@ pub async fn capture_colony() {
         Some(t) => t,
         None => {
             log_error!("Topology not initialized, skipping image capture");
-            return;
+            return None;
         }
     };
     
@@ -28,7 +129,7 @@ pub async fn capture_colony() {
         Some(dims) => dims,
         None => {
             log_error!("Could not determine colony dimensions, skipping image capture");
-            return;
+            return None;
         }
     };
     
@@ -36,14 +137,14 @@ pub async fn capture_colony() {
     let shards = topology.get_all_shards();
     if shards.is_empty() {
         log_error!("No shards in topology, skipping image capture");
-        return;
+        return None;
     }
     
-    // Get current tick from first available shard
-    let current_tick = shards
-        .first()
-        .and_then(|shard| backend_client::call_backend_for_tick_count(*shard))
-        .unwrap_or(0);
+    let colony_tick = backend_client::get_colony_tick(&shards).ok();
+    if let Some(note) = colony_tick.as_ref().and_then(|colony_tick| colony_tick.fallback_note()) {
+        log!("Image capture: {}", note);
+    }
+    let current_tick = colony_tick.map_or(0, |colony_tick| colony_tick.tick);
     
     // Format tick as zero-padded 7-digit string
     let tick_str = format_tick_filename(current_tick);
@@ -52,45 +153,237 @@ pub async fn capture_colony() {
         Some(id) => id,
         None => {
             log_error!("Colony instance ID is not set, skipping image capture");
-            return;
+            return None;
         }
     };
     
-    // Collect shard images
+    let min_fraction = min_changed_fraction();
+    let last_frame_tick = CoordinatorContext::get_instance().capture_summary().as_ref()
+        .filter(|summary| summary.instance_id == instance_id)
+        .and_then(|summary| summary.last_frame_tick);
+    // A tick behind the last frame means the colony restarted, so always capture then
+    if let Some(last_tick) = last_frame_tick.filter(|&tick| skip_unchanged && tick <= current_tick) {
+        let colony_pixels = colony_width as u64 * colony_height as u64;
+        let estimate = estimate_changed_pixels(&shards, |shard| {
+            let topology = &topology;
+            async move { fetch_estimated_changed_pixels(topology, shard, last_tick).await }
+        }).await;
+        if let Some(estimated_changed) = estimate {
+            if skips_unchanged_frame(estimated_changed, colony_pixels, min_fraction) {
+                log!("Skipping capture at tick {}: about {} of {} pixels changed since tick {} ({:.5} < {})",
+                     current_tick, estimated_changed, colony_pixels, last_tick,
+                     estimated_changed as f64 / colony_pixels as f64, min_fraction);
+                update_capture_summary(&instance_id, min_fraction, |summary| summary.frames_skipped += 1);
+                return None;
+            }
+        }
+    }
+
+    // Fetch every shard independently, a slow or failing backend only costs its own tiles
+    let frame = stitch_colony_frame(&shards, colony_width, colony_height, SHARD_FETCH_TIMEOUT, |shard| {
+        let topology = &topology;
+        async move { get_shard_creature_image_http(topology, shard).await }
+    }).await;
+    let missing_shard_ids = frame.missing_shard_ids();
+    log!("Collected {} of {} shard images", frame.total_shards - frame.missing_shards.len(), frame.total_shards);
+
+    let max_missing = max_missing_fraction();
+    let dir_path = images_dir(&instance_id);
+    match write_frame(&dir_path, current_tick, &frame, max_missing) {
+        Ok(true) => {
+            update_capture_summary(&instance_id, min_fraction, |summary| {
+                summary.frames_written += 1;
+                summary.last_frame_tick = Some(current_tick);
+                summary.last_missing_shards = missing_shard_ids.clone();
+            });
+            if missing_shard_ids.is_empty() {
+                log!("Successfully saved creature image to: {}/{}.png", dir_path.display(), tick_str);
+            } else {
+                log!("Saved creature image to: {}/{}.png with {} missing shard(s): {}",
+                     dir_path.display(), tick_str, missing_shard_ids.len(), missing_shard_ids.join(", "));
+            }
+            Some(current_tick)
+        }
+        Ok(false) => {
+            update_capture_summary(&instance_id, min_fraction, |summary| {
+                summary.frames_skipped_missing_shards += 1;
+                summary.last_missing_shards = missing_shard_ids.clone();
+            });
+            log_error!("Skipping capture at tick {}: {} of {} shards missing ({:.2} > {}): {}",
+                       current_tick, missing_shard_ids.len(), frame.total_shards, frame.missing_fraction(),
+                       max_missing, missing_shard_ids.join(", "));
+            None
+        }
+        Err(e) => {
+            log_error!("Failed to save image to disk: {}", e);
+            None
+        }
+    }
+}
+
+/// Stitches the live colony for the viewer page, with the tick of the first shard that answers
+pub async fn current_colony_frame() -> Result<(u64, StitchedFrame), String> {
+    let topology = ClusterTopology::get_instance().ok_or("Topology not initialized")?;
+    let (colony_width, colony_height) = get_colony_dimensions(&topology)
+        .ok_or("Could not determine colony dimensions")?;
+    let shards = topology.get_all_shards();
+    if shards.is_empty() {
+        return Err("No shards in topology".to_string());
+    }
+    let tick_shards = shards.clone();
+    let colony_tick = tokio::task::spawn_blocking(move || backend_client::get_colony_tick(&tick_shards)).await.ok().and_then(Result::ok);
+    if let Some(note) = colony_tick.as_ref().and_then(|colony_tick| colony_tick.fallback_note()) {
+        log!("Viewer frame: {}", note);
+    }
+    let current_tick = colony_tick.map_or(0, |colony_tick| colony_tick.tick);
+
+    let frame = stitch_colony_frame(&shards, colony_width, colony_height, SHARD_FETCH_TIMEOUT, |shard| {
+        let topology = &topology;
+        async move { get_shard_creature_image_http(topology, shard).await }
+    }).await;
+    Ok((current_tick, frame))
+}
+
+/// Fetches every shard concurrently, each bounded by per_shard_timeout, and stitches them
+/// into one colony image. Shards that fail or time out become hatched gray placeholders.
+pub async fn stitch_colony_frame<F, Fut>(
+    shards: &[Shard],
+    colony_width: i32,
+    colony_height: i32,
+    per_shard_timeout: Duration,
+    mut fetch_shard: F,
+) -> StitchedFrame
+where
+    F: FnMut(Shard) -> Fut,
+    Fut: Future<Output = Option<Vec<Color>>>,
+{
+    let fetches = shards.iter().map(|shard| tokio::time::timeout(per_shard_timeout, fetch_shard(*shard)));
+    let results = join_all(fetches).await;
+
     let mut shard_images: Vec<(Shard, Vec<Color>)> = Vec::new();
-    
-    for shard in &shards {
-        match get_shard_creature_image_http(&topology, *shard).await {
-            Some(colors) => {
-                shard_images.push((*shard, colors));
+    let mut missing_shards = Vec::new();
+    for (shard, result) in shards.iter().zip(results) {
+        match result {
+            Ok(Some(colors)) if colors.len() == (shard.width * shard.height) as usize => shard_images.push((*shard, colors)),
+            Ok(Some(colors)) => {
+                log_error!("Shard {} image has {} pixels, expected {}", shard.to_id(), colors.len(), shard.width * shard.height);
+                missing_shards.push(*shard);
+            }
+            Ok(None) => {
+                log_error!("Failed to retrieve image for shard {}", shard.to_id());
+                missing_shards.push(*shard);
             }
-            None => {
-                log_error!("Failed to retrieve image for shard {:?}", shard);
+            Err(_) => {
+                log_error!("Timed out after {:?} retrieving image for shard {}", per_shard_timeout, shard.to_id());
+                missing_shards.push(*shard);
             }
         }
     }
-    
-    if shard_images.is_empty() {
-        log_error!("No shard images collected, skipping image save");
-        return;
+
+    let mut image = combine_shard_images(&shard_images, colony_width, colony_height);
+    for shard in &missing_shards {
+        draw_missing_shard_placeholder(&mut image, shard);
     }
-    
-    log!("Collected {} shard images", shard_images.len());
-    
-    // Combine shard images into single image
-    let combined_image = combine_shard_images(&shard_images, colony_width, colony_height);
-    
-    // Save image to disk
-    if let Err(e) = save_image_to_disk(&combined_image, &instance_id, &tick_str) {
-        log_error!("Failed to save image to disk: {}", e);
-        return;
+    StitchedFrame { image, total_shards: shards.len(), missing_shards }
+}
+
+/// Writes {tick}.png into dir_path, plus a {tick}.missing.json sidecar when shards are missing.
+/// Returns false without writing anything when more than max_missing_fraction of shards are missing.
+pub fn write_frame(dir_path: &Path, tick: u64, frame: &StitchedFrame, max_missing_fraction: f64) -> Result<bool, String> {
+    if frame.missing_shards.len() == frame.total_shards || frame.missing_fraction() > max_missing_fraction {
+        return Ok(false);
+    }
+
+    let tick_str = format_tick_filename(tick);
+    save_image_to_disk(&frame.image, dir_path, &tick_str)?;
+
+    if !frame.missing_shards.is_empty() {
+        let sidecar = MissingShardsSidecar {
+            tick,
+            total_shards: frame.total_shards,
+            missing_shards: frame.missing_shard_ids(),
+        };
+        let sidecar_path = dir_path.join(format!("{}.missing.json", tick_str));
+        let json = serde_json::to_string_pretty(&sidecar).map_err(|e| e.to_string())?;
+        std::fs::write(&sidecar_path, json)
+            .map_err(|e| format!("Failed to write {}: {}", sidecar_path.display(), e))?;
+    }
+    Ok(true)
+}
+
+/// Whether a periodic frame is skipped: the backends estimate that fewer than min_fraction of
+/// the colony's pixels changed since the last written frame
+pub fn skips_unchanged_frame(estimated_changed: u64, colony_pixels: u64, min_fraction: f64) -> bool {
+    colony_pixels > 0 && (estimated_changed as f64 / colony_pixels as f64) < min_fraction
+}
+
+/// Sum of the backends' estimates of the pixels changed on every shard, asked concurrently.
+/// The estimates come from per-tick counts of deaths, births and moves, not from comparing
+/// images. None if any shard could not be asked, in which case the frame is written as usual.
+pub async fn estimate_changed_pixels<F, Fut>(shards: &[Shard], mut estimate_shard: F) -> Option<u64>
+where
+    F: FnMut(Shard) -> Fut,
+    Fut: Future<Output = Option<u64>>,
+{
+    join_all(shards.iter().map(|shard| estimate_shard(*shard))).await.into_iter().sum()
+}
+
+/// A shard's changed_pixels from GET /api/shard/{id}/image-changed
+async fn fetch_estimated_changed_pixels(topology: &ClusterTopology, shard: Shard, since_tick: u64) -> Option<u64> {
+    let host_info = topology.get_host_for_shard(&shard)?;
+    let http_port = get_backend_http_port(host_info).await?;
+    let url = format!("http://{}:{}/api/shard/{}/image-changed?since_tick={}",
+                      host_info.hostname, http_port, shard.to_id(), since_tick);
+
+    let url_clone = url.clone();
+    let body = tokio::task::spawn_blocking(move || {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_millis(1500))
+            .build()
+            .ok()?;
+        let mut request = client.get(&url_clone);
+        if let Some(token) = ApiAuthConfig::get_instance().admin_token() {
+            request = request.header(reqwest::header::AUTHORIZATION, bearer_header_value(token));
+        }
+        let response = request.send().ok()?;
+        if !response.status().is_success() {
+            return None;
+        }
+        response.json::<serde_json::Value>().ok()
+    }).await.ok().flatten();
+
+    let changed = body.as_ref().and_then(|b| b.get("changed_pixels")).and_then(|v| v.as_u64());
+    if changed.is_none() {
+        log_error!("Failed to get changed pixel count from {}", url);
+    }
+    changed
+}
+
+/// Applies an update to the capture summary and rewrites images_shots/capture_summary.json
+fn update_capture_summary(instance_id: &str, min_fraction: f64, update: impl FnOnce(&mut CaptureSummary)) {
+    let summary = {
+        let mut guard = CoordinatorContext::get_instance().capture_summary();
+        if guard.as_ref().is_none_or(|summary| summary.instance_id != instance_id) {
+            *guard = Some(CaptureSummary { instance_id: instance_id.to_string(), ..Default::default() });
+        }
+        let summary = guard.as_mut().expect("summary initialized above");
+        summary.min_changed_fraction = min_fraction;
+        update(summary);
+        summary.clone()
+    };
+
+    let dir_path = images_dir(instance_id);
+    let result = std::fs::create_dir_all(&dir_path)
+        .map_err(|e| e.to_string())
+        .and_then(|_| serde_json::to_string_pretty(&summary).map_err(|e| e.to_string()))
+        .and_then(|json| std::fs::write(dir_path.join("capture_summary.json"), json).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        log_error!("Failed to write capture summary to {}: {}", dir_path.display(), e);
     }
-    
-    log!("Successfully saved creature image to: {}/{}/images_shots/{}.png", BASE_BUCKET_DIR, instance_id, tick_str);
 }
 
 /// Get colony dimensions from topology
-fn get_colony_dimensions(topology: &ClusterTopology) -> Option<(i32, i32)> {
+pub fn get_colony_dimensions(topology: &ClusterTopology) -> Option<(i32, i32)> {
     let shard_width = topology.get_shard_width_from_mapping();
     let shard_height = topology.get_shard_height_from_mapping();
     let width_in_shards = topology.calculate_width_in_shards();
@@ -118,6 +411,9 @@ fn get_colony_instance_id_prefix() -> Option<String> {
     stored_info.colony_instance_id.clone()
 }
 
+/// Background of the captured creature images; plain, so captures match the runs before it was configurable
+const CAPTURE_IMAGE_BACKGROUND: ImageBackground = ImageBackground::Plain;
+
 /// Get shard creature image via HTTP API
 async fn get_shard_creature_image_http(topology: &ClusterTopology, shard: Shard) -> Option<Vec<Color>> {
     let host_info = topology.get_host_for_shard(&shard)?;
@@ -126,7 +422,7 @@ async fn get_shard_creature_image_http(topology: &ClusterTopology, shard: Shard)
     let http_port = get_backend_http_port(host_info).await?;
     
     let shard_id = shard.to_id();
-    let url = format!("http://{}:{}/api/shard/{}/image", host_info.hostname, http_port, shard_id);
+    let url = format!("http://{}:{}{}", host_info.hostname, http_port, CAPTURE_IMAGE_BACKGROUND.shard_image_path(&shard_id));
     let width = shard.width as usize;
     let height = shard.height as usize;
     
@@ -141,11 +437,13 @@ async fn get_shard_creature_image_http(topology: &ClusterTopology, shard: Shard)
             .build()
             .ok()?;
         
-        let response = client
+        let mut request = client
             .get(&url_clone)
-            .header(reqwest::header::ACCEPT_ENCODING, "gzip")
-            .send()
-            .ok()?;
+            .header(reqwest::header::ACCEPT_ENCODING, "gzip");
+        if let Some(token) = ApiAuthConfig::get_instance().admin_token() {
+            request = request.header(reqwest::header::AUTHORIZATION, bearer_header_value(token));
+        }
+        let response = request.send().ok()?;
         
         let status = response.status();
         let content_encoding = response
@@ -209,7 +507,7 @@ async fn get_shard_creature_image_http(topology: &ClusterTopology, shard: Shard)
 }
 
 /// Get backend HTTP port using SSM discovery (similar to GUI pattern)
-async fn get_backend_http_port(host_info: &HostInfo) -> Option<u16> {
+pub async fn get_backend_http_port(host_info: &HostInfo) -> Option<u16> {
     // Try to discover backend HTTP port using SSM
     // Try both localhost and aws modes (similar to GUI pattern)
     for mode in &["localhost", "aws"] {
@@ -226,7 +524,15 @@ async fn get_backend_http_port(host_info: &HostInfo) -> Option<u16> {
         }
     }
     
-    None
+    probe_backend_http_port(host_info).await
+}
+
+/// Finds the HTTP port of a backend the registry has none for by probing /health
+async fn probe_backend_http_port(host_info: &HostInfo) -> Option<u16> {
+    let host_info = host_info.clone();
+    tokio::task::spawn_blocking(move || {
+        CoordinatorContext::get_instance().http_port_probe().probe(&host_info, Instant::now(), answers_health)
+    }).await.ok().flatten()
 }
 
 /// Combine shard images into a single colony image
@@ -274,11 +580,20 @@ fn combine_shard_images(shard_images: &[(Shard, Vec<Color>)], colony_width: i32,
     combined
 }
 
-/// Save PNG image to disk with bucket directory structure
-fn save_image_to_disk(image: &RgbImage, instance_id: &str, tick_str: &str) -> Result<(), String> {
-    // Build directory path: output/s3/distributed-colony/{id}/images_shots
-    let dir_path = Path::new(BASE_BUCKET_DIR).join(instance_id).join("images_shots");
-    if let Err(e) = std::fs::create_dir_all(&dir_path) {
+/// Fill a shard's area with gray and diagonal hatching, so a missing shard is never mistaken for empty land
+fn draw_missing_shard_placeholder(image: &mut RgbImage, shard: &Shard) {
+    let (image_width, image_height) = image.dimensions();
+    for y in shard.y.max(0) as u32..((shard.y + shard.height).max(0) as u32).min(image_height) {
+        for x in shard.x.max(0) as u32..((shard.x + shard.width).max(0) as u32).min(image_width) {
+            let on_hatch = (x + y) % PLACEHOLDER_HATCH_SPACING < 2;
+            image.put_pixel(x, y, if on_hatch { PLACEHOLDER_HATCH } else { PLACEHOLDER_GRAY });
+        }
+    }
+}
+
+/// Save PNG image to disk, dir_path is images_dir(id)
+fn save_image_to_disk(image: &RgbImage, dir_path: &Path, tick_str: &str) -> Result<(), String> {
+    if let Err(e) = std::fs::create_dir_all(dir_path) {
         return Err(format!("Failed to create directory {}: {}", dir_path.display(), e));
     }
     
@@ -0,0 +1,61 @@
+//! GET /api/density: the per-shard density grids of the backends stitched into one colony grid,
+//! cached until the colony moves past the tick it was taken at
+use futures_util::future::join_all;
+use shared::cluster_topology::ClusterTopology;
+use shared::colony_model::Shard;
+use shared::density::{ColonyDensity, DensityGrid, MAX_DENSITY_CELLS};
+use shared::log_error;
+use std::sync::Arc;
+use crate::backend_client;
+use crate::colony_capture::get_colony_dimensions;
+use crate::coordinator_context::CoordinatorContext;
+use crate::tick_monitor::latest_min_tick;
+
+/// Buckets per side to ask each shard for, so that a shard bucket covers at most half a colony
+/// bucket along either axis and the stitch stays close to counting at full resolution
+pub fn shard_density_cells(cells: usize, colony: (i32, i32), shard: &Shard) -> usize {
+    let along = |colony_extent: i32, shard_extent: i32| (2 * cells * shard_extent.max(1) as usize).div_ceil(colony_extent.max(1) as usize);
+    along(colony.0, shard.width).max(along(colony.1, shard.height)).clamp(1, MAX_DENSITY_CELLS)
+}
+
+/// The colony grid from what each shard returned; a shard that failed counts as empty
+pub fn assemble_colony_density(area: Shard, cells: usize, results: Vec<(Shard, Result<DensityGrid, String>)>) -> ColonyDensity {
+    let mut parts = Vec::new();
+    let mut missing_shards = Vec::new();
+    for (shard, result) in results {
+        match result {
+            Ok(grid) => parts.push(grid),
+            Err(e) => {
+                log_error!("Density of shard {} unavailable: {}", shard.to_id(), e);
+                missing_shards.push(shard.to_id());
+            }
+        }
+    }
+    ColonyDensity { grid: DensityGrid::stitch(area, cells, &parts), missing_shards }
+}
+
+/// The cached colony grid at cells buckets per side, fetched again once the sampled colony tick
+/// passes the tick it was taken at
+pub async fn colony_density(cells: usize) -> Result<Arc<ColonyDensity>, String> {
+    let context = CoordinatorContext::get_instance();
+    if let Some(cached) = context.get_colony_density(cells) {
+        if latest_min_tick().is_some_and(|tick| tick <= cached.grid.tick) {
+            return Ok(cached);
+        }
+    }
+
+    let topology = ClusterTopology::get_instance().ok_or("Topology not initialized")?;
+    let (colony_width, colony_height) = get_colony_dimensions(&topology).ok_or("Could not determine colony dimensions")?;
+    let area = Shard { x: 0, y: 0, width: colony_width, height: colony_height };
+    let fetches = topology.get_all_shards().into_iter().map(|shard| async move {
+        let shard_cells = shard_density_cells(cells, (colony_width, colony_height), &shard);
+        let result = tokio::task::spawn_blocking(move || backend_client::call_backend_get_shard_density(shard, shard_cells))
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|result| result.map_err(|e| e.to_string()));
+        (shard, result)
+    });
+    let density = Arc::new(assemble_colony_density(area, cells, join_all(fetches).await));
+    context.set_colony_density(cells, density.clone());
+    Ok(density)
+}
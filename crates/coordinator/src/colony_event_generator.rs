@@ -1,11 +1,183 @@
 use shared::colony_events::{ColonyEvent, Region, Ellipse, CreateCreatureParams, ColonyRuleChange};
+use shared::colony_event_shared::event_type_name;
 use shared::be_api::Traits;
 use shared::utils::random_color;
 use shared::be_api::ColonyLifeRules;
+use shared::{log, log_error};
 use rand::{rngs::SmallRng, Rng};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
 
+use crate::biomes::randomize_biome_shift;
 use crate::coordinator_context::CoordinatorContext;
 
+/// Colony tick stamped on generated events. The ticker only watches one shard and the tick
+/// history sweep lags behind, so each stamp is the highest tick known, and never lower than
+/// the previous stamp.
+#[derive(Debug, Default)]
+pub struct EventTickClock {
+    last_stamped: u64,
+}
+
+impl EventTickClock {
+    pub fn stamp(&mut self, intended_tick: u64, latest_max_tick: Option<u64>) -> u64 {
+        let tick = latest_max_tick.unwrap_or(0).max(intended_tick).max(self.last_stamped);
+        self.last_stamped = tick;
+        tick
+    }
+}
+
+/// Path of a JSON file with the event generator config; the defaults apply when unset or unreadable
+const EVENT_GENERATOR_CONFIG_ENV: &str = "EVENT_GENERATOR_CONFIG";
+/// Colony event recorded when the population guard holds back a generated event
+pub const EVENT_SUPPRESSED_EVENT: &str = "Event Suppressed";
+
+/// What a generated event does to the colony's population
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EventImpact {
+    Harmful,
+    Neutral,
+    Beneficial,
+}
+
+/// When the generator holds back harmful events, and which events are harmful
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EventGeneratorConfig {
+    /// Harmful events are held back while the last capture counted fewer creatures. The default,
+    /// 1000, is under 2% of the cells of a single 250x250 shard, so only a colony close to dying
+    /// out is spared.
+    pub min_creatures: u64,
+    /// Harmful events are held back while the creature count fell by more than this fraction, 0..1,
+    /// over the decline window
+    pub max_decline_fraction: f64,
+    /// Stats captures the decline is measured over
+    pub decline_window: usize,
+    /// Emit a food bloom in place of a held-back event
+    #[serde(default)]
+    pub favor_beneficial: bool,
+    /// Impact per event type, as named in the colony events; unlisted types are neutral
+    pub impacts: BTreeMap<String, EventImpact>,
+}
+
+impl EventGeneratorConfig {
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let config: EventGeneratorConfig = serde_json::from_str(json).map_err(|e| format!("Invalid event generator config: {}", e))?;
+        if config.decline_window == 0 {
+            return Err("decline_window needs to be at least 1".to_string());
+        }
+        if !(0.0..=1.0).contains(&config.max_decline_fraction) {
+            return Err(format!("max_decline_fraction {} is outside 0..1", config.max_decline_fraction));
+        }
+        Ok(config)
+    }
+
+    pub fn default_config() -> Self {
+        let impacts = [
+            ("Less Food", EventImpact::Harmful),
+            ("Extinction", EventImpact::Harmful),
+            ("New Topography", EventImpact::Harmful),
+            ("Colony Rules Change", EventImpact::Harmful),
+            ("More Food", EventImpact::Beneficial),
+        ];
+        Self {
+            min_creatures: 1000,
+            max_decline_fraction: 0.5,
+            decline_window: 3,
+            favor_beneficial: true,
+            impacts: impacts.into_iter().map(|(event_type, impact)| (event_type.to_string(), impact)).collect(),
+        }
+    }
+
+    pub(crate) fn from_env() -> Self {
+        let Ok(path) = std::env::var(EVENT_GENERATOR_CONFIG_ENV) else {
+            return Self::default_config();
+        };
+        match std::fs::read_to_string(&path).map_err(|e| e.to_string()).and_then(|json| Self::parse(&json)) {
+            Ok(config) => {
+                log!("Loaded the event generator config from {}", path);
+                config
+            }
+            Err(e) => {
+                log_error!("Failed to load the event generator config from {}, using the defaults: {}", path, e);
+                Self::default_config()
+            }
+        }
+    }
+
+    pub fn impact(&self, event: &ColonyEvent) -> EventImpact {
+        self.impacts.get(event_type_name(event)).copied().unwrap_or(EventImpact::Neutral)
+    }
+}
+
+/// A generated event after the population guard looked at it
+#[derive(Debug)]
+pub enum ScreenedEvent {
+    Allowed(ColonyEvent),
+    Suppressed { suppressed: ColonyEvent, reason: String, replacement: Option<ColonyEvent> },
+}
+
+/// Holds back harmful events while the colony is small or shrinking fast, judged by the
+/// creature counts of the latest stats captures
+#[derive(Debug)]
+pub struct PopulationGuard {
+    config: EventGeneratorConfig,
+    /// Creature counts of the latest captures, oldest first, at most decline_window + 1
+    counts: VecDeque<u64>,
+}
+
+impl PopulationGuard {
+    pub fn new(config: EventGeneratorConfig) -> Self {
+        Self { config, counts: VecDeque::new() }
+    }
+
+    pub fn record_capture(&mut self, creatures_count: u64) {
+        self.counts.push_back(creatures_count);
+        while self.counts.len() > self.config.decline_window + 1 {
+            self.counts.pop_front();
+        }
+    }
+
+    /// Forgets the captures of the previous colony, on colony start
+    pub fn reset(&mut self) {
+        self.counts.clear();
+    }
+
+    /// Why harmful events are held back now; None while the colony is healthy or before any capture
+    pub fn harmful_block_reason(&self) -> Option<String> {
+        let latest = *self.counts.back()?;
+        if latest < self.config.min_creatures {
+            return Some(format!("{} creatures, below {}", latest, self.config.min_creatures));
+        }
+        let oldest = *self.counts.front()?;
+        let decline = if oldest > 0 { oldest.saturating_sub(latest) as f64 / oldest as f64 } else { 0.0 };
+        if decline > self.config.max_decline_fraction {
+            return Some(format!("creatures fell {:.0}% from {} to {} over {} captures",
+                                decline * 100.0, oldest, latest, self.counts.len() - 1));
+        }
+        None
+    }
+
+    pub fn allows(&self, impact: EventImpact) -> bool {
+        impact != EventImpact::Harmful || self.harmful_block_reason().is_none()
+    }
+
+    /// Lets the event through unless it is harmful while the colony struggles
+    pub fn screen(&self, event: ColonyEvent, rng: &mut SmallRng) -> ScreenedEvent {
+        let reason = match self.harmful_block_reason() {
+            Some(reason) if !self.allows(self.config.impact(&event)) => reason,
+            _ => return ScreenedEvent::Allowed(event),
+        };
+        let replacement = self.config.favor_beneficial.then(|| ColonyEvent::ChangeExtraFoodPerTick(rng.gen_range(1..5)));
+        ScreenedEvent::Suppressed { suppressed: event, reason, replacement }
+    }
+}
+
+/// Feeds a stats capture to the population guard of the event generator
+pub fn record_population(creatures_count: u64) {
+    CoordinatorContext::get_instance().population_guard().record_capture(creatures_count);
+}
+
 #[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
 pub enum EventFrequency {
     Normal,
@@ -13,6 +185,7 @@ pub enum EventFrequency {
     Extinction,
     Topography,
     ColonyRules,
+    Biomes,
 }
 
 pub fn randomize_colony_event(colony_width: i32, colony_height: i32, rng: &mut SmallRng) -> ColonyEvent {
@@ -50,6 +223,9 @@ pub fn randomize_event_by_frequency(frequency: EventFrequency, colony_width: i32
         },
         EventFrequency::ColonyRules => {
             randomize_colony_rules_change(CoordinatorContext::get_instance().get_colony_life_rules(), rng)
+        },
+        EventFrequency::Biomes => {
+            randomize_biome_shift(&CoordinatorContext::get_instance().get_biomes(), colony_width, colony_height, rng)
         }
     }
 }
@@ -70,11 +246,17 @@ pub fn get_next_event_tick_by_frequency(frequency: EventFrequency, rng: &mut Sma
         },
         EventFrequency::ColonyRules => {
             rng.gen_range(2000..3000)
+        },
+        EventFrequency::Biomes => {
+            rng.gen_range(3000..5000)
         }
     }
 }
 
-fn apply_random_change(value: u32, min_value: u32, rng: &mut SmallRng) -> (u32, u32) {
+// Random rule changes never go below 1, even where the rule allows 0
+const MIN_VALUE: u32 = 1;
+
+fn apply_random_change(value: u32, min_value: u32, max_value: u32, rng: &mut SmallRng) -> (u32, u32) {
     let old_value = value;
     
     // Calculate max change (20% of current value, rounded up)
@@ -83,11 +265,12 @@ fn apply_random_change(value: u32, min_value: u32, rng: &mut SmallRng) -> (u32,
     // Determine if we can decrease (only if current value > min_value)
     let can_decrease = value > min_value;
     
-    // Randomize increase/decrease, but only decrease if possible
-    let is_increase = if can_decrease {
-        rng.gen_bool(0.5)
-    } else {
-        true // force increase if we can't decrease
+    let can_increase = value < max_value;
+    
+    // Randomize increase/decrease, but only in a direction that stays in range
+    let is_increase = match (can_increase, can_decrease) {
+        (true, true) => rng.gen_bool(0.5),
+        (can_increase, _) => can_increase,
     };
     
     // Randomize the change amount between 1 and max_change
@@ -101,20 +284,20 @@ fn apply_random_change(value: u32, min_value: u32, rng: &mut SmallRng) -> (u32,
         value.saturating_add(change_amount)
     } else {
         value.saturating_sub(change_amount)
-    }.max(min_value);
+    }.clamp(min_value, max_value);
     
     (old_value, new_value)
 }
 
-fn apply_change_and_update(field: &mut u32, min_value: u32, rng: &mut SmallRng) -> (u32, u32) {
-    let (old, new) = apply_random_change(*field, min_value, rng);
+/// Changes a rule field randomly while keeping it inside its ColonyLifeRules::allowed_range
+fn apply_change_and_update(field: &mut u32, field_name: &str, rng: &mut SmallRng) -> (u32, u32) {
+    let range = ColonyLifeRules::allowed_range(field_name).expect("unknown colony life rule");
+    let (old, new) = apply_random_change(*field, range.min.max(MIN_VALUE), range.max, rng);
     *field = new;
     (old, new)
 }
 
 pub fn randomize_colony_rules_change(current_rules: ColonyLifeRules, rng: &mut SmallRng) -> ColonyEvent {
-    const MIN_VALUE: u32 = 1;
-    
     // Start with current rules passed as parameter
     let mut new_rules = current_rules;
     
@@ -126,6 +309,11 @@ pub fn randomize_colony_rules_change(current_rules: ColonyLifeRules, rng: &mut S
         "Health Cost If Can Move",
         "Mutation Chance",
         "Random Death Chance",
+        "Reproduction Food Cost",
+        "Reproduction Min Food",
+        "Mutation Size Step",
+        "Mutation Cost Step",
+        "Boolean Trait Flip Chance",
     ];
     
     // Randomly select which parameter to change
@@ -134,12 +322,17 @@ pub fn randomize_colony_rules_change(current_rules: ColonyLifeRules, rng: &mut S
     
     // Apply the random change
     let (old_value, new_value) = match display_name {
-        "Health Cost Per Size Unit" => apply_change_and_update(&mut new_rules.health_cost_per_size_unit, MIN_VALUE, rng),
-        "Eat Capacity Per Size Unit" => apply_change_and_update(&mut new_rules.eat_capacity_per_size_unit, MIN_VALUE, rng),
-        "Health Cost If Can Kill" => apply_change_and_update(&mut new_rules.health_cost_if_can_kill, MIN_VALUE, rng),
-        "Health Cost If Can Move" => apply_change_and_update(&mut new_rules.health_cost_if_can_move, MIN_VALUE, rng),
-        "Mutation Chance" => apply_change_and_update(&mut new_rules.mutation_chance, MIN_VALUE, rng),
-        "Random Death Chance" => apply_change_and_update(&mut new_rules.random_death_chance, MIN_VALUE, rng),
+        "Health Cost Per Size Unit" => apply_change_and_update(&mut new_rules.health_cost_per_size_unit, "health_cost_per_size_unit", rng),
+        "Eat Capacity Per Size Unit" => apply_change_and_update(&mut new_rules.eat_capacity_per_size_unit, "eat_capacity_per_size_unit", rng),
+        "Health Cost If Can Kill" => apply_change_and_update(&mut new_rules.health_cost_if_can_kill, "health_cost_if_can_kill", rng),
+        "Health Cost If Can Move" => apply_change_and_update(&mut new_rules.health_cost_if_can_move, "health_cost_if_can_move", rng),
+        "Mutation Chance" => apply_change_and_update(&mut new_rules.mutation_chance, "mutation_chance", rng),
+        "Random Death Chance" => apply_change_and_update(&mut new_rules.random_death_chance, "random_death_chance", rng),
+        "Reproduction Food Cost" => apply_change_and_update(&mut new_rules.reproduction_food_cost, "reproduction_food_cost", rng),
+        "Reproduction Min Food" => apply_change_and_update(&mut new_rules.reproduction_min_food, "reproduction_min_food", rng),
+        "Mutation Size Step" => apply_change_and_update(&mut new_rules.mutation_size_step, "mutation_size_step", rng),
+        "Mutation Cost Step" => apply_change_and_update(&mut new_rules.mutation_cost_step, "mutation_cost_step", rng),
+        "Boolean Trait Flip Chance" => apply_change_and_update(&mut new_rules.boolean_trait_flip_chance, "boolean_trait_flip_chance", rng),
         _ => panic!("Unknown parameter: {}", display_name),
     };
     
@@ -0,0 +1,221 @@
+use serde::{Deserialize, Serialize};
+use shared::be_api::{
+    BackendRequest, BackendResponse, Shard, StartTickingResponse, UpdateTopologyRequest, UpdateTopologyResponse
+};
+use shared::cluster_topology::{ClusterTopology, ExpandDirection, HostInfo};
+use shared::{log, log_error};
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use crate::coordinator_context::CoordinatorContext;
+use crate::global_topography::GlobalTopography;
+use crate::init_colony::{
+    colony_topography_info, connect_backend, connect_to_backend, receive_message, send_init_colony_shard, send_message, ShardTerrain,
+    send_start_ticking_to_backend
+};
+use crate::topology_push::publish_topology;
+
+#[derive(Deserialize, Debug)]
+pub struct ExpandColonyRequest {
+    pub direction: ExpandDirection,
+    /// Hostname or hostname:port of the backend that should host the new shards
+    #[serde(default)]
+    pub backend: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ExpandColonyResult {
+    pub width: i32,
+    pub height: i32,
+    pub backend: HostInfo,
+    pub new_shards: Vec<Shard>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ExpandColonyError {
+    InProgress,
+    TopologyNotInitialized,
+    UnknownBackend(String),
+    InvalidRules(String),
+    Failed(String),
+}
+
+/// Result of planning an expansion: the topology to switch to and where the new shards go
+#[derive(Debug)]
+pub struct ExpansionPlan {
+    pub topology: ClusterTopology,
+    pub new_shards: Vec<Shard>,
+    pub backend: HostInfo,
+    pub width: i32,
+    pub height: i32,
+}
+
+/// Clears the in-flight flag however the expansion ends
+struct ExpansionGuard;
+
+impl ExpansionGuard {
+    fn acquire() -> Option<Self> {
+        CoordinatorContext::get_instance().expansion_in_flight()
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .ok()
+            .map(|_| ExpansionGuard)
+    }
+}
+
+impl Drop for ExpansionGuard {
+    fn drop(&mut self) {
+        CoordinatorContext::get_instance().expansion_in_flight().store(false, Ordering::Release);
+    }
+}
+
+fn matches_backend(host: &HostInfo, requested: &str) -> bool {
+    host.hostname == requested || host.to_address() == requested
+}
+
+pub fn plan_expansion(topology: &ClusterTopology, direction: ExpandDirection, backend: Option<&str>) -> Result<ExpansionPlan, ExpandColonyError> {
+    let backend = match backend {
+        Some(requested) => topology.get_all_backend_hosts().iter()
+            .find(|host| matches_backend(host, requested))
+            .ok_or_else(|| ExpandColonyError::UnknownBackend(requested.to_string()))?,
+        None => topology.least_loaded_backend()
+            .ok_or_else(|| ExpandColonyError::Failed("Topology has no backends".to_string()))?,
+    };
+
+    let new_shards = topology.new_shards_for_expansion(direction);
+    if new_shards.is_empty() {
+        return Err(ExpandColonyError::Failed("Topology has no shards to expand from".to_string()));
+    }
+
+    let expanded = topology.with_added_shards(&new_shards, backend);
+    Ok(ExpansionPlan {
+        width: expanded.width_in_shards() * expanded.shard_width(),
+        height: expanded.height_in_shards() * expanded.shard_height(),
+        backend: backend.clone(),
+        new_shards,
+        topology: expanded,
+    })
+}
+
+async fn send_update_topology(backend_host: &HostInfo, topology: &ClusterTopology, width: i32, height: i32) -> Result<(), String> {
+    let mut stream = connect_to_backend(&backend_host.hostname, backend_host.port).await
+        .map_err(|e| format!("Connection failed: {}", e))?;
+
+    let request = BackendRequest::UpdateTopology(UpdateTopologyRequest {
+        topology: topology.clone(),
+        width,
+        height,
+    });
+    send_message(&mut stream, &request).await;
+
+    match receive_message::<BackendResponse>(&mut stream).await {
+        Some(BackendResponse::UpdateTopology(UpdateTopologyResponse::Ok)) => Ok(()),
+        Some(BackendResponse::UpdateTopology(UpdateTopologyResponse::ColonyNotInitialized)) => Err("colony not initialized".to_string()),
+        Some(BackendResponse::UpdateTopology(UpdateTopologyResponse::Error(msg))) => Err(msg),
+        Some(_) => Err("Unexpected response type".to_string()),
+        None => Err("Failed to receive response".to_string()),
+    }
+}
+
+/// Puts the backends in updated back on the topology and dimensions from before the expansion.
+/// New shards already created stay on their backend awaiting terrain, outside the topology,
+/// and a retried expansion takes them over.
+async fn roll_back_topology(updated: &[HostInfo], original: &ClusterTopology) {
+    let width = original.width_in_shards() * original.shard_width();
+    let height = original.height_in_shards() * original.shard_height();
+    for backend_host in updated {
+        match send_update_topology(backend_host, original, width, height).await {
+            Ok(()) => log!("Rolled {} back to the {}x{} topology", backend_host.to_address(), width, height),
+            Err(e) => log_error!("Failed to roll {} back to the {}x{} topology: {}", backend_host.to_address(), width, height, e),
+        }
+    }
+}
+
+/// Adds a column or row of shards to the running colony. Only one expansion runs at a time.
+pub async fn expand_colony(request: ExpandColonyRequest) -> Result<ExpandColonyResult, ExpandColonyError> {
+    let _guard = ExpansionGuard::acquire().ok_or(ExpandColonyError::InProgress)?;
+
+    let topology = ClusterTopology::get_instance().ok_or(ExpandColonyError::TopologyNotInitialized)?;
+    let plan = plan_expansion(&topology, request.direction, request.backend.as_deref())?;
+    let rules = CoordinatorContext::get_instance().get_colony_life_rules();
+    rules.validate().map_err(ExpandColonyError::InvalidRules)?;
+    log!("Expanding colony {:?} to {}x{}: {} new shards on {}",
+         request.direction, plan.width, plan.height, plan.new_shards.len(), plan.backend.to_address());
+
+    // Step 1: every backend learns the new topology and dimensions first, so the new shards
+    // pass dimension validation and edge shards start exchanging borders with them.
+    // Any failure up to step 3 puts the updated backends back on the original topology.
+    let mut updated = Vec::new();
+    for backend_host in plan.topology.get_all_backend_hosts() {
+        if let Err(e) = send_update_topology(backend_host, &plan.topology, plan.width, plan.height).await {
+            roll_back_topology(&updated, &topology).await;
+            return Err(ExpandColonyError::Failed(format!("UpdateTopology to {} failed: {}", backend_host.to_address(), e)));
+        }
+        updated.push(backend_host.clone());
+    }
+
+    // Step 2: create the new shards with the rules currently in effect, seeded like the initial ones.
+    // They hold still until their terrain arrives in step 4.
+    let (seeding, original_size) = {
+        let stored_info = CoordinatorContext::get_instance().get_coord_stored_info();
+        let run_config = stored_info.run_config.as_ref();
+        (run_config.map(|config| config.seeding).unwrap_or_default(),
+         run_config.map(|config| (config.colony_width, config.colony_height)))
+    };
+    let new_topology = Arc::new(plan.topology.clone());
+    for shard in &plan.new_shards {
+        let created = match connect_backend(&plan.backend).await {
+            Ok(mut stream) => send_init_colony_shard(&mut stream, &plan.backend, *shard, new_topology.clone(), rules, seeding, ShardTerrain::Deferred).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = created {
+            roll_back_topology(&updated, &topology).await;
+            return Err(ExpandColonyError::Failed(e.to_string()));
+        }
+    }
+
+    // Step 3: switch the coordinator over; topography routing below relies on it
+    if let Err(e) = ClusterTopology::replace(plan.topology.clone()) {
+        roll_back_topology(&updated, &topology).await;
+        return Err(ExpandColonyError::Failed(e.to_string()));
+    }
+    publish_topology();
+    let (topography_seed, extra_food_pattern) = {
+        let mut stored_info = CoordinatorContext::get_instance().get_coord_stored_info();
+        stored_info.colony_width = Some(plan.width);
+        stored_info.colony_height = Some(plan.height);
+        let run_config = stored_info.run_config.as_ref();
+        (run_config.map(|config| config.topography_seed), run_config.and_then(|config| config.extra_food_pattern))
+    };
+
+    // Step 4: terrain for the new region only, existing shards keep theirs. The field is drawn
+    // from the colony seed for the size the colony started at, so the new region continues the
+    // rivers, sanctuaries and extra food pattern of the original terrain.
+    let mut topography_info = colony_topography_info(&plan.topology);
+    let (original_width, original_height) = original_size.unwrap_or((
+        topology.width_in_shards() * topology.shard_width(),
+        topology.height_in_shards() * topology.shard_height(),
+    ));
+    topography_info.total_width = original_width as usize;
+    topography_info.total_height = original_height as usize;
+    topography_info.seed = topography_seed;
+    topography_info.extra_food_pattern = extra_food_pattern;
+    GlobalTopography::expanded(topography_info, plan.width as usize, plan.height as usize)
+        .generate_topography_for_shards(&plan.new_shards).await;
+
+    // The new shards take their part of the biomes, which stay where they were
+    crate::biomes::send_biomes_to_backends(std::slice::from_ref(&plan.backend)).await;
+
+    // Step 5: StartTicking is idempotent, so it is safe when the backend already ticks
+    match send_start_ticking_to_backend(&plan.backend).await {
+        Ok(StartTickingResponse::Ok) => {}
+        Ok(other) => log_error!("Backend {} did not start ticking after expansion: {:?}", plan.backend.to_address(), other),
+        Err(e) => log_error!("Failed to send StartTicking to {}: {}", plan.backend.to_address(), e),
+    }
+
+    log!("Colony expansion complete: {}x{}", plan.width, plan.height);
+    Ok(ExpandColonyResult {
+        width: plan.width,
+        height: plan.height,
+        backend: plan.backend,
+        new_shards: plan.new_shards,
+    })
+}
@@ -1,18 +1,101 @@
-use shared::cluster_topology::{ClusterTopology, HostInfo, NodeAddress, NodeStatus, TopologyConfig};
+use shared::cluster_topology::{ClusterTopology, HostInfo, NodeAddress, TopologyConfig};
 use shared::{log, log_error};
-use shared::colony_model::Shard;
-use shared::cluster_registry::{get_instance, ClusterRegistry};
+use shared::colony_model::{ExtraFoodPattern, SeedingOptions, Shard};
+use serde::{Deserialize, Serialize};
+use shared::cluster_registry::{get_instance, ClusterRegistry, ClusterRegistryImpl};
+use futures_util::future::join_all;
 use std::collections::HashMap;
+use std::time::Duration;
 use crate::init_colony::initialize_colony;
+use crate::colony_step::reset_ticker_state;
 use crate::coordinator_context::CoordinatorContext;
 use crate::coordinator_storage::ColonyStatus;
+use crate::coordinator_error::CoordinatorError;
 
-pub async fn colony_start_colony(idempotency_key: Option<String>) {
+/// Recorded in the run configuration; see create_shard_map_with_even_distribution
+pub const SHARD_ASSIGNMENT_STRATEGY: &str = "round-robin";
+/// Colony-start fails when fewer backends than this answer a ping
+const MIN_BACKENDS_ENV: &str = "COLONY_START_MIN_BACKENDS";
+const DEFAULT_MIN_BACKENDS: usize = 1;
+/// Backends are checked concurrently, so this bounds the whole check
+const BACKEND_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Why the last colony-start did not complete, reported by GET /colony-start
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ColonyStartFailure {
+    pub kind: &'static str,
+    pub error: String,
+    /// The status an HTTP call failing this way would answer with
+    pub http_status: &'static str,
+}
+
+impl From<&CoordinatorError> for ColonyStartFailure {
+    fn from(error: &CoordinatorError) -> Self {
+        Self { kind: error.kind(), error: error.to_string(), http_status: error.http_status() }
+    }
+}
+
+pub fn last_start_failure() -> Option<ColonyStartFailure> {
+    CoordinatorContext::get_instance().last_start_failure().clone()
+}
+
+fn record_start_failure(error: CoordinatorError) {
+    log_error!("Colony-start failed: {}", error);
+    *CoordinatorContext::get_instance().last_start_failure() = Some(ColonyStartFailure::from(&error));
+}
+
+/// Optional JSON body of POST /colony-start; an empty body starts with the defaults
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct ColonyStartRequest {
+    pub seeding: SeedingOptions,
+    /// Stops the run and writes a run summary once every shard reached this tick
+    pub target_tick: Option<u64>,
+    /// Ticks of warm-up after seeding, see crate::warmup
+    pub warmup_ticks: Option<u64>,
+    /// Initial extra food layout; None draws the procedural rivers
+    pub extra_food_pattern: Option<ExtraFoodPattern>,
+}
+
+impl ColonyStartRequest {
+    pub fn parse(body: &str) -> Result<Self, String> {
+        let request: Self = if body.trim().is_empty() {
+            Self::default()
+        } else {
+            serde_json::from_str(body).map_err(|e| format!("Invalid colony-start request: {}", e))?
+        };
+        request.seeding.validate()?;
+        if let Some(pattern) = &request.extra_food_pattern {
+            pattern.validate()?;
+        }
+        if request.target_tick == Some(0) {
+            return Err("target_tick must be positive".to_string());
+        }
+        if request.warmup_ticks == Some(0) {
+            return Err("warmup_ticks must be positive".to_string());
+        }
+        if let (Some(warmup_ticks), Some(target_tick)) = (request.warmup_ticks, request.target_tick) {
+            if warmup_ticks >= target_tick {
+                return Err("warmup_ticks must be below target_tick".to_string());
+            }
+        }
+        Ok(request)
+    }
+}
+
+pub async fn colony_start_colony(idempotency_key: Option<String>, request: ColonyStartRequest) {
     log!("Starting colony-start process: discovering backends and creating shard map");
+    *CoordinatorContext::get_instance().last_start_failure() = None;
+    reset_ticker_state();
     
     // Generate and store colony instance ID and idempotency key early (before topology initialization)
     // This ensures it's available as soon as the topology is ready and for GET /topology requests
     let context = CoordinatorContext::get_instance();
+    {
+        let mut stored_info = context.get_coord_stored_info();
+        stored_info.target_tick = request.target_tick;
+        stored_info.warmup_ticks = request.warmup_ticks;
+    }
     if let Some(key) = &idempotency_key {
         let mut stored_info = context.get_coord_stored_info();
         stored_info.colony_start_idempotency_key = Some(key.clone());
@@ -25,16 +108,17 @@ pub async fn colony_start_colony(idempotency_key: Option<String>) {
     }
     
     // Step 1: Discover available backend nodes from AWS config
-    let (available_backends, coordinator_address) = discover_and_ping_backends().await;
-    
-    if available_backends.is_empty() {
-        log_error!("No available backend nodes found. Cannot start colony.");
-        // Revert status to NotInitialized on failure
-        let context = CoordinatorContext::get_instance();
-        let mut stored_info = context.get_coord_stored_info();
-        stored_info.status = ColonyStatus::NotInitialized;
-        return;
-    }
+    let (available_backends, coordinator_address) = match discover_and_ping_backends().await {
+        Ok(discovered) => discovered,
+        Err(err) => {
+            record_start_failure(err);
+            // Revert status to NotInitialized on failure
+            let context = CoordinatorContext::get_instance();
+            let mut stored_info = context.get_coord_stored_info();
+            stored_info.status = ColonyStatus::NotInitialized;
+            return;
+        }
+    };
     
     log!("Found {} available backend nodes", available_backends.len());
     for backend in &available_backends {
@@ -74,9 +158,10 @@ pub async fn colony_start_colony(idempotency_key: Option<String>) {
     match ClusterTopology::initialize(config) {
         Ok(_) => {
             log!("ClusterTopology initialized with dynamic topology");
+            crate::topology_push::publish_topology();
         }
         Err(err) => {
-            log_error!("Failed to install dynamic topology: {}", err);
+            record_start_failure(CoordinatorError::TopologyRejected(err.to_string()));
             // Revert status to NotInitialized on failure
             let context = CoordinatorContext::get_instance();
             let mut stored_info = context.get_coord_stored_info();
@@ -88,7 +173,10 @@ pub async fn colony_start_colony(idempotency_key: Option<String>) {
     // Step 6: Initialize and start the colony
     // Note: coordinator_ticker should already be started in main()
     // initialize_colony() will set status to TopographyInitialized on success
-    initialize_colony().await;
+    if let Err(e) = initialize_colony(request.seeding, request.extra_food_pattern).await {
+        record_start_failure(e);
+        return;
+    }
     
     // Step 7: Colony instance ID and idempotency_key are already stored (done at the start)
     // Log completion with instance ID for visibility
@@ -102,13 +190,21 @@ pub async fn colony_start_colony(idempotency_key: Option<String>) {
     }
 }
 
-async fn discover_and_ping_backends() -> (Vec<HostInfo>, NodeAddress) {
+fn min_backends() -> usize {
+    std::env::var(MIN_BACKENDS_ENV)
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|&count| count > 0)
+        .unwrap_or(DEFAULT_MIN_BACKENDS)
+}
+
+async fn discover_and_ping_backends() -> Result<(Vec<HostInfo>, NodeAddress), CoordinatorError> {
     // Get coordinator address from ClusterRegistry
     let registry = match get_instance() {
         Some(r) => r,
         None => {
             log_error!("ClusterRegistry not initialized");
-            return (Vec::new(), NodeAddress::new("127.0.0.1".to_string(), "127.0.0.1".to_string(), 8082, 8083));
+            return Err(CoordinatorError::NoBackendsAvailable);
         }
     };
     
@@ -129,17 +225,56 @@ async fn discover_and_ping_backends() -> (Vec<HostInfo>, NodeAddress) {
         }
     };
     
+    let backends = discover_healthy_backends(&registry, &coordinator_address, min_backends()).await?;
+    Ok((backends, coordinator_address))
+}
+
+/// Pings every backend in the registry and keeps those that answer. On localhost the entries of
+/// backends that refuse the connection, left behind when a backend crashed, are deleted from the
+/// file registry; a backend that accepts but does not answer in time may only be busy and keeps
+/// its entry. SSM entries are left alone. Fails when fewer than min_backends answer.
+pub async fn discover_healthy_backends(
+    registry: &ClusterRegistryImpl,
+    coordinator_address: &NodeAddress,
+    min_backends: usize,
+) -> Result<Vec<HostInfo>, CoordinatorError> {
     // Discover backends from ClusterRegistry (works for both localhost and AWS)
     let backend_addresses = registry.discover_backends().await;
     log!("Discovered {} backends from ClusterRegistry", backend_addresses.len());
+    if backend_addresses.is_empty() {
+        return Err(CoordinatorError::NoBackendsAvailable);
+    }
     
     // Filter backends, excluding the coordinator
-    let filtered_backends = filter_backends_excluding_coordinator(backend_addresses, &coordinator_address).await;
+    let (healthy, unreachable) = filter_backends_excluding_coordinator(backend_addresses, coordinator_address).await;
+    if let ClusterRegistryImpl::File(file_registry) = registry {
+        for (address, _) in unreachable.iter().filter(|(_, check)| *check == BackendCheck::Refused) {
+            file_registry.remove_backend_entries(address);
+        }
+    }
     
-    (filtered_backends, coordinator_address)
+    if healthy.len() < min_backends {
+        return Err(CoordinatorError::NotEnoughBackends {
+            healthy: healthy.len(),
+            required: min_backends,
+            dead: unreachable.iter().map(|(address, _)| address.to_internal_address()).collect(),
+        });
+    }
+    Ok(healthy)
+}
+
+/// How a registered backend answered the discovery ping
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum BackendCheck {
+    Active,
+    /// Nothing listens on its port, e.g. the backend crashed
+    Refused,
+    /// Connected but no ping answer in time, or another build
+    NoAnswer,
 }
 
-/// Filter backend addresses, excluding the coordinator and inactive backends.
+/// Filter backend addresses, excluding the coordinator, and ping the rest concurrently.
+/// Returns the active backends and the addresses that did not answer, with how they failed.
 /// This function is extracted for testability.
 /// 
 /// In localhost mode, coordinator and backends share the same IP (127.0.0.1),
@@ -147,23 +282,32 @@ async fn discover_and_ping_backends() -> (Vec<HostInfo>, NodeAddress) {
 pub(crate) async fn filter_backends_excluding_coordinator(
     backend_addresses: Vec<NodeAddress>,
     coordinator_address: &NodeAddress,
-) -> Vec<HostInfo> {
+) -> (Vec<HostInfo>, Vec<(NodeAddress, BackendCheck)>) {
     let coordinator_internal_port = coordinator_address.internal_port;
     
-    // Filter to only active backends, excluding the coordinator itself
+    // Skip if this backend matches the coordinator's address (same IP and port)
+    // In localhost mode, IPs will match, so we check the port
+    let candidates: Vec<NodeAddress> = backend_addresses.into_iter()
+        .filter(|backend_address| {
+            let is_coordinator = backend_address.private_ip == coordinator_address.private_ip && 
+                backend_address.internal_port == coordinator_internal_port;
+            if is_coordinator {
+                log!("Skipping backend {}:{} (matches coordinator address)", backend_address.private_ip, backend_address.internal_port);
+            }
+            !is_coordinator
+        })
+        .collect();
+    
+    // Check if each backend is active by attempting to connect
+    let statuses = join_all(candidates.iter().map(|backend_address| async move {
+        tokio::time::timeout(BACKEND_CHECK_TIMEOUT, check_backend_status(backend_address)).await
+            .unwrap_or(BackendCheck::NoAnswer)
+    })).await;
+    
     let mut available_backends = Vec::new();
-    for backend_address in backend_addresses {
-        // Skip if this backend matches the coordinator's address (same IP and port)
-        // In localhost mode, IPs will match, so we check the port
-        if backend_address.private_ip == coordinator_address.private_ip && 
-           backend_address.internal_port == coordinator_internal_port {
-            log!("Skipping backend {}:{} (matches coordinator address)", backend_address.private_ip, backend_address.internal_port);
-            continue;
-        }
-        
-        // Check if backend is active by attempting to connect
-        let status = check_backend_status(&backend_address).await;
-        if status == NodeStatus::Active {
+    let mut unreachable = Vec::new();
+    for (backend_address, status) in candidates.into_iter().zip(statuses) {
+        if status == BackendCheck::Active {
             available_backends.push(HostInfo::new(
                 backend_address.private_ip,
                 backend_address.internal_port,
@@ -171,25 +315,30 @@ pub(crate) async fn filter_backends_excluding_coordinator(
         } else {
             log!("Skipping backend {}:{} (status: {:?})", 
                  backend_address.private_ip, backend_address.internal_port, status);
+            unreachable.push((backend_address, status));
         }
     }
     
-    available_backends
+    (available_backends, unreachable)
 }
 
-async fn check_backend_status(address: &NodeAddress) -> NodeStatus {
+async fn check_backend_status(address: &NodeAddress) -> BackendCheck {
     use tokio::time::{timeout, Duration};
     use tokio::net::TcpStream;
     use tokio_util::codec::{Framed, LengthDelimitedCodec};
     use futures_util::SinkExt;
     use tokio_stream::StreamExt;
     use shared::be_api::{BackendRequest, BackendResponse};
+    use shared::backend_communication::perform_handshake_async;
     
     let addr = address.to_address();
     let connect_timeout = Duration::from_secs(2);
     
     match timeout(connect_timeout, TcpStream::connect(&addr)).await {
-        Ok(Ok(stream)) => {
+        Ok(Ok(mut stream)) => {
+            if !matches!(timeout(connect_timeout, perform_handshake_async(&mut stream)).await, Ok(Ok(()))) {
+                return BackendCheck::NoAnswer;
+            }
             let mut framed = Framed::new(stream, LengthDelimitedCodec::new());
             
             // Send ping request to backend
@@ -201,16 +350,17 @@ async fn check_backend_status(address: &NodeAddress) -> NodeStatus {
                     match timeout(response_timeout, framed.next()).await {
                         Ok(Some(Ok(bytes))) => {
                             if let Ok(BackendResponse::Ping) = bincode::deserialize::<BackendResponse>(&bytes) {
-                                return NodeStatus::Active;
+                                return BackendCheck::Active;
                             }
                         }
                         _ => {}
                     }
                 }
             }
-            NodeStatus::Unknown
+            BackendCheck::NoAnswer
         }
-        _ => NodeStatus::Unknown,
+        Ok(Err(e)) if e.kind() == std::io::ErrorKind::ConnectionRefused => BackendCheck::Refused,
+        _ => BackendCheck::NoAnswer,
     }
 }
 
@@ -1,14 +1,19 @@
 use serde::Serialize;
 use std::collections::BTreeMap;
-use std::path::Path;
+use std::time::Instant;
 use shared::{log, log_error};
-use shared::be_api::StatMetric;
+use shared::be_api::{StatBucket, StatMetric};
+use shared::colony_model::Shard;
+use shared::coordinator_api::{ColonyMetricStats, SpeciesCluster};
+use shared::output_paths::OutputPaths;
 use crate::coordinator_context::CoordinatorContext;
+use crate::colony_stats_cache::CachedColonyStats;
+use crate::species_summary::{cluster_species, MAX_SPECIES_CLUSTERS, SPECIES_COLOR_RADIUS};
 use crate::backend_client;
+use crate::capture_gate::CaptureState;
 use shared::cluster_topology::ClusterTopology;
 use chrono::Utc;
 
-const BASE_BUCKET_DIR: &str = "output/s3/distributed-colony";
 const MIN_HISTOGRAM_COUNT: u64 = 20;
 const TOP_VALUES_LIMIT: usize = 20;
 
@@ -19,7 +24,13 @@ pub struct CreatureStatistics {
     pub tick: u64,
     #[serde(rename = "creatures_count")]
     pub creatures_count: u64,
+    /// Fraction of the creatures living inside sanctuaries, 0..1
+    #[serde(rename = "sanctuary_share")]
+    pub sanctuary_share: f64,
     pub histograms: Histograms,
+    /// Original colors clustered into at most MAX_SPECIES_CLUSTERS species
+    #[serde(rename = "dominant_species")]
+    pub dominant_species: Vec<SpeciesCluster>,
     pub meta: Metadata,
 }
 
@@ -58,6 +69,28 @@ pub struct Histograms {
     pub age: HistogramWithAverage,
     #[serde(rename = "original_color")]
     pub original_color: HistogramWithoutAverage,
+    /// Over all cells, 0 empty and 1 occupied; the average is the occupied fraction
+    #[serde(rename = "occupancy")]
+    pub occupancy: HistogramWithAverage,
+    /// Over all cells, 1 above FOOD_COVERAGE_MIN_FOOD; the average is the covered fraction
+    #[serde(rename = "food_coverage")]
+    pub food_coverage: HistogramWithAverage,
+}
+
+impl Histograms {
+    /// Average of every histogram that has one, keyed like the JSON fields
+    pub fn averages(&self) -> BTreeMap<String, f64> {
+        [
+            ("health", &self.health),
+            ("creature_size", &self.creature_size),
+            ("can_kill", &self.can_kill),
+            ("can_move", &self.can_move),
+            ("food", &self.food),
+            ("age", &self.age),
+            ("occupancy", &self.occupancy),
+            ("food_coverage", &self.food_coverage),
+        ].into_iter().map(|(name, histogram)| (name.to_string(), histogram.average)).collect()
+    }
 }
 
 /// Get all StatMetric variants
@@ -74,6 +107,9 @@ pub fn all_stat_metrics() -> Vec<StatMetric> {
         StatMetric::Food,
         StatMetric::Age,
         StatMetric::OriginalColor,
+        StatMetric::Sanctuary,
+        StatMetric::Occupancy,
+        StatMetric::FoodCoverage,
     ]
 }
 
@@ -97,6 +133,9 @@ pub fn enumerate_all_stat_metric_variants() -> Vec<StatMetric> {
             StatMetric::Food => StatMetric::Food,
             StatMetric::Age => StatMetric::Age,
             StatMetric::OriginalColor => StatMetric::OriginalColor,
+            StatMetric::Sanctuary => StatMetric::Sanctuary,
+            StatMetric::Occupancy => StatMetric::Occupancy,
+            StatMetric::FoodCoverage => StatMetric::FoodCoverage,
         }
     };
     
@@ -109,6 +148,9 @@ pub fn enumerate_all_stat_metric_variants() -> Vec<StatMetric> {
         StatMetric::Food,
         StatMetric::Age,
         StatMetric::OriginalColor,
+        StatMetric::Sanctuary,
+        StatMetric::Occupancy,
+        StatMetric::FoodCoverage,
     ]
 }
 
@@ -118,10 +160,23 @@ pub struct Metadata {
     pub created_at_utc: String,
     pub colony_width: Option<i32>,
     pub colony_height: Option<i32>,
+    /// ColonyRunConfig::config_hash of the run these stats belong to
+    pub config_hash: Option<String>,
 }
 
-/// Main function to capture colony statistics and save to disk
+/// Periodic statistics capture; skipped while the colony does not tick or its tick stands still
 pub async fn capture_colony_stats() {
+    let state = CaptureState::current();
+    if CoordinatorContext::get_instance().stats_capture_gate().check(&state).is_err() {
+        return;
+    }
+    if save_colony_stats().await.is_some() {
+        CoordinatorContext::get_instance().stats_capture_gate().record_capture(&state);
+    }
+}
+
+/// Collects the colony statistics and saves them to disk; returns them when collected
+pub async fn save_colony_stats() -> Option<CreatureStatistics> {
     log!("Starting creature statistics capture");
     
     // Get topology
@@ -129,7 +184,7 @@ pub async fn capture_colony_stats() {
         Some(t) => t,
         None => {
             log_error!("Topology not initialized, skipping statistics capture");
-            return;
+            return None;
         }
     };
     
@@ -137,7 +192,7 @@ pub async fn capture_colony_stats() {
     let shards = topology.get_all_shards();
     if shards.is_empty() {
         log_error!("No shards in topology, skipping statistics capture");
-        return;
+        return None;
     }
     
     // Collect statistics
@@ -146,24 +201,30 @@ pub async fn capture_colony_stats() {
     // Save to disk
     match stats_result {
         Ok(stats) => {
+            // Before taking the stored info lock, which raising an alarm needs for its event
+            crate::colony_stats_alarms::evaluate_capture(&stats);
+            crate::colony_event_generator::record_population(stats.creatures_count);
+            crate::lifecycle_events::check_extinction(stats.tick, stats.creatures_count);
             let context = CoordinatorContext::get_instance();
             let stored_info = context.get_coord_stored_info();
             let instance_id = match stored_info.colony_instance_id.as_deref() {
                 Some(id) => id,
                 None => {
                     log_error!("Colony instance ID is not set, skipping statistics capture");
-                    return;
+                    return Some(stats);
                 }
             };
             let tick_str = format_tick_filename(stats.tick);
             if let Err(e) = save_stats_to_disk(&stats, instance_id, &tick_str) {
                 log_error!("Failed to save statistics to disk: {}", e);
             } else {
-                log!("Successfully saved creature statistics to: {}/{}/stats_shots/{}.json", BASE_BUCKET_DIR, instance_id, tick_str);
+                log!("Successfully saved creature statistics to: {}/{}.json", OutputPaths::get_instance().stats_dir(instance_id).display(), tick_str);
             }
+            Some(stats)
         }
         Err(e) => {
             log_error!("Failed to collect statistics: {}", e);
+            None
         }
     }
 }
@@ -182,64 +243,29 @@ async fn collect_statistics(
         .to_string();
     let colony_width = stored_info.colony_width;
     let colony_height = stored_info.colony_height;
+    let config_hash = stored_info.run_config.as_ref().map(|config| config.config_hash());
     drop(stored_info);
 
-    // Get current tick from first available shard
-    let current_tick = shards
-        .first()
-        .and_then(|shard| backend_client::call_backend_for_tick_count(*shard))
-        .ok_or_else(|| "Could not get current tick".to_string())?;
+    let colony_tick = backend_client::get_colony_tick(shards)
+        .map_err(|e| format!("Could not get current tick: {}", e))?;
+    if let Some(note) = colony_tick.fallback_note() {
+        log!("Statistics capture: {}", note);
+    }
+    let current_tick = colony_tick.tick;
     
     // Collect histograms for all metrics
     let metrics = all_stat_metrics();
     
-    // Helper to map StatMetric to index
-    fn metric_id(m: StatMetric) -> u8 {
-        match m {
-            StatMetric::Health => 0,
-            StatMetric::Size => 1,
-            StatMetric::CanKill => 2,
-            StatMetric::CanMove => 3,
-            StatMetric::Food => 4,
-            StatMetric::Age => 5,
-            StatMetric::OriginalColor => 6,
-        }
-    }
-    
-    let mut pos_by_id: BTreeMap<u8, usize> = BTreeMap::new();
-    for (idx, m) in metrics.iter().copied().enumerate() {
-        pos_by_id.insert(metric_id(m), idx);
-    }
-    
-    let mut missing_shards = Vec::new();
-    let mut counts_per_metric: Vec<BTreeMap<i32, u64>> = vec![BTreeMap::new(); metrics.len()];
-    let mut string_counts_per_metric: Vec<BTreeMap<String, u64>> = vec![BTreeMap::new(); metrics.len()];
-    
-    for shard in shards {
-        match backend_client::call_backend_get_shard_stats(*shard, metrics.clone()) {
-            Some((_tick, per_metric, per_string_metric)) => {
-                for (_metric_idx, (metric, buckets)) in per_metric.into_iter().enumerate() {
-                    if let Some(&pos) = pos_by_id.get(&metric_id(metric)) {
-                        let entry = counts_per_metric.get_mut(pos).unwrap();
-                        for b in buckets {
-                            *entry.entry(b.value).or_insert(0) += b.occs;
-                        }
-                    }
-                }
-                for (_metric_idx, (metric, buckets)) in per_string_metric.into_iter().enumerate() {
-                    if let Some(&pos) = pos_by_id.get(&metric_id(metric)) {
-                        let entry = string_counts_per_metric.get_mut(pos).unwrap();
-                        for b in buckets {
-                            *entry.entry(b.value.clone()).or_insert(0) += b.occs;
-                        }
-                    }
-                }
-            }
-            None => {
-                missing_shards.push(shard.to_id());
-            }
-        }
-    }
+    let merged = fetch_merged_counts(shards, &metrics, None);
+    let dominant_species = species_summary(&metrics, &merged);
+    CoordinatorContext::get_instance().colony_stats_cache().store(CachedColonyStats {
+        colony_instance_id: Some(colony_instance_id.clone()),
+        tick: merged.max_tick,
+        stats: numeric_metric_stats(&metrics, &merged),
+        species: dominant_species.clone(),
+        computed_at: Instant::now(),
+    });
+    let MergedShardCounts { counts_per_metric, string_counts_per_metric, .. } = merged;
     
     // Build histograms with filtering (count >= 20)
     // Find indices for each metric we want to include in output
@@ -250,6 +276,9 @@ async fn collect_statistics(
     let mut food_idx = None;
     let mut age_idx = None;
     let mut original_color_idx = None;
+    let mut sanctuary_idx = None;
+    let mut occupancy_idx = None;
+    let mut food_coverage_idx = None;
     
     for (idx, metric) in metrics.iter().enumerate() {
         match metric {
@@ -260,6 +289,9 @@ async fn collect_statistics(
             StatMetric::Food => food_idx = Some(idx),
             StatMetric::Age => age_idx = Some(idx),
             StatMetric::OriginalColor => original_color_idx = Some(idx),
+            StatMetric::Sanctuary => sanctuary_idx = Some(idx),
+            StatMetric::Occupancy => occupancy_idx = Some(idx),
+            StatMetric::FoodCoverage => food_coverage_idx = Some(idx),
         }
     }
     
@@ -268,39 +300,43 @@ async fn collect_statistics(
         .and_then(|idx| counts_per_metric.get(idx))
         .map(|counts| counts.values().sum::<u64>())
         .unwrap_or(0);
+    // Sanctuary counts creatures as 0 (outside) or 1 (inside), so its average is the share inside
+    let sanctuary_share = sanctuary_idx
+        .map(|idx| build_histogram(&counts_per_metric[idx], HistogramBucketing::Exact).average)
+        .unwrap_or(0.0);
     
     let histograms = Histograms {
-        health: health_idx.map(|idx| build_histogram(&counts_per_metric[idx], false)).unwrap_or_else(|| HistogramWithAverage {
+        health: health_idx.map(|idx| build_histogram(&counts_per_metric[idx], HistogramBucketing::for_metric(StatMetric::Health))).unwrap_or_else(|| HistogramWithAverage {
             distribution: BTreeMap::new(),
             average: 0.0,
             was_cut: false,
             unique_values_count: 0,
         }),
-        creature_size: creature_size_idx.map(|idx| build_histogram(&counts_per_metric[idx], false)).unwrap_or_else(|| HistogramWithAverage {
+        creature_size: creature_size_idx.map(|idx| build_histogram(&counts_per_metric[idx], HistogramBucketing::for_metric(StatMetric::Size))).unwrap_or_else(|| HistogramWithAverage {
             distribution: BTreeMap::new(),
             average: 0.0,
             was_cut: false,
             unique_values_count: 0,
         }),
-        can_kill: can_kill_idx.map(|idx| build_histogram(&counts_per_metric[idx], true)).unwrap_or_else(|| HistogramWithAverage {
+        can_kill: can_kill_idx.map(|idx| build_histogram(&counts_per_metric[idx], HistogramBucketing::for_metric(StatMetric::CanKill))).unwrap_or_else(|| HistogramWithAverage {
             distribution: BTreeMap::new(),
             average: 0.0,
             was_cut: false,
             unique_values_count: 0,
         }),
-        can_move: can_move_idx.map(|idx| build_histogram(&counts_per_metric[idx], true)).unwrap_or_else(|| HistogramWithAverage {
+        can_move: can_move_idx.map(|idx| build_histogram(&counts_per_metric[idx], HistogramBucketing::for_metric(StatMetric::CanMove))).unwrap_or_else(|| HistogramWithAverage {
             distribution: BTreeMap::new(),
             average: 0.0,
             was_cut: false,
             unique_values_count: 0,
         }),
-        food: food_idx.map(|idx| build_histogram(&counts_per_metric[idx], false)).unwrap_or_else(|| HistogramWithAverage {
+        food: food_idx.map(|idx| build_histogram(&counts_per_metric[idx], HistogramBucketing::for_metric(StatMetric::Food))).unwrap_or_else(|| HistogramWithAverage {
             distribution: BTreeMap::new(),
             average: 0.0,
             was_cut: false,
             unique_values_count: 0,
         }),
-        age: age_idx.map(|idx| build_histogram(&counts_per_metric[idx], false)).unwrap_or_else(|| HistogramWithAverage {
+        age: age_idx.map(|idx| build_histogram(&counts_per_metric[idx], HistogramBucketing::for_metric(StatMetric::Age))).unwrap_or_else(|| HistogramWithAverage {
             distribution: BTreeMap::new(),
             average: 0.0,
             was_cut: false,
@@ -311,6 +347,18 @@ async fn collect_statistics(
             was_cut: false,
             unique_values_count: 0,
         }),
+        occupancy: occupancy_idx.map(|idx| build_histogram(&counts_per_metric[idx], HistogramBucketing::for_metric(StatMetric::Occupancy))).unwrap_or_else(|| HistogramWithAverage {
+            distribution: BTreeMap::new(),
+            average: 0.0,
+            was_cut: false,
+            unique_values_count: 0,
+        }),
+        food_coverage: food_coverage_idx.map(|idx| build_histogram(&counts_per_metric[idx], HistogramBucketing::for_metric(StatMetric::FoodCoverage))).unwrap_or_else(|| HistogramWithAverage {
+            distribution: BTreeMap::new(),
+            average: 0.0,
+            was_cut: false,
+            unique_values_count: 0,
+        }),
     };
     
     // Build metadata
@@ -318,18 +366,255 @@ async fn collect_statistics(
         created_at_utc: Utc::now().to_rfc3339(),
         colony_width,
         colony_height,
+        config_hash,
     };
     
     Ok(CreatureStatistics {
         colony_instance_id,
         tick: current_tick,
         creatures_count,
+        sanctuary_share,
         histograms,
+        dominant_species,
         meta,
     })
 }
 
-fn build_histogram(counts: &BTreeMap<i32, u64>, is_boolean: bool) -> HistogramWithAverage {
+/// Per-metric value counts summed over every shard that answered
+struct MergedShardCounts {
+    max_tick: u64,
+    /// Indexed like the metrics passed to fetch_merged_counts
+    counts_per_metric: Vec<BTreeMap<i32, u64>>,
+    string_counts_per_metric: Vec<BTreeMap<String, u64>>,
+    missing_shards: Vec<String>,
+}
+
+fn fetch_merged_counts(shards: &[Shard], metrics: &[StatMetric], region: Option<Shard>) -> MergedShardCounts {
+    let position = |metric: StatMetric| metrics.iter().position(|m| *m as u8 == metric as u8);
+    
+    let mut merged = MergedShardCounts {
+        max_tick: 0,
+        counts_per_metric: vec![BTreeMap::new(); metrics.len()],
+        string_counts_per_metric: vec![BTreeMap::new(); metrics.len()],
+        missing_shards: Vec::new(),
+    };
+    
+    for shard in shards {
+        match backend_client::call_backend_get_shard_stats(*shard, metrics.to_vec(), region) {
+            Ok((tick, per_metric, per_string_metric)) => {
+                merged.max_tick = merged.max_tick.max(tick);
+                for (metric, buckets) in per_metric {
+                    if let Some(pos) = position(metric) {
+                        let entry = &mut merged.counts_per_metric[pos];
+                        for b in buckets {
+                            *entry.entry(b.value).or_insert(0) += b.occs;
+                        }
+                    }
+                }
+                for (metric, buckets) in per_string_metric {
+                    if let Some(pos) = position(metric) {
+                        let entry = &mut merged.string_counts_per_metric[pos];
+                        for b in buckets {
+                            *entry.entry(b.value).or_insert(0) += b.occs;
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                log_error!("Shard {} left out of the colony stats: {}", shard.to_id(), e);
+                merged.missing_shards.push(shard.to_id());
+            }
+        }
+    }
+    
+    merged
+}
+
+/// Unfiltered per-metric buckets for the GUI; string metrics have no numeric buckets and are left out
+fn numeric_metric_stats(metrics: &[StatMetric], merged: &MergedShardCounts) -> Vec<ColonyMetricStats> {
+    metrics.iter()
+        .zip(merged.counts_per_metric.iter())
+        .filter(|(metric, _)| !matches!(metric, StatMetric::OriginalColor))
+        .map(|(metric, counts)| {
+            let total_count: u64 = counts.values().sum();
+            let total_value: i64 = counts.iter().map(|(&value, &count)| value as i64 * count as i64).sum();
+            ColonyMetricStats {
+                metric: *metric,
+                avg: if total_count > 0 { total_value as f64 / total_count as f64 } else { 0.0 },
+                buckets: counts.iter().map(|(&value, &occs)| StatBucket { value, occs }).collect(),
+            }
+        })
+        .collect()
+}
+
+/// Species clusters of the merged OriginalColor counts; empty when that metric was not fetched
+fn species_summary(metrics: &[StatMetric], merged: &MergedShardCounts) -> Vec<SpeciesCluster> {
+    metrics.iter()
+        .position(|metric| matches!(metric, StatMetric::OriginalColor))
+        .map(|idx| cluster_species(&merged.string_counts_per_metric[idx], MAX_SPECIES_CLUSTERS, SPECIES_COLOR_RADIUS))
+        .unwrap_or_default()
+}
+
+fn current_colony_instance_id() -> Option<String> {
+    CoordinatorContext::get_instance().get_coord_stored_info().colony_instance_id.clone()
+}
+
+/// Merged stats for /api/colony-stats, served from the cache while fresh.
+/// Returns the stats and whether they came from the cache.
+pub async fn get_colony_stats(metrics: Vec<StatMetric>) -> Result<(CachedColonyStats, bool), String> {
+    let cache = CoordinatorContext::get_instance().colony_stats_cache();
+    if let Some(cached) = cache.lookup(&metrics, Instant::now()) {
+        return Ok((cached, true));
+    }
+    
+    let _refresh = cache.lock_refresh().await;
+    // Another request may have refreshed the cache while we waited
+    if let Some(cached) = cache.lookup(&metrics, Instant::now()) {
+        return Ok((cached, true));
+    }
+    
+    let topology = ClusterTopology::get_instance().ok_or_else(|| "Topology not initialized".to_string())?;
+    let shards = topology.get_all_shards();
+    // OriginalColor is never a requested metric, it only feeds the species summary
+    let mut request_metrics = metrics.clone();
+    request_metrics.push(StatMetric::OriginalColor);
+    let fetched_metrics = request_metrics.clone();
+    let merged = tokio::task::spawn_blocking(move || fetch_merged_counts(&shards, &request_metrics, None))
+        .await
+        .map_err(|e| format!("Stats fan-out panicked: {}", e))?;
+    if merged.missing_shards.len() == topology.shard_count() {
+        return Err("No shard returned stats".to_string());
+    }
+    if !merged.missing_shards.is_empty() {
+        log_error!("Colony stats missing shards: {:?}", merged.missing_shards);
+    }
+    
+    let stats = CachedColonyStats {
+        colony_instance_id: current_colony_instance_id(),
+        tick: merged.max_tick,
+        stats: numeric_metric_stats(&fetched_metrics, &merged),
+        species: species_summary(&fetched_metrics, &merged),
+        computed_at: Instant::now(),
+    };
+    cache.store(stats.clone());
+    Ok((stats, false))
+}
+
+/// Stats of the cells inside a region, from get_region_stats
+pub struct RegionColonyStats {
+    pub stats: CachedColonyStats,
+    /// The region clipped to the shards it intersects; None when it misses every shard
+    pub region: Option<Shard>,
+    /// Cells of the region in the shards that answered
+    pub cells: u64,
+}
+
+/// Merged stats of the cells inside region (colony coordinates), asking only the shards it
+/// intersects. Not cached, every region is fanned out. A region that misses every shard gives
+/// empty stats, not an error.
+pub async fn get_region_stats(metrics: Vec<StatMetric>, region: Shard) -> Result<RegionColonyStats, String> {
+    if region.width <= 0 || region.height <= 0 {
+        return Err(format!("Invalid region size {}x{}", region.width, region.height));
+    }
+    let topology = ClusterTopology::get_instance().ok_or_else(|| "Topology not initialized".to_string())?;
+    let mut shards: Vec<Shard> = topology.get_all_shards().into_iter()
+        .filter(|shard| shard.intersection(&region).is_some())
+        .collect();
+    shards.sort_by_key(|shard| (shard.y, shard.x));
+    let mut request_metrics = metrics.clone();
+    request_metrics.push(StatMetric::OriginalColor);
+    let fetched_metrics = request_metrics.clone();
+    let fan_out_shards = shards.clone();
+    let merged = tokio::task::spawn_blocking(move || fetch_merged_counts(&fan_out_shards, &request_metrics, Some(region)))
+        .await
+        .map_err(|e| format!("Stats fan-out panicked: {}", e))?;
+    if !shards.is_empty() && merged.missing_shards.len() == shards.len() {
+        return Err("No shard returned stats".to_string());
+    }
+
+    let covered: Vec<Shard> = shards.iter()
+        .filter(|shard| !merged.missing_shards.contains(&shard.to_id()))
+        .filter_map(|shard| shard.intersection(&region))
+        .collect();
+    let cells = covered.iter().map(|area| area.width as u64 * area.height as u64).sum();
+    let effective_region = covered.iter().copied().reduce(|bounds, area| {
+        let (left, top) = (bounds.x.min(area.x), bounds.y.min(area.y));
+        let right = (bounds.x + bounds.width).max(area.x + area.width);
+        let bottom = (bounds.y + bounds.height).max(area.y + area.height);
+        Shard { x: left, y: top, width: right - left, height: bottom - top }
+    });
+    Ok(RegionColonyStats {
+        stats: CachedColonyStats {
+            colony_instance_id: current_colony_instance_id(),
+            tick: merged.max_tick,
+            stats: numeric_metric_stats(&fetched_metrics, &merged),
+            species: species_summary(&fetched_metrics, &merged),
+            computed_at: Instant::now(),
+        },
+        region: effective_region,
+        cells,
+    })
+}
+
+/// How build_histogram groups raw values into distribution keys. Without grouping, metrics
+/// that keep growing (age after a million ticks) spread over so many values that none reaches
+/// MIN_HISTOGRAM_COUNT and the histogram comes out empty.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HistogramBucketing {
+    /// One key per value, e.g. "0" and "1" for booleans
+    Exact,
+    /// Ranges of the given width: "0-9", "10-19", ...
+    FixedWidth(i32),
+    /// Power-of-two ranges: "0", "1", "2-3", "4-7", ..., "1024-2047"
+    Log2,
+}
+
+impl HistogramBucketing {
+    pub fn for_metric(metric: StatMetric) -> Self {
+        match metric {
+            StatMetric::Age => HistogramBucketing::Log2,
+            StatMetric::Food => HistogramBucketing::FixedWidth(10),
+            StatMetric::Health
+            | StatMetric::Size
+            | StatMetric::CanKill
+            | StatMetric::CanMove
+            | StatMetric::OriginalColor
+            | StatMetric::Sanctuary
+            | StatMetric::Occupancy
+            | StatMetric::FoodCoverage => HistogramBucketing::Exact,
+        }
+    }
+
+    /// Inclusive range of the bucket holding value; non-positive values stay exact under Log2
+    pub fn range(&self, value: i32) -> (i64, i64) {
+        let value = value as i64;
+        match *self {
+            HistogramBucketing::Exact => (value, value),
+            HistogramBucketing::FixedWidth(width) => {
+                let width = width.max(1) as i64;
+                let low = value.div_euclid(width) * width;
+                (low, low + width - 1)
+            }
+            HistogramBucketing::Log2 if value <= 0 => (value, value),
+            HistogramBucketing::Log2 => {
+                let low = 1i64 << (63 - value.leading_zeros());
+                (low, 2 * low - 1)
+            }
+        }
+    }
+}
+
+/// Distribution key of a bucket: "7" for a single value, "1024-2047" for a range
+fn range_key((low, high): (i64, i64)) -> String {
+    if low == high {
+        low.to_string()
+    } else {
+        format!("{}-{}", low, high)
+    }
+}
+
+/// The average always comes from the raw values; bucketing only shapes the distribution
+pub fn build_histogram(counts: &BTreeMap<i32, u64>, bucketing: HistogramBucketing) -> HistogramWithAverage {
     // Calculate average
     let mut total_value: i64 = 0;
     let mut total_count: u64 = 0;
@@ -345,14 +630,19 @@ fn build_histogram(counts: &BTreeMap<i32, u64>, is_boolean: bool) -> HistogramWi
         0.0
     };
     
+    // Group into buckets, keyed by their range so neighbouring buckets stay ordered
+    let mut bucketed: BTreeMap<(i64, i64), u64> = BTreeMap::new();
+    for (&value, &count) in counts.iter() {
+        *bucketed.entry(bucketing.range(value)).or_insert(0) += count;
+    }
+    
     // Filter: only include counts >= MIN_HISTOGRAM_COUNT, then take top 20 by count
-    let mut filtered: Vec<(i32, u64)> = counts
-        .iter()
-        .filter(|(_, &count)| count >= MIN_HISTOGRAM_COUNT)
-        .map(|(&value, &count)| (value, count))
+    let mut filtered: Vec<((i64, i64), u64)> = bucketed
+        .into_iter()
+        .filter(|(_, count)| *count >= MIN_HISTOGRAM_COUNT)
         .collect();
     
-    // Record the number of unique values before cutting to top 20
+    // Record the number of unique buckets before cutting to top 20
     let unique_values_count = filtered.len();
     let was_cut = unique_values_count > TOP_VALUES_LIMIT;
     
@@ -362,19 +652,8 @@ fn build_histogram(counts: &BTreeMap<i32, u64>, is_boolean: bool) -> HistogramWi
     
     // Build histogram map
     let mut hist = BTreeMap::new();
-    for (value, count) in filtered {
-        let key = if is_boolean {
-            // Boolean traits: "0" for false, "1" for true
-            if value == 0 {
-                "0".to_string()
-            } else {
-                "1".to_string()
-            }
-        } else {
-            // Non-boolean traits: use value as string
-            value.to_string()
-        };
-        hist.insert(key, count);
+    for (range, count) in filtered {
+        hist.insert(range_key(range), count);
     }
     
     HistogramWithAverage {
@@ -420,8 +699,7 @@ fn format_tick_filename(tick: u64) -> String {
 }
 
 fn save_stats_to_disk(stats: &CreatureStatistics, instance_id: &str, tick_str: &str) -> Result<(), String> {
-    // Build directory path: output/s3/distributed-colony/{id}/stats_shots
-    let dir_path = Path::new(BASE_BUCKET_DIR).join(instance_id).join("stats_shots");
+    let dir_path = OutputPaths::get_instance().stats_dir(instance_id);
     if let Err(e) = std::fs::create_dir_all(&dir_path) {
         return Err(format!("Failed to create directory {}: {}", dir_path.display(), e));
     }
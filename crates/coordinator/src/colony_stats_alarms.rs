@@ -0,0 +1,231 @@
+use serde::{Deserialize, Serialize};
+use shared::coordinator_api::ColonyEventDescription;
+use shared::{log, log_error};
+use crate::colony_stats::CreatureStatistics;
+use crate::coordinator_context::CoordinatorContext;
+
+/// Path of a JSON file with the alarm rules; the defaults apply when unset or unreadable
+const STATS_ALARMS_CONFIG_ENV: &str = "STATS_ALARMS_CONFIG";
+
+/// A value computed by every stats capture
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AlarmMetric {
+    CreaturesCount,
+    SanctuaryShare,
+    HealthAverage,
+    SizeAverage,
+    /// Fraction of the creatures that can kill, 0..1
+    CanKillShare,
+    CanMoveShare,
+    FoodAverage,
+    AgeAverage,
+}
+
+impl AlarmMetric {
+    pub fn value(&self, stats: &CreatureStatistics) -> f64 {
+        let histograms = &stats.histograms;
+        match self {
+            AlarmMetric::CreaturesCount => stats.creatures_count as f64,
+            AlarmMetric::SanctuaryShare => stats.sanctuary_share,
+            AlarmMetric::HealthAverage => histograms.health.average,
+            AlarmMetric::SizeAverage => histograms.creature_size.average,
+            AlarmMetric::CanKillShare => histograms.can_kill.average,
+            AlarmMetric::CanMoveShare => histograms.can_move.average,
+            AlarmMetric::FoodAverage => histograms.food.average,
+            AlarmMetric::AgeAverage => histograms.age.average,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlarmOp {
+    #[serde(rename = ">")]
+    Greater,
+    #[serde(rename = ">=")]
+    GreaterOrEqual,
+    #[serde(rename = "<")]
+    Less,
+    #[serde(rename = "<=")]
+    LessOrEqual,
+}
+
+impl AlarmOp {
+    pub fn holds(&self, value: f64, threshold: f64) -> bool {
+        match self {
+            AlarmOp::Greater => value > threshold,
+            AlarmOp::GreaterOrEqual => value >= threshold,
+            AlarmOp::Less => value < threshold,
+            AlarmOp::LessOrEqual => value <= threshold,
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            AlarmOp::Greater => ">",
+            AlarmOp::GreaterOrEqual => ">=",
+            AlarmOp::Less => "<",
+            AlarmOp::LessOrEqual => "<=",
+        }
+    }
+}
+
+fn default_for_n_captures() -> u32 {
+    1
+}
+
+/// Raised once `metric op threshold` held for for_n_captures captures in a row, cleared by the
+/// first capture where it does not
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AlarmRule {
+    pub name: String,
+    pub metric: AlarmMetric,
+    pub op: AlarmOp,
+    pub threshold: f64,
+    #[serde(default = "default_for_n_captures")]
+    pub for_n_captures: u32,
+    /// Stop generating colony events while raised
+    #[serde(default)]
+    pub pause_events: bool,
+}
+
+impl AlarmRule {
+    fn new(name: &str, metric: AlarmMetric, op: AlarmOp, threshold: f64, for_n_captures: u32, pause_events: bool) -> Self {
+        Self { name: name.to_string(), metric, op, threshold, for_n_captures, pause_events }
+    }
+
+    pub fn describe(&self) -> String {
+        format!("{:?} {} {}", self.metric, self.op.symbol(), self.threshold)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AlarmConfig {
+    pub rules: Vec<AlarmRule>,
+}
+
+impl AlarmConfig {
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let config: AlarmConfig = serde_json::from_str(json).map_err(|e| format!("Invalid alarm config: {}", e))?;
+        if let Some(rule) = config.rules.iter().find(|rule| rule.for_n_captures == 0) {
+            return Err(format!("Alarm {} needs for_n_captures of at least 1", rule.name));
+        }
+        Ok(config)
+    }
+
+    /// Health and food are u16 per cell, so averages this close to u16::MAX mean almost every
+    /// cell is saturated
+    pub fn default_rules() -> Self {
+        Self {
+            rules: vec![
+                AlarmRule::new("health-pinned-at-max", AlarmMetric::HealthAverage, AlarmOp::GreaterOrEqual, 60_000.0, 3, false),
+                AlarmRule::new("food-saturated", AlarmMetric::FoodAverage, AlarmOp::GreaterOrEqual, 60_000.0, 3, false),
+                AlarmRule::new("all-can-kill", AlarmMetric::CanKillShare, AlarmOp::GreaterOrEqual, 1.0, 3, true),
+                AlarmRule::new("colony-extinct", AlarmMetric::CreaturesCount, AlarmOp::LessOrEqual, 0.0, 2, true),
+            ],
+        }
+    }
+
+    /// Rules from the STATS_ALARMS_CONFIG file, the defaults when unset or unreadable
+    pub fn from_env() -> Self {
+        let Ok(path) = std::env::var(STATS_ALARMS_CONFIG_ENV) else {
+            return Self::default_rules();
+        };
+        match std::fs::read_to_string(&path).map_err(|e| e.to_string()).and_then(|json| Self::parse(&json)) {
+            Ok(config) => {
+                log!("Loaded {} stats alarm rules from {}", config.rules.len(), path);
+                config
+            }
+            Err(e) => {
+                log_error!("Failed to load stats alarms from {}, using the defaults: {}", path, e);
+                Self::default_rules()
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlarmTransition {
+    Raised { name: String, description: String, value: f64 },
+    Cleared { name: String },
+}
+
+#[derive(Debug)]
+pub struct AlarmEvaluator {
+    rules: Vec<AlarmRule>,
+    /// Consecutive captures each rule held, indexed like rules
+    streaks: Vec<u32>,
+    raised: Vec<bool>,
+}
+
+impl AlarmEvaluator {
+    pub fn new(config: AlarmConfig) -> Self {
+        let count = config.rules.len();
+        Self { rules: config.rules, streaks: vec![0; count], raised: vec![false; count] }
+    }
+
+    /// Feeds one capture to every rule and returns the alarms it raised or cleared
+    pub fn evaluate(&mut self, value_of: impl Fn(AlarmMetric) -> f64) -> Vec<AlarmTransition> {
+        let mut transitions = Vec::new();
+        for (idx, rule) in self.rules.iter().enumerate() {
+            let value = value_of(rule.metric);
+            if rule.op.holds(value, rule.threshold) {
+                self.streaks[idx] = self.streaks[idx].saturating_add(1);
+                if !self.raised[idx] && self.streaks[idx] >= rule.for_n_captures {
+                    self.raised[idx] = true;
+                    transitions.push(AlarmTransition::Raised { name: rule.name.clone(), description: rule.describe(), value });
+                }
+            } else {
+                self.streaks[idx] = 0;
+                if std::mem::take(&mut self.raised[idx]) {
+                    transitions.push(AlarmTransition::Cleared { name: rule.name.clone() });
+                }
+            }
+        }
+        transitions
+    }
+
+    pub fn raised_alarms(&self) -> Vec<String> {
+        self.rules.iter().zip(&self.raised).filter(|(_, raised)| **raised).map(|(rule, _)| rule.name.clone()).collect()
+    }
+
+    pub fn pauses_events(&self) -> bool {
+        self.rules.iter().zip(&self.raised).any(|(rule, raised)| *raised && rule.pause_events)
+    }
+}
+
+/// Names of the alarms currently raised, reported by /health
+pub fn raised_alarms() -> Vec<String> {
+    CoordinatorContext::get_instance().stats_alarms().raised_alarms()
+}
+
+/// Whether a raised alarm holds back the event generator
+pub fn events_paused_by_alarm() -> bool {
+    CoordinatorContext::get_instance().stats_alarms().pauses_events()
+}
+
+/// Runs the alarm rules over a capture and records every alarm raised or cleared as a colony event
+pub fn evaluate_capture(stats: &CreatureStatistics) {
+    let transitions = CoordinatorContext::get_instance().stats_alarms().evaluate(|metric| metric.value(stats));
+    for transition in transitions {
+        let (event_type, description) = match transition {
+            AlarmTransition::Raised { name, description, value } => {
+                log_error!("Stats alarm {} raised at tick {}: {} (now {:.2})", name, stats.tick, description, value);
+                ("Stats Alarm", format!("{}: {} (now {:.2})", name, description, value))
+            }
+            AlarmTransition::Cleared { name } => {
+                log!("Stats alarm {} cleared at tick {}", name, stats.tick);
+                ("Stats Alarm Cleared", name)
+            }
+        };
+        CoordinatorContext::get_instance().add_colony_event(ColonyEventDescription {
+            tick: stats.tick,
+            intended_tick: None,
+            event_type: event_type.to_string(),
+            description,
+            delivery: None,
+            no_effect: false,
+            region: None,
+        });
+    }
+}
@@ -0,0 +1,95 @@
+use shared::be_api::StatMetric;
+use shared::coordinator_api::{ColonyMetricStats, SpeciesCluster};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Matches the interval of the background capture_colony_stats task
+pub const DEFAULT_STATS_FRESHNESS: Duration = Duration::from_secs(10);
+const STATS_FRESHNESS_ENV: &str = "COLONY_STATS_CACHE_SECS";
+
+/// Merged colony stats for a set of metrics, as of the highest shard tick seen while merging
+#[derive(Debug, Clone)]
+pub struct CachedColonyStats {
+    /// Colony the stats were merged for; ticks start over with every instance
+    pub colony_instance_id: Option<String>,
+    pub tick: u64,
+    pub stats: Vec<ColonyMetricStats>,
+    /// Dominant-species summary, always merged alongside the requested metrics
+    pub species: Vec<SpeciesCluster>,
+    pub computed_at: Instant,
+}
+
+impl CachedColonyStats {
+    pub fn covers(&self, metrics: &[StatMetric]) -> bool {
+        metrics.iter().all(|metric| self.stats.iter().any(|s| s.metric as u8 == *metric as u8))
+    }
+
+    /// Copy holding only the requested metrics, in request order
+    pub fn select(&self, metrics: &[StatMetric]) -> CachedColonyStats {
+        let stats = metrics.iter()
+            .filter_map(|metric| self.stats.iter().find(|s| s.metric as u8 == *metric as u8).cloned())
+            .collect();
+        CachedColonyStats {
+            colony_instance_id: self.colony_instance_id.clone(),
+            tick: self.tick,
+            stats,
+            species: self.species.clone(),
+            computed_at: self.computed_at,
+        }
+    }
+}
+
+/// Last merged stats, shared by the background capture task and /api/colony-stats
+#[derive(Debug)]
+pub struct ColonyStatsCache {
+    freshness: Duration,
+    entry: Mutex<Option<CachedColonyStats>>,
+    /// Held while fanning out for /api/colony-stats so concurrent cache misses query backends once
+    refresh: tokio::sync::Mutex<()>,
+}
+
+impl ColonyStatsCache {
+    pub fn new(freshness: Duration) -> Self {
+        Self { freshness, entry: Mutex::new(None), refresh: tokio::sync::Mutex::new(()) }
+    }
+
+    /// Freshness from COLONY_STATS_CACHE_SECS, DEFAULT_STATS_FRESHNESS when unset
+    pub fn from_env() -> Self {
+        let freshness = std::env::var(STATS_FRESHNESS_ENV)
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_STATS_FRESHNESS);
+        ColonyStatsCache::new(freshness)
+    }
+
+    /// Serializes refreshes; see get_colony_stats
+    pub async fn lock_refresh(&self) -> tokio::sync::MutexGuard<'_, ()> {
+        self.refresh.lock().await
+    }
+
+    /// Cached stats for the requested metrics if the entry is fresh and covers all of them
+    pub fn lookup(&self, metrics: &[StatMetric], now: Instant) -> Option<CachedColonyStats> {
+        let entry = self.entry.lock().unwrap();
+        let cached = entry.as_ref()?;
+        let fresh = now.saturating_duration_since(cached.computed_at) <= self.freshness;
+        if fresh && cached.covers(metrics) {
+            Some(cached.select(metrics))
+        } else {
+            None
+        }
+    }
+
+    /// Replaces the entry unless it is still fresh, of the same colony instance and merged at a
+    /// newer tick. Ticks start over with a new instance, so its stats always replace the old ones.
+    pub fn store(&self, stats: CachedColonyStats) {
+        let mut entry = self.entry.lock().unwrap();
+        let keep_current = entry.as_ref().is_some_and(|current| {
+            let fresh = stats.computed_at.saturating_duration_since(current.computed_at) <= self.freshness;
+            fresh && current.colony_instance_id == stats.colony_instance_id && current.tick > stats.tick
+        });
+        if !keep_current {
+            *entry = Some(stats);
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use futures_util::future::join_all;
+use shared::be_api::{
+    BackendRequest, BackendResponse, SetTickerPausedRequest, SetTickerPausedResponse, StepTicksRequest, StepTicksResponse
+};
+use shared::cluster_topology::{ClusterTopology, HostInfo};
+use shared::colony_event_shared::{COLONY_STOPPED_EVENT, TICKING_STARTED_EVENT};
+use shared::{log, log_error};
+use std::future::Future;
+use std::sync::atomic::Ordering;
+use crate::coordinator_context::CoordinatorContext;
+use crate::init_colony::{connect_to_backend, receive_message, send_message, send_start_ticking_to_backend};
+use crate::lifecycle_events::record_lifecycle_event;
+use crate::run_summary::is_run_completed;
+
+/// Upper bound for a single /api/step call, each tick is a full round trip to every backend
+pub const MAX_STEP_COUNT: u32 = 1000;
+
+#[derive(Debug, PartialEq)]
+pub enum StepColonyError {
+    InProgress,
+    TopologyNotInitialized,
+    InvalidCount(String),
+    Failed(String),
+    /// The run reached its target_tick; it stays stopped
+    RunCompleted,
+}
+
+/// Clears the in-flight flag however the step ends
+struct StepGuard;
+
+impl StepGuard {
+    fn acquire() -> Option<Self> {
+        CoordinatorContext::get_instance().step_in_flight()
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .ok()
+            .map(|_| StepGuard)
+    }
+}
+
+impl Drop for StepGuard {
+    fn drop(&mut self) {
+        CoordinatorContext::get_instance().step_in_flight().store(false, Ordering::Release);
+    }
+}
+
+pub fn is_colony_paused() -> bool {
+    CoordinatorContext::get_instance().colony_paused().load(Ordering::SeqCst)
+}
+
+/// A new colony starts unpaused, at normal speed and without a step in flight, whatever the
+/// previous run left
+pub fn reset_ticker_state() {
+    let context = CoordinatorContext::get_instance();
+    context.colony_paused().store(false, Ordering::SeqCst);
+    context.step_in_flight().store(false, Ordering::Release);
+    context.fast_forward().store(false, Ordering::SeqCst);
+}
+
+pub fn is_fast_forward() -> bool {
+    CoordinatorContext::get_instance().fast_forward().load(Ordering::SeqCst)
+}
+
+/// ?count= defaults to a single tick
+pub fn parse_step_count(param: Option<&str>) -> Result<u32, StepColonyError> {
+    let count = match param {
+        None => 1,
+        Some(value) => value.parse::<u32>()
+            .map_err(|_| StepColonyError::InvalidCount(format!("Invalid count: {}", value)))?,
+    };
+    if count == 0 || count > MAX_STEP_COUNT {
+        return Err(StepColonyError::InvalidCount(format!("count must be between 1 and {}", MAX_STEP_COUNT)));
+    }
+    Ok(count)
+}
+
+async fn send_set_ticker_paused(backend_host: &HostInfo, paused: bool) -> Result<u64, String> {
+    let mut stream = connect_to_backend(&backend_host.hostname, backend_host.port).await
+        .map_err(|e| format!("Connection failed: {}", e))?;
+
+    send_message(&mut stream, &BackendRequest::SetTickerPaused(SetTickerPausedRequest { paused })).await;
+
+    match receive_message::<BackendResponse>(&mut stream).await {
+        Some(BackendResponse::SetTickerPaused(SetTickerPausedResponse::Ok { current_tick })) => Ok(current_tick),
+        Some(BackendResponse::SetTickerPaused(SetTickerPausedResponse::ColonyNotInitialized)) => Err("colony not initialized".to_string()),
+        Some(_) => Err("Unexpected response type".to_string()),
+        None => Err("Failed to receive response".to_string()),
+    }
+}
+
+async fn send_step_ticks(backend_host: &HostInfo, count: u32) -> Result<u64, String> {
+    let mut stream = connect_to_backend(&backend_host.hostname, backend_host.port).await
+        .map_err(|e| format!("Connection failed: {}", e))?;
+
+    send_message(&mut stream, &BackendRequest::StepTicks(StepTicksRequest { shard: None, count })).await;
+
+    match receive_message::<BackendResponse>(&mut stream).await {
+        Some(BackendResponse::StepTicks(StepTicksResponse::Ok { current_tick })) => Ok(current_tick),
+        Some(BackendResponse::StepTicks(StepTicksResponse::TickerNotPaused)) => Err("ticker not paused".to_string()),
+        Some(BackendResponse::StepTicks(StepTicksResponse::ColonyNotInitialized)) => Err("colony not initialized".to_string()),
+        Some(BackendResponse::StepTicks(StepTicksResponse::ShardNotAvailable)) => Err("shard not available".to_string()),
+        Some(_) => Err("Unexpected response type".to_string()),
+        None => Err("Failed to receive response".to_string()),
+    }
+}
+
+/// Steps every backend one tick at a time, waiting for all of them before the next tick,
+/// so border exchange always sees neighbors at the same tick. Returns the highest tick reached.
+pub async fn step_in_lockstep<F, Fut>(backends: &[HostInfo], count: u32, mut step_backend: F) -> Result<u64, String>
+where
+    F: FnMut(HostInfo) -> Fut,
+    Fut: Future<Output = Result<u64, String>>,
+{
+    let mut current_tick = 0;
+    for step in 1..=count {
+        let results = join_all(backends.iter().map(|backend| step_backend(backend.clone()))).await;
+        for (backend, result) in backends.iter().zip(results) {
+            match result {
+                Ok(tick) => current_tick = current_tick.max(tick),
+                Err(e) => return Err(format!("step {} of {} failed on {}: {}", step, count, backend.to_address(), e)),
+            }
+        }
+    }
+    Ok(current_tick)
+}
+
+fn unique_backends() -> Result<Vec<HostInfo>, StepColonyError> {
+    let topology = ClusterTopology::get_instance().ok_or(StepColonyError::TopologyNotInitialized)?;
+    let mut backends = topology.get_all_backend_hosts().clone();
+    backends.sort_by_key(|host| host.to_address());
+    backends.dedup();
+    Ok(backends)
+}
+
+/// Pauses or resumes the ticker on every backend. Returns the highest tick reported.
+pub async fn set_colony_paused(paused: bool) -> Result<u64, StepColonyError> {
+    if !paused && is_run_completed() {
+        return Err(StepColonyError::RunCompleted);
+    }
+    let backends = unique_backends()?;
+    let colony_paused = CoordinatorContext::get_instance().colony_paused();
+    let was_paused = colony_paused.load(Ordering::SeqCst);
+    // Mark paused before contacting backends, so a failure leaves the GUI able to retry a step
+    if paused {
+        colony_paused.store(true, Ordering::SeqCst);
+    }
+
+    let results = join_all(backends.iter().map(|backend| send_set_ticker_paused(backend, paused))).await;
+    let mut current_tick = 0;
+    for (backend, result) in backends.iter().zip(results) {
+        match result {
+            Ok(tick) => current_tick = current_tick.max(tick),
+            Err(e) => {
+                log_error!("Failed to {} backend {}: {}", if paused { "pause" } else { "resume" }, backend.to_address(), e);
+                return Err(StepColonyError::Failed(format!("{}: {}", backend.to_address(), e)));
+            }
+        }
+    }
+
+    if !paused {
+        colony_paused.store(false, Ordering::SeqCst);
+    }
+    log!("Colony {} at tick {}", if paused { "paused" } else { "resumed" }, current_tick);
+    if paused && !was_paused {
+        record_lifecycle_event(COLONY_STOPPED_EVENT, current_tick, format!("Ticking paused on {} backends", backends.len()));
+    } else if !paused && was_paused {
+        record_lifecycle_event(TICKING_STARTED_EVENT, current_tick, format!("Ticking resumed on {} backends", backends.len()));
+    }
+    Ok(current_tick)
+}
+
+/// Switches fast-forward mode on every backend by re-issuing StartTicking, which is idempotent.
+/// Before ticking started the mode is only recorded; StartTicking carries it to the backends then.
+pub async fn set_fast_forward(enabled: bool) -> Result<(), StepColonyError> {
+    CoordinatorContext::get_instance().fast_forward().store(enabled, Ordering::SeqCst);
+    if !CoordinatorContext::get_instance().get_coord_stored_info().ticking_started {
+        log!("Fast-forward {} once the colony starts ticking", if enabled { "on" } else { "off" });
+        return Ok(());
+    }
+
+    let backends = unique_backends()?;
+    let results = join_all(backends.iter().map(send_start_ticking_to_backend)).await;
+    for (backend, result) in backends.iter().zip(results) {
+        // Every answer means the backend applied the mode, even one refusing to tick
+        if let Err(e) = result {
+            log_error!("Failed to switch fast-forward on backend {}: {}", backend.to_address(), e);
+            return Err(StepColonyError::Failed(format!("{}: {}", backend.to_address(), e)));
+        }
+    }
+    log!("Fast-forward {} on {} backends", if enabled { "on" } else { "off" }, backends.len());
+    Ok(())
+}
+
+/// Pauses every backend, then advances the colony by count ticks. The colony stays paused.
+pub async fn step_colony(count: u32) -> Result<u64, StepColonyError> {
+    if is_run_completed() {
+        return Err(StepColonyError::RunCompleted);
+    }
+    let _guard = StepGuard::acquire().ok_or(StepColonyError::InProgress)?;
+    set_colony_paused(true).await?;
+
+    let backends = unique_backends()?;
+    let current_tick = step_in_lockstep(&backends, count, |backend| async move {
+        send_step_ticks(&backend, 1).await
+    }).await.map_err(StepColonyError::Failed)?;
+
+    log!("Stepped colony {} tick(s) to tick {}", count, current_tick);
+    Ok(current_tick)
+}
@@ -0,0 +1,311 @@
+use futures_util::future::join_all;
+use serde::Deserialize;
+use shared::api_auth::{bearer_header_value, ApiAuthConfig};
+use shared::be_api::{BackendRequest, BackendResponse, ColonyLifeRules, GetColonyInfoRequest, GetColonyInfoResponse, Shard};
+use shared::cluster_topology::{ClusterTopology, HostInfo};
+use shared::coordinator_api::{ColonyEventDescription, ColonyVerificationReport, ShardVerification};
+use shared::{log, log_error};
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use crate::colony_capture::get_backend_http_port;
+use crate::coordinator_context::CoordinatorContext;
+use crate::init_colony::{connect_to_backend, receive_message, send_message};
+use crate::shard_leases::with_lease_table;
+
+/// Shards further behind the colony max tick than this fail verification
+pub const MAX_TICK_DRIFT_ENV: &str = "VERIFY_MAX_TICK_DRIFT";
+const DEFAULT_MAX_TICK_DRIFT: u64 = 100;
+/// Upper bound for everything asked of one backend: colony info, shard list and one image
+const BACKEND_QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+const HTTP_REQUEST_TIMEOUT: Duration = Duration::from_millis(1500);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VerificationTrigger {
+    Manual,
+    ColonyStart,
+    /// This coordinator took over a colony that outlived its previous coordinator
+    Failover,
+}
+
+impl VerificationTrigger {
+    pub fn label(&self) -> &'static str {
+        match self {
+            VerificationTrigger::Manual => "manual",
+            VerificationTrigger::ColonyStart => "colony-start",
+            VerificationTrigger::Failover => "failover",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum VerifyColonyError {
+    InProgress,
+    TopologyNotInitialized,
+}
+
+/// Clears the in-flight flag however the pass ends
+struct VerifyGuard;
+
+impl VerifyGuard {
+    fn acquire() -> Option<Self> {
+        CoordinatorContext::get_instance().verify_in_flight()
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .ok()
+            .map(|_| VerifyGuard)
+    }
+}
+
+impl Drop for VerifyGuard {
+    fn drop(&mut self) {
+        CoordinatorContext::get_instance().verify_in_flight().store(false, Ordering::Release);
+    }
+}
+
+/// A shard as listed by a backend's GET /api/shards
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct HostedShard {
+    pub shard: Shard,
+    pub current_tick: u64,
+    #[serde(default)]
+    pub lease_epoch: u64,
+    /// The backend stopped ticking it, its lease lapsed or went to another backend
+    #[serde(default)]
+    pub suspended: bool,
+}
+
+#[derive(Deserialize)]
+struct HostedShardsBody {
+    shards: Vec<HostedShard>,
+}
+
+/// What one backend reports, as far as verification cares
+#[derive(Debug, Clone)]
+pub struct BackendSnapshot {
+    pub hosted: Vec<HostedShard>,
+    pub rules: Option<ColonyLifeRules>,
+    /// Spot-checked shard and whether its image had the right size
+    pub image_check: Option<(Shard, Result<(), String>)>,
+}
+
+fn max_tick_drift() -> u64 {
+    std::env::var(MAX_TICK_DRIFT_ENV)
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_MAX_TICK_DRIFT)
+}
+
+/// The image endpoint serves raw RGB, three bytes per cell
+pub fn check_image_len(shard: &Shard, len: usize) -> Result<(), String> {
+    let expected = shard.width as usize * shard.height as usize * 3;
+    if len == expected {
+        Ok(())
+    } else {
+        Err(format!("image of {} has {} bytes, expected {}", shard.to_id(), len, expected))
+    }
+}
+
+pub(crate) async fn http_get(url: String) -> Result<Vec<u8>, String> {
+    tokio::task::spawn_blocking(move || {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(HTTP_REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+        let mut request = client.get(&url);
+        if let Some(token) = ApiAuthConfig::get_instance().admin_token() {
+            request = request.header(reqwest::header::AUTHORIZATION, bearer_header_value(token));
+        }
+        let response = request.send().map_err(|e| format!("GET {} failed: {}", url, e))?;
+        if !response.status().is_success() {
+            return Err(format!("GET {} returned HTTP {}", url, response.status().as_u16()));
+        }
+        response.bytes().map(|bytes| bytes.to_vec()).map_err(|e| format!("GET {} failed: {}", url, e))
+    }).await.map_err(|e| format!("HTTP request panicked: {}", e))?
+}
+
+async fn query_rules(backend_host: &HostInfo) -> Result<Option<ColonyLifeRules>, String> {
+    let mut stream = connect_to_backend(&backend_host.hostname, backend_host.port).await
+        .map_err(|e| format!("Connection failed: {}", e))?;
+    send_message(&mut stream, &BackendRequest::GetColonyInfo(GetColonyInfoRequest)).await;
+    match receive_message::<BackendResponse>(&mut stream).await {
+        Some(BackendResponse::GetColonyInfo(GetColonyInfoResponse::Ok { colony_life_rules, .. })) => Ok(colony_life_rules.map(|rules| *rules)),
+        Some(BackendResponse::GetColonyInfo(GetColonyInfoResponse::ColonyNotInitialized)) => Err("Colony not initialized".to_string()),
+        Some(_) => Err("Unexpected response type".to_string()),
+        None => Err("Failed to receive response".to_string()),
+    }
+}
+
+async fn query_backend(backend_host: &HostInfo) -> Result<BackendSnapshot, String> {
+    let rules = query_rules(backend_host).await?;
+    let http_port = get_backend_http_port(backend_host).await
+        .ok_or_else(|| "HTTP port not found in the cluster registry".to_string())?;
+    let base_url = format!("http://{}:{}", backend_host.hostname, http_port);
+
+    let body = http_get(format!("{}/api/shards", base_url)).await?;
+    let mut hosted = serde_json::from_slice::<HostedShardsBody>(&body)
+        .map_err(|e| format!("Invalid /api/shards response: {}", e))?
+        .shards;
+    hosted.sort_by_key(|hosted| (hosted.shard.y, hosted.shard.x));
+
+    // One image per backend is enough to tell a backend serving broken images
+    let image_check = match hosted.first() {
+        Some(first) => {
+            let result = http_get(format!("{}/api/shard/{}/image", base_url, first.shard.to_id())).await
+                .and_then(|bytes| check_image_len(&first.shard, bytes.len()));
+            Some((first.shard, result))
+        }
+        None => None,
+    };
+    Ok(BackendSnapshot { hosted, rules, image_check })
+}
+
+/// Checks every topology shard against what the backends report: exactly one active host, the one
+/// the topology assigns, holding the lease epoch in issued_epochs, with the topology's dimensions,
+/// within max_tick_drift of the colony max tick and, where spot-checked, serving a full image.
+/// Suspended copies fail the shard too. Backends that did not answer or run different rules than
+/// expected_rules are listed separately and fail the pass too.
+pub fn build_report(
+    topology: &ClusterTopology,
+    snapshots: &[(HostInfo, Result<BackendSnapshot, String>)],
+    issued_epochs: &HashMap<Shard, u64>,
+    expected_rules: ColonyLifeRules,
+    max_tick_drift: u64,
+    trigger: VerificationTrigger,
+) -> ColonyVerificationReport {
+    let mut backend_problems = Vec::new();
+    // Keyed by position, so a shard hosted with the wrong size is still found
+    let mut hosts_by_position: HashMap<(i32, i32), Vec<(String, HostedShard)>> = HashMap::new();
+    let mut image_checks: HashMap<Shard, &Result<(), String>> = HashMap::new();
+    for (backend, snapshot) in snapshots {
+        let address = backend.to_address();
+        let snapshot = match snapshot {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                backend_problems.push(format!("{}: {}", address, e));
+                continue;
+            }
+        };
+        if snapshot.rules.is_some_and(|rules| rules != expected_rules) {
+            backend_problems.push(format!("{}: runs different colony rules than the coordinator", address));
+        }
+        for hosted in &snapshot.hosted {
+            if !topology.shard_to_host.keys().any(|shard| (shard.x, shard.y) == (hosted.shard.x, hosted.shard.y)) {
+                backend_problems.push(format!("{}: hosts {}, which is not in the topology", address, hosted.shard.to_id()));
+            }
+            hosts_by_position.entry((hosted.shard.x, hosted.shard.y)).or_default().push((address.clone(), *hosted));
+        }
+        if let Some((shard, result)) = &snapshot.image_check {
+            image_checks.insert(*shard, result);
+        }
+    }
+
+    let colony_max_tick = hosts_by_position.values().flatten().map(|(_, hosted)| hosted.current_tick).max();
+    let mut shards = topology.get_all_shards();
+    shards.sort_by_key(|shard| (shard.y, shard.x));
+    let shards: Vec<ShardVerification> = shards.iter()
+        .map(|shard| {
+            let hosts = hosts_by_position.get(&(shard.x, shard.y)).map(Vec::as_slice).unwrap_or(&[]);
+            let active: Vec<&(String, HostedShard)> = hosts.iter().filter(|(_, hosted)| !hosted.suspended).collect();
+            let mut problems = Vec::new();
+            match active.as_slice() {
+                [] if hosts.is_empty() => problems.push("not hosted by any backend".to_string()),
+                [] => problems.push("no backend ticks it".to_string()),
+                [(host, _)] => {
+                    if let Some(assigned) = topology.get_host_for_shard(shard).map(HostInfo::to_address) {
+                        if *host != assigned {
+                            problems.push(format!("hosted by {}, topology assigns {}", host, assigned));
+                        }
+                    }
+                }
+                _ => problems.push(format!("hosted by {} backends", active.len())),
+            }
+            for (host, hosted) in hosts {
+                if (hosted.shard.width, hosted.shard.height) != (shard.width, shard.height) {
+                    problems.push(format!("{} hosts it as {}x{}, topology says {}x{}",
+                        host, hosted.shard.width, hosted.shard.height, shard.width, shard.height));
+                }
+                if hosted.suspended {
+                    problems.push(format!("{} holds a suspended copy under lease epoch {}", host, hosted.lease_epoch));
+                } else if let Some(issued) = issued_epochs.get(shard).filter(|issued| **issued != hosted.lease_epoch) {
+                    problems.push(format!("{} holds lease epoch {}, coordinator issued {}", host, hosted.lease_epoch, issued));
+                }
+            }
+            let tick = hosts.iter().map(|(_, hosted)| hosted.current_tick).min();
+            let tick_drift = tick.zip(colony_max_tick).map(|(tick, max)| max - tick);
+            if let Some(drift) = tick_drift.filter(|drift| *drift > max_tick_drift) {
+                problems.push(format!("{} ticks behind the colony", drift));
+            }
+            let image_check = image_checks.get(shard);
+            if let Some(Err(e)) = image_check {
+                problems.push(e.clone());
+            }
+            ShardVerification {
+                shard_id: shard.to_id(),
+                hosted_by: hosts.iter().map(|(host, _)| host.clone()).collect(),
+                tick,
+                tick_drift,
+                image_ok: image_check.map(|result| result.is_ok()),
+                passed: problems.is_empty(),
+                problems,
+            }
+        })
+        .collect();
+
+    let shards_failed = shards.iter().filter(|shard| !shard.passed).count();
+    ColonyVerificationReport {
+        passed: shards_failed == 0 && backend_problems.is_empty(),
+        trigger: trigger.label().to_string(),
+        colony_max_tick,
+        max_tick_drift,
+        shards_failed,
+        backend_problems,
+        shards,
+    }
+}
+
+fn record_report(report: &ColonyVerificationReport) {
+    let summary = report.summary();
+    if report.passed {
+        log!("Colony verification ({}): {}", report.trigger, summary);
+    } else {
+        log_error!("Colony verification ({}): {}", report.trigger, summary);
+        for problem in &report.backend_problems {
+            log_error!("  {}", problem);
+        }
+        for shard in report.shards.iter().filter(|shard| !shard.passed) {
+            log_error!("  {}: {}", shard.shard_id, shard.problems.join("; "));
+        }
+    }
+    CoordinatorContext::get_instance().add_colony_event(ColonyEventDescription {
+        tick: report.colony_max_tick.unwrap_or(0),
+        intended_tick: None,
+        event_type: "Colony Verification".to_string(),
+        description: format!("{} ({})", summary, report.trigger),
+        delivery: None,
+        no_effect: false,
+        region: None,
+    });
+}
+
+/// Queries every backend in parallel, audits the topology against their answers and records
+/// the outcome in the log and as a colony event. One pass runs at a time.
+pub async fn verify_colony(trigger: VerificationTrigger) -> Result<ColonyVerificationReport, VerifyColonyError> {
+    let _guard = VerifyGuard::acquire().ok_or(VerifyColonyError::InProgress)?;
+    let topology = ClusterTopology::get_instance().ok_or(VerifyColonyError::TopologyNotInitialized)?;
+
+    let backends = topology.get_all_backend_hosts();
+    let snapshots = join_all(backends.iter().map(|backend| async move {
+        let snapshot = tokio::time::timeout(BACKEND_QUERY_TIMEOUT, query_backend(backend)).await
+            .unwrap_or_else(|_| Err("Timed out".to_string()));
+        (backend.clone(), snapshot)
+    })).await;
+
+    let issued_epochs: HashMap<Shard, u64> = with_lease_table(|table| table.leases())
+        .into_iter()
+        .map(|lease| (lease.shard, lease.epoch))
+        .collect();
+    let expected_rules = CoordinatorContext::get_instance().get_colony_life_rules();
+    let report = build_report(&topology, &snapshots, &issued_epochs, expected_rules, max_tick_drift(), trigger);
+    record_report(&report);
+    Ok(report)
+}
@@ -0,0 +1,295 @@
+//! Operator subcommands of the coordinator binary. Each one is a thin HTTP client of a running
+//! coordinator, found through the ClusterRegistry, so the colony logic stays in the server.
+use std::path::PathBuf;
+use std::time::Duration;
+use serde::Serialize;
+use serde_json::Value;
+use shared::api_auth::{bearer_header_value, ApiAuthConfig};
+use shared::cluster_registry::{create_cluster_registry, ClusterRegistry};
+use shared::colony_events::ColonyEvent;
+use shared::coordinator_api::{BackendsResponse, ColonyStatsResponse};
+use shared::be_api::StatMetric;
+use clap::{Parser, Subcommand};
+use uuid::Uuid;
+use crate::coordinator_server::{DeploymentMode, BUILD_VERSION};
+
+pub const SERVE_COMMAND: &str = "serve";
+
+/// Long enough for capture and for event delivery, which retries failed backends
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Operator commands; `serve <rpc_port> <http_port> <mode>` runs the coordinator, also without
+/// the serve command
+#[derive(Parser, Debug, PartialEq)]
+#[command(name = "coordinator", version = BUILD_VERSION)]
+pub struct CliArgs {
+    #[command(subcommand)]
+    pub command: CliCommand,
+    /// Print JSON for scripting
+    #[arg(long, global = true)]
+    pub json: bool,
+    /// Coordinator HTTP address (host:port); discovered in the registry when omitted
+    #[arg(long, global = true, value_name = "HOST:PORT")]
+    pub coordinator: Option<String>,
+    /// Registry to discover the coordinator in
+    #[arg(long = "mode", global = true, value_name = "localhost|aws", default_value = "localhost")]
+    pub deployment_mode: DeploymentMode,
+}
+
+#[derive(Subcommand, Debug, PartialEq)]
+pub enum CliCommand {
+    /// Start a colony
+    StartColony {
+        /// File with a colony-start JSON body
+        #[arg(long)]
+        config: Option<PathBuf>,
+        /// Seed of the initial seeding, overrides the one in the config
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+    /// Pause the colony
+    StopColony,
+    /// Tick range, backends and population
+    Status,
+    /// Apply the ColonyEvent in the file and print the recorded event
+    ApplyEvent {
+        /// File with a ColonyEvent JSON, e.g. {"ChangeExtraFoodPerTick":2}
+        #[arg(long, value_name = "FILE")]
+        event: PathBuf,
+    },
+    /// Write a colony frame now
+    Capture,
+}
+
+/// True for the first argument of an operator subcommand; anything else starts the server
+pub fn is_cli_command(arg: &str) -> bool {
+    CliCommand::has_subcommand(arg)
+}
+
+/// Parses the arguments after the program name, starting with the subcommand
+pub fn parse_cli_args(args: &[String]) -> Result<CliArgs, clap::Error> {
+    CliArgs::try_parse_from(std::iter::once("coordinator").chain(args.iter().map(String::as_str)))
+}
+
+/// What a command prints: text for people, or the JSON value with --json
+#[derive(Debug)]
+pub struct CliOutput {
+    pub text: String,
+    pub json: Value,
+}
+
+impl CliOutput {
+    pub fn render(&self, json: bool) -> String {
+        if json {
+            serde_json::to_string_pretty(&self.json).unwrap_or_else(|_| self.json.to_string())
+        } else {
+            self.text.clone()
+        }
+    }
+}
+
+/// HTTP base URL of the coordinator registered in the registry
+pub async fn discover_coordinator_url(registry: &impl ClusterRegistry) -> Result<String, String> {
+    let address = registry.discover_coordinator().await.ok_or("No coordinator registered in the cluster registry")?;
+    Ok(format!("http://{}", address.to_http_address()))
+}
+
+/// Runs a parsed command against the coordinator; Err when it could not be reached or refused
+pub async fn run_cli_command(args: &CliArgs) -> Result<CliOutput, String> {
+    let base_url = match &args.coordinator {
+        Some(address) => format!("http://{}", address),
+        None => {
+            let mode = match args.deployment_mode {
+                DeploymentMode::Aws => "aws",
+                DeploymentMode::Localhost => "localhost",
+            };
+            discover_coordinator_url(create_cluster_registry(mode).as_ref()).await?
+        }
+    };
+    let client = CoordinatorClient::new(base_url)?;
+    match &args.command {
+        CliCommand::StartColony { config, seed } => start_colony(&client, config.as_ref(), *seed).await,
+        CliCommand::StopColony => stop_colony(&client).await,
+        CliCommand::Status => status(&client).await,
+        CliCommand::ApplyEvent { event } => apply_event(&client, event).await,
+        CliCommand::Capture => capture(&client).await,
+    }
+}
+
+struct CoordinatorClient {
+    base_url: String,
+    client: reqwest::Client,
+}
+
+impl CoordinatorClient {
+    fn new(base_url: String) -> Result<Self, String> {
+        // The coordinator HTTP server answers one request per connection
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .pool_max_idle_per_host(0)
+            .build()
+            .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+        Ok(Self { base_url, client })
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let request = self.client.request(method, format!("{}{}", self.base_url, path));
+        match ApiAuthConfig::get_instance().admin_token() {
+            Some(token) => request.header(reqwest::header::AUTHORIZATION, bearer_header_value(token)),
+            None => request,
+        }
+    }
+
+    /// Status code and body, parsed as JSON when it is JSON and kept as a string otherwise
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<(u16, Value), String> {
+        let response = request.send().await.map_err(|e| format!("Coordinator at {} unreachable: {}", self.base_url, e))?;
+        let status = response.status().as_u16();
+        let text = response.text().await.map_err(|e| format!("Failed to read coordinator response: {}", e))?;
+        Ok((status, serde_json::from_str(&text).unwrap_or(Value::String(text))))
+    }
+
+    /// The body of a 2xx response, the coordinator's error otherwise
+    async fn expect_success(&self, request: reqwest::RequestBuilder) -> Result<Value, String> {
+        let (status, body) = self.send(request).await?;
+        if (200..300).contains(&status) {
+            return Ok(body);
+        }
+        let error = match &body {
+            Value::String(text) => text.clone(),
+            body => body.get("error").and_then(Value::as_str).map(str::to_string).unwrap_or_else(|| body.to_string()),
+        };
+        Err(format!("Coordinator answered {}: {}", status, error))
+    }
+
+    /// The body of a 200 parsed as T; None for any other answer
+    async fn get_optional<T: serde::de::DeserializeOwned>(&self, path: &str) -> Result<Option<T>, String> {
+        let (status, body) = self.send(self.request(reqwest::Method::GET, path)).await?;
+        Ok(if status == 200 { serde_json::from_value(body).ok() } else { None })
+    }
+}
+
+/// Reads a colony-start body from the config file, an empty one without, and applies --seed
+pub fn colony_start_body(config: Option<&PathBuf>, seed: Option<u64>) -> Result<Value, String> {
+    let mut body = match config {
+        Some(path) => {
+            let text = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            serde_json::from_str(&text).map_err(|e| format!("Invalid config {}: {}", path.display(), e))?
+        }
+        None => Value::Object(Default::default()),
+    };
+    let Value::Object(fields) = &mut body else {
+        return Err("The colony-start config must be a JSON object".to_string());
+    };
+    if let Some(seed) = seed {
+        let seeding = fields.entry("seeding").or_insert_with(|| Value::Object(Default::default()));
+        let Value::Object(seeding) = seeding else {
+            return Err("seeding in the colony-start config must be a JSON object".to_string());
+        };
+        seeding.insert("seed".to_string(), Value::from(seed));
+    }
+    Ok(body)
+}
+
+async fn start_colony(client: &CoordinatorClient, config: Option<&PathBuf>, seed: Option<u64>) -> Result<CliOutput, String> {
+    let body = colony_start_body(config, seed)?;
+    let idempotency_key = format!("cli-{}", Uuid::new_v4());
+    let path = format!("/colony-start?idempotency_key={}", idempotency_key);
+    client.expect_success(client.request(reqwest::Method::POST, &path).body(body.to_string())).await?;
+    Ok(CliOutput {
+        text: format!("Colony start accepted (idempotency key {}), follow it with status", idempotency_key),
+        json: serde_json::json!({ "accepted": true, "idempotency_key": idempotency_key }),
+    })
+}
+
+async fn stop_colony(client: &CoordinatorClient) -> Result<CliOutput, String> {
+    let state = client.expect_success(client.request(reqwest::Method::POST, "/api/pause")).await?;
+    let text = match state.get("current_tick").and_then(Value::as_u64) {
+        Some(tick) => format!("Colony paused at tick {}", tick),
+        None => "Colony paused".to_string(),
+    };
+    Ok(CliOutput { text, json: state })
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct BackendSummary {
+    pub backend: String,
+    pub healthy: bool,
+    pub tick_range: Option<(u64, u64)>,
+}
+
+/// Printed by the status command
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct StatusSummary {
+    pub colony_status: Value,
+    /// Lowest and highest shard tick across the backends that answered
+    pub tick_range: Option<(u64, u64)>,
+    pub backends: Vec<BackendSummary>,
+    /// Creatures in the colony, from the occupied cells; None before the colony has stats
+    pub population: Option<u64>,
+}
+
+impl StatusSummary {
+    pub fn new(health: &Value, backends: Option<BackendsResponse>, stats: Option<ColonyStatsResponse>) -> Self {
+        let backends: Vec<BackendSummary> = backends.map(|response| response.backends).unwrap_or_default().into_iter()
+            .map(|status| BackendSummary { backend: status.backend, healthy: status.healthy, tick_range: status.tick_range })
+            .collect();
+        let ranges = backends.iter().filter_map(|backend| backend.tick_range);
+        let tick_range = ranges.clone().map(|(min, _)| min).min().zip(ranges.map(|(_, max)| max).max());
+        let population = stats.and_then(|stats| stats.stats.into_iter().find(|metric_stats| matches!(metric_stats.metric, StatMetric::Occupancy)))
+            .map(|occupancy| occupancy.buckets.iter().filter(|bucket| bucket.value != 0).map(|bucket| bucket.occs).sum());
+        Self {
+            colony_status: health.get("colony_status").cloned().unwrap_or(Value::Null),
+            tick_range,
+            backends,
+            population,
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        let colony_status = match &self.colony_status {
+            Value::String(status) => status.clone(),
+            other => other.to_string(),
+        };
+        let mut lines = vec![format!("Colony: {}", colony_status)];
+        lines.push(match self.tick_range {
+            Some((min, max)) => format!("Ticks: {}..{}", min, max),
+            None => "Ticks: unknown".to_string(),
+        });
+        lines.push(match self.population {
+            Some(population) => format!("Population: {}", population),
+            None => "Population: unknown".to_string(),
+        });
+        let healthy = self.backends.iter().filter(|backend| backend.healthy).count();
+        lines.push(format!("Backends: {} of {} healthy", healthy, self.backends.len()));
+        for backend in &self.backends {
+            let ticks = backend.tick_range.map(|(min, max)| format!("ticks {}..{}", min, max)).unwrap_or_else(|| "no ticks".to_string());
+            lines.push(format!("  {} {} {}", backend.backend, if backend.healthy { "healthy" } else { "unhealthy" }, ticks));
+        }
+        lines.join("\n")
+    }
+}
+
+async fn status(client: &CoordinatorClient) -> Result<CliOutput, String> {
+    let health = client.expect_success(client.request(reqwest::Method::GET, "/health")).await?;
+    // Both answer 404 until the colony is started
+    let backends = client.get_optional::<BackendsResponse>("/api/backends").await?;
+    let stats = client.get_optional::<ColonyStatsResponse>("/api/colony-stats?metrics=Occupancy").await?;
+    let summary = StatusSummary::new(&health, backends, stats);
+    let json = serde_json::to_value(&summary).map_err(|e| e.to_string())?;
+    Ok(CliOutput { text: summary.to_text(), json })
+}
+
+async fn apply_event(client: &CoordinatorClient, event_file: &PathBuf) -> Result<CliOutput, String> {
+    let body = std::fs::read_to_string(event_file).map_err(|e| format!("Failed to read {}: {}", event_file.display(), e))?;
+    // Caught here rather than as a 400, so a typo points at the file
+    serde_json::from_str::<ColonyEvent>(&body).map_err(|e| format!("Invalid colony event in {}: {}", event_file.display(), e))?;
+    let event = client.expect_success(client.request(reqwest::Method::POST, "/api/colony-events").body(body)).await?;
+    let text = event.to_string();
+    Ok(CliOutput { text, json: event })
+}
+
+async fn capture(client: &CoordinatorClient) -> Result<CliOutput, String> {
+    let frame = client.expect_success(client.request(reqwest::Method::POST, "/api/captures")).await?;
+    let text = format!("Captured tick {} to {}", frame["tick"], frame["path"].as_str().unwrap_or("?"));
+    Ok(CliOutput { text, json: frame })
+}
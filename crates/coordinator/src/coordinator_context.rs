@@ -1,12 +1,78 @@
-use std::sync::{OnceLock, Mutex};
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::sync::{Arc, OnceLock, Mutex};
+use std::sync::atomic::AtomicBool;
+use crate::capture_gate::CaptureGate;
+use crate::circuit_breaker::CircuitBreaker;
+use crate::colony_capture::CaptureSummary;
+use crate::colony_start::ColonyStartFailure;
+use crate::colony_stats_cache::ColonyStatsCache;
+use crate::colony_stats_alarms::{AlarmConfig, AlarmEvaluator};
+use crate::colony_event_generator::{EventGeneratorConfig, PopulationGuard};
 use crate::coordinator_storage::CoordinatorStoredInfo;
-use shared::{coordinator_api::ColonyEventDescription, be_api::ColonyLifeRules};
+use crate::init_colony::ShardInitCounter;
+use crate::live_feed_hub::FeedHub;
+use crate::lifecycle_events::{ExtinctionWatch, RegistryMembership};
+use crate::rules_drift::RulesDriftWatch;
+use crate::shard_leases::ShardLeaseTable;
+use crate::tick_monitor::TickHistory;
+use crate::topology_push::TopologySubscribers;
+use shared::http_port_probe::HttpPortProbe;
+use shared::rpc_decode_failures::{DecodeFailureTracker, DECODE_FAILURE_LOG_INTERVAL};
+use shared::{coordinator_api::{CaptureConfig, ColonyEventDescription}, be_api::{Biome, ColonyLifeRules}, density::ColonyDensity};
+use tokio::sync::Notify;
 
 #[derive(Debug)]
 pub struct CoordinatorContext {
     coord_stored_info: Mutex<CoordinatorStoredInfo>,
+    capture_config: Mutex<CaptureConfig>,
+    // Newest last, at most MAX_REGION_EVENTS
+    region_events: Mutex<VecDeque<ColonyEventDescription>>,
+    topology_subscribers: Mutex<TopologySubscribers>,
+    // The last /api/density grid, with the cells it was asked for
+    colony_density: Mutex<Option<(usize, Arc<ColonyDensity>)>>,
+    population_guard: Mutex<PopulationGuard>,
+    // Frames written and skipped for the current colony instance, see colony_capture
+    capture_summary: Mutex<Option<CaptureSummary>>,
+    shard_leases: Mutex<ShardLeaseTable>,
+    // Circuit breaker per backend address, see circuit_breaker
+    backend_breakers: Mutex<HashMap<String, CircuitBreaker>>,
+    shard_init_counter: Mutex<ShardInitCounter>,
+    // Undecodable CoordinatorRequests per peer IP, served by /api/rpc-stats and /metrics
+    decode_failures: Mutex<DecodeFailureTracker>,
+    rules_drift_watch: Mutex<RulesDriftWatch>,
+    // Ids of the shards created with their topography still to follow
+    awaiting_topography: Mutex<BTreeSet<String>>,
+    // Set while a colony expansion runs, so a second one is refused
+    expansion_in_flight: AtomicBool,
+    registry_membership: Mutex<RegistryMembership>,
+    extinction_watch: Mutex<ExtinctionWatch>,
+    // Set by /api/pause and the run summary, cleared by /api/resume and colony-start
+    colony_paused: AtomicBool,
+    // Set while an /api/step call runs, so a second one is refused
+    step_in_flight: AtomicBool,
+    // Carried to the backends by StartTicking, see colony_step::set_fast_forward
+    fast_forward: AtomicBool,
+    // Set while a verification pass runs, so a second one is refused
+    verify_in_flight: AtomicBool,
+    tick_history: Mutex<TickHistory>,
+    stats_alarms: Mutex<AlarmEvaluator>,
+    colony_stats_cache: ColonyStatsCache,
+    // Gates of the periodic captures; final and on-demand captures do not go through them
+    stats_capture_gate: Mutex<CaptureGate>,
+    image_capture_gate: Mutex<CaptureGate>,
+    feed_hub: FeedHub,
+    // Wakes the periodic loops so a new interval applies to the wait in progress
+    capture_config_changed: Notify,
+    // Rebuilt by set_deployment_mode, since AWS mode turns probing off
+    http_port_probe: Mutex<HttpPortProbe>,
+    // Ids of the shards frozen through this coordinator
+    frozen_shards: Mutex<BTreeSet<String>>,
+    last_start_failure: Mutex<Option<ColonyStartFailure>>,
 }
 
+/// Region events kept for the GUI's event markers, see add_region_event
+pub const MAX_REGION_EVENTS: usize = 100;
+
 static COORDINATOR_CONTEXT: OnceLock<CoordinatorContext> = OnceLock::new();
 
 impl CoordinatorContext {
@@ -14,6 +80,35 @@ impl CoordinatorContext {
         COORDINATOR_CONTEXT.get_or_init(|| {
             CoordinatorContext {
                 coord_stored_info: Mutex::new(CoordinatorStoredInfo::new()),
+                capture_config: Mutex::new(crate::capture_config::capture_config_from_env()),
+                region_events: Mutex::new(VecDeque::new()),
+                topology_subscribers: Mutex::new(TopologySubscribers::default()),
+                colony_density: Mutex::new(None),
+                population_guard: Mutex::new(PopulationGuard::new(EventGeneratorConfig::from_env())),
+                capture_summary: Mutex::new(None),
+                shard_leases: Mutex::new(ShardLeaseTable::default()),
+                backend_breakers: Mutex::new(HashMap::new()),
+                shard_init_counter: Mutex::new(ShardInitCounter::default()),
+                decode_failures: Mutex::new(DecodeFailureTracker::new(DECODE_FAILURE_LOG_INTERVAL)),
+                rules_drift_watch: Mutex::new(RulesDriftWatch::new()),
+                awaiting_topography: Mutex::new(BTreeSet::new()),
+                expansion_in_flight: AtomicBool::new(false),
+                registry_membership: Mutex::new(RegistryMembership::new()),
+                extinction_watch: Mutex::new(ExtinctionWatch::new()),
+                colony_paused: AtomicBool::new(false),
+                step_in_flight: AtomicBool::new(false),
+                fast_forward: AtomicBool::new(false),
+                verify_in_flight: AtomicBool::new(false),
+                tick_history: Mutex::new(TickHistory::from_env()),
+                stats_alarms: Mutex::new(AlarmEvaluator::new(AlarmConfig::from_env())),
+                colony_stats_cache: ColonyStatsCache::from_env(),
+                stats_capture_gate: Mutex::new(CaptureGate::new("Statistics capture")),
+                image_capture_gate: Mutex::new(CaptureGate::new("Image capture")),
+                feed_hub: FeedHub::from_env(),
+                capture_config_changed: Notify::new(),
+                http_port_probe: Mutex::new(HttpPortProbe::for_deployment_mode("")),
+                frozen_shards: Mutex::new(BTreeSet::new()),
+                last_start_failure: Mutex::new(None),
             }
         })
     }
@@ -23,6 +118,7 @@ impl CoordinatorContext {
     }
 
     pub fn add_colony_event(&self, event: ColonyEventDescription) {
+        crate::live_feed_hub::publish_event(&event);
         let mut stored_info = self.coord_stored_info.lock().expect("Failed to acquire lock on coord_stored_info");
         stored_info.add_event(event);
     }
@@ -32,6 +128,22 @@ impl CoordinatorContext {
         stored_info.get_events().clone()
     }
     
+    /// Keeps a recent event with a region, e.g. CreateCreature, apart from the colony events;
+    /// only the last MAX_REGION_EVENTS are kept
+    pub fn add_region_event(&self, event: ColonyEventDescription) {
+        let mut region_events = self.region_events.lock().expect("Failed to acquire lock on region_events");
+        if region_events.len() == MAX_REGION_EVENTS {
+            region_events.pop_front();
+        }
+        region_events.push_back(event);
+    }
+
+    /// Newest first, like /api/colony-events
+    pub fn get_region_events(&self) -> Vec<ColonyEventDescription> {
+        let region_events = self.region_events.lock().expect("Failed to acquire lock on region_events");
+        region_events.iter().rev().cloned().collect()
+    }
+    
     pub fn get_colony_life_rules(&self) -> ColonyLifeRules {
         let stored_info = self.coord_stored_info.lock().expect("Failed to acquire lock on coord_stored_info");
         stored_info.colony_life_rules.unwrap_or_else(|| {
@@ -45,7 +157,18 @@ impl CoordinatorContext {
         stored_info.update_colony_rules(new_rules);
     }
     
+    pub fn get_biomes(&self) -> Vec<Biome> {
+        let stored_info = self.coord_stored_info.lock().expect("Failed to acquire lock on coord_stored_info");
+        stored_info.biomes.clone()
+    }
+    
+    pub fn set_biomes(&self, biomes: Vec<Biome>) {
+        let mut stored_info = self.coord_stored_info.lock().expect("Failed to acquire lock on coord_stored_info");
+        stored_info.biomes = biomes;
+    }
+    
     pub fn set_deployment_mode(&self, mode: String) {
+        *self.http_port_probe() = HttpPortProbe::for_deployment_mode(&mode);
         let mut stored_info = self.coord_stored_info.lock().expect("Failed to acquire lock on coord_stored_info");
         stored_info.deployment_mode = Some(mode);
     }
@@ -54,4 +177,153 @@ impl CoordinatorContext {
         let stored_info = self.coord_stored_info.lock().expect("Failed to acquire lock on coord_stored_info");
         stored_info.deployment_mode.clone()
     }
+
+    /// Backends that get the topology pushed, see topology_push
+    pub fn topology_subscribers(&self) -> std::sync::MutexGuard<'_, TopologySubscribers> {
+        self.topology_subscribers.lock().expect("Failed to acquire lock on topology_subscribers")
+    }
+
+    /// The last colony density grid, when it was taken at cells buckets per side
+    pub fn get_colony_density(&self, cells: usize) -> Option<Arc<ColonyDensity>> {
+        let cached = self.colony_density.lock().expect("Failed to acquire lock on colony_density");
+        cached.as_ref().filter(|(cached_cells, _)| *cached_cells == cells).map(|(_, density)| density.clone())
+    }
+
+    pub fn set_colony_density(&self, cells: usize, density: Arc<ColonyDensity>) {
+        *self.colony_density.lock().expect("Failed to acquire lock on colony_density") = Some((cells, density));
+    }
+
+    /// Screens the generated events by the creature counts of the stats captures
+    pub fn population_guard(&self) -> std::sync::MutexGuard<'_, PopulationGuard> {
+        self.population_guard.lock().expect("Failed to acquire lock on population_guard")
+    }
+
+    /// The capture summary of the last colony instance that captured a frame
+    pub(crate) fn capture_summary(&self) -> std::sync::MutexGuard<'_, Option<CaptureSummary>> {
+        self.capture_summary.lock().expect("Failed to acquire lock on capture_summary")
+    }
+
+    /// The lease issued for every assigned shard, see shard_leases
+    pub fn shard_leases(&self) -> std::sync::MutexGuard<'_, ShardLeaseTable> {
+        self.shard_leases.lock().expect("Failed to acquire lock on shard_leases")
+    }
+
+    pub fn backend_breakers(&self) -> std::sync::MutexGuard<'_, HashMap<String, CircuitBreaker>> {
+        self.backend_breakers.lock().expect("Failed to acquire lock on backend_breakers")
+    }
+
+    /// Progress of the shard initialization of the last colony-start
+    pub fn shard_init_counter(&self) -> std::sync::MutexGuard<'_, ShardInitCounter> {
+        self.shard_init_counter.lock().expect("Failed to acquire lock on shard_init_counter")
+    }
+
+    /// Not locked here: FrameDecoder::decode takes the tracker and locks it only on a failure
+    pub fn decode_failures(&self) -> &Mutex<DecodeFailureTracker> {
+        &self.decode_failures
+    }
+
+    /// Backends whose rules drifted at the last check, see rules_drift
+    pub fn rules_drift_watch(&self) -> std::sync::MutexGuard<'_, RulesDriftWatch> {
+        self.rules_drift_watch.lock().expect("Failed to acquire lock on rules_drift_watch")
+    }
+
+    /// Shards whose topography push is still outstanding, see global_topography
+    pub fn awaiting_topography(&self) -> std::sync::MutexGuard<'_, BTreeSet<String>> {
+        self.awaiting_topography.lock().expect("Failed to acquire lock on awaiting_topography")
+    }
+
+    /// Held through colony_expand's ExpansionGuard
+    pub fn expansion_in_flight(&self) -> &AtomicBool {
+        &self.expansion_in_flight
+    }
+
+    /// Registry members seen at the last sweep, see lifecycle_events
+    pub fn registry_membership(&self) -> std::sync::MutexGuard<'_, RegistryMembership> {
+        self.registry_membership.lock().expect("Failed to acquire lock on registry_membership")
+    }
+
+    /// Creature count of the last stats snapshot, see lifecycle_events
+    pub fn extinction_watch(&self) -> std::sync::MutexGuard<'_, ExtinctionWatch> {
+        self.extinction_watch.lock().expect("Failed to acquire lock on extinction_watch")
+    }
+
+    /// Whether the ticker is paused on every backend, see colony_step
+    pub fn colony_paused(&self) -> &AtomicBool {
+        &self.colony_paused
+    }
+
+    /// Held through colony_step's StepGuard
+    pub fn step_in_flight(&self) -> &AtomicBool {
+        &self.step_in_flight
+    }
+
+    /// Whether the backends skip presentation work to tick as fast as they can
+    pub fn fast_forward(&self) -> &AtomicBool {
+        &self.fast_forward
+    }
+
+    /// Held through colony_verification's VerifyGuard
+    pub fn verify_in_flight(&self) -> &AtomicBool {
+        &self.verify_in_flight
+    }
+
+    /// Cluster tick samples of tick_monitor::record_tick_history
+    pub fn tick_history(&self) -> std::sync::MutexGuard<'_, TickHistory> {
+        self.tick_history.lock().expect("Failed to acquire lock on tick_history")
+    }
+
+    /// Alarm rules run over every stats capture, see colony_stats_alarms
+    pub fn stats_alarms(&self) -> std::sync::MutexGuard<'_, AlarmEvaluator> {
+        self.stats_alarms.lock().expect("Failed to acquire lock on stats_alarms")
+    }
+
+    /// Last merged colony stats, see colony_stats
+    pub fn colony_stats_cache(&self) -> &ColonyStatsCache {
+        &self.colony_stats_cache
+    }
+
+    /// Gate of the periodic statistics capture, see colony_stats::capture_colony_stats
+    pub fn stats_capture_gate(&self) -> std::sync::MutexGuard<'_, CaptureGate> {
+        self.stats_capture_gate.lock().expect("Failed to acquire lock on stats_capture_gate")
+    }
+
+    /// Gate of the periodic frame capture, see colony_capture::capture_colony
+    pub fn image_capture_gate(&self) -> std::sync::MutexGuard<'_, CaptureGate> {
+        self.image_capture_gate.lock().expect("Failed to acquire lock on image_capture_gate")
+    }
+
+    /// The connected /ws clients, see live_feed_hub
+    pub fn feed_hub(&self) -> &FeedHub {
+        &self.feed_hub
+    }
+
+    /// Notified by capture_config::update_capture_config
+    pub fn capture_config_changed(&self) -> &Notify {
+        &self.capture_config_changed
+    }
+
+    /// Finds backend HTTP ports the registry has none for, see colony_capture
+    pub fn http_port_probe(&self) -> std::sync::MutexGuard<'_, HttpPortProbe> {
+        self.http_port_probe.lock().expect("Failed to acquire lock on http_port_probe")
+    }
+
+    /// Shards frozen through set_shard_frozen, see shard_freeze
+    pub fn frozen_shards(&self) -> std::sync::MutexGuard<'_, BTreeSet<String>> {
+        self.frozen_shards.lock().expect("Failed to acquire lock on frozen_shards")
+    }
+
+    /// Why the last colony-start did not complete, cleared when a new one begins
+    pub fn last_start_failure(&self) -> std::sync::MutexGuard<'_, Option<ColonyStartFailure>> {
+        self.last_start_failure.lock().expect("Failed to acquire lock on last_start_failure")
+    }
+
+    pub fn get_capture_config(&self) -> CaptureConfig {
+        *self.capture_config.lock().expect("Failed to acquire lock on capture_config")
+    }
+
+    /// Returns the replaced settings
+    pub fn set_capture_config(&self, config: CaptureConfig) -> CaptureConfig {
+        let mut current = self.capture_config.lock().expect("Failed to acquire lock on capture_config");
+        std::mem::replace(&mut *current, config)
+    }
 }
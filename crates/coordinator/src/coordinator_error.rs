@@ -0,0 +1,107 @@
+use shared::colony_model::Shard;
+use std::io::ErrorKind;
+use thiserror::Error;
+
+/// Why a coordinator call to a backend, or the colony start around it, failed
+#[derive(Debug, Error)]
+pub enum CoordinatorError {
+    #[error("failed to connect to backend {host}: {source}")]
+    Connect { host: String, #[source] source: std::io::Error },
+    #[error("backend {host} did not answer {op} in time")]
+    Timeout { host: String, op: &'static str },
+    #[error("connection to backend {host} failed during {op}: {reason}")]
+    ConnectionLost { host: String, op: &'static str, reason: String },
+    #[error("could not decode the {op} response of backend {host}")]
+    DeserializationFailed { host: String, op: &'static str },
+    #[error("backend {host} sent an unexpected response to {op}")]
+    UnexpectedResponse { host: String, op: &'static str },
+    #[error("backend {host} rejected {}: {response}", shard.map(|shard| format!("shard {}", shard.to_id())).unwrap_or_else(|| "the request".to_string()))]
+    BackendRejected { host: String, shard: Option<Shard>, response: String },
+    #[error("backend {host} was given colony dimensions that do not match: {reason}")]
+    DimensionMismatch { host: String, reason: String },
+    #[error("colony rules are invalid: {0}")]
+    InvalidRules(String),
+    #[error("topology not initialized")]
+    TopologyMissing,
+    #[error("topology has no backend for shard {}", shard.to_id())]
+    NoBackendForShard { shard: Shard },
+    #[error("no backend is available")]
+    NoBackendsAvailable,
+    #[error("only {healthy} healthy backends, {required} required; unreachable registry entries: {}", if dead.is_empty() { "none".to_string() } else { dead.join(", ") })]
+    NotEnoughBackends { healthy: usize, required: usize, dead: Vec<String> },
+    #[error("topology could not be installed: {0}")]
+    TopologyRejected(String),
+    #[error("backend {host} keeps failing, calls fail fast until the next probe")]
+    CircuitOpen { host: String },
+}
+
+impl CoordinatorError {
+    /// Errors of the transport rather than of the request; sending the same request again may succeed
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, CoordinatorError::Connect { .. } | CoordinatorError::Timeout { .. } | CoordinatorError::ConnectionLost { .. })
+    }
+
+    /// Stable name for JSON responses and the colony-start status
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CoordinatorError::Connect { .. } => "connect",
+            CoordinatorError::Timeout { .. } => "timeout",
+            CoordinatorError::ConnectionLost { .. } => "connection_lost",
+            CoordinatorError::DeserializationFailed { .. } => "deserialization_failed",
+            CoordinatorError::UnexpectedResponse { .. } => "unexpected_response",
+            CoordinatorError::BackendRejected { .. } => "backend_rejected",
+            CoordinatorError::DimensionMismatch { .. } => "dimension_mismatch",
+            CoordinatorError::InvalidRules(_) => "invalid_rules",
+            CoordinatorError::TopologyMissing => "topology_missing",
+            CoordinatorError::NoBackendForShard { .. } => "no_backend_for_shard",
+            CoordinatorError::NoBackendsAvailable => "no_backends_available",
+            CoordinatorError::NotEnoughBackends { .. } => "not_enough_backends",
+            CoordinatorError::TopologyRejected(_) => "topology_rejected",
+            CoordinatorError::CircuitOpen { .. } => "circuit_open",
+        }
+    }
+
+    pub fn http_status(&self) -> &'static str {
+        match self {
+            CoordinatorError::Connect { .. }
+            | CoordinatorError::ConnectionLost { .. }
+            | CoordinatorError::DeserializationFailed { .. }
+            | CoordinatorError::UnexpectedResponse { .. } => "502 Bad Gateway",
+            CoordinatorError::Timeout { .. } => "504 Gateway Timeout",
+            CoordinatorError::BackendRejected { .. } => "409 Conflict",
+            CoordinatorError::InvalidRules(_) => "400 Bad Request",
+            CoordinatorError::TopologyMissing | CoordinatorError::NoBackendForShard { .. } => "404 Not Found",
+            CoordinatorError::NoBackendsAvailable
+            | CoordinatorError::NotEnoughBackends { .. }
+            | CoordinatorError::CircuitOpen { .. } => "503 Service Unavailable",
+            CoordinatorError::TopologyRejected(_) | CoordinatorError::DimensionMismatch { .. } => "500 Internal Server Error",
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::json!({ "error": self.to_string(), "kind": self.kind() }).to_string()
+    }
+
+    /// Classifies an I/O failure while talking to a backend that is already connected
+    pub fn from_io(host: &str, op: &'static str, error: &std::io::Error) -> Self {
+        match error.kind() {
+            ErrorKind::TimedOut | ErrorKind::WouldBlock => CoordinatorError::Timeout { host: host.to_string(), op },
+            _ => CoordinatorError::ConnectionLost { host: host.to_string(), op, reason: error.to_string() },
+        }
+    }
+
+    /// Classifies an error of the blocking helpers in shared::backend_communication, which box
+    /// both socket and bincode errors
+    pub fn from_transport(host: &str, op: &'static str, error: &(dyn std::error::Error + 'static)) -> Self {
+        if let Some(io_error) = error.downcast_ref::<std::io::Error>() {
+            return Self::from_io(host, op, io_error);
+        }
+        let bincode_error = error.downcast_ref::<bincode::Error>().map(|error| error.as_ref())
+            .or_else(|| error.downcast_ref::<bincode::ErrorKind>());
+        match bincode_error {
+            Some(bincode::ErrorKind::Io(io_error)) => Self::from_io(host, op, io_error),
+            Some(_) => CoordinatorError::DeserializationFailed { host: host.to_string(), op },
+            None => CoordinatorError::ConnectionLost { host: host.to_string(), op, reason: error.to_string() },
+        }
+    }
+}
@@ -0,0 +1,310 @@
+use shared::coordinator_api::{CoordinatorRequest, CoordinatorResponse, RoutingEntry};
+use shared::cluster_topology::{ClusterTopology, HostInfo, NodeAddress};
+use shared::cluster_registry::{ClusterRegistry, create_cluster_registry, get_instance};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_util::codec::{Framed, LengthDelimitedCodec};
+use tokio_stream::StreamExt;
+use shared::logging::{log_startup, init_logging, set_panic_hook};
+use shared::output_paths::OutputPaths;
+use shared::{log_error, log};
+use shared::supervisor::spawn_supervised;
+use shared::backend_communication::accept_hello;
+use shared::rpc_decode_failures::{decode_failure_limit, DecodedFrame, FrameDecoder};
+use crate::capture_config::run_periodic;
+use futures_util::SinkExt;
+use crate::http_server::start_http_server;
+use crate::shard_leases::renew_backend_leases;
+use crate::topology_push::{subscribe_topology, TOPOLOGY_PUSH_TIMEOUT};
+use crate::coordinator_context::CoordinatorContext;
+use std::str::FromStr;
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeploymentMode {
+    Localhost,
+    Aws,
+}
+
+impl FromStr for DeploymentMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "localhost" => Ok(DeploymentMode::Localhost),
+            "aws" => Ok(DeploymentMode::Aws),
+            _ => Err(format!("Invalid deployment mode: {}. Must be 'localhost' or 'aws'", s)),
+        }
+    }
+}
+
+type FramedStream = Framed<TcpStream, LengthDelimitedCodec>;
+
+pub use shared::be_api::BUILD_VERSION;
+
+fn call_label(response: &CoordinatorResponse) -> &'static str {
+    match response {
+        CoordinatorResponse::GetRoutingTableResponse { .. } => "GetRoutingTable",
+        CoordinatorResponse::RenewShardLeasesResponse { .. } => "RenewShardLeases",
+        CoordinatorResponse::TopologyUpdate { .. } => "TopologyUpdate",
+    }
+}
+
+async fn send_response(framed: &mut FramedStream, response: CoordinatorResponse) {
+    let encoded = bincode::serialize(&response).expect("Failed to serialize CoordinatorResponse");
+    let label = call_label(&response);
+    if let Err(e) = framed.send(encoded.into()).await {
+        log_error!("Failed to send {} response: {}", label, e);
+    } else {
+        log!("Sent {} response", label);
+    }
+}
+
+async fn handle_get_routing_table() -> CoordinatorResponse {
+    let topology = match ClusterTopology::get_instance() {
+        Some(t) => t,
+        None => {
+            log_error!("Topology not initialized");
+            return CoordinatorResponse::GetRoutingTableResponse { entries: Vec::new() };
+        }
+    };
+    let mut entries = Vec::new();
+    
+    for shard in topology.get_all_shards() {
+        let host_info = topology.get_host_for_shard(&shard).unwrap();
+        entries.push(RoutingEntry {
+            shard,
+            hostname: host_info.hostname.clone(),
+            port: host_info.port,
+        });
+    }
+
+    CoordinatorResponse::GetRoutingTableResponse { entries }
+}
+
+
+/// Pushes the topology to a SubscribeTopology connection until the backend disconnects, a newer
+/// subscription of it replaces this one, or a push fails. The backend then re-subscribes.
+async fn serve_topology_subscription(framed: &mut FramedStream, backend: HostInfo) {
+    log!("Backend {} subscribed to topology updates", backend.to_address());
+    let mut updates = subscribe_topology(backend.clone());
+    loop {
+        tokio::select! {
+            update = updates.recv() => {
+                let Some(topology) = update else {
+                    break;
+                };
+                let encoded = bincode::serialize(&CoordinatorResponse::TopologyUpdate { topology: (*topology).clone() })
+                    .expect("Failed to serialize CoordinatorResponse");
+                match tokio::time::timeout(TOPOLOGY_PUSH_TIMEOUT, framed.send(encoded.into())).await {
+                    Ok(Ok(())) => {}
+                    Ok(Err(e)) => {
+                        log_error!("Dropping topology subscriber {}: {}", backend.to_address(), e);
+                        break;
+                    }
+                    Err(_) => {
+                        log_error!("Dropping topology subscriber {}: push timed out", backend.to_address());
+                        break;
+                    }
+                }
+            }
+            // A subscriber sends nothing after subscribing; only the stream ending matters
+            frame = framed.next() => {
+                if !matches!(frame, Some(Ok(_))) {
+                    break;
+                }
+            }
+        }
+    }
+    log!("Topology subscription of {} ended", backend.to_address());
+}
+
+async fn handle_client(socket: TcpStream) {
+    let peer = socket.peer_addr().map(|addr| addr.to_string()).unwrap_or_else(|_| "unknown".to_string());
+    let peer_key = socket.peer_addr().map(|addr| addr.ip().to_string()).unwrap_or_else(|_| peer.clone());
+    let mut framed = Framed::new(socket, LengthDelimitedCodec::new());
+    let Some(hello) = accept_hello(&mut framed, &peer).await else {
+        return;
+    };
+    let mut decoder = FrameDecoder::new(peer_key, decode_failure_limit());
+    while let Some(Ok(bytes)) = framed.next().await {
+        let response = match decoder.decode::<CoordinatorRequest>(&bytes, CoordinatorContext::get_instance().decode_failures()) {
+            DecodedFrame::Request(CoordinatorRequest::GetRoutingTable) => handle_get_routing_table().await,
+            DecodedFrame::Request(CoordinatorRequest::RenewShardLeases { backend, leases }) => {
+                CoordinatorResponse::RenewShardLeasesResponse { renewals: renew_backend_leases(&backend, &leases) }
+            }
+            DecodedFrame::Request(CoordinatorRequest::SubscribeTopology { backend }) => {
+                serve_topology_subscription(&mut framed, backend).await;
+                return;
+            }
+            DecodedFrame::Rejected { error, log_count, disconnect } => {
+                if let Some(count) = log_count {
+                    log_error!("Failed to deserialize CoordinatorRequest from {} (protocol {}, build {}), {} failures from this peer{}: {}",
+                               peer, hello.protocol_version, hello.build_version, count,
+                               if disconnect { ", closing the connection" } else { "" }, error);
+                }
+                if disconnect {
+                    break;
+                }
+                continue;
+            }
+        };
+        send_response(&mut framed, response).await;
+    }
+}
+
+
+fn check_port_available(port: u16) -> Result<(), String> {
+    use std::net::TcpListener;
+    match TcpListener::bind(format!("127.0.0.1:{}", port)) {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            if e.kind() == std::io::ErrorKind::AddrInUse {
+                Err(format!("Port {} is already in use", port))
+            } else {
+                Err(format!("Failed to check port {}: {}", port, e))
+            }
+        }
+    }
+}
+
+/// Everything run_coordinator needs; coordinator_main fills it from the command line or the environment
+#[derive(Debug, Clone)]
+pub struct CoordinatorServerConfig {
+    pub rpc_port: u16,
+    pub http_port: u16,
+    pub deployment_mode: DeploymentMode,
+}
+
+/// Runs the coordinator RPC and HTTP servers; only returns if the ports are unavailable or the output directory is not writable.
+/// Coordinator state (context, topology) is process-global, so one coordinator per process.
+pub async fn run_coordinator(config: CoordinatorServerConfig) -> Result<(), String> {
+    let CoordinatorServerConfig { rpc_port, http_port, deployment_mode } = config;
+    let deployment_mode_str = match deployment_mode {
+        DeploymentMode::Aws => "aws",
+        DeploymentMode::Localhost => "localhost",
+    };
+    
+    // Validate ports are available
+    check_port_available(rpc_port).map_err(|e| format!("RPC port validation failed: {}", e))?;
+    check_port_available(http_port).map_err(|e| format!("HTTP port validation failed: {}", e))?;
+    let output_paths = OutputPaths::get_instance();
+    output_paths.ensure_writable()?;
+    init_logging(output_paths.log_file(&format!("coordinator_{}", rpc_port)));
+    log_startup("COORDINATOR");
+
+    log!("Starting coordinator in {:?} deployment mode, version {}", deployment_mode, BUILD_VERSION);
+    log!("RPC port: {}, HTTP port: {}", rpc_port, http_port);
+    set_panic_hook();
+    
+    // Initialize ClusterRegistry early
+    let _registry = create_cluster_registry(deployment_mode_str);
+    
+    // Store deployment mode in coordinator context
+    let context = crate::coordinator_context::CoordinatorContext::get_instance();
+    context.set_deployment_mode(deployment_mode_str.to_string());
+    
+    // Coordinator ticker will be started by start_colony_ticking() after colony initialization
+    // Do NOT start ticker automatically here
+    
+    // Topology is never initialized automatically - it must be created explicitly via POST /colony-start
+    // This applies to both localhost and AWS modes
+    log!("Waiting for colony-start HTTP request to initialize topology and colony");
+
+    // Start HTTP server (in both AWS and localhost modes)
+    tokio::spawn(start_http_server(http_port));
+
+    // Start TCP listener for coordinator protocol
+    let bind_host = match deployment_mode {
+        DeploymentMode::Aws => "0.0.0.0",
+        DeploymentMode::Localhost => "127.0.0.1",
+    };
+    let addr = format!("{}:{}", bind_host, rpc_port);
+    let listener = match TcpListener::bind(&addr).await {
+        Ok(listener) => listener,
+        Err(err) => {
+            log_error!("Failed to bind coordinator protocol listener on {}: {}", addr, err);
+            panic!("Could not bind coordinator protocol listener on {}: {}", addr, err);
+        }
+    };
+    log!("Listening on {} for coordinator protocol", addr);
+
+    // Register coordinator in ClusterRegistry
+    let (coordinator_private_ip, coordinator_public_ip) = match deployment_mode {
+        DeploymentMode::Aws => {
+            // Get actual EC2 private IP
+            let private_ip = match shared::utils::get_ec2_private_ip().await {
+                Some(ip) => {
+                    log!("Discovered EC2 private IP: {}", ip);
+                    ip
+                }
+                None => {
+                    log_error!("Failed to get EC2 private IP, registration will fail");
+                    "0.0.0.0".to_string()
+                }
+            };
+            // Get actual EC2 public IP
+            let public_ip = match shared::utils::get_ec2_public_ip().await {
+                Some(ip) => {
+                    log!("Discovered EC2 public IP: {}", ip);
+                    ip
+                }
+                None => {
+                    log_error!("Failed to get EC2 public IP, registration will fail");
+                    "0.0.0.0".to_string()
+                }
+            };
+            (private_ip, public_ip)
+        }
+        DeploymentMode::Localhost => ("127.0.0.1".to_string(), "127.0.0.1".to_string()),
+    };
+    // Use RPC port for internal communication and HTTP port for HTTP endpoints
+    let coordinator_address = NodeAddress::new(coordinator_private_ip.clone(), coordinator_public_ip.clone(), rpc_port, http_port);
+    let internal_addr = coordinator_address.to_internal_address();
+    let http_addr = coordinator_address.to_http_address();
+    if let Some(registry) = get_instance() {
+        if let Err(e) = registry.register_coordinator(coordinator_address).await {
+            log_error!("Failed to register coordinator: {}", e);
+        } else {
+            log!("Registered coordinator in SSM ClusterRegistry: {} (internal), {} (http)", 
+                 internal_addr, http_addr);
+        }
+    }
+
+    // Setup signal handlers for graceful shutdown
+    let registry_clone = get_instance();
+    tokio::spawn(async move {
+        use tokio::signal;
+        let _ = signal::ctrl_c().await;
+        log!("Received shutdown signal, unregistering coordinator...");
+        if let Some(registry) = registry_clone {
+            if let Err(e) = registry.unregister_coordinator().await {
+                log_error!("Failed to unregister coordinator: {}", e);
+            }
+        }
+        std::process::exit(0);
+    });
+
+    // Periodic creature image capture and stats, with intervals from /api/capture-config
+    spawn_supervised("colony-capture", || run_periodic(
+        |config| (config.capture_interval_secs, config.captures_enabled),
+        crate::colony_capture::capture_colony,
+    ));
+
+    spawn_supervised("colony-stats", || run_periodic(
+        |config| (config.stats_interval_secs, config.stats_enabled),
+        crate::colony_stats::capture_colony_stats,
+    ));
+
+    spawn_supervised("tick-history", crate::tick_monitor::record_tick_history);
+    spawn_supervised("feed-frames", crate::live_feed_hub::publish_frames);
+
+    loop {
+        match listener.accept().await {
+            Ok((socket, _)) => {
+                log!("Accepted connection");
+                tokio::spawn(handle_client(socket));
+            }
+            Err(e) => log_error!("Connection failed: {}", e),
+        }
+    }
+} 
\ No newline at end of file
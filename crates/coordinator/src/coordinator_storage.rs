@@ -1,15 +1,20 @@
 use serde::{Serialize, Deserialize};
-use shared::{be_api::ColonyLifeRules, storage::StorageUtils};
-use shared::coordinator_api::ColonyEventDescription;
+use shared::{be_api::{Biome, ColonyLifeRules}, storage::StorageUtils};
+use shared::coordinator_api::{ColonyEventDescription, ColonyRunConfig};
+use shared::output_paths::OutputPaths;
 
 #[allow(dead_code)]
-pub const COORDINATOR_STATE_FILE: &str = "output/storage/colony.dat";
+pub fn coordinator_state_file() -> std::path::PathBuf {
+    OutputPaths::get_instance().storage_dir().join("colony.dat")
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum ColonyStatus {
     NotInitialized,
     Initializing,
     TopographyInitialized,
+    /// The run reached its target_tick and was stopped, see crate::run_summary
+    Completed,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -23,6 +28,20 @@ pub struct CoordinatorStoredInfo {
     pub colony_start_idempotency_key: Option<String>,
     pub colony_instance_id: Option<String>,
     pub deployment_mode: Option<String>,
+    /// Set once per colony instance when it starts, see record_run_config
+    pub run_config: Option<ColonyRunConfig>,
+    /// Regions with rule overrides, see crate::biomes
+    pub biomes: Vec<Biome>,
+    /// Stop the run once the slowest shard reaches this tick, from the colony-start body
+    pub target_tick: Option<u64>,
+    /// No random events nor periodic captures until the slowest shard reaches this tick, from the colony-start body
+    pub warmup_ticks: Option<u64>,
+    /// Set once the warm-up was over, see crate::warmup
+    pub warmup_ended: bool,
+    /// Unix time in ms when the shards were initialized
+    pub run_started_at_ms: Option<u64>,
+    /// Set once a backend accepted StartTicking; the periodic captures wait for it
+    pub ticking_started: bool,
 }
 
 impl CoordinatorStoredInfo {
@@ -37,6 +56,13 @@ impl CoordinatorStoredInfo {
             colony_start_idempotency_key: None,
             colony_instance_id: None,
             deployment_mode: None,
+            run_config: None,
+            biomes: Vec::new(),
+            target_tick: None,
+            warmup_ticks: None,
+            warmup_ended: false,
+            run_started_at_ms: None,
+            ticking_started: false,
         }
     }
     
@@ -56,6 +82,14 @@ impl CoordinatorStoredInfo {
         current_tick < self.pause_events_till
     }
     
+    /// Keeps the first configuration recorded for a colony instance; later calls for the same
+    /// instance are ignored, a new instance replaces it
+    pub fn record_run_config(&mut self, config: ColonyRunConfig) {
+        if self.run_config.as_ref().is_none_or(|recorded| recorded.colony_instance_id != config.colony_instance_id) {
+            self.run_config = Some(config);
+        }
+    }
+    
     pub fn update_colony_rules(&mut self, new_rules: ColonyLifeRules) {
         self.colony_life_rules = Some(new_rules);
     }
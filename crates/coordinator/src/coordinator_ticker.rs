@@ -1,20 +1,33 @@
 use shared::log;
 use shared::colony_model::Shard;
 use shared::colony_event_shared::{log_event, create_colony_event_description};
+use shared::colony_event_schema::validate_colony_event;
+use shared::coordinator_api::ColonyEventDescription;
 use crate::coordinator_context::CoordinatorContext;
-use crate::colony_event_generator::{randomize_event_by_frequency, get_next_event_tick_by_frequency, EventFrequency};
+use crate::colony_event_generator::{randomize_event_by_frequency, get_next_event_tick_by_frequency, EventFrequency, EventTickClock, ScreenedEvent, EVENT_SUPPRESSED_EVENT};
+use crate::lifecycle_events::record_lifecycle_event;
+use shared::colony_event_shared::event_type_name;
 use shared::utils::new_random_generator;
 use crate::backend_client;
-use crate::tick_monitor::TickMonitor;
-use crate::global_topography::{GlobalTopography, GlobalTopographyInfo};
+use crate::tick_monitor::{latest_max_tick, latest_min_tick, TickMonitor};
+use crate::run_summary::{complete_run, is_run_completed, reached_target};
+use crate::warmup::{end_warmup, is_colony_warming_up, reached_warmup};
+use crate::global_topography::regenerate_colony_topography;
 use crate::event_logging;
+use crate::coordinator_error::CoordinatorError;
+use crate::colony_stats_alarms::events_paused_by_alarm;
+use crate::rules_drift::{check_rules_drift, rules_drift_check_interval};
 use std::sync::Mutex;
 use std::collections::HashMap;
+use std::time::Instant;
 
 const TOPOGRAPHY_EVENT_PAUSE_TICKS: u64 = 2000;
 const DISABLED_EVENTS: bool = false;
 
 fn are_events_paused(tick_count: u64) -> bool {
+    if events_paused_by_alarm() {
+        return true;
+    }
     let context = CoordinatorContext::get_instance();
     let stored_info = context.get_coord_stored_info();
     stored_info.is_events_paused(tick_count)
@@ -26,12 +39,13 @@ fn set_event_pause(tick_count: u64, pause_ticks: u64) {
     stored_info.set_pause_events_till(tick_count + pause_ticks);
 }
 
-const EVENT_FREQUENCIES: [EventFrequency; 5] = [
+const EVENT_FREQUENCIES: [EventFrequency; 6] = [
     EventFrequency::Normal,
     EventFrequency::Rare,
     EventFrequency::Extinction,
     EventFrequency::Topography,
     EventFrequency::ColonyRules,
+    EventFrequency::Biomes,
 ];
 
 fn log_tick(tick_count: u64, tick_monitor: &Mutex<TickMonitor>) {
@@ -42,34 +56,72 @@ fn log_tick(tick_count: u64, tick_monitor: &Mutex<TickMonitor>) {
 
 async fn handle_new_topography_event(colony_width: i32, colony_height: i32) {
     log!("Generating new topography for colony {}x{}", colony_width, colony_height);
+    match regenerate_colony_topography().await {
+        Ok(_) => log!("New topography generation completed"),
+        Err(e) => shared::log_error!("New topography generation failed: {:?}", e),
+    }
+}
+
+/// Validation result for rules and biome change events, None for every other event
+/// The ranges of the event schema, then for biomes the overrides on top of the current rules
+fn event_validation(event: &shared::colony_events::ColonyEvent) -> Result<(), String> {
+    validate_colony_event(event)?;
+    match event {
+        shared::colony_events::ColonyEvent::ChangeBiomes(biome_change) => shared::colony_model::validate_biomes(
+            &biome_change.biomes, &CoordinatorContext::get_instance().get_colony_life_rules()),
+        _ => Ok(()),
+    }
+}
+
+/// Description of an event, stamped with the colony tick and the tick it was scheduled for if it was generated
+fn describe_event(event: &shared::colony_events::ColonyEvent, colony_tick: u64, intended_tick: Option<u64>) -> ColonyEventDescription {
+    let mut event_description = create_colony_event_description(event, colony_tick);
+    event_description.intended_tick = intended_tick;
+    event_description
+}
+
+/// Broadcasts an event to every backend and records it, CreateCreature events aside; events
+/// with a region are also kept for the GUI's event markers.
+/// Events are validated first, and rules and biome changes stored; Err when validation drops the event.
+/// NewTopography is not applied here, the ticker regenerates the topography itself.
+pub fn apply_colony_event(event: shared::colony_events::ColonyEvent, colony_tick: u64, intended_tick: Option<u64>) -> Result<ColonyEventDescription, String> {
+    event_validation(&event)?;
+    // Clone event for logging (before broadcasting consumes it)
+    let event_clone = event.clone();
     
-    // Create topography info similar to init_colony.rs
-    const SHARD_WIDTH: i32 = 250;
-    const SHARD_HEIGHT: i32 = 250;
+    if let shared::colony_events::ColonyEvent::ChangeColonyRules(rule_change) = &event {
+        CoordinatorContext::get_instance().update_colony_rules(rule_change.new_rules);
+    }
+    if let shared::colony_events::ColonyEvent::ChangeBiomes(biome_change) = &event {
+        CoordinatorContext::get_instance().set_biomes(biome_change.biomes.clone());
+    }
     
-    let topography_info = GlobalTopographyInfo {
-        total_width: colony_width as usize,
-        total_height: colony_height as usize,
-        shard_width: SHARD_WIDTH as usize,
-        shard_height: SHARD_HEIGHT as usize,
-
-        base_elevation: 5,
-        river_elevation_range: 45, 
-        river_influence_distance: 175.0,
-        river_count_range: (10, 20),
-        river_segments_range: (30, 45),
-        river_step_length_range: (20.0, 30.0),
-        river_direction_change: 0.6,
-        smoothing_iterations: 4,
-    };
+    let delivery = backend_client::broadcast_event_to_backends(event);
+    if delivery.had_no_effect() {
+        log!("[{}] Event {} affected no cells", colony_tick, delivery.event_id);
+    }
     
-    let topography = GlobalTopography::new(topography_info);
-    topography.generate_topography().await;
+    let mut event_description = describe_event(&event_clone, colony_tick, intended_tick);
+    event_description.description = format!("{} ({})", event_description.description, delivery.effect_summary());
+    event_description.no_effect = delivery.had_no_effect();
+    event_description.delivery = Some(delivery);
     
-    log!("New topography generation completed");
+    if event_description.region.is_some() {
+        CoordinatorContext::get_instance().add_region_event(event_description.clone());
+    }
+    // Store and log event to S3 after event is applied (excluding CreateCreature events)
+    if !matches!(event_clone, shared::colony_events::ColonyEvent::CreateCreature(_, _)) {
+        CoordinatorContext::get_instance().add_colony_event(event_description.clone());
+        
+        let rules = CoordinatorContext::get_instance().get_colony_life_rules();
+        if let Err(e) = event_logging::write_event_json(&event_clone, &event_description, rules) {
+            shared::log_error!("Failed to write event JSON: {}", e);
+        }
+    }
+    Ok(event_description)
 }
 
-fn handle_colony_events(tick_count: u64, next_event_ticks: &mut HashMap<EventFrequency, u64>, colony_width: i32, colony_height: i32) {
+fn handle_colony_events(tick_count: u64, next_event_ticks: &mut HashMap<EventFrequency, u64>, tick_clock: &mut EventTickClock, colony_width: i32, colony_height: i32) {
     if are_events_paused(tick_count) {
         return; 
     }
@@ -80,62 +132,49 @@ fn handle_colony_events(tick_count: u64, next_event_ticks: &mut HashMap<EventFre
         
         if let Some(&next_tick) = next_event_ticks.get(frequency) {
             if tick_count >= next_tick {
+                // The latest tick sweep rather than a fresh fan-out to every backend
+                let colony_tick = tick_clock.stamp(tick_count, latest_max_tick());
                 let event = randomize_event_by_frequency(*frequency, colony_width, colony_height, &mut event_rng);
-                log_event(&event, tick_count);
+                let screened = CoordinatorContext::get_instance().population_guard().screen(event, &mut event_rng);
+                let event = match screened {
+                    ScreenedEvent::Allowed(event) => Some(event),
+                    ScreenedEvent::Suppressed { suppressed, reason, replacement } => {
+                        let instead = match &replacement {
+                            Some(replacement) => format!(", {} instead", event_type_name(replacement)),
+                            None => String::new(),
+                        };
+                        record_lifecycle_event(EVENT_SUPPRESSED_EVENT, colony_tick,
+                                               format!("Held back {}: {}{}", event_type_name(&suppressed), reason, instead));
+                        replacement
+                    }
+                };
                 
-                // Store event in CoordinatorContext (excluding common events)
-                if !matches!(event, 
-                    shared::colony_events::ColonyEvent::CreateCreature(_, _)
-                ) {
-                    let event_description = create_colony_event_description(&event, tick_count);
-                    CoordinatorContext::get_instance().add_colony_event(event_description);
-                }
+                let Some(event) = event else {
+                    next_event_ticks.insert(*frequency, tick_count + get_next_event_tick_by_frequency(*frequency, &mut event_rng));
+                    continue;
+                };
+                log_event(&event, colony_tick);
                 
                 // Special handling for NewTopography event
                 if matches!(event, shared::colony_events::ColonyEvent::NewTopography()) {
+                    // Store event in CoordinatorContext; it is not broadcast so has no delivery status
+                    let event_description = describe_event(&event, colony_tick, Some(next_tick));
+                    CoordinatorContext::get_instance().add_colony_event(event_description.clone());
+                    
                     // Run async function in a blocking context
                     let rt = tokio::runtime::Runtime::new().expect("Failed to create runtime");
                     rt.block_on(handle_new_topography_event(colony_width, colony_height));
                     
                     // Log event to S3 after topography is generated
-                    let event_description = create_colony_event_description(&event, tick_count);
                     let rules = CoordinatorContext::get_instance().get_colony_life_rules();
-                    if let Err(e) = event_logging::write_event_json(
-                        &event,
-                        tick_count,
-                        &event_description.event_type,
-                        &event_description.description,
-                        rules,
-                    ) {
+                    if let Err(e) = event_logging::write_event_json(&event, &event_description, rules) {
                         shared::log_error!("Failed to write event JSON: {}", e);
                     }
                     
                     set_event_pause(tick_count, TOPOGRAPHY_EVENT_PAUSE_TICKS);
                     next_event_ticks.clear();
-                } else {
-                    // Clone event for logging (before broadcasting consumes it)
-                    let event_clone = event.clone();
-                    
-                    if let shared::colony_events::ColonyEvent::ChangeColonyRules(rule_change) = &event {
-                        CoordinatorContext::get_instance().update_colony_rules(rule_change.new_rules);
-                    }
-                    
-                    backend_client::broadcast_event_to_backends(event);
-                    
-                    // Log event to S3 after event is applied (excluding CreateCreature events)
-                    if !matches!(event_clone, shared::colony_events::ColonyEvent::CreateCreature(_, _)) {
-                        let event_description = create_colony_event_description(&event_clone, tick_count);
-                        let rules = CoordinatorContext::get_instance().get_colony_life_rules();
-                        if let Err(e) = event_logging::write_event_json(
-                            &event_clone,
-                            tick_count,
-                            &event_description.event_type,
-                            &event_description.description,
-                            rules,
-                        ) {
-                            shared::log_error!("Failed to write event JSON: {}", e);
-                        }
-                    }
+                } else if let Err(e) = apply_colony_event(event, colony_tick, Some(next_tick)) {
+                    shared::log_error!("Dropping rules change event at tick {}: {}", colony_tick, e);
                 }
                 
                 next_event_ticks.insert(*frequency, tick_count + get_next_event_tick_by_frequency(*frequency, &mut event_rng));
@@ -150,22 +189,63 @@ pub fn start_coordinator_ticker() {
     std::thread::spawn(move || {
         let tick_monitor = Mutex::new(TickMonitor::new());
         let mut next_event_ticks: HashMap<EventFrequency, u64> = HashMap::new();
+        let mut tick_clock = EventTickClock::default();
         let mut colony_dimensions: Option<(i32, i32)> = None;
+        // Shard the tick came from last time, so a fallback is logged once rather than every loop
+        let mut tick_shard: Option<Shard> = None;
+        let mut last_rules_check = Instant::now();
         
         loop {
-            let shard = Shard { x: 0, y: 0, width: 250, height: 250 };
-            
-            if let Some(tick_count) = backend_client::call_backend_for_tick_count(shard) {                
-                log_tick(tick_count, &tick_monitor);
+            match backend_client::get_topology_colony_tick() {
+                Ok(colony_tick) => {
+                    if tick_shard != Some(colony_tick.shard) {
+                        if let Some(note) = colony_tick.fallback_note() {
+                            log!("Coordinator ticker: {}", note);
+                        }
+                        tick_shard = Some(colony_tick.shard);
+                    }
+                    let tick_count = colony_tick.tick;
+                    log_tick(tick_count, &tick_monitor);
                 
-                // Get colony dimensions once and cache them
-                if colony_dimensions.is_none() {
-                    colony_dimensions = backend_client::call_backend_get_colony_info();
-                }
+                    // Stored dimensions follow colony expansion; otherwise ask a backend once and cache
+                    let stored_dimensions = {
+                        let stored_info = CoordinatorContext::get_instance().get_coord_stored_info();
+                        stored_info.colony_width.zip(stored_info.colony_height)
+                    };
+                    if stored_dimensions.is_some() {
+                        colony_dimensions = stored_dimensions;
+                    } else if colony_dimensions.is_none() {
+                        colony_dimensions = backend_client::call_backend_get_colony_info().ok();
+                    }
                 
-                if let Some((width, height)) = colony_dimensions {
-                    handle_colony_events(tick_count, &mut next_event_ticks, width, height);
+                    // A time-boxed run stops once the slowest shard of the latest tick sweep reached its target
+                    let reached = reached_target(&CoordinatorContext::get_instance().get_coord_stored_info(), latest_min_tick());
+                    if let Some(target_tick) = reached {
+                        let rt = tokio::runtime::Runtime::new().expect("Failed to create runtime");
+                        rt.block_on(complete_run(target_tick));
+                    }
+                
+                    // The end of the warm-up is recorded once, with a baseline stats capture
+                    let warmup = reached_warmup(&CoordinatorContext::get_instance().get_coord_stored_info(), latest_min_tick());
+                    if let Some(warmup_ticks) = warmup {
+                        let rt = tokio::runtime::Runtime::new().expect("Failed to create runtime");
+                        rt.block_on(end_warmup(warmup_ticks, latest_min_tick().unwrap_or(warmup_ticks)));
+                    }
+                
+                    // No random events while warming up; their schedule starts once it is over
+                    if let Some((width, height)) = colony_dimensions.filter(|_| !is_run_completed() && !is_colony_warming_up()) {
+                        handle_colony_events(tick_count, &mut next_event_ticks, &mut tick_clock, width, height);
+                    }
+                
+                    // Between event broadcasts, so a rules change is never caught half delivered
+                    if colony_dimensions.is_some() && last_rules_check.elapsed() >= rules_drift_check_interval() {
+                        check_rules_drift(tick_count);
+                        last_rules_check = Instant::now();
+                    }
                 }
+                // Connection failures are expected while backends start; a backend that answers but refuses is worth a line
+                Err(e @ (CoordinatorError::BackendRejected { .. } | CoordinatorError::UnexpectedResponse { .. })) => log!("{}", e),
+                Err(_) => {}
             }
             
             std::thread::sleep(std::time::Duration::from_secs(1));
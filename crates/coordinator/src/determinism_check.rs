@@ -0,0 +1,148 @@
+use futures_util::future::join_all;
+use shared::be_api::{BackendRequest, BackendResponse, GetStateHashesRequest, GetStateHashesResponse, ShardStateHashes, TickStateHash};
+use shared::cluster_topology::{ClusterTopology, HostInfo};
+use shared::coordinator_api::{DeterminismCheckResponse, ShardDeterminism};
+use shared::output_paths::OutputPaths;
+use shared::state_hash::compare_state_hashes;
+use shared::{log, log_error};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
+use crate::coordinator_context::CoordinatorContext;
+use crate::coordinator_error::CoordinatorError;
+use crate::init_colony::{connect_backend, request_backend};
+
+const BACKEND_QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+const STATE_HASHES_FILE: &str = "state_hashes.json";
+
+#[derive(Debug, PartialEq)]
+pub enum DeterminismCheckError {
+    /// instance_b is required, instance_a defaults to the running colony
+    MissingInstance,
+    /// No colony is running, so instance_a has no default and there is nothing to collect
+    NoRunningColony,
+    /// Not a colony instance id; ids name directories, so only letters, digits, '-' and '_' pass
+    InvalidInstance(String),
+    /// Neither collected nor saved hashes exist for the instance
+    NoHistory(String),
+}
+
+fn is_valid_instance_id(instance_id: &str) -> bool {
+    !instance_id.is_empty() && instance_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Saved hashes of an instance, next to its captures and stats
+pub fn state_hashes_path(instance_id: &str) -> PathBuf {
+    OutputPaths::get_instance().instance_dir(instance_id).join(STATE_HASHES_FILE)
+}
+
+pub fn load_state_hashes(instance_id: &str) -> Option<Vec<ShardStateHashes>> {
+    let json = std::fs::read_to_string(state_hashes_path(instance_id)).ok()?;
+    serde_json::from_str(&json)
+        .map_err(|e| log_error!("Ignoring unreadable state hashes of {}: {}", instance_id, e))
+        .ok()
+}
+
+fn save_state_hashes(instance_id: &str, history: &[ShardStateHashes]) -> Result<(), String> {
+    let path = state_hashes_path(instance_id);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let json = serde_json::to_string(history).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Folds freshly collected hashes into a saved history. Backends only keep their last ticks,
+/// so every collection extends the history; a tick seen twice keeps the newer hash.
+pub fn merge_state_hashes(history: &mut Vec<ShardStateHashes>, fresh: Vec<ShardStateHashes>) {
+    for shard_hashes in fresh {
+        match history.iter_mut().find(|saved| saved.shard == shard_hashes.shard) {
+            Some(saved) => {
+                let mut by_tick: BTreeMap<u64, u64> = saved.hashes.iter().map(|entry| (entry.tick, entry.hash)).collect();
+                by_tick.extend(shard_hashes.hashes.iter().map(|entry| (entry.tick, entry.hash)));
+                saved.hashes = by_tick.into_iter().map(|(tick, hash)| TickStateHash { tick, hash }).collect();
+            }
+            None => history.push(shard_hashes),
+        }
+    }
+    history.sort_by_key(|shard_hashes| (shard_hashes.shard.y, shard_hashes.shard.x));
+}
+
+/// Compares every shard that either history holds
+pub fn compare_histories(a: &[ShardStateHashes], b: &[ShardStateHashes], from_tick: u64) -> Vec<ShardDeterminism> {
+    let mut shards: Vec<_> = a.iter().map(|shard_hashes| shard_hashes.shard).collect();
+    shards.extend(b.iter().map(|shard_hashes| shard_hashes.shard).filter(|shard| !a.iter().any(|entry| entry.shard == *shard)));
+    shards.into_iter().map(|shard| {
+        let hashes_of = |history: &[ShardStateHashes]| history.iter()
+            .find(|entry| entry.shard == shard)
+            .map(|entry| entry.hashes.clone())
+            .unwrap_or_default();
+        let comparison = compare_state_hashes(&hashes_of(a), &hashes_of(b), from_tick);
+        ShardDeterminism {
+            shard_id: shard.to_id(),
+            compared_ticks: comparison.compared_ticks,
+            first_divergent_tick: comparison.first_divergent_tick,
+        }
+    }).collect()
+}
+
+async fn fetch_state_hashes(backend_host: &HostInfo) -> Result<Vec<ShardStateHashes>, CoordinatorError> {
+    let mut stream = connect_backend(backend_host).await?;
+    let request = BackendRequest::GetStateHashes(GetStateHashesRequest);
+    let rejected = |response: &str| CoordinatorError::BackendRejected { host: backend_host.to_address(), shard: None, response: response.to_string() };
+    match request_backend(&mut stream, backend_host, "GetStateHashes", &request).await? {
+        BackendResponse::GetStateHashes(GetStateHashesResponse::Ok(hashes)) => Ok(hashes),
+        BackendResponse::GetStateHashes(GetStateHashesResponse::AuditDisabled) => Err(rejected("determinism audit is off")),
+        BackendResponse::GetStateHashes(GetStateHashesResponse::ColonyNotInitialized) => Err(rejected("colony not initialized")),
+        _ => Err(CoordinatorError::UnexpectedResponse { host: backend_host.to_address(), op: "GetStateHashes" }),
+    }
+}
+
+/// Pulls the recent hashes of every backend into the saved history of the running instance;
+/// returns the backends that could not be queried
+async fn collect_state_hashes(instance_id: &str) -> Vec<String> {
+    let Some(topology) = ClusterTopology::get_instance() else {
+        return vec!["topology not initialized".to_string()];
+    };
+    let backends = topology.get_all_backend_hosts();
+    let results = join_all(backends.iter().map(|backend| async move {
+        tokio::time::timeout(BACKEND_QUERY_TIMEOUT, fetch_state_hashes(backend)).await
+            .unwrap_or_else(|_| Err(CoordinatorError::Timeout { host: backend.to_address(), op: "GetStateHashes" }))
+    })).await;
+
+    let mut history = load_state_hashes(instance_id).unwrap_or_default();
+    let mut problems = Vec::new();
+    for result in results {
+        match result {
+            Ok(hashes) => merge_state_hashes(&mut history, hashes),
+            Err(e) => problems.push(e.to_string()),
+        }
+    }
+    if let Err(e) = save_state_hashes(instance_id, &history) {
+        log_error!("{}", e);
+    }
+    problems
+}
+
+/// Compares the hashes of instance_a (the running colony when None) with those saved for instance_b.
+/// The running colony's backends are asked for their latest hashes first.
+pub async fn determinism_check(instance_a: Option<String>, instance_b: Option<String>, from_tick: u64) -> Result<DeterminismCheckResponse, DeterminismCheckError> {
+    let running = CoordinatorContext::get_instance().get_coord_stored_info().colony_instance_id.clone();
+    let instance_b = instance_b.ok_or(DeterminismCheckError::MissingInstance)?;
+    let instance_a = instance_a.or_else(|| running.clone()).ok_or(DeterminismCheckError::NoRunningColony)?;
+    if let Some(invalid) = [&instance_a, &instance_b].into_iter().find(|id| !is_valid_instance_id(id)) {
+        return Err(DeterminismCheckError::InvalidInstance(invalid.clone()));
+    }
+
+    let backend_problems = match &running {
+        Some(running) if *running == instance_a || *running == instance_b => collect_state_hashes(running).await,
+        _ => Vec::new(),
+    };
+    let history_a = load_state_hashes(&instance_a).ok_or_else(|| DeterminismCheckError::NoHistory(instance_a.clone()))?;
+    let history_b = load_state_hashes(&instance_b).ok_or_else(|| DeterminismCheckError::NoHistory(instance_b.clone()))?;
+
+    let shards = compare_histories(&history_a, &history_b, from_tick);
+    let diverged = shards.iter().any(|shard| shard.first_divergent_tick.is_some());
+    log!("Determinism check {} vs {}: {}", instance_a, instance_b, if diverged { "diverged" } else { "identical" });
+    Ok(DeterminismCheckResponse { instance_a, instance_b, from_tick, diverged, shards, backend_problems })
+}
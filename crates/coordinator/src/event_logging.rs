@@ -1,11 +1,12 @@
 use serde::Serialize;
-use std::path::Path;
 use shared::log;
 use shared::colony_events::ColonyEvent;
 use shared::be_api::ColonyLifeRules;
+use shared::coordinator_api::{ColonyEventDescription, ColonyRunConfig, EventDelivery};
+use shared::output_paths::OutputPaths;
 use crate::coordinator_context::CoordinatorContext;
+use crate::stats_comparison::RUN_CONFIG_FILE;
 
-const BASE_BUCKET_DIR: &str = "output/s3/distributed-colony";
 
 #[derive(Serialize)]
 pub struct EventJson {
@@ -13,6 +14,8 @@ pub struct EventJson {
     pub colony_instance_id: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tick: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub intended_tick: Option<u64>,
     #[serde(rename = "event_type")]
     pub event_type: String,
     #[serde(rename = "event_description")]
@@ -20,6 +23,8 @@ pub struct EventJson {
     #[serde(rename = "event_data", skip_serializing_if = "Option::is_none")]
     pub event_data: Option<ColonyEvent>,
     pub rules: ColonyLifeRules,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delivery: Option<EventDelivery>,
 }
 
 #[derive(Serialize)]
@@ -40,12 +45,10 @@ fn format_tick_filename(tick: u64) -> String {
     format!("{:07}", tick)
 }
 
-/// Write event JSON to disk
+/// Write event JSON to disk, including per-backend delivery outcome for broadcast events
 pub fn write_event_json(
     event: &ColonyEvent,
-    tick: u64,
-    event_type: &str,
-    event_description: &str,
+    description: &ColonyEventDescription,
     rules: ColonyLifeRules,
 ) -> Result<(), String> {
     let context = CoordinatorContext::get_instance();
@@ -59,15 +62,17 @@ pub fn write_event_json(
         }
     };
     
-    let tick_str = format_tick_filename(tick);
+    let tick_str = format_tick_filename(description.tick);
     
     let event_json = EventJson {
         colony_instance_id: instance_id.to_string(),
-        tick: Some(tick),
-        event_type: event_type.to_string(),
-        event_description: event_description.to_string(),
+        tick: Some(description.tick),
+        intended_tick: description.intended_tick,
+        event_type: description.event_type.clone(),
+        event_description: description.description.clone(),
         event_data: Some(event.clone()),
         rules,
+        delivery: description.delivery.clone(),
     };
     
     save_event_to_disk(&event_json, instance_id, &tick_str)
@@ -100,8 +105,7 @@ pub fn write_colony_created_event_json(rules: ColonyLifeRules) -> Result<(), Str
 }
 
 fn save_event_to_disk(event_json: &EventJson, instance_id: &str, tick_str: &str) -> Result<(), String> {
-    // Build directory path: output/s3/distributed-colony/{id}/events
-    let dir_path = Path::new(BASE_BUCKET_DIR).join(instance_id).join("events");
+    let dir_path = OutputPaths::get_instance().events_dir(instance_id);
     if let Err(e) = std::fs::create_dir_all(&dir_path) {
         return Err(format!("Failed to create directory {}: {}", dir_path.display(), e));
     }
@@ -118,14 +122,13 @@ fn save_event_to_disk(event_json: &EventJson, instance_id: &str, tick_str: &str)
     std::fs::write(&file_path, json)
         .map_err(|e| format!("Failed to write event file to {}: {}", file_path.display(), e))?;
     
-    log!("Successfully saved event to: {}/{}/events/{}", BASE_BUCKET_DIR, instance_id, filename);
+    log!("Successfully saved event to: {}", file_path.display());
     
     Ok(())
 }
 
 fn save_colony_created_event_to_disk(event_json: &ColonyCreatedEventJson, instance_id: &str, tick_str: &str) -> Result<(), String> {
-    // Build directory path: output/s3/distributed-colony/{id}/events
-    let dir_path = Path::new(BASE_BUCKET_DIR).join(instance_id).join("events");
+    let dir_path = OutputPaths::get_instance().events_dir(instance_id);
     if let Err(e) = std::fs::create_dir_all(&dir_path) {
         return Err(format!("Failed to create directory {}: {}", dir_path.display(), e));
     }
@@ -142,8 +145,22 @@ fn save_colony_created_event_to_disk(event_json: &ColonyCreatedEventJson, instan
     std::fs::write(&file_path, json)
         .map_err(|e| format!("Failed to write colony created event file to {}: {}", file_path.display(), e))?;
     
-    log!("Successfully saved colony creation event to: {}/{}/events/{}", BASE_BUCKET_DIR, instance_id, filename);
+    log!("Successfully saved colony creation event to: {}", file_path.display());
     
     Ok(())
 }
 
+
+/// Writes {instance_dir}/run_config.json, read back by compare-stats
+pub fn write_run_config_json(config: &ColonyRunConfig) -> Result<(), String> {
+    let instance_id = config.colony_instance_id.as_deref()
+        .ok_or_else(|| "Colony instance ID is not set".to_string())?;
+    let dir_path = OutputPaths::get_instance().instance_dir(instance_id);
+    std::fs::create_dir_all(&dir_path)
+        .map_err(|e| format!("Failed to create directory {}: {}", dir_path.display(), e))?;
+    let file_path = dir_path.join(RUN_CONFIG_FILE);
+    let json = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("Failed to serialize run configuration to JSON: {}", e))?;
+    std::fs::write(&file_path, json)
+        .map_err(|e| format!("Failed to write run configuration to {}: {}", file_path.display(), e))
+}
@@ -1,18 +1,35 @@
 // Global topography module for the coordinator
 // This module will handle global topography-related functionality
 
-use shared::be_api::{Shard, BackendRequest, BackendResponse, InitShardTopographyRequest, InitShardTopographyResponse};
+use shared::be_api::{pack_sanctuary_mask, Shard, BackendRequest, BackendResponse, InitShardTopographyRequest, InitShardTopographyResponse};
+use shared::colony_model::{ExtraFoodPattern, GlobalPos, LocalPos, StripeOrientation};
 use shared::{log, log_error};
-use shared::utils::new_random_generator;
+use shared::utils::{new_random_generator, new_seeded_random_generator, StableHasher};
 use shared::cluster_topology::ClusterTopology;
-use shared::backend_communication::{send_request_async, receive_response_async};
-use tokio::net::TcpStream;
+use shared::backend_communication::{connect_with_handshake_async, send_request_async, receive_response_async};
+use std::borrow::Cow;
+use crate::coordinator_context::CoordinatorContext;
+use crate::init_colony::colony_topography_info;
 
 #[derive(Debug)]
 struct RiverPath {
     points: Vec<(f32, f32)>,
 }
 
+/// A circle of cells exempt from random death
+#[derive(Debug)]
+struct Sanctuary {
+    x: i64,
+    y: i64,
+    radius: i64,
+}
+
+impl Sanctuary {
+    fn contains(&self, pos: GlobalPos) -> bool {
+        (pos.x as i64 - self.x).pow(2) + (pos.y as i64 - self.y).pow(2) <= self.radius * self.radius
+    }
+}
+
 pub struct GlobalTopographyInfo {
     pub total_width: usize,
     pub total_height: usize,
@@ -27,125 +44,239 @@ pub struct GlobalTopographyInfo {
     pub river_step_length_range: (f32, f32), // (min, max) step length for river segments
     pub river_direction_change: f32, // Maximum direction change per segment
     pub smoothing_iterations: usize,
+    /// Circular sanctuaries placed anywhere in the colony; 0 sends no sanctuary mask at all
+    pub sanctuary_count: usize,
+    pub sanctuary_radius_range: (usize, usize), // (min, max) radius in cells
+    /// Fixed seed for reproducible terrain; None draws a fresh one
+    pub seed: Option<u64>,
+    /// Laid over the colony instead of the rivers; None keeps the rivers
+    pub extra_food_pattern: Option<ExtraFoodPattern>,
+}
+
+pub fn mark_awaiting_topography(shard: &Shard) {
+    CoordinatorContext::get_instance().awaiting_topography().insert(shard.to_id());
+}
+
+pub fn is_awaiting_topography(shard: &Shard) -> bool {
+    CoordinatorContext::get_instance().awaiting_topography().contains(&shard.to_id())
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PushTopographyError {
+    TopologyNotInitialized,
+    InvalidShardId(String),
+    ShardNotFound(String),
+    ShardNotInitialized,
+    InvalidTopographyData,
+    Failed(String),
+}
+
+/// Sends one shard's terrain to the backend hosting it. A shard that is already ticking
+/// swaps it in between two ticks, see InitShardTopographyRequest.
+async fn send_shard_topography(shard: Shard, topography_data: Vec<u8>) -> Result<(), PushTopographyError> {
+    let topology = ClusterTopology::get_instance().ok_or(PushTopographyError::TopologyNotInitialized)?;
+    let host_info = topology.get_host_for_shard(&shard)
+        .ok_or_else(|| PushTopographyError::ShardNotFound(shard.to_id()))?;
+    let request = BackendRequest::InitShardTopography(InitShardTopographyRequest {
+        shard,
+        topography_data,
+    });
+
+    let mut stream = connect_with_handshake_async(&host_info.to_address()).await
+        .map_err(|e| PushTopographyError::Failed(format!("Connection to {} failed: {}", host_info.to_address(), e)))?;
+    send_request_async(&mut stream, &request).await
+        .map_err(|e| PushTopographyError::Failed(format!("Send failed: {}", e)))?;
+    match receive_response_async::<BackendResponse>(&mut stream).await {
+        Ok(BackendResponse::InitShardTopography(InitShardTopographyResponse::Ok)) => {
+            CoordinatorContext::get_instance().awaiting_topography().remove(&shard.to_id());
+            Ok(())
+        }
+        Ok(BackendResponse::InitShardTopography(InitShardTopographyResponse::ShardNotInitialized)) => Err(PushTopographyError::ShardNotInitialized),
+        Ok(BackendResponse::InitShardTopography(InitShardTopographyResponse::InvalidTopographyData)) => Err(PushTopographyError::InvalidTopographyData),
+        Ok(_) => Err(PushTopographyError::Failed("Unexpected response type".to_string())),
+        Err(e) => Err(PushTopographyError::Failed(format!("Receive failed: {}", e))),
+    }
+}
+
+/// Replaces the terrain of one running shard with a payload laid out like InitShardTopographyRequest
+pub async fn push_shard_topography(shard_id: &str, topography_data: Vec<u8>) -> Result<(), PushTopographyError> {
+    let shard = Shard::from_id(shard_id).map_err(PushTopographyError::InvalidShardId)?;
+    send_shard_topography(shard, topography_data).await.inspect_err(|e| {
+        log_error!("Failed to push topography to shard {}: {:?}", shard_id, e);
+    })?;
+    log!("Topography pushed to shard {}", shard_id);
+    Ok(())
+}
+
+/// Draws fresh terrain for the whole colony and pushes it to every shard; running shards
+/// keep their creatures, except those on new water. A colony started with an extra food
+/// pattern keeps it. Returns the hash of the global image.
+pub async fn regenerate_colony_topography() -> Result<String, PushTopographyError> {
+    let topology = ClusterTopology::get_instance().ok_or(PushTopographyError::TopologyNotInitialized)?;
+    let mut topography_info = colony_topography_info(&topology);
+    topography_info.extra_food_pattern = CoordinatorContext::get_instance().get_coord_stored_info().run_config.as_ref()
+        .and_then(|config| config.extra_food_pattern);
+    let topography = GlobalTopography::new(topography_info);
+    Ok(topography.generate_topography().await)
+}
+
+/// Recorded in the run configuration as the origin of the terrain
+pub const TOPOGRAPHY_SOURCE: &str = "procedural-rivers";
+/// Recorded instead of TOPOGRAPHY_SOURCE for a colony started with an extra_food_pattern
+pub const PATTERN_TOPOGRAPHY_SOURCE: &str = "extra-food-pattern";
+
+/// Colonies above this many pixels are generated one row of shards at a time
+/// instead of holding the whole elevation field in memory
+pub const STREAMING_THRESHOLD_PIXELS: usize = 16_000_000;
+
+/// The colony-wide elevation field, sliced into per-shard payloads. Every shard is cut
+/// from the same field, so terrain is continuous across shard borders.
+pub struct TopographyField<'a> {
+    topography: &'a GlobalTopography,
+    rivers: Vec<RiverPath>,
+    sanctuaries: Vec<Sanctuary>,
+    // Centers of ExtraFoodPattern::Oases, empty for any other terrain
+    oases: Vec<(f32, f32)>,
+    // None when streaming, row blocks are then generated on demand
+    full_field: Option<Vec<u8>>,
 }
 
 pub struct GlobalTopography {
     info: GlobalTopographyInfo,
+    /// Colony size the field covers. Rivers, sanctuaries and patterns are drawn for info's
+    /// total size, which is smaller after an expansion.
+    width: usize,
+    height: usize,
 }
 
 impl GlobalTopography {
     pub fn new(info: GlobalTopographyInfo) -> Self {
-        Self { info }
+        Self { width: info.total_width, height: info.total_height, info }
     }
 
-    async fn send_topography_to_local_shard(&self, shard: Shard, topography_data: Vec<u8>) {
-        let request = BackendRequest::InitShardTopography(InitShardTopographyRequest {
-            shard,
-            topography_data,
-        });
-
-        let topology = match ClusterTopology::get_instance() {
-            Some(t) => t,
-            None => {
-                log_error!("Topology not initialized");
-                return;
-            }
-        };
-        let host_info = match topology.get_host_for_shard(&shard) {
-            Some(host) => host,
-            None => {
-                log_error!("Shard not found in cluster topology: ({},{},{},{})", 
-                    shard.x, shard.y, shard.width, shard.height);
-                return;
-            }
-        };
-        
-        if let Ok(mut stream) = TcpStream::connect(host_info.to_address()).await {
-            if let Err(e) = send_request_async(&mut stream, &request).await {
-                log_error!("Failed to send topography to shard ({},{},{},{}): {}", 
-                    shard.x, shard.y, shard.width, shard.height, e);
-                return;
-            }
+    /// The terrain drawn for info's colony, laid over the colony it grew into by expansions.
+    /// The original region gets the terrain it had, the new region continues it.
+    pub fn expanded(info: GlobalTopographyInfo, width: usize, height: usize) -> Self {
+        Self { width, height, info }
+    }
 
-            match receive_response_async::<BackendResponse>(&mut stream).await {
-                Ok(response) => {
-                    match response {
-                        BackendResponse::InitShardTopography(InitShardTopographyResponse::Ok) => {
-                            log!("Topography sent to shard ({},{},{},{})", 
-                                shard.x, shard.y, shard.width, shard.height);
-                        },
-                        BackendResponse::InitShardTopography(InitShardTopographyResponse::ShardNotInitialized) => {
-                            log_error!("Shard not initialized for topography: ({},{},{},{})", 
-                                shard.x, shard.y, shard.width, shard.height);
-                        },
-                        BackendResponse::InitShardTopography(InitShardTopographyResponse::InvalidTopographyData) => {
-                            log_error!("Invalid topography data for shard: ({},{},{},{})", 
-                                shard.x, shard.y, shard.width, shard.height);
-                        },
-                        _ => {
-                            log_error!("Unexpected response for topography request");
-                        }
-                    }
-                },
-                Err(e) => {
-                    log_error!("Failed to receive response for topography request: {}", e);
-                }
-            }
-        } else {
-            log_error!("Failed to connect to backend for topography request");
+    pub(crate) async fn send_topography_to_local_shard(&self, shard: Shard, topography_data: Vec<u8>) {
+        match send_shard_topography(shard, topography_data).await {
+            Ok(()) => log!("Topography sent to shard ({},{},{},{})",
+                shard.x, shard.y, shard.width, shard.height),
+            Err(e) => log_error!("Failed to send topography to shard ({},{},{},{}): {:?}",
+                shard.x, shard.y, shard.width, shard.height, e),
         }
     }
 
+    /// Generates and distributes terrain to every shard; returns the hash of the global image
+    pub async fn generate_topography(&self) -> String {
+        self.generate_topography_for(None).await
+    }
 
-    pub async fn generate_topography(&self) {
-        log!("Generating global topography for colony {}x{}", self.info.total_width, self.info.total_height);
-        
-        // Create a full colony image with a global gradient
-        let global_image = self.create_global_topography_image();
+    /// Generates topography for the whole colony but only sends it to the given shards,
+    /// used after an expansion so existing shards keep their terrain
+    pub async fn generate_topography_for_shards(&self, shards: &[Shard]) {
+        let _ = self.generate_topography_for(Some(shards)).await;
+    }
+
+    async fn generate_topography_for(&self, only_shards: Option<&[Shard]>) -> String {
+        log!("Generating global topography for colony {}x{}", self.width, self.height);
         
-        // Calculate shard grid dimensions
-        let horizontal_count = self.info.total_width / self.info.shard_width;
-        let vertical_count = self.info.total_height / self.info.shard_height;
+        let field = self.field();
+        let horizontal_count = self.width / self.info.shard_width;
+        let vertical_count = field.block_count();
         
-        log!("Distributing topography to {} shards ({}x{})", 
-            horizontal_count * vertical_count, horizontal_count, vertical_count);
-
-        // Send topography data to each shard
-        for y in 0..vertical_count {
-            for x in 0..horizontal_count {
-                let shard = Shard {
-                    x: (x * self.info.shard_width) as i32,
-                    y: (y * self.info.shard_height) as i32,
-                    width: self.info.shard_width as i32,
-                    height: self.info.shard_height as i32,
-                };
-
-                // Extract shard-specific data from the global image
-                let shard_data = self.extract_shard_data(&global_image, x, y);
-                
+        log!("Distributing topography to {} shards ({}x{}){}", 
+            horizontal_count * vertical_count, horizontal_count, vertical_count,
+            if field.is_streamed() { ", streaming row blocks" } else { "" });
+
+        // Hashed block by block, equal to hashing the whole field at once
+        let mut hasher = StableHasher::new();
+        for block_y in 0..vertical_count {
+            let block = field.row_block(block_y);
+            hasher.update(&block);
+
+            for (shard, shard_data) in field.shard_payloads(block_y, &block) {
+                if only_shards.is_some_and(|shards| !shards.contains(&shard)) {
+                    continue;
+                }
                 self.send_topography_to_local_shard(shard, shard_data).await;
             }
         }
         
         log!("Global topography generation completed");
+        hasher.finish_hex()
     }
 
-    fn create_global_topography_image(&self) -> Vec<u8> {
-        let mut image = vec![self.info.base_elevation; self.info.total_width * self.info.total_height];
-        let mut rng = new_random_generator();
-        
-        // Step 1: Create river paths
-        let river_paths = self.generate_river_paths(&mut rng);
+    /// Draws the rivers from the seed and, below STREAMING_THRESHOLD_PIXELS, the whole field
+    pub fn field(&self) -> TopographyField<'_> {
+        let streamed = self.width * self.height > STREAMING_THRESHOLD_PIXELS;
+        self.build_field(streamed)
+    }
+
+    /// Always generates row blocks on demand, whatever the colony size
+    #[allow(dead_code)] // Used in tests/test_global_topography.rs
+    pub fn streamed_field(&self) -> TopographyField<'_> {
+        self.build_field(true)
+    }
+
+    fn build_field(&self, streamed: bool) -> TopographyField<'_> {
+        let mut rng = match self.info.seed {
+            Some(seed) => new_seeded_random_generator(seed),
+            None => new_random_generator(),
+        };
+        let rivers = self.generate_river_paths(&mut rng);
+        // Drawn after the rivers, so a seed gives the same rivers whatever the sanctuary settings
+        let sanctuaries = self.generate_sanctuaries(&mut rng);
+        let oases = self.generate_oases(&mut rng);
+        let full_field = if streamed {
+            None
+        } else {
+            Some(self.field_rows(&rivers, &oases, 0, self.height))
+        };
+        TopographyField { topography: self, rivers, sanctuaries, oases, full_field }
+    }
+
+    pub fn extra_food_pattern(&self) -> Option<ExtraFoodPattern> {
+        self.info.extra_food_pattern
+    }
+
+    pub fn source(&self) -> &'static str {
+        if self.info.extra_food_pattern.is_some() { PATTERN_TOPOGRAPHY_SOURCE } else { TOPOGRAPHY_SOURCE }
+    }
+
+    /// Rows row_start..row_end of the field: the pattern when there is one, else the rivers
+    fn field_rows(&self, rivers: &[RiverPath], oases: &[(f32, f32)], row_start: usize, row_end: usize) -> Vec<u8> {
+        match &self.info.extra_food_pattern {
+            Some(pattern) => (row_start..row_end)
+                .flat_map(|y| (0..self.width).map(move |x| extra_food_at(pattern, &self.info, oases, x, y)))
+                .collect(),
+            None => self.elevation_rows(rivers, row_start, row_end),
+        }
+    }
+
+    /// Smoothed elevation of rows row_start..row_end. Smoothing only reaches one row further
+    /// per iteration, so generating smoothing_iterations extra rows on each side makes the
+    /// result identical to the same rows of the whole field.
+    fn elevation_rows(&self, rivers: &[RiverPath], row_start: usize, row_end: usize) -> Vec<u8> {
+        let width = self.width;
+        let halo = self.info.smoothing_iterations;
+        let halo_start = row_start.saturating_sub(halo);
+        let halo_end = (row_end + halo).min(self.height);
+        let halo_height = halo_end - halo_start;
+
+        let mut image = vec![self.info.base_elevation; width * halo_height];
         
-        // Step 2: Apply river elevation and gradients
-        for y in 0..self.info.total_height {
-            for x in 0..self.info.total_width {
-                let idx = y * self.info.total_width + x;
+        // Apply river elevation and gradients
+        for y in 0..halo_height {
+            for x in 0..width {
+                let idx = y * width + x;
                 let mut max_river_influence: f32 = 0.0;
                 
                 // Check distance to all river paths
-                for river in &river_paths {
-                    let distance = self.distance_to_river(x as f32, y as f32, river);
+                for river in rivers {
+                    let distance = self.distance_to_river(x as f32, (halo_start + y) as f32, river);
                     let influence = self.calculate_river_influence(distance);
                     max_river_influence = max_river_influence.max(influence);
                 }
@@ -156,16 +287,15 @@ impl GlobalTopography {
             }
         }
         
-        // Step 3: Apply gradient smoothing around rivers
+        // Apply gradient smoothing around rivers
         for _ in 0..self.info.smoothing_iterations {
-            self.apply_laplacian_smoothing(&mut image);
+            Self::apply_laplacian_smoothing(&mut image, width, halo_height);
         }
         
-        image
+        let skip = (row_start - halo_start) * width;
+        image[skip..skip + (row_end - row_start) * width].to_vec()
     }
 
-
-    
     fn generate_river_paths(&self, rng: &mut impl rand::Rng) -> Vec<RiverPath> {
         let mut rivers = Vec::new();
         let num_rivers = rng.gen_range(self.info.river_count_range.0..=self.info.river_count_range.1);
@@ -178,7 +308,26 @@ impl GlobalTopography {
         rivers
     }
     
-    fn generate_single_river(&self, rng: &mut impl rand::Rng) -> RiverPath {
+    fn generate_sanctuaries(&self, rng: &mut impl rand::Rng) -> Vec<Sanctuary> {
+        let (min_radius, max_radius) = self.info.sanctuary_radius_range;
+        (0..self.info.sanctuary_count).map(|_| Sanctuary {
+            x: rng.gen_range(0..self.info.total_width) as i64,
+            y: rng.gen_range(0..self.info.total_height) as i64,
+            radius: rng.gen_range(min_radius..=max_radius) as i64,
+        }).collect()
+    }
+
+    fn generate_oases(&self, rng: &mut impl rand::Rng) -> Vec<(f32, f32)> {
+        let Some(ExtraFoodPattern::Oases { count, .. }) = self.info.extra_food_pattern else {
+            return Vec::new();
+        };
+        (0..count).map(|_| (
+            rng.gen_range(0..self.info.total_width) as f32,
+            rng.gen_range(0..self.info.total_height) as f32,
+        )).collect()
+    }
+    
+        fn generate_single_river(&self, rng: &mut impl rand::Rng) -> RiverPath {
         let mut points = Vec::new();
         
         // Start from a random edge
@@ -269,14 +418,8 @@ impl GlobalTopography {
             0.0
         }
     }
-    
-
 
-
-
-    fn apply_laplacian_smoothing(&self, image: &mut [u8]) {
-        let width = self.info.total_width;
-        let height = self.info.total_height;
+    fn apply_laplacian_smoothing(image: &mut [u8], width: usize, height: usize) {
         let mut smoothed = vec![0u8; image.len()];
         
         for y in 0..height {
@@ -319,28 +462,79 @@ impl GlobalTopography {
         // Copy smoothed values back to original image
         image.copy_from_slice(&smoothed);
     }
+}
 
-    fn extract_shard_data(&self, global_image: &[u8], shard_x: usize, shard_y: usize) -> Vec<u8> {
-        let mut shard_data = Vec::with_capacity(self.info.shard_width * self.info.shard_height);
-        
-        let start_x = shard_x * self.info.shard_width;
-        let start_y = shard_y * self.info.shard_height;
-        
-        for y in 0..self.info.shard_height {
-            for x in 0..self.info.shard_width {
-                let global_x = start_x + x;
-                let global_y = start_y + y;
-                let global_idx = global_y * self.info.total_width + global_x;
-                
-                if global_idx < global_image.len() {
-                    let value = global_image[global_idx];
-                    shard_data.push(value);
-                } else {
-                    shard_data.push(0); // Fallback value
-                }
-            }
+/// Extra food of colony cell (x, y) under pattern: between base_elevation and
+/// base_elevation + river_elevation_range, except inside oases. oases holds the oasis centers.
+pub fn extra_food_at(pattern: &ExtraFoodPattern, info: &GlobalTopographyInfo, oases: &[(f32, f32)], x: usize, y: usize) -> u8 {
+    let (x, y) = (x as f32, y as f32);
+    let strength = match *pattern {
+        ExtraFoodPattern::Uniform => 0.5,
+        ExtraFoodPattern::RadialGradient { center: (center_x, center_y), falloff } => {
+            let distance = (x - center_x * info.total_width as f32).hypot(y - center_y * info.total_height as f32);
+            (1.0 - distance / falloff).max(0.0)
         }
-        
-        shard_data
+        ExtraFoodPattern::Stripes { period, orientation } => {
+            let along = match orientation {
+                StripeOrientation::Horizontal => y,
+                StripeOrientation::Vertical => x,
+            };
+            let phase = (along % period as f32) / period as f32;
+            0.5 + 0.5 * (2.0 * std::f32::consts::PI * phase).cos()
+        }
+        ExtraFoodPattern::Oases { radius, richness, .. } => {
+            let radius = radius as f32;
+            let in_oasis = oases.iter().any(|&(oasis_x, oasis_y)| (x - oasis_x).powi(2) + (y - oasis_y).powi(2) <= radius * radius);
+            return if in_oasis { richness } else { info.base_elevation };
+        }
+    };
+    (info.base_elevation as f32 + strength * info.river_elevation_range as f32).round() as u8
+}
+
+impl TopographyField<'_> {
+    pub fn is_streamed(&self) -> bool {
+        self.full_field.is_none()
+    }
+
+    /// Number of shard rows in the colony
+    pub fn block_count(&self) -> usize {
+        self.topography.height / self.topography.info.shard_height
+    }
+
+    /// The shard_height rows of the field covering shard row block_y
+    pub fn row_block(&self, block_y: usize) -> Cow<'_, [u8]> {
+        let info = &self.topography.info;
+        let row_start = block_y * info.shard_height;
+        let row_end = row_start + info.shard_height;
+        match &self.full_field {
+            Some(field) => Cow::Borrowed(&field[row_start * self.topography.width..row_end * self.topography.width]),
+            None => Cow::Owned(self.topography.field_rows(&self.rivers, &self.oases, row_start, row_end)),
+        }
+    }
+
+    /// Cuts a row block into the InitShardTopography payload of every shard in it,
+    /// followed by the shard's sanctuary mask when the colony has sanctuaries
+    pub fn shard_payloads(&self, block_y: usize, block: &[u8]) -> Vec<(Shard, Vec<u8>)> {
+        let info = &self.topography.info;
+        (0..self.topography.width / info.shard_width).map(|shard_x| {
+            let shard = Shard {
+                x: (shard_x * info.shard_width) as i32,
+                y: (block_y * info.shard_height) as i32,
+                width: info.shard_width as i32,
+                height: info.shard_height as i32,
+            };
+            let start_x = shard_x * info.shard_width;
+            let mut shard_data = Vec::with_capacity(info.shard_width * info.shard_height);
+            for row in block.chunks_exact(self.topography.width) {
+                shard_data.extend_from_slice(&row[start_x..start_x + info.shard_width]);
+            }
+            if !self.sanctuaries.is_empty() {
+                let cells = (0..shard.height)
+                    .flat_map(|y| (0..shard.width).map(move |x| LocalPos::new(x, y).to_global(&shard)))
+                    .map(|pos| self.sanctuaries.iter().any(|sanctuary| sanctuary.contains(pos)));
+                shard_data.extend(pack_sanctuary_mask(cells));
+            }
+            (shard, shard_data)
+        }).collect()
     }
-}  
\ No newline at end of file
+}
@@ -1,15 +1,61 @@
 use shared::{log, log_error};
 use tokio::net::TcpListener;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use crate::colony_start::colony_start_colony;
+use crate::colony_start::{colony_start_colony, last_start_failure, ColonyStartRequest};
 use crate::coordinator_context::CoordinatorContext;
 use crate::coordinator_storage::ColonyStatus;
+use crate::init_colony::{send_start_ticking_to_backend, shard_init_summary};
+use crate::colony_expand::{expand_colony, ExpandColonyError, ExpandColonyRequest};
+use crate::colony_step::{is_colony_paused, is_fast_forward, parse_step_count, set_colony_paused, set_fast_forward, step_colony, StepColonyError};
+use crate::shard_freeze::{set_shard_frozen, shard_list, FreezeShardError};
+use crate::global_topography::{push_shard_topography, PushTopographyError};
+use crate::backend_status::backend_statuses;
+use crate::bootstrap::bootstrap;
+use crate::run_export::{plan_export, write_run_export, DEFAULT_EXPORT_STATS_SNAPSHOTS};
+use crate::colony_verification::{verify_colony, VerificationTrigger, VerifyColonyError};
+use crate::determinism_check::{determinism_check, DeterminismCheckError};
+use crate::shard_event_log::colony_event_detail;
 use shared::ssm;
-use shared::cluster_topology::ClusterTopology;
-use shared::coordinator_api::ColonyEventDescription;
+use shared::supervisor;
+use shared::utils::{is_root_page_request, parse_query_param};
+use shared::api_auth::{ApiAuthConfig, ApiScope};
+use shared::cluster_topology::{ClusterTopology, HostInfo};
+use shared::be_api::{Shard, StartTickingResponse, StatMetric, COLONY_TICK_HEADER};
+use shared::colony_events::ColonyEvent;
+use shared::colony_event_shared::log_event;
+use shared::colony_event_schema::{event_schema, event_type_schema};
+use shared::coordinator_api::{BiomesResponse, CaptureConfig, CapturePruneResponse, ColonyConfigResponse, ColonyEventDescription, ColonyStatsRequest, ColonyStatsResponse, ShardFrozenResponse, TickHistoryResponse, TickerStateResponse};
+use crate::colony_stats::{all_stat_metrics, get_colony_stats, get_region_stats};
+use crate::colony_stats_cache::CachedColonyStats;
+use crate::colony_capture::{capture_colony_on_demand, current_colony_frame};
+use crate::colony_density::colony_density;
+use crate::warmup::is_colony_warming_up;
+use shared::density::{parse_density_cells, DEFAULT_COLONY_DENSITY_CELLS};
+use crate::capture_frames::{parse_frame_tick, CaptureStore};
+use crate::capture_config::update_capture_config;
+use crate::tick_monitor::{latest_max_tick, unix_time_ms};
+use crate::coordinator_ticker::apply_colony_event;
+use crate::live_feed_hub::serve_feed;
+use crate::colony_stats_alarms::raised_alarms;
+use crate::rules_drift::current_rules_drift;
+use crate::run_summary::{is_run_completed, read_run_summary};
+use shared::live_feed::FEED_PATH;
+use shared::output_paths::OutputPaths;
+use shared::http_access_log::{AccessLog, AccessLoggedStream, AccessLoggedTcpStream};
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::protocol::Role;
+use tokio_tungstenite::WebSocketStream;
+use uuid::Uuid;
 use std::fmt::Write;
+use std::sync::Arc;
 
 const HTTP_BIND_HOST: &str = "0.0.0.0";
+/// Debug page served at GET /, polls /api/colony-image and draws the stitched colony
+const VIEWER_HTML: &str = include_str!("viewer.html");
+const MISSING_SHARDS_HEADER: &str = "X-Colony-Missing-Shards";
+const DEFAULT_TICK_HISTORY_MINUTES: u64 = 60;
+/// Enough for the terrain of a 4000x4000 shard with its sanctuary mask
+const MAX_TOPOGRAPHY_BODY_BYTES: usize = 18_000_000;
 
 fn build_http_bind_addr(port: u16) -> String {
     format!("{}:{}", HTTP_BIND_HOST, port)
@@ -18,7 +64,7 @@ fn build_http_bind_addr(port: u16) -> String {
 fn is_colony_already_started() -> bool {
     let context = CoordinatorContext::get_instance();
     let stored_info = context.get_coord_stored_info();
-    matches!(stored_info.status, ColonyStatus::TopographyInitialized)
+    matches!(stored_info.status, ColonyStatus::TopographyInitialized | ColonyStatus::Completed)
 }
 
 fn matches_stored_idempotency_key(key: &str) -> bool {
@@ -29,47 +75,36 @@ fn matches_stored_idempotency_key(key: &str) -> bool {
         .unwrap_or(false)
 }
 
-fn parse_query_param(request: &str, param_name: &str) -> Option<String> {
-    if let Some(query_start) = request.find('?') {
-        let mut query = &request[query_start + 1..];
-        if let Some(line_end) = query.find('\r') {
-            query = &query[..line_end];
-        } else if let Some(line_end) = query.find('\n') {
-            query = &query[..line_end];
-        } else if let Some(space_pos) = query.find(' ') {
-            query = &query[..space_pos];
-        }
-        for pair in query.split('&') {
-            if let Some(equal_pos) = pair.find('=') {
-                let key = &pair[..equal_pos];
-                let value = &pair[equal_pos + 1..];
-                if key == param_name {
-                    return Some(value.to_string());
-                }
-            }
-        }
-    }
-    None
-}
-
 pub async fn start_http_server(http_port: u16) {
     let addr = build_http_bind_addr(http_port);
     let listener = TcpListener::bind(&addr).await.expect("Failed to bind HTTP server");
     log!("HTTP server listening on {}", addr);
+    let access_log = Arc::new(AccessLog::rotating(OutputPaths::get_instance().log_file(&format!("coordinator_{}_access", http_port))));
     
     loop {
         match listener.accept().await {
-            Ok((mut stream, _)) => {
+            Ok((stream, peer_addr)) => {
+                let mut stream = AccessLoggedStream::new(stream, access_log.clone(), peer_addr);
+                let access_log = access_log.clone();
                 tokio::spawn(async move {
                     let mut buffer = [0; 1024];
                     if let Ok(n) = stream.read(&mut buffer).await {
                         let request = String::from_utf8_lossy(&buffer[..n]);
+                        stream.set_request(&request);
+                        
+                        let scope = match ApiAuthConfig::get_instance().authorize(&request) {
+                            Ok(scope) => scope,
+                            Err(rejection) => {
+                                let _ = stream.write_all(rejection.to_http_response().as_bytes()).await;
+                                return;
+                            }
+                        };
                         
                         if request.starts_with("POST /colony-start") {
                             let idempotency_key = parse_query_param(&request, "idempotency_key");
                             
                             if idempotency_key.is_none() {
-                                let response = "HTTP/1.1 400 Bad Request\r\nContent-Length: 35\r\n\r\nidempotency_key parameter required";
+                                let response = "HTTP/1.1 400 Bad Request\r\nContent-Length: 34\r\n\r\nidempotency_key parameter required";
                                 let _ = stream.write_all(response.as_bytes()).await;
                             } else {
                                 let idempotency_key = idempotency_key.unwrap();
@@ -80,14 +115,29 @@ pub async fn start_http_server(http_port: u16) {
                                 
                                 if colony_started {
                                     if idempotent_match {
-                                        let response = "HTTP/1.1 200 OK\r\nContent-Length: 40\r\n\r\nColony already started (idempotent)";
+                                        let body = "Colony already started (idempotent)";
+                                        let response = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}", body.len(), body);
                                         let _ = stream.write_all(response.as_bytes()).await;
                                     } else {
-                                        let response = "HTTP/1.1 409 Conflict\r\nContent-Length: 26\r\n\r\nColony already started";
+                                        let body = "Colony already started";
+                                        let response = format!("HTTP/1.1 409 Conflict\r\nContent-Length: {}\r\n\r\n{}", body.len(), body);
                                         let _ = stream.write_all(response.as_bytes()).await;
                                     }
+                                } else if let Err(e) = CoordinatorContext::get_instance().get_colony_life_rules().validate() {
+                                    log_error!("Refusing colony-start: {}", e);
+                                    let error_json = serde_json::json!({ "error": e });
+                                    write_json_response(&mut stream, "400 Bad Request", &error_json.to_string()).await;
                                 } else {
-                                    log!("Received colony-start request via HTTP with idempotency_key: {}", idempotency_key);
+                                    let start_request = match ColonyStartRequest::parse(request_body(&request)) {
+                                        Ok(start_request) => start_request,
+                                        Err(e) => {
+                                            log_error!("Refusing colony-start: {}", e);
+                                            let error_json = serde_json::json!({ "error": e });
+                                            write_json_response(&mut stream, "400 Bad Request", &error_json.to_string()).await;
+                                            return;
+                                        }
+                                    };
+                                    log!("Received colony-start request via HTTP with idempotency_key: {}, seeding: {:?}, target_tick: {:?}, warmup_ticks: {:?}", idempotency_key, start_request.seeding, start_request.target_tick, start_request.warmup_ticks);
                                     
                                     // Set status to Initializing before spawning async task
                                     let context = CoordinatorContext::get_instance();
@@ -98,17 +148,99 @@ pub async fn start_http_server(http_port: u16) {
                                     
                                     let key_clone = idempotency_key.clone();
                                     tokio::spawn(async move {
-                                        colony_start_colony(Some(key_clone)).await;
+                                        colony_start_colony(Some(key_clone), start_request).await;
                                     });
                                     
                                     let response = "HTTP/1.1 202 Accepted\r\nContent-Length: 0\r\n\r\n";
                                     let _ = stream.write_all(response.as_bytes()).await;
                                 }
                             }
+                        } else if request.starts_with("POST /api/backend/start-ticking") {
+                            handle_backend_start_ticking(&mut stream, &request).await;
+                        } else if request.starts_with("POST /api/expand-colony") {
+                            handle_expand_colony(&mut stream, &request).await;
+                        } else if request.starts_with("POST /api/pause") {
+                            handle_set_colony_paused(&mut stream, true).await;
+                        } else if request.starts_with("POST /api/resume") {
+                            handle_set_colony_paused(&mut stream, false).await;
+                        } else if request.starts_with("POST /api/step") {
+                            handle_step_colony(&mut stream, &request).await;
+                        } else if request.starts_with("POST /api/fast-forward") {
+                            handle_set_fast_forward(&mut stream, &request).await;
+                        } else if request.starts_with("POST /api/verify") {
+                            handle_verify_colony(&mut stream).await;
+                        } else if request.starts_with("POST /api/colony-events") {
+                            handle_apply_colony_event(&mut stream, &request).await;
+                        } else if request.starts_with("POST /api/captures") {
+                            handle_capture_colony(&mut stream).await;
+                        } else if request.starts_with("POST /api/shard/") && request_path(&request).ends_with("/topography") {
+                            handle_push_shard_topography(&mut stream, &request, &buffer[..n]).await;
+                        } else if request.starts_with("POST /api/shard/") {
+                            handle_freeze_shard(&mut stream, &request).await;
+                        } else if request.starts_with("GET ") && request_path(&request) == FEED_PATH {
+                            handle_feed_upgrade(stream, &request, scope).await;
+                        } else if request.starts_with("GET /api/shards") {
+                            handle_get_shards(&mut stream, scope).await;
+                        } else if request.starts_with("GET /api/backends") {
+                            handle_get_backends(&mut stream, scope).await;
+                        } else if request.starts_with("GET /api/bootstrap") {
+                            handle_get_bootstrap(&mut stream, scope).await;
+                        } else if request.starts_with("GET /api/tick-history") {
+                            handle_get_tick_history(&mut stream, &request).await;
+                        } else if request.starts_with("GET /api/biomes") {
+                            handle_get_biomes(&mut stream).await;
+                        } else if request.starts_with("GET /api/ticker-state") {
+                            write_ticker_state(&mut stream, TickerStateResponse { paused: is_colony_paused(), current_tick: None, fast_forward: is_fast_forward() }).await;
+                        } else if request.starts_with("GET /api/colony-stats") {
+                            handle_get_colony_stats(&mut stream, &request).await;
+                        } else if request.starts_with("POST /api/colony-stats") {
+                            handle_post_colony_stats(&mut stream, &request).await;
+                        } else if request.starts_with("GET /api/export-run") {
+                            handle_export_run(&mut stream, &request).await;
+                        } else if request.starts_with("GET /api/event-schema") {
+                            let json = serde_json::to_string(&event_schema()).expect("Failed to serialize event schema");
+                            write_json_response(&mut stream, "200 OK", &json).await;
+                        } else if request.starts_with("GET /api/run-summary") {
+                            handle_get_run_summary(&mut stream).await;
+                        } else if request.starts_with("GET /api/rpc-stats") {
+                            let json = serde_json::json!({ "deserialize_failures": CoordinatorContext::get_instance().decode_failures().lock().unwrap().peers() });
+                            write_json_response(&mut stream, "200 OK", &json.to_string()).await;
+                        } else if request.starts_with("GET /metrics") {
+                            let body = CoordinatorContext::get_instance().decode_failures().lock().unwrap().render_prometheus("coordinator") + &access_log.render_prometheus("coordinator");
+                            let response = format!(
+                                "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\n\r\n{}",
+                                body.len(),
+                                body
+                            );
+                            let _ = stream.write_all(response.as_bytes()).await;
+                        } else if request.starts_with("GET /api/colony-config") {
+                            handle_get_colony_config(&mut stream).await;
+                        } else if request.starts_with("GET /api/determinism-check") {
+                            handle_determinism_check(&mut stream, &request).await;
+                        } else if request.starts_with("GET /api/density") {
+                            handle_get_density(&mut stream, &request).await;
+                        } else if request.starts_with("GET /api/region-events") {
+                            handle_get_region_events(&mut stream).await;
+                        } else if request.starts_with("GET /api/colony-events/") {
+                            handle_get_colony_event_detail(&mut stream, &request).await;
                         } else if request.starts_with("GET /api/colony-events") {
                             handle_get_colony_events(&mut stream, &request).await;
+                        } else if request.starts_with("GET /api/capture-config") {
+                            handle_get_capture_config(&mut stream).await;
+                        } else if request.starts_with("PUT /api/capture-config") {
+                            handle_put_capture_config(&mut stream, &request).await;
+                        } else if request.starts_with("GET /api/captures/") {
+                            handle_get_capture_frame(&mut stream, &request).await;
+                        } else if request.starts_with("GET /api/captures") {
+                            handle_get_captures(&mut stream, &request).await;
+                        } else if request.starts_with("DELETE /api/captures") {
+                            handle_prune_captures(&mut stream, &request).await;
+                        } else if request.starts_with("GET /api/colony-image") {
+                            handle_get_colony_image(&mut stream).await;
                         } else if request.starts_with("GET /topology") {
-                            handle_get_topology(&mut stream).await;
+                            handle_get_topology(&mut stream, scope).await;
+                        } else if request.starts_with("GET /health") {
+                            handle_get_health(&mut stream).await;
                         } else if request.starts_with("GET /debug-ssm") {
                             let body = render_ssm_state().await;
                             let response = format!(
@@ -117,9 +249,15 @@ pub async fn start_http_server(http_port: u16) {
                                 body
                             );
                             let _ = stream.write_all(response.as_bytes()).await;
-                        } else if request.starts_with("GET /colony-start") || request.starts_with("GET /") {
-                            let response = "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: 13\r\n\r\nColony-start API";
+                        } else if is_root_page_request(&request) {
+                            let response = format!(
+                                "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\n\r\n{}",
+                                VIEWER_HTML.len(),
+                                VIEWER_HTML
+                            );
                             let _ = stream.write_all(response.as_bytes()).await;
+                        } else if request.starts_with("GET /colony-start") {
+                            write_colony_start_status(&mut stream).await;
                         } else {
                             let response = "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n";
                             let _ = stream.write_all(response.as_bytes()).await;
@@ -157,7 +295,942 @@ async fn render_ssm_state() -> String {
     body
 }
 
-async fn handle_get_colony_events(stream: &mut tokio::net::TcpStream, request: &str) {
+/// Liveness plus the restart counters of the supervised background tasks
+/// Degraded while a supervised task flaps, a stats alarm is raised or backends run drifted rules;
+/// "warming-up" instead while a colony started with warmup_ticks warms up and no task flaps
+async fn handle_get_health(stream: &mut AccessLoggedTcpStream) {
+    let tasks = supervisor::supervised_tasks_health();
+    let alarms = raised_alarms();
+    let rules_drift = current_rules_drift();
+    let status = if is_colony_warming_up() && supervisor::health_status(&tasks) == "ok" {
+        "warming-up"
+    } else if alarms.is_empty() && rules_drift.is_empty() {
+        supervisor::health_status(&tasks)
+    } else {
+        "degraded"
+    };
+    let colony_status = CoordinatorContext::get_instance().get_coord_stored_info().status.clone();
+    let body = format!(
+        r#"{{"status":"{}","colony_status":{},"tasks":{},"alarms":{},"rules_drift":{}}}"#,
+        status,
+        serde_json::to_string(&colony_status).unwrap_or_else(|_| "null".to_string()),
+        serde_json::to_string(&tasks).unwrap_or_else(|_| "[]".to_string()),
+        serde_json::to_string(&alarms).unwrap_or_else(|_| "[]".to_string()),
+        serde_json::to_string(&rules_drift).unwrap_or_else(|_| "[]".to_string())
+    );
+    write_json_response(stream, "200 OK", &body).await;
+}
+
+async fn write_json_response(stream: &mut AccessLoggedTcpStream, status_line: &str, json: &str) {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+        status_line,
+        json.len(),
+        json
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+}
+
+/// Re-issues StartTicking to a single backend, e.g. after it was restarted
+async fn handle_backend_start_ticking(stream: &mut AccessLoggedTcpStream, request: &str) {
+    let host = parse_query_param(request, "host");
+    let port = parse_query_param(request, "port").and_then(|p| p.parse::<u16>().ok());
+    let backend_host = match (host, port) {
+        (Some(host), Some(port)) => HostInfo::new(host, port),
+        _ => {
+            write_json_response(stream, "400 Bad Request", r#"{"error":"host and port parameters required"}"#).await;
+            return;
+        }
+    };
+    
+    let is_known_backend = ClusterTopology::get_instance()
+        .map(|topology| topology.get_all_backend_hosts().contains(&backend_host))
+        .unwrap_or(false);
+    if !is_known_backend {
+        write_json_response(stream, "404 Not Found", r#"{"error":"Backend not in topology"}"#).await;
+        return;
+    }
+    
+    log!("Re-issuing StartTicking to backend {} via HTTP", backend_host.to_address());
+    match send_start_ticking_to_backend(&backend_host).await {
+        Ok(StartTickingResponse::Ok) => {
+            write_json_response(stream, "200 OK", r#"{"result":"ok"}"#).await;
+        }
+        Ok(refused) => {
+            let reason = match refused {
+                StartTickingResponse::ColonyNotInitialized => "colony not initialized".to_string(),
+                StartTickingResponse::TopologyNotInitialized => "topology not initialized".to_string(),
+                StartTickingResponse::AwaitingTopography(shards) => format!("{} shards await their topography", shards.len()),
+                StartTickingResponse::StaleLeases(shards) => format!("{} shards hold a stale lease", shards.len()),
+                StartTickingResponse::Error(msg) => msg,
+                StartTickingResponse::Ok => unreachable!(),
+            };
+            let error_json = serde_json::json!({ "error": format!("Backend refused StartTicking: {}", reason) });
+            write_json_response(stream, "409 Conflict", &error_json.to_string()).await;
+        }
+        Err(e) => {
+            log_error!("Failed to re-issue StartTicking to {}: {}", backend_host.to_address(), e);
+            write_json_response(stream, e.http_status(), &e.to_json()).await;
+        }
+    }
+}
+
+/// Progress of the last POST /colony-start, with the reason it failed if it did and the
+/// shard being initialized while it runs
+async fn write_colony_start_status(stream: &mut AccessLoggedTcpStream) {
+    let (status, colony_instance_id) = {
+        let stored_info = CoordinatorContext::get_instance().get_coord_stored_info();
+        (format!("{:?}", stored_info.status), stored_info.colony_instance_id.clone())
+    };
+    let shard_init = shard_init_summary().await.map(|(counter, progress, summary)| serde_json::json!({
+        "shard_id": counter.current.map(|shard| shard.to_id()),
+        "shards_done": counter.done,
+        "shards_total": counter.total,
+        "progress": progress,
+        "summary": summary,
+    }));
+    let json = serde_json::json!({
+        "status": status,
+        "colony_instance_id": colony_instance_id,
+        "failure": last_start_failure(),
+        "shard_init": shard_init,
+    });
+    write_json_response(stream, "200 OK", &json.to_string()).await;
+}
+
+async fn write_ticker_state(stream: &mut AccessLoggedTcpStream, state: TickerStateResponse) {
+    let json = serde_json::to_string(&state).expect("Failed to serialize ticker state");
+    write_json_response(stream, "200 OK", &json).await;
+}
+
+async fn write_step_error(stream: &mut AccessLoggedTcpStream, error: StepColonyError) {
+    match error {
+        StepColonyError::InProgress => {
+            write_json_response(stream, "409 Conflict", r#"{"error":"Step already in progress"}"#).await;
+        }
+        StepColonyError::TopologyNotInitialized => {
+            write_json_response(stream, "404 Not Found", r#"{"error":"Topology not initialized"}"#).await;
+        }
+        StepColonyError::InvalidCount(e) => {
+            let error_json = serde_json::json!({ "error": e });
+            write_json_response(stream, "400 Bad Request", &error_json.to_string()).await;
+        }
+        StepColonyError::Failed(e) => {
+            let error_json = serde_json::json!({ "error": format!("Backend call failed: {}", e) });
+            write_json_response(stream, "502 Bad Gateway", &error_json.to_string()).await;
+        }
+        StepColonyError::RunCompleted => {
+            write_json_response(stream, "409 Conflict", r#"{"error":"The run reached its target tick and completed"}"#).await;
+        }
+    }
+}
+
+async fn handle_set_colony_paused(stream: &mut AccessLoggedTcpStream, paused: bool) {
+    match set_colony_paused(paused).await {
+        Ok(current_tick) => {
+            write_ticker_state(stream, TickerStateResponse { paused, current_tick: Some(current_tick), fast_forward: is_fast_forward() }).await;
+        }
+        Err(e) => write_step_error(stream, e).await,
+    }
+}
+
+/// Turns fast-forward mode on or off with ?enabled=true|false
+async fn handle_set_fast_forward(stream: &mut AccessLoggedTcpStream, request: &str) {
+    let enabled = match parse_query_param(request, "enabled").as_deref() {
+        Some("true") => true,
+        Some("false") => false,
+        _ => {
+            write_json_response(stream, "400 Bad Request", r#"{"error":"enabled parameter must be true or false"}"#).await;
+            return;
+        }
+    };
+    match set_fast_forward(enabled).await {
+        Ok(()) => {
+            write_ticker_state(stream, TickerStateResponse { paused: is_colony_paused(), current_tick: None, fast_forward: enabled }).await;
+        }
+        Err(e) => write_step_error(stream, e).await,
+    }
+}
+
+/// Pauses the colony and advances it by ?count= ticks in lockstep across backends
+async fn handle_step_colony(stream: &mut AccessLoggedTcpStream, request: &str) {
+    let count = match parse_step_count(parse_query_param(request, "count").as_deref()) {
+        Ok(count) => count,
+        Err(e) => {
+            write_step_error(stream, e).await;
+            return;
+        }
+    };
+    
+    log!("Received step request via HTTP: {} tick(s)", count);
+    match step_colony(count).await {
+        Ok(current_tick) => {
+            write_ticker_state(stream, TickerStateResponse { paused: true, current_tick: Some(current_tick), fast_forward: is_fast_forward() }).await;
+        }
+        Err(e) => write_step_error(stream, e).await,
+    }
+}
+
+/// POST /api/shard/{id}/freeze, ?frozen=false unfreezes
+async fn handle_freeze_shard(stream: &mut AccessLoggedTcpStream, request: &str) {
+    let Some(shard_id) = request_path(request).strip_prefix("/api/shard/").and_then(|rest| rest.strip_suffix("/freeze")) else {
+        write_json_response(stream, "404 Not Found", r#"{"error":"Unknown shard endpoint"}"#).await;
+        return;
+    };
+    let frozen = match parse_query_param(request, "frozen").as_deref() {
+        None | Some("true") => true,
+        Some("false") => false,
+        Some(other) => {
+            let error_json = serde_json::json!({ "error": format!("Invalid frozen value: {}", other) });
+            write_json_response(stream, "400 Bad Request", &error_json.to_string()).await;
+            return;
+        }
+    };
+
+    match set_shard_frozen(shard_id, frozen).await {
+        Ok(current_tick) => {
+            let body = ShardFrozenResponse { shard_id: shard_id.to_string(), frozen, current_tick };
+            let json = serde_json::to_string(&body).expect("Failed to serialize freeze response");
+            write_json_response(stream, "200 OK", &json).await;
+        }
+        Err(FreezeShardError::TopologyNotInitialized) => {
+            write_json_response(stream, "404 Not Found", r#"{"error":"Topology not initialized"}"#).await;
+        }
+        Err(FreezeShardError::InvalidShardId(e)) => {
+            let error_json = serde_json::json!({ "error": e });
+            write_json_response(stream, "400 Bad Request", &error_json.to_string()).await;
+        }
+        Err(FreezeShardError::ShardNotFound(id)) => {
+            let error_json = serde_json::json!({ "error": format!("Shard {} not in topology", id) });
+            write_json_response(stream, "404 Not Found", &error_json.to_string()).await;
+        }
+        Err(FreezeShardError::Failed(e)) => {
+            let error_json = serde_json::json!({ "error": format!("Backend call failed: {}", e) });
+            write_json_response(stream, "502 Bad Gateway", &error_json.to_string()).await;
+        }
+    }
+}
+
+/// Path of the request line without its query string
+fn request_path(request: &str) -> &str {
+    let path = request.split_whitespace().nth(1).unwrap_or("");
+    path.split('?').next().unwrap_or(path)
+}
+
+/// The whole body of a request whose first read_bytes were already read, up to max_len
+async fn read_binary_body(stream: &mut AccessLoggedTcpStream, request: &str, read_bytes: &[u8], max_len: usize) -> Result<Vec<u8>, String> {
+    let content_length: usize = request_header(request, "Content-Length")
+        .ok_or_else(|| "Content-Length required".to_string())?
+        .parse()
+        .map_err(|_| "Invalid Content-Length".to_string())?;
+    if content_length > max_len {
+        return Err(format!("Body of {} bytes exceeds the limit of {}", content_length, max_len));
+    }
+    let body_start = read_bytes.windows(4).position(|window| window == b"\r\n\r\n")
+        .map(|idx| idx + 4)
+        .ok_or_else(|| "Request headers too large".to_string())?;
+    let mut body = read_bytes[body_start..].to_vec();
+    body.truncate(content_length);
+    let already_read = body.len();
+    body.resize(content_length, 0);
+    stream.read_exact(&mut body[already_read..]).await.map_err(|e| format!("Failed to read body: {}", e))?;
+    Ok(body)
+}
+
+/// POST /api/shard/{id}/topography, the body laid out like InitShardTopographyRequest::topography_data
+async fn handle_push_shard_topography(stream: &mut AccessLoggedTcpStream, request: &str, read_bytes: &[u8]) {
+    let Some(shard_id) = request_path(request).strip_prefix("/api/shard/").and_then(|rest| rest.strip_suffix("/topography")) else {
+        write_json_response(stream, "404 Not Found", r#"{"error":"Unknown shard endpoint"}"#).await;
+        return;
+    };
+    let topography_data = match read_binary_body(stream, request, read_bytes, MAX_TOPOGRAPHY_BODY_BYTES).await {
+        Ok(body) => body,
+        Err(e) => {
+            let error_json = serde_json::json!({ "error": e });
+            write_json_response(stream, "400 Bad Request", &error_json.to_string()).await;
+            return;
+        }
+    };
+
+    match push_shard_topography(shard_id, topography_data).await {
+        Ok(()) => {
+            let body = serde_json::json!({ "shard_id": shard_id });
+            write_json_response(stream, "202 Accepted", &body.to_string()).await;
+        }
+        Err(PushTopographyError::TopologyNotInitialized) => {
+            write_json_response(stream, "404 Not Found", r#"{"error":"Topology not initialized"}"#).await;
+        }
+        Err(PushTopographyError::InvalidShardId(e)) => {
+            let error_json = serde_json::json!({ "error": e });
+            write_json_response(stream, "400 Bad Request", &error_json.to_string()).await;
+        }
+        Err(PushTopographyError::ShardNotFound(id)) => {
+            let error_json = serde_json::json!({ "error": format!("Shard {} not in topology", id) });
+            write_json_response(stream, "404 Not Found", &error_json.to_string()).await;
+        }
+        Err(PushTopographyError::ShardNotInitialized) => {
+            write_json_response(stream, "409 Conflict", r#"{"error":"Shard not initialized on its backend"}"#).await;
+        }
+        Err(PushTopographyError::InvalidTopographyData) => {
+            write_json_response(stream, "400 Bad Request", r#"{"error":"Topography data does not match the shard size"}"#).await;
+        }
+        Err(PushTopographyError::Failed(e)) => {
+            let error_json = serde_json::json!({ "error": format!("Backend call failed: {}", e) });
+            write_json_response(stream, "502 Bad Gateway", &error_json.to_string()).await;
+        }
+    }
+}
+
+async fn handle_get_shards(stream: &mut AccessLoggedTcpStream, scope: ApiScope) {
+    let Some(topology) = ClusterTopology::get_instance() else {
+        write_json_response(stream, "404 Not Found", r#"{"error":"Topology not initialized"}"#).await;
+        return;
+    };
+    // Same address redaction as /topology
+    let list = match scope {
+        ApiScope::Admin => shard_list(&topology),
+        ApiScope::Observer => {
+            let mut addresses = ssm::discover_backends().await;
+            addresses.extend(ssm::discover_coordinator().await);
+            shard_list(&topology.to_observer_view(&addresses))
+        }
+    };
+    let json = serde_json::to_string(&list).expect("Failed to serialize shard list");
+    write_json_response(stream, "200 OK", &json).await;
+}
+
+/// Audits every shard of the topology; a failed audit is still 200, see the report's passed flag
+async fn handle_verify_colony(stream: &mut AccessLoggedTcpStream) {
+    log!("Received verify request via HTTP");
+    match verify_colony(VerificationTrigger::Manual).await {
+        Ok(report) => {
+            let json = serde_json::to_string(&report).expect("Failed to serialize verification report");
+            write_json_response(stream, "200 OK", &json).await;
+        }
+        Err(VerifyColonyError::InProgress) => {
+            write_json_response(stream, "409 Conflict", r#"{"error":"Verification already in progress"}"#).await;
+        }
+        Err(VerifyColonyError::TopologyNotInitialized) => {
+            write_json_response(stream, "404 Not Found", r#"{"error":"Topology not initialized"}"#).await;
+        }
+    }
+}
+
+/// Live per-backend view: topology assignment, reported shards, ticks, health and version
+async fn handle_get_backends(stream: &mut AccessLoggedTcpStream, scope: ApiScope) {
+    let Some(topology) = ClusterTopology::get_instance() else {
+        write_json_response(stream, "404 Not Found", r#"{"error":"Topology not initialized"}"#).await;
+        return;
+    };
+    let mut response = backend_statuses(&topology).await;
+    // Same address redaction as /topology
+    if scope == ApiScope::Observer {
+        let mut addresses = ssm::discover_backends().await;
+        addresses.extend(ssm::discover_coordinator().await);
+        let observer_view = topology.to_observer_view(&addresses);
+        for (status, public_host) in response.backends.iter_mut().zip(observer_view.get_all_backend_hosts()) {
+            status.backend = public_host.to_address();
+        }
+    }
+    let json = serde_json::to_string(&response).expect("Failed to serialize backend list");
+    write_json_response(stream, "200 OK", &json).await;
+}
+
+/// Everything needed to attach a GUI in one call, redacted for observers like /topology
+async fn handle_get_bootstrap(stream: &mut AccessLoggedTcpStream, scope: ApiScope) {
+    let json = serde_json::to_string(&bootstrap(scope).await).expect("Failed to serialize bootstrap");
+    write_json_response(stream, "200 OK", &json).await;
+}
+
+fn request_body(request: &str) -> &str {
+    request.split_once("\r\n\r\n").map(|(_, body)| body).unwrap_or("")
+}
+
+/// GET /api/tick-history?minutes= (default 60)
+async fn handle_get_tick_history(stream: &mut AccessLoggedTcpStream, request: &str) {
+    let minutes = match parse_query_param(request, "minutes") {
+        None => DEFAULT_TICK_HISTORY_MINUTES,
+        Some(value) => match value.parse::<u64>() {
+            Ok(minutes) if minutes > 0 => minutes,
+            _ => {
+                let error_json = serde_json::json!({ "error": format!("Invalid minutes: {}", value) });
+                write_json_response(stream, "400 Bad Request", &error_json.to_string()).await;
+                return;
+            }
+        },
+    };
+    let samples = CoordinatorContext::get_instance().tick_history().since(minutes, unix_time_ms());
+    let json = serde_json::to_string(&TickHistoryResponse { samples }).expect("Failed to serialize tick history");
+    write_json_response(stream, "200 OK", &json).await;
+}
+
+async fn handle_get_biomes(stream: &mut AccessLoggedTcpStream) {
+    let biomes = CoordinatorContext::get_instance().get_biomes();
+    let json = serde_json::to_string(&BiomesResponse { biomes }).expect("Failed to serialize biomes");
+    write_json_response(stream, "200 OK", &json).await;
+}
+
+async fn handle_get_region_events(stream: &mut AccessLoggedTcpStream) {
+    let events = CoordinatorContext::get_instance().get_region_events();
+    let json = serde_json::json!({ "events": events });
+    write_json_response(stream, "200 OK", &json.to_string()).await;
+}
+
+async fn handle_get_density(stream: &mut AccessLoggedTcpStream, request: &str) {
+    let cells = match parse_density_cells(parse_query_param(request, "cells").as_deref(), DEFAULT_COLONY_DENSITY_CELLS) {
+        Ok(cells) => cells,
+        Err(e) => {
+            write_json_response(stream, "400 Bad Request", &serde_json::json!({ "error": e }).to_string()).await;
+            return;
+        }
+    };
+    match colony_density(cells).await {
+        Ok(density) => {
+            let json = serde_json::to_string(&*density).expect("Failed to serialize colony density");
+            write_json_response(stream, "200 OK", &json).await;
+        }
+        Err(e) => write_json_response(stream, "503 Service Unavailable", &serde_json::json!({ "error": e }).to_string()).await,
+    }
+}
+
+async fn handle_get_capture_config(stream: &mut AccessLoggedTcpStream) {
+    let config = CoordinatorContext::get_instance().get_capture_config();
+    let json = serde_json::to_string(&config).expect("Failed to serialize capture config");
+    write_json_response(stream, "200 OK", &json).await;
+}
+
+async fn handle_put_capture_config(stream: &mut AccessLoggedTcpStream, request: &str) {
+    let config: CaptureConfig = match serde_json::from_str(request_body(request)) {
+        Ok(config) => config,
+        Err(e) => {
+            let error_json = serde_json::json!({ "error": format!("Invalid capture config: {}", e) });
+            write_json_response(stream, "400 Bad Request", &error_json.to_string()).await;
+            return;
+        }
+    };
+    match update_capture_config(config).await {
+        Ok(config) => {
+            let json = serde_json::to_string(&config).expect("Failed to serialize capture config");
+            write_json_response(stream, "200 OK", &json).await;
+        }
+        Err(e) => {
+            let error_json = serde_json::json!({ "error": e });
+            write_json_response(stream, "400 Bad Request", &error_json.to_string()).await;
+        }
+    }
+}
+
+async fn handle_expand_colony(stream: &mut AccessLoggedTcpStream, request: &str) {
+    let expand_request: ExpandColonyRequest = match serde_json::from_str(request_body(request)) {
+        Ok(req) => req,
+        Err(e) => {
+            let error_json = serde_json::json!({ "error": format!("Invalid expand request: {}", e) });
+            write_json_response(stream, "400 Bad Request", &error_json.to_string()).await;
+            return;
+        }
+    };
+    
+    log!("Received expand-colony request via HTTP: {:?}", expand_request);
+    match expand_colony(expand_request).await {
+        Ok(result) => {
+            let json = serde_json::to_string(&result).expect("Failed to serialize expand result");
+            write_json_response(stream, "200 OK", &json).await;
+        }
+        Err(ExpandColonyError::InProgress) => {
+            write_json_response(stream, "409 Conflict", r#"{"error":"Colony expansion already in progress"}"#).await;
+        }
+        Err(ExpandColonyError::TopologyNotInitialized) => {
+            write_json_response(stream, "404 Not Found", r#"{"error":"Topology not initialized"}"#).await;
+        }
+        Err(ExpandColonyError::UnknownBackend(backend)) => {
+            let error_json = serde_json::json!({ "error": format!("Backend not in topology: {}", backend) });
+            write_json_response(stream, "404 Not Found", &error_json.to_string()).await;
+        }
+        Err(ExpandColonyError::InvalidRules(e)) => {
+            let error_json = serde_json::json!({ "error": e });
+            write_json_response(stream, "400 Bad Request", &error_json.to_string()).await;
+        }
+        Err(ExpandColonyError::Failed(e)) => {
+            log_error!("Colony expansion failed: {}", e);
+            let error_json = serde_json::json!({ "error": format!("Colony expansion failed: {}", e) });
+            write_json_response(stream, "502 Bad Gateway", &error_json.to_string()).await;
+        }
+    }
+}
+
+/// ?metrics=Health,Size selects metrics; all numeric metrics when omitted
+/// Every metric but OriginalColor, which only feeds the species summary
+fn default_stat_metrics() -> Vec<StatMetric> {
+    all_stat_metrics().into_iter().filter(|m| !matches!(m, StatMetric::OriginalColor)).collect()
+}
+
+fn parse_stat_metrics(request: &str) -> Result<Vec<StatMetric>, String> {
+    let Some(param) = parse_query_param(request, "metrics") else {
+        return Ok(default_stat_metrics());
+    };
+    param.split(',')
+        .filter(|name| !name.is_empty())
+        .map(|name| match serde_json::from_value::<StatMetric>(serde_json::Value::String(name.to_string())) {
+            Ok(StatMetric::OriginalColor) | Err(_) => Err(format!("Unsupported metric: {}", name)),
+            Ok(metric) => Ok(metric),
+        })
+        .collect()
+}
+
+/// ?region={x}_{y}_{width}_{height} in colony coordinates, the shard id format; None without one
+fn parse_stats_region(request: &str) -> Result<Option<Shard>, String> {
+    parse_query_param(request, "region")
+        .map(|param| Shard::from_id(&param).map_err(|e| format!("Invalid region: {}", e)))
+        .transpose()
+}
+
+/// GET /api/colony-stats[?metrics=..][&region=..]: the region parameter gives the same region
+/// stats as the POST body, so observer tokens, which may only GET, can ask for them too
+async fn handle_get_colony_stats(stream: &mut AccessLoggedTcpStream, request: &str) {
+    if !is_colony_already_started() {
+        write_json_response(stream, "404 Not Found", r#"{"error":"Colony not initialized"}"#).await;
+        return;
+    }
+    let parsed = parse_stat_metrics(request).and_then(|metrics| Ok((metrics, parse_stats_region(request)?)));
+    let (metrics, region) = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            let error_json = serde_json::json!({ "error": e });
+            write_json_response(stream, "400 Bad Request", &error_json.to_string()).await;
+            return;
+        }
+    };
+    
+    match region {
+        Some(region) => write_region_stats(stream, metrics, region).await,
+        None => write_colony_stats(stream, get_colony_stats(metrics).await.map(|(stats, cached)| colony_stats_response(stats, cached))).await,
+    }
+}
+
+/// POST /api/colony-stats: like the GET, with the metrics and an optional region in a JSON
+/// ColonyStatsRequest body
+async fn handle_post_colony_stats(stream: &mut AccessLoggedTcpStream, request: &str) {
+    if !is_colony_already_started() {
+        write_json_response(stream, "404 Not Found", r#"{"error":"Colony not initialized"}"#).await;
+        return;
+    }
+    let body = request_body(request).trim();
+    let parsed = if body.is_empty() {
+        Ok(ColonyStatsRequest::default())
+    } else {
+        serde_json::from_str::<ColonyStatsRequest>(body).map_err(|e| format!("Invalid colony stats request: {}", e))
+    };
+    let stats_request = parsed.and_then(|stats_request| match &stats_request.metrics {
+        Some(metrics) if metrics.iter().any(|metric| matches!(metric, StatMetric::OriginalColor)) => {
+            Err("Unsupported metric: OriginalColor".to_string())
+        }
+        _ => Ok(stats_request),
+    });
+    let stats_request = match stats_request {
+        Ok(stats_request) => stats_request,
+        Err(e) => {
+            write_json_response(stream, "400 Bad Request", &serde_json::json!({ "error": e }).to_string()).await;
+            return;
+        }
+    };
+    let metrics = stats_request.metrics.unwrap_or_else(default_stat_metrics);
+    let Some(region) = stats_request.region else {
+        write_colony_stats(stream, get_colony_stats(metrics).await.map(|(stats, cached)| colony_stats_response(stats, cached))).await;
+        return;
+    };
+    write_region_stats(stream, metrics, region).await;
+}
+
+/// Region stats ask only the shards the region intersects and are never cached
+async fn write_region_stats(stream: &mut AccessLoggedTcpStream, metrics: Vec<StatMetric>, region: Shard) {
+    if region.width <= 0 || region.height <= 0 {
+        let error_json = serde_json::json!({ "error": format!("Invalid region size {}x{}", region.width, region.height) });
+        write_json_response(stream, "400 Bad Request", &error_json.to_string()).await;
+        return;
+    }
+    let result = get_region_stats(metrics, region).await.map(|region_stats| ColonyStatsResponse {
+        region: region_stats.region,
+        region_cells: Some(region_stats.cells),
+        ..colony_stats_response(region_stats.stats, false)
+    });
+    write_colony_stats(stream, result).await;
+}
+
+fn colony_stats_response(stats: CachedColonyStats, cached: bool) -> ColonyStatsResponse {
+    ColonyStatsResponse {
+        tick: stats.tick,
+        age_ms: stats.computed_at.elapsed().as_millis() as u64,
+        cached,
+        stats: stats.stats,
+        species: stats.species,
+        region: None,
+        region_cells: None,
+    }
+}
+
+/// 200 with the stats, or 502 when the fan-out failed
+async fn write_colony_stats(stream: &mut AccessLoggedTcpStream, result: Result<ColonyStatsResponse, String>) {
+    match result {
+        Ok(response) => {
+            let json = serde_json::to_string(&response).expect("Failed to serialize colony stats");
+            write_json_response(stream, "200 OK", &json).await;
+        }
+        Err(e) => {
+            log_error!("Failed to get colony stats: {}", e);
+            let error_json = serde_json::json!({ "error": e });
+            write_json_response(stream, "502 Bad Gateway", &error_json.to_string()).await;
+        }
+    }
+}
+
+/// Captures of the current colony instance; 404 before an instance id is assigned
+fn current_capture_store() -> Option<CaptureStore> {
+    let instance_id = CoordinatorContext::get_instance().get_coord_stored_info().colony_instance_id.clone()?;
+    Some(CaptureStore::for_instance(&instance_id))
+}
+
+fn parse_tick_param(request: &str, name: &str) -> Result<Option<u64>, String> {
+    parse_query_param(request, name)
+        .map(|value| value.parse::<u64>().map_err(|_| format!("Invalid {}: {}", name, value)))
+        .transpose()
+}
+
+async fn handle_get_captures(stream: &mut AccessLoggedTcpStream, request: &str) {
+    let Some(store) = current_capture_store() else {
+        write_json_response(stream, "404 Not Found", r#"{"error":"Colony instance not initialized"}"#).await;
+        return;
+    };
+    let range = parse_tick_param(request, "from_tick").and_then(|from| Ok((from, parse_tick_param(request, "to_tick")?)));
+    let (from_tick, to_tick) = match range {
+        Ok(range) => range,
+        Err(e) => {
+            write_json_response(stream, "400 Bad Request", &serde_json::json!({ "error": e }).to_string()).await;
+            return;
+        }
+    };
+    match store.list(from_tick, to_tick) {
+        Ok(list) => {
+            let json = serde_json::to_string(&list).expect("Failed to serialize capture list");
+            write_json_response(stream, "200 OK", &json).await;
+        }
+        Err(e) => {
+            log_error!("Failed to list captures: {}", e);
+            write_json_response(stream, "500 Internal Server Error", &serde_json::json!({ "error": e }).to_string()).await;
+        }
+    }
+}
+
+async fn handle_prune_captures(stream: &mut AccessLoggedTcpStream, request: &str) {
+    let Some(store) = current_capture_store() else {
+        write_json_response(stream, "404 Not Found", r#"{"error":"Colony instance not initialized"}"#).await;
+        return;
+    };
+    let before_tick = match parse_tick_param(request, "before_tick") {
+        Ok(Some(tick)) => tick,
+        Ok(None) => {
+            write_json_response(stream, "400 Bad Request", r#"{"error":"before_tick parameter required"}"#).await;
+            return;
+        }
+        Err(e) => {
+            write_json_response(stream, "400 Bad Request", &serde_json::json!({ "error": e }).to_string()).await;
+            return;
+        }
+    };
+    match store.prune(before_tick) {
+        Ok(frames_deleted) => {
+            let json = serde_json::to_string(&CapturePruneResponse { frames_deleted }).expect("Failed to serialize prune response");
+            write_json_response(stream, "200 OK", &json).await;
+        }
+        Err(e) => {
+            log_error!("Failed to prune captures: {}", e);
+            write_json_response(stream, "500 Internal Server Error", &serde_json::json!({ "error": e }).to_string()).await;
+        }
+    }
+}
+
+/// Streams one frame PNG. A frame never changes once written, so it is cached for good,
+/// keyed by instance and tick.
+async fn handle_get_capture_frame(stream: &mut AccessLoggedTcpStream, request: &str) {
+    let file_name = request.split_whitespace().nth(1).unwrap_or("").trim_start_matches("/api/captures/");
+    let (Some(store), Some(tick)) = (current_capture_store(), parse_frame_tick(file_name)) else {
+        write_json_response(stream, "404 Not Found", r#"{"error":"Capture frame not found"}"#).await;
+        return;
+    };
+    let etag = format!("\"{}-{}\"", store.instance_id(), tick);
+    if request_header(request, "if-none-match") == Some(etag.as_str()) {
+        let response = format!("HTTP/1.1 304 Not Modified\r\nETag: {}\r\nContent-Length: 0\r\n\r\n", etag);
+        let _ = stream.write_all(response.as_bytes()).await;
+        return;
+    }
+
+    let path = store.frame_path(tick);
+    let mut file = match tokio::fs::File::open(&path).await {
+        Ok(file) => file,
+        Err(_) => {
+            write_json_response(stream, "404 Not Found", r#"{"error":"Capture frame not found"}"#).await;
+            return;
+        }
+    };
+    let length = match file.metadata().await {
+        Ok(metadata) => metadata.len(),
+        Err(e) => {
+            log_error!("Failed to stat {}: {}", path.display(), e);
+            write_json_response(stream, "500 Internal Server Error", r#"{"error":"Failed to read capture frame"}"#).await;
+            return;
+        }
+    };
+    let header = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: image/png\r\nCache-Control: public, max-age=31536000, immutable\r\nETag: {}\r\nContent-Length: {}\r\n\r\n",
+        etag,
+        length
+    );
+    if stream.write_all(header.as_bytes()).await.is_err() {
+        return;
+    }
+    if let Err(e) = tokio::io::copy(&mut file, stream).await {
+        log_error!("Failed to stream {}: {}", path.display(), e);
+    }
+}
+
+/// Value of a header in a raw HTTP request, matched case-insensitively
+fn request_header<'a>(request: &'a str, name: &str) -> Option<&'a str> {
+    request
+        .lines()
+        .skip(1)
+        .take_while(|line| !line.is_empty())
+        .find_map(|line| {
+            let (header, value) = line.split_once(':')?;
+            header.trim().eq_ignore_ascii_case(name).then(|| value.trim())
+        })
+}
+
+/// GET /ws: upgrades to the live feed WebSocket and serves it for as long as the client stays
+async fn handle_feed_upgrade(mut stream: AccessLoggedTcpStream, request: &str, scope: ApiScope) {
+    let upgrade = request_header(request, "Upgrade").is_some_and(|value| value.eq_ignore_ascii_case("websocket"));
+    let Some(key) = request_header(request, "Sec-WebSocket-Key").filter(|_| upgrade) else {
+        write_json_response(&mut stream, "400 Bad Request", r#"{"error":"Expected a WebSocket upgrade"}"#).await;
+        return;
+    };
+    let response = format!(
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+        derive_accept_key(key.as_bytes())
+    );
+    if stream.write_all(response.as_bytes()).await.is_err() {
+        return;
+    }
+    let ws = WebSocketStream::from_raw_socket(stream, Role::Server, None).await;
+    serve_feed(ws, scope).await;
+}
+
+/// The whole colony stitched from every shard's image as a PNG, for the viewer page
+async fn handle_get_colony_image(stream: &mut AccessLoggedTcpStream) {
+    let (current_tick, frame) = match current_colony_frame().await {
+        Ok(result) => result,
+        Err(e) => {
+            let error_json = serde_json::json!({ "error": e });
+            write_json_response(stream, "404 Not Found", &error_json.to_string()).await;
+            return;
+        }
+    };
+    let mut png = Vec::new();
+    if let Err(e) = frame.image.write_to(&mut std::io::Cursor::new(&mut png), image::ImageOutputFormat::Png) {
+        log_error!("Failed to encode colony image: {}", e);
+        write_json_response(stream, "500 Internal Server Error", r#"{"error":"Failed to encode colony image"}"#).await;
+        return;
+    }
+    let header = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: image/png\r\n{}: {}\r\n{}: {}\r\nContent-Length: {}\r\n\r\n",
+        COLONY_TICK_HEADER,
+        current_tick,
+        MISSING_SHARDS_HEADER,
+        frame.missing_shard_ids().join(","),
+        png.len()
+    );
+    if stream.write_all(header.as_bytes()).await.is_ok() {
+        let _ = stream.write_all(&png).await;
+    }
+}
+
+async fn handle_get_colony_config(stream: &mut AccessLoggedTcpStream) {
+    let run_config = CoordinatorContext::get_instance().get_coord_stored_info().run_config.clone();
+    match run_config {
+        Some(config) => {
+            let response = ColonyConfigResponse { config_hash: config.config_hash(), config };
+            let json = serde_json::to_string(&response).expect("Failed to serialize colony config");
+            write_json_response(stream, "200 OK", &json).await;
+        }
+        None => {
+            write_json_response(stream, "404 Not Found", r#"{"error":"Run configuration not recorded"}"#).await;
+        }
+    }
+}
+
+/// GET /api/run-summary, written once a run started with a target_tick reached it
+async fn handle_get_run_summary(stream: &mut AccessLoggedTcpStream) {
+    let instance_id = CoordinatorContext::get_instance().get_coord_stored_info().colony_instance_id.clone();
+    let Some(instance_id) = instance_id else {
+        write_json_response(stream, "404 Not Found", r#"{"error":"Colony instance ID is not set"}"#).await;
+        return;
+    };
+    match read_run_summary(&OutputPaths::get_instance().instance_dir(&instance_id)) {
+        Ok(Some(summary)) => {
+            let json = serde_json::to_string(&summary).expect("Failed to serialize run summary");
+            write_json_response(stream, "200 OK", &json).await;
+        }
+        Ok(None) => {
+            write_json_response(stream, "404 Not Found", r#"{"error":"The run has not completed"}"#).await;
+        }
+        Err(e) => {
+            let error_json = serde_json::json!({ "error": e });
+            write_json_response(stream, "500 Internal Server Error", &error_json.to_string()).await;
+        }
+    }
+}
+
+/// GET /api/export-run[?stats=N]: a tar.gz of the run's artifacts, streamed as it is assembled
+async fn handle_export_run(stream: &mut AccessLoggedTcpStream, request: &str) {
+    let stats_snapshots = match parse_query_param(request, "stats").map(|v| v.parse::<usize>()) {
+        None => DEFAULT_EXPORT_STATS_SNAPSHOTS,
+        Some(Ok(count)) => count,
+        Some(Err(_)) => {
+            write_json_response(stream, "400 Bad Request", r#"{"error":"Invalid stats count"}"#).await;
+            return;
+        }
+    };
+    let (status, instance_id) = {
+        let stored_info = CoordinatorContext::get_instance().get_coord_stored_info();
+        (stored_info.status.clone(), stored_info.colony_instance_id.clone())
+    };
+    if matches!(status, ColonyStatus::Initializing) {
+        write_json_response(stream, "409 Conflict", r#"{"error":"Colony start in progress"}"#).await;
+        return;
+    }
+    let Some(instance_id) = instance_id else {
+        write_json_response(stream, "404 Not Found", r#"{"error":"Colony instance ID is not set"}"#).await;
+        return;
+    };
+
+    let instance_dir = OutputPaths::get_instance().instance_dir(&instance_id);
+    let entries = match plan_export(&instance_dir, stats_snapshots) {
+        Ok(entries) => entries,
+        Err(e) => {
+            let error_json = serde_json::json!({ "error": e });
+            write_json_response(stream, "500 Internal Server Error", &error_json.to_string()).await;
+            return;
+        }
+    };
+
+    // Without a Content-Length the archive ends when the connection closes
+    let header = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/gzip\r\nContent-Disposition: attachment; filename=\"{}.tar.gz\"\r\nConnection: close\r\n\r\n",
+        instance_id
+    );
+    if stream.write_all(header.as_bytes()).await.is_err() {
+        return;
+    }
+    match write_run_export(&mut *stream, &instance_id, &entries).await {
+        Ok(_) => log!("Exported run {}", instance_id),
+        Err(e) => log_error!("Run export of {} failed: {}", instance_id, e),
+    }
+}
+
+/// GET /api/determinism-check?instance_b=..[&instance_a=..][&from_tick=..], instance_a defaults to the running colony
+async fn handle_determinism_check(stream: &mut AccessLoggedTcpStream, request: &str) {
+    let from_tick = match parse_query_param(request, "from_tick").map(|v| v.parse::<u64>()) {
+        None => 0,
+        Some(Ok(tick)) => tick,
+        Some(Err(_)) => {
+            write_json_response(stream, "400 Bad Request", r#"{"error":"from_tick must be a non-negative integer"}"#).await;
+            return;
+        }
+    };
+    let result = determinism_check(parse_query_param(request, "instance_a"), parse_query_param(request, "instance_b"), from_tick).await;
+    let (status, json) = match result {
+        Ok(response) => ("200 OK", serde_json::to_string(&response).expect("Failed to serialize determinism check")),
+        Err(DeterminismCheckError::MissingInstance) => ("400 Bad Request", r#"{"error":"instance_b parameter required"}"#.to_string()),
+        Err(DeterminismCheckError::InvalidInstance(id)) => ("400 Bad Request", serde_json::json!({ "error": format!("Invalid instance id: {}", id) }).to_string()),
+        Err(DeterminismCheckError::NoRunningColony) => ("404 Not Found", r#"{"error":"No running colony, pass instance_a"}"#.to_string()),
+        Err(DeterminismCheckError::NoHistory(id)) => ("404 Not Found", serde_json::json!({ "error": format!("No state hashes for instance {}", id) }).to_string()),
+    };
+    write_json_response(stream, status, &json).await;
+}
+
+/// Applies a ColonyEvent given as JSON, e.g. {"ChangeExtraFoodPerTick":2}, like a generated one
+async fn handle_apply_colony_event(stream: &mut AccessLoggedTcpStream, request: &str) {
+    if !is_colony_already_started() {
+        write_json_response(stream, "404 Not Found", r#"{"error":"Colony not initialized"}"#).await;
+        return;
+    }
+    if is_run_completed() {
+        write_json_response(stream, "409 Conflict", r#"{"error":"The run reached its target tick and completed"}"#).await;
+        return;
+    }
+    let event = match serde_json::from_str::<ColonyEvent>(request_body(request)) {
+        Ok(event) => event,
+        Err(e) => {
+            let error_json = serde_json::json!({ "error": format!("Invalid colony event: {}", e) });
+            write_json_response(stream, "400 Bad Request", &error_json.to_string()).await;
+            return;
+        }
+    };
+    let schema = event_type_schema(&event);
+    if schema.generated_only {
+        let error_json = serde_json::json!({ "error": format!("{} is only generated by the ticker", schema.name) });
+        write_json_response(stream, "400 Bad Request", &error_json.to_string()).await;
+        return;
+    }
+    let colony_tick = latest_max_tick().unwrap_or(0);
+    log_event(&event, colony_tick);
+    // Delivery blocks while it retries failed backends
+    match tokio::task::spawn_blocking(move || apply_colony_event(event, colony_tick, None)).await {
+        Ok(Ok(event_description)) => {
+            let json = serde_json::to_string(&event_description).expect("Failed to serialize colony event");
+            write_json_response(stream, "200 OK", &json).await;
+        }
+        Ok(Err(e)) => {
+            let error_json = serde_json::json!({ "error": e });
+            write_json_response(stream, "400 Bad Request", &error_json.to_string()).await;
+        }
+        Err(e) => {
+            log_error!("Applying colony event failed: {}", e);
+            write_json_response(stream, "500 Internal Server Error", r#"{"error":"Applying the event failed"}"#).await;
+        }
+    }
+}
+
+/// Writes a frame of the current colony right away, however little changed since the last one
+async fn handle_capture_colony(stream: &mut AccessLoggedTcpStream) {
+    let Some(store) = current_capture_store() else {
+        write_json_response(stream, "404 Not Found", r#"{"error":"Colony instance not initialized"}"#).await;
+        return;
+    };
+    match capture_colony_on_demand().await {
+        Some(tick) => {
+            let json = serde_json::json!({ "tick": tick, "path": store.frame_path(tick) });
+            write_json_response(stream, "200 OK", &json.to_string()).await;
+        }
+        None => {
+            write_json_response(stream, "503 Service Unavailable", r#"{"error":"No frame written, see the coordinator log"}"#).await;
+        }
+    }
+}
+
+/// Per-shard drill-down of one broadcast event, fetched from the backends that applied it
+async fn handle_get_colony_event_detail(stream: &mut AccessLoggedTcpStream, request: &str) {
+    let path = request.split_whitespace().nth(1).unwrap_or("").trim_start_matches("/api/colony-events/");
+    let Ok(event_id) = Uuid::parse_str(path.split('?').next().unwrap_or("")) else {
+        write_json_response(stream, "400 Bad Request", r#"{"error":"Invalid event id"}"#).await;
+        return;
+    };
+    let Some(detail) = colony_event_detail(event_id).await else {
+        write_json_response(stream, "404 Not Found", r#"{"error":"Event not found"}"#).await;
+        return;
+    };
+    let json = serde_json::to_string(&detail).expect("Failed to serialize colony event detail");
+    write_json_response(stream, "200 OK", &json).await;
+}
+
+async fn handle_get_colony_events(stream: &mut AccessLoggedTcpStream, request: &str) {
     // Check if colony is initialized
     if !is_colony_already_started() {
         let error_json = r#"{"error":"Colony not initialized"}"#;
@@ -216,7 +1289,7 @@ async fn handle_get_colony_events(stream: &mut tokio::net::TcpStream, request: &
 }
 
 
-async fn handle_get_topology(stream: &mut tokio::net::TcpStream) {
+async fn handle_get_topology(stream: &mut AccessLoggedTcpStream, scope: ApiScope) {
     // Check colony status first
     let context = CoordinatorContext::get_instance();
     let status = {
@@ -264,11 +1337,23 @@ async fn handle_get_topology(stream: &mut tokio::net::TcpStream) {
         #[serde(flatten)]
         topology: ClusterTopology,
         colony_instance_id: Option<String>,
+        colony_status: ColonyStatus,
     }
     
+    // Observers only see public hostnames, never the private addresses used for RPC
+    let topology = match scope {
+        ApiScope::Admin => (*topology).clone(),
+        ApiScope::Observer => {
+            let mut addresses = ssm::discover_backends().await;
+            addresses.extend(ssm::discover_coordinator().await);
+            topology.to_observer_view(&addresses)
+        }
+    };
+    
     let response_obj = TopologyResponse {
-        topology: (*topology).clone(),
+        topology,
         colony_instance_id: instance_id,
+        colony_status: status,
     };
     
     match serde_json::to_string(&response_obj) {
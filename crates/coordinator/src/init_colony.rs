@@ -1,37 +1,122 @@
 use shared::be_api::{
     BackendRequest, BackendResponse, ColonyLifeRules, GetColonyInfoRequest, 
     GetColonyInfoResponse, InitColonyRequest, InitColonyResponse, 
-    InitColonyShardRequest, InitColonyShardResponse, Shard, StartTickingRequest, StartTickingResponse
+    InitColonyShardRequest, InitColonyShardResponse, RefreshTopologyRequest, RefreshTopologyResponse, SeedingOptions, Shard, ShardInitProgress,
+    StartTickingRequest, StartTickingResponse
 };
 use shared::cluster_topology::HostInfo;
 use std::collections::HashSet;
 use shared::cluster_topology::ClusterTopology;
 use shared::{log, log_error};
+use shared::backend_communication::perform_handshake_async;
 use tokio::net::TcpStream;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use bincode;
 use backoff::{ExponentialBackoff, Error as BackoffError};
 use std::time::Duration;
 use std::sync::Arc;
+use crate::colony_step::is_fast_forward;
 use crate::coordinator_storage::{CoordinatorStoredInfo, ColonyStatus};
 use crate::coordinator_context::CoordinatorContext;
 use crate::event_logging;
+use crate::global_topography::{mark_awaiting_topography, GlobalTopography, GlobalTopographyInfo};
+use crate::colony_start::SHARD_ASSIGNMENT_STRATEGY;
+use crate::colony_verification::{http_get, verify_colony, VerificationTrigger};
+use crate::colony_capture::get_backend_http_port;
+use crate::coordinator_error::CoordinatorError;
+use crate::backend_status::query_backend;
+use crate::shard_leases::with_lease_table;
+use crate::lifecycle_events::record_lifecycle_event;
+use crate::tick_monitor::{latest_max_tick, unix_time_ms};
+use shared::colony_event_shared::{COLONY_STARTED_EVENT, FAILOVER_EVENT, TICKING_STARTED_EVENT};
+use shared::colony_model::ExtraFoodPattern;
+use shared::coordinator_api::ColonyRunConfig;
+use shared::utils::{new_random_generator, StableHasher};
+use rand::Rng;
 
-pub const COLONY_LIFE_INITIAL_RULES: ColonyLifeRules = ColonyLifeRules { 
-    health_cost_per_size_unit: 2,
-    eat_capacity_per_size_unit: 5,
-    health_cost_if_can_kill: 10,
-    health_cost_if_can_move: 5,
-    mutation_chance: 100,
-    random_death_chance: 100,
-};
+/// Shards colony-start initialized so far and the one it waits on, for GET /colony-start
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ShardInitCounter {
+    pub done: usize,
+    pub total: usize,
+    pub current: Option<Shard>,
+}
+
+/// "shard 3/20: 64% generated" for the shard being initialized, with the progress its backend
+/// reports; None when no shard is
+pub async fn shard_init_summary() -> Option<(ShardInitCounter, Option<f32>, String)> {
+    let counter = *CoordinatorContext::get_instance().shard_init_counter();
+    let shard = counter.current?;
+    let progress = fetch_shard_init_progress(shard).await;
+    let summary = match progress {
+        Some(progress) => format!("shard {}/{}: {:.0}% generated", counter.done + 1, counter.total, progress * 100.0),
+        None => format!("shard {}/{}", counter.done + 1, counter.total),
+    };
+    Some((counter, progress, summary))
+}
+
+async fn fetch_shard_init_progress(shard: Shard) -> Option<f32> {
+    let topology = ClusterTopology::get_instance()?;
+    let host_info = topology.get_host_for_shard(&shard)?;
+    let http_port = get_backend_http_port(host_info).await?;
+    let url = format!("http://{}:{}/api/shard/{}/init-progress", host_info.hostname, http_port, shard.to_id());
+    let body = http_get(url).await.ok()?;
+    serde_json::from_slice::<ShardInitProgress>(&body).ok().map(|progress| progress.progress)
+}
 
+/// Lives in shared so tests of every crate can build on the real initial rules
+pub use shared::colony_model::COLONY_LIFE_INITIAL_RULES;
+
+/// Shard terrain up to this size travels in the InitColonyShard call itself;
+/// larger payloads follow in their own InitShardTopography call
+pub const INLINE_TOPOGRAPHY_MAX_BYTES: usize = 1024 * 1024;
+
+/// Upper bound for one backend reply during colony start; a shard with inline terrain takes the longest
+const INIT_RPC_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How a new shard gets its terrain
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShardTerrain {
+    /// Sent along with the shard
+    Inline(Vec<u8>),
+    /// Sent afterwards with InitShardTopography; the backend holds the shard until then
+    Deferred,
+    /// Default terrain, nothing follows
+    Default,
+}
+
+pub fn sends_topography_inline(payload_len: usize) -> bool {
+    payload_len <= INLINE_TOPOGRAPHY_MAX_BYTES
+}
 
 fn generate_shards(topology: &ClusterTopology) -> Vec<Shard> {
     topology.get_all_shards()
 }
 
-async fn send_message<T: serde::Serialize>(stream: &mut TcpStream, msg: &T) {
+/// Topography parameters for the colony covered by the given topology
+pub fn colony_topography_info(topology: &ClusterTopology) -> GlobalTopographyInfo {
+    GlobalTopographyInfo {
+        total_width: (topology.width_in_shards() * topology.shard_width()) as usize,
+        total_height: (topology.height_in_shards() * topology.shard_height()) as usize,
+        shard_width: topology.shard_width() as usize,
+        shard_height: topology.shard_height() as usize,
+
+        base_elevation: 5,
+        river_elevation_range: 45, 
+        river_influence_distance: 175.0,
+        river_count_range: (10, 20),
+        river_segments_range: (30, 4045),
+        river_step_length_range: (20.0, 30.0),
+        river_direction_change: 0.6,
+        smoothing_iterations: 4,
+        sanctuary_count: 3,
+        sanctuary_radius_range: (20, 40),
+        seed: None,
+        extra_food_pattern: None,
+    }
+}
+
+pub(crate) async fn send_message<T: serde::Serialize>(stream: &mut TcpStream, msg: &T) {
     let encoded = bincode::serialize(msg).expect("Failed to serialize message");
     let len = (encoded.len() as u32).to_be_bytes();
     stream.write_all(&len).await.expect("Failed to write length");
@@ -39,7 +124,7 @@ async fn send_message<T: serde::Serialize>(stream: &mut TcpStream, msg: &T) {
 }
 
 // Helper to receive a length-prefixed message
-async fn receive_message<T: serde::de::DeserializeOwned>(stream: &mut TcpStream) -> Option<T> {
+pub(crate) async fn receive_message<T: serde::de::DeserializeOwned>(stream: &mut TcpStream) -> Option<T> {
     let mut len_buf = [0u8; 4];
     if stream.read_exact(&mut len_buf).await.is_err() {
         log_error!("Failed to read message length");
@@ -54,20 +139,42 @@ async fn receive_message<T: serde::de::DeserializeOwned>(stream: &mut TcpStream)
     bincode::deserialize(&buf).ok()
 } 
 
-async fn get_colony_info(stream: &mut TcpStream) -> Option<GetColonyInfoResponse> {
+/// Sends one request on an open connection and reads the reply, waiting at most INIT_RPC_TIMEOUT
+pub(crate) async fn request_backend(stream: &mut TcpStream, host: &HostInfo, op: &'static str, request: &BackendRequest) -> Result<BackendResponse, CoordinatorError> {
+    let address = host.to_address();
+    let encoded = bincode::serialize(request).expect("Failed to serialize message");
+    let len = (encoded.len() as u32).to_be_bytes();
+    stream.write_all(&len).await.map_err(|e| CoordinatorError::from_io(&address, op, &e))?;
+    stream.write_all(&encoded).await.map_err(|e| CoordinatorError::from_io(&address, op, &e))?;
+
+    let read_response = async {
+        let mut len_buf = [0u8; 4];
+        stream.read_exact(&mut len_buf).await?;
+        let mut buf = vec![0u8; u32::from_be_bytes(len_buf) as usize];
+        stream.read_exact(&mut buf).await?;
+        Ok::<_, std::io::Error>(buf)
+    };
+    let buf = tokio::time::timeout(INIT_RPC_TIMEOUT, read_response).await
+        .map_err(|_| CoordinatorError::Timeout { host: address.clone(), op })?
+        .map_err(|e| CoordinatorError::from_io(&address, op, &e))?;
+    bincode::deserialize(&buf).map_err(|_| CoordinatorError::DeserializationFailed { host: address, op })
+}
+
+/// connect_to_backend with the failure typed
+pub(crate) async fn connect_backend(host: &HostInfo) -> Result<TcpStream, CoordinatorError> {
+    connect_to_backend(&host.hostname, host.port).await
+        .map_err(|source| CoordinatorError::Connect { host: host.to_address(), source })
+}
+
+async fn get_colony_info(stream: &mut TcpStream, host: &HostInfo) -> Result<GetColonyInfoResponse, CoordinatorError> {
     let req = BackendRequest::GetColonyInfo(GetColonyInfoRequest);
-    send_message(stream, &req).await;
-    if let Some(response) = receive_message::<BackendResponse>(stream).await {
-        match response {
-            BackendResponse::GetColonyInfo(info) => Some(info),
-            _ => None,
-        }
-    } else {
-        None
+    match request_backend(stream, host, "GetColonyInfo", &req).await? {
+        BackendResponse::GetColonyInfo(info) => Ok(info),
+        _ => Err(CoordinatorError::UnexpectedResponse { host: host.to_address(), op: "GetColonyInfo" }),
     }
 }
 
-async fn connect_to_backend(hostname: &str, port: u16) -> Result<TcpStream, std::io::Error> {
+pub(crate) async fn connect_to_backend(hostname: &str, port: u16) -> Result<TcpStream, std::io::Error> {
     let addr = format!("{}:{}", hostname, port);
     
     // Configure exponential backoff: start with 100ms, max 2s, max 5 retries
@@ -80,127 +187,152 @@ async fn connect_to_backend(hostname: &str, port: u16) -> Result<TcpStream, std:
     };
     
     let operation = || async {
-        match TcpStream::connect(&addr).await {
-            Ok(stream) => Ok(stream),
+        let mut stream = match TcpStream::connect(&addr).await {
+            Ok(stream) => stream,
             Err(e) => {
                 log_error!("Failed to connect to backend at {}: {}", addr, e);
-                Err(BackoffError::transient(e))
+                return Err(BackoffError::transient(e));
             }
-        }
+        };
+        // A rejected handshake will not succeed on retry
+        perform_handshake_async(&mut stream).await.map_err(|e| {
+            log_error!("Handshake with backend at {} failed: {}", addr, e);
+            BackoffError::permanent(std::io::Error::other(e.to_string()))
+        })?;
+        Ok(stream)
     };
     
     backoff::future::retry(backoff, operation).await
 }
 
-async fn send_init_colony(stream: &mut TcpStream, topology: Arc<ClusterTopology>) {
+async fn send_init_colony(stream: &mut TcpStream, host: &HostInfo, topology: Arc<ClusterTopology>) -> Result<(), CoordinatorError> {
     let init = BackendRequest::InitColony(InitColonyRequest { 
         width: topology.width_in_shards() * topology.shard_width(), 
         height: topology.height_in_shards() * topology.shard_height(), 
         colony_life_rules: COLONY_LIFE_INITIAL_RULES 
     });
-    send_message(stream, &init).await;
-
-    if let Some(response) = receive_message::<BackendResponse>(stream).await {
-        match response {
-            BackendResponse::InitColony(InitColonyResponse::Ok) => log!("Colony initialized"),
-            BackendResponse::InitColony(InitColonyResponse::ColonyAlreadyInitialized) => log!("Colony already initialized"),
-            _ => log_error!("Unexpected response"),
-        }
+    match request_backend(stream, host, "InitColony", &init).await? {
+        BackendResponse::InitColony(InitColonyResponse::Ok) => log!("Colony initialized"),
+        BackendResponse::InitColony(InitColonyResponse::ColonyAlreadyInitialized) => log!("Colony already initialized"),
+        BackendResponse::InitColony(InitColonyResponse::DimensionMismatch(reason)) => {
+            return Err(CoordinatorError::DimensionMismatch { host: host.to_address(), reason });
+        },
+        _ => return Err(CoordinatorError::UnexpectedResponse { host: host.to_address(), op: "InitColony" }),
     }
+    Ok(())
 }
 
-async fn send_init_colony_shard(stream: &mut TcpStream, shard: Shard, topology: Arc<ClusterTopology>) {
+pub(crate) async fn send_init_colony_shard(stream: &mut TcpStream, host: &HostInfo, shard: Shard, topology: Arc<ClusterTopology>, colony_life_rules: ColonyLifeRules, seeding: SeedingOptions, terrain: ShardTerrain) -> Result<(), CoordinatorError> {
+    colony_life_rules.validate().map_err(CoordinatorError::InvalidRules)?;
+
     // Clone the topology to send to backend
     // Note: ClusterTopology is now Clone and serializable, so we can clone it directly
     let topology_clone = (*topology).clone();
     
+    let awaiting_topography = terrain == ShardTerrain::Deferred;
+    let topography_data = match terrain {
+        ShardTerrain::Inline(data) => Some(data),
+        ShardTerrain::Deferred | ShardTerrain::Default => None,
+    };
     let req = BackendRequest::InitColonyShard(InitColonyShardRequest { 
         shard: shard, 
-        colony_life_rules: COLONY_LIFE_INITIAL_RULES,
+        colony_life_rules,
         topology: Some(topology_clone),
+        seeding,
+        topography_data,
+        awaiting_topography,
+        colony_instance_id: CoordinatorContext::get_instance().get_coord_stored_info().colony_instance_id.clone(),
+        lease_epoch: with_lease_table(|table| table.assign(shard, host)),
     });
-    send_message(stream, &req).await;
-    if let Some(response) = receive_message::<BackendResponse>(stream).await {
-        match response {
-            BackendResponse::InitColonyShard(InitColonyShardResponse::Ok) => {
-            },
-            BackendResponse::InitColonyShard(InitColonyShardResponse::ShardAlreadyInitialized) => {
-            },
-            BackendResponse::InitColonyShard(InitColonyShardResponse::ColonyNotInitialized) => {
-                log_error!("Colony not initialized");
-            },
-            BackendResponse::InitColonyShard(InitColonyShardResponse::Error) => {
-                log_error!("Error initializing shard (missing or invalid topology)");
-            },
-            _ => log_error!("Unexpected response to InitColonyShard"),
-        }
-    }
+    let rejection = match request_backend(stream, host, "InitColonyShard", &req).await? {
+        BackendResponse::InitColonyShard(InitColonyShardResponse::Ok) => {
+            if awaiting_topography {
+                mark_awaiting_topography(&shard);
+            }
+            return Ok(());
+        },
+        BackendResponse::InitColonyShard(InitColonyShardResponse::ShardAlreadyInitialized) => return Ok(()),
+        BackendResponse::InitColonyShard(InitColonyShardResponse::ColonyNotInitialized) => "colony not initialized".to_string(),
+        BackendResponse::InitColonyShard(InitColonyShardResponse::InvalidShardDimensions) => {
+            "shard is outside the colony dimensions known to the backend".to_string()
+        },
+        BackendResponse::InitColonyShard(InitColonyShardResponse::InvalidRules(e)) => format!("invalid rules: {}", e),
+        BackendResponse::InitColonyShard(InitColonyShardResponse::InvalidSeeding(e)) => format!("invalid seeding: {}", e),
+        BackendResponse::InitColonyShard(InitColonyShardResponse::InvalidTopography(e)) => format!("invalid topography: {}", e),
+        BackendResponse::InitColonyShard(InitColonyShardResponse::TopologyConflict(e)) => format!("topology conflict: {}", e),
+        BackendResponse::InitColonyShard(InitColonyShardResponse::DimensionMismatch(reason)) => {
+            return Err(CoordinatorError::DimensionMismatch { host: host.to_address(), reason });
+        },
+        BackendResponse::InitColonyShard(InitColonyShardResponse::Error) => "missing or invalid topology".to_string(),
+        _ => return Err(CoordinatorError::UnexpectedResponse { host: host.to_address(), op: "InitColonyShard" }),
+    };
+    Err(CoordinatorError::BackendRejected { host: host.to_address(), shard: Some(shard), response: rejection })
 }
 
-pub async fn initialize_colony() {
+/// Initializes the colony and every shard on the backends of the installed topology, then
+/// starts ticking. Stops at the first backend that cannot be reached or refuses a shard.
+/// A new colony gets its extra food from extra_food_pattern when given, else from rivers.
+pub async fn initialize_colony(seeding: SeedingOptions, extra_food_pattern: Option<ExtraFoodPattern>) -> Result<(), CoordinatorError> {
     // Step 1: Get or initialize context
     // Note: Context may already be initialized, so we just get the instance
     // and reset the stored info if needed
     let context = CoordinatorContext::get_instance();
     
     // Reset stored info to fresh state, but preserve instance ID and idempotency key
-    // (these are set before initialize_colony is called)
+    // (these are set before initialize_colony is called). The run configuration is not kept,
+    // the new colony records its own.
     {
         let mut stored_info = context.get_coord_stored_info();
         let preserved_instance_id = stored_info.colony_instance_id.clone();
         let preserved_idempotency_key = stored_info.colony_start_idempotency_key.clone();
         let preserved_deployment_mode = stored_info.deployment_mode.clone();
+        let preserved_target_tick = stored_info.target_tick;
+        let preserved_warmup_ticks = stored_info.warmup_ticks;
         *stored_info = CoordinatorStoredInfo::new();
+        stored_info.target_tick = preserved_target_tick;
+        stored_info.warmup_ticks = preserved_warmup_ticks;
         stored_info.colony_instance_id = preserved_instance_id;
         stored_info.colony_start_idempotency_key = preserved_idempotency_key;
         stored_info.deployment_mode = preserved_deployment_mode;
     }
+    // The previous colony's creature counts would hold back or let through the new one's events
+    context.population_guard().reset();
     
     log!("Starting colony initialization with status: {:?}", context.get_coord_stored_info().status);
     
-    let topology = match ClusterTopology::get_instance() {
-        Some(t) => t,
-        None => {
-            log_error!("Topology not initialized. Cannot initialize colony.");
-            return;
-        }
-    };
+    let topology = ClusterTopology::get_instance().ok_or(CoordinatorError::TopologyMissing)?;
     let backend_hosts = topology.get_all_backend_hosts();
+    let first_backend = backend_hosts.first().ok_or(CoordinatorError::NoBackendsAvailable)?;
     
     // Step 1: Initialize colony if not already done - should ALWAYS be done
     log!("Step 1: Initializing colony");
     
     // Try to get colony info from the first backend
-    let mut stream = match connect_to_backend(&backend_hosts[0].hostname, backend_hosts[0].port).await {
-        Ok(stream) => stream,
-        Err(e) => {
-            log_error!("Failed to connect to backend {}:{} after retries: {}", 
-                      backend_hosts[0].hostname, backend_hosts[0].port, e);
-            return;
-        }
-    };
-    let colony_info = get_colony_info(&mut stream).await;
+    let mut stream = connect_backend(first_backend).await?;
+    let colony_info = get_colony_info(&mut stream, first_backend).await?;
     log!("Colony info: {:?}", colony_info);
+    let mut verification_trigger = VerificationTrigger::ColonyStart;
     
     match colony_info {
-        Some(GetColonyInfoResponse::Ok { width, height, shards: _, colony_life_rules, .. }) => {
-            let mut coord_info = context.get_coord_stored_info();
-            coord_info.colony_width = Some(width);
-            coord_info.colony_height = Some(height);
-            coord_info.colony_life_rules = colony_life_rules;
+        GetColonyInfoResponse::Ok { width, height, shards: _, colony_life_rules, .. } => {
+            {
+                let mut coord_info = context.get_coord_stored_info();
+                coord_info.colony_width = Some(width);
+                coord_info.colony_height = Some(height);
+                coord_info.colony_life_rules = colony_life_rules.map(|rules| *rules);
+            }
+            // The colony outlived its coordinator; the backends still name the old one
+            refresh_backend_topologies(&topology).await;
+            adopt_backend_leases(&topology).await;
+            verification_trigger = VerificationTrigger::Failover;
+            record_lifecycle_event(FAILOVER_EVENT, latest_max_tick().unwrap_or(0),
+                                   format!("Took over the running colony on {} backends", backend_hosts.len()));
         },
-        Some(GetColonyInfoResponse::ColonyNotInitialized) | None => {
+        GetColonyInfoResponse::ColonyNotInitialized => {
             // Initialize colony on all backends
             for backend_host in backend_hosts.iter() {
-                let mut stream = match connect_to_backend(&backend_host.hostname, backend_host.port).await {
-                    Ok(stream) => stream,
-                    Err(e) => {
-                        log_error!("Failed to connect to backend {}:{} after retries: {}", 
-                                  backend_host.hostname, backend_host.port, e);
-                        continue;
-                    }
-                };
-                send_init_colony(&mut stream, topology.clone()).await;
+                let mut stream = connect_backend(backend_host).await?;
+                send_init_colony(&mut stream, backend_host, topology.clone()).await?;
             }
             let mut coord_info = context.get_coord_stored_info();
             coord_info.colony_width = Some(topology.width_in_shards() * topology.shard_width());
@@ -209,54 +341,52 @@ pub async fn initialize_colony() {
         }
     }
     
-    // Step 2: Initialize shards - should ALWAYS be done
-    log!("Step 2: Initializing shards");
-    
-    let all_shards = generate_shards(&topology);
-    
-    // Initialize shards on their respective backends
-    for shard in all_shards.iter() {
-        if let Some(host_info) = topology.get_host_for_shard(shard) {
-            let mut stream = match connect_to_backend(&host_info.hostname, host_info.port).await {
-                Ok(stream) => stream,
-                Err(e) => {
-                    log_error!("Failed to connect to backend {}:{} for shard {:?} after retries: {}", 
-                              host_info.hostname, host_info.port, shard, e);
-                    continue;
-                }
-            };
-            send_init_colony_shard(&mut stream, *shard, topology.clone()).await;
-        } else {
-            log_error!("No backend found for shard {:?}", shard);
-        }
-    }    
+    // Pin the seed so the recorded run configuration reproduces the initial creatures
+    let seeding = SeedingOptions {
+        seed: Some(seeding.seed.unwrap_or_else(|| new_random_generator().gen())),
+        ..seeding
+    };
 
-    // Step 3: Initialize topography
+    // Step 2: Initialize shards - should ALWAYS be done. A new colony also gets its
+    // topography here, so no shard ever runs on the default terrain.
+    *context.shard_init_counter() = ShardInitCounter { done: 0, total: topology.get_all_shards().len(), current: None };
     if matches!(context.get_coord_stored_info().status, ColonyStatus::NotInitialized) {
-        log!("Step 3: Initializing topography");
+        log!("Step 2: Initializing shards with topography");
         
-        use crate::global_topography::{GlobalTopography, GlobalTopographyInfo};
-        let topography_info = GlobalTopographyInfo {
-            total_width: (topology.width_in_shards() * topology.shard_width()) as usize,
-            total_height: (topology.height_in_shards() * topology.shard_height()) as usize,
-            shard_width: topology.shard_width() as usize,
-            shard_height: topology.shard_height() as usize,
-
-            base_elevation: 5,
-            river_elevation_range: 45, 
-            river_influence_distance: 175.0,
-            river_count_range: (10, 20),
-            river_segments_range: (30, 4045),
-            river_step_length_range: (20.0, 30.0),
-            river_direction_change: 0.6,
-            smoothing_iterations: 4,
-        };
-        GlobalTopography::new(topography_info).generate_topography().await;
+        let seed: u64 = new_random_generator().gen();
+        let mut topography_info = colony_topography_info(&topology);
+        topography_info.seed = Some(seed);
+        topography_info.extra_food_pattern = extra_food_pattern;
+        let topography = GlobalTopography::new(topography_info);
+        let topography_hash = initialize_shards_with_topography(&topography, &topology, seeding).await?;
         
         let mut coord_stored_info = context.get_coord_stored_info();
         coord_stored_info.status = ColonyStatus::TopographyInitialized;
+        coord_stored_info.run_started_at_ms = Some(unix_time_ms());
+        let run_config = build_run_config(&coord_stored_info, &topology, &topography, seed, topography_hash, seeding);
+        log!("Run configuration recorded, config hash {}", run_config.config_hash());
+        if let Err(e) = event_logging::write_run_config_json(&run_config) {
+            log_error!("Failed to write run configuration JSON: {}", e);
+        }
+        let description = format!("{}x{} colony of {} shards on {} backends, config hash {}",
+                                  run_config.colony_width, run_config.colony_height,
+                                  run_config.width_in_shards * run_config.height_in_shards, run_config.backend_count, run_config.config_hash());
+        coord_stored_info.record_run_config(run_config);
+        drop(coord_stored_info);
+        record_lifecycle_event(COLONY_STARTED_EVENT, 0, description);
+    } else {
+        log!("Step 2: Initializing shards");
+        for shard in generate_shards(&topology).iter() {
+            init_shard_on_backend(&topology, *shard, seeding, ShardTerrain::Default).await?;
+        }
     }
     
+    let (colony_width, colony_height) = {
+        let stored_info = context.get_coord_stored_info();
+        (stored_info.colony_width.unwrap_or(0), stored_info.colony_height.unwrap_or(0))
+    };
+    crate::biomes::init_biomes(backend_hosts, colony_width, colony_height).await;
+    
     log!("Colony initialization completed with status: {:?}", context.get_coord_stored_info().status);
     
     // Log colony creation event
@@ -265,24 +395,132 @@ pub async fn initialize_colony() {
         log_error!("Failed to write colony creation event JSON: {}", e);
     }
     
-    // Step 4: Start colony ticking (coordinator ticker + notify all backends)
-    start_colony_ticking().await;
+    // Step 3: Start colony ticking (coordinator ticker + notify all backends)
+    let ticking = start_colony_ticking().await;
+
+    // Step 4: Audit the cluster, the outcome is logged and recorded as a colony event
+    if let Err(e) = verify_colony(verification_trigger).await {
+        log_error!("Skipping colony verification: {:?}", e);
+    }
+    ticking
+}
+
+/// Hands this coordinator's topology to every backend of a colony that is already running
+async fn refresh_backend_topologies(topology: &Arc<ClusterTopology>) {
+    let colony_instance_id = CoordinatorContext::get_instance().get_coord_stored_info().colony_instance_id.clone();
+    for backend_host in topology.get_all_backend_hosts() {
+        match refresh_backend_topology(backend_host, topology, colony_instance_id.clone()).await {
+            Ok(true) => log!("Backend {} now uses this coordinator's topology", backend_host.to_address()),
+            Ok(false) => {}
+            Err(e) => log_error!("Failed to refresh the topology of backend {}: {}", backend_host.to_address(), e),
+        }
+    }
+}
+
+/// Takes over the shard leases the backends of a running colony hold, so the shards keep their epochs
+async fn adopt_backend_leases(topology: &ClusterTopology) {
+    for backend_host in topology.get_all_backend_hosts() {
+        match query_backend(backend_host).await {
+            Ok(report) => with_lease_table(|table| {
+                for lease in report.leases {
+                    table.adopt(lease, backend_host, topology.get_host_for_shard(&lease.shard));
+                }
+            }),
+            Err(e) => log_error!("Failed to read the shard leases of backend {}: {}", backend_host.to_address(), e),
+        }
+    }
+}
+
+/// Whether the backend replaced its topology
+async fn refresh_backend_topology(backend_host: &HostInfo, topology: &ClusterTopology, colony_instance_id: Option<String>) -> Result<bool, CoordinatorError> {
+    let mut stream = connect_backend(backend_host).await?;
+    let request = BackendRequest::RefreshTopology(RefreshTopologyRequest {
+        topology: topology.clone(),
+        colony_instance_id,
+    });
+    match request_backend(&mut stream, backend_host, "RefreshTopology", &request).await? {
+        BackendResponse::RefreshTopology(RefreshTopologyResponse::Ok { replaced }) => Ok(replaced),
+        BackendResponse::RefreshTopology(RefreshTopologyResponse::TopologyNotInitialized) => {
+            log!("Backend {} has no topology yet", backend_host.to_address());
+            Ok(false)
+        }
+        BackendResponse::RefreshTopology(RefreshTopologyResponse::TopologyConflict(e)) => {
+            Err(CoordinatorError::BackendRejected { host: backend_host.to_address(), shard: None, response: e })
+        }
+        _ => Err(CoordinatorError::UnexpectedResponse { host: backend_host.to_address(), op: "RefreshTopology" }),
+    }
+}
+
+async fn init_shard_on_backend(topology: &Arc<ClusterTopology>, shard: Shard, seeding: SeedingOptions, terrain: ShardTerrain) -> Result<(), CoordinatorError> {
+    let host_info = topology.get_host_for_shard(&shard).ok_or(CoordinatorError::NoBackendForShard { shard })?;
+    CoordinatorContext::get_instance().shard_init_counter().current = Some(shard);
+    let result = async {
+        let mut stream = connect_backend(host_info).await?;
+        send_init_colony_shard(&mut stream, host_info, shard, topology.clone(), COLONY_LIFE_INITIAL_RULES, seeding, terrain).await
+    }.await;
+    let mut counter = CoordinatorContext::get_instance().shard_init_counter();
+    counter.current = None;
+    if result.is_ok() {
+        counter.done += 1;
+    }
+    result
+}
+
+/// Creates every shard together with its terrain, one row of shards at a time. Small
+/// payloads go in the InitColonyShard call; larger ones follow right after it, with the
+/// backend holding the shard until they arrive. Returns the hash of the global image,
+/// the same as GlobalTopography::generate_topography.
+async fn initialize_shards_with_topography(topography: &GlobalTopography, topology: &Arc<ClusterTopology>, seeding: SeedingOptions) -> Result<String, CoordinatorError> {
+    let field = topography.field();
+    let mut hasher = StableHasher::new();
+    for block_y in 0..field.block_count() {
+        let block = field.row_block(block_y);
+        hasher.update(&block);
+
+        for (shard, shard_data) in field.shard_payloads(block_y, &block) {
+            if sends_topography_inline(shard_data.len()) {
+                init_shard_on_backend(topology, shard, seeding, ShardTerrain::Inline(shard_data)).await?;
+            } else {
+                init_shard_on_backend(topology, shard, seeding, ShardTerrain::Deferred).await?;
+                topography.send_topography_to_local_shard(shard, shard_data).await;
+            }
+        }
+    }
+    log!("Shards initialized with topography");
+    Ok(hasher.finish_hex())
+}
+
+fn build_run_config(stored_info: &CoordinatorStoredInfo, topology: &ClusterTopology, topography: &GlobalTopography, topography_seed: u64, topography_hash: String, seeding: SeedingOptions) -> ColonyRunConfig {
+    ColonyRunConfig {
+        colony_instance_id: stored_info.colony_instance_id.clone(),
+        deployment_mode: stored_info.deployment_mode.clone().unwrap_or_else(|| "localhost".to_string()),
+        colony_width: topology.width_in_shards() * topology.shard_width(),
+        colony_height: topology.height_in_shards() * topology.shard_height(),
+        width_in_shards: topology.width_in_shards(),
+        height_in_shards: topology.height_in_shards(),
+        shard_width: topology.shard_width(),
+        shard_height: topology.shard_height(),
+        backend_count: topology.get_all_backend_hosts().len(),
+        assignment_strategy: SHARD_ASSIGNMENT_STRATEGY.to_string(),
+        topography_seed,
+        topography_source: topography.source().to_string(),
+        topography_hash,
+        initial_rules: COLONY_LIFE_INITIAL_RULES,
+        seeding,
+        extra_food_pattern: topography.extra_food_pattern(),
+    }
 }
 
-pub async fn start_colony_ticking() {
+/// Starts the coordinator ticker and notifies every backend; returns the first backend that
+/// could not be reached or refused, after trying them all
+pub async fn start_colony_ticking() -> Result<(), CoordinatorError> {
     log!("Starting colony ticking: initiating coordinator ticker and notifying all backends");
     
     // Step 1: Start coordinator ticker
     crate::coordinator_ticker::start_coordinator_ticker();
     
     // Step 2: Get topology and all backends
-    let topology_arc = match ClusterTopology::get_instance() {
-        Some(t) => t,
-        None => {
-            log_error!("Topology not initialized. Cannot start colony ticking.");
-            return;
-        }
-    };
+    let topology_arc = ClusterTopology::get_instance().ok_or(CoordinatorError::TopologyMissing)?;
     
     // Step 3: Send StartTicking to all unique backends
     let backend_hosts = topology_arc.get_all_backend_hosts();
@@ -292,46 +530,46 @@ pub async fn start_colony_ticking() {
     }
     
     let backend_count = unique_backends.len();
+    let mut first_failure = None;
     for backend_host in unique_backends {
-        match send_start_ticking_to_backend(&backend_host).await {
+        let refusal = match send_start_ticking_to_backend(&backend_host).await {
             Ok(StartTickingResponse::Ok) => {
-                log!("Backend {}:{} started ticking", backend_host.hostname, backend_host.port);
+                log!("Backend {} started ticking", backend_host.to_address());
+                CoordinatorContext::get_instance().get_coord_stored_info().ticking_started = true;
+                continue;
             }
-            Ok(StartTickingResponse::ColonyNotInitialized) => {
-                log_error!("Backend {}:{} cannot start ticking: colony not initialized", 
-                          backend_host.hostname, backend_host.port);
+            Ok(StartTickingResponse::ColonyNotInitialized) => "colony not initialized".to_string(),
+            Ok(StartTickingResponse::TopologyNotInitialized) => "topology not initialized".to_string(),
+            Ok(StartTickingResponse::AwaitingTopography(shards)) => {
+                format!("shards {:?} still await their topography", shards.iter().map(|shard| shard.to_id()).collect::<Vec<_>>())
             }
-            Ok(StartTickingResponse::TopologyNotInitialized) => {
-                log_error!("Backend {}:{} cannot start ticking: topology not initialized", 
-                          backend_host.hostname, backend_host.port);
-            }
-            Ok(StartTickingResponse::Error(msg)) => {
-                log_error!("Backend {}:{} failed to start ticking: {}", 
-                          backend_host.hostname, backend_host.port, msg);
+            Ok(StartTickingResponse::StaleLeases(shards)) => {
+                format!("shards {:?} hold a stale lease and stay suspended", shards.iter().map(|shard| shard.to_id()).collect::<Vec<_>>())
             }
+            Ok(StartTickingResponse::Error(msg)) => msg,
             Err(e) => {
-                log_error!("Failed to send StartTicking to {}:{}: {}", 
-                          backend_host.hostname, backend_host.port, e);
+                log_error!("Failed to send StartTicking: {}", e);
+                first_failure.get_or_insert(e);
+                continue;
             }
-        }
+        };
+        log_error!("Backend {} cannot start ticking: {}", backend_host.to_address(), refusal);
+        first_failure.get_or_insert(CoordinatorError::BackendRejected { host: backend_host.to_address(), shard: None, response: refusal });
     }
     
     log!("Colony ticking started: coordinator ticker active, {} backends notified", backend_count);
+    if first_failure.is_none() {
+        record_lifecycle_event(TICKING_STARTED_EVENT, latest_max_tick().unwrap_or(0), format!("Ticking started on {} backends", backend_count));
+    }
+    first_failure.map_or(Ok(()), Err)
 }
 
-async fn send_start_ticking_to_backend(backend_host: &HostInfo) -> Result<StartTickingResponse, String> {
-    let mut stream = connect_to_backend(&backend_host.hostname, backend_host.port).await
-        .map_err(|e| format!("Connection failed: {}", e))?;
-    
-    let request = BackendRequest::StartTicking(StartTickingRequest {});
-    send_message(&mut stream, &request).await;
-    
-    if let Some(response) = receive_message::<BackendResponse>(&mut stream).await {
-        match response {
-            BackendResponse::StartTicking(resp) => Ok(resp),
-            _ => Err("Unexpected response type".to_string()),
-        }
-    } else {
-        Err("Failed to receive response".to_string())
+pub async fn send_start_ticking_to_backend(backend_host: &HostInfo) -> Result<StartTickingResponse, CoordinatorError> {
+    let mut stream = connect_backend(backend_host).await?;
+    let leases = with_lease_table(|table| table.leases());
+    let request = BackendRequest::StartTicking(StartTickingRequest { leases, fast_forward: Some(is_fast_forward()) });
+    match request_backend(&mut stream, backend_host, "StartTicking", &request).await? {
+        BackendResponse::StartTicking(resp) => Ok(resp),
+        _ => Err(CoordinatorError::UnexpectedResponse { host: backend_host.to_address(), op: "StartTicking" }),
     }
-} 
\ No newline at end of file
+}
@@ -1,13 +1,43 @@
 pub mod coordinator_storage;
 pub mod coordinator_context;
+pub mod coordinator_error;
 pub mod global_topography;
 pub mod init_colony;
 pub mod colony_start;
 pub mod http_server;
 pub mod coordinator_ticker;
 pub mod backend_client;
+pub mod circuit_breaker;
 pub mod tick_monitor;
 pub mod colony_event_generator;
+pub mod biomes;
 pub mod colony_stats;
+pub mod colony_stats_cache;
+pub mod colony_stats_alarms;
+pub mod species_summary;
 pub mod event_logging;
 
+pub mod colony_expand;
+pub mod colony_step;
+pub mod shard_freeze;
+pub mod shard_event_log;
+pub mod backend_status;
+pub mod bootstrap;
+pub mod colony_verification;
+pub mod shard_leases;
+pub mod determinism_check;
+pub mod colony_capture;
+pub mod colony_density;
+pub mod warmup;
+pub mod capture_config;
+pub mod capture_gate;
+pub mod capture_frames;
+pub mod coordinator_server;
+pub mod stats_comparison;
+pub mod live_feed_hub;
+pub mod run_summary;
+pub mod run_export;
+pub mod lifecycle_events;
+pub mod rules_drift;
+pub mod topology_push;
+pub mod coordinator_cli;
@@ -0,0 +1,85 @@
+use std::collections::BTreeSet;
+use shared::cluster_registry::{get_instance, ClusterRegistry};
+use shared::colony_event_shared::{BACKEND_JOINED_EVENT, BACKEND_LEFT_EVENT, EXTINCTION_DETECTED_EVENT};
+use shared::coordinator_api::ColonyEventDescription;
+use shared::log;
+use crate::coordinator_context::CoordinatorContext;
+
+/// Logs a lifecycle milestone and adds it to the colony events
+pub fn record_lifecycle_event(event_type: &str, tick: u64, description: String) {
+    log!("[{}] {}: {}", tick, event_type, description);
+    CoordinatorContext::get_instance().add_colony_event(ColonyEventDescription {
+        tick,
+        intended_tick: None,
+        event_type: event_type.to_string(),
+        description,
+        delivery: None,
+        no_effect: false,
+        region: None,
+    });
+}
+
+/// Backends registered in the cluster registry, compared between sweeps
+#[derive(Debug, Default)]
+pub struct RegistryMembership {
+    known: Option<BTreeSet<String>>,
+}
+
+impl RegistryMembership {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Backends that joined and left since the last call; the first call only learns the members
+    pub fn update(&mut self, current: BTreeSet<String>) -> (Vec<String>, Vec<String>) {
+        let changes = match &self.known {
+            Some(known) => (current.difference(known).cloned().collect(), known.difference(&current).cloned().collect()),
+            None => (Vec::new(), Vec::new()),
+        };
+        self.known = Some(current);
+        changes
+    }
+}
+
+/// Creature count of the previous stats snapshot, to notice the colony dying out
+#[derive(Debug, Default)]
+pub struct ExtinctionWatch {
+    last_count: Option<u64>,
+}
+
+impl ExtinctionWatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// True once when the count drops to zero from a living colony
+    pub fn observe(&mut self, creatures_count: u64) -> bool {
+        let extinct = creatures_count == 0 && self.last_count.is_some_and(|count| count > 0);
+        self.last_count = Some(creatures_count);
+        extinct
+    }
+}
+
+/// Records the backends that joined or left the cluster registry since the previous check
+pub async fn check_registry_membership(tick: u64) {
+    let Some(registry) = get_instance() else {
+        return;
+    };
+    let current: BTreeSet<String> = registry.discover_backends().await.iter()
+        .map(|address| address.to_internal_address())
+        .collect();
+    let (joined, left) = CoordinatorContext::get_instance().registry_membership().update(current);
+    for backend in joined {
+        record_lifecycle_event(BACKEND_JOINED_EVENT, tick, format!("Backend {} registered", backend));
+    }
+    for backend in left {
+        record_lifecycle_event(BACKEND_LEFT_EVENT, tick, format!("Backend {} left the registry", backend));
+    }
+}
+
+/// Records an extinction the first time a stats snapshot finds no creatures
+pub fn check_extinction(tick: u64, creatures_count: u64) {
+    if CoordinatorContext::get_instance().extinction_watch().observe(creatures_count) {
+        record_lifecycle_event(EXTINCTION_DETECTED_EVENT, tick, "No creatures left in the colony".to_string());
+    }
+}
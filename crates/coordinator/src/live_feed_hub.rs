@@ -0,0 +1,293 @@
+use futures_util::{SinkExt, StreamExt};
+use image::imageops::FilterType;
+use shared::api_auth::ApiScope;
+use shared::cluster_topology::ClusterTopology;
+use shared::coordinator_api::{BackendStatus, ColonyEventDescription, TickSample};
+use shared::live_feed::{base64_encode, FeedMessage, FeedRequest, FeedTopic};
+use shared::{log, log_error, ssm};
+use std::collections::{HashSet, VecDeque};
+use std::io::Cursor;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::sync::Notify;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+use crate::colony_capture::current_colony_frame;
+use crate::coordinator_context::CoordinatorContext;
+
+const FEED_QUEUE_CAPACITY_ENV: &str = "FEED_QUEUE_CAPACITY";
+const FEED_FRAME_INTERVAL_ENV: &str = "FEED_FRAME_INTERVAL_SECS";
+const DEFAULT_FEED_QUEUE_CAPACITY: usize = 256;
+const DEFAULT_FEED_FRAME_INTERVAL: Duration = Duration::from_secs(5);
+/// Frames are downsampled to this width at most; the GUI keeps fetching full images over HTTP
+const FRAME_MAX_WIDTH: u32 = 320;
+
+/// Messages waiting for one client. A client that reads too slowly loses the oldest ones
+/// rather than holding up the coordinator or growing without bound.
+#[derive(Debug)]
+pub struct FeedQueue {
+    capacity: usize,
+    messages: VecDeque<Arc<str>>,
+    dropped: u64,
+}
+
+impl FeedQueue {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity: capacity.max(1), messages: VecDeque::new(), dropped: 0 }
+    }
+
+    pub fn push(&mut self, message: Arc<str>) {
+        if self.messages.len() == self.capacity {
+            self.messages.pop_front();
+            self.dropped += 1;
+        }
+        self.messages.push_back(message);
+    }
+
+    /// Queued messages, oldest first, and how many were dropped since the last drain
+    pub fn drain(&mut self) -> (Vec<Arc<str>>, u64) {
+        (self.messages.drain(..).collect(), std::mem::take(&mut self.dropped))
+    }
+}
+
+#[derive(Debug)]
+struct Subscriber {
+    id: u64,
+    observer: bool,
+    topics: Mutex<HashSet<FeedTopic>>,
+    queue: Mutex<FeedQueue>,
+    notify: Notify,
+}
+
+impl Subscriber {
+    fn push(&self, message: Arc<str>) {
+        self.queue.lock().unwrap().push(message);
+        self.notify.notify_one();
+    }
+}
+
+/// Fans coordinator updates out to the connected /ws clients
+#[derive(Debug)]
+pub struct FeedHub {
+    queue_capacity: usize,
+    next_id: AtomicU64,
+    subscribers: Mutex<Vec<Arc<Subscriber>>>,
+    /// Health of each backend at the previous sweep, by topology index
+    last_health: Mutex<Vec<(bool, Option<String>)>>,
+}
+
+impl FeedHub {
+    /// Queue capacity per client from FEED_QUEUE_CAPACITY
+    pub fn from_env() -> Self {
+        let queue_capacity = std::env::var(FEED_QUEUE_CAPACITY_ENV)
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(DEFAULT_FEED_QUEUE_CAPACITY);
+        FeedHub {
+            queue_capacity,
+            next_id: AtomicU64::new(0),
+            subscribers: Mutex::new(Vec::new()),
+            last_health: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn register(&self, observer: bool) -> Arc<Subscriber> {
+        let subscriber = Arc::new(Subscriber {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            observer,
+            topics: Mutex::new(HashSet::new()),
+            queue: Mutex::new(FeedQueue::new(self.queue_capacity)),
+            notify: Notify::new(),
+        });
+        self.subscribers.lock().unwrap().push(Arc::clone(&subscriber));
+        subscriber
+    }
+
+    fn unregister(&self, id: u64) {
+        self.subscribers.lock().unwrap().retain(|subscriber| subscriber.id != id);
+    }
+
+    pub fn has_subscribers(&self, topic: FeedTopic) -> bool {
+        self.subscribers.lock().unwrap().iter().any(|subscriber| subscriber.topics.lock().unwrap().contains(&topic))
+    }
+
+    /// Queues the message for every client subscribed to its topic
+    pub fn publish(&self, message: &FeedMessage) {
+        self.publish_scoped(message, message);
+    }
+
+    /// Like publish, with a redacted variant for observer clients. Each variant is serialized
+    /// once, and only if some client wants it.
+    fn publish_scoped(&self, admin_message: &FeedMessage, observer_message: &FeedMessage) {
+        let Some(topic) = admin_message.topic() else {
+            return;
+        };
+        let mut admin_json: Option<Arc<str>> = None;
+        let mut observer_json: Option<Arc<str>> = None;
+        for subscriber in self.subscribers.lock().unwrap().iter() {
+            if !subscriber.topics.lock().unwrap().contains(&topic) {
+                continue;
+            }
+            let (json, message) = if subscriber.observer {
+                (&mut observer_json, observer_message)
+            } else {
+                (&mut admin_json, admin_message)
+            };
+            let json = json.get_or_insert_with(|| {
+                serde_json::to_string(message).expect("Failed to serialize feed message").into()
+            });
+            subscriber.push(Arc::clone(json));
+        }
+    }
+}
+
+pub fn publish_event(event: &ColonyEventDescription) {
+    CoordinatorContext::get_instance().feed_hub().publish(&FeedMessage::Event { event: event.clone() });
+}
+
+pub fn publish_tick(sample: TickSample) {
+    CoordinatorContext::get_instance().feed_hub().publish(&FeedMessage::Tick {
+        timestamp_ms: sample.timestamp_ms,
+        min_tick: sample.min_tick,
+        max_tick: sample.max_tick,
+    });
+}
+
+/// Compares one sweep with the previous and returns a BackendHealth message per backend that
+/// changed, every backend on the first sweep
+pub fn backend_health_changes(last_health: &mut Vec<(bool, Option<String>)>, statuses: &[BackendStatus]) -> Vec<FeedMessage> {
+    let mut changes = Vec::new();
+    for (index, status) in statuses.iter().enumerate() {
+        let health = (status.healthy, status.error.clone());
+        if last_health.get(index) != Some(&health) {
+            changes.push(FeedMessage::BackendHealth {
+                index,
+                backend: status.backend.clone(),
+                healthy: status.healthy,
+                error: status.error.clone(),
+            });
+        }
+    }
+    *last_health = statuses.iter().map(|status| (status.healthy, status.error.clone())).collect();
+    changes
+}
+
+/// Pushes the backends whose health changed; observers get the same public hostnames as
+/// /api/backends gives them
+pub async fn publish_backend_health(topology: &ClusterTopology, statuses: &[BackendStatus]) {
+    let hub = CoordinatorContext::get_instance().feed_hub();
+    let changes = backend_health_changes(&mut hub.last_health.lock().unwrap(), statuses);
+    if changes.is_empty() || !hub.has_subscribers(FeedTopic::BackendHealth) {
+        return;
+    }
+    let mut addresses = ssm::discover_backends().await;
+    addresses.extend(ssm::discover_coordinator().await);
+    let observer_view = topology.to_observer_view(&addresses);
+    let public_hosts = observer_view.get_all_backend_hosts();
+    for change in changes {
+        let FeedMessage::BackendHealth { index, healthy, ref error, .. } = change else {
+            continue;
+        };
+        let backend = public_hosts.get(index).map(|host| host.to_address()).unwrap_or_else(|| format!("backend-{}", index + 1));
+        let observer_change = FeedMessage::BackendHealth { index, backend, healthy, error: error.clone() };
+        hub.publish_scoped(&change, &observer_change);
+    }
+}
+
+fn frame_interval() -> Duration {
+    std::env::var(FEED_FRAME_INTERVAL_ENV)
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_FEED_FRAME_INTERVAL)
+}
+
+/// Stitches and pushes a downsampled colony frame while any client subscribes to frames
+pub async fn publish_frames() {
+    let mut timer = tokio::time::interval(frame_interval());
+    loop {
+        timer.tick().await;
+        if !CoordinatorContext::get_instance().feed_hub().has_subscribers(FeedTopic::Frames) {
+            continue;
+        }
+        let (tick, frame) = match current_colony_frame().await {
+            Ok(result) => result,
+            Err(e) => {
+                log_error!("Feed frame skipped: {}", e);
+                continue;
+            }
+        };
+        let image = if frame.image.width() > FRAME_MAX_WIDTH {
+            let height = (frame.image.height() * FRAME_MAX_WIDTH / frame.image.width()).max(1);
+            image::imageops::resize(&frame.image, FRAME_MAX_WIDTH, height, FilterType::Nearest)
+        } else {
+            frame.image
+        };
+        let mut png = Vec::new();
+        if let Err(e) = image.write_to(&mut Cursor::new(&mut png), image::ImageOutputFormat::Png) {
+            log_error!("Failed to encode feed frame: {}", e);
+            continue;
+        }
+        CoordinatorContext::get_instance().feed_hub().publish(&FeedMessage::Frame {
+            tick,
+            width: image.width(),
+            height: image.height(),
+            missing_shards: frame.missing_shards.iter().map(|shard| shard.to_id()).collect(),
+            png_base64: base64_encode(&png),
+        });
+    }
+}
+
+/// Serves one upgraded /ws connection until the client closes it or the socket fails
+pub async fn serve_feed<S>(ws: WebSocketStream<S>, scope: ApiScope)
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let hub = CoordinatorContext::get_instance().feed_hub();
+    let subscriber = hub.register(scope == ApiScope::Observer);
+    log!("Feed client {} connected", subscriber.id);
+    let (mut sink, mut source) = ws.split();
+    loop {
+        let outgoing = tokio::select! {
+            _ = subscriber.notify.notified() => {
+                let (messages, dropped) = subscriber.queue.lock().unwrap().drain();
+                let lagged = (dropped > 0).then(|| {
+                    serde_json::to_string(&FeedMessage::Lagged { dropped }).expect("Failed to serialize feed message").into()
+                });
+                lagged.into_iter().chain(messages).collect()
+            }
+            incoming = source.next() => match incoming {
+                Some(Ok(Message::Text(text))) => match serde_json::from_str::<FeedRequest>(text.as_str()) {
+                    Ok(FeedRequest::Subscribe { topics }) => {
+                        *subscriber.topics.lock().unwrap() = topics.iter().copied().collect();
+                        let ack: Arc<str> = serde_json::to_string(&FeedMessage::Subscribed { topics })
+                            .expect("Failed to serialize feed message").into();
+                        vec![ack]
+                    }
+                    Err(e) => {
+                        log_error!("Feed client {} sent an invalid request: {}", subscriber.id, e);
+                        Vec::new()
+                    }
+                },
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                // Pings are answered by tungstenite on the next send
+                Some(Ok(_)) => Vec::new(),
+            }
+        };
+        let mut failed = false;
+        for json in outgoing {
+            if sink.feed(Message::text(json.as_ref())).await.is_err() {
+                failed = true;
+                break;
+            }
+        }
+        if failed || sink.flush().await.is_err() {
+            break;
+        }
+    }
+    hub.unregister(subscriber.id);
+    log!("Feed client {} disconnected", subscriber.id);
+}
@@ -0,0 +1,172 @@
+//! Watches for backends applying other ColonyLifeRules than the coordinator stored, e.g. after
+//! one missed a ChangeColonyRules event, and optionally sends them the coordinator's rules again.
+use serde::Serialize;
+use shared::be_api::ColonyLifeRules;
+use shared::cluster_topology::ClusterTopology;
+use shared::colony_events::{ColonyEvent, ColonyRuleChange};
+use shared::coordinator_api::EventDelivery;
+use shared::log;
+use std::time::Duration;
+use uuid::Uuid;
+use crate::backend_client;
+use crate::coordinator_context::CoordinatorContext;
+use crate::coordinator_error::CoordinatorError;
+use crate::lifecycle_events::record_lifecycle_event;
+
+const RULES_DRIFT_CHECK_INTERVAL_ENV: &str = "RULES_DRIFT_CHECK_SECS";
+const RULES_DRIFT_REPAIR_ENV: &str = "RULES_DRIFT_REPAIR";
+const DEFAULT_RULES_DRIFT_CHECK_INTERVAL_SECS: u64 = 30;
+
+pub const RULES_DRIFT_EVENT: &str = "Rules Drift";
+pub const RULES_DRIFT_REPAIRED_EVENT: &str = "Rules Drift Repaired";
+
+pub fn rules_drift_check_interval() -> Duration {
+    let secs = std::env::var(RULES_DRIFT_CHECK_INTERVAL_ENV)
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+        .unwrap_or(DEFAULT_RULES_DRIFT_CHECK_INTERVAL_SECS);
+    Duration::from_secs(secs)
+}
+
+/// Off unless RULES_DRIFT_REPAIR=true
+pub fn rules_drift_repair_enabled() -> bool {
+    std::env::var(RULES_DRIFT_REPAIR_ENV)
+        .ok()
+        .and_then(|v| v.parse::<bool>().ok())
+        .unwrap_or(false)
+}
+
+/// One rule a backend applies with another value than the coordinator
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct RuleFieldDrift {
+    pub field: String,
+    pub expected: serde_json::Value,
+    pub actual: serde_json::Value,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct BackendRulesDrift {
+    pub backend: String,
+    pub fields: Vec<RuleFieldDrift>,
+}
+
+impl BackendRulesDrift {
+    pub fn describe(&self) -> String {
+        let fields: Vec<String> = self.fields.iter()
+            .map(|drift| format!("{} {} instead of {}", drift.field, drift.actual, drift.expected))
+            .collect();
+        format!("{} ({})", self.backend, fields.join(", "))
+    }
+}
+
+/// The fields of actual that differ from expected, in field name order
+pub fn rules_differences(expected: &ColonyLifeRules, actual: &ColonyLifeRules) -> Vec<RuleFieldDrift> {
+    let (Ok(serde_json::Value::Object(expected)), Ok(serde_json::Value::Object(actual))) =
+        (serde_json::to_value(expected), serde_json::to_value(actual)) else {
+        return Vec::new();
+    };
+    expected.into_iter()
+        .filter_map(|(field, expected)| {
+            let actual = actual.get(&field).cloned().unwrap_or(serde_json::Value::Null);
+            (actual != expected).then_some(RuleFieldDrift { field, expected, actual })
+        })
+        .collect()
+}
+
+/// What one check found and fixed
+#[derive(Debug, Default, PartialEq)]
+pub struct RulesDriftCheck {
+    pub drift: Vec<BackendRulesDrift>,
+    /// Backends that took the coordinator's rules again
+    pub repaired: Vec<String>,
+    /// Backends that did not answer; they are neither drifting nor in line
+    pub unreachable: Vec<String>,
+}
+
+/// Drifting backends between checks, so a drift is recorded once rather than on every check
+#[derive(Debug, Default)]
+pub struct RulesDriftWatch {
+    drift: Vec<BackendRulesDrift>,
+}
+
+impl RulesDriftWatch {
+    pub const fn new() -> Self {
+        Self { drift: Vec::new() }
+    }
+
+    pub fn drift(&self) -> &[BackendRulesDrift] {
+        &self.drift
+    }
+
+    /// Compares what query reports for each backend with canonical. A drift that is new or
+    /// changed becomes a colony event. With repair, canonical is re-sent to the drifting
+    /// backends and the ones that applied it no longer count as drifting.
+    pub fn check<Q, R>(&mut self, canonical: &ColonyLifeRules, backends: &[String], tick: u64, mut query: Q, repair: Option<R>) -> RulesDriftCheck
+    where
+        Q: FnMut(&str) -> Result<Option<ColonyLifeRules>, CoordinatorError>,
+        R: FnOnce(&[String]) -> EventDelivery,
+    {
+        let mut check = RulesDriftCheck::default();
+        for backend in backends {
+            match query(backend) {
+                Ok(Some(rules)) => {
+                    let fields = rules_differences(canonical, &rules);
+                    if !fields.is_empty() {
+                        check.drift.push(BackendRulesDrift { backend: backend.clone(), fields });
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    log!("Rules drift check skips {}: {}", backend, e);
+                    check.unreachable.push(backend.clone());
+                }
+            }
+        }
+
+        if !check.drift.is_empty() && check.drift != self.drift {
+            let described: Vec<String> = check.drift.iter().map(BackendRulesDrift::describe).collect();
+            record_lifecycle_event(RULES_DRIFT_EVENT, tick, format!("Backends run other rules than the coordinator: {}", described.join("; ")));
+        }
+
+        if let Some(repair) = repair.filter(|_| !check.drift.is_empty()) {
+            let drifting: Vec<String> = check.drift.iter().map(|drift| drift.backend.clone()).collect();
+            let delivery = repair(&drifting);
+            check.repaired = delivery.applied_to;
+            if !check.repaired.is_empty() {
+                record_lifecycle_event(RULES_DRIFT_REPAIRED_EVENT, tick,
+                    format!("Sent the coordinator's rules to {} again", check.repaired.join(", ")));
+            }
+            check.drift.retain(|drift| !check.repaired.contains(&drift.backend));
+        }
+
+        self.drift = check.drift.clone();
+        check
+    }
+}
+
+/// Backends whose rules drifted at the last check, for /health
+pub fn current_rules_drift() -> Vec<BackendRulesDrift> {
+    CoordinatorContext::get_instance().rules_drift_watch().drift().to_vec()
+}
+
+/// Asks every topology backend for its rules and compares them with the coordinator's stored ones
+pub fn check_rules_drift(tick: u64) {
+    let Some(topology) = ClusterTopology::get_instance() else {
+        return;
+    };
+    let backends: Vec<String> = topology.get_all_backend_hosts().iter().map(|host| host.to_address()).collect();
+    let canonical = CoordinatorContext::get_instance().get_colony_life_rules();
+    let repair = rules_drift_repair_enabled().then_some(|drifting: &[String]| {
+        // A rules change applied meanwhile must not be undone with the rules read before it
+        if CoordinatorContext::get_instance().get_colony_life_rules() != canonical {
+            return EventDelivery { event_id: Uuid::nil(), applied_to: Vec::new(), failed_on: drifting.to_vec(), effects: Vec::new() };
+        }
+        let event = ColonyEvent::ChangeColonyRules(ColonyRuleChange {
+            new_rules: canonical,
+            description: "Coordinator rules re-sent after drift".to_string(),
+        });
+        backend_client::send_event_to_backends(event, drifting)
+    });
+    CoordinatorContext::get_instance().rules_drift_watch().check(&canonical, &backends, tick, backend_client::call_backend_get_colony_rules, repair);
+}
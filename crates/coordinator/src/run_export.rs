@@ -0,0 +1,202 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use chrono::Utc;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use shared::be_api::BUILD_VERSION;
+use tokio::io::{AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use crate::run_summary::RUN_SUMMARY_FILE;
+use crate::stats_comparison::RUN_CONFIG_FILE;
+
+/// Stats snapshots in an export unless ?stats= asks for another count
+pub const DEFAULT_EXPORT_STATS_SNAPSHOTS: usize = 20;
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+const EVENTS_DIR: &str = "events";
+const STATS_DIR: &str = "stats_shots";
+const CAPTURES_DIR: &str = "images_shots";
+const CAPTURE_SUFFIX: &str = ".png";
+const BLOCK_SIZE: usize = 512;
+/// File contents are compressed and written out in chunks of this size
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// First entry of every export
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ExportManifest {
+    pub colony_instance_id: String,
+    /// RFC 3339
+    pub exported_at: String,
+    pub coordinator_version: String,
+    /// Archive paths of the other entries, in archive order
+    pub entries: Vec<String>,
+}
+
+/// A file of the instance directory and its path in the archive
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportEntry {
+    pub archive_path: String,
+    pub source: PathBuf,
+}
+
+/// File names in dir with the given suffix, sorted; tick-named files sort in tick order
+fn sorted_file_names(dir: &Path, suffix: &str) -> Result<Vec<String>, String> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to list {}: {}", dir.display(), e)),
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_file()))
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| name.ends_with(suffix))
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+/// What an export of instance_dir holds: the run configuration, every event file, the last
+/// stats_snapshots stats snapshots, the latest capture and the run summary, where they exist
+pub fn plan_export(instance_dir: &Path, stats_snapshots: usize) -> Result<Vec<ExportEntry>, String> {
+    let entry = |archive_path: String, source: PathBuf| ExportEntry { archive_path, source };
+    let mut entries = Vec::new();
+    if instance_dir.join(RUN_CONFIG_FILE).is_file() {
+        entries.push(entry(RUN_CONFIG_FILE.to_string(), instance_dir.join(RUN_CONFIG_FILE)));
+    }
+    let events_dir = instance_dir.join(EVENTS_DIR);
+    for name in sorted_file_names(&events_dir, "")? {
+        entries.push(entry(format!("{}/{}", EVENTS_DIR, name), events_dir.join(&name)));
+    }
+    let stats_dir = instance_dir.join(STATS_DIR);
+    let stats = sorted_file_names(&stats_dir, ".json")?;
+    for name in &stats[stats.len().saturating_sub(stats_snapshots)..] {
+        entries.push(entry(format!("{}/{}", STATS_DIR, name), stats_dir.join(name)));
+    }
+    let captures_dir = instance_dir.join(CAPTURES_DIR);
+    if let Some(name) = sorted_file_names(&captures_dir, CAPTURE_SUFFIX)?.pop() {
+        entries.push(entry(format!("{}/{}", CAPTURES_DIR, name), captures_dir.join(&name)));
+    }
+    if instance_dir.join(RUN_SUMMARY_FILE).is_file() {
+        entries.push(entry(RUN_SUMMARY_FILE.to_string(), instance_dir.join(RUN_SUMMARY_FILE)));
+    }
+    Ok(entries)
+}
+
+fn write_octal(field: &mut [u8], value: u64) {
+    let digits = format!("{:0width$o}", value, width = field.len() - 1);
+    field[..digits.len()].copy_from_slice(digits.as_bytes());
+}
+
+/// ustar header of a regular file
+fn tar_header(path: &str, size: u64, mtime: u64) -> Result<[u8; BLOCK_SIZE], String> {
+    if path.len() > 100 {
+        return Err(format!("Archive path too long: {}", path));
+    }
+    let mut header = [0u8; BLOCK_SIZE];
+    header[..path.len()].copy_from_slice(path.as_bytes());
+    write_octal(&mut header[100..108], 0o644);
+    write_octal(&mut header[108..116], 0);
+    write_octal(&mut header[116..124], 0);
+    write_octal(&mut header[124..136], size);
+    write_octal(&mut header[136..148], mtime);
+    header[156] = b'0';
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+    // The checksum is computed with its own field set to spaces
+    header[148..156].fill(b' ');
+    let checksum: u64 = header.iter().map(|&byte| byte as u64).sum();
+    write_octal(&mut header[148..155], checksum);
+    Ok(header)
+}
+
+/// Writes a tar.gz to out as it is assembled, holding at most one chunk of a file in memory
+pub struct TarGzStream<W: AsyncWrite + Unpin> {
+    out: W,
+    encoder: GzEncoder<Vec<u8>>,
+    mtime: u64,
+}
+
+impl<W: AsyncWrite + Unpin> TarGzStream<W> {
+    pub fn new(out: W) -> Self {
+        let mtime = Utc::now().timestamp().max(0) as u64;
+        Self { out, encoder: GzEncoder::new(Vec::new(), Compression::default()), mtime }
+    }
+
+    async fn write_compressed(&mut self, bytes: &[u8]) -> Result<(), String> {
+        self.encoder.write_all(bytes).map_err(|e| format!("Failed to compress: {}", e))?;
+        let compressed = std::mem::take(self.encoder.get_mut());
+        self.out.write_all(&compressed).await.map_err(|e| format!("Failed to write archive: {}", e))
+    }
+
+    async fn pad_to_block(&mut self, size: u64) -> Result<(), String> {
+        let padding = (BLOCK_SIZE - (size % BLOCK_SIZE as u64) as usize) % BLOCK_SIZE;
+        self.write_compressed(&[0u8; BLOCK_SIZE][..padding]).await
+    }
+
+    pub async fn append_bytes(&mut self, path: &str, bytes: &[u8]) -> Result<(), String> {
+        let header = tar_header(path, bytes.len() as u64, self.mtime)?;
+        self.write_compressed(&header).await?;
+        self.write_compressed(bytes).await?;
+        self.pad_to_block(bytes.len() as u64).await
+    }
+
+    /// Appends the file with the size it has now; bytes written to it meanwhile are left out
+    pub async fn append_file(&mut self, path: &str, source: &Path) -> Result<(), String> {
+        let file = tokio::fs::File::open(source).await
+            .map_err(|e| format!("Failed to open {}: {}", source.display(), e))?;
+        let size = file.metadata().await
+            .map_err(|e| format!("Failed to stat {}: {}", source.display(), e))?
+            .len();
+        let header = tar_header(path, size, self.mtime)?;
+        self.write_compressed(&header).await?;
+
+        let mut file = file.take(size);
+        let mut chunk = vec![0u8; CHUNK_SIZE];
+        let mut written = 0u64;
+        loop {
+            let read = file.read(&mut chunk).await
+                .map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+            if read == 0 {
+                break;
+            }
+            self.write_compressed(&chunk[..read]).await?;
+            written += read as u64;
+        }
+        // A file truncated meanwhile is zero-filled to the size in its header
+        while written < size {
+            let zeros = (size - written).min(CHUNK_SIZE as u64) as usize;
+            self.write_compressed(&vec![0u8; zeros]).await?;
+            written += zeros as u64;
+        }
+        self.pad_to_block(size).await
+    }
+
+    /// Writes the end-of-archive blocks and the gzip trailer
+    pub async fn finish(mut self) -> Result<W, String> {
+        self.write_compressed(&[0u8; 2 * BLOCK_SIZE]).await?;
+        let rest = self.encoder.finish().map_err(|e| format!("Failed to compress: {}", e))?;
+        self.out.write_all(&rest).await.map_err(|e| format!("Failed to write archive: {}", e))?;
+        self.out.flush().await.map_err(|e| format!("Failed to write archive: {}", e))?;
+        Ok(self.out)
+    }
+}
+
+/// Streams the planned export to out: manifest.json, then every entry, all under a
+/// {colony_instance_id}/ directory
+pub async fn write_run_export<W: AsyncWrite + Unpin>(out: W, instance_id: &str, entries: &[ExportEntry]) -> Result<W, String> {
+    let manifest = ExportManifest {
+        colony_instance_id: instance_id.to_string(),
+        exported_at: Utc::now().to_rfc3339(),
+        coordinator_version: BUILD_VERSION.to_string(),
+        entries: entries.iter().map(|entry| entry.archive_path.clone()).collect(),
+    };
+    let manifest_json = serde_json::to_vec_pretty(&manifest).map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+
+    let mut archive = TarGzStream::new(out);
+    archive.append_bytes(&format!("{}/{}", instance_id, MANIFEST_FILE), &manifest_json).await?;
+    for entry in entries {
+        archive.append_file(&format!("{}/{}", instance_id, entry.archive_path), &entry.source).await?;
+    }
+    archive.finish().await
+}
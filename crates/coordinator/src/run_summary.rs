@@ -0,0 +1,117 @@
+use std::path::{Path, PathBuf};
+use shared::colony_event_shared::TARGET_TICK_REACHED_EVENT;
+use shared::coordinator_api::{ColonyEventDescription, RunSummary};
+use shared::output_paths::OutputPaths;
+use shared::{log, log_error};
+use crate::colony_capture::capture_final_colony_frame;
+use crate::colony_stats::save_colony_stats;
+use crate::colony_step::{is_fast_forward, set_colony_paused, set_fast_forward};
+use crate::coordinator_context::CoordinatorContext;
+use crate::coordinator_storage::{ColonyStatus, CoordinatorStoredInfo};
+use crate::lifecycle_events::record_lifecycle_event;
+use crate::tick_monitor::unix_time_ms;
+
+pub const RUN_SUMMARY_FILE: &str = "run_summary.json";
+
+/// The target tick once the slowest shard reached it, unless the run already completed
+pub fn reached_target(stored_info: &CoordinatorStoredInfo, min_tick: Option<u64>) -> Option<u64> {
+    if matches!(stored_info.status, ColonyStatus::Completed) {
+        return None;
+    }
+    stored_info.target_tick.filter(|target| min_tick.is_some_and(|tick| tick >= *target))
+}
+
+pub fn is_run_completed() -> bool {
+    matches!(CoordinatorContext::get_instance().get_coord_stored_info().status, ColonyStatus::Completed)
+}
+
+/// Events that took effect: delivered to at least one backend, or never broadcast (NewTopography)
+pub fn count_applied_events(events: &[ColonyEventDescription]) -> usize {
+    events.iter()
+        .filter(|event| event.delivery.as_ref().is_none_or(|delivery| !delivery.applied_to.is_empty()))
+        .count()
+}
+
+/// Summary without the final stats, which the caller fills in; None without a colony instance id
+pub fn build_run_summary(stored_info: &CoordinatorStoredInfo, target_tick: u64, final_tick: u64, completed_at_ms: u64) -> Option<RunSummary> {
+    let started_at_ms = stored_info.run_started_at_ms;
+    Some(RunSummary {
+        colony_instance_id: stored_info.colony_instance_id.clone()?,
+        target_tick,
+        final_tick,
+        creatures_count: None,
+        metric_averages: Default::default(),
+        events_applied: count_applied_events(&stored_info.colony_events),
+        started_at_ms,
+        completed_at_ms,
+        wall_clock_secs: started_at_ms.map(|started| completed_at_ms.saturating_sub(started) as f64 / 1000.0),
+        config_hash: stored_info.run_config.as_ref().map(|config| config.config_hash()),
+    })
+}
+
+/// Writes {instance_dir}/run_summary.json
+pub fn write_run_summary(instance_dir: &Path, summary: &RunSummary) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(instance_dir)
+        .map_err(|e| format!("Failed to create directory {}: {}", instance_dir.display(), e))?;
+    let file_path = instance_dir.join(RUN_SUMMARY_FILE);
+    let json = serde_json::to_string_pretty(summary)
+        .map_err(|e| format!("Failed to serialize run summary to JSON: {}", e))?;
+    std::fs::write(&file_path, json)
+        .map_err(|e| format!("Failed to write run summary to {}: {}", file_path.display(), e))?;
+    Ok(file_path)
+}
+
+/// Ok(None) when the run has not completed, or was started without a target_tick
+pub fn read_run_summary(instance_dir: &Path) -> Result<Option<RunSummary>, String> {
+    let file_path = instance_dir.join(RUN_SUMMARY_FILE);
+    let json = match std::fs::read_to_string(&file_path) {
+        Ok(json) => json,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("Failed to read {}: {}", file_path.display(), e)),
+    };
+    serde_json::from_str(&json).map(Some).map_err(|e| format!("Invalid run summary {}: {}", file_path.display(), e))
+}
+
+/// Stops a run that reached target_tick: pauses every backend, leaves fast-forward, takes a final capture and stats
+/// snapshot, writes the run summary and marks the colony Completed. A backend that cannot be
+/// paused leaves the run going, so the coordinator ticker tries again.
+pub async fn complete_run(target_tick: u64) {
+    log!("Every shard reached target tick {}, stopping the run", target_tick);
+    let final_tick = match set_colony_paused(true).await {
+        Ok(tick) => tick,
+        Err(e) => {
+            log_error!("Failed to stop the run at target tick {}: {:?}", target_tick, e);
+            return;
+        }
+    };
+    record_lifecycle_event(TARGET_TICK_REACHED_EVENT, target_tick, format!("Every shard reached target tick {}, stopped at tick {}", target_tick, final_tick));
+
+    // A fast-forwarding backend renders no images, so the final frame needs the mode off
+    if is_fast_forward() {
+        if let Err(e) = set_fast_forward(false).await {
+            log_error!("Failed to turn fast-forward off for the final capture: {:?}", e);
+        }
+    }
+    capture_final_colony_frame().await;
+    let stats = save_colony_stats().await;
+
+    let context = CoordinatorContext::get_instance();
+    let mut stored_info = context.get_coord_stored_info();
+    stored_info.status = ColonyStatus::Completed;
+    let Some(mut summary) = build_run_summary(&stored_info, target_tick, final_tick, unix_time_ms()) else {
+        log_error!("Colony instance ID is not set, skipping the run summary");
+        return;
+    };
+    drop(stored_info);
+    if let Some(stats) = &stats {
+        summary.creatures_count = Some(stats.creatures_count);
+        summary.metric_averages = stats.histograms.averages();
+    }
+
+    let instance_dir = OutputPaths::get_instance().instance_dir(&summary.colony_instance_id);
+    match write_run_summary(&instance_dir, &summary) {
+        Ok(file_path) => log!("Run completed at tick {} ({:?} creatures), summary saved to {}",
+                              final_tick, summary.creatures_count, file_path.display()),
+        Err(e) => log_error!("Failed to write the run summary: {}", e),
+    }
+}
@@ -0,0 +1,59 @@
+use futures_util::future::join_all;
+use shared::be_api::{BackendRequest, BackendResponse, GetEventLogRequest, GetEventLogResponse, ShardEventLog};
+use shared::cluster_topology::{ClusterTopology, HostInfo};
+use shared::coordinator_api::{BackendEventLog, ColonyEventDetailResponse, EventDelivery};
+use std::time::Duration;
+use uuid::Uuid;
+use crate::coordinator_context::CoordinatorContext;
+use crate::init_colony::{connect_to_backend, receive_message, send_message};
+
+const BACKEND_QUERY_TIMEOUT: Duration = Duration::from_secs(3);
+/// Records per shard; one event is logged at most once per shard
+const RECORDS_PER_SHARD: usize = 1;
+
+/// Topology backends that applied the event, in topology order
+pub fn backends_that_applied(delivery: &EventDelivery, topology: &ClusterTopology) -> Vec<HostInfo> {
+    topology.get_all_backend_hosts().iter()
+        .filter(|host| delivery.applied_to.contains(&host.to_address()))
+        .cloned()
+        .collect()
+}
+
+async fn fetch_event_log(backend_host: &HostInfo, event_id: Uuid) -> Result<Vec<ShardEventLog>, String> {
+    let mut stream = connect_to_backend(&backend_host.hostname, backend_host.port).await
+        .map_err(|e| format!("Connection failed: {}", e))?;
+
+    let request = GetEventLogRequest { event_id: Some(event_id), limit: RECORDS_PER_SHARD };
+    send_message(&mut stream, &BackendRequest::GetEventLog(request)).await;
+
+    match receive_message::<BackendResponse>(&mut stream).await {
+        Some(BackendResponse::GetEventLog(GetEventLogResponse::Ok(logs))) => Ok(logs),
+        Some(BackendResponse::GetEventLog(GetEventLogResponse::ColonyNotInitialized)) => Err("colony not initialized".to_string()),
+        Some(_) => Err("Unexpected response type".to_string()),
+        None => Err("Failed to receive response".to_string()),
+    }
+}
+
+/// The event with the per-shard records of every backend that applied it, one call per backend.
+/// None when the coordinator has no broadcast event with this id.
+pub async fn colony_event_detail(event_id: Uuid) -> Option<ColonyEventDetailResponse> {
+    let event = CoordinatorContext::get_instance().get_colony_events().into_iter()
+        .find(|event| event.delivery.as_ref().is_some_and(|delivery| delivery.event_id == event_id))?;
+    let backends = match (&event.delivery, ClusterTopology::get_instance()) {
+        (Some(delivery), Some(topology)) => backends_that_applied(delivery, &topology),
+        _ => Vec::new(),
+    };
+
+    let logs = join_all(backends.iter().map(|backend| async move {
+        tokio::time::timeout(BACKEND_QUERY_TIMEOUT, fetch_event_log(backend, event_id)).await
+            .unwrap_or_else(|_| Err("Timed out".to_string()))
+    })).await;
+
+    let backends = backends.iter().zip(logs)
+        .map(|(backend, log)| match log {
+            Ok(shards) => BackendEventLog { backend: backend.to_address(), shards, error: None },
+            Err(e) => BackendEventLog { backend: backend.to_address(), shards: Vec::new(), error: Some(e) },
+        })
+        .collect();
+    Some(ColonyEventDetailResponse { event, backends })
+}
@@ -0,0 +1,72 @@
+use shared::be_api::{BackendRequest, BackendResponse, SetShardFrozenRequest, SetShardFrozenResponse, Shard};
+use shared::cluster_topology::{ClusterTopology, HostInfo};
+use shared::coordinator_api::{ShardListEntry, ShardListResponse};
+use shared::{log, log_error};
+use crate::coordinator_context::CoordinatorContext;
+use crate::global_topography::is_awaiting_topography;
+use crate::init_colony::{connect_to_backend, receive_message, send_message};
+
+#[derive(Debug, PartialEq)]
+pub enum FreezeShardError {
+    TopologyNotInitialized,
+    InvalidShardId(String),
+    ShardNotFound(String),
+    Failed(String),
+}
+
+pub fn is_shard_frozen(shard: &Shard) -> bool {
+    CoordinatorContext::get_instance().frozen_shards().contains(&shard.to_id())
+}
+
+async fn send_set_shard_frozen(backend_host: &HostInfo, shard: Shard, frozen: bool) -> Result<u64, String> {
+    let mut stream = connect_to_backend(&backend_host.hostname, backend_host.port).await
+        .map_err(|e| format!("Connection failed: {}", e))?;
+
+    send_message(&mut stream, &BackendRequest::SetShardFrozen(SetShardFrozenRequest { shard, frozen })).await;
+
+    match receive_message::<BackendResponse>(&mut stream).await {
+        Some(BackendResponse::SetShardFrozen(SetShardFrozenResponse::Ok { current_tick })) => Ok(current_tick),
+        Some(BackendResponse::SetShardFrozen(SetShardFrozenResponse::ColonyNotInitialized)) => Err("colony not initialized".to_string()),
+        Some(BackendResponse::SetShardFrozen(SetShardFrozenResponse::ShardNotAvailable)) => Err("shard not hosted".to_string()),
+        Some(_) => Err("Unexpected response type".to_string()),
+        None => Err("Failed to receive response".to_string()),
+    }
+}
+
+/// Freezes or unfreezes one shard on its backend. Returns the tick the shard is at.
+pub async fn set_shard_frozen(shard_id: &str, frozen: bool) -> Result<u64, FreezeShardError> {
+    let shard = Shard::from_id(shard_id).map_err(FreezeShardError::InvalidShardId)?;
+    let topology = ClusterTopology::get_instance().ok_or(FreezeShardError::TopologyNotInitialized)?;
+    let backend = topology.get_host_for_shard(&shard)
+        .ok_or_else(|| FreezeShardError::ShardNotFound(shard_id.to_string()))?
+        .clone();
+
+    let current_tick = send_set_shard_frozen(&backend, shard, frozen).await.map_err(|e| {
+        log_error!("Failed to {} shard {} on {}: {}", if frozen { "freeze" } else { "unfreeze" }, shard_id, backend.to_address(), e);
+        FreezeShardError::Failed(format!("{}: {}", backend.to_address(), e))
+    })?;
+
+    let mut frozen_shards = CoordinatorContext::get_instance().frozen_shards();
+    if frozen {
+        frozen_shards.insert(shard.to_id());
+    } else {
+        frozen_shards.remove(&shard.to_id());
+    }
+    log!("Shard {} {} at tick {}", shard_id, if frozen { "frozen" } else { "unfrozen" }, current_tick);
+    Ok(current_tick)
+}
+
+/// Every shard of the topology with its backend, frozen and topography state, ordered by position
+pub fn shard_list(topology: &ClusterTopology) -> ShardListResponse {
+    let mut shards: Vec<ShardListEntry> = topology.shard_to_host.iter()
+        .map(|(shard, host)| ShardListEntry {
+            shard_id: shard.to_id(),
+            shard: *shard,
+            backend: host.to_address(),
+            frozen: is_shard_frozen(shard),
+            awaiting_topography: is_awaiting_topography(shard),
+        })
+        .collect();
+    shards.sort_by_key(|entry| (entry.shard.y, entry.shard.x));
+    ShardListResponse { shards }
+}
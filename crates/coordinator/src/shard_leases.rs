@@ -0,0 +1,84 @@
+use shared::be_api::{Shard, ShardLease};
+use shared::cluster_topology::{ClusterTopology, HostInfo};
+use shared::coordinator_api::LeaseRenewal;
+use shared::log;
+use std::collections::HashMap;
+use crate::coordinator_context::CoordinatorContext;
+
+/// The lease the coordinator issued for every assigned shard: holder and epoch
+#[derive(Debug, Default)]
+pub struct ShardLeaseTable {
+    leases: HashMap<Shard, (HostInfo, u64)>,
+}
+
+impl ShardLeaseTable {
+    /// Epoch for assigning shard to host: the one host already holds, or the next one when the
+    /// shard moves to host or was never assigned
+    pub fn assign(&mut self, shard: Shard, host: &HostInfo) -> u64 {
+        let epoch = match self.leases.get(&shard) {
+            Some((holder, epoch)) if holder == host => *epoch,
+            Some((_, epoch)) => epoch + 1,
+            None => 1,
+        };
+        self.leases.insert(shard, (host.clone(), epoch));
+        epoch
+    }
+
+    /// Takes over a lease a backend already holds, e.g. after this coordinator replaced another.
+    /// Only the backend the topology assigns the shard to is believed.
+    pub fn adopt(&mut self, lease: ShardLease, host: &HostInfo, assigned: Option<&HostInfo>) {
+        if assigned != Some(host) {
+            return;
+        }
+        let current = self.leases.get(&lease.shard).map_or(0, |(_, epoch)| *epoch);
+        if lease.epoch >= current {
+            self.leases.insert(lease.shard, (host.clone(), lease.epoch));
+        }
+    }
+
+    /// Answers one lease of a backend's RenewShardLeases. A shard this coordinator never issued
+    /// is adopted from the backend the topology assigns it to; None when neither knows the shard.
+    pub fn renew(&mut self, host: &HostInfo, lease: &ShardLease, assigned: Option<&HostInfo>) -> Option<LeaseRenewal> {
+        if !self.leases.contains_key(&lease.shard) {
+            self.adopt(*lease, host, assigned);
+        }
+        match self.leases.get_mut(&lease.shard) {
+            Some((holder, epoch)) if holder == host => {
+                *epoch = (*epoch).max(lease.epoch);
+                Some(LeaseRenewal::Renewed(lease.shard))
+            }
+            Some((holder, epoch)) => Some(LeaseRenewal::Superseded { shard: lease.shard, epoch: *epoch, owner: holder.to_address() }),
+            None => assigned.map(|owner| LeaseRenewal::Superseded { shard: lease.shard, epoch: lease.epoch, owner: owner.to_address() }),
+        }
+    }
+
+    pub fn epoch(&self, shard: &Shard) -> Option<u64> {
+        self.leases.get(shard).map(|(_, epoch)| *epoch)
+    }
+
+    /// Every issued lease, as sent with StartTicking
+    pub fn leases(&self) -> Vec<ShardLease> {
+        self.leases.iter().map(|(shard, (_, epoch))| ShardLease { shard: *shard, epoch: *epoch }).collect()
+    }
+}
+
+/// Runs f on the coordinator's lease table
+pub fn with_lease_table<T>(f: impl FnOnce(&mut ShardLeaseTable) -> T) -> T {
+    f(&mut CoordinatorContext::get_instance().shard_leases())
+}
+
+/// Handles RenewShardLeases; nothing is renewed before this coordinator has a topology
+pub fn renew_backend_leases(backend: &HostInfo, leases: &[ShardLease]) -> Vec<LeaseRenewal> {
+    let Some(topology) = ClusterTopology::get_instance() else {
+        return Vec::new();
+    };
+    with_lease_table(|table| leases.iter()
+        .filter_map(|lease| {
+            let renewal = table.renew(backend, lease, topology.get_host_for_shard(&lease.shard));
+            if let Some(LeaseRenewal::Superseded { shard, epoch, owner }) = &renewal {
+                log!("Backend {} holds a stale lease on {}: epoch {} belongs to {}", backend.to_address(), shard.to_id(), epoch, owner);
+            }
+            renewal
+        })
+        .collect())
+}
@@ -0,0 +1,65 @@
+use shared::colony_model::Color;
+use shared::coordinator_api::SpeciesCluster;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+/// Clusters reported in the dominant-species summary
+pub const MAX_SPECIES_CLUSTERS: usize = 8;
+/// A color joins a cluster when it is this close (RGB distance) to the cluster's representative.
+/// Larger than the diagonal of a cluster id cell, so two clusters never share an id.
+pub const SPECIES_COLOR_RADIUS: f64 = 60.0;
+// Cluster ids keep the top 3 bits of each channel
+const CLUSTER_ID_MASK: u8 = 0xE0;
+
+/// Parses the "r_g_b" values of the OriginalColor stat
+pub fn parse_original_color(value: &str) -> Option<Color> {
+    let mut parts = value.split('_').map(|part| part.parse::<u8>().ok());
+    let color = Color { red: parts.next()??, green: parts.next()??, blue: parts.next()?? };
+    parts.next().is_none().then_some(color)
+}
+
+fn color_distance(a: &Color, b: &Color) -> f64 {
+    let d = |x: u8, y: u8| (x as f64 - y as f64).powi(2);
+    (d(a.red, b.red) + d(a.green, b.green) + d(a.blue, b.blue)).sqrt()
+}
+
+/// Coarse enough that a species keeps its id while its colors drift within the cluster
+pub fn species_cluster_id(color: &Color) -> String {
+    format!("species-{:02x}{:02x}{:02x}", color.red & CLUSTER_ID_MASK, color.green & CLUSTER_ID_MASK, color.blue & CLUSTER_ID_MASK)
+}
+
+/// Greedy clustering of original colors, most common first. Each cluster is represented by
+/// its most common color; once max_clusters exist, remaining colors join the nearest one.
+/// Clusters are ordered by count, largest first.
+pub fn cluster_species(color_counts: &BTreeMap<String, u64>, max_clusters: usize, radius: f64) -> Vec<SpeciesCluster> {
+    let mut colors: Vec<(Color, u64)> = color_counts.iter()
+        .filter_map(|(value, &count)| parse_original_color(value).map(|color| (color, count)))
+        .filter(|(_, count)| *count > 0)
+        .collect();
+    // Ties keep the BTreeMap order, so the result does not depend on iteration luck
+    colors.sort_by_key(|(_, count)| Reverse(*count));
+
+    let mut clusters: Vec<(Color, u64)> = Vec::new();
+    for (color, count) in colors {
+        let nearest = clusters.iter()
+            .enumerate()
+            .map(|(idx, cluster)| (idx, color_distance(&cluster.0, &color)))
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        match nearest {
+            Some((idx, distance)) if distance <= radius || clusters.len() >= max_clusters => clusters[idx].1 += count,
+            _ if clusters.len() < max_clusters => clusters.push((color, count)),
+            _ => {}
+        }
+    }
+
+    let total: u64 = clusters.iter().map(|(_, count)| count).sum();
+    clusters.sort_by_key(|(_, count)| Reverse(*count));
+    clusters.into_iter()
+        .map(|(color, count)| SpeciesCluster {
+            cluster_id: species_cluster_id(&color),
+            color,
+            count,
+            share: count as f64 / total as f64,
+        })
+        .collect()
+}
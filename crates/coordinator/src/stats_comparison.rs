@@ -0,0 +1,283 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use shared::log_error;
+use shared::coordinator_api::ColonyRunConfig;
+use shared::output_paths::OutputPaths;
+
+pub const COMPARE_STATS_COMMAND: &str = "compare-stats";
+/// Written next to stats_shots when a colony starts, see event_logging::write_run_config_json
+pub const RUN_CONFIG_FILE: &str = "run_config.json";
+
+const CHART_WIDTH: f64 = 800.0;
+const CHART_HEIGHT: f64 = 300.0;
+const SERIES_COLORS: [&str; 2] = ["#1f77b4", "#d62728"];
+
+/// The parts of a stats_shots/{tick}.json file the comparison reads
+#[derive(Deserialize)]
+struct StatsShot {
+    tick: u64,
+    creatures_count: u64,
+    #[serde(default)]
+    histograms: BTreeMap<String, ShotHistogram>,
+}
+
+#[derive(Deserialize)]
+struct ShotHistogram {
+    #[serde(default)]
+    average: Option<f64>,
+}
+
+/// One instance's side of the comparison
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InstanceSummary {
+    pub colony_instance_id: String,
+    /// None for colonies started before the run configuration was written to disk
+    pub run_config: Option<ColonyRunConfig>,
+    pub snapshot_count: usize,
+    pub final_tick: Option<u64>,
+    /// Histogram averages of the last snapshot, keyed by metric
+    pub final_averages: BTreeMap<String, f64>,
+    /// Stats files that could not be parsed and were left out
+    pub skipped_files: Vec<String>,
+}
+
+/// creatures_count of both instances at one tick. A tick only one instance has a snapshot for
+/// is interpolated linearly in the other; None outside the other instance's tick range.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AlignedPoint {
+    pub tick: u64,
+    pub a: Option<f64>,
+    pub b: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StatsComparison {
+    pub a: InstanceSummary,
+    pub b: InstanceSummary,
+    pub creatures_count: Vec<AlignedPoint>,
+}
+
+/// Entry point of `coordinator compare-stats <instance_a> <instance_b> [output_dir]`;
+/// returns the paths of the JSON and HTML reports
+pub fn run_compare_stats(args: &[String]) -> Result<(PathBuf, PathBuf), String> {
+    let [instance_a, instance_b, rest @ ..] = args else {
+        return Err(format!("Usage: coordinator {} <instance_a> <instance_b> [output_dir]", COMPARE_STATS_COMMAND));
+    };
+    let base_dir = OutputPaths::get_instance().bucket_dir();
+    let output_dir = rest.first().map(PathBuf::from).unwrap_or_else(|| base_dir.join("comparisons"));
+    let comparison = compare_instances(&base_dir, instance_a, instance_b)?;
+    write_report(&comparison, &output_dir)
+}
+
+/// Reads both instances from base_dir/{instance_id} and lines up their creatures_count by tick
+pub fn compare_instances(base_dir: &Path, instance_a: &str, instance_b: &str) -> Result<StatsComparison, String> {
+    let (a, series_a) = load_instance(base_dir, instance_a)?;
+    let (b, series_b) = load_instance(base_dir, instance_b)?;
+    Ok(StatsComparison { a, b, creatures_count: align_series(&series_a, &series_b) })
+}
+
+/// Summary of an instance plus its (tick, creatures_count) series in tick order
+fn load_instance(base_dir: &Path, instance_id: &str) -> Result<(InstanceSummary, Vec<(u64, f64)>), String> {
+    let instance_dir = base_dir.join(instance_id);
+    let stats_dir = instance_dir.join("stats_shots");
+    let entries = std::fs::read_dir(&stats_dir)
+        .map_err(|e| format!("Failed to read {}: {}", stats_dir.display(), e))?;
+
+    let mut shots: BTreeMap<u64, StatsShot> = BTreeMap::new();
+    let mut skipped_files = Vec::new();
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+        if path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+        let parsed = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|json| serde_json::from_str::<StatsShot>(&json).map_err(|e| e.to_string()));
+        match parsed {
+            Ok(shot) => {
+                shots.insert(shot.tick, shot);
+            }
+            Err(e) => {
+                log_error!("Skipping stats file {}: {}", path.display(), e);
+                skipped_files.push(entry.file_name().to_string_lossy().into_owned());
+            }
+        }
+    }
+    skipped_files.sort();
+
+    let run_config = read_run_config(&instance_dir.join(RUN_CONFIG_FILE));
+    let last = shots.values().next_back();
+    let summary = InstanceSummary {
+        colony_instance_id: instance_id.to_string(),
+        run_config,
+        snapshot_count: shots.len(),
+        final_tick: last.map(|shot| shot.tick),
+        final_averages: last
+            .map(|shot| shot.histograms.iter()
+                .filter_map(|(metric, histogram)| Some((metric.clone(), histogram.average?)))
+                .collect())
+            .unwrap_or_default(),
+        skipped_files,
+    };
+    let series = shots.values().map(|shot| (shot.tick, shot.creatures_count as f64)).collect();
+    Ok((summary, series))
+}
+
+fn read_run_config(path: &Path) -> Option<ColonyRunConfig> {
+    let json = std::fs::read_to_string(path).ok()?;
+    match serde_json::from_str(&json) {
+        Ok(config) => Some(config),
+        Err(e) => {
+            log_error!("Ignoring unreadable run configuration {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// Both series on the union of their ticks; each series must be in tick order
+pub fn align_series(a: &[(u64, f64)], b: &[(u64, f64)]) -> Vec<AlignedPoint> {
+    let ticks: BTreeSet<u64> = a.iter().chain(b).map(|(tick, _)| *tick).collect();
+    ticks.into_iter()
+        .map(|tick| AlignedPoint { tick, a: value_at(a, tick), b: value_at(b, tick) })
+        .collect()
+}
+
+/// The series' value at tick, interpolated between the snapshots around it
+fn value_at(series: &[(u64, f64)], tick: u64) -> Option<f64> {
+    let after = series.partition_point(|(t, _)| *t < tick);
+    let &(next_tick, next_value) = series.get(after)?;
+    if next_tick == tick {
+        return Some(next_value);
+    }
+    let &(prev_tick, prev_value) = series.get(after.checked_sub(1)?)?;
+    let t = (tick - prev_tick) as f64 / (next_tick - prev_tick) as f64;
+    Some(prev_value + (next_value - prev_value) * t)
+}
+
+/// Writes compare_{a}_{b}.json and .html into output_dir; returns both paths
+pub fn write_report(comparison: &StatsComparison, output_dir: &Path) -> Result<(PathBuf, PathBuf), String> {
+    std::fs::create_dir_all(output_dir)
+        .map_err(|e| format!("Failed to create directory {}: {}", output_dir.display(), e))?;
+    let name = format!("compare_{}_{}", comparison.a.colony_instance_id, comparison.b.colony_instance_id);
+    let json_path = output_dir.join(format!("{}.json", name));
+    let html_path = output_dir.join(format!("{}.html", name));
+
+    let json = serde_json::to_string_pretty(comparison)
+        .map_err(|e| format!("Failed to serialize comparison to JSON: {}", e))?;
+    std::fs::write(&json_path, json)
+        .map_err(|e| format!("Failed to write {}: {}", json_path.display(), e))?;
+    std::fs::write(&html_path, render_html(comparison))
+        .map_err(|e| format!("Failed to write {}: {}", html_path.display(), e))?;
+    Ok((json_path, html_path))
+}
+
+/// Self-contained page: the overlaid creatures_count chart, final averages and both run configurations
+pub fn render_html(comparison: &StatsComparison) -> String {
+    let (a, b) = (&comparison.a, &comparison.b);
+    let mut html = String::new();
+    let _ = writeln!(html, "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{} vs {}</title>",
+        escape(&a.colony_instance_id), escape(&b.colony_instance_id));
+    let _ = writeln!(html, "<style>body{{font-family:sans-serif;margin:20px}}table{{border-collapse:collapse}}\
+        td,th{{border:1px solid #ccc;padding:4px 8px;text-align:left}}.diff{{background:#fff3c4}}</style></head><body>");
+    let _ = writeln!(html, "<h1>Colony stats comparison</h1>");
+    let _ = writeln!(html, "<p><span style=\"color:{}\">&#9632; A: {}</span> &nbsp; <span style=\"color:{}\">&#9632; B: {}</span></p>",
+        SERIES_COLORS[0], escape(&a.colony_instance_id), SERIES_COLORS[1], escape(&b.colony_instance_id));
+
+    let _ = writeln!(html, "<h2>creatures_count</h2>");
+    html.push_str(&render_chart(&comparison.creatures_count));
+
+    let _ = writeln!(html, "<h2>Final averages</h2>\n<table><tr><th>Metric</th><th>A (tick {})</th><th>B (tick {})</th></tr>",
+        a.final_tick.map_or("-".to_string(), |tick| tick.to_string()),
+        b.final_tick.map_or("-".to_string(), |tick| tick.to_string()));
+    let metrics: BTreeSet<&String> = a.final_averages.keys().chain(b.final_averages.keys()).collect();
+    for metric in metrics {
+        let value = |summary: &InstanceSummary| summary.final_averages.get(metric).map_or("-".to_string(), |v| format!("{:.3}", v));
+        let _ = writeln!(html, "<tr><td>{}</td><td>{}</td><td>{}</td></tr>", escape(metric), value(a), value(b));
+    }
+    let _ = writeln!(html, "</table>");
+
+    let _ = writeln!(html, "<h2>Run configurations</h2>\n<table><tr><th>Setting</th><th>A</th><th>B</th></tr>");
+    let (config_a, config_b) = (flatten_config(a.run_config.as_ref()), flatten_config(b.run_config.as_ref()));
+    let keys: BTreeSet<&String> = config_a.keys().chain(config_b.keys()).collect();
+    for key in keys {
+        let (value_a, value_b) = (config_a.get(key), config_b.get(key));
+        let class = if value_a != value_b { " class=\"diff\"" } else { "" };
+        let _ = writeln!(html, "<tr{}><td>{}</td><td>{}</td><td>{}</td></tr>", class, escape(key),
+            escape(value_a.map_or("-", String::as_str)), escape(value_b.map_or("-", String::as_str)));
+    }
+    let _ = writeln!(html, "</table>");
+
+    for summary in [a, b] {
+        if !summary.skipped_files.is_empty() {
+            let _ = writeln!(html, "<p>Skipped unreadable stats files of {}: {}</p>",
+                escape(&summary.colony_instance_id), escape(&summary.skipped_files.join(", ")));
+        }
+    }
+    let _ = writeln!(html, "</body></html>");
+    html
+}
+
+/// Inline SVG with one polyline per instance, broken where the instance has no value
+fn render_chart(points: &[AlignedPoint]) -> String {
+    let mut svg = format!("<svg width=\"{}\" height=\"{}\" style=\"border:1px solid #ccc\">\n", CHART_WIDTH, CHART_HEIGHT);
+    let (Some(first), Some(last)) = (points.first(), points.last()) else {
+        svg.push_str("<text x=\"10\" y=\"20\">No stats snapshots</text>\n</svg>\n");
+        return svg;
+    };
+    let max_value = points.iter().flat_map(|p| [p.a, p.b]).flatten().fold(0.0, f64::max).max(1.0);
+    let tick_span = (last.tick - first.tick).max(1) as f64;
+    let x = |tick: u64| (tick - first.tick) as f64 / tick_span * CHART_WIDTH;
+    let y = |value: f64| CHART_HEIGHT - value / max_value * CHART_HEIGHT;
+
+    let series: [fn(&AlignedPoint) -> Option<f64>; 2] = [|p| p.a, |p| p.b];
+    for (value_of, color) in series.iter().zip(SERIES_COLORS) {
+        let mut segment: Vec<String> = Vec::new();
+        for point in points.iter().map(Some).chain([None]) {
+            match point.and_then(|p| Some((p.tick, value_of(p)?))) {
+                Some((tick, value)) => segment.push(format!("{:.1},{:.1}", x(tick), y(value))),
+                None if !segment.is_empty() => {
+                    let _ = writeln!(svg, "<polyline fill=\"none\" stroke=\"{}\" stroke-width=\"2\" points=\"{}\"/>", color, segment.join(" "));
+                    segment.clear();
+                }
+                None => {}
+            }
+        }
+    }
+    let _ = writeln!(svg, "<text x=\"4\" y=\"14\" font-size=\"12\">{}</text>", max_value as u64);
+    let _ = writeln!(svg, "<text x=\"4\" y=\"{}\" font-size=\"12\">tick {}</text>", CHART_HEIGHT - 4.0, first.tick);
+    let _ = writeln!(svg, "<text x=\"{}\" y=\"{}\" font-size=\"12\" text-anchor=\"end\">tick {}</text>", CHART_WIDTH - 4.0, CHART_HEIGHT - 4.0, last.tick);
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Run configuration as dotted paths, e.g. initial_rules.mutation_chance -> 100
+fn flatten_config(config: Option<&ColonyRunConfig>) -> BTreeMap<String, String> {
+    let mut flat = BTreeMap::new();
+    if let Some(value) = config.and_then(|config| serde_json::to_value(config).ok()) {
+        flatten_value("", &value, &mut flat);
+    }
+    flat
+}
+
+fn flatten_value(prefix: &str, value: &serde_json::Value, flat: &mut BTreeMap<String, String>) {
+    match value {
+        serde_json::Value::Object(fields) => {
+            for (key, field) in fields {
+                let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                flatten_value(&path, field, flat);
+            }
+        }
+        serde_json::Value::String(s) => {
+            flat.insert(prefix.to_string(), s.clone());
+        }
+        other => {
+            flat.insert(prefix.to_string(), other.to_string());
+        }
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
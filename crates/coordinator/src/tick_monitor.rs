@@ -1,4 +1,15 @@
-use std::time::Instant;
+use shared::coordinator_api::TickSample;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use crate::coordinator_context::CoordinatorContext;
+
+const TICK_HISTORY_CAPACITY_ENV: &str = "TICK_HISTORY_CAPACITY";
+const TICK_HISTORY_RETENTION_ENV: &str = "TICK_HISTORY_RETENTION_SECS";
+const TICK_HISTORY_SAMPLE_ENV: &str = "TICK_HISTORY_SAMPLE_SECS";
+/// An hour at one sample every 5 seconds
+const DEFAULT_TICK_HISTORY_CAPACITY: usize = 720;
+const DEFAULT_TICK_HISTORY_RETENTION: Duration = Duration::from_secs(3600);
+const DEFAULT_TICK_HISTORY_SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
 
 pub struct TickMonitor {
     last_tick: u64,
@@ -37,3 +48,99 @@ impl TickMonitor {
         }
     }
 }
+
+/// Ring buffer of cluster tick samples; the oldest are dropped past capacity or retention
+#[derive(Debug)]
+pub struct TickHistory {
+    capacity: usize,
+    retention: Duration,
+    samples: VecDeque<TickSample>,
+}
+
+impl TickHistory {
+    pub fn new(capacity: usize, retention: Duration) -> Self {
+        Self { capacity: capacity.max(1), retention, samples: VecDeque::new() }
+    }
+
+    /// Capacity and retention from TICK_HISTORY_CAPACITY and TICK_HISTORY_RETENTION_SECS
+    pub fn from_env() -> Self {
+        let capacity = std::env::var(TICK_HISTORY_CAPACITY_ENV)
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(DEFAULT_TICK_HISTORY_CAPACITY);
+        let retention = std::env::var(TICK_HISTORY_RETENTION_ENV)
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_TICK_HISTORY_RETENTION);
+        TickHistory::new(capacity, retention)
+    }
+
+    pub fn record(&mut self, sample: TickSample) {
+        self.samples.push_back(sample);
+        let oldest_kept_ms = sample.timestamp_ms.saturating_sub(self.retention.as_millis() as u64);
+        while self.samples.len() > self.capacity
+            || self.samples.front().is_some_and(|oldest| oldest.timestamp_ms < oldest_kept_ms) {
+            self.samples.pop_front();
+        }
+    }
+
+    /// The most recent sweep, if any
+    pub fn latest(&self) -> Option<TickSample> {
+        self.samples.back().copied()
+    }
+
+    /// Samples from the last `minutes` before now_ms, oldest first
+    pub fn since(&self, minutes: u64, now_ms: u64) -> Vec<TickSample> {
+        let from_ms = now_ms.saturating_sub(minutes.saturating_mul(60_000));
+        self.samples.iter().filter(|sample| sample.timestamp_ms >= from_ms).copied().collect()
+    }
+}
+
+pub fn unix_time_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
+
+pub fn tick_history_sample_interval() -> Duration {
+    std::env::var(TICK_HISTORY_SAMPLE_ENV)
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_TICK_HISTORY_SAMPLE_INTERVAL)
+}
+
+/// Min and max tick over the backends' reported ranges, None when no backend reported one
+pub fn cluster_tick_range(ranges: impl IntoIterator<Item = (u64, u64)>) -> Option<(u64, u64)> {
+    ranges.into_iter().reduce(|(min_a, max_a), (min_b, max_b)| (min_a.min(min_b), max_a.max(max_b)))
+}
+
+/// Highest shard tick of the latest record_tick_history sweep, without querying the backends
+pub fn latest_max_tick() -> Option<u64> {
+    CoordinatorContext::get_instance().tick_history().latest().map(|sample| sample.max_tick)
+}
+
+/// Lowest shard tick of the latest record_tick_history sweep, without querying the backends
+pub fn latest_min_tick() -> Option<u64> {
+    CoordinatorContext::get_instance().tick_history().latest().map(|sample| sample.min_tick)
+}
+
+/// Samples the cluster tick range forever; runs whether or not a GUI is connected. Each sweep
+/// also feeds the /ws tick and backend health updates.
+pub async fn record_tick_history() {
+    let mut timer = tokio::time::interval(tick_history_sample_interval());
+    loop {
+        timer.tick().await;
+        let Some(topology) = shared::cluster_topology::ClusterTopology::get_instance() else {
+            continue;
+        };
+        let statuses = crate::backend_status::backend_statuses(&topology).await;
+        if let Some((min_tick, max_tick)) = cluster_tick_range(statuses.backends.iter().filter_map(|backend| backend.tick_range)) {
+            let sample = TickSample { timestamp_ms: unix_time_ms(), min_tick, max_tick };
+            CoordinatorContext::get_instance().tick_history().record(sample);
+            crate::live_feed_hub::publish_tick(sample);
+        }
+        crate::lifecycle_events::check_registry_membership(latest_max_tick().unwrap_or(0)).await;
+        crate::live_feed_hub::publish_backend_health(&topology, &statuses.backends).await;
+    }
+}
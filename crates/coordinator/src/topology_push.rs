@@ -0,0 +1,69 @@
+//! Backends subscribed to topology changes over a long-lived RPC connection. After every change
+//! to shard_to_host the coordinator pushes the whole ClusterTopology, so backends send borders to
+//! a reassigned shard's new owner without waiting for an UpdateTopology round.
+use shared::cluster_topology::{ClusterTopology, HostInfo};
+use shared::log;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use crate::coordinator_context::CoordinatorContext;
+
+/// A subscriber that takes longer than this to accept a push is dropped; it re-subscribes
+pub const TOPOLOGY_PUSH_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug)]
+struct TopologySubscriber {
+    backend: HostInfo,
+    sender: UnboundedSender<Arc<ClusterTopology>>,
+}
+
+/// One queue per subscribed backend; the connection serving a subscription drains it
+#[derive(Debug, Default)]
+pub struct TopologySubscribers {
+    subscribers: Vec<TopologySubscriber>,
+}
+
+impl TopologySubscribers {
+    /// Registers backend, replacing an older subscription of it. current, when set, is queued
+    /// first, so a reconnecting backend re-syncs with the full table.
+    pub fn subscribe(&mut self, backend: HostInfo, current: Option<Arc<ClusterTopology>>) -> UnboundedReceiver<Arc<ClusterTopology>> {
+        let (sender, receiver) = unbounded_channel();
+        if let Some(topology) = current {
+            let _ = sender.send(topology);
+        }
+        self.subscribers.retain(|subscriber| subscriber.backend != backend && !subscriber.sender.is_closed());
+        self.subscribers.push(TopologySubscriber { backend, sender });
+        receiver
+    }
+
+    /// Queues topology for every subscriber and drops those whose connection ended.
+    /// Returns the backends it was queued for.
+    pub fn publish(&mut self, topology: Arc<ClusterTopology>) -> Vec<HostInfo> {
+        self.subscribers.retain(|subscriber| subscriber.sender.send(topology.clone()).is_ok());
+        self.backends()
+    }
+
+    pub fn backends(&self) -> Vec<HostInfo> {
+        self.subscribers.iter()
+            .filter(|subscriber| !subscriber.sender.is_closed())
+            .map(|subscriber| subscriber.backend.clone())
+            .collect()
+    }
+}
+
+/// Subscribes backend to the coordinator's topology, starting with the current one
+pub fn subscribe_topology(backend: HostInfo) -> UnboundedReceiver<Arc<ClusterTopology>> {
+    let mut subscribers = CoordinatorContext::get_instance().topology_subscribers();
+    // Read under the lock, so a change published meanwhile is queued after this one
+    subscribers.subscribe(backend, ClusterTopology::get_instance())
+}
+
+/// Pushes the coordinator's topology to the subscribed backends; called after every change to it
+pub fn publish_topology() {
+    let mut subscribers = CoordinatorContext::get_instance().topology_subscribers();
+    let Some(topology) = ClusterTopology::get_instance() else {
+        return;
+    };
+    let backends = subscribers.publish(topology);
+    log!("Pushed topology to {} subscribed backends", backends.len());
+}
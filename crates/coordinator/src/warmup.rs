@@ -0,0 +1,39 @@
+//! Warm-up of a colony started with warmup_ticks. The first ticks after seeding are transient
+//! noise, so until the slowest shard reaches warmup_ticks no random events are generated, the
+//! periodic stats and frame captures are skipped and /health reports "warming-up". The end is
+//! recorded as a colony event and followed by a stats capture that serves as the baseline.
+use shared::colony_event_shared::WARMUP_ENDED_EVENT;
+use shared::log_error;
+use crate::colony_stats::save_colony_stats;
+use crate::coordinator_context::CoordinatorContext;
+use crate::coordinator_storage::CoordinatorStoredInfo;
+use crate::lifecycle_events::record_lifecycle_event;
+use crate::tick_monitor::latest_min_tick;
+
+/// Whether the colony is still warming up at min_tick, the slowest shard's tick. Without a tick
+/// sample yet a colony with a warm-up counts as warming up.
+pub fn is_warming_up(stored_info: &CoordinatorStoredInfo, min_tick: Option<u64>) -> bool {
+    !stored_info.warmup_ended && stored_info.warmup_ticks.is_some_and(|warmup_ticks| min_tick.is_none_or(|tick| tick < warmup_ticks))
+}
+
+/// The warm-up tick once the slowest shard reached it, unless the end was already recorded
+pub fn reached_warmup(stored_info: &CoordinatorStoredInfo, min_tick: Option<u64>) -> Option<u64> {
+    if stored_info.warmup_ended {
+        return None;
+    }
+    stored_info.warmup_ticks.filter(|warmup_ticks| min_tick.is_some_and(|tick| tick >= *warmup_ticks))
+}
+
+pub fn is_colony_warming_up() -> bool {
+    is_warming_up(&CoordinatorContext::get_instance().get_coord_stored_info(), latest_min_tick())
+}
+
+/// Records the end of the warm-up, reached at tick, and takes the baseline stats capture
+pub async fn end_warmup(warmup_ticks: u64, tick: u64) {
+    CoordinatorContext::get_instance().get_coord_stored_info().warmup_ended = true;
+    record_lifecycle_event(WARMUP_ENDED_EVENT, tick,
+        format!("Every shard passed warm-up tick {}, events and captures resume", warmup_ticks));
+    if save_colony_stats().await.is_none() {
+        log_error!("Baseline statistics capture after the warm-up failed");
+    }
+}
@@ -0,0 +1,54 @@
+//! Fixtures shared by the coordinator integration tests
+use backend::be_server::dispatch_request;
+use futures_util::{SinkExt, StreamExt};
+use shared::backend_communication::accept_hello;
+use shared::be_api::BackendRequest;
+use shared::output_paths::OUTPUT_DIR_ENV;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::net::TcpListener;
+use tokio_util::codec::{Framed, LengthDelimitedCodec};
+
+/// A new empty directory under the system temp dir, unique per process and call
+pub fn temp_dir(name: &str) -> PathBuf {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).expect("Clock before epoch").as_nanos();
+    let dir = std::env::temp_dir().join(format!("{}_{}_{}", name, std::process::id(), nanos));
+    std::fs::create_dir_all(&dir).expect("Failed to create temp dir");
+    dir
+}
+
+/// Points COLONY_OUTPUT_DIR at a temp dir, so the servers a test starts write their logs there
+/// rather than into the crate. Call it before anything reads OutputPaths.
+#[allow(dead_code)]
+pub fn use_temp_output_dir() {
+    static OUTPUT_DIR: OnceLock<PathBuf> = OnceLock::new();
+    OUTPUT_DIR.get_or_init(|| {
+        let dir = temp_dir("colony_output");
+        std::env::set_var(OUTPUT_DIR_ENV, &dir);
+        dir
+    });
+}
+
+/// Answers the RPC handshake and requests through the backend's own dispatch, in this process
+#[allow(dead_code)]
+pub async fn spawn_live_backend() -> u16 {
+    let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        while let Ok((socket, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let mut framed = Framed::new(socket, LengthDelimitedCodec::new());
+                if accept_hello(&mut framed, "test").await.is_none() {
+                    return;
+                }
+                while let Some(Ok(bytes)) = framed.next().await {
+                    let request: BackendRequest = bincode::deserialize(&bytes).unwrap();
+                    let response = bincode::serialize(&dispatch_request(request).await).unwrap();
+                    let _ = framed.send(response.into()).await;
+                }
+            });
+        }
+    });
+    port
+}
@@ -0,0 +1,113 @@
+mod common;
+
+use coordinator::colony_start::discover_healthy_backends;
+use coordinator::coordinator_error::CoordinatorError;
+use shared::cluster_registry::{ClusterRegistry, ClusterRegistryImpl, FileClusterRegistry};
+use shared::cluster_topology::{HostInfo, NodeAddress};
+use std::path::{Path, PathBuf};
+use tokio::net::TcpListener;
+use common::spawn_live_backend;
+
+const LOCALHOST: &str = "127.0.0.1";
+
+fn address(rpc_port: u16) -> NodeAddress {
+    NodeAddress::new(LOCALHOST.to_string(), LOCALHOST.to_string(), rpc_port, rpc_port + 1)
+}
+
+fn coordinator() -> NodeAddress {
+    address(1)
+}
+
+/// A port nothing listens on, like the one of a crashed backend
+fn dead_port() -> u16 {
+    let listener = std::net::TcpListener::bind((LOCALHOST, 0)).unwrap();
+    listener.local_addr().unwrap().port()
+}
+
+/// Accepts connections but never answers the handshake, like a busy backend
+async fn spawn_silent_backend() -> u16 {
+    let listener = TcpListener::bind((LOCALHOST, 0)).await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        let mut held = Vec::new();
+        while let Ok((socket, _)) = listener.accept().await {
+            held.push(socket);
+        }
+    });
+    port
+}
+
+fn temp_registry(name: &str) -> (PathBuf, ClusterRegistryImpl) {
+    let dir = std::env::temp_dir().join(format!("backend_discovery_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    (dir.clone(), ClusterRegistryImpl::File(FileClusterRegistry::with_base_path(dir)))
+}
+
+fn registered_ids(dir: &Path) -> Vec<String> {
+    let mut ids: Vec<String> = std::fs::read_dir(dir.join("backends")).unwrap()
+        .map(|entry| entry.unwrap().path().file_stem().unwrap().to_string_lossy().into_owned())
+        .collect();
+    ids.sort();
+    ids
+}
+
+#[tokio::test]
+async fn test_orphaned_entries_are_dropped_and_deleted() {
+    let (dir, registry) = temp_registry("mixed");
+    let live = [spawn_live_backend().await, spawn_live_backend().await];
+    let dead = dead_port();
+    registry.register_backend("live-a".to_string(), address(live[0])).await.unwrap();
+    registry.register_backend("live-b".to_string(), address(live[1])).await.unwrap();
+    registry.register_backend("crashed".to_string(), address(dead)).await.unwrap();
+
+    let mut healthy = discover_healthy_backends(&registry, &coordinator(), 2).await.unwrap();
+    healthy.sort_by_key(|host| host.port);
+    let mut expected: Vec<HostInfo> = live.iter().map(|&port| HostInfo::new(LOCALHOST.to_string(), port)).collect();
+    expected.sort_by_key(|host| host.port);
+    assert_eq!(healthy, expected);
+    assert_eq!(registered_ids(&dir), vec!["live-a", "live-b"]);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_unresponsive_backend_is_skipped_but_keeps_its_entry() {
+    let (dir, registry) = temp_registry("silent");
+    let live = spawn_live_backend().await;
+    let silent = spawn_silent_backend().await;
+    registry.register_backend("live".to_string(), address(live)).await.unwrap();
+    registry.register_backend("busy".to_string(), address(silent)).await.unwrap();
+
+    let healthy = discover_healthy_backends(&registry, &coordinator(), 1).await.unwrap();
+    assert_eq!(healthy, vec![HostInfo::new(LOCALHOST.to_string(), live)]);
+    assert_eq!(registered_ids(&dir), vec!["busy", "live"]);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_too_few_healthy_backends_fails_listing_the_dead_entries() {
+    let (dir, registry) = temp_registry("too_few");
+    let live = spawn_live_backend().await;
+    let dead = [dead_port(), dead_port()];
+    registry.register_backend("live".to_string(), address(live)).await.unwrap();
+    for (index, &port) in dead.iter().enumerate() {
+        registry.register_backend(format!("crashed-{}", index), address(port)).await.unwrap();
+    }
+
+    let error = discover_healthy_backends(&registry, &coordinator(), 2).await.unwrap_err();
+    let CoordinatorError::NotEnoughBackends { healthy, required, dead: mut listed } = error else {
+        panic!("unexpected error: {}", error);
+    };
+    assert_eq!((healthy, required), (1, 2));
+    listed.sort();
+    let mut expected: Vec<String> = dead.iter().map(|&port| format!("{}:{}", LOCALHOST, port)).collect();
+    expected.sort();
+    assert_eq!(listed, expected);
+    // Dead entries are cleaned up even though the start fails
+    assert_eq!(registered_ids(&dir), vec!["live"]);
+
+    // An empty registry is not a list of dead entries
+    let (empty_dir, empty) = temp_registry("empty");
+    assert!(matches!(discover_healthy_backends(&empty, &coordinator(), 1).await, Err(CoordinatorError::NoBackendsAvailable)));
+    std::fs::remove_dir_all(&dir).unwrap();
+    std::fs::remove_dir_all(&empty_dir).unwrap();
+}
@@ -0,0 +1,58 @@
+use coordinator::backend_status::{merge_backend_status, BackendReport};
+use shared::be_api::ShardLease;
+use shared::colony_model::Shard;
+
+fn shard(x: i32, y: i32) -> Shard {
+    Shard { x, y, width: 10, height: 10 }
+}
+
+fn report(hosted_shards: Vec<Shard>) -> Result<BackendReport, String> {
+    let leases = hosted_shards.iter().map(|shard| ShardLease { shard: *shard, epoch: 1 }).collect();
+    Ok(BackendReport { hosted_shards, tick_range: Some((40, 42)), version: Some("1.2.3".to_string()), leases, suspended_shards: Vec::new() })
+}
+
+#[test]
+fn test_matching_backend_has_no_discrepancies() {
+    let assigned = vec![shard(10, 0), shard(0, 0)];
+    let status = merge_backend_status("10.0.0.1:8084".to_string(), &assigned, report(vec![shard(0, 0), shard(10, 0)]), |_| Some(1));
+    assert!(status.healthy);
+    assert!(!status.has_discrepancies());
+    assert_eq!(status.assigned_shards, vec![shard(0, 0).to_id(), shard(10, 0).to_id()]);
+    assert_eq!(status.hosted_shards, Some(status.assigned_shards.clone()));
+    assert_eq!(status.tick_range, Some((40, 42)));
+    assert_eq!(status.version.as_deref(), Some("1.2.3"));
+}
+
+#[test]
+fn test_discrepancies_listed_both_ways() {
+    // Shard (10,0) failed to init here, and (0,10) was moved away but never dropped
+    let assigned = vec![shard(0, 0), shard(10, 0)];
+    let status = merge_backend_status("10.0.0.1:8084".to_string(), &assigned, report(vec![shard(0, 0), shard(0, 10)]), |_| Some(1));
+    assert!(status.has_discrepancies());
+    assert_eq!(status.assigned_not_hosting, vec![shard(10, 0).to_id()]);
+    assert_eq!(status.hosting_not_assigned, vec![shard(0, 10).to_id()]);
+}
+
+#[test]
+fn test_unreachable_backend_reports_no_discrepancies() {
+    let assigned = vec![shard(0, 0)];
+    let status = merge_backend_status("10.0.0.1:8084".to_string(), &assigned, Err("Timed out".to_string()), |_| Some(1));
+    assert!(!status.healthy);
+    assert_eq!(status.hosted_shards, None);
+    assert_eq!(status.error.as_deref(), Some("Timed out"));
+    assert!(!status.has_discrepancies());
+    assert_eq!(status.assigned_shards, vec![shard(0, 0).to_id()]);
+}
+
+#[test]
+fn test_lease_mismatches_and_suspended_shards() {
+    let assigned = vec![shard(0, 0), shard(10, 0)];
+    let mut backend_report = report(assigned.clone()).unwrap();
+    backend_report.suspended_shards = vec![shard(10, 0)];
+    // The coordinator reissued (10,0) to another backend; (0,0) was issued by a coordinator that is gone
+    let issued_epoch = |shard: &Shard| if shard.x == 10 { Some(2) } else { None };
+    let status = merge_backend_status("10.0.0.1:8084".to_string(), &assigned, Ok(backend_report), issued_epoch);
+    assert!(status.has_discrepancies());
+    assert_eq!(status.lease_mismatches, vec![format!("{}: epoch 1, coordinator issued 2", shard(10, 0).to_id())]);
+    assert_eq!(status.suspended_shards, vec![shard(10, 0).to_id()]);
+}
@@ -0,0 +1,60 @@
+use coordinator::biomes::{default_biomes, randomize_biome_shift, shift_biome_edge};
+use coordinator::init_colony::COLONY_LIFE_INITIAL_RULES;
+use shared::colony_events::ColonyEvent;
+use shared::colony_model::{validate_biomes, Biome, ColonyLifeRulesOverride};
+use shared::utils::new_seeded_random_generator;
+
+const WIDTH: i32 = 500;
+const HEIGHT: i32 = 500;
+
+fn biome(x: i32, y: i32, width: i32, height: i32) -> Biome {
+    Biome { name: "test".to_string(), x, y, width, height, overrides: ColonyLifeRulesOverride::default() }
+}
+
+#[test]
+fn test_default_biome_is_a_harsher_north_half() {
+    let biomes = default_biomes(WIDTH, HEIGHT);
+    assert_eq!(biomes.len(), 1);
+    assert_eq!((biomes[0].x, biomes[0].y, biomes[0].width, biomes[0].height), (0, 0, WIDTH, HEIGHT / 2));
+    assert!(validate_biomes(&biomes, &COLONY_LIFE_INITIAL_RULES).is_ok());
+    let north = biomes[0].overrides.apply(&COLONY_LIFE_INITIAL_RULES);
+    assert!(north.health_cost_per_size_unit > COLONY_LIFE_INITIAL_RULES.health_cost_per_size_unit);
+    assert!(north.random_death_chance < COLONY_LIFE_INITIAL_RULES.random_death_chance);
+}
+
+#[test]
+fn test_invalid_override_is_rejected() {
+    let mut invalid = biome(0, 0, 10, 10);
+    invalid.overrides.mutation_chance = Some(0);
+    let error = validate_biomes(&[invalid], &COLONY_LIFE_INITIAL_RULES).unwrap_err();
+    assert!(error.contains("mutation_chance"), "{}", error);
+    assert!(validate_biomes(&[biome(0, 0, 0, 10)], &COLONY_LIFE_INITIAL_RULES).is_err());
+}
+
+#[test]
+fn test_edge_shift_stays_inside_the_colony() {
+    let mut north = biome(0, 0, WIDTH, HEIGHT / 2);
+    assert_eq!(shift_biome_edge(&mut north, 3, 30, WIDTH, HEIGHT), (250, 280));
+    assert_eq!(north.height, 280);
+
+    assert_eq!(shift_biome_edge(&mut north, 2, -30, WIDTH, HEIGHT), (0, 0));
+    assert_eq!(shift_biome_edge(&mut north, 1, 30, WIDTH, HEIGHT), (WIDTH, WIDTH));
+    // An edge never crosses the opposite one
+    assert_eq!(shift_biome_edge(&mut north, 3, -1000, WIDTH, HEIGHT), (280, 1));
+    assert_eq!((north.y, north.height), (0, 1));
+}
+
+#[test]
+fn test_random_shift_moves_one_edge() {
+    let biomes = vec![biome(0, 0, WIDTH, HEIGHT / 2)];
+    let mut rng = new_seeded_random_generator(3);
+    for _ in 0..50 {
+        let ColonyEvent::ChangeBiomes(change) = randomize_biome_shift(&biomes, WIDTH, HEIGHT, &mut rng) else {
+            panic!("Expected a biome change");
+        };
+        let shifted = &change.biomes[0];
+        assert!(shifted.x >= 0 && shifted.y >= 0 && shifted.width >= 1 && shifted.height >= 1);
+        assert!(shifted.x + shifted.width <= WIDTH && shifted.y + shifted.height <= HEIGHT);
+        assert!(change.description.starts_with("Moved the "), "{}", change.description);
+    }
+}
@@ -0,0 +1,94 @@
+use coordinator::bootstrap::build_bootstrap;
+use shared::api_auth::ApiScope;
+use shared::cluster_topology::{ClusterTopology, HostInfo, NodeAddress};
+use shared::colony_model::Shard;
+use shared::coordinator_api::{BackendStatus, TopologySummary};
+
+fn host(ip: &str, port: u16) -> HostInfo {
+    HostInfo::new(ip.to_string(), port)
+}
+
+/// Two by one shards of 100x50 cells on two backends
+fn topology() -> ClusterTopology {
+    ClusterTopology {
+        coordinator_host: host("10.0.0.1", 8082),
+        backend_hosts: vec![host("10.0.0.2", 8084), host("10.0.0.3", 8084)],
+        shard_to_host: [
+            (Shard { x: 0, y: 0, width: 100, height: 50 }, host("10.0.0.2", 8084)),
+            (Shard { x: 100, y: 0, width: 100, height: 50 }, host("10.0.0.3", 8084)),
+        ].into_iter().collect(),
+    }
+}
+
+/// The second backend is not in the registry
+fn addresses() -> Vec<NodeAddress> {
+    vec![
+        NodeAddress::new("10.0.0.1".to_string(), "52.0.0.1".to_string(), 8082, 8083),
+        NodeAddress::new("10.0.0.2".to_string(), "52.0.0.2".to_string(), 8084, 8085),
+    ]
+}
+
+fn status(healthy: bool, error: Option<&str>) -> BackendStatus {
+    BackendStatus {
+        backend: String::new(),
+        assigned_shards: Vec::new(),
+        hosted_shards: None,
+        tick_range: None,
+        healthy,
+        version: None,
+        error: error.map(str::to_string),
+        assigned_not_hosting: Vec::new(),
+        hosting_not_assigned: Vec::new(),
+        lease_mismatches: Vec::new(),
+        suspended_shards: Vec::new(),
+        breaker: Default::default(),
+    }
+}
+
+fn bootstrap(scope: ApiScope) -> shared::coordinator_api::BootstrapResponse {
+    let topology = topology();
+    let addresses = addresses();
+    let statuses = [status(true, None), status(false, Some("Connection refused"))];
+    build_bootstrap("TopographyInitialized".to_string(), Some("run-a".to_string()), Some(&addresses[0]),
+                    Some(&topology), &addresses, &statuses, scope)
+}
+
+#[test]
+fn test_admin_bootstrap_lists_backend_ports_and_health() {
+    let response = bootstrap(ApiScope::Admin);
+    assert_eq!(response.coordinator_http.as_deref(), Some("52.0.0.1:8083"));
+    assert_eq!((response.colony_status.as_str(), response.colony_instance_id.as_deref()), ("TopographyInitialized", Some("run-a")));
+    assert_eq!(response.summary, Some(TopologySummary {
+        colony_width: 200, colony_height: 50, width_in_shards: 2, height_in_shards: 1, shard_width: 100, shard_height: 50,
+    }));
+    assert_eq!(response.topology.unwrap().get_all_backend_hosts(), &topology().backend_hosts);
+
+    let [known, unregistered] = &response.backends[..] else { panic!("{:?}", response.backends) };
+    assert_eq!(known.host, host("10.0.0.2", 8084));
+    assert_eq!((known.public_ip.as_deref(), known.private_ip.as_deref()), (Some("52.0.0.2"), Some("10.0.0.2")));
+    assert_eq!((known.rpc_port, known.http_port, known.healthy), (8084, Some(8085), true));
+    assert_eq!((unregistered.public_ip.as_deref(), unregistered.http_port), (None, None));
+    assert!(!unregistered.healthy);
+    assert_eq!(unregistered.error.as_deref(), Some("Connection refused"));
+}
+
+#[test]
+fn test_observer_bootstrap_hides_private_addresses() {
+    let response = bootstrap(ApiScope::Observer);
+    let json = serde_json::to_string(&response).unwrap();
+    assert!(!json.contains("10.0.0."), "{}", json);
+    // Backends are named like in the observer's topology, so the GUI can map them
+    let topology = response.topology.unwrap();
+    for (backend, shown) in response.backends.iter().zip(topology.get_all_backend_hosts()) {
+        assert_eq!(&backend.host, shown);
+        assert_eq!(backend.private_ip, None);
+    }
+    assert_eq!(response.backends[0].host, host("52.0.0.2", 8084));
+}
+
+#[test]
+fn test_bootstrap_before_colony_start() {
+    let response = build_bootstrap("NotInitialized".to_string(), None, None, None, &[], &[], ApiScope::Admin);
+    assert!(response.topology.is_none() && response.summary.is_none() && response.backends.is_empty());
+    assert!(!response.coordinator_version.is_empty());
+}
@@ -0,0 +1,65 @@
+use coordinator::capture_config::{describe_capture_config_change, run_periodic, update_capture_config, validate_capture_config};
+use coordinator::coordinator_context::CoordinatorContext;
+use shared::coordinator_api::CaptureConfig;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+const CONFIG: CaptureConfig = CaptureConfig {
+    capture_interval_secs: 60,
+    stats_interval_secs: 10,
+    captures_enabled: true,
+    stats_enabled: true,
+};
+
+#[test]
+fn test_intervals_under_one_second_are_rejected() {
+    assert!(validate_capture_config(&CONFIG).is_ok());
+    let error = validate_capture_config(&CaptureConfig { capture_interval_secs: 0, ..CONFIG }).unwrap_err();
+    assert!(error.contains("capture_interval_secs"), "{}", error);
+    let error = validate_capture_config(&CaptureConfig { stats_interval_secs: 0, ..CONFIG }).unwrap_err();
+    assert!(error.contains("stats_interval_secs"), "{}", error);
+}
+
+#[test]
+fn test_change_description_lists_what_changed() {
+    assert_eq!(describe_capture_config_change(&CONFIG, &CONFIG), "no change");
+    let new = CaptureConfig { capture_interval_secs: 30, stats_enabled: false, ..CONFIG };
+    assert_eq!(describe_capture_config_change(&CONFIG, &new), "capture interval 60s -> 30s, stats disabled");
+}
+
+#[tokio::test]
+async fn test_loop_follows_config_updates_without_restart() {
+    let context = CoordinatorContext::get_instance();
+    context.set_capture_config(CaptureConfig { capture_interval_secs: 3600, captures_enabled: false, ..CONFIG });
+    let runs = Arc::new(AtomicUsize::new(0));
+    let periodic = {
+        let runs = Arc::clone(&runs);
+        tokio::spawn(run_periodic(
+            |config| (config.capture_interval_secs, config.captures_enabled),
+            move || {
+                let runs = Arc::clone(&runs);
+                async move { runs.fetch_add(1, Ordering::SeqCst); }
+            },
+        ))
+    };
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    // Shortening the interval wakes the loop, but a disabled iteration is a no-op
+    update_capture_config(CaptureConfig { capture_interval_secs: 1, captures_enabled: false, ..CONFIG }).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    assert_eq!(runs.load(Ordering::SeqCst), 0);
+
+    update_capture_config(CaptureConfig { capture_interval_secs: 1, ..CONFIG }).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    assert!(runs.load(Ordering::SeqCst) >= 1);
+
+    assert!(update_capture_config(CaptureConfig { capture_interval_secs: 0, ..CONFIG }).await.is_err());
+    assert_eq!(context.get_capture_config().capture_interval_secs, 1);
+    let audit: Vec<String> = context.get_colony_events().into_iter()
+        .filter(|event| event.event_type == "Capture Config Change")
+        .map(|event| event.description)
+        .collect();
+    assert_eq!(audit, vec!["capture interval 3600s -> 1s".to_string(), "captures enabled".to_string()]);
+    periodic.abort();
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use coordinator::capture_frames::{frame_file_name, parse_frame_tick, CaptureStore};
+use std::path::Path;
+use common::temp_dir;
+
+/// Frames at ticks 10, 20 and 30 (tick bytes long), tick 20 with a sidecar, plus the capture summary
+fn write_frames(dir: &Path) {
+    for tick in [10u64, 20, 30] {
+        std::fs::write(dir.join(frame_file_name(tick)), vec![0u8; tick as usize]).unwrap();
+    }
+    std::fs::write(dir.join("0000020.missing.json"), r#"{"tick":20,"total_shards":4,"missing_shards":["0_10_10_10"]}"#).unwrap();
+    std::fs::write(dir.join("capture_summary.json"), "{}").unwrap();
+}
+
+#[test]
+fn test_list_captures_with_range_and_sidecars() {
+    let dir = temp_dir("capture_frames_list");
+    write_frames(&dir);
+    let store = CaptureStore::new("abc", dir.clone());
+
+    let list = store.list(None, None).expect("list failed");
+    assert_eq!(list.colony_instance_id, "abc");
+    let ticks: Vec<(u64, u64, usize)> = list.frames.iter().map(|f| (f.tick, f.bytes, f.missing_shards.len())).collect();
+    assert_eq!(ticks, vec![(10, 10, 0), (20, 20, 1), (30, 30, 0)]);
+    assert_eq!(list.frames[1].url, "/api/captures/0000020.png");
+    assert_eq!(list.frames[1].missing_shards, vec!["0_10_10_10".to_string()]);
+    assert!(!list.frames[0].timestamp.is_empty());
+
+    let ranged = store.list(Some(15), Some(30)).expect("list failed");
+    assert_eq!(ranged.frames.iter().map(|f| f.tick).collect::<Vec<_>>(), vec![20, 30]);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_prune_removes_frames_and_sidecars_below_tick() {
+    let dir = temp_dir("capture_frames_prune");
+    write_frames(&dir);
+    let store = CaptureStore::new("abc", dir.clone());
+
+    assert_eq!(store.prune(30).expect("prune failed"), 2);
+    assert!(!dir.join("0000020.missing.json").exists());
+    assert!(dir.join("capture_summary.json").exists());
+    assert_eq!(store.list(None, None).unwrap().frames.iter().map(|f| f.tick).collect::<Vec<_>>(), vec![30]);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_missing_capture_dir_lists_nothing() {
+    let store = CaptureStore::new("abc", std::env::temp_dir().join("capture_frames_never_written"));
+    assert!(store.list(None, None).unwrap().frames.is_empty());
+    assert_eq!(store.prune(100).unwrap(), 0);
+}
+
+#[test]
+fn test_parse_frame_tick() {
+    assert_eq!(parse_frame_tick("0000042.png"), Some(42));
+    assert_eq!(parse_frame_tick("42.png"), Some(42));
+    assert_eq!(parse_frame_tick("0000042.missing.json"), None);
+    assert_eq!(parse_frame_tick("../secret.png"), None);
+    assert_eq!(parse_frame_tick(".png"), None);
+}
@@ -0,0 +1,61 @@
+use coordinator::capture_gate::{CaptureGate, CaptureSkip, CaptureState};
+use coordinator::coordinator_storage::{ColonyStatus, CoordinatorStoredInfo};
+
+fn stored_info(status: ColonyStatus, ticking_started: bool) -> CoordinatorStoredInfo {
+    let mut info = CoordinatorStoredInfo::new();
+    info.status = status;
+    info.ticking_started = ticking_started;
+    info.colony_instance_id = Some("run-a".to_string());
+    info
+}
+
+fn ticking(max_tick: u64) -> CaptureState {
+    CaptureState::from_stored_info(&stored_info(ColonyStatus::TopographyInitialized, true), false, Some(max_tick))
+}
+
+#[test]
+fn test_not_started_colony_is_skipped() {
+    let mut gate = CaptureGate::new("test");
+    for info in [
+        CoordinatorStoredInfo::new(),
+        stored_info(ColonyStatus::Initializing, false),
+        // Shards initialized, StartTicking not issued yet
+        stored_info(ColonyStatus::TopographyInitialized, false),
+    ] {
+        let state = CaptureState::from_stored_info(&info, false, Some(0));
+        assert_eq!(gate.check(&state), Err(CaptureSkip::NotTicking), "{:?}", info.status);
+    }
+    assert_eq!(gate.check(&ticking(0)), Ok(()));
+}
+
+#[test]
+fn test_paused_fast_forward_and_completed_colony_is_skipped() {
+    let mut gate = CaptureGate::new("test");
+    let paused = CaptureState { paused: true, ..ticking(40) };
+    assert_eq!(gate.check(&paused), Err(CaptureSkip::Paused));
+    let fast_forward = CaptureState { fast_forward: true, ..ticking(40) };
+    assert_eq!(gate.check(&fast_forward), Err(CaptureSkip::FastForward));
+    let completed = CaptureState::from_stored_info(&stored_info(ColonyStatus::Completed, true), true, Some(50));
+    assert_eq!(gate.check(&completed), Err(CaptureSkip::Completed));
+    // Resumed
+    assert_eq!(gate.check(&ticking(40)), Ok(()));
+}
+
+#[test]
+fn test_stalled_tick_is_captured_once() {
+    let mut gate = CaptureGate::new("test");
+    assert_eq!(gate.check(&ticking(100)), Ok(()));
+    gate.record_capture(&ticking(100));
+    assert_eq!(gate.check(&ticking(100)), Err(CaptureSkip::Stalled(100)));
+    assert_eq!(gate.check(&ticking(100)), Err(CaptureSkip::Stalled(100)));
+    assert_eq!(gate.check(&ticking(101)), Ok(()));
+
+    // A new colony instance at the same tick is not a stall
+    let mut other_run = stored_info(ColonyStatus::TopographyInitialized, true);
+    other_run.colony_instance_id = Some("run-b".to_string());
+    assert_eq!(gate.check(&CaptureState::from_stored_info(&other_run, false, Some(100))), Ok(()));
+
+    // Without a tick-history sweep there is nothing to compare with
+    gate.record_capture(&CaptureState { max_tick: None, ..ticking(0) });
+    assert_eq!(gate.check(&CaptureState { max_tick: None, ..ticking(0) }), Ok(()));
+}
@@ -0,0 +1,104 @@
+use coordinator::backend_client::backend_breaker_state;
+use coordinator::backend_status::backend_statuses;
+use coordinator::circuit_breaker::{BreakerConfig, CircuitBreaker};
+use coordinator::colony_stats::get_colony_stats;
+use shared::be_api::StatMetric;
+use shared::cluster_topology::{ClusterTopology, HostInfo, TopologyConfig};
+use shared::colony_model::Shard;
+use shared::coordinator_api::BreakerState;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+const CONFIG: BreakerConfig = BreakerConfig {
+    failure_threshold: 3,
+    base_backoff: Duration::from_secs(5),
+    max_backoff: Duration::from_secs(12),
+};
+
+fn secs(secs: u64) -> Duration {
+    Duration::from_secs(secs)
+}
+
+#[test]
+fn test_opens_after_consecutive_failures_only() {
+    let mut breaker = CircuitBreaker::new(CONFIG);
+    let now = Instant::now();
+    assert_eq!(breaker.record_failure(now), None);
+    assert_eq!(breaker.record_failure(now), None);
+    // A success in between starts the count over
+    assert_eq!(breaker.record_success(), None);
+    assert_eq!(breaker.record_failure(now), None);
+    assert_eq!(breaker.record_failure(now), None);
+    assert_eq!(breaker.try_acquire(now), (true, None));
+    assert_eq!(breaker.record_failure(now), Some(BreakerState::Open));
+    assert_eq!(breaker.state(), BreakerState::Open);
+    assert_eq!(breaker.retry_in(now), Some(secs(5)));
+}
+
+#[test]
+fn test_open_fails_fast_until_one_probe_after_backoff() {
+    let mut breaker = CircuitBreaker::new(CONFIG);
+    let start = Instant::now();
+    for _ in 0..3 {
+        breaker.record_failure(start);
+    }
+    assert_eq!(breaker.try_acquire(start + secs(4)), (false, None));
+    assert_eq!(breaker.try_acquire(start + secs(5)), (true, Some(BreakerState::HalfOpen)));
+    // Only the probe goes out while it is in flight
+    assert_eq!(breaker.try_acquire(start + secs(6)), (false, None));
+    assert_eq!(breaker.record_success(), Some(BreakerState::Closed));
+    assert_eq!(breaker.consecutive_failures(), 0);
+    assert_eq!(breaker.try_acquire(start + secs(6)), (true, None));
+}
+
+#[test]
+fn test_failed_probes_double_the_backoff_up_to_the_cap() {
+    let mut breaker = CircuitBreaker::new(CONFIG);
+    let mut now = Instant::now();
+    for _ in 0..3 {
+        breaker.record_failure(now);
+    }
+    for expected_backoff in [10, 12, 12] {
+        now += breaker.retry_in(now).unwrap();
+        assert_eq!(breaker.try_acquire(now), (true, Some(BreakerState::HalfOpen)));
+        assert_eq!(breaker.record_failure(now), Some(BreakerState::Open));
+        assert_eq!(breaker.retry_in(now), Some(secs(expected_backoff)));
+    }
+    // Recovery resets the backoff for the next outage
+    now += breaker.retry_in(now).unwrap();
+    breaker.try_acquire(now);
+    breaker.record_success();
+    for _ in 0..3 {
+        breaker.record_failure(now);
+    }
+    assert_eq!(breaker.retry_in(now), Some(secs(5)));
+}
+
+/// A port nothing listens on, so connections are refused
+fn dead_port() -> u16 {
+    let listener = std::net::TcpListener::bind(("127.0.0.1", 0)).unwrap();
+    listener.local_addr().unwrap().port()
+}
+
+#[tokio::test]
+async fn test_dead_backend_is_failed_fast_and_its_shards_left_out() {
+    let dead = HostInfo::new("127.0.0.1".to_string(), dead_port());
+    let shards: Vec<Shard> = (0..4).map(|i| Shard { x: i * 10, y: 0, width: 10, height: 10 }).collect();
+    let topology = ClusterTopology::initialize(TopologyConfig::new(
+        HostInfo::new("127.0.0.1".to_string(), dead_port()),
+        vec![dead.clone()],
+        shards.iter().map(|shard| (*shard, dead.clone())).collect::<HashMap<_, _>>(),
+    ))
+    .unwrap();
+
+    // The breaker opens after the third refused shard, the fourth fails fast and is missing all the same
+    let error = get_colony_stats(vec![StatMetric::Health]).await.expect_err("stats of a dead backend");
+    assert_eq!(error, "No shard returned stats");
+    assert_eq!(backend_breaker_state(&dead.to_address()), BreakerState::Open);
+
+    let statuses = backend_statuses(&topology).await;
+    let status = &statuses.backends[0];
+    assert!(!status.healthy);
+    assert_eq!(status.breaker, BreakerState::Open);
+    assert!(status.error.as_deref().is_some_and(|error| error.contains("fail fast")), "{:?}", status.error);
+}
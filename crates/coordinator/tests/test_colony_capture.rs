@@ -0,0 +1,134 @@
+mod common;
+
+use coordinator::colony_capture::{estimate_changed_pixels, skips_unchanged_frame, stitch_colony_frame, write_frame, MissingShardsSidecar};
+use coordinator::coordinator_context::CoordinatorContext;
+use shared::cluster_topology::HostInfo;
+use shared::colony_model::{Color, Shard};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use common::temp_dir;
+
+const SHARD_SIZE: i32 = 10;
+const FETCH_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// 2x2 shards of SHARD_SIZE
+fn shards() -> Vec<Shard> {
+    let mut shards = Vec::new();
+    for y in 0..2 {
+        for x in 0..2 {
+            shards.push(Shard { x: x * SHARD_SIZE, y: y * SHARD_SIZE, width: SHARD_SIZE, height: SHARD_SIZE });
+        }
+    }
+    shards
+}
+
+fn red_shard() -> Vec<Color> {
+    vec![Color { red: 255, green: 0, blue: 0 }; (SHARD_SIZE * SHARD_SIZE) as usize]
+}
+
+#[tokio::test]
+async fn test_failed_shard_becomes_placeholder_with_sidecar() {
+    let shards = shards();
+    let failing = shards[1];
+    let hanging = shards[2];
+
+    let frame = stitch_colony_frame(&shards, 2 * SHARD_SIZE, 2 * SHARD_SIZE, FETCH_TIMEOUT, |shard| async move {
+        if shard == failing {
+            None
+        } else if shard == hanging {
+            // Only the per-shard timeout gets this one out
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            None
+        } else {
+            Some(red_shard())
+        }
+    }).await;
+    assert_eq!(frame.missing_shards, vec![failing, hanging]);
+
+    let dir = temp_dir("colony_capture");
+    let written = write_frame(&dir, 42, &frame, 0.5).expect("write_frame failed");
+    assert!(written);
+
+    let png = image::open(dir.join("0000042.png")).expect("Failed to read frame").to_rgb8();
+    assert_eq!(png.dimensions(), (2 * SHARD_SIZE as u32, 2 * SHARD_SIZE as u32));
+    assert_eq!(png.get_pixel(0, 0).0, [255, 0, 0]);
+    // Missing shards are gray, never the black of an empty colony
+    let placeholder = png.get_pixel(failing.x as u32 + 3, failing.y as u32 + 2).0;
+    assert!(placeholder[0] > 0 && placeholder[0] == placeholder[1] && placeholder[1] == placeholder[2], "{:?}", placeholder);
+
+    let sidecar: MissingShardsSidecar = serde_json::from_str(
+        &std::fs::read_to_string(dir.join("0000042.missing.json")).expect("Missing sidecar")
+    ).expect("Invalid sidecar");
+    assert_eq!(sidecar, MissingShardsSidecar {
+        tick: 42,
+        total_shards: 4,
+        missing_shards: vec![failing.to_id(), hanging.to_id()],
+    });
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn test_frame_skipped_above_missing_fraction() {
+    let shards = shards();
+    let available = shards[0];
+    let frame = stitch_colony_frame(&shards, 2 * SHARD_SIZE, 2 * SHARD_SIZE, FETCH_TIMEOUT, |shard| async move {
+        (shard == available).then(red_shard)
+    }).await;
+
+    let dir = temp_dir("colony_capture_skip");
+    assert_eq!(write_frame(&dir, 7, &frame, 0.5), Ok(false));
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+
+    // A complete frame has no sidecar
+    let frame = stitch_colony_frame(&shards, 2 * SHARD_SIZE, 2 * SHARD_SIZE, FETCH_TIMEOUT, |_| async { Some(red_shard()) }).await;
+    assert_eq!(write_frame(&dir, 7, &frame, 0.0), Ok(true));
+    assert!(dir.join("0000007.png").exists());
+    assert!(!dir.join("0000007.missing.json").exists());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_unchanged_frames_are_skipped_below_the_fraction() {
+    let colony_pixels = 400;
+    assert!(skips_unchanged_frame(0, colony_pixels, 0.01));
+    assert!(skips_unchanged_frame(3, colony_pixels, 0.01));
+    assert!(!skips_unchanged_frame(4, colony_pixels, 0.01));
+    assert!(!skips_unchanged_frame(400, colony_pixels, 0.01));
+    // A fraction of 0 writes every frame
+    assert!(!skips_unchanged_frame(0, colony_pixels, 0.0));
+}
+
+#[tokio::test]
+async fn test_changed_pixel_estimates_are_asked_concurrently_and_summed() {
+    let shards = shards();
+    // Every shard waits for all the others, so asking one after another never finishes
+    let barrier = Arc::new(tokio::sync::Barrier::new(shards.len()));
+    let estimate = tokio::time::timeout(Duration::from_secs(5), estimate_changed_pixels(&shards, |shard| {
+        let barrier = barrier.clone();
+        async move {
+            barrier.wait().await;
+            Some(shard.x as u64 + 1)
+        }
+    })).await.expect("shards were asked one after another");
+    assert_eq!(estimate, Some(1 + 11 + 1 + 11));
+
+    // A shard without an answer gets the frame written as usual
+    let unanswered = shards[3];
+    assert_eq!(estimate_changed_pixels(&shards, |shard| async move { (shard != unanswered).then_some(0) }).await, None);
+}
+
+#[test]
+fn test_http_port_probe_follows_the_deployment_mode_set_later() {
+    let context = CoordinatorContext::get_instance();
+    let answers = |_: &str, port: u16| port == 9001;
+    // Built before the mode is known, the probe is on
+    assert_eq!(context.http_port_probe().probe(&HostInfo::new("10.0.0.1".to_string(), 9000), Instant::now(), answers), Some(9001));
+
+    context.set_deployment_mode("aws".to_string());
+    assert_eq!(context.http_port_probe().probe(&HostInfo::new("10.0.0.2".to_string(), 9000), Instant::now(), answers), None);
+
+    context.set_deployment_mode("localhost".to_string());
+    assert_eq!(context.http_port_probe().probe(&HostInfo::new("10.0.0.3".to_string(), 9000), Instant::now(), answers), Some(9001));
+}
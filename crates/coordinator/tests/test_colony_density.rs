@@ -0,0 +1,33 @@
+use coordinator::colony_density::{assemble_colony_density, shard_density_cells};
+use shared::colony_model::Shard;
+use shared::density::{DensityGrid, MAX_DENSITY_CELLS};
+
+fn shard(x: i32, y: i32) -> Shard {
+    Shard { x, y, width: 50, height: 50 }
+}
+
+#[test]
+fn test_shards_are_asked_for_buckets_finer_than_the_colony_grid() {
+    // A 4x2 colony of 50x50 shards at 32 buckets per side: colony buckets are about 6x3 cells,
+    // shard buckets at most half that along the shorter side
+    assert_eq!(shard_density_cells(32, (200, 100), &shard(0, 0)), 32);
+    assert_eq!(shard_density_cells(8, (200, 100), &shard(0, 0)), 8);
+    assert_eq!(shard_density_cells(1, (1000, 1000), &shard(0, 0)), 1);
+    assert_eq!(shard_density_cells(512, (100, 100), &shard(0, 0)), MAX_DENSITY_CELLS);
+}
+
+#[test]
+fn test_unavailable_shards_are_listed_and_count_as_empty() {
+    let answered = DensityGrid { area: shard(0, 0), tick: 12, cols: 2, rows: 2, counts: vec![4, 0, 0, 4] };
+    let results = vec![
+        (shard(0, 0), Ok(answered)),
+        (shard(50, 0), Err("connection refused".to_string())),
+    ];
+    let density = assemble_colony_density(Shard { x: 0, y: 0, width: 100, height: 50 }, 4, results);
+    assert_eq!(density.missing_shards, vec![shard(50, 0).to_id()]);
+    assert_eq!(density.grid.tick, 12);
+    assert_eq!(density.grid.total(), 8);
+    // 4 columns over 100 cells: the answered shard fills the left two
+    let column = |col: usize| (0..density.grid.rows).map(|row| density.grid.counts[row * density.grid.cols + col]).sum::<u32>();
+    assert_eq!([column(0), column(1), column(2), column(3)], [4, 4, 0, 0]);
+}
@@ -0,0 +1,61 @@
+use coordinator::colony_expand::{plan_expansion, ExpandColonyError};
+use shared::cluster_topology::{ClusterTopology, ExpandDirection, HostInfo};
+use shared::colony_model::Shard;
+use std::collections::HashMap;
+
+const SHARD_SIZE: i32 = 250;
+
+/// 2x2 colony: backend a hosts three shards, backend b hosts one
+fn topology() -> ClusterTopology {
+    let a = HostInfo::new("10.0.0.1".to_string(), 8082);
+    let b = HostInfo::new("10.0.0.2".to_string(), 8082);
+    let mut shard_to_host = HashMap::new();
+    for (col, row, host) in [(0, 0, &a), (1, 0, &a), (0, 1, &a), (1, 1, &b)] {
+        let shard = Shard { x: col * SHARD_SIZE, y: row * SHARD_SIZE, width: SHARD_SIZE, height: SHARD_SIZE };
+        shard_to_host.insert(shard, host.clone());
+    }
+    ClusterTopology {
+        coordinator_host: HostInfo::new("10.0.0.9".to_string(), 8083),
+        backend_hosts: vec![a, b],
+        shard_to_host,
+    }
+}
+
+#[test]
+fn test_expand_right_adds_column_on_least_loaded_backend() {
+    let topology = topology();
+    let plan = plan_expansion(&topology, ExpandDirection::Right, None).expect("plan failed");
+
+    assert_eq!(plan.backend, HostInfo::new("10.0.0.2".to_string(), 8082));
+    assert_eq!(plan.new_shards, vec![
+        Shard { x: 500, y: 0, width: SHARD_SIZE, height: SHARD_SIZE },
+        Shard { x: 500, y: 250, width: SHARD_SIZE, height: SHARD_SIZE },
+    ]);
+    assert_eq!((plan.width, plan.height), (750, 500));
+    assert_eq!(plan.topology.shard_count(), 6);
+    for shard in &plan.new_shards {
+        assert_eq!(plan.topology.get_host_for_shard(shard), Some(&plan.backend));
+    }
+
+    // Edge shards now see the new column as neighbors
+    let edge = Shard { x: 250, y: 0, width: SHARD_SIZE, height: SHARD_SIZE };
+    assert!(plan.topology.get_adjacent_shards(&edge).contains(&plan.new_shards[0]));
+}
+
+#[test]
+fn test_expand_bottom_on_requested_backend() {
+    let topology = topology();
+    let plan = plan_expansion(&topology, ExpandDirection::Bottom, Some("10.0.0.1")).expect("plan failed");
+
+    assert_eq!(plan.backend, HostInfo::new("10.0.0.1".to_string(), 8082));
+    assert!(plan.new_shards.iter().all(|shard| shard.y == 500));
+    assert_eq!(plan.new_shards.len(), 2);
+    assert_eq!((plan.width, plan.height), (500, 750));
+}
+
+#[test]
+fn test_expand_rejects_unknown_backend() {
+    let topology = topology();
+    let result = plan_expansion(&topology, ExpandDirection::Right, Some("10.0.0.7:8082"));
+    assert_eq!(result.err(), Some(ExpandColonyError::UnknownBackend("10.0.0.7:8082".to_string())));
+}
@@ -1,4 +1,6 @@
-use coordinator::colony_stats::{all_stat_metrics, enumerate_all_stat_metric_variants};
+use coordinator::colony_stats::{all_stat_metrics, build_histogram, enumerate_all_stat_metric_variants, HistogramBucketing};
+use shared::be_api::StatMetric;
+use std::collections::BTreeMap;
 use std::mem::discriminant;
 
 /// Test that all_stat_metrics() includes all StatMetric variants.
@@ -84,3 +86,56 @@ fn test_all_stat_metrics_ordering() {
     }
 }
 
+
+#[test]
+fn test_bucket_ranges() {
+    let log2 = HistogramBucketing::Log2;
+    assert_eq!(log2.range(0), (0, 0));
+    assert_eq!(log2.range(1), (1, 1));
+    assert_eq!(log2.range(3), (2, 3));
+    assert_eq!(log2.range(1024), (1024, 2047));
+    assert_eq!(log2.range(2047), (1024, 2047));
+    assert_eq!(log2.range(i32::MAX), (1 << 30, i32::MAX as i64));
+
+    let fixed = HistogramBucketing::FixedWidth(10);
+    assert_eq!(fixed.range(0), (0, 9));
+    assert_eq!(fixed.range(25), (20, 29));
+    assert_eq!(fixed.range(-1), (-10, -1));
+
+    assert_eq!(HistogramBucketing::for_metric(StatMetric::Age), log2);
+    assert_eq!(HistogramBucketing::for_metric(StatMetric::Food), fixed);
+    assert_eq!(HistogramBucketing::for_metric(StatMetric::CanKill), HistogramBucketing::Exact);
+    assert_eq!(HistogramBucketing::for_metric(StatMetric::Occupancy), HistogramBucketing::Exact);
+    assert_eq!(HistogramBucketing::for_metric(StatMetric::FoodCoverage), HistogramBucketing::Exact);
+}
+
+/// Ages after a long run: every creature has its own age, so exact keys all fall under the
+/// minimum count while log2 buckets keep the histogram populated
+#[test]
+fn test_unique_ages_still_produce_a_histogram() {
+    let counts: BTreeMap<i32, u64> = (1000..1100).map(|age| (age, 1)).collect();
+
+    let exact = build_histogram(&counts, HistogramBucketing::Exact);
+    assert!(exact.distribution.is_empty());
+
+    let bucketed = build_histogram(&counts, HistogramBucketing::Log2);
+    let expected: BTreeMap<String, u64> = [("512-1023".to_string(), 24), ("1024-2047".to_string(), 76)].into();
+    assert_eq!(bucketed.distribution, expected);
+    assert_eq!(bucketed.unique_values_count, 2);
+    assert!(!bucketed.was_cut);
+}
+
+#[test]
+fn test_bucketed_average_uses_raw_values() {
+    // 30 creatures of age 1024 and 30 of age 1030: the bucket midpoint would be 1535.5
+    let counts: BTreeMap<i32, u64> = [(1024, 30), (1030, 30)].into();
+    let histogram = build_histogram(&counts, HistogramBucketing::Log2);
+    assert_eq!(histogram.distribution.get("1024-2047"), Some(&60));
+    assert_eq!(histogram.average, 1027.0);
+
+    let food: BTreeMap<i32, u64> = [(1, 20), (2, 20), (15, 40)].into();
+    let histogram = build_histogram(&food, HistogramBucketing::FixedWidth(10));
+    let expected: BTreeMap<String, u64> = [("0-9".to_string(), 40), ("10-19".to_string(), 40)].into();
+    assert_eq!(histogram.distribution, expected);
+    assert_eq!(histogram.average, 8.25);
+}
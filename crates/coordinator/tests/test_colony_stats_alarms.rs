@@ -0,0 +1,75 @@
+use coordinator::colony_stats_alarms::{AlarmConfig, AlarmEvaluator, AlarmMetric, AlarmOp, AlarmRule, AlarmTransition};
+
+fn rule(name: &str, metric: AlarmMetric, op: AlarmOp, threshold: f64, for_n_captures: u32) -> AlarmRule {
+    AlarmRule { name: name.to_string(), metric, op, threshold, for_n_captures, pause_events: false }
+}
+
+fn names(transitions: &[AlarmTransition]) -> Vec<String> {
+    transitions
+        .iter()
+        .map(|transition| match transition {
+            AlarmTransition::Raised { name, .. } => format!("+{}", name),
+            AlarmTransition::Cleared { name } => format!("-{}", name),
+        })
+        .collect()
+}
+
+#[test]
+fn test_alarm_raises_after_n_consecutive_captures_and_clears_at_once() {
+    let mut evaluator = AlarmEvaluator::new(AlarmConfig {
+        rules: vec![rule("all-can-kill", AlarmMetric::CanKillShare, AlarmOp::GreaterOrEqual, 1.0, 3)],
+    });
+    let mut capture = |share: f64| names(&evaluator.evaluate(|_| share));
+
+    assert!(capture(1.0).is_empty());
+    assert!(capture(1.0).is_empty());
+    // A single capture below the threshold restarts the count
+    assert!(capture(0.9).is_empty());
+    assert!(capture(1.0).is_empty());
+    assert!(capture(1.0).is_empty());
+    assert_eq!(capture(1.0), vec!["+all-can-kill"]);
+    // Raised once, not on every capture that keeps holding
+    assert!(capture(1.0).is_empty());
+    assert_eq!(capture(0.5), vec!["-all-can-kill"]);
+    assert!(capture(0.5).is_empty());
+}
+
+#[test]
+fn test_rules_are_independent_and_report_pausing() {
+    let mut pausing = rule("colony-extinct", AlarmMetric::CreaturesCount, AlarmOp::LessOrEqual, 0.0, 1);
+    pausing.pause_events = true;
+    let mut evaluator = AlarmEvaluator::new(AlarmConfig {
+        rules: vec![rule("health-high", AlarmMetric::HealthAverage, AlarmOp::Greater, 100.0, 2), pausing],
+    });
+    let values = |health: f64, creatures: f64| move |metric| match metric {
+        AlarmMetric::HealthAverage => health,
+        AlarmMetric::CreaturesCount => creatures,
+        _ => 0.0,
+    };
+
+    assert!(evaluator.evaluate(values(150.0, 10.0)).is_empty());
+    let transitions = evaluator.evaluate(values(150.0, 0.0));
+    assert_eq!(names(&transitions), vec!["+health-high", "+colony-extinct"]);
+    assert!(matches!(&transitions[0], AlarmTransition::Raised { description, value, .. } if description == "HealthAverage > 100" && *value == 150.0));
+    assert_eq!(evaluator.raised_alarms(), vec!["health-high".to_string(), "colony-extinct".to_string()]);
+    assert!(evaluator.pauses_events());
+
+    assert_eq!(names(&evaluator.evaluate(values(150.0, 5.0))), vec!["-colony-extinct"]);
+    assert!(!evaluator.pauses_events());
+    assert_eq!(evaluator.raised_alarms(), vec!["health-high".to_string()]);
+}
+
+#[test]
+fn test_alarm_config_json() {
+    let config = AlarmConfig::parse(r#"{"rules":[{"name":"food-saturated","metric":"food_average","op":">=","threshold":60000}]}"#).unwrap();
+    assert_eq!(config.rules, vec![rule("food-saturated", AlarmMetric::FoodAverage, AlarmOp::GreaterOrEqual, 60_000.0, 1)]);
+
+    assert!(AlarmConfig::parse(r#"{"rules":[{"name":"x","metric":"mood","op":">","threshold":1}]}"#).is_err());
+    assert!(AlarmConfig::parse(r#"{"rules":[{"name":"x","metric":"age_average","op":"=>","threshold":1}]}"#).is_err());
+    assert!(AlarmConfig::parse(r#"{"rules":[{"name":"x","metric":"age_average","op":">","threshold":1,"for_n_captures":0}]}"#).unwrap_err().contains("for_n_captures"));
+
+    let defaults = AlarmConfig::default_rules();
+    assert!(defaults.rules.iter().all(|rule| rule.for_n_captures >= 1));
+    // The defaults survive a round trip through the config format
+    assert_eq!(AlarmConfig::parse(&serde_json::to_string(&defaults).unwrap()).unwrap(), defaults);
+}
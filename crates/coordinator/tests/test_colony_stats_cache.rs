@@ -0,0 +1,83 @@
+use coordinator::colony_stats_cache::{CachedColonyStats, ColonyStatsCache};
+use shared::be_api::{StatBucket, StatMetric};
+use shared::coordinator_api::ColonyMetricStats;
+use std::time::{Duration, Instant};
+
+const FRESHNESS: Duration = Duration::from_secs(10);
+
+fn metric_stats(metric: StatMetric, value: i32) -> ColonyMetricStats {
+    ColonyMetricStats { metric, avg: value as f64, buckets: vec![StatBucket { value, occs: 3 }] }
+}
+
+fn cached(tick: u64, computed_at: Instant) -> CachedColonyStats {
+    CachedColonyStats {
+        colony_instance_id: Some("colony-a".to_string()),
+        tick,
+        stats: vec![metric_stats(StatMetric::Health, 40), metric_stats(StatMetric::Size, 7)],
+        species: Vec::new(),
+        computed_at,
+    }
+}
+
+#[test]
+fn test_cache_hit_returns_requested_metrics() {
+    let cache = ColonyStatsCache::new(FRESHNESS);
+    let now = Instant::now();
+    cache.store(cached(120, now));
+
+    let hit = cache.lookup(&[StatMetric::Size], now + Duration::from_secs(4)).expect("expected cache hit");
+    assert_eq!(hit.tick, 120);
+    assert_eq!(hit.stats.len(), 1);
+    assert!(matches!(hit.stats[0].metric, StatMetric::Size));
+    assert_eq!(hit.computed_at, now);
+}
+
+#[test]
+fn test_cache_expires_after_freshness_window() {
+    let cache = ColonyStatsCache::new(FRESHNESS);
+    let now = Instant::now();
+    cache.store(cached(120, now));
+
+    assert!(cache.lookup(&[StatMetric::Health], now + FRESHNESS).is_some());
+    assert!(cache.lookup(&[StatMetric::Health], now + FRESHNESS + Duration::from_millis(1)).is_none());
+}
+
+#[test]
+fn test_cache_misses_on_metric_set_mismatch() {
+    let cache = ColonyStatsCache::new(FRESHNESS);
+    let now = Instant::now();
+    cache.store(cached(120, now));
+
+    assert!(cache.lookup(&[StatMetric::Health, StatMetric::Age], now).is_none());
+    assert!(cache.lookup(&[StatMetric::Food], now).is_none());
+}
+
+#[test]
+fn test_older_tick_does_not_replace_fresh_newer_entry() {
+    let cache = ColonyStatsCache::new(FRESHNESS);
+    let now = Instant::now();
+    cache.store(cached(120, now));
+    cache.store(cached(100, now + Duration::from_secs(1)));
+
+    let hit = cache.lookup(&[StatMetric::Health], now).expect("expected cache hit");
+    assert_eq!(hit.tick, 120);
+}
+
+#[test]
+fn test_restarted_colony_replaces_entry() {
+    let cache = ColonyStatsCache::new(FRESHNESS);
+    let now = Instant::now();
+    cache.store(cached(120, now));
+
+    // A new colony instance starts again from tick 0
+    let restarted = CachedColonyStats { colony_instance_id: Some("colony-b".to_string()), ..cached(5, now + Duration::from_secs(1)) };
+    cache.store(restarted);
+    let hit = cache.lookup(&[StatMetric::Health], now + Duration::from_secs(1)).expect("expected cache hit");
+    assert_eq!((hit.colony_instance_id.as_deref(), hit.tick), (Some("colony-b"), 5));
+
+    // So does an older tick once the entry expired
+    cache.store(cached(300, now));
+    cache.store(cached(40, now + FRESHNESS + Duration::from_secs(1)));
+    let hit = cache.lookup(&[StatMetric::Health], now + FRESHNESS + Duration::from_secs(1)).expect("expected cache hit");
+    assert_eq!(hit.tick, 40);
+}
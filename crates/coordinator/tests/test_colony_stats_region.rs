@@ -0,0 +1,82 @@
+mod common;
+
+use backend::backend_config;
+use backend::be_server::dispatch_request;
+use backend::rate_limiter::RateLimitConfig;
+use coordinator::colony_stats::{get_region_stats, RegionColonyStats};
+use coordinator::init_colony::COLONY_LIFE_INITIAL_RULES;
+use shared::be_api::{BackendRequest, InitColonyRequest, InitColonyShardRequest, SeedingOptions, Shard, StatMetric};
+use shared::cluster_topology::{ClusterTopology, HostInfo};
+use std::collections::HashMap;
+use common::spawn_live_backend;
+
+const LOCALHOST: &str = "127.0.0.1";
+const SHARD_SIZE: i32 = 10;
+
+fn shard(col: i32) -> Shard {
+    Shard { x: col * SHARD_SIZE, y: 0, width: SHARD_SIZE, height: SHARD_SIZE }
+}
+
+/// Cells the Occupancy histogram counted, occupied or not
+fn occupancy_cells(stats: &RegionColonyStats) -> u64 {
+    stats.stats.stats.iter()
+        .filter(|metric| matches!(metric.metric, StatMetric::Occupancy))
+        .flat_map(|metric| &metric.buckets)
+        .map(|bucket| bucket.occs)
+        .sum()
+}
+
+/// The only test in this binary: topology and colony are process-global
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_region_stats() {
+    let live = HostInfo::new(LOCALHOST.to_string(), spawn_live_backend().await);
+    backend_config::set_backend_hostname(live.hostname.clone());
+    backend_config::set_backend_port(live.port);
+    backend_config::set_rate_limit_config(RateLimitConfig { enabled: false, ..RateLimitConfig::default() });
+    let topology = ClusterTopology {
+        coordinator_host: HostInfo::new(LOCALHOST.to_string(), 0),
+        backend_hosts: vec![live.clone()],
+        shard_to_host: HashMap::from([(shard(0), live.clone()), (shard(1), live)]),
+    };
+    dispatch_request(BackendRequest::InitColony(InitColonyRequest {
+        width: 2 * SHARD_SIZE,
+        height: SHARD_SIZE,
+        colony_life_rules: COLONY_LIFE_INITIAL_RULES,
+    })).await;
+    for col in 0..2 {
+        dispatch_request(BackendRequest::InitColonyShard(InitColonyShardRequest {
+            shard: shard(col),
+            colony_life_rules: COLONY_LIFE_INITIAL_RULES,
+            topology: Some(topology.clone()),
+            seeding: SeedingOptions::default(),
+            topography_data: None,
+            awaiting_topography: false,
+            colony_instance_id: None,
+            lease_epoch: 1,
+        })).await;
+    }
+
+    // Spans the border between the two shards
+    let across = Shard { x: 5, y: 2, width: 10, height: 4 };
+    let stats = get_region_stats(vec![StatMetric::Occupancy], across).await.expect("stats of both shards");
+    assert_eq!(stats.region, Some(across));
+    assert_eq!(stats.cells, 40);
+    assert_eq!(occupancy_cells(&stats), 40);
+
+    // Clipped to the colony
+    let overhanging = Shard { x: -5, y: -5, width: 100, height: 100 };
+    let stats = get_region_stats(vec![StatMetric::Occupancy], overhanging).await.unwrap();
+    assert_eq!(stats.region, Some(Shard { x: 0, y: 0, width: 2 * SHARD_SIZE, height: SHARD_SIZE }));
+    assert_eq!(stats.cells, 200);
+    assert_eq!(occupancy_cells(&stats), 200);
+
+    // Outside every shard: empty but valid
+    let outside = Shard { x: 50, y: 50, width: 5, height: 5 };
+    let stats = get_region_stats(vec![StatMetric::Occupancy, StatMetric::Health], outside).await.expect("empty stats, not an error");
+    assert_eq!(stats.region, None);
+    assert_eq!(stats.cells, 0);
+    assert_eq!(occupancy_cells(&stats), 0);
+    assert!(stats.stats.species.is_empty());
+
+    assert!(get_region_stats(vec![StatMetric::Occupancy], Shard { x: 0, y: 0, width: 0, height: 5 }).await.is_err());
+}
@@ -0,0 +1,78 @@
+use coordinator::colony_start::{colony_start_colony, ColonyStartRequest};
+use coordinator::colony_step::{is_colony_paused, is_fast_forward, parse_step_count, step_in_lockstep, StepColonyError, MAX_STEP_COUNT};
+use coordinator::coordinator_context::CoordinatorContext;
+use shared::cluster_topology::HostInfo;
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+
+fn backends() -> Vec<HostInfo> {
+    vec![HostInfo::new("a".to_string(), 8082), HostInfo::new("b".to_string(), 8082)]
+}
+
+#[tokio::test]
+async fn test_lockstep_waits_for_every_backend_between_ticks() {
+    let ticks: Arc<Mutex<HashMap<String, u64>>> = Arc::new(Mutex::new(HashMap::new()));
+
+    let result = step_in_lockstep(&backends(), 3, |backend| {
+        let ticks = Arc::clone(&ticks);
+        async move {
+            let mut ticks = ticks.lock().unwrap();
+            // No backend may run ahead of another by more than the tick being stepped
+            let slowest = ticks.values().copied().min().unwrap_or(0);
+            let tick = ticks.entry(backend.to_address()).or_insert(0);
+            assert!(*tick <= slowest, "{} ran ahead", backend.to_address());
+            *tick += 1;
+            Ok(*tick)
+        }
+    }).await;
+
+    assert_eq!(result, Ok(3));
+    let ticks = ticks.lock().unwrap();
+    assert_eq!(ticks.values().copied().collect::<Vec<_>>(), vec![3, 3]);
+}
+
+#[tokio::test]
+async fn test_lockstep_stops_at_first_failed_tick() {
+    let calls = Arc::new(Mutex::new(0));
+
+    let result = step_in_lockstep(&backends(), 5, |backend| {
+        let calls = Arc::clone(&calls);
+        async move {
+            let mut calls = calls.lock().unwrap();
+            *calls += 1;
+            if backend.hostname == "b" && *calls > 2 {
+                return Err("connection refused".to_string());
+            }
+            Ok(*calls)
+        }
+    }).await;
+
+    let error = result.unwrap_err();
+    assert!(error.contains("step 2 of 5"), "{}", error);
+    assert_eq!(*calls.lock().unwrap(), 4);
+}
+
+#[test]
+fn test_step_count_bounds() {
+    assert_eq!(parse_step_count(None), Ok(1));
+    assert_eq!(parse_step_count(Some("10")), Ok(10));
+    assert_eq!(parse_step_count(Some(&MAX_STEP_COUNT.to_string())), Ok(MAX_STEP_COUNT));
+    for invalid in ["0", "-1", "abc", &(MAX_STEP_COUNT + 1).to_string()] {
+        assert!(matches!(parse_step_count(Some(invalid)), Err(StepColonyError::InvalidCount(_))), "{}", invalid);
+    }
+}
+
+#[tokio::test]
+async fn test_colony_start_clears_the_previous_pause_and_fast_forward() {
+    // As left by /api/pause, or by the run summary pausing at target_tick
+    CoordinatorContext::get_instance().colony_paused().store(true, Ordering::SeqCst);
+    CoordinatorContext::get_instance().fast_forward().store(true, Ordering::SeqCst);
+    assert!(is_colony_paused());
+
+    // No registry is set up, so the start fails at discovery, after the state was reset
+    colony_start_colony(None, ColonyStartRequest::default()).await;
+    assert!(!is_colony_paused());
+    assert!(!is_fast_forward());
+    assert!(CoordinatorContext::get_instance().last_start_failure().is_some());
+}
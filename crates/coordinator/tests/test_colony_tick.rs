@@ -0,0 +1,83 @@
+mod common;
+
+use backend::backend_config;
+use backend::be_server::dispatch_request;
+use backend::rate_limiter::RateLimitConfig;
+use coordinator::backend_client::{get_colony_tick, get_topology_colony_tick};
+use coordinator::colony_stats::save_colony_stats;
+use coordinator::coordinator_context::CoordinatorContext;
+use coordinator::init_colony::COLONY_LIFE_INITIAL_RULES;
+use shared::be_api::{BackendRequest, InitColonyRequest, InitColonyShardRequest, SeedingOptions, Shard};
+use shared::cluster_topology::{ClusterTopology, HostInfo};
+use shared::output_paths::OUTPUT_DIR_ENV;
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+use common::spawn_live_backend;
+
+const LOCALHOST: &str = "127.0.0.1";
+const SHARD_SIZE: i32 = 10;
+
+/// A port nothing listens on, like the one of a crashed backend
+fn dead_port() -> u16 {
+    let listener = std::net::TcpListener::bind((LOCALHOST, 0)).unwrap();
+    listener.local_addr().unwrap().port()
+}
+
+fn shard(col: i32) -> Shard {
+    Shard { x: col * SHARD_SIZE, y: 0, width: SHARD_SIZE, height: SHARD_SIZE }
+}
+
+/// The only test in this binary: topology, colony and OutputPaths are process-global
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_tick_falls_back_when_the_first_shard_is_down() {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).expect("Clock before epoch").as_nanos();
+    let root = std::env::temp_dir().join(format!("colony_tick_{}_{}", std::process::id(), nanos));
+    std::env::set_var(OUTPUT_DIR_ENV, &root);
+
+    // The first shard in row-major order lives on a backend that is down
+    let dead = HostInfo::new(LOCALHOST.to_string(), dead_port());
+    let live = HostInfo::new(LOCALHOST.to_string(), spawn_live_backend().await);
+    backend_config::set_backend_hostname(live.hostname.clone());
+    backend_config::set_backend_port(live.port);
+    backend_config::set_rate_limit_config(RateLimitConfig { enabled: false, ..RateLimitConfig::default() });
+    let topology = ClusterTopology {
+        coordinator_host: HostInfo::new(LOCALHOST.to_string(), dead_port()),
+        backend_hosts: vec![dead.clone(), live.clone()],
+        shard_to_host: HashMap::from([(shard(0), dead), (shard(1), live)]),
+    };
+    dispatch_request(BackendRequest::InitColony(InitColonyRequest {
+        width: 2 * SHARD_SIZE,
+        height: SHARD_SIZE,
+        colony_life_rules: COLONY_LIFE_INITIAL_RULES,
+    })).await;
+    dispatch_request(BackendRequest::InitColonyShard(InitColonyShardRequest {
+        shard: shard(1),
+        colony_life_rules: COLONY_LIFE_INITIAL_RULES,
+        topology: Some(topology),
+        seeding: SeedingOptions::default(),
+        topography_data: None,
+        awaiting_topography: false,
+        colony_instance_id: None,
+        lease_epoch: 1,
+    })).await;
+    assert!(ClusterTopology::get_instance().is_some());
+
+    let colony_tick = tokio::task::spawn_blocking(get_topology_colony_tick).await.unwrap().expect("tick from the live shard");
+    assert_eq!(colony_tick.shard, shard(1));
+    assert_eq!(colony_tick.skipped, [shard(0)]);
+    assert!(colony_tick.fallback_note().is_some_and(|note| note.contains(&shard(0).to_id())));
+
+    // Asked in row-major order whatever order the shards come in
+    let reversed = tokio::task::spawn_blocking(|| get_colony_tick(&[shard(1), shard(0)])).await.unwrap().unwrap();
+    assert_eq!(reversed.skipped, [shard(0)]);
+    let only_live = tokio::task::spawn_blocking(|| get_colony_tick(&[shard(1)])).await.unwrap().unwrap();
+    assert_eq!(only_live.fallback_note(), None);
+    assert!(tokio::task::spawn_blocking(|| get_colony_tick(&[shard(0)])).await.unwrap().is_err());
+
+    CoordinatorContext::get_instance().get_coord_stored_info().colony_instance_id = Some("tick".to_string());
+    let stats = save_colony_stats().await.expect("stats capture without the first shard");
+    assert_eq!(stats.tick, colony_tick.tick);
+    assert!(root.join("s3/distributed-colony/tick/stats_shots").read_dir().unwrap().next().is_some());
+
+    let _ = std::fs::remove_dir_all(&root);
+}
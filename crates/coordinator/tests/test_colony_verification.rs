@@ -0,0 +1,128 @@
+use coordinator::colony_verification::{build_report, check_image_len, BackendSnapshot, HostedShard, VerificationTrigger};
+use coordinator::init_colony::COLONY_LIFE_INITIAL_RULES;
+use shared::be_api::{ColonyLifeRules, Shard};
+use shared::cluster_topology::{ClusterTopology, HostInfo};
+use std::collections::HashMap;
+
+const MAX_DRIFT: u64 = 100;
+
+fn backend(port: u16) -> HostInfo {
+    HostInfo::new("127.0.0.1".to_string(), port)
+}
+
+fn shard(col: i32) -> Shard {
+    Shard { x: col * 10, y: 0, width: 10, height: 10 }
+}
+
+/// Four shards in a row, two per backend
+fn topology() -> ClusterTopology {
+    let shard_to_host: HashMap<Shard, HostInfo> = (0..4).map(|col| (shard(col), backend(8082 + (col / 2) as u16))).collect();
+    ClusterTopology { coordinator_host: backend(8081), backend_hosts: vec![backend(8082), backend(8083)], shard_to_host }
+}
+
+fn hosted(shard: Shard, current_tick: u64) -> HostedShard {
+    HostedShard { shard, current_tick, lease_epoch: 1, suspended: false }
+}
+
+fn snapshot(hosted: Vec<HostedShard>) -> Result<BackendSnapshot, String> {
+    let image_check = hosted.first().map(|first| (first.shard, Ok(())));
+    Ok(BackendSnapshot { hosted, rules: Some(COLONY_LIFE_INITIAL_RULES), image_check })
+}
+
+fn healthy() -> Vec<(HostInfo, Result<BackendSnapshot, String>)> {
+    vec![
+        (backend(8082), snapshot(vec![hosted(shard(0), 500), hosted(shard(1), 498)])),
+        (backend(8083), snapshot(vec![hosted(shard(2), 500), hosted(shard(3), 499)])),
+    ]
+}
+
+fn problems_of(report: &shared::coordinator_api::ColonyVerificationReport, shard_id: &str) -> Vec<String> {
+    report.shards.iter().find(|shard| shard.shard_id == shard_id).unwrap().problems.clone()
+}
+
+#[test]
+fn test_consistent_cluster_passes() {
+    let report = build_report(&topology(), &healthy(), &HashMap::new(), COLONY_LIFE_INITIAL_RULES, MAX_DRIFT, VerificationTrigger::Manual);
+    assert!(report.passed, "{:?}", report);
+    assert_eq!(report.trigger, "manual");
+    assert_eq!(report.colony_max_tick, Some(500));
+    assert_eq!(report.shards.len(), 4);
+    assert_eq!(report.shards[1].tick_drift, Some(2));
+    assert_eq!(report.shards[0].image_ok, Some(true));
+    assert_eq!(report.shards[1].image_ok, None);
+    assert_eq!(report.summary(), "PASS: 4/4 shards ok");
+}
+
+#[test]
+fn test_hosting_and_dimension_problems_fail_their_shards() {
+    let mut snapshots = healthy();
+    // shard 1 also on the second backend, shard 3 missing, shard 2 hosted with the wrong height
+    let wrong_size = Shard { height: 20, ..shard(2) };
+    snapshots[1].1 = snapshot(vec![hosted(shard(1), 500), hosted(wrong_size, 500)]);
+
+    let report = build_report(&topology(), &snapshots, &HashMap::new(), COLONY_LIFE_INITIAL_RULES, MAX_DRIFT, VerificationTrigger::Failover);
+    assert!(!report.passed);
+    assert_eq!(report.shards_failed, 3);
+    assert!(problems_of(&report, &shard(1).to_id())[0].contains("hosted by 2 backends"));
+    assert!(problems_of(&report, &shard(2).to_id())[0].contains("10x20"));
+    assert_eq!(problems_of(&report, &shard(3).to_id()), vec!["not hosted by any backend".to_string()]);
+    assert!(report.summary().starts_with("FAIL: 1/4"));
+
+    // A single host other than the assigned one is a problem too
+    let mut snapshots = healthy();
+    snapshots[0].1 = snapshot(vec![hosted(shard(0), 500)]);
+    snapshots[1].1 = snapshot(vec![hosted(shard(1), 500), hosted(shard(2), 500), hosted(shard(3), 500)]);
+    let report = build_report(&topology(), &snapshots, &HashMap::new(), COLONY_LIFE_INITIAL_RULES, MAX_DRIFT, VerificationTrigger::Manual);
+    assert!(problems_of(&report, &shard(1).to_id())[0].contains("topology assigns 127.0.0.1:8082"));
+}
+
+#[test]
+fn test_drift_images_rules_and_unreachable_backends() {
+    let mut snapshots = healthy();
+    let mut lagging = snapshot(vec![hosted(shard(2), 350), hosted(shard(3), 500)]).unwrap();
+    lagging.image_check = Some((shard(2), check_image_len(&shard(2), 299)));
+    lagging.rules = Some(ColonyLifeRules { mutation_chance: 1, ..COLONY_LIFE_INITIAL_RULES });
+    snapshots[1].1 = Ok(lagging);
+
+    let report = build_report(&topology(), &snapshots, &HashMap::new(), COLONY_LIFE_INITIAL_RULES, MAX_DRIFT, VerificationTrigger::ColonyStart);
+    let problems = problems_of(&report, &shard(2).to_id());
+    assert_eq!(problems.len(), 2, "{:?}", problems);
+    assert!(problems[0].contains("150 ticks behind"));
+    assert!(problems[1].contains("299 bytes, expected 300"));
+    assert_eq!(report.backend_problems, vec!["127.0.0.1:8083: runs different colony rules than the coordinator".to_string()]);
+
+    let mut snapshots = healthy();
+    snapshots[0].1 = Err("Timed out".to_string());
+    let report = build_report(&topology(), &snapshots, &HashMap::new(), COLONY_LIFE_INITIAL_RULES, MAX_DRIFT, VerificationTrigger::Manual);
+    assert_eq!(report.backend_problems, vec!["127.0.0.1:8082: Timed out".to_string()]);
+    assert_eq!(report.shards_failed, 2);
+    assert!(report.summary().ends_with("backend problems: 1"));
+}
+
+#[test]
+fn test_suspended_copies_and_stale_epochs_fail_their_shards() {
+    let issued: HashMap<Shard, u64> = (0..4).map(|col| (shard(col), 1)).collect();
+    let report = build_report(&topology(), &healthy(), &issued, COLONY_LIFE_INITIAL_RULES, MAX_DRIFT, VerificationTrigger::Manual);
+    assert!(report.passed, "{:?}", report);
+
+    // Shard 1 moved to the second backend under epoch 2; the first still holds its suspended copy.
+    // Shard 3 carries an epoch the coordinator never issued.
+    let mut issued = issued;
+    issued.insert(shard(1), 2);
+    let mut snapshots = healthy();
+    snapshots[0].1 = snapshot(vec![hosted(shard(0), 500), HostedShard { suspended: true, ..hosted(shard(1), 498) }]);
+    snapshots[1].1 = snapshot(vec![HostedShard { lease_epoch: 2, ..hosted(shard(1), 500) }, hosted(shard(2), 500), HostedShard { lease_epoch: 3, ..hosted(shard(3), 499) }]);
+    let report = build_report(&topology(), &snapshots, &issued, COLONY_LIFE_INITIAL_RULES, MAX_DRIFT, VerificationTrigger::Manual);
+    let problems = problems_of(&report, &shard(1).to_id());
+    // One active host, the suspended copy is reported on its own
+    assert_eq!(problems.len(), 2, "{:?}", problems);
+    assert!(problems[0].contains("topology assigns 127.0.0.1:8082"));
+    assert_eq!(problems[1], "127.0.0.1:8082 holds a suspended copy under lease epoch 1");
+    assert_eq!(problems_of(&report, &shard(3).to_id()), vec!["127.0.0.1:8083 holds lease epoch 3, coordinator issued 1".to_string()]);
+
+    // A shard whose only copy is suspended is not ticking anywhere
+    let mut snapshots = healthy();
+    snapshots[1].1 = snapshot(vec![hosted(shard(2), 500), HostedShard { suspended: true, ..hosted(shard(3), 499) }]);
+    let report = build_report(&topology(), &snapshots, &HashMap::new(), COLONY_LIFE_INITIAL_RULES, MAX_DRIFT, VerificationTrigger::Manual);
+    assert_eq!(problems_of(&report, &shard(3).to_id())[0], "no backend ticks it");
+}
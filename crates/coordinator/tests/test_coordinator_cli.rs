@@ -0,0 +1,126 @@
+use coordinator::coordinator_cli::{colony_start_body, is_cli_command, parse_cli_args, CliCommand, StatusSummary};
+use coordinator::coordinator_server::DeploymentMode;
+use shared::be_api::{StatBucket, StatMetric};
+use shared::coordinator_api::{BackendStatus, BackendsResponse, BreakerState, ColonyMetricStats, ColonyStatsResponse};
+use std::path::PathBuf;
+
+fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+fn backend(backend: &str, healthy: bool, tick_range: Option<(u64, u64)>) -> BackendStatus {
+    BackendStatus {
+        backend: backend.to_string(),
+        assigned_shards: Vec::new(),
+        hosted_shards: None,
+        tick_range,
+        healthy,
+        version: None,
+        error: None,
+        assigned_not_hosting: Vec::new(),
+        hosting_not_assigned: Vec::new(),
+        lease_mismatches: Vec::new(),
+        suspended_shards: Vec::new(),
+        breaker: BreakerState::Closed,
+    }
+}
+
+#[test]
+fn test_serve_and_port_arguments_are_not_cli_commands() {
+    assert!(is_cli_command("status"));
+    assert!(is_cli_command("apply-event"));
+    assert!(!is_cli_command("serve"));
+    assert!(!is_cli_command("8082"));
+    assert!(!is_cli_command("aws"));
+}
+
+#[test]
+fn test_parse_start_colony() {
+    let parsed = parse_cli_args(&args(&["start-colony", "--config", "run.json", "--seed", "42", "--json", "--coordinator", "127.0.0.1:8083"])).unwrap();
+    assert_eq!(parsed.command, CliCommand::StartColony { config: Some(PathBuf::from("run.json")), seed: Some(42) });
+    assert!(parsed.json);
+    assert_eq!(parsed.coordinator.as_deref(), Some("127.0.0.1:8083"));
+    assert_eq!(parsed.deployment_mode, DeploymentMode::Localhost);
+
+    let parsed = parse_cli_args(&args(&["status", "--mode", "aws"])).unwrap();
+    assert_eq!(parsed.command, CliCommand::Status);
+    assert!(!parsed.json);
+    assert_eq!(parsed.deployment_mode, DeploymentMode::Aws);
+}
+
+#[test]
+fn test_apply_event_reads_the_event_file() {
+    let parsed = parse_cli_args(&args(&["apply-event", "--event", "event.json"])).unwrap();
+    assert_eq!(parsed.command, CliCommand::ApplyEvent { event: PathBuf::from("event.json") });
+    assert!(!parsed.json);
+    // --json only switches the output format, it takes no file
+    let parsed = parse_cli_args(&args(&["apply-event", "--event", "event.json", "--json"])).unwrap();
+    assert!(parsed.json);
+    assert!(parse_cli_args(&args(&["apply-event"])).is_err());
+    assert!(parse_cli_args(&args(&["apply-event", "--json", "event.json"])).is_err());
+}
+
+#[test]
+fn test_parse_errors() {
+    assert!(parse_cli_args(&[]).is_err());
+    assert!(parse_cli_args(&args(&["restart"])).is_err());
+    assert!(parse_cli_args(&args(&["start-colony", "--seed", "many"])).is_err());
+    assert!(parse_cli_args(&args(&["start-colony", "--config"])).is_err());
+    // Options of one command are not accepted by another
+    assert!(parse_cli_args(&args(&["stop-colony", "--seed", "1"])).is_err());
+    assert!(parse_cli_args(&args(&["status", "--mode", "gcp"])).is_err());
+}
+
+#[test]
+fn test_colony_start_body_applies_seed() {
+    assert_eq!(colony_start_body(None, None).unwrap(), serde_json::json!({}));
+    assert_eq!(colony_start_body(None, Some(7)).unwrap(), serde_json::json!({ "seeding": { "seed": 7 } }));
+
+    let config = std::env::temp_dir().join(format!("cli_start_config_{}.json", std::process::id()));
+    std::fs::write(&config, r#"{"target_tick":500,"seeding":{"density":0.2,"seed":1}}"#).unwrap();
+    let body = colony_start_body(Some(&config), Some(9)).unwrap();
+    assert_eq!(body, serde_json::json!({ "target_tick": 500, "seeding": { "density": 0.2, "seed": 9 } }));
+    std::fs::write(&config, "[1, 2]").unwrap();
+    assert!(colony_start_body(Some(&config), None).is_err());
+    std::fs::remove_file(&config).unwrap();
+    assert!(colony_start_body(Some(&config), None).is_err(), "missing file");
+}
+
+#[test]
+fn test_status_summary() {
+    let health = serde_json::json!({ "status": "ok", "colony_status": "TopographyInitialized" });
+    let backends = BackendsResponse {
+        backends: vec![
+            backend("127.0.0.1:8084", true, Some((120, 130))),
+            backend("127.0.0.1:8086", true, Some((118, 125))),
+            backend("127.0.0.1:8088", false, None),
+        ],
+    };
+    let stats = ColonyStatsResponse {
+        tick: 130,
+        age_ms: 0,
+        cached: false,
+        stats: vec![ColonyMetricStats {
+            metric: StatMetric::Occupancy,
+            avg: 0.25,
+            buckets: vec![StatBucket { value: 0, occs: 300 }, StatBucket { value: 1, occs: 100 }],
+        }],
+        species: Vec::new(),
+        region: None,
+        region_cells: None,
+    };
+    let summary = StatusSummary::new(&health, Some(backends), Some(stats));
+    assert_eq!(summary.colony_status, "TopographyInitialized");
+    assert_eq!(summary.tick_range, Some((118, 130)));
+    assert_eq!(summary.population, Some(100));
+    assert_eq!(summary.backends.len(), 3);
+    let text = summary.to_text();
+    assert!(text.contains("Ticks: 118..130"), "{}", text);
+    assert!(text.contains("Backends: 2 of 3 healthy"), "{}", text);
+
+    // Before the colony is started
+    let summary = StatusSummary::new(&serde_json::json!({ "colony_status": "NotInitialized" }), None, None);
+    assert_eq!(summary.tick_range, None);
+    assert_eq!(summary.population, None);
+    assert!(summary.backends.is_empty());
+}
@@ -0,0 +1,76 @@
+use coordinator::colony_start::ColonyStartFailure;
+use coordinator::coordinator_error::CoordinatorError;
+use shared::colony_model::Shard;
+use std::io::ErrorKind;
+
+const HOST: &str = "10.0.0.5:8082";
+
+fn shard() -> Shard {
+    Shard { x: 250, y: 0, width: 250, height: 250 }
+}
+
+/// One of each variant with the kind, HTTP status and retry policy it must map to
+fn every_variant() -> Vec<(CoordinatorError, &'static str, &'static str, bool)> {
+    vec![
+        (CoordinatorError::Connect { host: HOST.to_string(), source: ErrorKind::ConnectionRefused.into() }, "connect", "502 Bad Gateway", true),
+        (CoordinatorError::Timeout { host: HOST.to_string(), op: "GetShardStats" }, "timeout", "504 Gateway Timeout", true),
+        (CoordinatorError::ConnectionLost { host: HOST.to_string(), op: "InitColony", reason: "reset".to_string() }, "connection_lost", "502 Bad Gateway", true),
+        (CoordinatorError::DeserializationFailed { host: HOST.to_string(), op: "ApplyEvent" }, "deserialization_failed", "502 Bad Gateway", false),
+        (CoordinatorError::UnexpectedResponse { host: HOST.to_string(), op: "StartTicking" }, "unexpected_response", "502 Bad Gateway", false),
+        (CoordinatorError::BackendRejected { host: HOST.to_string(), shard: Some(shard()), response: "colony not initialized".to_string() }, "backend_rejected", "409 Conflict", false),
+        (CoordinatorError::InvalidRules("mutation_chance out of range".to_string()), "invalid_rules", "400 Bad Request", false),
+        (CoordinatorError::TopologyMissing, "topology_missing", "404 Not Found", false),
+        (CoordinatorError::NoBackendForShard { shard: shard() }, "no_backend_for_shard", "404 Not Found", false),
+        (CoordinatorError::NoBackendsAvailable, "no_backends_available", "503 Service Unavailable", false),
+        (CoordinatorError::NotEnoughBackends { healthy: 1, required: 2, dead: vec!["127.0.0.1:8086".to_string()] }, "not_enough_backends", "503 Service Unavailable", false),
+        (CoordinatorError::TopologyRejected("duplicate shard".to_string()), "topology_rejected", "500 Internal Server Error", false),
+        (CoordinatorError::CircuitOpen { host: HOST.to_string() }, "circuit_open", "503 Service Unavailable", false),
+    ]
+}
+
+#[test]
+fn test_every_variant_maps_to_status_kind_and_retry_policy() {
+    for (error, kind, status, retryable) in every_variant() {
+        assert_eq!(error.kind(), kind);
+        assert_eq!(error.http_status(), status, "{}", kind);
+        assert_eq!(error.is_retryable(), retryable, "{}", kind);
+
+        let json: serde_json::Value = serde_json::from_str(&error.to_json()).unwrap();
+        assert_eq!(json["kind"], kind);
+        assert_eq!(json["error"], error.to_string());
+
+        let failure = ColonyStartFailure::from(&error);
+        assert_eq!((failure.kind, failure.http_status), (kind, status));
+    }
+}
+
+#[test]
+fn test_messages_name_the_backend_and_shard() {
+    let rejected = CoordinatorError::BackendRejected { host: HOST.to_string(), shard: Some(shard()), response: "invalid seeding".to_string() };
+    assert_eq!(rejected.to_string(), format!("backend {} rejected shard {}: invalid seeding", HOST, shard().to_id()));
+
+    let rejected = CoordinatorError::BackendRejected { host: HOST.to_string(), shard: None, response: "colony not initialized".to_string() };
+    assert_eq!(rejected.to_string(), format!("backend {} rejected the request: colony not initialized", HOST));
+}
+
+#[test]
+fn test_transport_errors_are_classified() {
+    let timed_out = std::io::Error::from(ErrorKind::TimedOut);
+    assert!(matches!(CoordinatorError::from_io(HOST, "GetShardStats", &timed_out), CoordinatorError::Timeout { .. }));
+    // A read timeout on a blocking socket surfaces as WouldBlock on Unix
+    let would_block = std::io::Error::from(ErrorKind::WouldBlock);
+    assert!(matches!(CoordinatorError::from_io(HOST, "GetShardStats", &would_block), CoordinatorError::Timeout { .. }));
+    let reset = std::io::Error::from(ErrorKind::ConnectionReset);
+    assert!(matches!(CoordinatorError::from_io(HOST, "GetShardStats", &reset), CoordinatorError::ConnectionLost { .. }));
+
+    // The blocking helpers box bincode errors; truncated input is a lost connection, garbage is not
+    let truncated = Box::<dyn std::error::Error>::from(bincode::deserialize::<u64>(&[1, 2]).unwrap_err());
+    assert!(matches!(CoordinatorError::from_transport(HOST, "ApplyEvent", truncated.as_ref()), CoordinatorError::ConnectionLost { .. }));
+    let garbage = Box::<dyn std::error::Error>::from(bincode::deserialize::<bool>(&[7]).unwrap_err());
+    let error = CoordinatorError::from_transport(HOST, "ApplyEvent", garbage.as_ref());
+    assert!(matches!(error, CoordinatorError::DeserializationFailed { .. }));
+    assert!(!error.is_retryable());
+
+    let boxed_io: Box<dyn std::error::Error> = Box::new(std::io::Error::from(ErrorKind::TimedOut));
+    assert!(CoordinatorError::from_transport(HOST, "ApplyEvent", boxed_io.as_ref()).is_retryable());
+}
@@ -0,0 +1,39 @@
+use coordinator::determinism_check::{compare_histories, merge_state_hashes};
+use shared::be_api::{Shard, ShardStateHashes, TickStateHash};
+
+fn shard(x: i32) -> Shard {
+    Shard { x, y: 0, width: 250, height: 250 }
+}
+
+fn hashes(x: i32, entries: &[(u64, u64)]) -> ShardStateHashes {
+    ShardStateHashes { shard: shard(x), hashes: entries.iter().map(|&(tick, hash)| TickStateHash { tick, hash }).collect() }
+}
+
+#[test]
+fn test_merge_extends_the_saved_history() {
+    let mut history = vec![hashes(250, &[(1, 11), (2, 12), (3, 13)])];
+    // A later collection overlaps the saved ticks and brings a shard the history did not have
+    merge_state_hashes(&mut history, vec![hashes(250, &[(3, 99), (4, 14)]), hashes(0, &[(4, 40)])]);
+
+    assert_eq!(history, vec![hashes(0, &[(4, 40)]), hashes(250, &[(1, 11), (2, 12), (3, 99), (4, 14)])]);
+}
+
+#[test]
+fn test_histories_report_the_first_divergent_tick_per_shard() {
+    let run_a = vec![hashes(0, &[(1, 1), (2, 2), (3, 3)]), hashes(250, &[(1, 5), (2, 6), (3, 7)])];
+    let run_b = vec![hashes(0, &[(1, 1), (2, 2), (3, 3)]), hashes(250, &[(1, 5), (2, 60), (3, 70)]), hashes(500, &[(1, 9)])];
+
+    let shards = compare_histories(&run_a, &run_b, 0);
+    let summary: Vec<_> = shards.iter().map(|s| (s.shard_id.as_str(), s.compared_ticks, s.first_divergent_tick)).collect();
+    assert_eq!(summary, vec![
+        ("0_0_250_250", 3, None),
+        ("250_0_250_250", 3, Some(2)),
+        // Only one run has the shard, nothing to compare
+        ("500_0_250_250", 0, None),
+    ]);
+
+    // Ticks before from_tick are ignored, as after restoring a snapshot taken at that tick
+    let shards = compare_histories(&run_a, &run_b, 3);
+    assert_eq!(shards[1].compared_ticks, 1);
+    assert_eq!(shards[1].first_divergent_tick, Some(3));
+}
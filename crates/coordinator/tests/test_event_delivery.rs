@@ -0,0 +1,122 @@
+use coordinator::backend_client::deliver_event;
+use coordinator::coordinator_error::CoordinatorError;
+use shared::be_api::{ApplyEventResponse, Shard, ShardEventEffect};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// Minimal stand-in for a backend: remembers applied event ids like the real shards do
+#[derive(Default)]
+struct FakeBackend {
+    applied_ids: HashSet<Uuid>,
+    applications: usize,
+    calls: usize,
+}
+
+impl FakeBackend {
+    fn apply(&mut self, event_id: Uuid) -> ApplyEventResponse {
+        if self.applied_ids.insert(event_id) {
+            self.applications += 1;
+            ApplyEventResponse::Ok { effects: Vec::new() }
+        } else {
+            ApplyEventResponse::AlreadyApplied
+        }
+    }
+}
+
+fn refused(addr: &str) -> CoordinatorError {
+    CoordinatorError::Connect { host: addr.to_string(), source: std::io::ErrorKind::ConnectionRefused.into() }
+}
+
+#[test]
+fn test_retry_converges_without_double_application() {
+    let backends = vec!["a:8082".to_string(), "b:8082".to_string(), "c:8082".to_string()];
+    let mut fakes: HashMap<String, FakeBackend> = backends.iter()
+        .map(|addr| (addr.clone(), FakeBackend::default()))
+        .collect();
+    let event_id = Uuid::new_v4();
+
+    let delivery = deliver_event(event_id, &backends, |addr| {
+        let fake = fakes.get_mut(addr).expect("unknown backend");
+        fake.calls += 1;
+        match (addr, fake.calls) {
+            // Applies the event but the response times out on the first attempt
+            ("b:8082", 1) => {
+                fake.apply(event_id);
+                Err(CoordinatorError::Timeout { host: addr.to_string(), op: "ApplyEvent" })
+            }
+            // Unreachable on the first attempt
+            ("c:8082", 1) => Err(refused(addr)),
+            _ => Ok(fake.apply(event_id)),
+        }
+    });
+
+    assert!(delivery.failed_on.is_empty());
+    let applied: HashSet<&String> = delivery.applied_to.iter().collect();
+    assert_eq!(applied, backends.iter().collect::<HashSet<_>>());
+    assert_eq!(delivery.event_id, event_id);
+
+    // Only failed backends were retried, and nobody applied the event twice
+    assert_eq!(fakes["a:8082"].calls, 1);
+    assert_eq!(fakes["b:8082"].calls, 2);
+    assert_eq!(fakes["c:8082"].calls, 2);
+    for fake in fakes.values() {
+        assert_eq!(fake.applications, 1);
+    }
+}
+
+#[test]
+fn test_persistent_failure_is_reported() {
+    let backends = vec!["a:8082".to_string(), "down:8082".to_string()];
+    let delivery = deliver_event(Uuid::new_v4(), &backends, |addr| {
+        if addr == "down:8082" {
+            Err(refused(addr))
+        } else {
+            Ok(ApplyEventResponse::Ok { effects: Vec::new() })
+        }
+    });
+
+    assert_eq!(delivery.applied_to, vec!["a:8082".to_string()]);
+    assert_eq!(delivery.failed_on, vec!["down:8082".to_string()]);
+}
+
+#[test]
+fn test_effects_are_aggregated_across_backends() {
+    let backends = vec!["a:8082".to_string(), "b:8082".to_string()];
+    let shard = |x| Shard { x, y: 0, width: 10, height: 10 };
+    let delivery = deliver_event(Uuid::new_v4(), &backends, |addr| {
+        let effects = if addr == "a:8082" {
+            vec![ShardEventEffect { shard: shard(0), cells_affected: 30, creatures_affected: 4, tick_applied: 100 }]
+        } else {
+            vec![ShardEventEffect { shard: shard(10), cells_affected: 12, creatures_affected: 1, tick_applied: 102 }]
+        };
+        Ok(ApplyEventResponse::Ok { effects })
+    });
+
+    assert_eq!(delivery.cells_affected(), 42);
+    assert_eq!(delivery.creatures_affected(), 5);
+    assert!(!delivery.had_no_effect());
+    assert_eq!(delivery.effect_summary(), "42 cells, 5 creatures on 2 shards");
+    assert_eq!(delivery.applied_tick_range(), Some((100, 102)));
+
+    // Applied everywhere, touched nothing: a region that missed all shards
+    let missed = deliver_event(Uuid::new_v4(), &backends, |_| Ok(ApplyEventResponse::Ok { effects: Vec::new() }));
+    assert!(missed.had_no_effect());
+    assert_eq!(missed.applied_tick_range(), None);
+}
+
+#[test]
+fn test_non_retryable_errors_are_not_retried() {
+    let backends = vec!["a:8082".to_string(), "garbled:8082".to_string()];
+    let mut garbled_calls = 0;
+    let delivery = deliver_event(Uuid::new_v4(), &backends, |addr| {
+        if addr == "garbled:8082" {
+            garbled_calls += 1;
+            Err(CoordinatorError::DeserializationFailed { host: addr.to_string(), op: "ApplyEvent" })
+        } else {
+            Ok(ApplyEventResponse::Ok { effects: Vec::new() })
+        }
+    });
+
+    assert_eq!(garbled_calls, 1);
+    assert_eq!(delivery.failed_on, vec!["garbled:8082".to_string()]);
+}
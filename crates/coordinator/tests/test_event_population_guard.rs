@@ -0,0 +1,88 @@
+use coordinator::colony_event_generator::{EventGeneratorConfig, EventImpact, PopulationGuard, ScreenedEvent};
+use shared::colony_events::ColonyEvent;
+use shared::utils::new_random_generator;
+
+fn guard_after(counts: &[u64], favor_beneficial: bool) -> PopulationGuard {
+    let mut config = EventGeneratorConfig::default_config();
+    config.favor_beneficial = favor_beneficial;
+    let mut guard = PopulationGuard::new(config);
+    for count in counts {
+        guard.record_capture(*count);
+    }
+    guard
+}
+
+#[test]
+fn test_default_impacts_per_event_type() {
+    let config = EventGeneratorConfig::default_config();
+    assert_eq!(config.impact(&ColonyEvent::Extinction()), EventImpact::Harmful);
+    assert_eq!(config.impact(&ColonyEvent::NewTopography()), EventImpact::Harmful);
+    assert_eq!(config.impact(&ColonyEvent::ChangeExtraFoodPerTick(-2)), EventImpact::Harmful);
+    assert_eq!(config.impact(&ColonyEvent::ChangeExtraFoodPerTick(2)), EventImpact::Beneficial);
+}
+
+#[test]
+fn test_everything_allowed_before_any_capture_and_while_healthy() {
+    for counts in [&[][..], &[50_000, 48_000, 52_000, 51_000][..]] {
+        let guard = guard_after(counts, false);
+        assert_eq!(guard.harmful_block_reason(), None, "{:?}", counts);
+        assert!(guard.allows(EventImpact::Harmful));
+    }
+}
+
+#[test]
+fn test_small_population_blocks_only_harmful_events() {
+    let guard = guard_after(&[5_000, 3_000, 800], false);
+    assert!(guard.harmful_block_reason().unwrap().contains("800 creatures"));
+    assert!(!guard.allows(EventImpact::Harmful));
+    assert!(guard.allows(EventImpact::Neutral));
+    assert!(guard.allows(EventImpact::Beneficial));
+}
+
+#[test]
+fn test_steep_decline_blocks_harmful_events_until_it_leaves_the_window() {
+    let mut guard = guard_after(&[100_000, 90_000, 60_000, 40_000], false);
+    assert!(guard.harmful_block_reason().unwrap().contains("fell 60%"));
+
+    // Stabilised at 40k: still down 56% from 90k three captures ago
+    guard.record_capture(40_000);
+    assert!(!guard.allows(EventImpact::Harmful));
+    guard.record_capture(40_000);
+    assert!(guard.allows(EventImpact::Harmful));
+}
+
+#[test]
+fn test_screen_suppresses_and_optionally_favors_a_food_bloom() {
+    let mut rng = new_random_generator();
+    let crashed = [10_000, 500];
+
+    match guard_after(&crashed, false).screen(ColonyEvent::Extinction(), &mut rng) {
+        ScreenedEvent::Suppressed { replacement: None, reason, .. } => assert!(!reason.is_empty()),
+        other => panic!("expected a suppression without replacement, got {:?}", other),
+    }
+    match guard_after(&crashed, true).screen(ColonyEvent::NewTopography(), &mut rng) {
+        ScreenedEvent::Suppressed { replacement: Some(ColonyEvent::ChangeExtraFoodPerTick(amount)), .. } => assert!(amount > 0),
+        other => panic!("expected a food bloom replacement, got {:?}", other),
+    }
+    assert!(matches!(guard_after(&crashed, true).screen(ColonyEvent::ChangeExtraFoodPerTick(3), &mut rng), ScreenedEvent::Allowed(_)));
+}
+
+#[test]
+fn test_config_parse_rejects_an_empty_window() {
+    let json = r#"{"min_creatures": 10, "max_decline_fraction": 0.3, "decline_window": 0, "impacts": {"Extinction": "harmful"}}"#;
+    assert!(EventGeneratorConfig::parse(json).is_err());
+    let config = EventGeneratorConfig::parse(&json.replace("\"decline_window\": 0", "\"decline_window\": 2")).unwrap();
+    assert_eq!(config.impact(&ColonyEvent::Extinction()), EventImpact::Harmful);
+    assert_eq!(config.impact(&ColonyEvent::NewTopography()), EventImpact::Neutral);
+    assert!(!config.favor_beneficial);
+}
+
+#[test]
+fn test_reset_forgets_the_previous_colony() {
+    let mut guard = guard_after(&[40_000, 10_000, 500], false);
+    assert!(!guard.allows(EventImpact::Harmful));
+    guard.reset();
+    assert_eq!(guard.harmful_block_reason(), None);
+    guard.record_capture(20_000);
+    assert!(guard.allows(EventImpact::Harmful));
+}
@@ -0,0 +1,32 @@
+use coordinator::colony_event_generator::EventTickClock;
+use coordinator::tick_monitor::TickHistory;
+use shared::coordinator_api::TickSample;
+use std::time::Duration;
+
+#[test]
+fn test_stamped_ticks_are_monotonic() {
+    let mut clock = EventTickClock::default();
+    // (tick the ticker saw on its shard, max tick of the latest sweep)
+    let observations = [(100, None), (450, Some(470)), (460, Some(470)), (900, Some(520)), (905, None), (1400, Some(1450))];
+    let stamps: Vec<u64> = observations.iter().map(|&(intended, swept)| clock.stamp(intended, swept)).collect();
+
+    assert_eq!(stamps, vec![100, 470, 470, 900, 905, 1450]);
+    assert!(stamps.windows(2).all(|pair| pair[0] <= pair[1]), "{:?}", stamps);
+}
+
+#[test]
+fn test_a_lagging_sweep_never_moves_the_stamp_back() {
+    let mut clock = EventTickClock::default();
+    assert_eq!(clock.stamp(2_000_000, Some(2_000_150)), 2_000_150);
+    // Both sources behind the last stamp, e.g. the watched shard was restored from a snapshot
+    assert_eq!(clock.stamp(1_999_900, Some(2_000_100)), 2_000_150);
+}
+
+#[test]
+fn test_latest_sweep_accessor() {
+    let mut history = TickHistory::new(10, Duration::from_secs(3600));
+    assert_eq!(history.latest(), None);
+    history.record(TickSample { timestamp_ms: 1000, min_tick: 5, max_tick: 9 });
+    history.record(TickSample { timestamp_ms: 2000, min_tick: 12, max_tick: 17 });
+    assert_eq!(history.latest().map(|sample| sample.max_tick), Some(17));
+}
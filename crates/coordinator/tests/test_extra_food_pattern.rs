@@ -0,0 +1,129 @@
+use coordinator::colony_start::ColonyStartRequest;
+use coordinator::global_topography::{extra_food_at, GlobalTopography, GlobalTopographyInfo, PATTERN_TOPOGRAPHY_SOURCE, TOPOGRAPHY_SOURCE};
+use shared::colony_model::{ExtraFoodPattern, StripeOrientation};
+
+const BASE: u8 = 5;
+const PEAK: u8 = 50;
+
+/// 100x60 colony of 20x20 shards, extra food between BASE and PEAK
+fn info(pattern: Option<ExtraFoodPattern>) -> GlobalTopographyInfo {
+    GlobalTopographyInfo {
+        total_width: 100,
+        total_height: 60,
+        shard_width: 20,
+        shard_height: 20,
+        base_elevation: BASE,
+        river_elevation_range: PEAK - BASE,
+        river_influence_distance: 20.0,
+        river_count_range: (2, 4),
+        river_segments_range: (5, 10),
+        river_step_length_range: (4.0, 8.0),
+        river_direction_change: 0.6,
+        smoothing_iterations: 4,
+        sanctuary_count: 0,
+        sanctuary_radius_range: (0, 0),
+        seed: Some(9),
+        extra_food_pattern: pattern,
+    }
+}
+
+fn value(pattern: ExtraFoodPattern, oases: &[(f32, f32)], x: usize, y: usize) -> u8 {
+    extra_food_at(&pattern, &info(Some(pattern)), oases, x, y)
+}
+
+#[test]
+fn test_uniform_is_halfway_everywhere() {
+    for (x, y) in [(0, 0), (57, 13), (99, 59)] {
+        assert_eq!(value(ExtraFoodPattern::Uniform, &[], x, y), 28);
+    }
+}
+
+#[test]
+fn test_radial_gradient_fades_linearly_from_the_center() {
+    let pattern = ExtraFoodPattern::RadialGradient { center: (0.5, 0.5), falloff: 30.0 };
+    assert_eq!(value(pattern, &[], 50, 30), PEAK);
+    // A third of the falloff away keeps two thirds of the range
+    assert_eq!(value(pattern, &[], 60, 30), 35);
+    assert_eq!(value(pattern, &[], 50, 45), 28);
+    assert_eq!(value(pattern, &[], 80, 30), BASE);
+    assert_eq!(value(pattern, &[], 0, 0), BASE);
+}
+
+#[test]
+fn test_stripes_repeat_every_period_along_their_orientation() {
+    let vertical = ExtraFoodPattern::Stripes { period: 20, orientation: StripeOrientation::Vertical };
+    assert_eq!(value(vertical, &[], 0, 7), PEAK);
+    assert!((BASE + 1..PEAK).contains(&value(vertical, &[], 5, 7)));
+    assert_eq!(value(vertical, &[], 10, 7), BASE);
+    assert_eq!(value(vertical, &[], 40, 59), PEAK);
+    // Vertical bands do not change down a column
+    assert!((0..60).all(|y| value(vertical, &[], 13, y) == value(vertical, &[], 13, 0)));
+
+    let horizontal = ExtraFoodPattern::Stripes { period: 20, orientation: StripeOrientation::Horizontal };
+    assert_eq!(value(horizontal, &[], 33, 10), BASE);
+    assert_eq!(value(horizontal, &[], 33, 20), PEAK);
+}
+
+#[test]
+fn test_oases_are_rich_within_their_radius_only() {
+    let pattern = ExtraFoodPattern::Oases { count: 2, radius: 5, richness: 120 };
+    let oases = [(10.0, 10.0), (70.0, 40.0)];
+    assert_eq!(value(pattern, &oases, 10, 10), 120);
+    assert_eq!(value(pattern, &oases, 13, 14), 120);
+    assert_eq!(value(pattern, &oases, 74, 44), BASE);
+    assert_eq!(value(pattern, &oases, 70, 45), 120);
+    assert_eq!(value(pattern, &oases, 40, 25), BASE);
+}
+
+#[test]
+fn test_seeded_oases_place_count_circles_of_richness() {
+    let pattern = ExtraFoodPattern::Oases { count: 3, radius: 4, richness: 200 };
+    let topography = GlobalTopography::new(info(Some(pattern)));
+    let field = topography.field();
+    let mut rich = 0;
+    for block_y in 0..field.block_count() {
+        let block = field.row_block(block_y);
+        assert!(block.iter().all(|&food| food == 200 || food == BASE));
+        rich += block.iter().filter(|&&food| food == 200).count();
+    }
+    // Three circles of 49 cells, less any overlap or part cut off by the colony edge
+    assert!(rich > 0 && rich <= 3 * 49, "{} rich cells", rich);
+    assert_eq!(topography.source(), PATTERN_TOPOGRAPHY_SOURCE);
+}
+
+#[test]
+fn test_pattern_is_continuous_across_shards_and_streaming() {
+    let pattern = ExtraFoodPattern::RadialGradient { center: (0.3, 0.6), falloff: 45.0 };
+    let topography = GlobalTopography::new(info(Some(pattern)));
+    let (field, streamed) = (topography.field(), topography.streamed_field());
+    for block_y in 0..field.block_count() {
+        let block = field.row_block(block_y);
+        assert_eq!(block, streamed.row_block(block_y));
+        for (shard, data) in field.shard_payloads(block_y, &block) {
+            for (idx, &food) in data.iter().enumerate() {
+                let (x, y) = (shard.x as usize + idx % 20, shard.y as usize + idx / 20);
+                assert_eq!(food, value(pattern, &[], x, y), "cell ({}, {})", x, y);
+            }
+        }
+    }
+    assert_eq!(GlobalTopography::new(info(None)).source(), TOPOGRAPHY_SOURCE);
+}
+
+#[test]
+fn test_colony_start_request_extra_food_pattern() {
+    assert_eq!(ColonyStartRequest::parse("").unwrap().extra_food_pattern, None);
+    let request = ColonyStartRequest::parse(
+        r#"{"extra_food_pattern":{"Stripes":{"period":40,"orientation":"Horizontal"}}}"#
+    ).expect("Valid pattern");
+    assert_eq!(request.extra_food_pattern, Some(ExtraFoodPattern::Stripes { period: 40, orientation: StripeOrientation::Horizontal }));
+    assert!(ColonyStartRequest::parse(r#"{"extra_food_pattern":"Uniform"}"#).is_ok());
+
+    for invalid in [
+        r#"{"extra_food_pattern":{"RadialGradient":{"center":[1.5,0.5],"falloff":10}}}"#,
+        r#"{"extra_food_pattern":{"RadialGradient":{"center":[0.5,0.5],"falloff":0}}}"#,
+        r#"{"extra_food_pattern":{"Stripes":{"period":1,"orientation":"Vertical"}}}"#,
+        r#"{"extra_food_pattern":{"Oases":{"count":3,"radius":10,"richness":0}}}"#,
+    ] {
+        assert!(ColonyStartRequest::parse(invalid).is_err(), "{}", invalid);
+    }
+}
@@ -0,0 +1,210 @@
+use coordinator::global_topography::{mark_awaiting_topography, GlobalTopography, GlobalTopographyInfo};
+use coordinator::init_colony::{sends_topography_inline, INLINE_TOPOGRAPHY_MAX_BYTES};
+use coordinator::shard_freeze::shard_list;
+use shared::be_api::{sanctuary_bit, sanctuary_mask_len};
+use shared::cluster_topology::{ClusterTopology, HostInfo};
+use shared::colony_model::Shard;
+use std::collections::HashMap;
+
+const SHARD_SIZE: usize = 16;
+const SHARDS_WIDE: usize = 3;
+const SHARDS_HIGH: usize = 2;
+
+fn topography(seed: u64) -> GlobalTopography {
+    GlobalTopography::new(topography_info(seed))
+}
+
+fn topography_info(seed: u64) -> GlobalTopographyInfo {
+    GlobalTopographyInfo {
+        total_width: SHARDS_WIDE * SHARD_SIZE,
+        total_height: SHARDS_HIGH * SHARD_SIZE,
+        shard_width: SHARD_SIZE,
+        shard_height: SHARD_SIZE,
+        base_elevation: 5,
+        river_elevation_range: 45,
+        river_influence_distance: 20.0,
+        river_count_range: (2, 4),
+        river_segments_range: (5, 10),
+        river_step_length_range: (4.0, 8.0),
+        river_direction_change: 0.6,
+        smoothing_iterations: 4,
+        sanctuary_count: 0,
+        sanctuary_radius_range: (0, 0),
+        seed: Some(seed),
+        extra_food_pattern: None,
+    }
+}
+
+/// Payload of every shard, keyed by its (x, y) position in shards
+fn payloads(topography: &GlobalTopography, streamed: bool) -> HashMap<(usize, usize), Vec<u8>> {
+    let field = if streamed { topography.streamed_field() } else { topography.field() };
+    assert_eq!(field.is_streamed(), streamed);
+    let mut payloads = HashMap::new();
+    for block_y in 0..field.block_count() {
+        let block = field.row_block(block_y);
+        for (shard, data) in field.shard_payloads(block_y, &block) {
+            assert_eq!(data.len(), SHARD_SIZE * SHARD_SIZE);
+            payloads.insert((shard.x as usize / SHARD_SIZE, shard.y as usize / SHARD_SIZE), data);
+        }
+    }
+    payloads
+}
+
+/// The colony-wide field put back together from the shard payloads
+fn reassemble(payloads: &HashMap<(usize, usize), Vec<u8>>) -> Vec<u8> {
+    let width = SHARDS_WIDE * SHARD_SIZE;
+    let mut field = vec![0u8; width * SHARDS_HIGH * SHARD_SIZE];
+    for (&(shard_x, shard_y), data) in payloads {
+        for (row, values) in data.chunks_exact(SHARD_SIZE).enumerate() {
+            let start = (shard_y * SHARD_SIZE + row) * width + shard_x * SHARD_SIZE;
+            field[start..start + SHARD_SIZE].copy_from_slice(values);
+        }
+    }
+    field
+}
+
+#[test]
+fn test_adjacent_shards_share_edges_of_one_field() {
+    let topography = topography(42);
+    let in_memory = payloads(&topography, false);
+    let streamed = payloads(&topography, true);
+
+    // Streaming row blocks must not change a single value, least of all at block borders
+    assert_eq!(in_memory, streamed);
+
+    let width = SHARDS_WIDE * SHARD_SIZE;
+    let field = reassemble(&in_memory);
+    let column = |x: usize, rows: std::ops::Range<usize>| rows.map(|y| field[y * width + x]).collect::<Vec<u8>>();
+    let row = |y: usize, columns: std::ops::Range<usize>| field[y * width + columns.start..y * width + columns.end].to_vec();
+    let edge_column = |data: &Vec<u8>, x: usize| data.chunks_exact(SHARD_SIZE).map(|r| r[x]).collect::<Vec<u8>>();
+    let edge_row = |data: &Vec<u8>, y: usize| data[y * SHARD_SIZE..(y + 1) * SHARD_SIZE].to_vec();
+
+    for shard_y in 0..SHARDS_HIGH {
+        for shard_x in 0..SHARDS_WIDE {
+            let data = &in_memory[&(shard_x, shard_y)];
+            let (x0, y0) = (shard_x * SHARD_SIZE, shard_y * SHARD_SIZE);
+            // Each edge of a shard is exactly the field's row/column on that side of the border,
+            // so the neighbor's facing edge is the field's very next row/column
+            assert_eq!(edge_column(data, 0), column(x0, y0..y0 + SHARD_SIZE));
+            assert_eq!(edge_column(data, SHARD_SIZE - 1), column(x0 + SHARD_SIZE - 1, y0..y0 + SHARD_SIZE));
+            assert_eq!(edge_row(data, 0), row(y0, x0..x0 + SHARD_SIZE));
+            assert_eq!(edge_row(data, SHARD_SIZE - 1), row(y0 + SHARD_SIZE - 1, x0..x0 + SHARD_SIZE));
+        }
+    }
+
+    // No seam: stepping across a shard border is no steeper than stepping inside a shard
+    let step = |a: usize, b: usize| (field[a] as i32 - field[b] as i32).abs();
+    let mut interior_max = 0;
+    let mut border_max = 0;
+    for y in 0..SHARDS_HIGH * SHARD_SIZE {
+        for x in 0..width - 1 {
+            let value = step(y * width + x, y * width + x + 1);
+            if (x + 1) % SHARD_SIZE == 0 {
+                border_max = border_max.max(value);
+            } else {
+                interior_max = interior_max.max(value);
+            }
+        }
+    }
+    assert!(border_max <= interior_max, "border step {} > interior step {}", border_max, interior_max);
+}
+
+#[test]
+fn test_same_seed_same_field() {
+    assert_eq!(payloads(&topography(7), false), payloads(&topography(7), false));
+    assert_ne!(payloads(&topography(7), false), payloads(&topography(8), false));
+}
+
+#[test]
+fn test_expanded_field_continues_the_original_terrain() {
+    let original = payloads(&topography(42), false);
+    let expanded_topography = GlobalTopography::expanded(topography_info(42), (SHARDS_WIDE + 1) * SHARD_SIZE, SHARDS_HIGH * SHARD_SIZE);
+    let field = expanded_topography.field();
+    let mut expanded = HashMap::new();
+    for block_y in 0..field.block_count() {
+        let block = field.row_block(block_y);
+        for (shard, data) in field.shard_payloads(block_y, &block) {
+            expanded.insert((shard.x as usize / SHARD_SIZE, shard.y as usize / SHARD_SIZE), data);
+        }
+    }
+    assert_eq!(expanded.len(), (SHARDS_WIDE + 1) * SHARDS_HIGH);
+
+    // The original region keeps its terrain; only smoothing near the old edge sees the new column
+    let smoothing = topography_info(42).smoothing_iterations;
+    for ((shard_x, shard_y), data) in &original {
+        let rows = data.chunks_exact(SHARD_SIZE).zip(expanded[&(*shard_x, *shard_y)].chunks_exact(SHARD_SIZE));
+        let kept = if *shard_x == SHARDS_WIDE - 1 { SHARD_SIZE - smoothing } else { SHARD_SIZE };
+        for (original_row, expanded_row) in rows {
+            assert_eq!(original_row[..kept], expanded_row[..kept], "shard ({}, {})", shard_x, shard_y);
+        }
+    }
+
+    // No seam where the new column meets the old edge
+    let interior_max = original.values()
+        .flat_map(|data| data.chunks_exact(SHARD_SIZE).flat_map(|row| row.windows(2).map(|pair| (pair[0] as i32 - pair[1] as i32).abs())))
+        .max()
+        .unwrap();
+    for shard_y in 0..SHARDS_HIGH {
+        let old_edge = &expanded[&(SHARDS_WIDE - 1, shard_y)];
+        let new_edge = &expanded[&(SHARDS_WIDE, shard_y)];
+        for (old_row, new_row) in old_edge.chunks_exact(SHARD_SIZE).zip(new_edge.chunks_exact(SHARD_SIZE)) {
+            let step = (old_row[SHARD_SIZE - 1] as i32 - new_row[0] as i32).abs();
+            assert!(step <= interior_max, "step {} across the old edge > interior step {}", step, interior_max);
+        }
+    }
+}
+
+#[test]
+fn test_inline_topography_threshold() {
+    // Typical shards travel with InitColonyShard, very large ones in their own call
+    assert!(sends_topography_inline(SHARD_SIZE * SHARD_SIZE));
+    assert!(sends_topography_inline(500 * 500));
+    assert!(sends_topography_inline(INLINE_TOPOGRAPHY_MAX_BYTES));
+    assert!(!sends_topography_inline(INLINE_TOPOGRAPHY_MAX_BYTES + 1));
+    assert!(!sends_topography_inline(2000 * 2000));
+}
+
+#[test]
+fn test_shard_list_reports_awaiting_topography() {
+    let backend = HostInfo::new("10.0.0.1".to_string(), 8082);
+    let shards: Vec<Shard> = (0..2).map(|col| Shard { x: col * 16, y: 900, width: 16, height: 16 }).collect();
+    let topology = ClusterTopology {
+        coordinator_host: HostInfo::new("10.0.0.9".to_string(), 8083),
+        backend_hosts: vec![backend.clone()],
+        shard_to_host: shards.iter().map(|shard| (*shard, backend.clone())).collect(),
+    };
+
+    mark_awaiting_topography(&shards[1]);
+    let list = shard_list(&topology);
+    assert_eq!(list.shards.iter().map(|entry| entry.awaiting_topography).collect::<Vec<_>>(), vec![false, true]);
+}
+
+#[test]
+fn test_sanctuary_mask_follows_the_terrain() {
+    let with_sanctuaries = GlobalTopography::new(GlobalTopographyInfo {
+        sanctuary_count: 2,
+        sanctuary_radius_range: (3, 6),
+        ..topography_info(42)
+    });
+    let cells = SHARD_SIZE * SHARD_SIZE;
+    let field = with_sanctuaries.field();
+    let streamed = with_sanctuaries.streamed_field();
+    let plain = payloads(&topography(42), false);
+
+    let mut inside = 0;
+    for block_y in 0..field.block_count() {
+        let block = field.row_block(block_y);
+        let streamed_block = streamed.row_block(block_y);
+        for ((shard, data), (_, streamed_data)) in field.shard_payloads(block_y, &block).into_iter()
+            .zip(streamed.shard_payloads(block_y, &streamed_block)) {
+            assert_eq!(data, streamed_data);
+            assert_eq!(data.len(), cells + sanctuary_mask_len(cells));
+            // Same seed, same rivers: the sanctuaries only add the mask
+            let key = (shard.x as usize / SHARD_SIZE, shard.y as usize / SHARD_SIZE);
+            assert_eq!(data[..cells], plain[&key][..]);
+            inside += (0..cells).filter(|idx| sanctuary_bit(&data[cells..], *idx)).count();
+        }
+    }
+    // Two circles of radius 3..6 cover part of the colony, never all of it
+    assert!(inside > 0 && inside < SHARDS_WIDE * SHARDS_HIGH * cells, "{} cells inside sanctuaries", inside);
+}
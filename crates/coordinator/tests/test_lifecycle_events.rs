@@ -0,0 +1,49 @@
+use coordinator::coordinator_context::CoordinatorContext;
+use coordinator::lifecycle_events::{check_extinction, record_lifecycle_event, ExtinctionWatch, RegistryMembership};
+use shared::colony_event_shared::{BACKEND_JOINED_EVENT, EXTINCTION_DETECTED_EVENT, LIFECYCLE_EVENT_TYPES};
+use std::collections::BTreeSet;
+
+fn members(backends: &[&str]) -> BTreeSet<String> {
+    backends.iter().map(|backend| backend.to_string()).collect()
+}
+
+#[test]
+fn test_registry_membership_reports_joins_and_departures() {
+    let mut membership = RegistryMembership::new();
+    let none: Vec<String> = Vec::new();
+    // The first sweep only learns who is registered
+    assert_eq!(membership.update(members(&["a:8084", "b:8086"])), (none.clone(), none.clone()));
+    assert_eq!(membership.update(members(&["a:8084", "b:8086"])), (none.clone(), none.clone()));
+    assert_eq!(membership.update(members(&["b:8086", "c:8088"])), (vec!["c:8088".to_string()], vec!["a:8084".to_string()]));
+    assert_eq!(membership.update(members(&[])), (none, vec!["b:8086".to_string(), "c:8088".to_string()]));
+}
+
+#[test]
+fn test_extinction_is_detected_once_per_die_off() {
+    let mut watch = ExtinctionWatch::new();
+    // A colony that starts empty never went extinct
+    assert!(!watch.observe(0));
+    assert!(!watch.observe(120));
+    assert!(watch.observe(0));
+    assert!(!watch.observe(0));
+    assert!(!watch.observe(3));
+    assert!(watch.observe(0));
+}
+
+#[test]
+fn test_lifecycle_events_are_recorded() {
+    let event_types: BTreeSet<&str> = LIFECYCLE_EVENT_TYPES.into_iter().collect();
+    assert_eq!(event_types.len(), LIFECYCLE_EVENT_TYPES.len(), "event types must be distinct");
+
+    record_lifecycle_event(BACKEND_JOINED_EVENT, 7, "Backend 127.0.0.1:8084 registered".to_string());
+    check_extinction(10, 50);
+    check_extinction(20, 0);
+
+    let events = CoordinatorContext::get_instance().get_colony_events();
+    let joined = events.iter().find(|event| event.event_type == BACKEND_JOINED_EVENT).expect("joined event");
+    assert_eq!(joined.tick, 7);
+    assert!(joined.delivery.is_none());
+    let extinct: Vec<_> = events.iter().filter(|event| event.event_type == EXTINCTION_DETECTED_EVENT).collect();
+    assert_eq!(extinct.len(), 1);
+    assert_eq!(extinct[0].tick, 20);
+}
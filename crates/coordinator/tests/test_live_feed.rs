@@ -0,0 +1,108 @@
+mod common;
+
+use coordinator::coordinator_context::CoordinatorContext;
+use coordinator::http_server::start_http_server;
+use coordinator::live_feed_hub::{backend_health_changes, publish_tick, FeedQueue};
+use shared::coordinator_api::{BackendStatus, BreakerState, ColonyEventDescription, TickSample};
+use shared::live_feed::{FeedClient, FeedMessage, FeedTopic};
+use std::time::Duration;
+
+const HTTP_PORT: u16 = 18391;
+
+fn status(backend: &str, healthy: bool, error: Option<&str>) -> BackendStatus {
+    BackendStatus {
+        backend: backend.to_string(),
+        assigned_shards: Vec::new(),
+        hosted_shards: None,
+        tick_range: None,
+        healthy,
+        version: None,
+        error: error.map(str::to_string),
+        assigned_not_hosting: Vec::new(),
+        hosting_not_assigned: Vec::new(),
+        lease_mismatches: Vec::new(),
+        suspended_shards: Vec::new(),
+        breaker: BreakerState::Closed,
+    }
+}
+
+#[test]
+fn test_queue_drops_oldest_and_counts() {
+    let mut queue = FeedQueue::new(3);
+    for i in 0..5 {
+        queue.push(i.to_string().into());
+    }
+    let (messages, dropped) = queue.drain();
+    assert_eq!(messages.iter().map(|m| m.as_ref()).collect::<Vec<_>>(), vec!["2", "3", "4"]);
+    assert_eq!(dropped, 2);
+
+    queue.push("5".into());
+    assert_eq!(queue.drain().1, 0);
+}
+
+#[test]
+fn test_backend_health_reports_changes_only() {
+    let mut last = Vec::new();
+    let first = vec![status("10.0.0.1:8082", true, None), status("10.0.0.2:8082", true, None)];
+    assert_eq!(backend_health_changes(&mut last, &first).len(), 2);
+    assert!(backend_health_changes(&mut last, &first).is_empty());
+
+    let down = vec![status("10.0.0.1:8082", true, None), status("10.0.0.2:8082", false, Some("Timed out"))];
+    let changes = backend_health_changes(&mut last, &down);
+    assert_eq!(changes.len(), 1);
+    match &changes[0] {
+        FeedMessage::BackendHealth { index, healthy, error, .. } => {
+            assert_eq!((*index, *healthy, error.as_deref()), (1, false, Some("Timed out")));
+        }
+        other => panic!("unexpected message {:?}", other),
+    }
+}
+
+fn connect(topics: &'static [FeedTopic]) -> FeedClient {
+    let address = format!("127.0.0.1:{}", HTTP_PORT);
+    for _ in 0..50 {
+        if let Ok(client) = FeedClient::connect(&address, None, topics, Duration::from_secs(5)) {
+            return client;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    panic!("coordinator feed did not come up on {}", address);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_feed_pushes_subscribed_topics_only() {
+    common::use_temp_output_dir();
+    tokio::spawn(start_http_server(HTTP_PORT));
+
+    let mut client = tokio::task::spawn_blocking(|| {
+        let mut client = connect(&[FeedTopic::Events]);
+        assert!(matches!(client.next_message().unwrap(), FeedMessage::Subscribed { topics } if topics == vec![FeedTopic::Events]));
+        client
+    }).await.unwrap();
+
+    // Not subscribed to ticks, so only the event arrives
+    publish_tick(TickSample { timestamp_ms: 1, min_tick: 2, max_tick: 3 });
+    CoordinatorContext::get_instance().add_colony_event(ColonyEventDescription {
+        tick: 42,
+        intended_tick: None,
+        event_type: "Feed Test".to_string(),
+        description: "pushed".to_string(),
+        delivery: None,
+        no_effect: false,
+        region: None,
+    });
+    client = tokio::task::spawn_blocking(move || {
+        match client.next_message().unwrap() {
+            FeedMessage::Event { event } => assert_eq!((event.tick, event.event_type.as_str()), (42, "Feed Test")),
+            other => panic!("unexpected message {:?}", other),
+        }
+        client.subscribe(&[FeedTopic::Ticks]).unwrap();
+        assert!(matches!(client.next_message().unwrap(), FeedMessage::Subscribed { .. }));
+        client
+    }).await.unwrap();
+
+    publish_tick(TickSample { timestamp_ms: 1, min_tick: 2, max_tick: 3 });
+    tokio::task::spawn_blocking(move || {
+        assert!(matches!(client.next_message().unwrap(), FeedMessage::Tick { min_tick: 2, max_tick: 3, .. }));
+    }).await.unwrap();
+}
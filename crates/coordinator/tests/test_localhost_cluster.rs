@@ -0,0 +1,384 @@
+//! End-to-end colony-start on a localhost cluster: one coordinator and one or two backends.
+//! Node state (colony, topology, registry) is process-global, so each node runs its library
+//! entry point (run_coordinator / run_backend) in a re-executed copy of this test binary,
+//! with its own working directory so output/ssm and output/logs never leak between tests.
+
+use backend::be_server::{run_backend, BackendServerConfig, DeploymentMode as BackendDeploymentMode};
+use backend::rate_limiter::RateLimitConfig;
+use backend::image_qos::QosConfig;
+use backend::presentation_snapshots::SnapshotConfig;
+use coordinator::coordinator_cli::{discover_coordinator_url, parse_cli_args, run_cli_command, CliOutput};
+use coordinator::coordinator_server::{run_coordinator, CoordinatorServerConfig, DeploymentMode as CoordinatorDeploymentMode};
+use shared::be_api::Shard;
+use shared::cluster_registry::FileClusterRegistry;
+use shared::cluster_topology::ClusterTopology;
+use shared::colony_event_shared::{COLONY_STARTED_EVENT, COLONY_STOPPED_EVENT, TARGET_TICK_REACHED_EVENT, TICKING_STARTED_EVENT};
+use shared::coordinator_api::{ColonyEventDescription, ColonyStatsResponse, RunSummary};
+use shared::output_paths::OutputPaths;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Set on node processes to "<role>:<rpc_port>:<http_port>"
+const NODE_ENV: &str = "COLONY_TEST_NODE";
+const NODE_TEST_NAME: &str = "cluster_node_process";
+const LOCALHOST: &str = "127.0.0.1";
+const CLUSTER_DEADLINE: Duration = Duration::from_secs(50);
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+const MIN_TICKS: u64 = 5;
+const TARGET_TICK: u64 = 100;
+/// Reaching TARGET_TICK takes a couple of minutes in debug builds on a small machine
+const TIME_BOXED_RUN_DEADLINE: Duration = Duration::from_secs(300);
+/// Status and capture merge the whole colony, which is slow in debug builds next to the other clusters
+const CLI_RUN_DEADLINE: Duration = Duration::from_secs(300);
+
+/// Entry point of the node processes; a no-op when run as a regular test
+#[test]
+fn cluster_node_process() {
+    let Ok(node) = std::env::var(NODE_ENV) else { return };
+    let parts: Vec<&str> = node.split(':').collect();
+    let rpc_port: u16 = parts[1].parse().expect("Invalid rpc port");
+    let http_port: u16 = parts[2].parse().expect("Invalid http port");
+
+    let runtime = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
+    let result = runtime.block_on(async {
+        match parts[0] {
+            "coordinator" => run_coordinator(CoordinatorServerConfig {
+                rpc_port,
+                http_port,
+                deployment_mode: CoordinatorDeploymentMode::Localhost,
+            }).await,
+            "backend" => run_backend(BackendServerConfig {
+                hostname: LOCALHOST.to_string(),
+                rpc_port,
+                http_port,
+                deployment_mode: BackendDeploymentMode::Localhost,
+                rate_limit: RateLimitConfig::default(),
+                qos: QosConfig::default(),
+                snapshots: SnapshotConfig::default(),
+            }).await,
+            role => panic!("Unknown node role: {}", role),
+        }
+    });
+    result.expect("Node exited");
+}
+
+fn free_port() -> u16 {
+    let listener = std::net::TcpListener::bind((LOCALHOST, 0)).expect("Failed to bind a free port");
+    listener.local_addr().expect("Failed to read local address").port()
+}
+
+/// Coordinator and backends running as child processes; killed and cleaned up on drop
+struct LocalCluster {
+    work_dir: PathBuf,
+    nodes: Vec<Child>,
+    coordinator_http_port: u16,
+    /// Backend RPC port (as listed in the topology) -> HTTP port
+    backend_http_ports: HashMap<u16, u16>,
+}
+
+impl LocalCluster {
+    fn start(backend_count: usize) -> Self {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).expect("Clock before epoch").as_nanos();
+        let work_dir = std::env::temp_dir().join(format!("colony_cluster_{}_{}", std::process::id(), nanos));
+        std::fs::create_dir_all(&work_dir).expect("Failed to create cluster work dir");
+
+        let mut cluster = LocalCluster {
+            work_dir,
+            nodes: Vec::new(),
+            coordinator_http_port: free_port(),
+            backend_http_ports: HashMap::new(),
+        };
+        cluster.spawn_node("coordinator", free_port(), cluster.coordinator_http_port);
+        for _ in 0..backend_count {
+            let (rpc_port, http_port) = (free_port(), free_port());
+            cluster.spawn_node("backend", rpc_port, http_port);
+            cluster.backend_http_ports.insert(rpc_port, http_port);
+        }
+        cluster
+    }
+
+    fn spawn_node(&mut self, role: &str, rpc_port: u16, http_port: u16) {
+        let child = Command::new(std::env::current_exe().expect("Failed to locate test binary"))
+            .args([NODE_TEST_NAME, "--exact", "--nocapture"])
+            .env(NODE_ENV, format!("{}:{}:{}", role, rpc_port, http_port))
+            .current_dir(&self.work_dir)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("Failed to spawn node process");
+        self.nodes.push(child);
+    }
+
+    fn registry_dir(&self) -> PathBuf {
+        self.work_dir.join("output").join("ssm")
+    }
+
+    fn registered_backends(&self) -> usize {
+        std::fs::read_dir(self.registry_dir().join("backends"))
+            .map(|entries| entries.filter_map(Result::ok).count())
+            .unwrap_or(0)
+    }
+
+    fn coordinator_url(&self, path: &str) -> String {
+        format!("http://{}:{}{}", LOCALHOST, self.coordinator_http_port, path)
+    }
+
+    /// Every colony event the coordinator recorded, most recent first
+    fn colony_events(&self, client: &reqwest::blocking::Client) -> Vec<ColonyEventDescription> {
+        #[derive(serde::Deserialize)]
+        struct EventsResponse {
+            events: Vec<ColonyEventDescription>,
+        }
+        let response: EventsResponse = client.get(self.coordinator_url("/api/colony-events?limit=1000")).send()
+            .expect("colony-events request failed")
+            .json()
+            .expect("Invalid colony events");
+        response.events
+    }
+
+    fn wait_until<T>(&self, what: &str, deadline: Instant, mut poll: impl FnMut() -> Option<T>) -> T {
+        loop {
+            if let Some(value) = poll() {
+                return value;
+            }
+            assert!(Instant::now() < deadline, "Timed out waiting for {}", what);
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+impl Drop for LocalCluster {
+    fn drop(&mut self) {
+        for node in &mut self.nodes {
+            let _ = node.kill();
+            let _ = node.wait();
+        }
+        // Keep the node logs of a failed run around for inspection
+        if std::thread::panicking() {
+            eprintln!("Cluster logs kept in {}", self.work_dir.join("output").join("logs").display());
+        } else {
+            let _ = std::fs::remove_dir_all(&self.work_dir);
+        }
+    }
+}
+
+#[test]
+fn test_colony_start_on_two_backends() {
+    let deadline = Instant::now() + CLUSTER_DEADLINE;
+    let cluster = LocalCluster::start(2);
+    // The node HTTP servers answer one request per connection, so never reuse one
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(5))
+        .pool_max_idle_per_host(0)
+        .build()
+        .expect("Failed to build HTTP client");
+
+    cluster.wait_until("node registration", deadline, || {
+        let coordinator_registered = cluster.registry_dir().join("coordinator.json").exists();
+        (coordinator_registered && cluster.registered_backends() == 2).then_some(())
+    });
+
+    let response = client.post(cluster.coordinator_url("/colony-start?idempotency_key=localhost-cluster-test"))
+        .send()
+        .expect("colony-start request failed");
+    assert_eq!(response.status().as_u16(), 202);
+
+    // /topology answers {"status":"in-progress"} until the colony is initialized
+    let topology: ClusterTopology = cluster.wait_until("topology", deadline, || {
+        let response = client.get(cluster.coordinator_url("/topology")).send().ok()?;
+        if !response.status().is_success() {
+            return None;
+        }
+        serde_json::from_str(&response.text().ok()?).ok()
+    });
+    assert_eq!(topology.get_all_backend_hosts().len(), 2);
+    assert!(!topology.get_all_shards().is_empty());
+
+    for http_port in cluster.backend_http_ports.values() {
+        let url = format!("http://{}:{}/api/colony-info", LOCALHOST, http_port);
+        cluster.wait_until("ticking", deadline, || {
+            let info: serde_json::Value = client.get(&url).send().ok()?.json().ok()?;
+            let current_tick = info["current_tick"].as_u64()?;
+            (current_tick >= MIN_TICKS).then_some(())
+        });
+    }
+
+    // Pausing frees the CPU for the stats and image queries below, which are slow in debug builds
+    let response = client.post(cluster.coordinator_url("/api/pause")).send().expect("pause request failed");
+    assert!(response.status().is_success(), "pause returned {}", response.status());
+
+    let events = cluster.colony_events(&client);
+    for event_type in [COLONY_STARTED_EVENT, TICKING_STARTED_EVENT, COLONY_STOPPED_EVENT] {
+        assert!(events.iter().any(|event| event.event_type == event_type), "no {} event in {:?}", event_type, events);
+    }
+    let started = events.iter().find(|event| event.event_type == COLONY_STARTED_EVENT).expect("Colony Started event");
+    assert_eq!(started.tick, 0);
+    assert!(started.description.contains("config hash"), "{}", started.description);
+
+    let stats: ColonyStatsResponse = cluster.wait_until("colony stats", deadline, || {
+        let response = client.get(cluster.coordinator_url("/api/colony-stats?metrics=Health,Size")).send().ok()?;
+        if !response.status().is_success() {
+            return None;
+        }
+        response.json().ok()
+    });
+    assert!(!stats.stats.is_empty());
+    for metric_stats in &stats.stats {
+        assert!(!metric_stats.buckets.is_empty(), "{:?} has no buckets", metric_stats.metric);
+        assert!(metric_stats.avg.is_finite() && metric_stats.avg >= 0.0, "{:?} avg {}", metric_stats.metric, metric_stats.avg);
+    }
+
+    // Region stats are a GET as well, so observer tokens can ask for them
+    let region = Shard { x: 0, y: 0, width: 10, height: 10 };
+    let url = cluster.coordinator_url(&format!("/api/colony-stats?metrics=Health&region={}", region.to_id()));
+    let response = client.get(&url).send().expect("Region stats request failed");
+    assert!(response.status().is_success(), "{} returned {}", url, response.status());
+    let region_stats: ColonyStatsResponse = response.json().expect("Failed to parse region stats");
+    assert_eq!(region_stats.region, Some(region));
+    assert_eq!(region_stats.region_cells, Some(100));
+
+    for shard in topology.get_all_shards() {
+        let host = topology.get_host_for_shard(&shard).expect("Shard without host");
+        let http_port = cluster.backend_http_ports[&host.port];
+        let url = format!("http://{}:{}/api/shard/{}/image", LOCALHOST, http_port, shard.to_id());
+        let response = client.get(&url).send().expect("Shard image request failed");
+        assert!(response.status().is_success(), "{} returned {}", url, response.status());
+        // RGB bytes, gzip already decoded by reqwest
+        let image = response.bytes().expect("Failed to read shard image");
+        assert_eq!(image.len(), (shard.width * shard.height * 3) as usize, "{}", url);
+    }
+}
+
+#[test]
+fn test_time_boxed_run_stops_at_target_tick() {
+    let deadline = Instant::now() + TIME_BOXED_RUN_DEADLINE;
+    let cluster = LocalCluster::start(2);
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(5))
+        .pool_max_idle_per_host(0)
+        .build()
+        .expect("Failed to build HTTP client");
+
+    cluster.wait_until("node registration", deadline, || {
+        let coordinator_registered = cluster.registry_dir().join("coordinator.json").exists();
+        (coordinator_registered && cluster.registered_backends() == 2).then_some(())
+    });
+
+    let response = client.post(cluster.coordinator_url("/colony-start?idempotency_key=time-boxed-run-test"))
+        .body(format!(r#"{{"target_tick":{}}}"#, TARGET_TICK))
+        .send()
+        .expect("colony-start request failed");
+    assert_eq!(response.status().as_u16(), 202);
+
+    // 404 until the ticker noticed the target tick and finished the final capture and stats
+    let summary: RunSummary = cluster.wait_until("run summary", deadline, || {
+        let response = client.get(cluster.coordinator_url("/api/run-summary")).send().ok()?;
+        if !response.status().is_success() {
+            return None;
+        }
+        response.json().ok()
+    });
+    assert_eq!(summary.target_tick, TARGET_TICK);
+    assert!(summary.final_tick >= TARGET_TICK, "final tick {}", summary.final_tick);
+    assert!(summary.config_hash.is_some());
+    assert!(summary.wall_clock_secs.is_some_and(|secs| secs > 0.0));
+    assert!(summary.creatures_count.is_some_and(|count| count > 0), "{:?}", summary.creatures_count);
+    assert!(summary.metric_averages.contains_key("health"));
+    let summary_file = OutputPaths::new(cluster.work_dir.join("output")).instance_dir(&summary.colony_instance_id).join("run_summary.json");
+    assert!(summary_file.exists(), "{} missing", summary_file.display());
+
+    let topology: serde_json::Value = client.get(cluster.coordinator_url("/topology")).send().expect("topology request failed").json().expect("Invalid topology");
+    assert_eq!(topology["colony_status"], "Completed");
+    let health: serde_json::Value = client.get(cluster.coordinator_url("/health")).send().expect("health request failed").json().expect("Invalid health");
+    assert_eq!(health["colony_status"], "Completed");
+    let events = cluster.colony_events(&client);
+    let reached = events.iter().find(|event| event.event_type == TARGET_TICK_REACHED_EVENT).expect("Target Tick Reached event");
+    assert_eq!(reached.tick, TARGET_TICK);
+    assert!(events.iter().any(|event| event.event_type == COLONY_STOPPED_EVENT), "{:?}", events);
+
+    // Stopped for good: resuming is refused and the ticker stays paused
+    let response = client.post(cluster.coordinator_url("/api/resume")).send().expect("resume request failed");
+    assert_eq!(response.status().as_u16(), 409);
+    let ticker: serde_json::Value = client.get(cluster.coordinator_url("/api/ticker-state")).send().expect("ticker-state request failed").json().expect("Invalid ticker state");
+    assert_eq!(ticker["paused"], true);
+}
+
+/// Runs a coordinator subcommand in-process against the given coordinator HTTP address
+fn run_cli(runtime: &tokio::runtime::Runtime, coordinator: &str, args: &[&str]) -> Result<CliOutput, String> {
+    let mut args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+    args.extend(["--coordinator".to_string(), coordinator.to_string()]);
+    let cli_args = parse_cli_args(&args).map_err(|e| e.to_string())?;
+    runtime.block_on(run_cli_command(&cli_args))
+}
+
+#[test]
+fn test_cli_subcommands_drive_a_colony() {
+    let deadline = Instant::now() + CLI_RUN_DEADLINE;
+    let cluster = LocalCluster::start(1);
+    let runtime = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
+
+    cluster.wait_until("node registration", deadline, || {
+        let coordinator_registered = cluster.registry_dir().join("coordinator.json").exists();
+        (coordinator_registered && cluster.registered_backends() == 1).then_some(())
+    });
+    let url = runtime.block_on(discover_coordinator_url(&FileClusterRegistry::with_base_path(cluster.registry_dir())))
+        .expect("Coordinator not discovered");
+    assert_eq!(url, cluster.coordinator_url(""));
+    let coordinator = url.trim_start_matches("http://").to_string();
+
+    let status = run_cli(&runtime, &coordinator, &["status"]).expect("status failed");
+    assert_eq!(status.json["population"], serde_json::Value::Null);
+
+    let started = run_cli(&runtime, &coordinator, &["start-colony", "--seed", "7"]).expect("start-colony failed");
+    assert_eq!(started.json["accepted"], true);
+
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(5))
+        .pool_max_idle_per_host(0)
+        .build()
+        .expect("Failed to build HTTP client");
+    let http_port = *cluster.backend_http_ports.values().next().expect("No backend");
+    let url = format!("http://{}:{}/api/colony-info", LOCALHOST, http_port);
+    cluster.wait_until("ticking", deadline, || {
+        let info: serde_json::Value = client.get(&url).send().ok()?.json().ok()?;
+        (info["current_tick"].as_u64()? >= MIN_TICKS).then_some(())
+    });
+    // A second start has a new idempotency key, so the started colony refuses it
+    let error = run_cli(&runtime, &coordinator, &["start-colony"]).expect_err("second start-colony succeeded");
+    assert!(error.contains("409"), "{}", error);
+
+    let event_file = cluster.work_dir.join("event.json");
+    std::fs::write(&event_file, r#"{"ChangeExtraFoodPerTick":2}"#).expect("Failed to write event file");
+    let applied = run_cli(&runtime, &coordinator, &["apply-event", "--event", event_file.to_str().unwrap()]).expect("apply-event failed");
+    assert_eq!(applied.json["delivery"]["applied_to"].as_array().map(Vec::len), Some(1), "{}", applied.json);
+    let events = cluster.colony_events(&client);
+    assert!(events.iter().any(|event| event.description == applied.json["description"]), "{:?}", events);
+
+    // Pausing frees the CPU for the capture and stats below, which are slow in debug builds
+    let stopped = run_cli(&runtime, &coordinator, &["stop-colony"]).expect("stop-colony failed");
+    assert_eq!(stopped.json["paused"], true);
+
+    let captured = run_cli(&runtime, &coordinator, &["capture"]).expect("capture failed");
+    let frame = PathBuf::from(captured.json["path"].as_str().expect("capture path"));
+    // Relative to the coordinator working directory unless the output directory is absolute
+    assert!(cluster.work_dir.join(&frame).exists(), "{} missing", frame.display());
+    let status = run_cli(&runtime, &coordinator, &["status"]).expect("status failed");
+    assert!(status.json["tick_range"][1].as_u64().is_some_and(|tick| tick >= MIN_TICKS), "{}", status.json);
+    assert!(status.json["population"].as_u64().is_some_and(|population| population > 0), "{}", status.json);
+
+    // The binary discovers the coordinator in the registry of its working directory
+    let binary = |args: &[&str]| Command::new(env!("CARGO_BIN_EXE_coordinator"))
+        .args(args)
+        .current_dir(&cluster.work_dir)
+        .stderr(Stdio::null())
+        .output()
+        .expect("Failed to run the coordinator binary");
+    let output = binary(&["status", "--json"]);
+    assert!(output.status.success());
+    let status: serde_json::Value = serde_json::from_slice(&output.stdout).expect("status --json prints JSON");
+    assert_eq!(status["backends"].as_array().map(Vec::len), Some(1));
+    assert_eq!(binary(&["start-colony"]).status.code(), Some(1));
+    assert_eq!(binary(&["start-colony", "--seed", "many"]).status.code(), Some(2));
+}
@@ -0,0 +1,68 @@
+use coordinator::colony_capture::images_dir;
+use coordinator::coordinator_context::CoordinatorContext;
+use coordinator::event_logging::{write_colony_created_event_json, write_run_config_json};
+use coordinator::init_colony::COLONY_LIFE_INITIAL_RULES;
+use shared::cluster_registry::{ClusterRegistry, FileClusterRegistry};
+use shared::cluster_topology::NodeAddress;
+use shared::colony_model::SeedingOptions;
+use shared::coordinator_api::ColonyRunConfig;
+use shared::log;
+use shared::logging::init_logging;
+use shared::output_paths::{OutputPaths, OUTPUT_DIR_ENV};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const INSTANCE_ID: &str = "out";
+
+fn run_config() -> ColonyRunConfig {
+    ColonyRunConfig {
+        colony_instance_id: Some(INSTANCE_ID.to_string()),
+        deployment_mode: "localhost".to_string(),
+        colony_width: 500,
+        colony_height: 500,
+        width_in_shards: 2,
+        height_in_shards: 2,
+        shard_width: 250,
+        shard_height: 250,
+        backend_count: 1,
+        assignment_strategy: "round-robin".to_string(),
+        topography_seed: 7,
+        topography_source: "procedural-rivers".to_string(),
+        topography_hash: "00000000deadbeef".to_string(),
+        initial_rules: COLONY_LIFE_INITIAL_RULES,
+        seeding: SeedingOptions::default(),
+        extra_food_pattern: None,
+    }
+}
+
+/// The only test in this binary: OutputPaths is read from the environment once per process
+#[tokio::test]
+async fn test_writers_respect_the_output_root() {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).expect("Clock before epoch").as_nanos();
+    let root = std::env::temp_dir().join(format!("output_root_{}_{}", std::process::id(), nanos));
+    std::env::set_var(OUTPUT_DIR_ENV, &root);
+    let paths = OutputPaths::get_instance();
+    assert_eq!(paths.root(), root.as_path());
+    paths.ensure_writable().expect("Temp root should be writable");
+
+    init_logging(paths.log_file("coordinator_test"));
+    log!("Writing under {}", root.display());
+    assert!(root.join("logs/coordinator_test.log").is_file());
+
+    FileClusterRegistry::new()
+        .register_backend("be-1".to_string(), NodeAddress::new("127.0.0.1".to_string(), "127.0.0.1".to_string(), 8082, 8085))
+        .await
+        .expect("Failed to register backend");
+    assert!(root.join("ssm/backends/be-1.json").is_file());
+
+    write_run_config_json(&run_config()).expect("Failed to write run config");
+    assert!(root.join("s3/distributed-colony/out/run_config.json").is_file());
+
+    CoordinatorContext::get_instance().get_coord_stored_info().colony_instance_id = Some(INSTANCE_ID.to_string());
+    write_colony_created_event_json(COLONY_LIFE_INITIAL_RULES).expect("Failed to write creation event");
+    assert!(root.join("s3/distributed-colony/out/events").read_dir().unwrap().next().is_some());
+
+    assert_eq!(images_dir(INSTANCE_ID), root.join("s3/distributed-colony/out/images_shots"));
+    assert!(!std::path::Path::new("output/s3/distributed-colony/out").exists());
+
+    let _ = std::fs::remove_dir_all(&root);
+}
@@ -0,0 +1,31 @@
+use coordinator::coordinator_context::{CoordinatorContext, MAX_REGION_EVENTS};
+use shared::colony_events::{Ellipse, Region};
+use shared::coordinator_api::ColonyEventDescription;
+
+fn creature_event(tick: u64) -> ColonyEventDescription {
+    ColonyEventDescription {
+        tick,
+        intended_tick: None,
+        event_type: "CreateCreature".to_string(),
+        description: String::new(),
+        delivery: None,
+        no_effect: false,
+        region: Some(Region::Ellipse(Ellipse { x: 10, y: 10, radius_x: 3, radius_y: 3 })),
+    }
+}
+
+#[test]
+fn test_region_events_are_bounded_and_kept_apart_from_colony_events() {
+    let context = CoordinatorContext::get_instance();
+    let colony_events = context.get_colony_events().len();
+    for tick in 0..(MAX_REGION_EVENTS as u64 + 20) {
+        context.add_region_event(creature_event(tick));
+    }
+
+    let region_events = context.get_region_events();
+    assert_eq!(region_events.len(), MAX_REGION_EVENTS);
+    // Newest first, the oldest 20 dropped
+    assert_eq!(region_events[0].tick, MAX_REGION_EVENTS as u64 + 19);
+    assert_eq!(region_events[MAX_REGION_EVENTS - 1].tick, 20);
+    assert_eq!(context.get_colony_events().len(), colony_events);
+}
@@ -0,0 +1,111 @@
+use coordinator::backend_client::deliver_event;
+use coordinator::coordinator_context::CoordinatorContext;
+use coordinator::coordinator_error::CoordinatorError;
+use coordinator::init_colony::COLONY_LIFE_INITIAL_RULES;
+use coordinator::rules_drift::{rules_differences, RulesDriftWatch, RULES_DRIFT_EVENT, RULES_DRIFT_REPAIRED_EVENT};
+use shared::be_api::{ApplyEventResponse, ColonyLifeRules};
+use shared::coordinator_api::EventDelivery;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+type Backends = HashMap<String, Option<ColonyLifeRules>>;
+type Repair = fn(&[String]) -> EventDelivery;
+
+/// Three backends on the initial rules; addresses are unique per test as colony events are global
+fn backends(test: u16) -> (Vec<String>, Backends) {
+    let addresses: Vec<String> = (0..3).map(|idx| format!("10.{}.0.{}:8082", test, idx)).collect();
+    let rules = addresses.iter().map(|addr| (addr.clone(), Some(COLONY_LIFE_INITIAL_RULES))).collect();
+    (addresses, rules)
+}
+
+fn changed_rules() -> ColonyLifeRules {
+    ColonyLifeRules { mutation_chance: 50, reproduction_min_food: 90, ..COLONY_LIFE_INITIAL_RULES }
+}
+
+/// Delivers new rules to every backend but the one that misses the event
+fn apply_rules_missing_one(rules: &mut Backends, addresses: &[String], new_rules: ColonyLifeRules, missed: &str) -> EventDelivery {
+    deliver_event(Uuid::new_v4(), addresses, |addr| {
+        if addr == missed {
+            return Err(CoordinatorError::Connect { host: addr.to_string(), source: std::io::ErrorKind::ConnectionRefused.into() });
+        }
+        rules.insert(addr.to_string(), Some(new_rules));
+        Ok(ApplyEventResponse::Ok { effects: Vec::new() })
+    })
+}
+
+fn query(rules: &Backends) -> impl FnMut(&str) -> Result<Option<ColonyLifeRules>, CoordinatorError> + '_ {
+    |addr| rules.get(addr).copied().ok_or(CoordinatorError::NoBackendsAvailable)
+}
+
+fn events_of(event_type: &str, backend: &str) -> usize {
+    CoordinatorContext::get_instance().get_colony_events().iter()
+        .filter(|event| event.event_type == event_type && event.description.contains(backend))
+        .count()
+}
+
+#[test]
+fn test_rules_differences_lists_the_changed_fields() {
+    assert!(rules_differences(&COLONY_LIFE_INITIAL_RULES, &COLONY_LIFE_INITIAL_RULES).is_empty());
+    let fields: Vec<String> = rules_differences(&changed_rules(), &COLONY_LIFE_INITIAL_RULES).into_iter().map(|drift| drift.field).collect();
+    assert_eq!(fields, vec!["mutation_chance".to_string(), "reproduction_min_food".to_string()]);
+}
+
+#[test]
+fn test_backend_that_missed_a_rules_change_is_detected_once() {
+    let (addresses, mut rules) = backends(1);
+    let delivery = apply_rules_missing_one(&mut rules, &addresses, changed_rules(), &addresses[2]);
+    assert_eq!(delivery.failed_on, vec![addresses[2].clone()]);
+
+    let mut watch = RulesDriftWatch::new();
+    let check = watch.check(&changed_rules(), &addresses, 700, query(&rules), None::<Repair>);
+    assert_eq!(check.drift.len(), 1);
+    assert_eq!(check.drift[0].backend, addresses[2]);
+    let drift = &check.drift[0].fields[0];
+    assert_eq!((drift.field.as_str(), drift.expected.as_u64(), drift.actual.as_u64()),
+               ("mutation_chance", Some(50), Some(COLONY_LIFE_INITIAL_RULES.mutation_chance as u64)));
+    assert!(check.drift[0].describe().contains("mutation_chance"));
+    assert_eq!(watch.drift(), check.drift.as_slice());
+    assert_eq!(events_of(RULES_DRIFT_EVENT, &addresses[2]), 1);
+
+    // The same drift is not recorded again, it stays until the backend catches up
+    watch.check(&changed_rules(), &addresses, 710, query(&rules), None::<Repair>);
+    assert_eq!(events_of(RULES_DRIFT_EVENT, &addresses[2]), 1);
+    rules.insert(addresses[2].clone(), Some(changed_rules()));
+    assert!(watch.check(&changed_rules(), &addresses, 720, query(&rules), None::<Repair>).drift.is_empty());
+    assert!(watch.drift().is_empty());
+}
+
+#[test]
+fn test_repair_resends_the_coordinator_rules_to_drifting_backends() {
+    let (addresses, mut rules) = backends(2);
+    apply_rules_missing_one(&mut rules, &addresses, changed_rules(), &addresses[1]);
+
+    let mut watch = RulesDriftWatch::new();
+    let mut repaired_rules = rules.clone();
+    let check = watch.check(&changed_rules(), &addresses, 900, query(&rules), Some(|drifting: &[String]| {
+        assert_eq!(drifting, [addresses[1].clone()]);
+        apply_rules_missing_one(&mut repaired_rules, drifting, changed_rules(), "")
+    }));
+    assert_eq!(check.repaired, vec![addresses[1].clone()]);
+    assert!(check.drift.is_empty());
+    assert!(watch.drift().is_empty());
+    assert_eq!(events_of(RULES_DRIFT_EVENT, &addresses[1]), 1);
+    assert_eq!(events_of(RULES_DRIFT_REPAIRED_EVENT, &addresses[1]), 1);
+
+    let check = watch.check(&changed_rules(), &addresses, 910, query(&repaired_rules), None::<Repair>);
+    assert_eq!(check, Default::default());
+}
+
+#[test]
+fn test_unreachable_and_uninitialized_backends_are_not_drift() {
+    let (addresses, mut rules) = backends(3);
+    rules.remove(&addresses[0]);
+    rules.insert(addresses[1].clone(), None);
+
+    let mut watch = RulesDriftWatch::new();
+    let check = watch.check(&COLONY_LIFE_INITIAL_RULES, &addresses, 0, query(&rules), Some(|_: &[String]| -> EventDelivery {
+        panic!("nothing to repair")
+    }));
+    assert!(check.drift.is_empty());
+    assert_eq!(check.unreachable, vec![addresses[0].clone()]);
+}
@@ -0,0 +1,78 @@
+use coordinator::coordinator_storage::CoordinatorStoredInfo;
+use coordinator::init_colony::COLONY_LIFE_INITIAL_RULES;
+use coordinator::colony_start::ColonyStartRequest;
+use shared::colony_model::{SeedingOptions, SeedingPattern};
+use shared::coordinator_api::ColonyRunConfig;
+
+fn run_config(instance_id: &str, seed: u64) -> ColonyRunConfig {
+    ColonyRunConfig {
+        colony_instance_id: Some(instance_id.to_string()),
+        deployment_mode: "localhost".to_string(),
+        colony_width: 500,
+        colony_height: 500,
+        width_in_shards: 2,
+        height_in_shards: 2,
+        shard_width: 250,
+        shard_height: 250,
+        backend_count: 2,
+        assignment_strategy: "round-robin".to_string(),
+        topography_seed: seed,
+        topography_source: "procedural-rivers".to_string(),
+        topography_hash: "00000000deadbeef".to_string(),
+        initial_rules: COLONY_LIFE_INITIAL_RULES,
+        seeding: SeedingOptions { seed: Some(seed), ..SeedingOptions::default() },
+        extra_food_pattern: None,
+    }
+}
+
+#[test]
+fn test_config_hash_ignores_instance_id() {
+    assert_eq!(run_config("a", 7).config_hash(), run_config("b", 7).config_hash());
+    assert_ne!(run_config("a", 7).config_hash(), run_config("a", 8).config_hash());
+}
+
+#[test]
+fn test_run_config_is_recorded_once() {
+    let mut info = CoordinatorStoredInfo::new();
+    info.record_run_config(run_config("a", 7));
+
+    // A later rules change must not leak into the recorded configuration
+    let mut changed_rules = COLONY_LIFE_INITIAL_RULES;
+    changed_rules.mutation_chance = 500;
+    info.update_colony_rules(changed_rules);
+    info.record_run_config(run_config("a", 8));
+
+    let recorded = info.run_config.as_ref().expect("config not recorded");
+    assert_eq!(recorded.topography_seed, 7);
+    assert_eq!(recorded.initial_rules.mutation_chance, COLONY_LIFE_INITIAL_RULES.mutation_chance);
+}
+
+#[test]
+fn test_new_colony_instance_replaces_run_config() {
+    let mut info = CoordinatorStoredInfo::new();
+    info.record_run_config(run_config("a", 7));
+    info.record_run_config(run_config("b", 8));
+
+    let recorded = info.run_config.as_ref().expect("config not recorded");
+    assert_eq!(recorded.colony_instance_id.as_deref(), Some("b"));
+    assert_eq!(recorded.topography_seed, 8);
+}
+
+#[test]
+fn test_colony_start_request_seeding() {
+    // No body keeps the historic uniform seeding
+    assert_eq!(ColonyStartRequest::parse("").expect("Empty body").seeding, SeedingOptions::default());
+
+    let request = ColonyStartRequest::parse(
+        r#"{"seeding":{"density":0.3,"pattern":{"Clustered":{"num_clusters":4,"cluster_radius":12}}}}"#
+    ).expect("Clustered body");
+    assert_eq!(request.seeding.pattern, SeedingPattern::Clustered { num_clusters: 4, cluster_radius: 12 });
+    assert_eq!(request.seeding.density, 0.3);
+    assert_eq!(request.seeding.seed, None);
+
+    assert!(ColonyStartRequest::parse(r#"{"seeding":{"density":1.5}}"#).is_err());
+    assert!(ColonyStartRequest::parse(r#"{"seeding":{"pattern":{"Clustered":{"num_clusters":0,"cluster_radius":5}}}}"#).is_err());
+    // Radii past i32 would wrap negative, and their square overflows
+    assert!(ColonyStartRequest::parse(r#"{"seeding":{"pattern":{"Clustered":{"num_clusters":4,"cluster_radius":3000000000}}}}"#).is_err());
+    assert!(ColonyStartRequest::parse(r#"{"seeding":{"pattern":{"Clustered":{"num_clusters":4000000000,"cluster_radius":5}}}}"#).is_err());
+}
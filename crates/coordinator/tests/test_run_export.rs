@@ -0,0 +1,78 @@
+mod common;
+
+use coordinator::run_export::{plan_export, write_run_export, ExportManifest, MANIFEST_FILE};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use common::temp_dir;
+
+const INSTANCE_ID: &str = "run-export-test";
+
+fn write(dir: &Path, path: &str, contents: &[u8]) {
+    let path = dir.join(path);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, contents).unwrap();
+}
+
+/// An instance directory like the coordinator leaves it, with an event log larger than a chunk
+fn instance_dir() -> PathBuf {
+    let dir = temp_dir("run_export_instance");
+    write(&dir, "run_config.json", br#"{"colony_width":500}"#);
+    write(&dir, "events/0000000_colony_created.json", br#"{"event":"created"}"#);
+    let large: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+    write(&dir, "events/0000120_drought.json", &large);
+    for tick in [100, 200, 300, 400] {
+        write(&dir, &format!("stats_shots/{:07}.json", tick), format!(r#"{{"tick":{}}}"#, tick).as_bytes());
+    }
+    write(&dir, "images_shots/0000200.png", b"old frame");
+    write(&dir, "images_shots/0000400.png", b"latest frame");
+    write(&dir, "images_shots/0000400.missing.json", b"[]");
+    write(&dir, "run_summary.json", br#"{"final_tick":400}"#);
+    dir
+}
+
+/// Extracts the archive with the system tar and returns the directory it was extracted into
+async fn export_and_extract(instance_dir: &Path, stats_snapshots: usize) -> PathBuf {
+    let entries = plan_export(instance_dir, stats_snapshots).expect("plan");
+    let archive = write_run_export(Vec::new(), INSTANCE_ID, &entries).await.expect("export");
+    let out = temp_dir("run_export_out");
+    let archive_path = out.join("export.tar.gz");
+    std::fs::write(&archive_path, archive).unwrap();
+    let status = Command::new("tar").arg("xzf").arg(&archive_path).arg("-C").arg(&out).status().expect("tar");
+    assert!(status.success(), "tar failed to extract the archive");
+    out.join(INSTANCE_ID)
+}
+
+#[tokio::test]
+async fn test_export_contains_the_run_artifacts() {
+    let source = instance_dir();
+    let extracted = export_and_extract(&source, 2).await;
+
+    let manifest: ExportManifest = serde_json::from_slice(&std::fs::read(extracted.join(MANIFEST_FILE)).unwrap()).unwrap();
+    assert_eq!(manifest.colony_instance_id, INSTANCE_ID);
+    assert!(chrono::DateTime::parse_from_rfc3339(&manifest.exported_at).is_ok(), "{}", manifest.exported_at);
+    assert_eq!(manifest.entries, [
+        "run_config.json",
+        "events/0000000_colony_created.json",
+        "events/0000120_drought.json",
+        "stats_shots/0000300.json",
+        "stats_shots/0000400.json",
+        "images_shots/0000400.png",
+        "run_summary.json",
+    ]);
+    for entry in &manifest.entries {
+        assert_eq!(std::fs::read(extracted.join(entry)).unwrap(), std::fs::read(source.join(entry)).unwrap(), "{}", entry);
+    }
+    assert!(!extracted.join("stats_shots/0000100.json").exists());
+    assert!(!extracted.join("images_shots/0000200.png").exists());
+}
+
+#[tokio::test]
+async fn test_export_of_a_run_without_artifacts() {
+    let source = temp_dir("run_export_empty");
+    write(&source, "events/0000000_colony_created.json", b"{}");
+    let extracted = export_and_extract(&source, 20).await;
+
+    let manifest: ExportManifest = serde_json::from_slice(&std::fs::read(extracted.join(MANIFEST_FILE)).unwrap()).unwrap();
+    // No run summary before the run completed
+    assert_eq!(manifest.entries, ["events/0000000_colony_created.json"]);
+}
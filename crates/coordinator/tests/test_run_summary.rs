@@ -0,0 +1,95 @@
+use coordinator::colony_start::ColonyStartRequest;
+use coordinator::coordinator_storage::{ColonyStatus, CoordinatorStoredInfo};
+use coordinator::init_colony::COLONY_LIFE_INITIAL_RULES;
+use coordinator::run_summary::{build_run_summary, count_applied_events, read_run_summary, reached_target, write_run_summary};
+use shared::colony_model::SeedingOptions;
+use shared::coordinator_api::{ColonyEventDescription, ColonyRunConfig, EventDelivery};
+
+fn event(applied_to: Option<Vec<&str>>) -> ColonyEventDescription {
+    ColonyEventDescription {
+        tick: 10,
+        intended_tick: None,
+        event_type: "Test".to_string(),
+        description: "test event".to_string(),
+        delivery: applied_to.map(|applied_to| EventDelivery {
+            applied_to: applied_to.into_iter().map(str::to_string).collect(),
+            ..EventDelivery::default()
+        }),
+        no_effect: false,
+        region: None,
+    }
+}
+
+fn run_config() -> ColonyRunConfig {
+    ColonyRunConfig {
+        colony_instance_id: Some("run".to_string()),
+        deployment_mode: "localhost".to_string(),
+        colony_width: 500,
+        colony_height: 500,
+        width_in_shards: 2,
+        height_in_shards: 2,
+        shard_width: 250,
+        shard_height: 250,
+        backend_count: 2,
+        assignment_strategy: "round-robin".to_string(),
+        topography_seed: 7,
+        topography_source: "procedural-rivers".to_string(),
+        topography_hash: "00000000deadbeef".to_string(),
+        initial_rules: COLONY_LIFE_INITIAL_RULES,
+        seeding: SeedingOptions::default(),
+        extra_food_pattern: None,
+    }
+}
+
+#[test]
+fn test_target_reached_by_the_slowest_shard() {
+    let mut info = CoordinatorStoredInfo::new();
+    assert_eq!(reached_target(&info, Some(1_000)), None, "a run without a target never stops");
+
+    info.target_tick = Some(100);
+    assert_eq!(reached_target(&info, None), None);
+    assert_eq!(reached_target(&info, Some(99)), None);
+    assert_eq!(reached_target(&info, Some(100)), Some(100));
+    assert_eq!(reached_target(&info, Some(140)), Some(100));
+
+    info.status = ColonyStatus::Completed;
+    assert_eq!(reached_target(&info, Some(140)), None, "a completed run is stopped once");
+}
+
+#[test]
+fn test_colony_start_request_target_tick() {
+    assert_eq!(ColonyStartRequest::parse("").expect("Empty body").target_tick, None);
+    assert_eq!(ColonyStartRequest::parse(r#"{"target_tick":100}"#).expect("Target body").target_tick, Some(100));
+    assert!(ColonyStartRequest::parse(r#"{"target_tick":0}"#).is_err());
+}
+
+#[test]
+fn test_run_summary_round_trip() {
+    let mut info = CoordinatorStoredInfo::new();
+    assert!(build_run_summary(&info, 100, 104, 5_000).is_none(), "no summary without an instance id");
+
+    info.colony_instance_id = Some("run".to_string());
+    info.run_started_at_ms = Some(2_500);
+    info.record_run_config(run_config());
+    // Delivered, never broadcast, and delivered to nobody
+    info.add_event(event(Some(vec!["127.0.0.1:8084"])));
+    info.add_event(event(None));
+    info.add_event(event(Some(Vec::new())));
+    assert_eq!(count_applied_events(info.get_events()), 2);
+
+    let mut summary = build_run_summary(&info, 100, 104, 5_000).expect("summary");
+    assert_eq!(summary.colony_instance_id, "run");
+    assert_eq!((summary.target_tick, summary.final_tick), (100, 104));
+    assert_eq!(summary.events_applied, 2);
+    assert_eq!(summary.wall_clock_secs, Some(2.5));
+    assert_eq!(summary.config_hash, Some(run_config().config_hash()));
+    summary.creatures_count = Some(42);
+    summary.metric_averages.insert("health".to_string(), 61.5);
+
+    let dir = std::env::temp_dir().join(format!("run_summary_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    assert_eq!(read_run_summary(&dir), Ok(None));
+    write_run_summary(&dir, &summary).expect("write");
+    assert_eq!(read_run_summary(&dir), Ok(Some(summary)));
+    std::fs::remove_dir_all(&dir).expect("cleanup");
+}
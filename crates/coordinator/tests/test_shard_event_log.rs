@@ -0,0 +1,20 @@
+use coordinator::shard_event_log::backends_that_applied;
+use shared::cluster_topology::{ClusterTopology, HostInfo};
+use shared::coordinator_api::EventDelivery;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+#[test]
+fn test_drill_down_queries_only_backends_that_applied() {
+    let backends: Vec<HostInfo> = (0..3).map(|i| HostInfo::new(format!("backend-{}", i), 8082)).collect();
+    let topology = ClusterTopology { coordinator_host: HostInfo::new("coordinator".to_string(), 8083), backend_hosts: backends.clone(), shard_to_host: HashMap::new() };
+    let delivery = EventDelivery {
+        event_id: Uuid::new_v4(),
+        applied_to: vec!["backend-2:8082".to_string(), "backend-0:8082".to_string(), "gone:8082".to_string()],
+        failed_on: vec!["backend-1:8082".to_string()],
+        effects: Vec::new(),
+    };
+
+    // In topology order, without the backend that failed or one no longer in the topology
+    assert_eq!(backends_that_applied(&delivery, &topology), vec![backends[0].clone(), backends[2].clone()]);
+}
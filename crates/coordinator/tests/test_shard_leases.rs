@@ -0,0 +1,114 @@
+use backend::colony_shard::ColonyShard;
+use backend::shard_lease::{apply_renewal, check_coordinator_lease, BorderEpochs, LeaseState, Suspension};
+use backend::shard_utils::ShardUtils;
+use coordinator::init_colony::COLONY_LIFE_INITIAL_RULES;
+use coordinator::shard_leases::ShardLeaseTable;
+use shared::be_api::{SeedingOptions, Shard, ShardLease, SHARD_LEASE_DURATION};
+use shared::cluster_topology::HostInfo;
+use shared::coordinator_api::LeaseRenewal;
+use shared::utils::new_seeded_random_generator;
+use std::time::{Duration, Instant};
+
+const SHARD: Shard = Shard { x: 0, y: 0, width: 10, height: 10 };
+
+fn backend(port: u16) -> HostInfo {
+    HostInfo::new("127.0.0.1".to_string(), port)
+}
+
+/// A backend's copy of SHARD, holding the lease it was granted
+fn hosted_copy(epoch: u64, now: Instant) -> ColonyShard {
+    let mut colony_shard = ShardUtils::new_colony_shard(&SHARD, &COLONY_LIFE_INITIAL_RULES, &SeedingOptions::default(), &mut new_seeded_random_generator(1));
+    colony_shard.lease = LeaseState::granted(epoch, now);
+    colony_shard
+}
+
+#[test]
+fn test_partitioned_backend_loses_its_shard() {
+    let (a, b) = (backend(8082), backend(8083));
+    let mut table = ShardLeaseTable::default();
+    let start = Instant::now();
+
+    let mut copy_a = hosted_copy(table.assign(SHARD, &a), start);
+    assert_eq!(copy_a.lease.epoch, 1);
+
+    // A is cut off from the coordinator and cannot renew; its lease runs out
+    let lapsed = start + SHARD_LEASE_DURATION;
+    assert!(!copy_a.lease.expire_if_due(lapsed - Duration::from_secs(1), SHARD_LEASE_DURATION));
+    assert!(copy_a.lease.expire_if_due(lapsed, SHARD_LEASE_DURATION));
+    assert!(!copy_a.lease.expire_if_due(lapsed, SHARD_LEASE_DURATION), "suspending is reported once");
+    assert_eq!(copy_a.lease.suspension, Some(Suspension::Expired));
+
+    // The coordinator hands the shard to B under the next epoch
+    let mut copy_b = hosted_copy(table.assign(SHARD, &b), lapsed);
+    assert_eq!(copy_b.lease.epoch, 2);
+    let renewal = table.renew(&b, &copy_b.lease.to_lease(SHARD), Some(&b)).unwrap();
+    assert_eq!(renewal, LeaseRenewal::Renewed(SHARD));
+    apply_renewal(&mut copy_b, &renewal, lapsed);
+
+    // The partition heals: A's renewal is refused and A stays suspended for good
+    let renewal = table.renew(&a, &copy_a.lease.to_lease(SHARD), Some(&b)).unwrap();
+    assert_eq!(renewal, LeaseRenewal::Superseded { shard: SHARD, epoch: 2, owner: b.to_address() });
+    apply_renewal(&mut copy_a, &renewal, lapsed);
+    assert_eq!(copy_a.lease.suspension, Some(Suspension::Superseded { epoch: 2 }));
+    copy_a.lease.renew(lapsed);
+    assert!(copy_a.lease.is_suspended(), "a renewal does not resume a superseded shard");
+
+    // Exactly one copy ticks, and neighbors drop the borders A still sends
+    let active: Vec<&ColonyShard> = [&copy_a, &copy_b].into_iter().filter(|copy| !copy.lease.is_suspended()).collect();
+    assert_eq!(active.len(), 1);
+    assert_eq!(active[0].lease.epoch, 2);
+    let mut border_epochs = BorderEpochs::default();
+    assert!(border_epochs.check(&SHARD, 2).is_ok());
+    let err = border_epochs.check(&SHARD, 1).unwrap_err();
+    assert!(err.contains("lease epoch 1") && err.contains("epoch 2"), "{}", err);
+    assert!(border_epochs.check(&SHARD, 2).is_ok());
+
+    // StartTicking carries the issued leases, which A must not tick under
+    let mut copy_a = hosted_copy(1, lapsed);
+    assert!(check_coordinator_lease(&mut copy_a, &table.leases(), lapsed));
+    assert!(copy_a.lease.is_suspended());
+    assert!(!check_coordinator_lease(&mut copy_b, &table.leases(), lapsed));
+    assert!(!copy_b.lease.is_suspended());
+}
+
+#[test]
+fn test_short_partition_resumes_on_renewal() {
+    let a = backend(8082);
+    let mut table = ShardLeaseTable::default();
+    let start = Instant::now();
+    let mut copy_a = hosted_copy(table.assign(SHARD, &a), start);
+
+    let lapsed = start + SHARD_LEASE_DURATION;
+    assert!(copy_a.lease.expire_if_due(lapsed, SHARD_LEASE_DURATION));
+    // Nobody took the shard over meanwhile, so A gets it back
+    let renewal = table.renew(&a, &copy_a.lease.to_lease(SHARD), Some(&a)).unwrap();
+    apply_renewal(&mut copy_a, &renewal, lapsed);
+    assert!(!copy_a.lease.is_suspended());
+    assert_eq!(table.assign(SHARD, &a), 1, "reassigning to the holder keeps the epoch");
+
+    // A shard created without a lease never expires
+    let mut unleased = LeaseState::default();
+    assert!(!unleased.expire_if_due(lapsed + SHARD_LEASE_DURATION, SHARD_LEASE_DURATION));
+}
+
+#[test]
+fn test_new_coordinator_adopts_backend_leases() {
+    let (a, b) = (backend(8082), backend(8083));
+    let mut table = ShardLeaseTable::default();
+
+    // Only the assigned backend is believed
+    table.adopt(ShardLease { shard: SHARD, epoch: 4 }, &b, Some(&a));
+    assert_eq!(table.epoch(&SHARD), None);
+    table.adopt(ShardLease { shard: SHARD, epoch: 3 }, &a, Some(&a));
+    assert_eq!(table.epoch(&SHARD), Some(3));
+    assert_eq!(table.assign(SHARD, &a), 3);
+
+    // A renewal for a shard this coordinator never issued adopts it from the assigned backend
+    let other = Shard { x: 10, ..SHARD };
+    let mut table = ShardLeaseTable::default();
+    assert_eq!(table.renew(&a, &ShardLease { shard: other, epoch: 2 }, Some(&a)), Some(LeaseRenewal::Renewed(other)));
+    assert_eq!(table.epoch(&other), Some(2));
+    assert_eq!(table.renew(&b, &ShardLease { shard: SHARD, epoch: 1 }, None), None);
+    assert!(matches!(table.renew(&b, &ShardLease { shard: SHARD, epoch: 1 }, Some(&a)),
+                     Some(LeaseRenewal::Superseded { owner, .. }) if owner == a.to_address()));
+}
@@ -0,0 +1,79 @@
+use coordinator::species_summary::{cluster_species, parse_original_color, species_cluster_id, MAX_SPECIES_CLUSTERS, SPECIES_COLOR_RADIUS};
+use shared::colony_model::Color;
+use shared::coordinator_api::SpeciesCluster;
+use std::collections::BTreeMap;
+
+fn counts(colors: &[((u8, u8, u8), u64)]) -> BTreeMap<String, u64> {
+    colors.iter().map(|&((r, g, b), count)| (format!("{}_{}_{}", r, g, b), count)).collect()
+}
+
+fn rgb(cluster: &SpeciesCluster) -> (u8, u8, u8) {
+    (cluster.color.red, cluster.color.green, cluster.color.blue)
+}
+
+#[test]
+fn test_three_known_groups() {
+    // Reds, greens and blues, each a few shades around one base color
+    let palette = counts(&[
+        ((200, 10, 10), 50), ((210, 20, 5), 30), ((190, 0, 25), 20),
+        ((10, 200, 10), 80), ((20, 190, 30), 40),
+        ((10, 10, 200), 10), ((0, 25, 215), 5),
+    ]);
+    let clusters = cluster_species(&palette, MAX_SPECIES_CLUSTERS, SPECIES_COLOR_RADIUS);
+
+    assert_eq!(clusters.len(), 3);
+    // Largest first, each represented by its most common shade
+    assert_eq!(clusters.iter().map(|c| (rgb(c), c.count)).collect::<Vec<_>>(), vec![
+        ((10, 200, 10), 120),
+        ((200, 10, 10), 100),
+        ((10, 10, 200), 15),
+    ]);
+    assert!((clusters[0].share - 120.0 / 235.0).abs() < 1e-9);
+    assert!((clusters.iter().map(|c| c.share).sum::<f64>() - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_cluster_limit_merges_into_nearest() {
+    // Four distinct colors but only room for two clusters
+    let palette = counts(&[
+        ((255, 0, 0), 100),
+        ((0, 0, 255), 90),
+        ((200, 0, 60), 10),
+        ((40, 0, 220), 5),
+    ]);
+    let clusters = cluster_species(&palette, 2, 30.0);
+    assert_eq!(clusters.iter().map(|c| (rgb(c), c.count)).collect::<Vec<_>>(), vec![
+        ((255, 0, 0), 110),
+        ((0, 0, 255), 95),
+    ]);
+}
+
+#[test]
+fn test_cluster_ids_stable_under_drift() {
+    let before = cluster_species(&counts(&[((100, 150, 200), 10)]), MAX_SPECIES_CLUSTERS, SPECIES_COLOR_RADIUS);
+    // The dominant shade drifted a little but stayed in the same id cell
+    let after = cluster_species(&counts(&[((100, 150, 200), 4), ((104, 155, 210), 9)]), MAX_SPECIES_CLUSTERS, SPECIES_COLOR_RADIUS);
+    assert_eq!(after.len(), 1);
+    assert_eq!(before[0].cluster_id, after[0].cluster_id);
+    assert_eq!(species_cluster_id(&Color { red: 100, green: 150, blue: 200 }), "species-6080c0");
+
+    // Separate clusters never share an id
+    let palette = counts(&[((0, 0, 0), 5), ((31, 31, 31), 1), ((40, 40, 40), 3), ((255, 255, 255), 2), ((130, 20, 250), 1)]);
+    let clusters = cluster_species(&palette, MAX_SPECIES_CLUSTERS, SPECIES_COLOR_RADIUS);
+    let mut ids: Vec<&String> = clusters.iter().map(|c| &c.cluster_id).collect();
+    ids.sort();
+    ids.dedup();
+    assert_eq!(ids.len(), clusters.len());
+}
+
+#[test]
+fn test_invalid_and_empty_input() {
+    assert!(cluster_species(&BTreeMap::new(), MAX_SPECIES_CLUSTERS, SPECIES_COLOR_RADIUS).is_empty());
+    let mut palette = counts(&[((1, 2, 3), 4)]);
+    palette.insert("not_a_color".to_string(), 100);
+    palette.insert("1_2_3_4".to_string(), 100);
+    let clusters = cluster_species(&palette, MAX_SPECIES_CLUSTERS, SPECIES_COLOR_RADIUS);
+    assert_eq!(clusters.len(), 1);
+    assert_eq!(clusters[0].count, 4);
+    assert!(parse_original_color("256_0_0").is_none());
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use coordinator::init_colony::COLONY_LIFE_INITIAL_RULES;
+use coordinator::stats_comparison::{align_series, compare_instances, write_report, AlignedPoint, RUN_CONFIG_FILE};
+use shared::colony_model::{ColonyLifeRules, SeedingOptions};
+use shared::coordinator_api::ColonyRunConfig;
+use std::path::Path;
+use common::temp_dir;
+
+fn run_config(instance_id: &str, mutation_chance: u32) -> ColonyRunConfig {
+    ColonyRunConfig {
+        colony_instance_id: Some(instance_id.to_string()),
+        deployment_mode: "localhost".to_string(),
+        colony_width: 500,
+        colony_height: 500,
+        width_in_shards: 2,
+        height_in_shards: 2,
+        shard_width: 250,
+        shard_height: 250,
+        backend_count: 2,
+        assignment_strategy: "round-robin".to_string(),
+        topography_seed: 7,
+        topography_source: "procedural-rivers".to_string(),
+        topography_hash: "00000000deadbeef".to_string(),
+        initial_rules: ColonyLifeRules { mutation_chance, ..COLONY_LIFE_INITIAL_RULES },
+        seeding: SeedingOptions::default(),
+        extra_food_pattern: None,
+    }
+}
+
+/// A stats_shots file as colony_stats writes it, trimmed to a few fields
+fn write_shot(base_dir: &Path, instance_id: &str, tick: u64, creatures_count: u64, health_average: f64) {
+    let dir = base_dir.join(instance_id).join("stats_shots");
+    std::fs::create_dir_all(&dir).unwrap();
+    let json = format!(
+        r#"{{"colony_instance_id":"{}","tick":{},"creatures_count":{},"sanctuary_share":0.0,
+            "histograms":{{"health":{{"distribution":{{}},"average":{},"was_cut":false,"unique_values_count":0}},
+            "original_color":{{"distribution":{{}},"was_cut":false,"unique_values_count":0}}}},
+            "dominant_species":[],"meta":{{"created_at_utc":"2025-01-01T00:00:00Z"}}}}"#,
+        instance_id, tick, creatures_count, health_average);
+    std::fs::write(dir.join(format!("{:07}.json", tick)), json).unwrap();
+}
+
+#[test]
+fn test_align_series_interpolates_inside_and_gaps_outside() {
+    let a = [(0, 100.0), (10, 200.0), (20, 300.0)];
+    let b = [(5, 50.0), (20, 80.0), (30, 90.0)];
+    let point = |tick, a, b| AlignedPoint { tick, a, b };
+    assert_eq!(align_series(&a, &b), vec![
+        point(0, Some(100.0), None),
+        point(5, Some(150.0), Some(50.0)),
+        point(10, Some(200.0), Some(60.0)),
+        point(20, Some(300.0), Some(80.0)),
+        point(30, None, Some(90.0)),
+    ]);
+    assert!(align_series(&[], &[]).is_empty());
+}
+
+#[test]
+fn test_compare_instances_from_snapshot_dirs() {
+    let base_dir = temp_dir("stats_comparison");
+    write_shot(&base_dir, "a", 10, 400, 50.0);
+    write_shot(&base_dir, "a", 20, 600, 55.5);
+    write_shot(&base_dir, "b", 15, 300, 40.0);
+    std::fs::write(base_dir.join("a").join("stats_shots").join("0000030.json"), "{ truncated").unwrap();
+    std::fs::write(base_dir.join("a").join(RUN_CONFIG_FILE), serde_json::to_string(&run_config("a", 100)).unwrap()).unwrap();
+
+    let comparison = compare_instances(&base_dir, "a", "b").expect("comparison failed");
+    assert_eq!(comparison.a.snapshot_count, 2);
+    assert_eq!(comparison.a.final_tick, Some(20));
+    assert_eq!(comparison.a.final_averages.get("health"), Some(&55.5));
+    assert!(!comparison.a.final_averages.contains_key("original_color"));
+    assert_eq!(comparison.a.skipped_files, vec!["0000030.json".to_string()]);
+    assert_eq!(comparison.a.run_config.as_ref().map(|config| config.initial_rules.mutation_chance), Some(100));
+    assert!(comparison.b.run_config.is_none());
+    assert_eq!(comparison.creatures_count, vec![
+        AlignedPoint { tick: 10, a: Some(400.0), b: None },
+        AlignedPoint { tick: 15, a: Some(500.0), b: Some(300.0) },
+        AlignedPoint { tick: 20, a: Some(600.0), b: None },
+    ]);
+
+    let (json_path, html_path) = write_report(&comparison, &base_dir.join("report")).expect("report failed");
+    assert!(json_path.ends_with("compare_a_b.json"));
+    let html = std::fs::read_to_string(html_path).unwrap();
+    assert!(html.contains("<polyline"));
+    assert!(html.contains("initial_rules.mutation_chance"));
+    assert!(html.contains("0000030.json"));
+
+    let _ = std::fs::remove_dir_all(&base_dir);
+}
+
+#[test]
+fn test_compare_unknown_instance_is_an_error() {
+    let base_dir = temp_dir("stats_comparison_missing");
+    write_shot(&base_dir, "a", 10, 400, 50.0);
+    let error = compare_instances(&base_dir, "a", "nope").expect_err("missing instance");
+    assert!(error.contains("nope"), "{}", error);
+    let _ = std::fs::remove_dir_all(&base_dir);
+}
@@ -0,0 +1,48 @@
+use coordinator::tick_monitor::{cluster_tick_range, TickHistory};
+use shared::coordinator_api::TickSample;
+use std::time::Duration;
+
+const MINUTE_MS: u64 = 60_000;
+
+fn sample(timestamp_ms: u64, max_tick: u64) -> TickSample {
+    TickSample { timestamp_ms, min_tick: max_tick.saturating_sub(5), max_tick }
+}
+
+#[test]
+fn test_ring_buffer_wraps_around_at_capacity() {
+    let mut history = TickHistory::new(3, Duration::from_secs(3600));
+    for i in 0..5 {
+        history.record(sample(i * 1000, i * 10));
+    }
+    let ticks: Vec<u64> = history.since(60, 5000).iter().map(|s| s.max_tick).collect();
+    assert_eq!(ticks, vec![20, 30, 40]);
+}
+
+#[test]
+fn test_samples_past_retention_are_dropped() {
+    let mut history = TickHistory::new(100, Duration::from_secs(120));
+    history.record(sample(0, 1));
+    history.record(sample(MINUTE_MS, 2));
+    history.record(sample(3 * MINUTE_MS, 3));
+    let ticks: Vec<u64> = history.since(60, 3 * MINUTE_MS).iter().map(|s| s.max_tick).collect();
+    assert_eq!(ticks, vec![2, 3]);
+}
+
+#[test]
+fn test_minutes_filter() {
+    let mut history = TickHistory::new(100, Duration::from_secs(3600));
+    for minute in 0..=30 {
+        history.record(sample(minute * MINUTE_MS, minute));
+    }
+    let now = 30 * MINUTE_MS;
+    let last_five: Vec<u64> = history.since(5, now).iter().map(|s| s.max_tick).collect();
+    assert_eq!(last_five, vec![25, 26, 27, 28, 29, 30]);
+    assert_eq!(history.since(60, now).len(), 31);
+    assert!(history.since(5, now + 10 * MINUTE_MS).is_empty());
+}
+
+#[test]
+fn test_cluster_tick_range_spans_backends() {
+    assert_eq!(cluster_tick_range([(10, 12), (8, 11), (9, 15)]), Some((8, 15)));
+    assert_eq!(cluster_tick_range([]), None);
+}
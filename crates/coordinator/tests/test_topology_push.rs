@@ -0,0 +1,71 @@
+use backend::shard_routing::border_update_hosts;
+use backend::topology_subscription::apply_topology_push;
+use coordinator::topology_push::TopologySubscribers;
+use shared::be_api::Shard;
+use shared::cluster_topology::{ClusterTopology, HostInfo};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+const SHARD_SIZE: i32 = 10;
+
+fn host(port: u16) -> HostInfo {
+    HostInfo::new("127.0.0.1".to_string(), port)
+}
+
+fn shard(col: i32) -> Shard {
+    Shard { x: col * SHARD_SIZE, y: 0, width: SHARD_SIZE, height: SHARD_SIZE }
+}
+
+/// A 3x1 colony: shard 0 on this backend, shards 1 and 2 on owner_of_1 and 8093
+fn topology(coordinator_port: u16, owner_of_1: u16) -> ClusterTopology {
+    ClusterTopology {
+        coordinator_host: host(coordinator_port),
+        backend_hosts: vec![host(8091), host(8092), host(8093), host(8094)],
+        shard_to_host: [(shard(0), host(8091)), (shard(1), host(owner_of_1)), (shard(2), host(8093))].into_iter().collect(),
+    }
+}
+
+fn hosts(ports: &[u16]) -> HashSet<HostInfo> {
+    ports.iter().map(|port| host(*port)).collect()
+}
+
+#[test]
+fn test_border_updates_go_to_the_new_owner_after_a_pushed_reassignment() {
+    // The backend's copy from InitColonyShard, and its subscription with the coordinator
+    ClusterTopology::replace(topology(8090, 8092)).unwrap();
+    let this_backend = host(8091);
+    let mut subscribers = TopologySubscribers::default();
+    let mut updates = subscribers.subscribe(this_backend.clone(), ClusterTopology::get_instance());
+    assert_eq!(apply_topology_push((*updates.try_recv().unwrap()).clone()), Ok(false));
+    assert_eq!(border_update_hosts(&ClusterTopology::get_instance().unwrap(), &shard(0), &this_backend), hosts(&[8092]));
+
+    // The coordinator moves shard 1 to 8094 and pushes the new table
+    assert_eq!(subscribers.publish(Arc::new(topology(8090, 8094))), vec![this_backend.clone()]);
+    assert_eq!(apply_topology_push((*updates.try_recv().unwrap()).clone()), Ok(true));
+    assert_eq!(border_update_hosts(&ClusterTopology::get_instance().unwrap(), &shard(0), &this_backend), hosts(&[8094]));
+    assert_eq!(border_update_hosts(&ClusterTopology::get_instance().unwrap(), &shard(1), &host(8094)), hosts(&[8091, 8093]));
+
+    // Another coordinator's table is not taken from a push
+    assert!(apply_topology_push(topology(9090, 8092)).is_err());
+    assert_eq!(ClusterTopology::get_instance().unwrap().get_host_for_shard(&shard(1)), Some(&host(8094)));
+}
+
+#[test]
+fn test_dropped_subscribers_resync_with_the_full_table() {
+    let mut subscribers = TopologySubscribers::default();
+    let first = subscribers.subscribe(host(8091), None);
+    let mut second = subscribers.subscribe(host(8092), None);
+    assert_eq!(subscribers.backends(), vec![host(8091), host(8092)]);
+
+    // 8091's connection ended; the next push drops it
+    drop(first);
+    assert_eq!(subscribers.publish(Arc::new(topology(8090, 8092))), vec![host(8092)]);
+    assert!(second.try_recv().is_ok());
+
+    // On reconnect the current table comes first, and the old subscription of the same backend is replaced
+    let current = Arc::new(topology(8090, 8094));
+    let mut resubscribed = subscribers.subscribe(host(8092), Some(current.clone()));
+    assert_eq!(resubscribed.try_recv().unwrap().get_host_for_shard(&shard(1)), Some(&host(8094)));
+    assert!(second.try_recv().is_err());
+    assert_eq!(subscribers.backends(), vec![host(8092)]);
+}
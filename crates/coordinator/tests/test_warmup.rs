@@ -0,0 +1,57 @@
+use coordinator::capture_gate::{CaptureGate, CaptureSkip, CaptureState};
+use coordinator::colony_start::ColonyStartRequest;
+use coordinator::coordinator_storage::{ColonyStatus, CoordinatorStoredInfo};
+use coordinator::warmup::{is_warming_up, reached_warmup};
+
+fn warming_colony(warmup_ticks: u64) -> CoordinatorStoredInfo {
+    let mut info = CoordinatorStoredInfo::new();
+    info.status = ColonyStatus::TopographyInitialized;
+    info.ticking_started = true;
+    info.warmup_ticks = Some(warmup_ticks);
+    info
+}
+
+#[test]
+fn test_warmup_ends_when_the_slowest_shard_reaches_it() {
+    let mut info = warming_colony(3000);
+    // No tick sample yet, then the slowest shard just short of the warm-up
+    assert!(is_warming_up(&info, None));
+    assert!(is_warming_up(&info, Some(2999)));
+    assert_eq!(reached_warmup(&info, Some(2999)), None);
+
+    assert!(!is_warming_up(&info, Some(3000)));
+    assert_eq!(reached_warmup(&info, Some(3000)), Some(3000));
+    assert_eq!(reached_warmup(&info, Some(3050)), Some(3000));
+
+    // Recorded once; a tick sample from a lagging sweep does not bring the warm-up back
+    info.warmup_ended = true;
+    assert_eq!(reached_warmup(&info, Some(3100)), None);
+    assert!(!is_warming_up(&info, Some(2000)));
+}
+
+#[test]
+fn test_colony_without_warmup_never_warms_up() {
+    let info = CoordinatorStoredInfo::new();
+    assert!(!is_warming_up(&info, None));
+    assert_eq!(reached_warmup(&info, Some(100)), None);
+}
+
+#[test]
+fn test_periodic_captures_wait_for_the_end_of_the_warmup() {
+    let mut gate = CaptureGate::new("test");
+    let info = warming_colony(500);
+    let during = CaptureState { warming_up: is_warming_up(&info, Some(499)), ..CaptureState::from_stored_info(&info, false, Some(510)) };
+    assert_eq!(gate.check(&during), Err(CaptureSkip::WarmingUp));
+
+    let after = CaptureState { warming_up: is_warming_up(&info, Some(500)), ..CaptureState::from_stored_info(&info, false, Some(510)) };
+    assert_eq!(gate.check(&after), Ok(()));
+}
+
+#[test]
+fn test_warmup_ticks_in_the_colony_start_body() {
+    assert_eq!(ColonyStartRequest::parse(r#"{"warmup_ticks":2000}"#).unwrap().warmup_ticks, Some(2000));
+    assert_eq!(ColonyStartRequest::parse("").unwrap().warmup_ticks, None);
+    assert!(ColonyStartRequest::parse(r#"{"warmup_ticks":0}"#).is_err());
+    assert!(ColonyStartRequest::parse(r#"{"warmup_ticks":5000,"target_tick":5000}"#).is_err());
+    assert!(ColonyStartRequest::parse(r#"{"warmup_ticks":1000,"target_tick":5000}"#).is_ok());
+}
@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use shared::cluster_topology::{ClusterTopology, HostInfo};
+use shared::coordinator_api::BootstrapResponse;
+use shared::http_port_probe::HttpPortProbe;
+use crate::call_be;
+use crate::OBSERVER_CANNOT_START_COLONY;
+
+const MAX_RETRIES: u64 = 10;
+
+/// Public IP and HTTP port of each backend host in the topology
+pub type BackendHttpInfo = HashMap<HostInfo, (String, u16)>;
+
+/// Everything the GUI needs from the cluster before opening its window
+pub struct ClusterAttachment {
+    pub topology: Arc<ClusterTopology>,
+    pub colony_instance_id: Option<String>,
+    pub coordinator_http_info: Option<(String, u16)>,
+    pub backend_http_info: BackendHttpInfo,
+}
+
+/// The coordinator and backend HTTP addresses of a bootstrap; backends the coordinator
+/// could not find in the cluster registry are left out, like with the registry lookup
+pub fn http_info(bootstrap: &BootstrapResponse) -> (Option<(String, u16)>, BackendHttpInfo) {
+    let coordinator_http_info = bootstrap.coordinator_http.as_deref()
+        .and_then(|address| address.rsplit_once(':'))
+        .and_then(|(ip, port)| Some((ip.to_string(), port.parse().ok()?)));
+    let backend_http_info = bootstrap.backends.iter()
+        .filter_map(|backend| Some((backend.host.clone(), (backend.public_ip.clone()?, backend.http_port?))))
+        .collect();
+    (coordinator_http_info, backend_http_info)
+}
+
+/// The HTTP addresses of the topology's backends after a refresh: the bootstrap's where it has
+/// one, else the one known before, e.g. probed. Backends that left the topology are dropped.
+pub fn merge_backend_http_info(current: &BackendHttpInfo, mut fetched: BackendHttpInfo, topology: &ClusterTopology) -> BackendHttpInfo {
+    topology.get_all_backend_hosts().iter()
+        .filter_map(|host| {
+            let info = fetched.remove(host).or_else(|| current.get(host).cloned())?;
+            Some((host.clone(), info))
+        })
+        .collect()
+}
+
+/// The bootstrap once the colony has a topology that is done initializing
+fn attachment(bootstrap: BootstrapResponse) -> Option<ClusterAttachment> {
+    if bootstrap.colony_status == "Initializing" {
+        return None;
+    }
+    let (coordinator_http_info, backend_http_info) = http_info(&bootstrap);
+    Some(ClusterAttachment {
+        topology: Arc::new(bootstrap.topology?),
+        colony_instance_id: bootstrap.colony_instance_id,
+        coordinator_http_info,
+        backend_http_info,
+    })
+}
+
+/// Probes the HTTP port of each backend the attachment has none for; the backend is then
+/// reached by its topology hostname
+pub fn probe_missing_http_ports<F>(attachment: &mut ClusterAttachment, probe: &mut HttpPortProbe, is_healthy: F)
+where
+    F: Fn(&str, u16) -> bool,
+{
+    for host in attachment.topology.get_all_backend_hosts() {
+        if attachment.backend_http_info.contains_key(host) {
+            continue;
+        }
+        if let Some(port) = probe.probe(host, Instant::now(), &is_healthy) {
+            attachment.backend_http_info.insert(host.clone(), (host.hostname.clone(), port));
+        }
+    }
+}
+
+fn initiate_colony_start(client: &reqwest::blocking::Client, coordinator_ip: &str, http_port: u16) -> Result<(), String> {
+    let idempotency_key = format!("gui-auto-{}", SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs());
+    let colony_start_url = format!("http://{}:{}/colony-start?idempotency_key={}", coordinator_ip, http_port, idempotency_key);
+    let response = call_be::with_auth_blocking(client.post(&colony_start_url))
+        .send()
+        .map_err(|e| format!("Failed to initiate colony-start: {}", e))?;
+    let status = response.status();
+    if !status.is_success() {
+        let error_text = response.text().unwrap_or_else(|_| "Unknown error".to_string());
+        return Err(format!("Failed to initiate colony-start: HTTP {}: {}", status, error_text));
+    }
+    Ok(())
+}
+
+/// Attaches to the colony with GET /api/bootstrap, starting it first unless in observer mode.
+/// Returns None when the coordinator predates the endpoint.
+pub fn attach(coordinator_ip: &str, http_port: u16, observer_mode: bool) -> Result<Option<ClusterAttachment>, String> {
+    let url = format!("http://{}:{}/api/bootstrap", coordinator_ip, http_port);
+    let client = reqwest::blocking::Client::new();
+    let mut retry_count = 0;
+    let mut colony_start_sent = false;
+    loop {
+        let response = call_be::with_auth_blocking(client.get(&url))
+            .send()
+            .map_err(|e| format!("Failed to connect to coordinator at {}: {}", url, e))?;
+        let status = response.status();
+        if status.as_u16() == 404 {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            let error_text = response.text().unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(format!("HTTP error {}: {}", status, error_text));
+        }
+        let bootstrap: BootstrapResponse = response.json()
+            .map_err(|e| format!("Failed to parse bootstrap: {}", e))?;
+
+        if bootstrap.colony_status == "NotInitialized" && !colony_start_sent {
+            if observer_mode {
+                return Err(OBSERVER_CANNOT_START_COLONY.to_string());
+            }
+            eprintln!("Topology not initialized. Automatically initiating colony-start...");
+            initiate_colony_start(&client, coordinator_ip, http_port)?;
+            eprintln!("Colony-start initiated. Waiting for topology to be available...");
+            colony_start_sent = true;
+        } else if let Some(attachment) = attachment(bootstrap) {
+            match &attachment.colony_instance_id {
+                Some(id) => eprintln!("GUI: Attached to colony instance {}", id),
+                None => eprintln!("GUI: Warning - colony instance ID is None in bootstrap response"),
+            }
+            return Ok(Some(attachment));
+        } else {
+            retry_count += 1;
+            if retry_count >= MAX_RETRIES {
+                return Err("Topology still initializing after maximum retries. Please wait and try again.".to_string());
+            }
+        }
+        std::thread::sleep(Duration::from_millis(500 * (retry_count + 1)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::coordinator_api::BootstrapBackend;
+
+    fn backend(host: &str, public_ip: Option<&str>, http_port: Option<u16>) -> BootstrapBackend {
+        BootstrapBackend {
+            host: HostInfo::new(host.to_string(), 8084),
+            public_ip: public_ip.map(str::to_string),
+            private_ip: None,
+            rpc_port: 8084,
+            http_port,
+            healthy: true,
+            error: None,
+        }
+    }
+
+    fn bootstrap(colony_status: &str, topology: Option<ClusterTopology>) -> BootstrapResponse {
+        BootstrapResponse {
+            coordinator_version: "test".to_string(),
+            coordinator_http: Some("52.0.0.1:8083".to_string()),
+            colony_status: colony_status.to_string(),
+            colony_instance_id: Some("run-a".to_string()),
+            summary: None,
+            topology,
+            backends: vec![backend("52.0.0.2", Some("52.0.0.2"), Some(8085)), backend("10.0.0.3", None, None)],
+        }
+    }
+
+    fn topology() -> ClusterTopology {
+        ClusterTopology {
+            coordinator_host: HostInfo::new("10.0.0.1".to_string(), 8082),
+            backend_hosts: Vec::new(),
+            shard_to_host: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_http_info_skips_unregistered_backends() {
+        let (coordinator, backends) = http_info(&bootstrap("TopographyInitialized", None));
+        assert_eq!(coordinator, Some(("52.0.0.1".to_string(), 8083)));
+        assert_eq!(backends.len(), 1);
+        assert_eq!(backends[&HostInfo::new("52.0.0.2".to_string(), 8084)], ("52.0.0.2".to_string(), 8085));
+    }
+
+    #[test]
+    fn test_attachment_waits_for_initialized_topology() {
+        assert!(attachment(bootstrap("NotInitialized", None)).is_none());
+        assert!(attachment(bootstrap("Initializing", Some(topology()))).is_none());
+        let attached = attachment(bootstrap("TopographyInitialized", Some(topology()))).unwrap();
+        assert_eq!(attached.colony_instance_id.as_deref(), Some("run-a"));
+        assert_eq!(attached.backend_http_info.len(), 1);
+    }
+
+    #[test]
+    fn test_probe_missing_http_ports_fills_only_unregistered_backends() {
+        let registered = HostInfo::new("52.0.0.2".to_string(), 8084);
+        let unregistered = HostInfo::new("10.0.0.3".to_string(), 8084);
+        let mut topology = topology();
+        topology.backend_hosts = vec![registered.clone(), unregistered.clone()];
+        let mut attached = attachment(bootstrap("TopographyInitialized", Some(topology))).unwrap();
+        let mut probe = HttpPortProbe::new(true, Duration::from_secs(180));
+
+        probe_missing_http_ports(&mut attached, &mut probe, |hostname, port| hostname == "10.0.0.3" && port == 8085);
+
+        assert_eq!(attached.backend_http_info[&registered], ("52.0.0.2".to_string(), 8085));
+        assert_eq!(attached.backend_http_info[&unregistered], ("10.0.0.3".to_string(), 8085));
+    }
+
+    #[test]
+    fn test_refreshed_http_info_follows_the_topology() {
+        let kept = HostInfo::new("52.0.0.2".to_string(), 8084);
+        let probed = HostInfo::new("10.0.0.3".to_string(), 8084);
+        let added = HostInfo::new("52.0.0.4".to_string(), 8084);
+        let gone = HostInfo::new("52.0.0.5".to_string(), 8084);
+        let current: BackendHttpInfo = [
+            (kept.clone(), ("52.0.0.2".to_string(), 8085)),
+            (probed.clone(), ("10.0.0.3".to_string(), 8085)),
+            (gone.clone(), ("52.0.0.5".to_string(), 8085)),
+        ].into_iter().collect();
+        let fetched: BackendHttpInfo = [
+            (kept.clone(), ("52.0.0.2".to_string(), 9085)),
+            (added.clone(), ("52.0.0.4".to_string(), 8085)),
+        ].into_iter().collect();
+        let mut topology = topology();
+        topology.backend_hosts = vec![kept.clone(), probed.clone(), added.clone()];
+
+        let merged = merge_backend_http_info(&current, fetched, &topology);
+
+        assert_eq!(merged.len(), 3);
+        assert_eq!(merged[&kept], ("52.0.0.2".to_string(), 9085));
+        assert_eq!(merged[&probed], ("10.0.0.3".to_string(), 8085));
+        assert_eq!(merged[&added], ("52.0.0.4".to_string(), 8085));
+    }
+}
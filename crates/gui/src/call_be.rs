@@ -1,16 +1,47 @@
 #![allow(deprecated)]
 use eframe::egui;
 use egui_extras::RetainedImage;
-use shared::be_api::{ShardLayer, Shard, Color, ColonyLifeRules};
-use shared::coordinator_api::ColonyEventDescription;
+use shared::be_api::{ShardLayer, Shard, Color, ColonyLifeRules, ShardRedirect};
+use shared::coordinator_api::{BackendsResponse, BiomesResponse, CaptureListResponse, ColonyConfigResponse, ColonyEventDescription, ColonyVerificationReport, ColonyStatsResponse, ShardListResponse, TickHistoryResponse, TickerStateResponse};
 use shared::cluster_topology::{ClusterTopology, HostInfo};
 use std::time::{Duration, Instant};
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use crate::latency_tracker::{LatencyTracker, OperationKey, OperationType};
-use shared::{log_error};
+use crate::stale_frames::STALE_FRAMES;
+use crate::shard_redirects::ShardRedirects;
+use shared::{log, log_error};
 use futures::future::join_all;
+use shared::api_auth::bearer_header_value;
+use shared::live_feed::{FeedClient, FeedTopic};
+use shared::shard_render::ImageBackground;
+use shared::layer_stats::{decode_layer_with_stats, ShardLayerData, LAYER_FORMAT_VERSION_WITH_STATS};
 
-pub fn get_all_shard_retained_images(config: &crate::ShardConfig, topology: &ClusterTopology, latency_tracker: &Arc<LatencyTracker>, backend_http_info: &std::collections::HashMap<HostInfo, (String, u16)>) -> Vec<Option<RetainedImage>> {
+static API_TOKEN: OnceLock<Option<String>> = OnceLock::new();
+
+/// Token sent as a bearer header on every coordinator/backend request; set once at startup
+pub fn set_api_token(token: Option<String>) {
+    let _ = API_TOKEN.set(token);
+}
+
+fn api_token() -> Option<&'static str> {
+    API_TOKEN.get().and_then(|t| t.as_deref())
+}
+
+fn with_auth(request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+    match api_token() {
+        Some(token) => request.header(reqwest::header::AUTHORIZATION, bearer_header_value(token)),
+        None => request,
+    }
+}
+
+pub fn with_auth_blocking(request: reqwest::blocking::RequestBuilder) -> reqwest::blocking::RequestBuilder {
+    match api_token() {
+        Some(token) => request.header(reqwest::header::AUTHORIZATION, bearer_header_value(token)),
+        None => request,
+    }
+}
+
+pub fn get_all_shard_retained_images(config: &crate::ShardConfig, topology: &ClusterTopology, latency_tracker: &Arc<LatencyTracker>, backend_http_info: &std::collections::HashMap<HostInfo, (String, u16)>, redirects: &Arc<ShardRedirects>, background: ImageBackground) -> Vec<Option<RetainedImage>> {
     let shards: Vec<Shard> = (0..config.total_shards())
         .map(|i| config.get_shard(i))
         .collect();
@@ -18,8 +49,8 @@ pub fn get_all_shard_retained_images(config: &crate::ShardConfig, topology: &Clu
     // Pre-extract host info for each shard to avoid lifetime issues
     let shard_hosts: Vec<(Shard, HostInfo)> = shards.iter()
         .map(|&shard| {
-            let host_info = topology.get_host_for_shard(&shard).cloned().expect("Shard not found in cluster topology");
-            (shard, host_info)
+            let host_info = topology.get_host_for_shard(&shard).expect("Shard not found in cluster topology");
+            (shard, redirects.host_for(&shard, host_info))
         })
         .collect();
     
@@ -31,8 +62,9 @@ pub fn get_all_shard_retained_images(config: &crate::ShardConfig, topology: &Clu
             let host_info = host_info.clone();
             let latency_tracker = latency_tracker.clone();
             let backend_http_info = backend_http_info.clone();
+            let redirects = Arc::clone(redirects);
             tokio::task::spawn(async move {
-                get_shard_retained_image_with_host_async(shard, host_info, &latency_tracker, &backend_http_info).await
+                get_shard_retained_image_with_host_async(shard, host_info, &latency_tracker, &backend_http_info, &redirects, background).await
             })
         }).collect();
         
@@ -45,18 +77,15 @@ pub fn get_all_shard_retained_images(config: &crate::ShardConfig, topology: &Clu
     result
 }
 
-async fn get_shard_retained_image_with_host_async(shard: Shard, host_info: HostInfo, latency_tracker: &LatencyTracker, backend_http_info: &std::collections::HashMap<HostInfo, (String, u16)>) -> Option<RetainedImage> {
-    let (public_ip, http_port) = backend_http_info.get(&host_info)?.clone();
+async fn get_shard_retained_image_with_host_async(shard: Shard, host_info: HostInfo, latency_tracker: &LatencyTracker, backend_http_info: &std::collections::HashMap<HostInfo, (String, u16)>, redirects: &ShardRedirects, background: ImageBackground) -> Option<RetainedImage> {
     let shard_id = shard.to_id();
-
-    let url = format!("http://{}:{}/api/shard/{}/image", public_ip, http_port, shard_id);
     let client = reqwest::Client::builder()
         .timeout(Duration::from_millis(1500))
         .build()
         .ok()?;
 
     let start = Instant::now();
-    let response_result = client.get(&url).send().await;
+    let (host_info, url, response_result) = send_shard_request(&client, &shard, host_info, backend_http_info, redirects, &background.shard_image_path(&shard_id)).await?;
     let latency = start.elapsed();
 
     let key = OperationKey::new(OperationType::GetShardImage, host_info.clone());
@@ -75,6 +104,7 @@ async fn get_shard_retained_image_with_host_async(shard: Shard, host_info: HostI
     };
     
     if response.status().is_success() {
+        STALE_FRAMES.record_response_headers(response.headers());
         let content_length = response.content_length().unwrap_or(0);
         let content_encoding = response
             .headers()
@@ -150,6 +180,34 @@ async fn get_shard_retained_image_with_host_async(shard: Shard, host_info: HostI
     }
 }
 
+/// GETs a shard path from its backend. A 421 names the backend that hosts the shard now:
+/// remember it for the next frames and retry there once. Returns the host and URL that answered.
+async fn send_shard_request(client: &reqwest::Client, shard: &Shard, host_info: HostInfo, backend_http_info: &std::collections::HashMap<HostInfo, (String, u16)>, redirects: &ShardRedirects, path: &str) -> Option<(HostInfo, String, reqwest::Result<reqwest::Response>)> {
+    let (public_ip, http_port) = backend_http_info.get(&host_info)?.clone();
+    let url = format!("http://{}:{}{}", public_ip, http_port, path);
+    let response = with_auth(client.get(&url)).send().await;
+
+    let misdirected = matches!(&response, Ok(r) if r.status() == reqwest::StatusCode::MISDIRECTED_REQUEST);
+    if !misdirected {
+        return Some((host_info, url, response));
+    }
+    let redirect = match response.ok()?.json::<ShardRedirect>().await {
+        Ok(redirect) => redirect,
+        Err(e) => {
+            log_error!("GUI HTTP error: unreadable shard redirect from {}:{}, url={}, error={}", host_info.hostname, host_info.port, url, e);
+            return None;
+        }
+    };
+    log!("Shard {} is hosted by {}:{}, not {}:{}; retrying there",
+         redirect.shard_id, redirect.host.hostname, redirect.host.port, host_info.hostname, host_info.port);
+    redirects.record(*shard, redirect.host.clone());
+
+    let (public_ip, http_port) = backend_http_info.get(&redirect.host)?.clone();
+    let url = format!("http://{}:{}{}", public_ip, http_port, path);
+    let response = with_auth(client.get(&url)).send().await;
+    Some((redirect.host, url, response))
+}
+
 fn color_vec_to_image(colors: &[Color], width: usize, height: usize) -> egui::ColorImage {
     let mut img = egui::ColorImage::new([width, height], egui::Color32::BLACK);
     for (i, color) in colors.iter().enumerate() {
@@ -162,7 +220,12 @@ fn color_vec_to_image(colors: &[Color], width: usize, height: usize) -> egui::Co
     img
 }
 
-pub fn get_all_shard_layer_data(layer: ShardLayer, config: &crate::ShardConfig, topology: &ClusterTopology, latency_tracker: &Arc<LatencyTracker>, backend_http_info: &std::collections::HashMap<HostInfo, (String, u16)>) -> Vec<Option<Vec<i32>>> {
+pub fn get_all_shard_layer_data(layer: ShardLayer, config: &crate::ShardConfig, topology: &ClusterTopology, latency_tracker: &Arc<LatencyTracker>, backend_http_info: &std::collections::HashMap<HostInfo, (String, u16)>, redirects: &Arc<ShardRedirects>) -> Vec<Option<ShardLayerData>> {
+    get_shard_layer_data(layer, config, topology, latency_tracker, backend_http_info, redirects, &vec![true; config.total_shards()])
+}
+
+/// Fetches the shards flagged in fetch; the others come back as None without a request
+pub fn get_shard_layer_data(layer: ShardLayer, config: &crate::ShardConfig, topology: &ClusterTopology, latency_tracker: &Arc<LatencyTracker>, backend_http_info: &std::collections::HashMap<HostInfo, (String, u16)>, redirects: &Arc<ShardRedirects>, fetch: &[bool]) -> Vec<Option<ShardLayerData>> {
     let shards: Vec<Shard> = (0..config.total_shards())
         .map(|i| config.get_shard(i))
         .collect();
@@ -170,22 +233,27 @@ pub fn get_all_shard_layer_data(layer: ShardLayer, config: &crate::ShardConfig,
     // Pre-extract host info for each shard to avoid lifetime issues
     let shard_hosts: Vec<(Shard, HostInfo)> = shards.iter()
         .map(|&shard| {
-            let host_info = topology.get_host_for_shard(&shard).cloned().expect("Shard not found in cluster topology");
-            (shard, host_info)
+            let host_info = topology.get_host_for_shard(&shard).expect("Shard not found in cluster topology");
+            (shard, redirects.host_for(&shard, host_info))
         })
         .collect();
     
     // Create tokio runtime for parallel async fetching
     let rt = tokio::runtime::Runtime::new().unwrap();
     rt.block_on(async {
-        let futures: Vec<_> = shard_hosts.iter().map(|(shard, host_info)| {
+        let futures: Vec<_> = shard_hosts.iter().enumerate().map(|(idx, (shard, host_info))| {
             let shard = *shard;
             let layer = layer;
             let host_info = host_info.clone();
             let latency_tracker = latency_tracker.clone();
             let backend_http_info = backend_http_info.clone();
+            let redirects = Arc::clone(redirects);
+            let skipped = !fetch.get(idx).copied().unwrap_or(true);
             tokio::task::spawn(async move {
-                get_shard_layer_data_with_host_async(shard, layer, host_info, &latency_tracker, &backend_http_info).await
+                if skipped {
+                    return None;
+                }
+                get_shard_layer_data_with_host_async(shard, layer, host_info, &latency_tracker, &backend_http_info, &redirects).await
             })
         }).collect();
         
@@ -207,23 +275,23 @@ fn shard_layer_to_kebab_case(layer: ShardLayer) -> &'static str {
         ShardLayer::Food => "food",
         ShardLayer::Health => "health",
         ShardLayer::Age => "age",
+        ShardLayer::Sanctuary => "sanctuary",
     }
 }
 
 
-async fn get_shard_layer_data_with_host_async(shard: Shard, layer: ShardLayer, host_info: HostInfo, latency_tracker: &LatencyTracker, backend_http_info: &std::collections::HashMap<HostInfo, (String, u16)>) -> Option<Vec<i32>> {
-    let (public_ip, http_port) = backend_http_info.get(&host_info)?.clone();
+async fn get_shard_layer_data_with_host_async(shard: Shard, layer: ShardLayer, host_info: HostInfo, latency_tracker: &LatencyTracker, backend_http_info: &std::collections::HashMap<HostInfo, (String, u16)>, redirects: &ShardRedirects) -> Option<ShardLayerData> {
     let shard_id = shard.to_id();
     let layer_name = shard_layer_to_kebab_case(layer);
 
-    let url = format!("http://{}:{}/api/shard/{}/layer/{}", public_ip, http_port, shard_id, layer_name);
+    let path = format!("/api/shard/{}/layer/{}?version={}", shard_id, layer_name, LAYER_FORMAT_VERSION_WITH_STATS);
     let client = reqwest::Client::builder()
         .timeout(Duration::from_millis(1500))
         .build()
         .ok()?;
 
     let start = Instant::now();
-    let response = client.get(&url).send().await;
+    let (host_info, url, response) = send_shard_request(&client, &shard, host_info, backend_http_info, redirects, &path).await?;
     let latency = start.elapsed();
     let latency_ms = latency.as_millis() as f64;
 
@@ -254,43 +322,17 @@ async fn get_shard_layer_data_with_host_async(shard: Shard, layer: ShardLayer, h
     };
     
     if response.status().is_success() {
+        STALE_FRAMES.record_response_headers(response.headers());
         let binary_data = response.bytes().await.ok()?;
         
-        // Parse binary format: length (u32 LE) + i32 values (LE)
-        if binary_data.len() < 4 {
-            return None;
-        }
-        
-        let count = u32::from_le_bytes([
-            binary_data[0],
-            binary_data[1],
-            binary_data[2],
-            binary_data[3],
-        ]) as usize;
-        
-        if binary_data.len() != 4 + count * 4 {
-            return None;
-        }
-        
-        let mut data = Vec::with_capacity(count);
-        for i in 0..count {
-            let offset = 4 + i * 4;
-            let value = i32::from_le_bytes([
-                binary_data[offset],
-                binary_data[offset + 1],
-                binary_data[offset + 2],
-                binary_data[offset + 3],
-            ]);
-            data.push(value);
-        }
-        
-        Some(data)
+        // Version 2 format: count + min/max/mean/histogram header + i32 values (LE)
+        decode_layer_with_stats(&binary_data)
     } else {
         None
     }
 }
 
-pub fn get_all_shard_color_data(config: &crate::ShardConfig, topology: &ClusterTopology, latency_tracker: &Arc<LatencyTracker>, backend_http_info: &std::collections::HashMap<HostInfo, (String, u16)>) -> Vec<Option<Vec<Color>>> {
+pub fn get_all_shard_color_data(config: &crate::ShardConfig, topology: &ClusterTopology, latency_tracker: &Arc<LatencyTracker>, backend_http_info: &std::collections::HashMap<HostInfo, (String, u16)>, redirects: &Arc<ShardRedirects>, background: ImageBackground) -> Vec<Option<Vec<Color>>> {
     let shards: Vec<Shard> = (0..config.total_shards())
         .map(|i| config.get_shard(i))
         .collect();
@@ -298,8 +340,8 @@ pub fn get_all_shard_color_data(config: &crate::ShardConfig, topology: &ClusterT
     // Pre-extract host info for each shard to avoid lifetime issues
     let shard_hosts: Vec<(Shard, HostInfo)> = shards.iter()
         .map(|&shard| {
-            let host_info = topology.get_host_for_shard(&shard).cloned().expect("Shard not found in cluster topology");
-            (shard, host_info)
+            let host_info = topology.get_host_for_shard(&shard).expect("Shard not found in cluster topology");
+            (shard, redirects.host_for(&shard, host_info))
         })
         .collect();
     
@@ -311,8 +353,9 @@ pub fn get_all_shard_color_data(config: &crate::ShardConfig, topology: &ClusterT
             let host_info = host_info.clone();
             let latency_tracker = latency_tracker.clone();
             let backend_http_info = backend_http_info.clone();
+            let redirects = Arc::clone(redirects);
             tokio::task::spawn(async move {
-                get_shard_color_data_with_host_async(shard, host_info, &latency_tracker, &backend_http_info).await
+                get_shard_color_data_with_host_async(shard, host_info, &latency_tracker, &backend_http_info, &redirects, background).await
             })
         }).collect();
         
@@ -326,18 +369,15 @@ pub fn get_all_shard_color_data(config: &crate::ShardConfig, topology: &ClusterT
 }
 
 
-async fn get_shard_color_data_with_host_async(shard: Shard, host_info: HostInfo, latency_tracker: &LatencyTracker, backend_http_info: &std::collections::HashMap<HostInfo, (String, u16)>) -> Option<Vec<Color>> {
-    let (public_ip, http_port) = backend_http_info.get(&host_info)?.clone();
+async fn get_shard_color_data_with_host_async(shard: Shard, host_info: HostInfo, latency_tracker: &LatencyTracker, backend_http_info: &std::collections::HashMap<HostInfo, (String, u16)>, redirects: &ShardRedirects, background: ImageBackground) -> Option<Vec<Color>> {
     let shard_id = shard.to_id();
-
-    let url = format!("http://{}:{}/api/shard/{}/image", public_ip, http_port, shard_id);
     let client = reqwest::Client::builder()
         .timeout(Duration::from_millis(1500))
         .build()
         .ok()?;
 
     let start = Instant::now();
-    let response_result = client.get(&url).send().await;
+    let (host_info, url, response_result) = send_shard_request(&client, &shard, host_info, backend_http_info, redirects, &background.shard_image_path(&shard_id)).await?;
     let latency = start.elapsed();
     let latency_ms = latency.as_millis() as f64;
 
@@ -376,6 +416,7 @@ async fn get_shard_color_data_with_host_async(shard: Shard, host_info: HostInfo,
     };
     
     if response.status().is_success() {
+        STALE_FRAMES.record_response_headers(response.headers());
         let content_length = response.content_length().unwrap_or(0);
         let content_encoding = response
             .headers()
@@ -456,7 +497,7 @@ pub fn get_colony_info(topology: &ClusterTopology, backend_http_info: &std::coll
         .build()
         .ok()?;
     
-    let response = client.get(&url).send().ok()?;
+    let response = with_auth_blocking(client.get(&url)).send().ok()?;
     
     if response.status().is_success() {
         #[derive(serde::Deserialize)]
@@ -487,7 +528,7 @@ pub fn get_colony_events(limit: usize, coordinator_http_info: Option<&(String, u
         .build()
         .ok()?;
     
-    let response = client.get(&url).send().ok()?;
+    let response = with_auth_blocking(client.get(&url)).send().ok()?;
     
     if response.status().is_success() {
         #[derive(serde::Deserialize)]
@@ -499,3 +540,377 @@ pub fn get_colony_events(limit: usize, coordinator_http_info: Option<&(String, u
         None
     }
 }
+
+/// Recent events with a region, newest first, for the event markers
+pub fn get_region_events(coordinator_http_info: Option<&(String, u16)>) -> Option<Vec<ColonyEventDescription>> {
+    let (coordinator_host, http_port) = coordinator_http_info?.clone();
+    let url = format!("http://{}:{}/api/region-events", coordinator_host, http_port);
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_millis(1500))
+        .build()
+        .ok()?;
+
+    let response = with_auth_blocking(client.get(&url)).send().ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    #[derive(serde::Deserialize)]
+    struct Response {
+        events: Vec<ColonyEventDescription>,
+    }
+    response.json::<Response>().ok().map(|r| r.events)
+}
+
+/// Opens the coordinator's live feed; None when the coordinator is unknown or refuses the upgrade
+pub fn connect_feed(topics: &[FeedTopic], read_timeout: Duration, coordinator_http_info: Option<&(String, u16)>) -> Option<FeedClient> {
+    let (coordinator_host, http_port) = coordinator_http_info?.clone();
+    let address = format!("{}:{}", coordinator_host, http_port);
+    FeedClient::connect(&address, api_token(), topics, read_timeout).ok()
+}
+
+/// Histograms of every metric the coordinator merges by default, colony-wide or of the cells in region
+pub fn get_colony_stats(region: Option<Shard>, coordinator_http_info: Option<&(String, u16)>) -> Option<ColonyStatsResponse> {
+    let (coordinator_host, http_port) = coordinator_http_info?.clone();
+
+    // A GET, so observer tokens get region stats too
+    let region_param = region.map(|region| format!("?region={}", region.to_id())).unwrap_or_default();
+    let url = format!("http://{}:{}/api/colony-stats{}", coordinator_host, http_port, region_param);
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_millis(3000))
+        .build()
+        .ok()?;
+
+    let response = with_auth_blocking(client.get(&url)).send().ok()?;
+
+    if response.status().is_success() {
+        response.json::<ColonyStatsResponse>().ok()
+    } else {
+        None
+    }
+}
+
+/// Run configuration recorded by the coordinator at colony start; None until it is recorded
+pub fn get_colony_config(coordinator_http_info: Option<&(String, u16)>) -> Option<ColonyConfigResponse> {
+    let (coordinator_host, http_port) = coordinator_http_info?.clone();
+    
+    let url = format!("http://{}:{}/api/colony-config", coordinator_host, http_port);
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_millis(1500))
+        .build()
+        .ok()?;
+    
+    let response = with_auth_blocking(client.get(&url)).send().ok()?;
+    
+    if response.status().is_success() {
+        response.json::<ColonyConfigResponse>().ok()
+    } else {
+        None
+    }
+}
+
+pub fn get_ticker_state(coordinator_http_info: Option<&(String, u16)>) -> Option<TickerStateResponse> {
+    let (coordinator_host, http_port) = coordinator_http_info?.clone();
+    
+    let url = format!("http://{}:{}/api/ticker-state", coordinator_host, http_port);
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_millis(1500))
+        .build()
+        .ok()?;
+    
+    let response = with_auth_blocking(client.get(&url)).send().ok()?;
+    
+    if response.status().is_success() {
+        response.json::<TickerStateResponse>().ok()
+    } else {
+        None
+    }
+}
+
+pub fn get_tick_history(minutes: u64, coordinator_http_info: Option<&(String, u16)>) -> Option<TickHistoryResponse> {
+    let (coordinator_host, http_port) = coordinator_http_info?.clone();
+
+    let url = format!("http://{}:{}/api/tick-history?minutes={}", coordinator_host, http_port, minutes);
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_millis(1500))
+        .build()
+        .ok()?;
+
+    let response = with_auth_blocking(client.get(&url)).send().ok()?;
+
+    if response.status().is_success() {
+        response.json::<TickHistoryResponse>().ok()
+    } else {
+        None
+    }
+}
+
+pub fn get_shard_list(coordinator_http_info: Option<&(String, u16)>) -> Option<ShardListResponse> {
+    let (coordinator_host, http_port) = coordinator_http_info?.clone();
+
+    let url = format!("http://{}:{}/api/shards", coordinator_host, http_port);
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_millis(1500))
+        .build()
+        .ok()?;
+
+    let response = with_auth_blocking(client.get(&url)).send().ok()?;
+
+    if response.status().is_success() {
+        response.json::<ShardListResponse>().ok()
+    } else {
+        None
+    }
+}
+
+pub fn get_biomes(coordinator_http_info: Option<&(String, u16)>) -> Option<BiomesResponse> {
+    let (coordinator_host, http_port) = coordinator_http_info?.clone();
+
+    let url = format!("http://{}:{}/api/biomes", coordinator_host, http_port);
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_millis(1500))
+        .build()
+        .ok()?;
+
+    let response = with_auth_blocking(client.get(&url)).send().ok()?;
+
+    if response.status().is_success() {
+        response.json::<BiomesResponse>().ok()
+    } else {
+        None
+    }
+}
+
+pub fn get_backends(coordinator_http_info: Option<&(String, u16)>) -> Option<BackendsResponse> {
+    let (coordinator_host, http_port) = coordinator_http_info?.clone();
+
+    let url = format!("http://{}:{}/api/backends", coordinator_host, http_port);
+    // The coordinator waits up to 2s on each unreachable backend
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(4))
+        .build()
+        .ok()?;
+
+    let response = with_auth_blocking(client.get(&url)).send().ok()?;
+
+    if response.status().is_success() {
+        response.json::<BackendsResponse>().ok()
+    } else {
+        None
+    }
+}
+
+/// GETs a coordinator path; errors name the HTTP status and body
+fn get_from_coordinator(path: &str, timeout: Duration, coordinator_http_info: Option<&(String, u16)>) -> Result<reqwest::blocking::Response, String> {
+    let (coordinator_host, http_port) = coordinator_http_info
+        .ok_or_else(|| "Coordinator HTTP address unknown".to_string())?
+        .clone();
+
+    let url = format!("http://{}:{}{}", coordinator_host, http_port, path);
+    let client = reqwest::blocking::Client::builder()
+        .timeout(timeout)
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+
+    let response = with_auth_blocking(client.get(&url))
+        .send()
+        .map_err(|e| format!("Request failed: {}", e))?;
+
+    if response.status().is_success() {
+        Ok(response)
+    } else {
+        let status = response.status();
+        let body = response.text().unwrap_or_default();
+        Err(format!("HTTP {}: {}", status.as_u16(), body))
+    }
+}
+
+/// The periodic capture frames of the current colony instance, oldest first
+pub fn get_capture_list(coordinator_http_info: Option<&(String, u16)>) -> Result<CaptureListResponse, String> {
+    get_from_coordinator("/api/captures", Duration::from_millis(1500), coordinator_http_info)?
+        .json::<CaptureListResponse>()
+        .map_err(|e| format!("Invalid response: {}", e))
+}
+
+/// PNG of one capture frame; url is the frame's path from the capture list
+pub fn get_capture_frame(url: &str, coordinator_http_info: Option<&(String, u16)>) -> Result<Vec<u8>, String> {
+    let response = get_from_coordinator(url, Duration::from_secs(10), coordinator_http_info)?;
+    response.bytes().map(|bytes| bytes.to_vec()).map_err(|e| format!("Failed to read capture frame: {}", e))
+}
+
+/// POSTs a pause/resume/step call to the coordinator (admin token required)
+fn post_ticker_action(path_and_query: &str, timeout: Duration, coordinator_http_info: Option<&(String, u16)>) -> Result<TickerStateResponse, String> {
+    let (coordinator_host, http_port) = coordinator_http_info
+        .ok_or_else(|| "Coordinator HTTP address unknown".to_string())?
+        .clone();
+
+    let url = format!("http://{}:{}{}", coordinator_host, http_port, path_and_query);
+    let client = reqwest::blocking::Client::builder()
+        .timeout(timeout)
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+
+    let response = with_auth_blocking(client.post(&url))
+        .send()
+        .map_err(|e| format!("Request failed: {}", e))?;
+
+    if response.status().is_success() {
+        response.json::<TickerStateResponse>().map_err(|e| format!("Invalid response: {}", e))
+    } else {
+        let status = response.status();
+        let body = response.text().unwrap_or_default();
+        Err(format!("HTTP {}: {}", status.as_u16(), body))
+    }
+}
+
+pub fn set_colony_paused(paused: bool, coordinator_http_info: Option<&(String, u16)>) -> Result<TickerStateResponse, String> {
+    let path = if paused { "/api/pause" } else { "/api/resume" };
+    post_ticker_action(path, Duration::from_millis(5000), coordinator_http_info)
+}
+
+/// Each stepped tick is a round trip to every backend, so allow more time than other calls
+pub fn step_colony(count: u32, coordinator_http_info: Option<&(String, u16)>) -> Result<TickerStateResponse, String> {
+    post_ticker_action(&format!("/api/step?count={}", count), Duration::from_secs(30), coordinator_http_info)
+}
+
+/// Runs a full verification pass on the coordinator (admin token required). Every backend is
+/// queried with a 5s cap, so allow more time than other calls.
+pub fn verify_colony(coordinator_http_info: Option<&(String, u16)>) -> Result<ColonyVerificationReport, String> {
+    let (coordinator_host, http_port) = coordinator_http_info
+        .ok_or_else(|| "Coordinator HTTP address unknown".to_string())?
+        .clone();
+
+    let url = format!("http://{}:{}/api/verify", coordinator_host, http_port);
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(15))
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+
+    let response = with_auth_blocking(client.post(&url))
+        .send()
+        .map_err(|e| format!("Request failed: {}", e))?;
+
+    if response.status().is_success() {
+        response.json::<ColonyVerificationReport>().map_err(|e| format!("Invalid response: {}", e))
+    } else {
+        let status = response.status();
+        let body = response.text().unwrap_or_default();
+        Err(format!("HTTP {}: {}", status.as_u16(), body))
+    }
+}
+
+/// Re-reads the topology and the colony instance id from the coordinator; None while it is
+/// unavailable or still initializing
+pub fn get_topology(coordinator_http_info: Option<&(String, u16)>) -> Option<(ClusterTopology, Option<String>)> {
+    let (coordinator_host, http_port) = coordinator_http_info?.clone();
+    
+    let url = format!("http://{}:{}/topology", coordinator_host, http_port);
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_millis(1500))
+        .build()
+        .ok()?;
+    
+    let response = with_auth_blocking(client.get(&url)).send().ok()?;
+    
+    if !response.status().is_success() {
+        return None;
+    }
+    let json_value = response.json::<serde_json::Value>().ok()?;
+    let colony_instance_id = json_value.get("colony_instance_id").and_then(|v| v.as_str()).map(|s| s.to_string());
+    let topology = serde_json::from_value::<ClusterTopology>(json_value).ok()?;
+    Some((topology, colony_instance_id))
+}
+
+/// Re-reads the backends' HTTP addresses from the coordinator's bootstrap; None while it is
+/// unavailable
+pub fn get_backend_http_info(coordinator_http_info: Option<&(String, u16)>) -> Option<crate::bootstrap::BackendHttpInfo> {
+    let (coordinator_host, http_port) = coordinator_http_info?.clone();
+    
+    let url = format!("http://{}:{}/api/bootstrap", coordinator_host, http_port);
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_millis(1500))
+        .build()
+        .ok()?;
+    
+    let response = with_auth_blocking(client.get(&url)).send().ok()?;
+    
+    if !response.status().is_success() {
+        return None;
+    }
+    let bootstrap = response.json::<shared::coordinator_api::BootstrapResponse>().ok()?;
+    Some(crate::bootstrap::http_info(&bootstrap).1)
+}
+
+/// Pings a backend's /health endpoint; the outcome is recorded in the latency tracker
+/// so the Cluster tab's Lat/Err columns reflect health checks too.
+pub fn ping_backend_health(host_info: &HostInfo, latency_tracker: &LatencyTracker, backend_http_info: &std::collections::HashMap<HostInfo, (String, u16)>) -> bool {
+    let key = OperationKey::new(OperationType::HealthCheck, host_info.clone());
+    let (public_ip, http_port) = match backend_http_info.get(host_info) {
+        Some(info) => info.clone(),
+        None => {
+            latency_tracker.record_error(key);
+            return false;
+        }
+    };
+
+    let url = format!("http://{}:{}/health", public_ip, http_port);
+    let client = match reqwest::blocking::Client::builder()
+        .timeout(Duration::from_millis(1500))
+        .build() {
+        Ok(client) => client,
+        Err(_) => return false,
+    };
+
+    let start = Instant::now();
+    let result = with_auth_blocking(client.get(&url)).send();
+    let latency = start.elapsed();
+
+    match result {
+        Ok(response) if response.status().is_success() => {
+            latency_tracker.record_success(key, latency);
+            true
+        }
+        Ok(response) => {
+            latency_tracker.record_error(key);
+            log_error!("GUI health check failed: host={}:{}, url={}, status_code={}",
+                       host_info.hostname, host_info.port, url, response.status().as_u16());
+            false
+        }
+        Err(e) => {
+            latency_tracker.record_error(key);
+            log_error!("GUI health check error: host={}:{}, url={}, duration_ms={:.2}, error={}",
+                       host_info.hostname, host_info.port, url, latency.as_secs_f64() * 1000.0, e);
+            false
+        }
+    }
+}
+
+pub fn backend_metrics_url(host_info: &HostInfo, backend_http_info: &std::collections::HashMap<HostInfo, (String, u16)>) -> Option<String> {
+    let (public_ip, http_port) = backend_http_info.get(host_info)?;
+    Some(format!("http://{}:{}/metrics", public_ip, http_port))
+}
+
+/// Asks the coordinator to re-issue StartTicking to one backend (admin token required)
+pub fn request_backend_start_ticking(host_info: &HostInfo, coordinator_http_info: Option<&(String, u16)>) -> Result<(), String> {
+    let (coordinator_host, http_port) = coordinator_http_info
+        .ok_or_else(|| "Coordinator HTTP address unknown".to_string())?
+        .clone();
+
+    let url = format!("http://{}:{}/api/backend/start-ticking?host={}&port={}",
+                      coordinator_host, http_port, host_info.hostname, host_info.port);
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_millis(5000))
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+
+    let response = with_auth_blocking(client.post(&url))
+        .send()
+        .map_err(|e| format!("Request failed: {}", e))?;
+
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        let status = response.status();
+        let body = response.text().unwrap_or_default();
+        Err(format!("HTTP {}: {}", status.as_u16(), body))
+    }
+}
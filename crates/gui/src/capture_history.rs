@@ -0,0 +1,178 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use eframe::egui;
+use shared::coordinator_api::CaptureFrameEntry;
+
+/// Decoded frames kept for scrubbing; a 1000x1000 colony frame takes 4 MB
+pub const HISTORY_CACHE_FRAMES: usize = 24;
+
+/// Bounded cache that drops the least recently used frame first
+pub struct FrameCache<T> {
+    capacity: usize,
+    frames: HashMap<u64, T>,
+    // Ticks from least to most recently used
+    recency: VecDeque<u64>,
+}
+
+impl<T> FrameCache<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity: capacity.max(1), frames: HashMap::new(), recency: VecDeque::new() }
+    }
+
+    pub fn get(&mut self, tick: u64) -> Option<&T> {
+        if self.frames.contains_key(&tick) {
+            self.touch(tick);
+        }
+        self.frames.get(&tick)
+    }
+
+    pub fn insert(&mut self, tick: u64, frame: T) {
+        if self.frames.insert(tick, frame).is_some() {
+            self.touch(tick);
+            return;
+        }
+        self.recency.push_back(tick);
+        while self.recency.len() > self.capacity {
+            if let Some(oldest) = self.recency.pop_front() {
+                self.frames.remove(&oldest);
+            }
+        }
+    }
+
+    fn touch(&mut self, tick: u64) {
+        self.recency.retain(|&used| used != tick);
+        self.recency.push_back(tick);
+    }
+}
+
+/// History mode of the Creatures tab: the coordinator's capture frames, scrubbed by tick.
+/// Shared with the threads that fetch the list and the frames.
+pub struct CaptureHistory {
+    /// From GET /api/captures, oldest first; None until it arrived
+    pub frames: Option<Vec<CaptureFrameEntry>>,
+    pub selected: usize,
+    pub cache: FrameCache<Arc<egui::ColorImage>>,
+    /// Tick of the frame being fetched; one at a time, so fast scrubbing only fetches where it stops
+    pub loading: Option<u64>,
+    /// Why the capture list could not be loaded
+    pub error: Option<String>,
+    /// Last frame that failed to load and why; not fetched again until the list is reloaded
+    pub failed_frame: Option<(u64, String)>,
+}
+
+impl Default for CaptureHistory {
+    fn default() -> Self {
+        Self { frames: None, selected: 0, cache: FrameCache::new(HISTORY_CACHE_FRAMES), loading: None, error: None, failed_frame: None }
+    }
+}
+
+impl CaptureHistory {
+    /// Takes a fresh capture list and selects its latest frame
+    pub fn set_frames(&mut self, frames: Vec<CaptureFrameEntry>) {
+        self.selected = frames.len().saturating_sub(1);
+        self.frames = Some(frames);
+        self.error = None;
+        self.failed_frame = None;
+    }
+
+    pub fn selected_frame(&self) -> Option<&CaptureFrameEntry> {
+        self.frames.as_ref()?.get(self.selected)
+    }
+
+    /// Moves the selection by delta frames, stopping at the first and last one
+    pub fn step(&mut self, delta: isize) {
+        let count = self.frames.as_ref().map_or(0, Vec::len);
+        if count > 0 {
+            self.selected = self.selected.saturating_add_signed(delta).min(count - 1);
+        }
+    }
+
+    /// The selected frame when it needs fetching and no other fetch is running
+    pub fn frame_to_fetch(&mut self) -> Option<CaptureFrameEntry> {
+        let frame = self.selected_frame()?.clone();
+        let failed = self.failed_frame.as_ref().is_some_and(|(tick, _)| *tick == frame.tick);
+        if failed || self.loading.is_some() || self.cache.get(frame.tick).is_some() {
+            return None;
+        }
+        Some(frame)
+    }
+}
+
+pub fn decode_frame(png: &[u8]) -> Result<egui::ColorImage, String> {
+    let image = image::load_from_memory(png).map_err(|e| format!("Invalid capture frame: {}", e))?.to_rgba8();
+    let size = [image.width() as usize, image.height() as usize];
+    Ok(egui::ColorImage::from_rgba_unmultiplied(size, image.as_raw()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(tick: u64) -> CaptureFrameEntry {
+        CaptureFrameEntry {
+            tick,
+            timestamp: String::new(),
+            url: format!("/api/captures/{:07}.png", tick),
+            bytes: 0,
+            missing_shards: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_cache_drops_least_recently_used() {
+        let mut cache = FrameCache::new(2);
+        cache.insert(10, "a");
+        cache.insert(20, "b");
+        assert_eq!(cache.get(10), Some(&"a"));
+        cache.insert(30, "c");
+        assert_eq!(cache.get(20), None);
+        assert_eq!(cache.get(10), Some(&"a"));
+        assert_eq!(cache.get(30), Some(&"c"));
+    }
+
+    #[test]
+    fn test_step_stays_within_the_frames() {
+        let mut history = CaptureHistory::default();
+        history.step(1);
+        assert_eq!(history.selected_frame(), None);
+
+        history.set_frames(vec![entry(100), entry(200), entry(300)]);
+        assert_eq!(history.selected_frame().map(|frame| frame.tick), Some(300));
+        history.step(1);
+        assert_eq!(history.selected, 2);
+        history.step(-5);
+        assert_eq!(history.selected, 0);
+        history.step(1);
+        assert_eq!(history.selected_frame().map(|frame| frame.tick), Some(200));
+    }
+
+    #[test]
+    fn test_only_uncached_frames_are_fetched_one_at_a_time() {
+        let mut history = CaptureHistory::default();
+        history.set_frames(vec![entry(100), entry(200)]);
+        assert_eq!(history.frame_to_fetch().map(|frame| frame.tick), Some(200));
+
+        history.loading = Some(200);
+        assert_eq!(history.frame_to_fetch(), None);
+        history.loading = None;
+        history.cache.insert(200, Arc::new(egui::ColorImage::new([1, 1], egui::Color32::BLACK)));
+        assert_eq!(history.frame_to_fetch(), None);
+        history.step(-1);
+        history.failed_frame = Some((100, "HTTP 404".to_string()));
+        assert_eq!(history.frame_to_fetch(), None);
+        history.set_frames(vec![entry(100)]);
+        assert_eq!(history.frame_to_fetch().map(|frame| frame.tick), Some(100));
+    }
+
+    #[test]
+    fn test_decode_frame() {
+        let mut png = Vec::new();
+        image::RgbImage::from_pixel(3, 2, image::Rgb([10, 20, 30]))
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        let frame = decode_frame(&png).unwrap();
+        assert_eq!(frame.size, [3, 2]);
+        assert_eq!(frame.pixels[5], egui::Color32::from_rgb(10, 20, 30));
+        assert!(decode_frame(b"not a png").is_err());
+    }
+}
@@ -0,0 +1,86 @@
+use shared::be_api::Shard;
+use shared::colony_model::GlobalPos;
+use shared::layer_stats::ShardLayerData;
+use crate::ShardConfig;
+
+/// Where a colony cell lives in the per-shard data, laid out like the combined image
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CellLocation {
+    pub shard_index: usize,
+    pub shard: Shard,
+    /// Row-major index of the cell within its shard's values
+    pub local_index: usize,
+}
+
+/// The shard and local index of a colony cell; None outside the colony
+pub fn locate_cell(config: &ShardConfig, pos: GlobalPos) -> Option<CellLocation> {
+    if config.total_shards() == 0 || pos.x < 0 || pos.y < 0 || pos.x >= config.total_width || pos.y >= config.total_height {
+        return None;
+    }
+    let col = ((pos.x / config.shard_width().max(1)) as usize).min(config.cols - 1);
+    let row = ((pos.y / config.shard_height().max(1)) as usize).min(config.rows - 1);
+    let shard_index = row * config.cols + col;
+    let mut shard = config.get_shard(shard_index);
+    // The last column and row absorb the remainder, as in the combined image
+    if col == config.cols - 1 {
+        shard.width = config.total_width - shard.x;
+    }
+    if row == config.rows - 1 {
+        shard.height = config.total_height - shard.y;
+    }
+    let local_index = ((pos.y - shard.y) * shard.width + (pos.x - shard.x)) as usize;
+    Some(CellLocation { shard_index, shard, local_index })
+}
+
+/// Status line for the layer value under the cursor, e.g. "x=812 y=344 value=207 (shard 750_250_250x250)";
+/// None outside the colony
+pub fn cell_readout(config: &ShardConfig, data: &[Option<ShardLayerData>], pos: GlobalPos) -> Option<String> {
+    let location = locate_cell(config, pos)?;
+    let value = data.get(location.shard_index)
+        .and_then(Option::as_ref)
+        .and_then(|shard_data| shard_data.values.get(location.local_index));
+    let shard = location.shard;
+    let value = value.map_or("no data".to_string(), |value| format!("value={}", value));
+    Some(format!("x={} y={} {} (shard {}_{}_{}x{})", pos.x, pos.y, value, shard.x, shard.y, shard.width, shard.height))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::layer_stats::LayerStats;
+
+    /// Two by two shards of 10x10 cells
+    fn config() -> ShardConfig {
+        ShardConfig { total_width: 20, total_height: 20, cols: 2, rows: 2 }
+    }
+
+    fn layer(values: Vec<i32>) -> Option<ShardLayerData> {
+        Some(ShardLayerData { stats: LayerStats::compute(&values), values })
+    }
+
+    #[test]
+    fn test_locate_cell() {
+        let location = locate_cell(&config(), GlobalPos::new(13, 4)).unwrap();
+        assert_eq!(location.shard_index, 1);
+        assert_eq!(location.shard, Shard { x: 10, y: 0, width: 10, height: 10 });
+        assert_eq!(location.local_index, 43);
+        assert_eq!(locate_cell(&config(), GlobalPos::new(19, 19)).unwrap().local_index, 99);
+        assert_eq!(locate_cell(&config(), GlobalPos::new(20, 0)), None);
+        assert_eq!(locate_cell(&config(), GlobalPos::new(0, -1)), None);
+
+        // A remainder that does not divide into shards goes to the last column
+        let uneven = ShardConfig { total_width: 21, total_height: 20, cols: 2, rows: 2 };
+        let location = locate_cell(&uneven, GlobalPos::new(20, 1)).unwrap();
+        assert_eq!(location.shard, Shard { x: 10, y: 0, width: 11, height: 10 });
+        assert_eq!(location.local_index, 21);
+    }
+
+    #[test]
+    fn test_readout_shows_value_or_missing_data() {
+        let data = vec![layer((0..100).collect()), layer((100..200).collect()), None, layer(Vec::new())];
+        assert_eq!(cell_readout(&config(), &data, GlobalPos::new(13, 4)).as_deref(), Some("x=13 y=4 value=143 (shard 10_0_10x10)"));
+        assert_eq!(cell_readout(&config(), &data, GlobalPos::new(2, 15)).as_deref(), Some("x=2 y=15 no data (shard 0_10_10x10)"));
+        assert_eq!(cell_readout(&config(), &data, GlobalPos::new(12, 12)).as_deref(), Some("x=12 y=12 no data (shard 10_10_10x10)"));
+        assert_eq!(cell_readout(&config(), &data, GlobalPos::new(25, 5)), None);
+    }
+}
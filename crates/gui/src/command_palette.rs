@@ -0,0 +1,219 @@
+use eframe::egui;
+use shared::cluster_topology::ClusterTopology;
+use shared::colony_model::{GlobalPos, Shard};
+use std::time::{Duration, Instant};
+
+/// How long the outline of a palette target flashes after the view jumps to it
+pub const FLASH_DURATION: Duration = Duration::from_millis(1500);
+
+/// Where the palette jumps to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PaletteTarget {
+    Shard(Shard),
+    Cell { pos: GlobalPos, shard: Shard },
+}
+
+impl PaletteTarget {
+    /// Area to center and outline, in colony coordinates
+    pub fn area(&self) -> Shard {
+        match *self {
+            PaletteTarget::Shard(shard) => shard,
+            PaletteTarget::Cell { pos, .. } => Shard { x: pos.x, y: pos.y, width: 1, height: 1 },
+        }
+    }
+}
+
+/// Screen position of a colony coordinate in the combined image, which is drawn at scale points per cell
+pub fn colony_to_screen(image_rect: egui::Rect, scale: f32, pos: GlobalPos) -> egui::Pos2 {
+    image_rect.min + egui::vec2(pos.x as f32, pos.y as f32) * scale
+}
+
+/// Screen rectangle of a colony rectangle in the combined image
+pub fn colony_rect_to_screen(image_rect: egui::Rect, scale: f32, x: i32, y: i32, width: i32, height: i32) -> egui::Rect {
+    egui::Rect::from_min_size(colony_to_screen(image_rect, scale, GlobalPos::new(x, y)), egui::vec2(width as f32, height as f32) * scale)
+}
+
+/// Colony cell under a screen position of the combined image, if any
+pub fn screen_to_colony(image_rect: egui::Rect, scale: f32, pos: egui::Pos2) -> Option<GlobalPos> {
+    if !image_rect.contains(pos) {
+        return None;
+    }
+    let offset = (pos - image_rect.min) / scale;
+    Some(GlobalPos::new(offset.x.floor() as i32, offset.y.floor() as i32))
+}
+
+/// Parses "x_y_w_h" or "x_y_wxh" as a shard id; None when the input is not shaped like one
+fn parse_shard_id(input: &str) -> Option<Result<Shard, String>> {
+    let parts: Vec<&str> = input.split('_').collect();
+    match parts.as_slice() {
+        [x, y, size] => {
+            let (width, height) = size.split_once(['x', 'X'])?;
+            Some(Shard::from_id(&format!("{}_{}_{}_{}", x, y, width, height)))
+        }
+        [_, _, _, _] => Some(Shard::from_id(input)),
+        _ => None,
+    }
+}
+
+fn parse_coordinate(input: &str) -> Option<Result<GlobalPos, String>> {
+    let (x, y) = input.split_once(',')?;
+    let parse = |value: &str| value.trim().parse::<i32>().map_err(|_| format!("'{}' is not a whole number", value.trim()));
+    Some(parse(x).and_then(|x| Ok(GlobalPos::new(x, parse(y)?))))
+}
+
+/// Resolves palette input against the current topology
+pub fn parse_target(input: &str, topology: &ClusterTopology) -> Result<PaletteTarget, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err("Type a shard id like 1000_500_250x250 or a coordinate like 1234,567".to_string());
+    }
+    let shards = topology.get_all_shards();
+    if let Some(shard) = parse_shard_id(input) {
+        let shard = shard?;
+        return if shards.contains(&shard) {
+            Ok(PaletteTarget::Shard(shard))
+        } else {
+            Err(format!("No shard {} in the colony", shard.to_id()))
+        };
+    }
+    if let Some(pos) = parse_coordinate(input) {
+        let pos = pos?;
+        return shards.into_iter()
+            .find(|shard| shard.contains(pos))
+            .map(|shard| PaletteTarget::Cell { pos, shard })
+            .ok_or_else(|| format!("({}, {}) is outside the colony", pos.x, pos.y));
+    }
+    Err(format!("'{}' is neither a shard id nor an x,y coordinate", input))
+}
+
+/// Ctrl+K text field that resolves a shard or coordinate to jump to
+#[derive(Default)]
+pub struct CommandPalette {
+    open: bool,
+    input: String,
+    error: Option<String>,
+    focus_pending: bool,
+}
+
+impl CommandPalette {
+    pub fn open(&mut self) {
+        self.open = true;
+        self.focus_pending = true;
+    }
+
+    /// Draws the palette while open; returns the target once valid input is submitted.
+    /// Invalid input keeps the palette open and shows why.
+    pub fn show(&mut self, ctx: &egui::Context, topology: &ClusterTopology) -> Option<PaletteTarget> {
+        if !self.open {
+            return None;
+        }
+        let mut target = None;
+        egui::Window::new("Go to")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 60.0))
+            .show(ctx, |ui| {
+                let response = ui.add(egui::TextEdit::singleline(&mut self.input)
+                    .hint_text("1000_500_250x250 or 1234,567")
+                    .desired_width(280.0));
+                if std::mem::take(&mut self.focus_pending) {
+                    response.request_focus();
+                }
+                if response.changed() {
+                    self.error = None;
+                }
+                if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    match parse_target(&self.input, topology) {
+                        Ok(resolved) => target = Some(resolved),
+                        Err(e) => {
+                            self.error = Some(e);
+                            response.request_focus();
+                        }
+                    }
+                }
+                if let Some(error) = &self.error {
+                    ui.colored_label(egui::Color32::RED, error);
+                }
+            });
+        if target.is_some() || ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.open = false;
+            self.error = None;
+        }
+        target
+    }
+}
+
+/// Outline of the last palette target, fading out over FLASH_DURATION
+pub fn draw_flash(ui: &egui::Ui, image_rect: egui::Rect, scale: f32, target: &PaletteTarget, since: Instant) {
+    let elapsed = since.elapsed();
+    if elapsed >= FLASH_DURATION {
+        return;
+    }
+    let fade = 1.0 - elapsed.as_secs_f32() / FLASH_DURATION.as_secs_f32();
+    let area = target.area();
+    let rect = colony_rect_to_screen(image_rect, scale, area.x, area.y, area.width, area.height).expand(2.0);
+    let color = egui::Color32::from_rgb(255, 220, 0).gamma_multiply(fade);
+    let painter = ui.painter_at(image_rect);
+    painter.rect_stroke(rect, 0.0, egui::Stroke::new(3.0, color));
+    // A cell is too small to spot alone, so its shard is outlined too
+    if let PaletteTarget::Cell { shard, .. } = target {
+        let shard_rect = colony_rect_to_screen(image_rect, scale, shard.x, shard.y, shard.width, shard.height);
+        painter.rect_stroke(shard_rect.shrink(1.0), 0.0, egui::Stroke::new(1.5, color));
+    }
+    ui.ctx().request_repaint();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::cluster_topology::HostInfo;
+    use std::collections::HashMap;
+
+    /// 2x2 shards of 250x250, so the colony is 500x500
+    fn topology() -> ClusterTopology {
+        let host = HostInfo::new("127.0.0.1".to_string(), 8082);
+        let shard_to_host: HashMap<Shard, HostInfo> = [(0, 0), (250, 0), (0, 250), (250, 250)].into_iter()
+            .map(|(x, y)| (Shard { x, y, width: 250, height: 250 }, host.clone()))
+            .collect();
+        ClusterTopology { coordinator_host: host.clone(), backend_hosts: vec![host], shard_to_host }
+    }
+
+    #[test]
+    fn test_shard_ids_in_both_forms() {
+        let expected = PaletteTarget::Shard(Shard { x: 250, y: 0, width: 250, height: 250 });
+        assert_eq!(parse_target("250_0_250x250", &topology()), Ok(expected));
+        assert_eq!(parse_target(" 250_0_250_250 ", &topology()), Ok(expected));
+        assert!(parse_target("1000_500_250x250", &topology()).unwrap_err().contains("No shard"));
+        assert!(parse_target("250_0_abcx250", &topology()).is_err());
+    }
+
+    #[test]
+    fn test_coordinates_resolve_to_their_shard() {
+        let target = parse_target("300, 260", &topology()).unwrap();
+        assert_eq!(target, PaletteTarget::Cell {
+            pos: GlobalPos::new(300, 260),
+            shard: Shard { x: 250, y: 250, width: 250, height: 250 },
+        });
+        assert_eq!(target.area(), Shard { x: 300, y: 260, width: 1, height: 1 });
+        assert!(parse_target("500,0", &topology()).unwrap_err().contains("outside"));
+        assert!(parse_target("12,abc", &topology()).unwrap_err().contains("abc"));
+        assert!(parse_target("north", &topology()).is_err());
+        assert!(parse_target("", &topology()).is_err());
+    }
+
+    #[test]
+    fn test_screen_transform() {
+        let image_rect = egui::Rect::from_min_size(egui::pos2(40.0, 100.0), egui::vec2(500.0, 500.0));
+        let pos = GlobalPos::new(300, 260);
+        assert_eq!(colony_to_screen(image_rect, 1.0, pos), egui::pos2(340.0, 360.0));
+        assert_eq!(colony_rect_to_screen(image_rect, 1.0, 250, 0, 250, 250),
+            egui::Rect::from_min_size(egui::pos2(290.0, 100.0), egui::vec2(250.0, 250.0)));
+        assert_eq!(screen_to_colony(image_rect, 1.0, egui::pos2(340.5, 360.9)), Some(pos));
+
+        // Zoomed in, a cell covers scale x scale points
+        let zoomed_rect = egui::Rect::from_min_size(egui::pos2(40.0, 100.0), egui::vec2(2000.0, 2000.0));
+        assert_eq!(colony_to_screen(zoomed_rect, 4.0, pos), egui::pos2(1240.0, 1140.0));
+        assert_eq!(screen_to_colony(zoomed_rect, 4.0, egui::pos2(1243.0, 1143.0)), Some(pos));
+        assert_eq!(screen_to_colony(zoomed_rect, 4.0, egui::pos2(39.0, 100.0)), None);
+    }
+}
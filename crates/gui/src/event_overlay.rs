@@ -0,0 +1,142 @@
+use eframe::egui;
+use shared::colony_events::Region;
+use shared::colony_model::Shard;
+use shared::coordinator_api::ColonyEventDescription;
+use crate::command_palette::colony_rect_to_screen;
+
+/// Colony ticks an event location stays outlined unless changed in the view bar
+pub const DEFAULT_OVERLAY_TICKS: u64 = 500;
+pub const MAX_OVERLAY_TICKS: u64 = 20_000;
+
+/// Colors handed out to event types, by a hash of the type name
+const EVENT_TYPE_PALETTE: [(u8, u8, u8); 6] = [
+    (255, 90, 90),
+    (255, 200, 40),
+    (80, 200, 255),
+    (180, 120, 255),
+    (90, 230, 130),
+    (255, 140, 220),
+];
+
+/// A recent event location and how much of its outline is left
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventMarker {
+    pub event_type: String,
+    /// Bounding box of the event region, in colony coordinates
+    pub area: Shard,
+    /// 1.0 when the event just happened, falling towards 0.0 as it expires
+    pub fade: f32,
+}
+
+/// Fade of an event stamped event_tick, None once lifetime_ticks passed. The tick samples lag
+/// the event feed, so an event ahead of current_tick counts as fresh.
+pub fn marker_fade(event_tick: u64, current_tick: u64, lifetime_ticks: u64) -> Option<f32> {
+    let age = current_tick.saturating_sub(event_tick);
+    (age < lifetime_ticks).then(|| 1.0 - age as f32 / lifetime_ticks as f32)
+}
+
+fn region_bounds(region: &Region) -> Shard {
+    match region {
+        Region::Ellipse(ellipse) => Shard {
+            x: ellipse.x - ellipse.radius_x,
+            y: ellipse.y - ellipse.radius_y,
+            width: 2 * ellipse.radius_x + 1,
+            height: 2 * ellipse.radius_y + 1,
+        },
+    }
+}
+
+/// Markers of the events with a region that have not expired, oldest first so newer ones
+/// are drawn on top. events is newest first, like /api/colony-events.
+pub fn active_markers(events: &[ColonyEventDescription], current_tick: u64, lifetime_ticks: u64) -> Vec<EventMarker> {
+    events.iter().rev()
+        .filter_map(|event| {
+            let region = event.region.as_ref()?;
+            let fade = marker_fade(event.tick, current_tick, lifetime_ticks)?;
+            Some(EventMarker { event_type: event.event_type.clone(), area: region_bounds(region), fade })
+        })
+        .collect()
+}
+
+/// The same event type keeps its color across runs
+pub fn event_type_color(event_type: &str) -> egui::Color32 {
+    let hash = event_type.bytes().fold(0usize, |hash, byte| hash.wrapping_mul(31).wrapping_add(byte as usize));
+    let (r, g, b) = EVENT_TYPE_PALETTE[hash % EVENT_TYPE_PALETTE.len()];
+    egui::Color32::from_rgb(r, g, b)
+}
+
+/// Fading ellipse outlines with the event type above them, on top of the combined image
+pub fn draw_event_markers(ui: &egui::Ui, image_rect: egui::Rect, scale: f32, markers: &[EventMarker]) {
+    if markers.is_empty() {
+        return;
+    }
+    let painter = ui.painter_at(image_rect);
+    for marker in markers {
+        let color = event_type_color(&marker.event_type).gamma_multiply(marker.fade);
+        let area = marker.area;
+        // Tiny regions stay visible when zoomed out
+        let rect = colony_rect_to_screen(image_rect, scale, area.x, area.y, area.width, area.height);
+        let radius = (rect.size() / 2.0).max(egui::Vec2::splat(6.0));
+        painter.add(egui::Shape::ellipse_stroke(rect.center(), radius, egui::Stroke::new(2.0, color)));
+        painter.text(rect.center() - egui::vec2(0.0, radius.y + 2.0), egui::Align2::CENTER_BOTTOM,
+                     &marker.event_type, egui::FontId::proportional(12.0), color);
+    }
+    // Keep fading while no new frame arrives
+    ui.ctx().request_repaint_after(std::time::Duration::from_millis(250));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::colony_events::Ellipse;
+
+    fn event(event_type: &str, tick: u64, region: Option<Region>) -> ColonyEventDescription {
+        ColonyEventDescription {
+            tick,
+            intended_tick: None,
+            event_type: event_type.to_string(),
+            description: String::new(),
+            delivery: None,
+            no_effect: false,
+            region,
+        }
+    }
+
+    fn ellipse(x: i32, y: i32) -> Option<Region> {
+        Some(Region::Ellipse(Ellipse { x, y, radius_x: 4, radius_y: 2 }))
+    }
+
+    #[test]
+    fn test_markers_fade_linearly_and_expire() {
+        assert_eq!(marker_fade(1000, 1000, 500), Some(1.0));
+        assert_eq!(marker_fade(1000, 1250, 500), Some(0.5));
+        assert_eq!(marker_fade(1000, 1499, 500).map(|fade| fade > 0.0), Some(true));
+        assert_eq!(marker_fade(1000, 1500, 500), None);
+        assert_eq!(marker_fade(1000, 900, 500), Some(1.0));
+        assert_eq!(marker_fade(1000, 1000, 0), None);
+    }
+
+    #[test]
+    fn test_active_markers_skip_expired_and_regionless_events() {
+        // Newest first, as the coordinator serves them
+        let events = vec![
+            event("CreateCreature", 2900, ellipse(50, 60)),
+            event("ChangeExtraFoodPerTick", 2800, None),
+            event("CreateCreature", 2600, ellipse(10, 20)),
+            event("CreateCreature", 1000, ellipse(30, 30)),
+        ];
+        let markers = active_markers(&events, 3000, 500);
+        assert_eq!(markers.len(), 2);
+        // Oldest first, so the newest is drawn last
+        assert_eq!(markers[0].area, Shard { x: 6, y: 18, width: 9, height: 5 });
+        assert!((markers[0].fade - 0.2).abs() < 1e-6);
+        assert_eq!(markers[1].area.x, 46);
+        assert!((markers[1].fade - 0.8).abs() < 1e-6);
+        assert!(active_markers(&events, 3500, 500).is_empty());
+    }
+
+    #[test]
+    fn test_event_type_colors_are_stable() {
+        assert_eq!(event_type_color("CreateCreature"), event_type_color("CreateCreature"));
+    }
+}
@@ -0,0 +1,122 @@
+use eframe::egui;
+use std::time::{Duration, Instant};
+
+/// Frames further apart than this many refresh intervals skipped a refresh; fading across the
+/// gap would show motion that never happened, so the new frame is shown as is
+const MAX_FRAME_GAP_INTERVALS: u32 = 2;
+
+fn lerp_channel(a: u8, b: u8, t: f32) -> u8 {
+    (a as f32 + (b as f32 - a as f32) * t).round() as u8
+}
+
+/// Pixel-wise blend of two equally sized images, t = 0 is from and t = 1 is to
+pub fn blend_images(from: &egui::ColorImage, to: &egui::ColorImage, t: f32) -> egui::ColorImage {
+    let pixels = from.pixels.iter().zip(&to.pixels)
+        .map(|(a, b)| egui::Color32::from_rgb(lerp_channel(a.r(), b.r(), t), lerp_channel(a.g(), b.g(), t), lerp_channel(a.b(), b.b(), t)))
+        .collect();
+    egui::ColorImage { size: to.size, pixels }
+}
+
+/// Cross-fades the Creatures image from the previous frame to the current one over a refresh
+/// interval, so slow refreshes change the view gradually instead of in jumps
+pub struct FrameInterpolator {
+    interval: Duration,
+    previous: Option<egui::ColorImage>,
+    current: Option<(egui::ColorImage, Instant)>,
+}
+
+impl FrameInterpolator {
+    pub fn new(interval: Duration) -> Self {
+        Self { interval, previous: None, current: None }
+    }
+
+    /// Drops both frames, e.g. when interpolation is switched off
+    pub fn reset(&mut self) {
+        self.previous = None;
+        self.current = None;
+    }
+
+    /// Image to show now for the frame fetched at frame_at. A new frame_at starts a fade from the
+    /// last frame unless the two are too far apart or differ in size.
+    pub fn blend(&mut self, image: egui::ColorImage, frame_at: Instant, now: Instant) -> egui::ColorImage {
+        match self.current.take() {
+            Some((last, last_at)) if last_at != frame_at => {
+                let gap = frame_at.saturating_duration_since(last_at);
+                let contiguous = gap <= self.interval * MAX_FRAME_GAP_INTERVALS && last.size == image.size;
+                self.previous = contiguous.then_some(last);
+            }
+            // The same frame is rebuilt every egui frame, overlays included, so the latest build is kept below
+            Some(_) => {}
+            None => self.previous = None,
+        }
+        self.current = Some((image.clone(), frame_at));
+
+        let t = self.progress(now);
+        match &self.previous {
+            Some(previous) if t < 1.0 => blend_images(previous, &image, t),
+            _ => {
+                self.previous = None;
+                image
+            }
+        }
+    }
+
+    /// Whether a fade is still running and needs repaints
+    pub fn is_fading(&self, now: Instant) -> bool {
+        self.previous.is_some() && self.progress(now) < 1.0
+    }
+
+    fn progress(&self, now: Instant) -> f32 {
+        match &self.current {
+            Some((_, frame_at)) if !self.interval.is_zero() => {
+                (now.saturating_duration_since(*frame_at).as_secs_f32() / self.interval.as_secs_f32()).min(1.0)
+            }
+            _ => 1.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INTERVAL: Duration = Duration::from_millis(1000);
+
+    fn solid(value: u8) -> egui::ColorImage {
+        egui::ColorImage::new([2, 1], egui::Color32::from_rgb(value, value, value))
+    }
+
+    fn shade(image: &egui::ColorImage) -> u8 {
+        image.pixels[0].r()
+    }
+
+    #[test]
+    fn test_fades_from_previous_to_current_over_the_interval() {
+        let mut interpolator = FrameInterpolator::new(INTERVAL);
+        let start = Instant::now();
+        assert_eq!(shade(&interpolator.blend(solid(0), start, start)), 0);
+
+        let next = start + INTERVAL;
+        assert_eq!(shade(&interpolator.blend(solid(200), next, next)), 0);
+        assert_eq!(shade(&interpolator.blend(solid(200), next, next + INTERVAL / 4)), 50);
+        assert!(interpolator.is_fading(next + INTERVAL / 2));
+        assert_eq!(shade(&interpolator.blend(solid(200), next, next + INTERVAL)), 200);
+        assert!(!interpolator.is_fading(next + INTERVAL));
+    }
+
+    #[test]
+    fn test_frames_too_far_apart_are_not_blended() {
+        let mut interpolator = FrameInterpolator::new(INTERVAL);
+        let start = Instant::now();
+        interpolator.blend(solid(0), start, start);
+
+        let late = start + INTERVAL * 3;
+        assert_eq!(shade(&interpolator.blend(solid(200), late, late)), 200);
+        assert!(!interpolator.is_fading(late));
+
+        // A resized colony is not blended either
+        let next = late + INTERVAL;
+        let resized = egui::ColorImage::new([3, 1], egui::Color32::from_rgb(100, 100, 100));
+        assert_eq!(shade(&interpolator.blend(resized, next, next)), 100);
+    }
+}
@@ -1,18 +1,53 @@
 #![allow(deprecated)]
 use eframe::{egui, App};
 use egui_extras::RetainedImage;
-use std::sync::{Arc, Condvar, Mutex};
+use std::sync::{Arc, Condvar, Mutex, RwLock};
 use std::thread;
 use std::time::{Duration, Instant};
-use shared::be_api::{ShardLayer, ColonyLifeRules};
+use shared::be_api::{ShardLayer, ColonyLifeRules, COLONY_LIFE_INITIAL_RULES, StatMetric};
 use shared::cluster_topology::ClusterTopology;
 use shared::cluster_registry::create_cluster_registry;
 use shared::ssm;
-use shared::coordinator_api::ColonyEventDescription;
+use shared::http_port_probe::{answers_health, HttpPortProbe};
+use shared::colony_event_shared::LIFECYCLE_EVENT_TYPES;
+use shared::coordinator_api::{BackendsResponse, CaptureFrameEntry, ColonyConfigResponse, ColonyRunConfig, ColonyEventDescription, ColonyStatsResponse, ColonyVerificationReport, TickSample};
+use shared::api_auth::{ADMIN_TOKEN_ENV, OBSERVER_TOKEN_ENV};
 use shared::log;
+use shared::layer_stats::ShardLayerData;
+use shared::shard_render::{lerp_rgb, terrain_rgb, ImageBackground};
+use shared::colony_model::GlobalPos;
+use shared::live_feed::{FeedClient, FeedMessage, FeedTopic};
+use shared::output_paths::OutputPaths;
+use responsiveness::{GuiResponsivenessState, PollCycle, ResponsivenessTracker};
+use bootstrap::ClusterAttachment;
+use capture_history::CaptureHistory;
+use instance_change::{detect_instance_change, InstanceChange, InstanceNotice};
+use histogram::{draw_histogram, draw_tick_sparkline, HistogramOptions};
+use event_overlay::{active_markers, draw_event_markers, DEFAULT_OVERLAY_TICKS, MAX_OVERLAY_TICKS};
+use command_palette::{colony_rect_to_screen, colony_to_screen, draw_flash, screen_to_colony, CommandPalette, PaletteTarget};
+use frame_interpolation::FrameInterpolator;
+use minimap::{MinimapFrame, MINIMAP_MAX_SIDE, MISSING_SHARD_COLOR};
+use view_link::{ViewState, ViewZoom, VIEW_LINK_PREFIX};
+use viewport_polling::{ShardRefresh, ViewportPoller};
+use shard_redirects::ShardRedirects;
 
+mod bootstrap;
 mod call_be;
+mod capture_history;
+mod cell_readout;
+mod command_palette;
+mod event_overlay;
+mod frame_interpolation;
+mod histogram;
+mod instance_change;
 mod latency_tracker;
+mod minimap;
+mod responsiveness;
+mod shard_redirects;
+mod stale_frames;
+mod stats_export;
+mod view_link;
+mod viewport_polling;
 
 const REFRESH_INTERVAL_MS_LOCALHOST: u64 = 100;
 // In AWS we poll less frequently to reduce backend load.
@@ -20,6 +55,17 @@ const REFRESH_INTERVAL_MS_LOCALHOST: u64 = 100;
 const REFRESH_INTERVAL_MS_AWS: u64 = 3000;
 const MIN_CREATURE_SIZE_LEGEND_MAX: i32 = 30;
 const FOOD_VALUE_LEGEND_MAX: i32 = 255;
+const NODE_HEALTH_PING_INTERVAL: Duration = Duration::from_secs(15);
+const TOPOLOGY_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+const FROZEN_SHARDS_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+const BACKEND_STATUS_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+const TICK_HISTORY_MINUTES: u64 = 60;
+const COLONY_EVENTS_SHOWN: usize = 30;
+/// Ticks arrive every few seconds while a colony runs; a silent feed is reconnected
+const FEED_READ_TIMEOUT: Duration = Duration::from_secs(30);
+const FEED_RECONNECT_INTERVAL: Duration = Duration::from_secs(10);
+const OBSERVER_FLAG: &str = "--observer";
+const OBSERVER_CANNOT_START_COLONY: &str = "Topology not initialized and observer mode cannot start the colony";
 
 #[derive(Clone, Copy, PartialEq, Debug)]
 enum Tab {
@@ -32,10 +78,77 @@ enum Tab {
     CostPerTurn,
     Health,
     Age,
+    Stats,
     Info,
     Cluster,
 }
 
+impl Tab {
+    /// Tabs that draw the combined colony image
+    fn shows_colony_image(self) -> bool {
+        !matches!(self, Tab::Stats | Tab::Info | Tab::Cluster)
+    }
+
+    /// Fixed top of the color scale for layer tabs whose values have a known range
+    fn legend_max(self) -> Option<i32> {
+        match self {
+            Tab::Sizes => Some(MIN_CREATURE_SIZE_LEGEND_MAX),
+            Tab::Food => Some(FOOD_VALUE_LEGEND_MAX),
+            Tab::Health => Some(10),
+            _ => None,
+        }
+    }
+}
+
+/// How a layer tab turns values into colors, shared by the tab's image and its minimap
+#[derive(Clone, Copy)]
+enum LayerColoring {
+    /// 1 and 2 at the two ends of the terrain palette, anything else white
+    Boolean,
+    /// Along the terrain palette up to max; 0 is white
+    Scaled { max: i32 },
+}
+
+impl LayerColoring {
+    fn for_tab(tab: Tab, data: &[Option<ShardLayerData>]) -> Self {
+        match tab {
+            Tab::CanKill | Tab::CanMove => LayerColoring::Boolean,
+            _ => LayerColoring::Scaled { max: layer_scale_max(data, tab.legend_max()) },
+        }
+    }
+
+    fn color(self, value: i32) -> egui::Color32 {
+        match self {
+            LayerColoring::Boolean => match value {
+                1 => BEImageApp::terrain_color(0.0),
+                2 => BEImageApp::terrain_color(1.0),
+                _ => egui::Color32::WHITE,
+            },
+            LayerColoring::Scaled { max } if value != 0 && max > 0 => BEImageApp::terrain_color(value as f32 / max as f32),
+            LayerColoring::Scaled { .. } => egui::Color32::WHITE,
+        }
+    }
+}
+
+/// Global maximum for consistent normalization, taken from the per-shard stats headers; never below legend_max
+fn layer_scale_max(data: &[Option<ShardLayerData>], legend_max: Option<i32>) -> i32 {
+    let global_max = data.iter()
+        .filter_map(|shard_data| shard_data.as_ref())
+        .map(|data| data.stats.max)
+        .max()
+        .unwrap_or(0);
+    legend_max.unwrap_or(global_max).max(global_max)
+}
+
+/// The extra food pattern a run started with, or the procedural rivers
+fn extra_food_pattern_label(config: &ColonyRunConfig) -> String {
+    config.extra_food_pattern.map_or_else(|| "procedural rivers".to_string(), |pattern| format!("{:?}", pattern))
+}
+
+fn to_be_color(color: egui::Color32) -> shared::be_api::Color {
+    shared::be_api::Color { red: color.r(), green: color.g(), blue: color.b() }
+}
+
 #[derive(Clone)]
 pub struct ShardConfig {
     pub total_width: i32,
@@ -105,42 +218,216 @@ impl ShardConfig {
 struct BEImageApp {
     creatures: Arc<Mutex<Vec<Option<RetainedImage>>>>,
     creatures_color_data: Arc<Mutex<Vec<Option<Vec<shared::be_api::Color>>>>>,
-    extra_food: Arc<Mutex<Vec<Option<Vec<i32>>>>>,
-    sizes: Arc<Mutex<Vec<Option<Vec<i32>>>>>,
-    can_kill: Arc<Mutex<Vec<Option<Vec<i32>>>>>,
-    can_move: Arc<Mutex<Vec<Option<Vec<i32>>>>>,
-    cost_per_turn: Arc<Mutex<Vec<Option<Vec<i32>>>>>,
-    food: Arc<Mutex<Vec<Option<Vec<i32>>>>>,
-    health: Arc<Mutex<Vec<Option<Vec<i32>>>>>,
-    age: Arc<Mutex<Vec<Option<Vec<i32>>>>>,
+    // When creatures_color_data was last replaced, so interpolation can tell frames apart
+    creatures_frame_at: Arc<Mutex<Instant>>,
+    extra_food: Arc<Mutex<Vec<Option<ShardLayerData>>>>,
+    sizes: Arc<Mutex<Vec<Option<ShardLayerData>>>>,
+    can_kill: Arc<Mutex<Vec<Option<ShardLayerData>>>>,
+    can_move: Arc<Mutex<Vec<Option<ShardLayerData>>>>,
+    cost_per_turn: Arc<Mutex<Vec<Option<ShardLayerData>>>>,
+    food: Arc<Mutex<Vec<Option<ShardLayerData>>>>,
+    health: Arc<Mutex<Vec<Option<ShardLayerData>>>>,
+    age: Arc<Mutex<Vec<Option<ShardLayerData>>>>,
+    // Sanctuary mask per shard, fetched while the overlay is shown on the Creatures tab
+    sanctuary: Arc<Mutex<Vec<Option<ShardLayerData>>>>,
+    show_sanctuaries: Arc<Mutex<bool>>,
+    // Empty cells of the Creatures image: plain white or tinted by food, rendered by the backend
+    image_background: Arc<Mutex<ImageBackground>>,
     colony_info: Arc<Mutex<Option<(Option<shared::be_api::ColonyLifeRules>, Option<u64>)>>>,
     colony_events: Arc<Mutex<Option<Vec<ColonyEventDescription>>>>,
+    // Event type shown in the Info tab's events list, None for all
+    events_filter: Arc<Mutex<Option<String>>>,
+    // Cluster min/max tick over the last TICK_HISTORY_MINUTES, refreshed with the Info tab
+    tick_history: Arc<Mutex<Option<Vec<TickSample>>>>,
+    // While the coordinator's /ws feed is up it keeps events and tick history current, so the Info tab skips those polls
+    feed_connected: Arc<Mutex<bool>>,
+    // Fetched once; the coordinator never changes it for a running colony
+    colony_config: Arc<Mutex<Option<ColonyConfigResponse>>>,
+    // None until the coordinator reported it; updated from pause/resume/step responses
+    ticker_paused: Arc<Mutex<Option<bool>>>,
+    ticker_action_status: Arc<Mutex<Option<String>>>,
+    colony_stats: Arc<Mutex<Option<ColonyStatsResponse>>>,
+    // The Stats tab shows the cells of the last viewport of an image tab instead of the whole colony
+    stats_viewport_region: Arc<Mutex<bool>>,
+    // Per-chart log-scale toggle of the Stats tab, keyed by metric name
+    stats_log_scale: std::collections::HashMap<String, bool>,
+    // Outcome of the last Stats tab export, shown next to the button
+    stats_export_status: Arc<Mutex<Option<String>>>,
     ctx: Option<egui::Context>,
     thread_started: bool,
     current_tab: Tab,
     shared_current_tab: Arc<Mutex<Tab>>,
     shard_config: Arc<Mutex<ShardConfig>>,
-    cluster_topology: Arc<ClusterTopology>,
+    cluster_topology: SharedTopology,
     last_update_time: Arc<Mutex<Instant>>,
     combined_texture: Option<egui::TextureHandle>,
     deployment_mode: String,
     coordinator_http_info: Option<(String, u16)>, // (public_ip, http_port)
-    backend_http_info: std::collections::HashMap<shared::cluster_topology::HostInfo, (String, u16)>, // HostInfo -> (public_ip, http_port)
+    // HostInfo -> (public_ip, http_port), re-read from the coordinator on each topology refresh
+    backend_http_info: SharedBackendHttpInfo,
+    // Where 421 responses said shards moved, until the next topology refresh
+    shard_redirects: Arc<ShardRedirects>,
     latency_tracker: Arc<latency_tracker::LatencyTracker>,
-    colony_instance_id: Option<String>,
+    // Updated by the topology refresh when the coordinator reports another colony instance
+    colony_instance_id: Arc<Mutex<Option<String>>>,
+    // Set by the topology refresh after a restart; the next frame drops everything of the old colony
+    pending_instance_change: Arc<Mutex<Option<String>>>,
+    instance_notice: Option<InstanceNotice>,
     tab_change_signal: Arc<(Mutex<bool>, Condvar)>,
-    responsiveness_state: Arc<Mutex<GuiResponsivenessState>>,
+    responsiveness: Arc<Mutex<ResponsivenessTracker>>,
+    observer_mode: bool,
+    node_health: NodeHealthMap,
+    cluster_action_status: Arc<Mutex<Option<String>>>,
+    // Last POST /api/verify started from the Cluster tab
+    verification_report: Arc<Mutex<Option<Result<ColonyVerificationReport, String>>>>,
+    verification_running: Arc<Mutex<bool>>,
+    // Ids of the shards frozen as read-only regions, from GET /api/shards
+    frozen_shards: Arc<Mutex<std::collections::HashSet<String>>>,
+    // Regions with their own rules, from GET /api/biomes
+    biomes: Arc<Mutex<Vec<shared::be_api::Biome>>>,
+    show_biomes: bool,
+    // Fading outlines where region events struck, kept for event_overlay_ticks colony ticks.
+    // The events come from GET /api/region-events, newest first.
+    region_events: Arc<Mutex<Vec<ColonyEventDescription>>>,
+    show_event_overlay: bool,
+    event_overlay_ticks: u64,
+    // Live per-backend view from GET /api/backends, refreshed while the Cluster tab is open
+    backend_statuses: Arc<Mutex<Option<BackendsResponse>>>,
+    command_palette: CommandPalette,
+    // Last palette target and when the view jumped to it; the scroll happens on the next image frame
+    palette_jump: Option<(PaletteTarget, Instant)>,
+    palette_scroll_pending: bool,
+    // Cross-fade of the Creatures image between refreshes; off by default and free while off
+    interpolate_frames: bool,
+    frame_interpolator: FrameInterpolator,
+    // Image view that a colony:// link captures and restores, see view_link
+    zoom: ViewZoom,
+    // Colony cell at the center of the image viewport when it was last drawn
+    view_center: Option<GlobalPos>,
+    // Center to scroll to on the next image frame, set when a link is opened
+    pending_center: Option<GlobalPos>,
+    inspected_cell: Option<GlobalPos>,
+    /// Colony cell under the cursor in the image, for the layer value readout
+    hovered_cell: Option<GlobalPos>,
+    view_link_input: String,
+    // Outcome of the last copy or open; Err is shown as a warning
+    view_link_status: Option<Result<String, String>>,
+    // Downsampled image of the current tab, rebuilt by the polling thread while shown
+    show_minimap: Arc<Mutex<bool>>,
+    minimap_frame: Arc<Mutex<Option<MinimapFrame>>>,
+    // Uploaded minimap and the frame generation it came from
+    minimap_texture: Option<(u64, egui::TextureHandle)>,
+    // Colony area (in cells) shown in the image, so the polling thread fetches only shards near it
+    visible_area: Arc<Mutex<Option<egui::Rect>>>,
+    // History mode of the Creatures tab shows coordinator capture frames; live polling of it is suspended
+    history_mode: Arc<Mutex<bool>>,
+    capture_history: Arc<Mutex<CaptureHistory>>,
+    // Uploaded history frame and its tick
+    history_texture: Option<(u64, egui::TextureHandle)>,
+    // Which poll cycle last brought data for each shard of the polled tab, for the stale shard overlay
+    shard_refresh: Arc<Mutex<ShardRefresh>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct NodeHealth {
+    is_up: bool,
+    last_seen: Option<Instant>,
+}
+
+type NodeHealthMap = Arc<Mutex<std::collections::HashMap<shared::cluster_topology::HostInfo, NodeHealth>>>;
+
+/// Outlines and labels the frozen shards on top of the combined image (drawn at scale points per cell)
+fn draw_frozen_shards(ui: &egui::Ui, image_rect: egui::Rect, scale: f32, config: &ShardConfig, frozen_shards: &std::collections::HashSet<String>) {
+    if frozen_shards.is_empty() {
+        return;
+    }
+    let frozen_color = egui::Color32::from_rgb(90, 170, 255);
+    let painter = ui.painter_at(image_rect);
+    for idx in 0..config.total_shards() {
+        let shard = config.get_shard(idx);
+        if !frozen_shards.contains(&shard.to_id()) {
+            continue;
+        }
+        let rect = colony_rect_to_screen(image_rect, scale, shard.x, shard.y, shard.width, shard.height);
+        painter.rect_filled(rect, 0.0, frozen_color.gamma_multiply(0.15));
+        painter.rect_stroke(rect.shrink(1.0), 0.0, egui::Stroke::new(2.0, frozen_color));
+        painter.text(rect.min + egui::vec2(6.0, 6.0), egui::Align2::LEFT_TOP, "❄ frozen", egui::FontId::proportional(14.0), frozen_color);
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum GuiResponsivenessState {
-    Healthy,
-    Slow,
-    Unresponsive,
+/// Dims the shards whose latest fetches failed; they keep showing their last good frame
+fn draw_stale_shards(ui: &egui::Ui, image_rect: egui::Rect, scale: f32, config: &ShardConfig, stale_shards: &[(usize, u64)]) {
+    let stale_color = egui::Color32::from_rgb(160, 160, 160);
+    let painter = ui.painter_at(image_rect);
+    for &(idx, missed) in stale_shards {
+        if idx >= config.total_shards() {
+            continue;
+        }
+        let shard = config.get_shard(idx);
+        let rect = colony_rect_to_screen(image_rect, scale, shard.x, shard.y, shard.width, shard.height);
+        painter.rect_filled(rect, 0.0, egui::Color32::BLACK.gamma_multiply(0.35));
+        painter.text(rect.left_bottom() + egui::vec2(6.0, -6.0), egui::Align2::LEFT_BOTTOM, format!("stale, {} polls missed", missed), egui::FontId::proportional(14.0), stale_color);
+    }
+}
+
+/// Outlines and names the biomes on top of the combined image; a later biome is drawn over an earlier one
+fn draw_biomes(ui: &egui::Ui, image_rect: egui::Rect, scale: f32, biomes: &[shared::be_api::Biome]) {
+    let biome_color = egui::Color32::from_rgb(40, 200, 120);
+    let painter = ui.painter_at(image_rect);
+    for biome in biomes {
+        let rect = colony_rect_to_screen(image_rect, scale, biome.x, biome.y, biome.width, biome.height);
+        painter.rect_stroke(rect.shrink(1.0), 0.0, egui::Stroke::new(2.0, biome_color));
+        painter.text(rect.min + egui::vec2(6.0, 6.0), egui::Align2::LEFT_TOP, &biome.name, egui::FontId::proportional(14.0), biome_color);
+    }
+}
+
+/// Marks the inspected cell; below 8x zoom the marker is larger than the cell so it stays visible
+fn draw_inspected_cell(ui: &egui::Ui, image_rect: egui::Rect, scale: f32, cell: GlobalPos) {
+    let color = egui::Color32::from_rgb(255, 60, 200);
+    let center = colony_to_screen(image_rect, scale, cell) + egui::vec2(scale, scale) / 2.0;
+    let rect = egui::Rect::from_center_size(center, egui::Vec2::splat(scale.max(8.0)));
+    ui.painter_at(image_rect).rect_stroke(rect.expand(1.0), 0.0, egui::Stroke::new(2.0, color));
+}
+
+/// Topology shared with the background threads; swapped when the colony is expanded
+type SharedTopology = Arc<RwLock<Arc<ClusterTopology>>>;
+type SharedBackendHttpInfo = Arc<RwLock<bootstrap::BackendHttpInfo>>;
+
+/// Adopts a topology re-read from the coordinator if its shard layout changed, or always
+/// for a restarted colony, and forgets the redirects the old one needed. Returns true when
+/// the shard configuration was updated.
+fn apply_refreshed_topology(topology_handle: &SharedTopology, shard_config: &Mutex<ShardConfig>, redirects: &ShardRedirects, topology: ClusterTopology, force: bool) -> bool {
+    let current = Arc::clone(&topology_handle.read().unwrap());
+    if !force && current.shard_count() == topology.shard_count() {
+        return false;
+    }
+    let new_config = ShardConfig::from_topology(&topology);
+    log!("GUI topology refresh: colony is now {}x{} ({} shards)",
+         new_config.total_width, new_config.total_height, topology.shard_count());
+    *shard_config.lock().unwrap() = new_config;
+    *topology_handle.write().unwrap() = Arc::new(topology);
+    redirects.clear();
+    true
+}
+
+fn ping_and_record_node_health(
+    host: &shared::cluster_topology::HostInfo,
+    node_health: &NodeHealthMap,
+    latency_tracker: &latency_tracker::LatencyTracker,
+    backend_http_info: &std::collections::HashMap<shared::cluster_topology::HostInfo, (String, u16)>,
+) {
+    let is_up = call_be::ping_backend_health(host, latency_tracker, backend_http_info);
+    let mut health = node_health.lock().unwrap();
+    let last_seen = if is_up {
+        Some(Instant::now())
+    } else {
+        health.get(host).and_then(|h| h.last_seen)
+    };
+    health.insert(host.clone(), NodeHealth { is_up, last_seen });
 }
 
 impl BEImageApp {
-    fn new(cluster_topology: Arc<ClusterTopology>, deployment_mode: String, coordinator_http_info: Option<(String, u16)>, backend_http_info: std::collections::HashMap<shared::cluster_topology::HostInfo, (String, u16)>, colony_instance_id: Option<String>) -> Self {
+    fn new(cluster_topology: Arc<ClusterTopology>, deployment_mode: String, coordinator_http_info: Option<(String, u16)>, backend_http_info: std::collections::HashMap<shared::cluster_topology::HostInfo, (String, u16)>, colony_instance_id: Option<String>, observer_mode: bool) -> Self {
         let shard_config = Arc::new(Mutex::new(ShardConfig::from_topology(&cluster_topology)));
         let total_shards = {
             let config_guard = shard_config.lock().unwrap();
@@ -148,6 +435,7 @@ impl BEImageApp {
         };
 
         let latency_tracker = Arc::new(latency_tracker::LatencyTracker::new(100));
+        let shard_redirects = Arc::new(ShardRedirects::new());
         // In AWS mode, don't load initial data - wait for tab click. In localhost, load immediately.
         let (creatures, creatures_color_data) = if deployment_mode == "aws" {
             let total_shards = {
@@ -157,8 +445,8 @@ impl BEImageApp {
             (Arc::new(Mutex::new((0..total_shards).map(|_| None).collect())),
              Arc::new(Mutex::new((0..total_shards).map(|_| None).collect())))
         } else {
-            let images = call_be::get_all_shard_retained_images(&shard_config.lock().unwrap(), cluster_topology.as_ref(), &latency_tracker, &backend_http_info);
-            let color_data = call_be::get_all_shard_color_data(&shard_config.lock().unwrap(), cluster_topology.as_ref(), &latency_tracker, &backend_http_info);
+            let images = call_be::get_all_shard_retained_images(&shard_config.lock().unwrap(), cluster_topology.as_ref(), &latency_tracker, &backend_http_info, &shard_redirects, ImageBackground::default());
+            let color_data = call_be::get_all_shard_color_data(&shard_config.lock().unwrap(), cluster_topology.as_ref(), &latency_tracker, &backend_http_info, &shard_redirects, ImageBackground::default());
             (Arc::new(Mutex::new(images)), Arc::new(Mutex::new(color_data)))
         };
         let extra_food = Arc::new(Mutex::new((0..total_shards).map(|_| None).collect()));
@@ -169,14 +457,18 @@ impl BEImageApp {
         let food = Arc::new(Mutex::new((0..total_shards).map(|_| None).collect()));
         let health = Arc::new(Mutex::new((0..total_shards).map(|_| None).collect()));
         let age = Arc::new(Mutex::new((0..total_shards).map(|_| None).collect()));
+        let sanctuary = Arc::new(Mutex::new((0..total_shards).map(|_| None).collect()));
         let colony_info = Arc::new(Mutex::new(None));
         let colony_events = Arc::new(Mutex::new(None));
+        let colony_config = Arc::new(Mutex::new(None));
         let current_tab = Tab::Creatures;
         let tab_change_signal = Arc::new((Mutex::new(false), Condvar::new()));
-        let responsiveness_state = Arc::new(Mutex::new(GuiResponsivenessState::Healthy));
+        let responsiveness = Arc::new(Mutex::new(ResponsivenessTracker::for_deployment_mode(&deployment_mode)));
+        let refresh_interval_ms = if deployment_mode == "aws" { REFRESH_INTERVAL_MS_AWS } else { REFRESH_INTERVAL_MS_LOCALHOST };
         Self {
             creatures,
             creatures_color_data,
+            creatures_frame_at: Arc::new(Mutex::new(Instant::now())),
             extra_food,
             sizes,
             can_kill,
@@ -185,23 +477,71 @@ impl BEImageApp {
             food,
             health,
             age,
+            sanctuary,
+            show_sanctuaries: Arc::new(Mutex::new(false)),
+            image_background: Arc::new(Mutex::new(ImageBackground::default())),
             colony_info,
             colony_events,
+            events_filter: Arc::new(Mutex::new(None)),
+            tick_history: Arc::new(Mutex::new(None)),
+            feed_connected: Arc::new(Mutex::new(false)),
+            colony_config,
+            ticker_paused: Arc::new(Mutex::new(None)),
+            ticker_action_status: Arc::new(Mutex::new(None)),
+            colony_stats: Arc::new(Mutex::new(None)),
+            stats_viewport_region: Arc::new(Mutex::new(false)),
+            stats_log_scale: std::collections::HashMap::new(),
+            stats_export_status: Arc::new(Mutex::new(None)),
             ctx: None,
             thread_started: false,
             current_tab,
             shared_current_tab: Arc::new(Mutex::new(current_tab)),
             shard_config,
-            cluster_topology,
+            cluster_topology: Arc::new(RwLock::new(cluster_topology)),
             last_update_time: Arc::new(Mutex::new(Instant::now())),
             combined_texture: None,
             deployment_mode,
             coordinator_http_info,
-            backend_http_info,
+            backend_http_info: Arc::new(RwLock::new(backend_http_info)),
+            shard_redirects,
             latency_tracker,
-            colony_instance_id,
+            colony_instance_id: Arc::new(Mutex::new(colony_instance_id)),
+            pending_instance_change: Arc::new(Mutex::new(None)),
+            instance_notice: None,
             tab_change_signal,
-            responsiveness_state,
+            responsiveness,
+            observer_mode,
+            node_health: Arc::new(Mutex::new(std::collections::HashMap::new())),
+            cluster_action_status: Arc::new(Mutex::new(None)),
+            verification_report: Arc::new(Mutex::new(None)),
+            verification_running: Arc::new(Mutex::new(false)),
+            frozen_shards: Arc::new(Mutex::new(std::collections::HashSet::new())),
+            biomes: Arc::new(Mutex::new(Vec::new())),
+            show_biomes: false,
+            region_events: Arc::new(Mutex::new(Vec::new())),
+            show_event_overlay: true,
+            event_overlay_ticks: DEFAULT_OVERLAY_TICKS,
+            backend_statuses: Arc::new(Mutex::new(None)),
+            command_palette: CommandPalette::default(),
+            palette_jump: None,
+            palette_scroll_pending: false,
+            interpolate_frames: false,
+            frame_interpolator: FrameInterpolator::new(Duration::from_millis(refresh_interval_ms)),
+            zoom: ViewZoom::Scale(1),
+            view_center: None,
+            pending_center: None,
+            inspected_cell: None,
+            hovered_cell: None,
+            view_link_input: String::new(),
+            view_link_status: None,
+            show_minimap: Arc::new(Mutex::new(true)),
+            minimap_frame: Arc::new(Mutex::new(None)),
+            visible_area: Arc::new(Mutex::new(None)),
+            minimap_texture: None,
+            history_mode: Arc::new(Mutex::new(false)),
+            capture_history: Arc::new(Mutex::new(CaptureHistory::default())),
+            history_texture: None,
+            shard_refresh: Arc::new(Mutex::new(ShardRefresh::default())),
         }
     }
 }
@@ -213,6 +553,7 @@ impl App for BEImageApp {
             self.ctx = Some(ctx.clone());
             let creatures = self.creatures.clone();
             let creatures_color_data = self.creatures_color_data.clone();
+            let creatures_frame_at = Arc::clone(&self.creatures_frame_at);
             let extra_food = self.extra_food.clone();
             let sizes = self.sizes.clone();
             let can_kill = self.can_kill.clone();
@@ -221,16 +562,29 @@ impl App for BEImageApp {
             let food = self.food.clone();
             let health = self.health.clone();
             let age = self.age.clone();
+            let sanctuary = self.sanctuary.clone();
+            let show_sanctuaries = Arc::clone(&self.show_sanctuaries);
+            let image_background = Arc::clone(&self.image_background);
+            let show_minimap = Arc::clone(&self.show_minimap);
+            let minimap_frame = Arc::clone(&self.minimap_frame);
+            let visible_area = Arc::clone(&self.visible_area);
+            let history_mode = Arc::clone(&self.history_mode);
+            let shard_refresh = Arc::clone(&self.shard_refresh);
+            let colony_stats = Arc::clone(&self.colony_stats);
+            let stats_viewport_region = Arc::clone(&self.stats_viewport_region);
+            let coordinator_http_info = self.coordinator_http_info.clone();
             let ctx_clone = ctx.clone();
             let shared_current_tab = self.shared_current_tab.clone();
             let shard_config = self.shard_config.clone();
-            let cluster_topology = Arc::clone(&self.cluster_topology);
+            let topology_handle = Arc::clone(&self.cluster_topology);
             let last_update_time = self.last_update_time.clone();
+            let responsiveness = Arc::clone(&self.responsiveness);
             let deployment_mode = self.deployment_mode.clone();
             let latency_tracker = Arc::clone(&self.latency_tracker);
             let tab_change_signal = Arc::clone(&self.tab_change_signal);
             let deployment_mode_clone = deployment_mode.clone();
-            let backend_http_info = self.backend_http_info.clone();
+            let backend_http_info_handle = Arc::clone(&self.backend_http_info);
+            let shard_redirects = Arc::clone(&self.shard_redirects);
             let is_aws_mode = deployment_mode == "aws";
             // Signal the thread once on startup in AWS mode so it can load the initial tab
             if is_aws_mode {
@@ -245,6 +599,9 @@ impl App for BEImageApp {
                 } else {
                     REFRESH_INTERVAL_MS_LOCALHOST
                 };
+                let mut minimap_generation = 0;
+                let mut viewport_poller = ViewportPoller::default();
+                let mut poll_cycle: u64 = 0;
                 loop {
                     // In AWS mode we do not poll on a timer at all.
                     // Instead, we only fetch data when a tab is first presented
@@ -262,117 +619,117 @@ impl App for BEImageApp {
                     }
 
                     // Start polling cycle timing
+                    poll_cycle += 1;
                     let cycle_start = Instant::now();
                     let time_before_update = *last_update_time.lock().unwrap();
                     let mut had_success = false;
+                    // Info and Cluster tabs fetch nothing here, so their cycles don't count as polls
+                    let mut polled = true;
                     
                     // Look at the selected tab and get only the info required for the current Tab
                     let tab = *shared_current_tab.lock().unwrap();
                     let config = shard_config.lock().unwrap().clone();
+                    let cluster_topology = Arc::clone(&topology_handle.read().unwrap());
+                    let backend_http_info = backend_http_info_handle.read().unwrap().clone();
+                    let layer_store = match tab {
+                        Tab::ExtraFood => Some((ShardLayer::ExtraFood, &extra_food)),
+                        Tab::Sizes => Some((ShardLayer::CreatureSize, &sizes)),
+                        Tab::CanKill => Some((ShardLayer::CanKill, &can_kill)),
+                        Tab::CanMove => Some((ShardLayer::CanMove, &can_move)),
+                        Tab::CostPerTurn => Some((ShardLayer::CostPerTurn, &cost_per_turn)),
+                        Tab::Food => Some((ShardLayer::Food, &food)),
+                        Tab::Health => Some((ShardLayer::Health, &health)),
+                        Tab::Age => Some((ShardLayer::Age, &age)),
+                        _ => None,
+                    };
+                    // Image tabs other than the layers fetch every shard
+                    let mut fetched_shards = if tab.shows_colony_image() { config.total_shards() } else { 0 };
                     
                     match tab {
-                        Tab::Creatures => {
-                            let images = call_be::get_all_shard_retained_images(&config, cluster_topology.as_ref(), &latency_tracker, &backend_http_info);
-                            let color_data = call_be::get_all_shard_color_data(&config, cluster_topology.as_ref(), &latency_tracker, &backend_http_info);
-                            // Only update if we got valid data (don't overwrite with None on backend failures)
-                            if !images.iter().all(|img| img.is_none()) {
-                                let mut locked = creatures.lock().unwrap();
-                                *locked = images;
-                                *last_update_time.lock().unwrap() = Instant::now();
-                                had_success = true;
-                            }
-                            if !color_data.iter().all(|data| data.is_none()) {
-                                let mut locked = creatures_color_data.lock().unwrap();
-                                *locked = color_data;
-                            }
+                        Tab::Creatures if *history_mode.lock().unwrap() => {
+                            // History frames come from the coordinator, see show_capture_history
+                            polled = false;
+                            fetched_shards = 0;
                         }
-                        Tab::ExtraFood => {
-                            let extra_food_data = call_be::get_all_shard_layer_data(ShardLayer::ExtraFood, &config, cluster_topology.as_ref(), &latency_tracker, &backend_http_info);
-                            // Only update if we got valid data (don't overwrite with None on backend failures)
-                            if !extra_food_data.iter().all(|data| data.is_none()) {
-                                let mut locked = extra_food.lock().unwrap();
-                                *locked = extra_food_data;
-                                *last_update_time.lock().unwrap() = Instant::now();
-                                had_success = true;
-                            }
-                        }
-                        Tab::Sizes => {
-                            let sizes_data = call_be::get_all_shard_layer_data(ShardLayer::CreatureSize, &config, cluster_topology.as_ref(), &latency_tracker, &backend_http_info);
-                            // Only update if we got valid data (don't overwrite with None on backend failures)
-                            if !sizes_data.iter().all(|data| data.is_none()) {
-                                let mut locked = sizes.lock().unwrap();
-                                *locked = sizes_data;
-                                *last_update_time.lock().unwrap() = Instant::now();
-                                had_success = true;
-                            }
-                        }
-                        Tab::Age => {
-                            let age_data = call_be::get_all_shard_layer_data(ShardLayer::Age, &config, cluster_topology.as_ref(), &latency_tracker, &backend_http_info);
-                            if !age_data.iter().all(|data| data.is_none()) {
-                                let mut locked = age.lock().unwrap();
-                                *locked = age_data;
-                                *last_update_time.lock().unwrap() = Instant::now();
-                                had_success = true;
-                            }
-                        }
-                        Tab::CanKill => {
-                            let can_kill_data = call_be::get_all_shard_layer_data(ShardLayer::CanKill, &config, cluster_topology.as_ref(), &latency_tracker, &backend_http_info);
-                            // Only update if we got valid data (don't overwrite with None on backend failures)
-                            if !can_kill_data.iter().all(|data| data.is_none()) {
-                                let mut locked = can_kill.lock().unwrap();
-                                *locked = can_kill_data;
+                        Tab::Creatures => {
+                            let background = *image_background.lock().unwrap();
+                            let images = call_be::get_all_shard_retained_images(&config, cluster_topology.as_ref(), &latency_tracker, &backend_http_info, &shard_redirects, background);
+                            let color_data = call_be::get_all_shard_color_data(&config, cluster_topology.as_ref(), &latency_tracker, &backend_http_info, &shard_redirects, background);
+                            // Shard by shard, so a failed fetch keeps the shard's previous frame instead of going black
+                            if viewport_polling::merge_successful(&mut creatures.lock().unwrap(), images).contains(&true) {
                                 *last_update_time.lock().unwrap() = Instant::now();
                                 had_success = true;
                             }
-                        }
-                        Tab::CanMove => {
-                            let can_move_data = call_be::get_all_shard_layer_data(ShardLayer::CanMove, &config, cluster_topology.as_ref(), &latency_tracker, &backend_http_info);
-                            // Only update if we got valid data (don't overwrite with None on backend failures)
-                            if !can_move_data.iter().all(|data| data.is_none()) {
-                                let mut locked = can_move.lock().unwrap();
-                                *locked = can_move_data;
-                                *last_update_time.lock().unwrap() = Instant::now();
-                                had_success = true;
+                            let updated = viewport_polling::merge_successful(&mut creatures_color_data.lock().unwrap(), color_data);
+                            if updated.contains(&true) {
+                                *creatures_frame_at.lock().unwrap() = Instant::now();
                             }
-                        }
-                        Tab::CostPerTurn => {
-                            let cost_per_turn_data = call_be::get_all_shard_layer_data(ShardLayer::CostPerTurn, &config, cluster_topology.as_ref(), &latency_tracker, &backend_http_info);
-                            // Only update if we got valid data (don't overwrite with None on backend failures)
-                            if !cost_per_turn_data.iter().all(|data| data.is_none()) {
-                                let mut locked = cost_per_turn.lock().unwrap();
-                                *locked = cost_per_turn_data;
-                                *last_update_time.lock().unwrap() = Instant::now();
-                                had_success = true;
+                            shard_refresh.lock().unwrap().record(tab, poll_cycle, &updated);
+                            if *show_sanctuaries.lock().unwrap() {
+                                let sanctuary_data = call_be::get_all_shard_layer_data(ShardLayer::Sanctuary, &config, cluster_topology.as_ref(), &latency_tracker, &backend_http_info, &shard_redirects);
+                                viewport_polling::merge_successful(&mut sanctuary.lock().unwrap(), sanctuary_data);
                             }
                         }
-                        Tab::Food => {
-                            let food_data = call_be::get_all_shard_layer_data(ShardLayer::Food, &config, cluster_topology.as_ref(), &latency_tracker, &backend_http_info);
-                            // Only update if we got valid data (don't overwrite with None on backend failures)
-                            if !food_data.iter().all(|data| data.is_none()) {
-                                let mut locked = food.lock().unwrap();
-                                *locked = food_data;
-                                *last_update_time.lock().unwrap() = Instant::now();
-                                had_success = true;
+                        Tab::ExtraFood | Tab::Sizes | Tab::CanKill | Tab::CanMove | Tab::CostPerTurn | Tab::Food | Tab::Health | Tab::Age => {
+                            if let Some((layer, store)) = layer_store {
+                                // Off-screen shards keep their last data and are refetched only every few cycles.
+                                // AWS mode polls once per tab change, so it always fetches the whole colony.
+                                let have_data: Vec<bool> = store.lock().unwrap().iter().map(Option::is_some).collect();
+                                let viewport = if is_aws { None } else { *visible_area.lock().unwrap() };
+                                let fetch = viewport_poller.plan(layer, &config, viewport, &have_data);
+                                fetched_shards = fetch.iter().filter(|&&fetch| fetch).count();
+                                let layer_data = call_be::get_shard_layer_data(layer, &config, cluster_topology.as_ref(), &latency_tracker, &backend_http_info, &shard_redirects, &fetch);
+                                let updated = viewport_polling::merge_fetched(&mut store.lock().unwrap(), layer_data, &fetch);
+                                if updated.contains(&true) {
+                                    *last_update_time.lock().unwrap() = Instant::now();
+                                    had_success = true;
+                                }
+                                // Shards skipped this cycle were not due, so they do not count as missed
+                                let refreshed: Vec<bool> = updated.iter().zip(&fetch).map(|(&updated, &fetched)| updated || !fetched).collect();
+                                shard_refresh.lock().unwrap().record(tab, poll_cycle, &refreshed);
                             }
                         }
-                        Tab::Health => {
-                            let health_data = call_be::get_all_shard_layer_data(ShardLayer::Health, &config, cluster_topology.as_ref(), &latency_tracker, &backend_http_info);
-                            // Only update if we got valid data (don't overwrite with None on backend failures)
-                            if !health_data.iter().all(|data| data.is_none()) {
-                                let mut locked = health.lock().unwrap();
-                                *locked = health_data;
+                        Tab::Stats => {
+                            // The coordinator caches the merged stats, so polling here is cheap for the backends
+                            // Region stats are fanned out on every poll, only the colony-wide ones are cached
+                            let region = if *stats_viewport_region.lock().unwrap() {
+                                visible_area.lock().unwrap().and_then(viewport_polling::viewport_region)
+                            } else {
+                                None
+                            };
+                            if let Some(stats) = call_be::get_colony_stats(region, coordinator_http_info.as_ref()) {
+                                *colony_stats.lock().unwrap() = Some(stats);
                                 *last_update_time.lock().unwrap() = Instant::now();
                                 had_success = true;
                             }
                         }
                         Tab::Info => {
                             // No automatic polling for Info tab - data is loaded once when tab is first accessed
+                            polled = false;
                         }
                         Tab::Cluster => {
-                            // No automatic polling for Cluster tab - data is static and retrieved at startup
+                            // Cluster tab status is refreshed by its own thread, see the backend status refresh
+                            polled = false;
                         }
                     }
                     
+                    // Downsampled here rather than per UI frame, from the frame this poll stored
+                    if had_success && tab.shows_colony_image() && *show_minimap.lock().unwrap() {
+                        // Built from all stored shards, whether fetched this cycle or earlier
+                        let image = match layer_store {
+                            Some((_, layer)) => {
+                                let guard = layer.lock().unwrap();
+                                let layer = guard.as_slice();
+                                let coloring = LayerColoring::for_tab(tab, layer);
+                                minimap::downsample(&config, layer, MINIMAP_MAX_SIDE, |data, idx| data.values.get(idx).map(|value| coloring.color(*value)))
+                            }
+                            None => minimap::downsample(&config, creatures_color_data.lock().unwrap().as_slice(), MINIMAP_MAX_SIDE,
+                                |colors, idx| colors.get(idx).map(|color| egui::Color32::from_rgb(color.red, color.green, color.blue))),
+                        };
+                        minimap_generation += 1;
+                        *minimap_frame.lock().unwrap() = Some(MinimapFrame { tab, generation: minimap_generation, image });
+                    }
+
                     // End polling cycle timing and log
                     let cycle_end = Instant::now();
                     let cycle_duration_ms = cycle_end.duration_since(cycle_start).as_millis() as f64;
@@ -386,8 +743,23 @@ impl App for BEImageApp {
                     let successes = if had_success { 1 } else { 0 };
                     let errors = if had_success { 0 } else { 1 };
                     
-                    log!("GUI poll cycle: duration_ms={:.2}, successes={}, errors={}, time_since_last_update_ms={:.2}, mode={}", 
-                         cycle_duration_ms, successes, errors, time_since_last_update, deployment_mode_clone);
+                    let skipped_shards = if tab.shows_colony_image() { config.total_shards() - fetched_shards } else { 0 };
+                    log!("GUI poll cycle: duration_ms={:.2}, successes={}, errors={}, time_since_last_update_ms={:.2}, fetched_shards={}, skipped_shards={}, mode={}", 
+                         cycle_duration_ms, successes, errors, time_since_last_update, fetched_shards, skipped_shards, deployment_mode_clone);
+
+                    if polled {
+                        let cycle = PollCycle {
+                            ended_at: cycle_end,
+                            success: had_success,
+                            duration: cycle_end.duration_since(cycle_start),
+                            data_age: cycle_end.duration_since(current_update_time),
+                        };
+                        let mut tracker = responsiveness.lock().unwrap();
+                        if let Some((prev_state, current_state)) = tracker.record(cycle) {
+                            log!("GUI responsiveness state changed: prev={:?}, current={:?}, failed_polls={}/{}, data_age_ms={}",
+                                 prev_state, current_state, tracker.failed_count(), tracker.window_len(), cycle.data_age.as_millis());
+                        }
+                    }
                     
                     ctx_clone.request_repaint();
                     if !is_aws {
@@ -407,8 +779,151 @@ impl App for BEImageApp {
                     }
                 }
             });
+            // Background liveness pings for the Cluster tab
+            {
+                let node_health = Arc::clone(&self.node_health);
+                let topology_handle = Arc::clone(&self.cluster_topology);
+                let latency_tracker = Arc::clone(&self.latency_tracker);
+                let backend_http_info_handle = Arc::clone(&self.backend_http_info);
+                let ctx_clone = ctx.clone();
+                thread::spawn(move || loop {
+                    let cluster_topology = Arc::clone(&topology_handle.read().unwrap());
+                    let backend_http_info = backend_http_info_handle.read().unwrap().clone();
+                    for backend in cluster_topology.get_all_backend_hosts() {
+                        ping_and_record_node_health(backend, &node_health, &latency_tracker, &backend_http_info);
+                    }
+                    ctx_clone.request_repaint();
+                    thread::sleep(NODE_HEALTH_PING_INTERVAL);
+                });
+            }
+            // Periodic topology refresh so colony expansion and restarts show up without restarting the GUI
+            {
+                let topology_handle = Arc::clone(&self.cluster_topology);
+                let shard_config = Arc::clone(&self.shard_config);
+                let colony_instance_id = Arc::clone(&self.colony_instance_id);
+                let pending_instance_change = Arc::clone(&self.pending_instance_change);
+                let backend_http_info = Arc::clone(&self.backend_http_info);
+                let shard_redirects = Arc::clone(&self.shard_redirects);
+                let coordinator_http_info = self.coordinator_http_info.clone();
+                let tab_change_signal = Arc::clone(&self.tab_change_signal);
+                let ctx_clone = ctx.clone();
+                thread::spawn(move || loop {
+                    thread::sleep(TOPOLOGY_REFRESH_INTERVAL);
+                    let Some((topology, reported_id)) = call_be::get_topology(coordinator_http_info.as_ref()) else {
+                        continue;
+                    };
+                    let change = detect_instance_change(colony_instance_id.lock().unwrap().as_deref(), reported_id.as_deref());
+                    let replaced = matches!(change, InstanceChange::Replaced(_));
+                    match change {
+                        InstanceChange::Unchanged => {}
+                        InstanceChange::Identified(id) => *colony_instance_id.lock().unwrap() = Some(id),
+                        InstanceChange::Replaced(id) => {
+                            log!("GUI: colony restarted as instance {}", id);
+                            *colony_instance_id.lock().unwrap() = Some(id.clone());
+                            *pending_instance_change.lock().unwrap() = Some(id);
+                        }
+                    }
+                    // Expansion and restarts can bring backends, or HTTP ports, the GUI has not seen
+                    if let Some(fetched) = call_be::get_backend_http_info(coordinator_http_info.as_ref()) {
+                        let mut known = backend_http_info.write().unwrap();
+                        let merged = bootstrap::merge_backend_http_info(&known, fetched, &topology);
+                        *known = merged;
+                    }
+                    if apply_refreshed_topology(&topology_handle, &shard_config, &shard_redirects, topology, replaced) {
+                        // Wake the poller so the new shards are fetched right away (also in AWS mode)
+                        let (lock, cvar) = &*tab_change_signal;
+                        *lock.lock().unwrap() = true;
+                        cvar.notify_one();
+                        ctx_clone.request_repaint();
+                    }
+                });
+            }
+            // Frozen shard, biome and region event refresh for the image overlays
+            {
+                let frozen_shards = Arc::clone(&self.frozen_shards);
+                let biomes = Arc::clone(&self.biomes);
+                let region_events = Arc::clone(&self.region_events);
+                let coordinator_http_info = self.coordinator_http_info.clone();
+                let ctx_clone = ctx.clone();
+                thread::spawn(move || loop {
+                    if let Some(list) = call_be::get_shard_list(coordinator_http_info.as_ref()) {
+                        let frozen: std::collections::HashSet<String> = list.shards.into_iter()
+                            .filter(|entry| entry.frozen)
+                            .map(|entry| entry.shard_id)
+                            .collect();
+                        let mut current = frozen_shards.lock().unwrap();
+                        if *current != frozen {
+                            *current = frozen;
+                            ctx_clone.request_repaint();
+                        }
+                    }
+                    if let Some(response) = call_be::get_biomes(coordinator_http_info.as_ref()) {
+                        let mut current = biomes.lock().unwrap();
+                        if *current != response.biomes {
+                            *current = response.biomes;
+                            ctx_clone.request_repaint();
+                        }
+                    }
+                    if let Some(events) = call_be::get_region_events(coordinator_http_info.as_ref()) {
+                        let mut current = region_events.lock().unwrap();
+                        if *current != events {
+                            *current = events;
+                            ctx_clone.request_repaint();
+                        }
+                    }
+                    thread::sleep(FROZEN_SHARDS_REFRESH_INTERVAL);
+                });
+            }
+            // Live backend status for the Cluster tab; each refresh queries every backend, so only while it is shown
+            {
+                let backend_statuses = Arc::clone(&self.backend_statuses);
+                let shared_current_tab = Arc::clone(&self.shared_current_tab);
+                let coordinator_http_info = self.coordinator_http_info.clone();
+                let ctx_clone = ctx.clone();
+                thread::spawn(move || loop {
+                    if *shared_current_tab.lock().unwrap() == Tab::Cluster {
+                        if let Some(statuses) = call_be::get_backends(coordinator_http_info.as_ref()) {
+                            *backend_statuses.lock().unwrap() = Some(statuses);
+                            ctx_clone.request_repaint();
+                        }
+                    }
+                    thread::sleep(BACKEND_STATUS_REFRESH_INTERVAL);
+                });
+            }
+            // Events and tick updates pushed over the coordinator feed; images stay on HTTP
+            {
+                let colony_events = Arc::clone(&self.colony_events);
+                let tick_history = Arc::clone(&self.tick_history);
+                let feed_connected = Arc::clone(&self.feed_connected);
+                let coordinator_http_info = self.coordinator_http_info.clone();
+                let ctx_clone = ctx.clone();
+                thread::spawn(move || loop {
+                    if let Some(client) = call_be::connect_feed(&[FeedTopic::Events, FeedTopic::Ticks], FEED_READ_TIMEOUT, coordinator_http_info.as_ref()) {
+                        *feed_connected.lock().unwrap() = true;
+                        follow_feed(client, &colony_events, &tick_history, coordinator_http_info.as_ref(), &ctx_clone);
+                        *feed_connected.lock().unwrap() = false;
+                    }
+                    thread::sleep(FEED_RECONNECT_INTERVAL);
+                });
+            }
             self.thread_started = true;
         }
+        let instance_change = self.pending_instance_change.lock().unwrap().take();
+        if let Some(colony_instance_id) = instance_change {
+            self.attach_to_new_instance(&colony_instance_id);
+        }
+        if ctx.input(|i| i.modifiers.command && i.key_pressed(egui::Key::K)) {
+            self.command_palette.open();
+        }
+        let topology = Arc::clone(&self.cluster_topology.read().unwrap());
+        if let Some(target) = self.command_palette.show(ctx, &topology) {
+            if !self.current_tab.shows_colony_image() {
+                self.current_tab = Tab::Creatures;
+                self.publish_current_tab();
+            }
+            self.palette_jump = Some((target, Instant::now()));
+            self.palette_scroll_pending = true;
+        }
         egui::CentralPanel::default().show(ctx, |ui| {
             
             // Tab control
@@ -423,72 +938,60 @@ impl App for BEImageApp {
                 ui.selectable_value(&mut self.current_tab, Tab::CostPerTurn, "Cost Per Turn");
                 ui.selectable_value(&mut self.current_tab, Tab::Health, "Health");
                 ui.selectable_value(&mut self.current_tab, Tab::Age, "Age");
+                ui.selectable_value(&mut self.current_tab, Tab::Stats, "Stats");
                 ui.selectable_value(&mut self.current_tab, Tab::Info, "Info");
                 ui.selectable_value(&mut self.current_tab, Tab::Cluster, "Cluster");
                 
                 // Update shared tab if changed
                 if self.current_tab != old_tab {
-                    if let Ok(mut shared_tab) = self.shared_current_tab.lock() {
-                        *shared_tab = self.current_tab;
-                    }
-                    // Signal background thread to wake up immediately
-                    let (lock, cvar) = &*self.tab_change_signal;
-                    *lock.lock().unwrap() = true;
-                    cvar.notify_one();
+                    self.publish_current_tab();
                 }
                 
                 // Show status indicator only when there are issues and not on Info or Cluster tabs
                 if self.current_tab != Tab::Info && self.current_tab != Tab::Cluster {
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        let last_update = *self.last_update_time.lock().unwrap();
-                        let time_since_update = last_update.elapsed();
-                        let time_since_update_ms = time_since_update.as_millis() as f64;
-
-                        // Determine responsiveness thresholds based on deployment mode.
-                        // For localhost we keep very tight thresholds (1s / 5s) so issues surface quickly.
-                        // For AWS, where the poll interval is 3s, we only mark Slow/Unresponsive
-                        // after several missed polls so transient hiccups don't look like outages.
-                        let (slow_threshold_ms, unresponsive_threshold_ms) =
-                            if self.deployment_mode == "aws" {
-                                // ~2x and ~4x the AWS poll interval
-                                (6000.0, 12000.0)
-                            } else {
-                                (1000.0, 5000.0)
-                            };
+                        // State is derived by the polling thread over the last POLL_WINDOW_CYCLES polls,
+                        // so a single slow or failed poll doesn't flip the indicator
+                        let tracker = self.responsiveness.lock().unwrap();
+                        let (state, failed, window) = (tracker.state(), tracker.failed_count(), tracker.window_len());
+                        drop(tracker);
 
-                        // Determine current responsiveness state
-                        let current_state = if time_since_update_ms > unresponsive_threshold_ms {
-                            GuiResponsivenessState::Unresponsive
-                        } else if time_since_update_ms > slow_threshold_ms {
-                            GuiResponsivenessState::Slow
-                        } else {
-                            GuiResponsivenessState::Healthy
-                        };
-                        
-                        // Check for state transition and log
-                        let mut state_guard = self.responsiveness_state.lock().unwrap();
-                        let prev_state = *state_guard;
-                        if prev_state != current_state {
-                            log!("GUI responsiveness state changed: prev={:?}, current={:?}, time_since_update_ms={:.2}", 
-                                 prev_state, current_state, time_since_update_ms);
-                            *state_guard = current_state;
+                        // Backends under load serve cached frames, see X-Colony-Stale
+                        if self.current_tab != Tab::Stats {
+                            if let Some(stale_ticks) = stale_frames::STALE_FRAMES.stale_ticks(Instant::now()) {
+                                ui.label(egui::RichText::new(format!("frames are {} ticks stale (backend busy)", stale_ticks)).weak().small());
+                            }
                         }
-                        drop(state_guard);
-                        
+
                         // Display UI indicator (only in localhost mode, not AWS)
                         if self.deployment_mode != "aws" {
-                            if time_since_update.as_secs() > 5 {
-                                ui.colored_label(egui::Color32::RED, "⚠️ Backend Unresponsive");
-                            } else if time_since_update.as_millis() > 1000 {
-                                ui.colored_label(egui::Color32::YELLOW, "🔄 Slow Response");
+                            let recent = format!("{}/{} recent polls failed", failed, window);
+                            match state {
+                                GuiResponsivenessState::Unresponsive => {
+                                    ui.colored_label(egui::Color32::RED, format!("⚠️ Backend Unresponsive ({})", recent));
+                                }
+                                GuiResponsivenessState::Slow => {
+                                    ui.colored_label(egui::Color32::YELLOW, format!("🔄 Slow Response ({})", recent));
+                                }
+                                // Don't show anything when all is well
+                                GuiResponsivenessState::Healthy => {}
                             }
-                            // Don't show anything when all is well (time_since_update <= 1000ms)
                         }
                     });
                 }
             });
             ui.separator();
-            
+            if let Some(notice) = &self.instance_notice {
+                match notice.visible(Instant::now()) {
+                    Some(message) => {
+                        ui.colored_label(egui::Color32::from_rgb(100, 200, 100), message);
+                        ui.ctx().request_repaint_after(Duration::from_millis(500));
+                    }
+                    None => self.instance_notice = None,
+                }
+            }
+            self.show_view_bar(ui);
+
             match self.current_tab {
                 Tab::Creatures => self.show_creatures_tab(ui),
                 Tab::ExtraFood => self.show_extra_food_tab(ui),
@@ -499,6 +1002,7 @@ impl App for BEImageApp {
                 Tab::CostPerTurn => self.show_cost_per_turn_tab(ui),
                 Tab::Health => self.show_health_tab(ui),
                 Tab::Age => self.show_age_tab(ui),
+                Tab::Stats => self.show_stats_tab(ui),
                 Tab::Info => self.show_info_tab(ui),
                 Tab::Cluster => self.show_cluster_tab(ui),
             }
@@ -507,55 +1011,220 @@ impl App for BEImageApp {
 }
 
 impl BEImageApp {
-    fn lerp(a: u8, b: u8, t: f32) -> u8 {
-        ((1.0 - t) * (a as f32) + t * (b as f32)).round() as u8
+    /// Hands the current tab to the polling thread and wakes it up
+    fn publish_current_tab(&self) {
+        if let Ok(mut shared_tab) = self.shared_current_tab.lock() {
+            *shared_tab = self.current_tab;
+        }
+        // Signal background thread to wake up immediately
+        let (lock, cvar) = &*self.tab_change_signal;
+        *lock.lock().unwrap() = true;
+        cvar.notify_one();
     }
 
-    fn lerp_rgb(a: (u8, u8, u8), b: (u8, u8, u8), t: f32) -> (u8, u8, u8) {
-        (
-            Self::lerp(a.0, b.0, t),
-            Self::lerp(a.1, b.1, t),
-            Self::lerp(a.2, b.2, t),
-        )
+    /// Drops everything cached from the previous colony after a restart: textures, per-shard
+    /// data, stats and events, and the view position. ShardConfig was already rebuilt by the refresh.
+    fn attach_to_new_instance(&mut self, colony_instance_id: &str) {
+        let total_shards = self.shard_config.lock().unwrap().total_shards();
+        *self.creatures.lock().unwrap() = (0..total_shards).map(|_| None).collect();
+        *self.creatures_color_data.lock().unwrap() = (0..total_shards).map(|_| None).collect();
+        for layer in [&self.extra_food, &self.sizes, &self.can_kill, &self.can_move, &self.cost_per_turn,
+                      &self.food, &self.health, &self.age, &self.sanctuary] {
+            *layer.lock().unwrap() = (0..total_shards).map(|_| None).collect();
+        }
+        *self.colony_info.lock().unwrap() = None;
+        *self.colony_events.lock().unwrap() = None;
+        *self.tick_history.lock().unwrap() = None;
+        *self.colony_config.lock().unwrap() = None;
+        *self.colony_stats.lock().unwrap() = None;
+        *self.minimap_frame.lock().unwrap() = None;
+        *self.capture_history.lock().unwrap() = CaptureHistory::default();
+        *self.shard_refresh.lock().unwrap() = ShardRefresh::default();
+        self.combined_texture = None;
+        self.minimap_texture = None;
+        self.history_texture = None;
+        self.frame_interpolator.reset();
+
+        self.zoom = ViewZoom::Scale(1);
+        self.view_center = None;
+        self.pending_center = Some(GlobalPos { x: 0, y: 0 });
+        self.inspected_cell = None;
+        self.hovered_cell = None;
+        self.palette_jump = None;
+        self.instance_notice = Some(InstanceNotice::new(colony_instance_id, Instant::now()));
+        // Refetch the current tab for the new colony right away
+        self.publish_current_tab();
     }
 
-    fn terrain_color(normalized: f32) -> egui::Color32 {
-        // From top to bottom in the image
-        let palette = [
-            (0, 102, 0),      // Dark Green
-            (0, 204, 0),      // Green
-            (153, 255, 102),  // Light Green
-            (255, 255, 128),  // Yellow
-            (222, 184, 135),  // Tan
-            (204, 51, 0),     // Red
-            (143, 10, 10),     // Dark Red
-        ];
-
-        let clamped = normalized.clamp(0.0, 1.0);
-        let scaled = clamped * (palette.len() - 1) as f32;
-        let idx = scaled.floor() as usize;
-        let t = scaled.fract();
-
-        let (r, g, b) = if idx >= palette.len() - 1 {
-            palette[palette.len() - 1]
-        } else {
-            Self::lerp_rgb(palette[idx], palette[idx + 1], t)
+    fn current_view_state(&self) -> ViewState {
+        ViewState {
+            tab: self.current_tab,
+            zoom: self.zoom,
+            center: self.view_center,
+            show_sanctuaries: *self.show_sanctuaries.lock().unwrap(),
+            show_biomes: self.show_biomes,
+            inspected_cell: self.inspected_cell,
+        }
+    }
+
+    /// Switches to the view of a colony:// link; coordinates outside the colony fall back to fit-all
+    fn apply_view_state(&mut self, mut state: ViewState) {
+        let (width, height) = {
+            let config = self.shard_config.lock().unwrap();
+            (config.total_width, config.total_height)
         };
+        let warning = state.fit_to_bounds(width, height);
+        if let Some(warning) = &warning {
+            log!("GUI view link: {}", warning);
+        }
+        self.view_link_status = warning.map(Err);
+        self.current_tab = state.tab;
+        self.zoom = state.zoom;
+        self.pending_center = state.center;
+        self.inspected_cell = state.inspected_cell;
+        self.show_biomes = state.show_biomes;
+        *self.show_sanctuaries.lock().unwrap() = state.show_sanctuaries;
+        // Also wakes the poller, which fetches the sanctuary mask if the link turned it on
+        self.publish_current_tab();
+    }
+
+    /// Zoom, inspected cell and the copy/open controls for colony:// view links
+    fn show_view_bar(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if self.current_tab.shows_colony_image() {
+                ui.label("Zoom:");
+                egui::ComboBox::from_id_salt("view_zoom")
+                    .selected_text(self.zoom.label())
+                    .show_ui(ui, |ui| {
+                        for zoom in [ViewZoom::Fit, ViewZoom::Scale(1), ViewZoom::Scale(2), ViewZoom::Scale(4), ViewZoom::Scale(8), ViewZoom::Scale(view_link::MAX_ZOOM)] {
+                            // Keep the same cell in the middle when the zoom changes
+                            if ui.selectable_value(&mut self.zoom, zoom, zoom.label()).changed() {
+                                self.pending_center = self.view_center;
+                            }
+                        }
+                    });
+                if let Some(cell) = self.inspected_cell {
+                    ui.label(format!("Inspecting {},{}", cell.x, cell.y));
+                    if ui.small_button("✕").on_hover_text("Stop inspecting").clicked() {
+                        self.inspected_cell = None;
+                    }
+                } else {
+                    ui.label(egui::RichText::new("click the image to inspect a cell").weak().small());
+                }
+                let mut show_minimap = *self.show_minimap.lock().unwrap();
+                if ui.checkbox(&mut show_minimap, "Minimap").changed() {
+                    *self.show_minimap.lock().unwrap() = show_minimap;
+                    // Build the minimap right away, AWS mode only polls on this signal
+                    self.publish_current_tab();
+                }
+                ui.checkbox(&mut self.show_event_overlay, "Event markers")
+                    .on_hover_text("Outline where region events struck, fading out over the ticks that follow");
+                if self.show_event_overlay {
+                    ui.add(egui::DragValue::new(&mut self.event_overlay_ticks).range(1..=MAX_OVERLAY_TICKS).suffix(" ticks"));
+                }
+                ui.separator();
+            }
+            if ui.button("Copy view link").clicked() {
+                let link = self.current_view_state().to_link();
+                ui.ctx().copy_text(link.clone());
+                self.view_link_status = Some(Ok(format!("Copied {}", link)));
+            }
+            let response = ui.add(egui::TextEdit::singleline(&mut self.view_link_input)
+                .hint_text("colony://creatures?x=812&y=344&zoom=4")
+                .desired_width(260.0));
+            let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            if ui.button("Open").clicked() || submitted {
+                match ViewState::parse(&self.view_link_input) {
+                    Ok(state) => self.apply_view_state(state),
+                    Err(e) => self.view_link_status = Some(Err(e)),
+                }
+            }
+            match &self.view_link_status {
+                Some(Ok(status)) => { ui.label(egui::RichText::new(status).weak().small()); }
+                Some(Err(warning)) => { ui.colored_label(egui::Color32::YELLOW, format!("⚠ {}", warning)); }
+                None => {}
+            }
+        });
+    }
 
+    fn terrain_color(normalized: f32) -> egui::Color32 {
+        let (r, g, b) = terrain_rgb(normalized);
         egui::Color32::from_rgb(r, g, b)
     }
     
     fn show_creatures_tab(&mut self, ui: &mut egui::Ui) {
-        let colors: Vec<Option<Vec<shared::be_api::Color>>> = {
+        let mut history_mode = *self.history_mode.lock().unwrap();
+        if ui.checkbox(&mut history_mode, "History")
+            .on_hover_text("Scrub through the coordinator's capture frames; live updates pause meanwhile")
+            .changed() {
+            *self.history_mode.lock().unwrap() = history_mode;
+            if history_mode {
+                self.load_capture_list(ui.ctx());
+            } else {
+                // Resume live polling right away, AWS mode only polls on this signal
+                self.publish_current_tab();
+            }
+        }
+        if history_mode {
+            self.show_capture_history(ui);
+            return;
+        }
+        let mut show_sanctuaries = *self.show_sanctuaries.lock().unwrap();
+        if ui.checkbox(&mut show_sanctuaries, "Show sanctuaries").changed() {
+            *self.show_sanctuaries.lock().unwrap() = show_sanctuaries;
+            // Fetch the mask right away, AWS mode only polls on this signal
+            let (lock, cvar) = &*self.tab_change_signal;
+            *lock.lock().unwrap() = true;
+            cvar.notify_one();
+        }
+        let mut food_tinted = *self.image_background.lock().unwrap() == ImageBackground::FoodTinted;
+        if ui.checkbox(&mut food_tinted, "Tint empty cells by food")
+            .on_hover_text("Empty cells are shaded by their food along the terrain palette")
+            .changed() {
+            *self.image_background.lock().unwrap() = if food_tinted { ImageBackground::FoodTinted } else { ImageBackground::Plain };
+            let (lock, cvar) = &*self.tab_change_signal;
+            *lock.lock().unwrap() = true;
+            cvar.notify_one();
+        }
+        ui.checkbox(&mut self.show_biomes, "Show biomes");
+        if ui.checkbox(&mut self.interpolate_frames, "Smooth between refreshes")
+            .on_hover_text("Cross-fades the previous and current image over the refresh interval")
+            .changed() && !self.interpolate_frames {
+            self.frame_interpolator.reset();
+        }
+        let mut colors: Vec<Option<Vec<shared::be_api::Color>>> = {
             let locked = self.creatures_color_data.lock().unwrap();
             locked.clone()
         };
-        self.show_combined_image(ui, &colors, |shard_data| {
+        if show_sanctuaries {
+            let sanctuary = self.sanctuary.lock().unwrap();
+            for (shard_colors, mask) in colors.iter_mut().zip(sanctuary.iter()) {
+                if let (Some(shard_colors), Some(mask)) = (shard_colors, mask) {
+                    Self::tint_sanctuaries(shard_colors, &mask.values);
+                }
+            }
+        }
+        // Only the Creatures image is interpolated; blending layer values would invent data
+        let frame_at = self.interpolate_frames.then(|| *self.creatures_frame_at.lock().unwrap());
+        self.show_combined_image(ui, &colors, frame_at, |shard_data| {
             shard_data.clone()
         });
     }
 
-    fn show_combined_image<T, F>(&mut self, ui: &mut egui::Ui, data: &[Option<T>], converter: F)
+    /// Blends a translucent gold over the cells inside a sanctuary
+    fn tint_sanctuaries(colors: &mut [shared::be_api::Color], mask: &[i32]) {
+        const TINT: (u8, u8, u8) = (255, 200, 0);
+        const ALPHA: f32 = 0.35;
+        for (color, &inside) in colors.iter_mut().zip(mask) {
+            if inside == 1 {
+                let (red, green, blue) = lerp_rgb((color.red, color.green, color.blue), TINT, ALPHA);
+                *color = shared::be_api::Color { red, green, blue };
+            }
+        }
+    }
+
+    /// frame_at is set when the image should be cross-faded from the previous frame, see FrameInterpolator
+    fn show_combined_image<T, F>(&mut self, ui: &mut egui::Ui, data: &[Option<T>], frame_at: Option<Instant>, converter: F)
     where
         F: Fn(&Option<T>) -> Option<Vec<shared::be_api::Color>>,
     {
@@ -563,7 +1232,8 @@ impl BEImageApp {
         let config = self.shard_config.lock().unwrap();
         let total_width = config.total_width as usize;
         let total_height = config.total_height as usize;
-        let mut combined_img = egui::ColorImage::new([total_width, total_height], egui::Color32::BLACK);
+        // Shards whose data has not arrived stay gray
+        let mut combined_img = egui::ColorImage::new([total_width, total_height], MISSING_SHARD_COLOR);
         
         // Process each shard
         for (idx, shard_data) in data.iter().enumerate() {
@@ -600,13 +1270,29 @@ impl BEImageApp {
             }
         }
         
-        // Upload/update a persistent texture and display it
-        let texture_options = egui::TextureOptions::LINEAR;
-        let display_width = config.total_width as f32;
-        let display_height = config.total_height as f32;
+        if let Some(frame_at) = frame_at {
+            let now = Instant::now();
+            combined_img = self.frame_interpolator.blend(combined_img, frame_at, now);
+            if self.frame_interpolator.is_fading(now) {
+                ui.ctx().request_repaint();
+            }
+        }
+
+        // Points per cell; fit-all shrinks or grows the colony into the space left for the image
+        let scale = match self.zoom {
+            ViewZoom::Fit => {
+                let available = ui.available_size();
+                (available.x / config.total_width.max(1) as f32).min(available.y / config.total_height.max(1) as f32).max(0.01)
+            }
+            ViewZoom::Scale(scale) => scale as f32,
+        };
+        // Zoomed in, cells stay crisp squares instead of blurring into each other
+        let texture_options = if scale > 1.0 { egui::TextureOptions::NEAREST } else { egui::TextureOptions::LINEAR };
+        let display_width = config.total_width as f32 * scale;
+        let display_height = config.total_height as f32 * scale;
         
-        // Update or create texture
-        if let Some(tex) = &mut self.combined_texture {
+        // Update or create texture; a handle keeps the size it was created with, so a resized colony needs a new one
+        if let Some(tex) = self.combined_texture.as_mut().filter(|tex| tex.size() == combined_img.size) {
             tex.set(combined_img, texture_options);
         } else {
             let tex = ui.ctx().load_texture("combined", combined_img, texture_options);
@@ -614,68 +1300,230 @@ impl BEImageApp {
         }
         
         // Wrap in scroll area to allow horizontal and vertical scrolling
-        egui::ScrollArea::both()
-            .auto_shrink([false; 2])
-            .show(ui, |ui| {
+        let mut scroll_area = egui::ScrollArea::both().auto_shrink([false; 2]);
+        if let Some(center) = self.pending_center.take() {
+            let offset = egui::vec2(center.x as f32 + 0.5, center.y as f32 + 0.5) * scale - ui.available_size() / 2.0;
+            scroll_area = scroll_area.scroll_offset(offset.max(egui::Vec2::ZERO));
+        }
+        let output = scroll_area.show(ui, |ui| {
                 if let Some(tex) = &self.combined_texture {
-                    ui.add(
+                    let response = ui.add(
                         egui::Image::new(tex)
                             .fit_to_exact_size(egui::vec2(display_width, display_height))
+                            .sense(egui::Sense::click())
                     );
+                    // Hover readout and click-to-inspect share one transform, so they always name the same cell
+                    self.hovered_cell = response.hover_pos().and_then(|pos| screen_to_colony(response.rect, scale, pos));
+                    if response.clicked() && self.hovered_cell.is_some() {
+                        self.inspected_cell = self.hovered_cell;
+                    }
+                    draw_frozen_shards(ui, response.rect, scale, &config, &self.frozen_shards.lock().unwrap());
+                    draw_stale_shards(ui, response.rect, scale, &config, &self.shard_refresh.lock().unwrap().stale_shards(self.current_tab));
+                    if self.show_biomes {
+                        draw_biomes(ui, response.rect, scale, &self.biomes.lock().unwrap());
+                    }
+                    if self.show_event_overlay {
+                        // The feed keeps the tick samples current
+                        let current_tick = self.tick_history.lock().unwrap().as_ref().and_then(|samples| samples.last()).map(|sample| sample.max_tick);
+                        if let Some(current_tick) = current_tick {
+                            let markers = active_markers(&self.region_events.lock().unwrap(), current_tick, self.event_overlay_ticks);
+                            draw_event_markers(ui, response.rect, scale, &markers);
+                        }
+                    }
+                    if let Some(cell) = self.inspected_cell {
+                        draw_inspected_cell(ui, response.rect, scale, cell);
+                    }
+                    if let Some((target, since)) = self.palette_jump {
+                        if std::mem::take(&mut self.palette_scroll_pending) {
+                            let area = target.area();
+                            ui.scroll_to_rect(colony_rect_to_screen(response.rect, scale, area.x, area.y, area.width, area.height), Some(egui::Align::Center));
+                        }
+                        draw_flash(ui, response.rect, scale, &target, since);
+                    }
                 }
             });
+        let visible = egui::Rect::from_min_size((output.state.offset / scale).to_pos2(), output.inner_rect.size() / scale);
+        *self.visible_area.lock().unwrap() = Some(visible);
+        if *self.show_minimap.lock().unwrap() {
+            let frame = self.minimap_frame.lock().unwrap();
+            if let Some(frame) = frame.as_ref().filter(|frame| frame.tab == self.current_tab) {
+                // Uploaded once per poll, not every UI frame
+                if self.minimap_texture.as_ref().is_none_or(|(generation, _)| *generation != frame.generation) {
+                    let texture = ui.ctx().load_texture("minimap", frame.image.clone(), egui::TextureOptions::LINEAR);
+                    self.minimap_texture = Some((frame.generation, texture));
+                }
+                if let Some((_, texture)) = &self.minimap_texture {
+                    let image_size = egui::vec2(frame.image.size[0] as f32, frame.image.size[1] as f32);
+                    let size = image_size * (MINIMAP_MAX_SIDE as f32 / image_size.max_elem().max(1.0));
+                    let rect = egui::Rect::from_min_size(output.inner_rect.max - size - egui::vec2(12.0, 12.0), size);
+                    let colony_size = egui::vec2(config.total_width as f32, config.total_height as f32);
+                    if let Some(center) = minimap::show_minimap(ui, rect, texture, colony_size, visible) {
+                        self.pending_center = Some(center);
+                        ui.ctx().request_repaint();
+                    }
+                }
+            }
+        }
+        let center = (output.state.offset + output.inner_rect.size() / 2.0) / scale;
+        self.view_center = Some(GlobalPos::new(
+            (center.x as i32).clamp(0, (config.total_width - 1).max(0)),
+            (center.y as i32).clamp(0, (config.total_height - 1).max(0)),
+        ));
     }
 
-    fn show_layer_tab(&mut self, ui: &mut egui::Ui, data: &Arc<Mutex<Vec<Option<Vec<i32>>>>>) {
+    /// Fetches the capture list in the background; the latest frame is selected once it arrives
+    fn load_capture_list(&self, ctx: &egui::Context) {
+        {
+            let mut history = self.capture_history.lock().unwrap();
+            history.frames = None;
+            history.error = None;
+        }
+        let capture_history = Arc::clone(&self.capture_history);
+        let coordinator_http_info = self.coordinator_http_info.clone();
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let result = call_be::get_capture_list(coordinator_http_info.as_ref());
+            let mut history = capture_history.lock().unwrap();
+            match result {
+                Ok(list) => history.set_frames(list.frames),
+                Err(e) => history.error = Some(e),
+            }
+            ctx.request_repaint();
+        });
+    }
+
+    fn fetch_capture_frame(&self, ctx: &egui::Context, frame: CaptureFrameEntry) {
+        let capture_history = Arc::clone(&self.capture_history);
+        let coordinator_http_info = self.coordinator_http_info.clone();
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let result = call_be::get_capture_frame(&frame.url, coordinator_http_info.as_ref())
+                .and_then(|png| capture_history::decode_frame(&png));
+            let mut history = capture_history.lock().unwrap();
+            history.loading = None;
+            match result {
+                Ok(image) => history.cache.insert(frame.tick, Arc::new(image)),
+                Err(e) => {
+                    log!("GUI failed to load capture frame {}: {}", frame.tick, e);
+                    history.failed_frame = Some((frame.tick, e));
+                }
+            }
+            ctx.request_repaint();
+        });
+    }
+
+    /// Slider and left/right arrows over the capture ticks, showing the selected frame
+    fn show_capture_history(&mut self, ui: &mut egui::Ui) {
+        if !ui.ctx().wants_keyboard_input() {
+            let delta = ui.input(|i| i.key_pressed(egui::Key::ArrowRight) as isize - i.key_pressed(egui::Key::ArrowLeft) as isize);
+            if delta != 0 {
+                self.capture_history.lock().unwrap().step(delta);
+            }
+        }
+        let mut history = self.capture_history.lock().unwrap();
+        let Some(frames) = history.frames.as_ref() else {
+            match &history.error {
+                Some(e) => { ui.colored_label(egui::Color32::YELLOW, format!("⚠ Could not load the captures: {}", e)); }
+                None => { ui.label("Loading captures..."); }
+            }
+            drop(history);
+            if ui.button("Retry").clicked() {
+                self.load_capture_list(ui.ctx());
+            }
+            return;
+        };
+        if frames.is_empty() {
+            drop(history);
+            ui.label("No captures yet. The coordinator saves a frame every few minutes while the colony ticks.");
+            if ui.button("Check again").clicked() {
+                self.load_capture_list(ui.ctx());
+            }
+            return;
+        }
+
+        let count = frames.len();
+        let frame = frames[history.selected].clone();
+        let mut reload = false;
+        ui.horizontal(|ui| {
+            ui.heading(format!("Tick {}", frame.tick));
+            ui.label(egui::RichText::new(format!("frame {} of {}, captured {}", history.selected + 1, count, frame.timestamp)).weak());
+            if !frame.missing_shards.is_empty() {
+                ui.colored_label(egui::Color32::YELLOW, format!("{} shards missing", frame.missing_shards.len()));
+            }
+            reload = ui.button("Reload list").clicked();
+        });
+        ui.horizontal(|ui| {
+            let mut selected = history.selected;
+            ui.spacing_mut().slider_width = 600.0;
+            ui.add(egui::Slider::new(&mut selected, 0..=count - 1).show_value(false));
+            history.selected = selected;
+            ui.label(egui::RichText::new("← / → step one frame").weak().small());
+        });
+        if let Some((tick, e)) = &history.failed_frame {
+            ui.colored_label(egui::Color32::YELLOW, format!("⚠ Frame {} could not be loaded: {}", tick, e));
+        }
+
+        let tick = history.selected_frame().map_or(frame.tick, |frame| frame.tick);
+        let to_fetch = history.frame_to_fetch();
+        if let Some(to_fetch) = &to_fetch {
+            history.loading = Some(to_fetch.tick);
+        }
+        let image = history.cache.get(tick).cloned();
+        drop(history);
+        if let Some(to_fetch) = to_fetch {
+            self.fetch_capture_frame(ui.ctx(), to_fetch);
+        }
+        if reload {
+            self.load_capture_list(ui.ctx());
+        }
+
+        // Until the selected frame arrives, the previous one stays up
+        if let Some(image) = image.filter(|_| self.history_texture.as_ref().is_none_or(|(shown, _)| *shown != tick)) {
+            let texture = ui.ctx().load_texture("history", (*image).clone(), egui::TextureOptions::NEAREST);
+            self.history_texture = Some((tick, texture));
+        }
+        let Some((shown, texture)) = &self.history_texture else {
+            ui.label("Loading frame...");
+            return;
+        };
+        if *shown != tick {
+            ui.label(egui::RichText::new(format!("loading tick {}, showing tick {}", tick, shown)).weak().small());
+        }
+        let size = texture.size_vec2();
+        let scale = match self.zoom {
+            ViewZoom::Fit => {
+                let available = ui.available_size();
+                (available.x / size.x.max(1.0)).min(available.y / size.y.max(1.0)).max(0.01)
+            }
+            ViewZoom::Scale(scale) => scale as f32,
+        };
+        egui::ScrollArea::both().auto_shrink([false; 2]).show(ui, |ui| {
+            ui.add(egui::Image::new(texture).fit_to_exact_size(size * scale));
+        });
+    }
+
+    fn show_layer_tab(&mut self, ui: &mut egui::Ui, data: &Arc<Mutex<Vec<Option<ShardLayerData>>>>) {
         self.show_layer_tab_with_legend(ui, data, None)
     }
 
-    fn show_layer_tab_with_legend(&mut self, ui: &mut egui::Ui, data: &Arc<Mutex<Vec<Option<Vec<i32>>>>>, legend_max_value: Option<i32>) {
-        let locked_vec: Vec<Option<Vec<i32>>> = {
+    fn show_layer_tab_with_legend(&mut self, ui: &mut egui::Ui, data: &Arc<Mutex<Vec<Option<ShardLayerData>>>>, legend_max_value: Option<i32>) {
+        let locked_vec: Vec<Option<ShardLayerData>> = {
             let locked = data.lock().unwrap();
             locked.clone()
         };
         
-        // Find global maximum across all shards for consistent normalization
-        let global_max = locked_vec.iter()
-            .filter_map(|shard_data| shard_data.as_ref())
-            .flat_map(|data| data.iter())
-            .max()
-            .copied()
-            .unwrap_or(0);
-
         // Use provided legend values or calculate from data
         let legend_min = 0;
+        let global_max = layer_scale_max(&locked_vec, legend_max_value);
         let legend_max = legend_max_value.unwrap_or(global_max);
-        let global_max = legend_max.max(global_max);
-
-        self.show_combined_image(ui, &locked_vec, |shard_data| {
-            if let Some(data) = shard_data {
-                if global_max > 0 {
-                    // Convert i32 data to colors using global normalization
-                    let mut colors = Vec::new();
-                    for &val in data {
-                        if val == 0 {
-                            colors.push(shared::be_api::Color { red: 255, green: 255, blue: 255, });
-                        } else {
-                            let normalized = val as f32 / global_max as f32;
-                            let color = Self::terrain_color(normalized);
-                            colors.push(shared::be_api::Color { red: color.r(), green: color.g(), blue: color.b(), });
-                        }
-                    }
-                    Some(colors)
-                } else {
-                    // All values are 0, use white
-                    let mut colors = Vec::new();
-                    for _ in 0..data.len() {
-                        colors.push(shared::be_api::Color { red: 255, green: 255, blue: 255, });
-                    }
-                    Some(colors)
-                }
-            } else {
-                None
-            }
+        let coloring = LayerColoring::Scaled { max: global_max };
+
+        self.show_combined_image(ui, &locked_vec, None, |shard_data| {
+            shard_data.as_ref().map(|data| data.values.iter().map(|&val| to_be_color(coloring.color(val))).collect())
         });
+        // Hidden outside the colony; an empty line keeps the legend from jumping
+        let readout = self.hovered_cell.and_then(|cell| cell_readout::cell_readout(&self.shard_config.lock().unwrap(), &locked_vec, cell));
+        ui.label(egui::RichText::new(readout.unwrap_or_default()).monospace().small());
         
         // Add legend below the image
         if global_max > 0 {
@@ -714,13 +1562,20 @@ impl BEImageApp {
     }
 
     fn show_extra_food_tab(&mut self, ui: &mut egui::Ui) {
+        if self.colony_config.lock().unwrap().is_none() {
+            if let Some(config) = call_be::get_colony_config(self.coordinator_http_info.as_ref()) {
+                *self.colony_config.lock().unwrap() = Some(config);
+            }
+        }
+        let pattern = self.colony_config.lock().unwrap().as_ref().map(|response| extra_food_pattern_label(&response.config));
+        ui.label(format!("Initial layout: {}", pattern.unwrap_or_else(|| "unknown".to_string())));
         let extra_food = self.extra_food.clone();
         self.show_layer_tab(ui, &extra_food);
     }
 
     fn show_sizes_tab(&mut self, ui: &mut egui::Ui) {
         let sizes = self.sizes.clone();
-        self.show_layer_tab_with_legend(ui, &sizes, Some(MIN_CREATURE_SIZE_LEGEND_MAX));
+        self.show_layer_tab_with_legend(ui, &sizes, Tab::Sizes.legend_max());
     }
 
     fn show_can_kill_tab(&mut self, ui: &mut egui::Ui) {
@@ -740,12 +1595,12 @@ impl BEImageApp {
 
     fn show_food_tab(&mut self, ui: &mut egui::Ui) {
         let food = self.food.clone();
-        self.show_layer_tab_with_legend(ui, &food, Some(FOOD_VALUE_LEGEND_MAX));
+        self.show_layer_tab_with_legend(ui, &food, Tab::Food.legend_max());
     }
 
     fn show_health_tab(&mut self, ui: &mut egui::Ui) {
         let health = self.health.clone();
-        self.show_layer_tab_with_legend(ui, &health, Some(10)); 
+        self.show_layer_tab_with_legend(ui, &health, Tab::Health.legend_max());
     }
 
     fn show_age_tab(&mut self, ui: &mut egui::Ui) {
@@ -753,64 +1608,163 @@ impl BEImageApp {
         self.show_layer_tab(ui, &age);
     }
 
-    fn show_layer_tab_boolean(&mut self, ui: &mut egui::Ui, data: &Arc<Mutex<Vec<Option<Vec<i32>>>>>) {
-        let locked_vec: Vec<Option<Vec<i32>>> = {
+    fn show_layer_tab_boolean(&mut self, ui: &mut egui::Ui, data: &Arc<Mutex<Vec<Option<ShardLayerData>>>>) {
+        let locked_vec: Vec<Option<ShardLayerData>> = {
             let locked = data.lock().unwrap();
             locked.clone()
         };
-        self.show_combined_image(ui, &locked_vec, |shard_data| {
-            if let Some(data) = shard_data {
-                // Convert i32 data to colors using boolean mapping
-                let mut colors = Vec::new();
-                for &val in data {
-                    match val {
-                        1 => {
-                            let color = Self::terrain_color(0.0);
-                            colors.push(shared::be_api::Color { red: color.r(), green: color.g(), blue: color.b() });
-                        }
-                        2 => {
-                            let color = Self::terrain_color(1.0);
-                            colors.push(shared::be_api::Color { red: color.r(), green: color.g(), blue: color.b() });
+        self.show_combined_image(ui, &locked_vec, None, |shard_data| {
+            shard_data.as_ref().map(|data| data.values.iter().map(|&val| to_be_color(LayerColoring::Boolean.color(val))).collect())
+        });
+    }
+
+    fn rule_range_tooltip(field: &str) -> String {
+        match ColonyLifeRules::allowed_range(field) {
+            Some(range) => format!("Allowed range: {} to {}", Self::format_number_with_commas(range.min as u64), Self::format_number_with_commas(range.max as u64)),
+            None => String::new(),
+        }
+    }
+
+    fn format_number_with_commas(num: u64) -> String {
+        let num_str = num.to_string();
+        let mut result = String::new();
+        let chars: Vec<char> = num_str.chars().collect();
+        
+        for (i, &ch) in chars.iter().enumerate() {
+            if i > 0 && (chars.len() - i) % 3 == 0 {
+                result.push(',');
+            }
+            result.push(ch);
+        }
+        
+        result
+    }
+
+    fn show_stats_tab(&mut self, ui: &mut egui::Ui) {
+        let mut use_viewport = *self.stats_viewport_region.lock().unwrap();
+        let viewport_known = self.visible_area.lock().unwrap().is_some();
+        if ui.add_enabled(viewport_known, egui::Checkbox::new(&mut use_viewport, "Use current viewport as region"))
+            .on_hover_text("Stats of the cells visible on the image tabs instead of the whole colony")
+            .on_disabled_hover_text("Open an image tab first")
+            .changed() {
+            *self.stats_viewport_region.lock().unwrap() = use_viewport;
+            // Refetch right away, AWS mode only polls on this signal
+            let (lock, cvar) = &*self.tab_change_signal;
+            *lock.lock().unwrap() = true;
+            cvar.notify_one();
+        }
+        let Some(response) = self.colony_stats.lock().unwrap().clone() else {
+            ui.label("Colony stats not available yet");
+            return;
+        };
+        ui.label(format!(
+            "Tick {} ({})",
+            Self::format_number_with_commas(response.tick),
+            if response.cached { format!("cached, {} ms old", response.age_ms) } else { "fresh".to_string() }
+        ));
+        match (response.region, response.region_cells) {
+            (Some(region), Some(cells)) => {
+                ui.label(format!("Region ({}, {}) {}x{}, {} cells",
+                    region.x, region.y, region.width, region.height, Self::format_number_with_commas(cells)));
+            }
+            (None, Some(_)) => {
+                ui.label("The region is outside the colony");
+            }
+            _ => {}
+        }
+        ui.horizontal(|ui| {
+            if ui.button("Export").on_hover_text(format!("Write CSV and JSON to {}", stats_export::export_dir().display())).clicked() {
+                let stats = response.clone();
+                let tick_history = self.tick_history.lock().unwrap().clone();
+                let stats_export_status = Arc::clone(&self.stats_export_status);
+                let ctx = ui.ctx().clone();
+                thread::spawn(move || {
+                    let dir = stats_export::export_dir();
+                    let status = match stats_export::export_stats(&dir, &stats, tick_history.as_deref()) {
+                        Ok(paths) => format!("Exported {} files for tick {} to {}", paths.len(), stats.tick, dir.display()),
+                        Err(e) => format!("Export failed: {}", e),
+                    };
+                    log!("{}", status);
+                    *stats_export_status.lock().unwrap() = Some(status);
+                    ctx.request_repaint();
+                });
+            }
+            if let Some(status) = self.stats_export_status.lock().unwrap().as_ref() {
+                ui.label(status);
+            }
+        });
+        ui.add_space(6.0);
+
+        egui::ScrollArea::vertical().auto_shrink([false; 2]).show(ui, |ui| {
+            if !response.species.is_empty() {
+                ui.group(|ui| {
+                    ui.strong("Dominant species");
+                    ui.horizontal_wrapped(|ui| {
+                        for species in &response.species {
+                            let color = egui::Color32::from_rgb(species.color.red, species.color.green, species.color.blue);
+                            let (rect, swatch) = ui.allocate_exact_size(egui::vec2(18.0, 18.0), egui::Sense::hover());
+                            ui.painter().rect_filled(rect, 3.0, color);
+                            ui.painter().rect_stroke(rect, 3.0, egui::Stroke::new(1.0, ui.visuals().weak_text_color()));
+                            swatch.on_hover_text(format!(
+                                "{}\nrgb({}, {}, {})\n{} creatures",
+                                species.cluster_id, species.color.red, species.color.green, species.color.blue,
+                                Self::format_number_with_commas(species.count)
+                            ));
+                            ui.label(format!("{:.1}%", species.share * 100.0));
+                            ui.add_space(8.0);
                         }
-                        _ => {
-                            colors.push(shared::be_api::Color { red: 255, green: 255, blue: 255 });
+                    });
+                });
+                ui.add_space(6.0);
+            }
+            for metric_stats in &response.stats {
+                let name = format!("{:?}", metric_stats.metric);
+                let log_scale = self.stats_log_scale.entry(name.clone()).or_insert(false);
+                ui.group(|ui| {
+                    ui.horizontal(|ui| {
+                        ui.strong(&name);
+                        ui.label(format!("avg {:.2}", metric_stats.avg));
+                        // Counted over every cell, so the average is a share of the world
+                        if matches!(metric_stats.metric, StatMetric::Occupancy | StatMetric::FoodCoverage) {
+                            ui.label(format!("({:.1}% of cells)", metric_stats.avg * 100.0));
                         }
-                    }
-                }
-                Some(colors)
-            } else {
-                None
+                        ui.checkbox(log_scale, "Log scale");
+                    });
+                    draw_histogram(ui, &metric_stats.buckets, &HistogramOptions {
+                        log_scale: *log_scale,
+                        avg: Some(metric_stats.avg),
+                        ..HistogramOptions::default()
+                    });
+                });
+                ui.add_space(6.0);
             }
         });
     }
 
-    fn format_number_with_commas(num: u64) -> String {
-        let num_str = num.to_string();
-        let mut result = String::new();
-        let chars: Vec<char> = num_str.chars().collect();
-        
-        for (i, &ch) in chars.iter().enumerate() {
-            if i > 0 && (chars.len() - i) % 3 == 0 {
-                result.push(',');
-            }
-            result.push(ch);
-        }
-        
-        result
-    }
-
     fn show_info_tab(&self, ui: &mut egui::Ui) {
         
         
         // Always refresh data when Info tab is accessed
-        if let Some(info) = call_be::get_colony_info(self.cluster_topology.as_ref(), &self.backend_http_info) {
+        let cluster_topology = Arc::clone(&self.cluster_topology.read().unwrap());
+        if let Some(info) = call_be::get_colony_info(cluster_topology.as_ref(), &self.backend_http_info.read().unwrap()) {
             let mut locked = self.colony_info.lock().unwrap();
             *locked = Some(info);
         }
         
-        if let Some(events) = call_be::get_colony_events(30, self.coordinator_http_info.as_ref()) {
-            let mut locked = self.colony_events.lock().unwrap();
-            *locked = Some(events);
+        if !*self.feed_connected.lock().unwrap() {
+            refresh_events_and_tick_history(&self.colony_events, &self.tick_history, self.coordinator_http_info.as_ref());
+        }
+        
+        if self.colony_config.lock().unwrap().is_none() {
+            if let Some(config) = call_be::get_colony_config(self.coordinator_http_info.as_ref()) {
+                *self.colony_config.lock().unwrap() = Some(config);
+            }
+        }
+        
+        if !self.observer_mode && self.ticker_paused.lock().unwrap().is_none() {
+            if let Some(state) = call_be::get_ticker_state(self.coordinator_http_info.as_ref()) {
+                *self.ticker_paused.lock().unwrap() = Some(state.paused);
+            }
         }
         
         // Get cached colony info
@@ -827,6 +1781,16 @@ impl BEImageApp {
                 } else {
                     ui.label("Current Tick: Not available");
                 }
+                
+                if let Some(samples) = self.tick_history.lock().unwrap().as_ref() {
+                    ui.label(format!("Tick progression, last {} minutes (max tick, min-max band):", TICK_HISTORY_MINUTES));
+                    draw_tick_sparkline(ui, samples, egui::vec2(300.0, 40.0));
+                }
+                
+                // Mutating actions, hidden for observers
+                if !self.observer_mode {
+                    self.show_ticker_controls(ui);
+                }
             });
             
             ui.add_space(10.0);
@@ -836,20 +1800,13 @@ impl BEImageApp {
                 ui.group(|ui| {
                     
                     // Initial rules for comparison
-                    const INITIAL_RULES: ColonyLifeRules = ColonyLifeRules {
-                        health_cost_per_size_unit: 2,
-                        eat_capacity_per_size_unit: 5,
-                        health_cost_if_can_kill: 10,
-                        health_cost_if_can_move: 5,
-                        mutation_chance: 100,
-                        random_death_chance: 100,
-                    };
+                    const INITIAL_RULES: ColonyLifeRules = COLONY_LIFE_INITIAL_RULES;
                     
                     egui::Grid::new("colony_life_rules_grid")
                         .num_columns(2)
                         .spacing([20.0, 4.0])
                         .show(ui, |ui| {
-                            ui.label("Health Cost Per Size Unit:");
+                            ui.label("Health Cost Per Size Unit:").on_hover_text(Self::rule_range_tooltip("health_cost_per_size_unit"));
                             let current = life_info.health_cost_per_size_unit;
                             let initial = INITIAL_RULES.health_cost_per_size_unit;
                             if current != initial {
@@ -859,7 +1816,7 @@ impl BEImageApp {
                             }
                             ui.end_row();
                             
-                            ui.label("Eat Capacity Per Size Unit:");
+                            ui.label("Eat Capacity Per Size Unit:").on_hover_text(Self::rule_range_tooltip("eat_capacity_per_size_unit"));
                             let current = life_info.eat_capacity_per_size_unit;
                             let initial = INITIAL_RULES.eat_capacity_per_size_unit;
                             if current != initial {
@@ -869,7 +1826,7 @@ impl BEImageApp {
                             }
                             ui.end_row();
                             
-                            ui.label("Health Cost If Can Kill:");
+                            ui.label("Health Cost If Can Kill:").on_hover_text(Self::rule_range_tooltip("health_cost_if_can_kill"));
                             let current = life_info.health_cost_if_can_kill;
                             let initial = INITIAL_RULES.health_cost_if_can_kill;
                             if current != initial {
@@ -879,7 +1836,7 @@ impl BEImageApp {
                             }
                             ui.end_row();
                             
-                            ui.label("Health Cost If Can Move:");
+                            ui.label("Health Cost If Can Move:").on_hover_text(Self::rule_range_tooltip("health_cost_if_can_move"));
                             let current = life_info.health_cost_if_can_move;
                             let initial = INITIAL_RULES.health_cost_if_can_move;
                             if current != initial {
@@ -889,7 +1846,7 @@ impl BEImageApp {
                             }
                             ui.end_row();
                             
-                            ui.label("Mutation Chance:");
+                            ui.label("Mutation Chance:").on_hover_text(Self::rule_range_tooltip("mutation_chance"));
                             let current = life_info.mutation_chance;
                             let initial = INITIAL_RULES.mutation_chance;
                             if current != initial {
@@ -899,7 +1856,7 @@ impl BEImageApp {
                             }
                             ui.end_row();
                             
-                            ui.label("Random Death Chance:");
+                            ui.label("Random Death Chance:").on_hover_text(Self::rule_range_tooltip("random_death_chance"));
                             let current = life_info.random_death_chance;
                             let initial = INITIAL_RULES.random_death_chance;
                             if current != initial {
@@ -908,12 +1865,145 @@ impl BEImageApp {
                                 ui.label(format!("{}", current));
                             }
                             ui.end_row();
+                            
+                            ui.label("Kill Success Base Chance:").on_hover_text(Self::rule_range_tooltip("kill_success_base_chance"));
+                            let current = life_info.kill_success_base_chance;
+                            let initial = INITIAL_RULES.kill_success_base_chance;
+                            if current != initial {
+                                ui.label(format!("{} (initial={})", current, initial));
+                            } else {
+                                ui.label(format!("{}", current));
+                            }
+                            ui.end_row();
+                            
+                            ui.label("Kill Size Advantage Percent:").on_hover_text(Self::rule_range_tooltip("kill_size_advantage_percent"));
+                            let current = life_info.kill_size_advantage_percent;
+                            let initial = INITIAL_RULES.kill_size_advantage_percent;
+                            if current != initial {
+                                ui.label(format!("{} (initial={})", current, initial));
+                            } else {
+                                ui.label(format!("{}", current));
+                            }
+                            ui.end_row();
+                            
+                            ui.label("Kill Counter Damage:").on_hover_text(Self::rule_range_tooltip("kill_counter_damage"));
+                            let current = life_info.kill_counter_damage;
+                            let initial = INITIAL_RULES.kill_counter_damage;
+                            if current != initial {
+                                ui.label(format!("{} (initial={})", current, initial));
+                            } else {
+                                ui.label(format!("{}", current));
+                            }
+                            ui.end_row();
+                            
+                            ui.label("Reproduction Food Cost:").on_hover_text(Self::rule_range_tooltip("reproduction_food_cost"));
+                            let current = life_info.reproduction_food_cost;
+                            let initial = INITIAL_RULES.reproduction_food_cost;
+                            if current != initial {
+                                ui.label(format!("{} (initial={})", current, initial));
+                            } else {
+                                ui.label(format!("{}", current));
+                            }
+                            ui.end_row();
+                            
+                            ui.label("Reproduction Min Food:").on_hover_text(Self::rule_range_tooltip("reproduction_min_food"));
+                            let current = life_info.reproduction_min_food;
+                            let initial = INITIAL_RULES.reproduction_min_food;
+                            if current != initial {
+                                ui.label(format!("{} (initial={})", current, initial));
+                            } else {
+                                ui.label(format!("{}", current));
+                            }
+                            ui.end_row();
+                            
+                            ui.label("Mutation Size Step:").on_hover_text(Self::rule_range_tooltip("mutation_size_step"));
+                            let current = life_info.mutation_size_step;
+                            let initial = INITIAL_RULES.mutation_size_step;
+                            if current != initial {
+                                ui.label(format!("{} (initial={})", current, initial));
+                            } else {
+                                ui.label(format!("{}", current));
+                            }
+                            ui.end_row();
+                            
+                            ui.label("Mutation Cost Step:").on_hover_text(Self::rule_range_tooltip("mutation_cost_step"));
+                            let current = life_info.mutation_cost_step;
+                            let initial = INITIAL_RULES.mutation_cost_step;
+                            if current != initial {
+                                ui.label(format!("{} (initial={})", current, initial));
+                            } else {
+                                ui.label(format!("{}", current));
+                            }
+                            ui.end_row();
+                            
+                            ui.label("Boolean Trait Flip Chance:").on_hover_text(Self::rule_range_tooltip("boolean_trait_flip_chance"));
+                            let current = life_info.boolean_trait_flip_chance;
+                            let initial = INITIAL_RULES.boolean_trait_flip_chance;
+                            if current != initial {
+                                ui.label(format!("{} (initial={})", current, initial));
+                            } else {
+                                ui.label(format!("{}", current));
+                            }
+                            ui.end_row();
+                            
+                            ui.label("Color Drift Per Generation:").on_hover_text(Self::rule_range_tooltip("color_drift_per_generation"));
+                            let current = life_info.color_drift_per_generation;
+                            let initial = INITIAL_RULES.color_drift_per_generation;
+                            if current != initial {
+                                ui.label(format!("{} (initial={})", current, initial));
+                            } else {
+                                ui.label(format!("{}", current));
+                            }
+                            ui.end_row();
+                            
+                            ui.label("Color Mutation Chance:").on_hover_text(Self::rule_range_tooltip("color_mutation_chance"));
+                            let current = life_info.color_mutation_chance;
+                            let initial = INITIAL_RULES.color_mutation_chance;
+                            if current != initial {
+                                ui.label(format!("{} (initial={})", current, initial));
+                            } else {
+                                ui.label(format!("{}", current));
+                            }
+                            ui.end_row();
+                            
+                            ui.label("Starvation Grace Ticks:").on_hover_text(Self::rule_range_tooltip("starvation_grace_ticks"));
+                            let current = life_info.starvation_grace_ticks;
+                            let initial = INITIAL_RULES.starvation_grace_ticks;
+                            if current != initial {
+                                ui.label(format!("{} (initial={})", current, initial));
+                            } else {
+                                ui.label(format!("{}", current));
+                            }
+                            ui.end_row();
+                            
+                            ui.label("Food Sharing Radius:").on_hover_text(Self::rule_range_tooltip("food_sharing_radius"));
+                            let current = life_info.food_sharing_radius;
+                            let initial = INITIAL_RULES.food_sharing_radius;
+                            if current != initial {
+                                ui.label(format!("{} (initial={})", current, initial));
+                            } else {
+                                ui.label(format!("{}", current));
+                            }
+                            ui.end_row();
+                            
+                            ui.label("Food Sharing Percent:").on_hover_text(Self::rule_range_tooltip("food_sharing_percent"));
+                            let current = life_info.food_sharing_percent;
+                            let initial = INITIAL_RULES.food_sharing_percent;
+                            if current != initial {
+                                ui.label(format!("{} (initial={})", current, initial));
+                            } else {
+                                ui.label(format!("{}", current));
+                            }
+                            ui.end_row();
                         });
                 });
             } else {
                 ui.label("Colony Life Configuration: Not available");
             }
             
+            ui.add_space(10.0);
+            self.show_run_configuration(ui);
+            
             ui.add_space(20.0);
             
             // Display colony events
@@ -924,12 +2014,25 @@ impl BEImageApp {
                     if events.is_empty() {
                         ui.label("No events recorded yet.");
                     } else {
+                        let mut filter = self.events_filter.lock().unwrap();
+                        ui.horizontal(|ui| {
+                            ui.label("Event type:");
+                            egui::ComboBox::from_id_salt("events_filter")
+                                .selected_text(filter.as_deref().unwrap_or("All"))
+                                .show_ui(ui, |ui| {
+                                    ui.selectable_value(&mut *filter, None, "All");
+                                    for event_type in Self::event_type_options(events) {
+                                        ui.selectable_value(&mut *filter, Some(event_type.clone()), event_type);
+                                    }
+                                });
+                        });
                         egui::Grid::new("colony_events_grid")
-                            .num_columns(3)
+                            .num_columns(4)
                             .spacing([20.0, 4.0])
                             .show(ui, |ui| {
                                 // Header row
                                 ui.label("Tick");
+                                ui.label("Applied At").on_hover_text("Shard ticks at which backends applied the event, when they differ from Tick");
                                 ui.label("Event Type");
                                 ui.label("Description");
                                 ui.end_row();
@@ -937,11 +2040,13 @@ impl BEImageApp {
                                 ui.separator();
                                 ui.separator();
                                 ui.separator();
+                                ui.separator();
                                 ui.end_row();
                                 
                                 // Event rows
-                                for event in events.iter() {
+                                for event in events.iter().filter(|event| filter.as_ref().is_none_or(|event_type| *event_type == event.event_type)) {
                                     ui.label(format!("{}", Self::format_number_with_commas(event.tick)));
+                                    ui.label(Self::applied_at_label(event).unwrap_or_default());
                                     ui.label(&event.event_type);
                                     ui.label(&event.description);
                                     ui.end_row();
@@ -957,13 +2062,85 @@ impl BEImageApp {
         }
     }
 
+    /// Event types for the events filter: the lifecycle milestones plus every type recorded so far
+    fn event_type_options(events: &[ColonyEventDescription]) -> Vec<String> {
+        let mut event_types: std::collections::BTreeSet<String> = LIFECYCLE_EVENT_TYPES.iter().map(|event_type| event_type.to_string()).collect();
+        event_types.extend(events.iter().map(|event| event.event_type.clone()));
+        event_types.into_iter().collect()
+    }
+
+    /// Range of shard ticks the event was applied at, None when it was applied at its stamped tick
+    fn applied_at_label(event: &ColonyEventDescription) -> Option<String> {
+        let (first, last) = event.delivery.as_ref()?.applied_tick_range()?;
+        if (first, last) == (event.tick, event.tick) {
+            None
+        } else if first == last {
+            Some(Self::format_number_with_commas(first))
+        } else {
+            Some(format!("{} – {}", Self::format_number_with_commas(first), Self::format_number_with_commas(last)))
+        }
+    }
+
+    fn show_run_configuration(&self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Run configuration")
+            .default_open(false)
+            .show(ui, |ui| {
+                let config_guard = self.colony_config.lock().unwrap();
+                let Some(response) = config_guard.as_ref() else {
+                    ui.label("Not available");
+                    return;
+                };
+                let config = &response.config;
+                let rules = &config.initial_rules;
+                egui::Grid::new("run_configuration_grid")
+                    .num_columns(2)
+                    .spacing([20.0, 4.0])
+                    .show(ui, |ui| {
+                        let rows = [
+                            ("Config Hash", response.config_hash.clone()),
+                            ("Instance ID", config.colony_instance_id.clone().unwrap_or_else(|| "-".to_string())),
+                            ("Deployment Mode", config.deployment_mode.clone()),
+                            ("Dimensions", format!("{}x{}", config.colony_width, config.colony_height)),
+                            ("Shard Grid", format!("{}x{} shards of {}x{}", config.width_in_shards, config.height_in_shards, config.shard_width, config.shard_height)),
+                            ("Backends", format!("{} ({})", config.backend_count, config.assignment_strategy)),
+                            ("Topography", format!("{} (hash {})", config.topography_source, config.topography_hash)),
+                            ("Topography Seed", config.topography_seed.to_string()),
+                            ("Extra Food", extra_food_pattern_label(config)),
+                            ("Seeding", format!(
+                                "{:?}, density {}, seed {}",
+                                config.seeding.pattern, config.seeding.density,
+                                config.seeding.seed.map_or_else(|| "-".to_string(), |seed| seed.to_string())
+                            )),
+                            ("Initial Rules", format!(
+                                "health/size={}, eat/size={}, kill={}, move={}, mutation=1/{}, death=1/{}, kill chance={}%+{}%/size, counter damage={}, reproduction cost={} (min health {}), mutation size step={}, cost step={}, flip chance={}%, color drift={}, color mutation=1/{}, starvation grace={} ticks, food sharing={}% within {}",
+                                rules.health_cost_per_size_unit, rules.eat_capacity_per_size_unit,
+                                rules.health_cost_if_can_kill, rules.health_cost_if_can_move,
+                                rules.mutation_chance, rules.random_death_chance,
+                                rules.kill_success_base_chance, rules.kill_size_advantage_percent, rules.kill_counter_damage,
+                                rules.reproduction_food_cost, rules.reproduction_min_food,
+                                rules.mutation_size_step, rules.mutation_cost_step, rules.boolean_trait_flip_chance,
+                                rules.color_drift_per_generation, rules.color_mutation_chance,
+                                rules.starvation_grace_ticks, rules.food_sharing_percent, rules.food_sharing_radius
+                            )),
+                        ];
+                        for (label, value) in rows {
+                            ui.label(format!("{}:", label));
+                            ui.label(value);
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+
     fn show_cluster_tab(&mut self, ui: &mut egui::Ui) {
+        let cluster_topology = Arc::clone(&self.cluster_topology.read().unwrap());
+        let backend_statuses = self.backend_statuses.lock().unwrap().clone();
         ui.vertical(|ui| {
             ui.heading("Cluster Topology");
             ui.separator();
             
             // Display instance ID in separate info section
-            match &self.colony_instance_id {
+            match &*self.colony_instance_id.lock().unwrap() {
                 Some(id) => {
                     ui.horizontal(|ui| {
                         ui.label("Colony Instance:");
@@ -982,12 +2159,15 @@ impl BEImageApp {
             
             // Deployment mode header
             ui.heading(format!("Deployment Mode: {}", self.deployment_mode));
+            if self.observer_mode {
+                ui.colored_label(egui::Color32::YELLOW, "Observer mode (read-only)");
+            }
             ui.add_space(20.0);
             
             // Node list
             ui.group(|ui| {
                 egui::Grid::new("cluster_nodes_grid")
-                    .num_columns(7)
+                    .num_columns(11)
                     .spacing([20.0, 4.0])
                     .show(ui, |ui| {
                         // Header row
@@ -996,21 +2176,21 @@ impl BEImageApp {
                         ui.label(egui::RichText::new("RPC Port").strong());
                         ui.label(egui::RichText::new("HTTP Port").strong());
                         ui.label(egui::RichText::new("Shards").strong());
+                        ui.label(egui::RichText::new("Ticks").strong());
+                        ui.label(egui::RichText::new("Version").strong());
                         ui.label(egui::RichText::new("Lat").strong());
                         ui.label(egui::RichText::new("Err %").strong());
+                        ui.label(egui::RichText::new("Status").strong());
+                        ui.label(egui::RichText::new("Actions").strong());
                         ui.end_row();
 
-                        ui.separator();
-                        ui.separator();
-                        ui.separator();
-                        ui.separator();
-                        ui.separator();
-                        ui.separator();
-                        ui.separator();
+                        for _ in 0..11 {
+                            ui.separator();
+                        }
                         ui.end_row();
                         
                         // Coordinator node
-                        let coordinator_host = self.cluster_topology.get_coordinator_host();
+                        let coordinator_host = cluster_topology.get_coordinator_host();
                         let coordinator_http = self.coordinator_http_info
                             .as_ref()
                             .map(|(_, p)| p.to_string())
@@ -1034,15 +2214,19 @@ impl BEImageApp {
                         ui.label(coordinator_host.port.to_string());
                         ui.label(coordinator_http);
                         ui.label("—"); // Coordinator doesn't have shards
+                        ui.label("—");
+                        ui.label("—");
                         ui.label(coord_lat_str);
                         ui.label(coord_err_str);
+                        ui.label("—");
+                        ui.label("—");
                         ui.end_row();
                         
                         // Backend nodes
-                        let backend_hosts = self.cluster_topology.get_all_backend_hosts();
-                        let shard_to_host = &self.cluster_topology.shard_to_host;
+                        let backend_hosts = cluster_topology.get_all_backend_hosts();
+                        let shard_to_host = &cluster_topology.shard_to_host;
                         
-                        // Calculate shard counts per backend
+                        // Topology shard counts, only shown until the live status arrives
                         let mut backend_shard_counts: std::collections::HashMap<shared::cluster_topology::HostInfo, usize> = 
                             std::collections::HashMap::new();
                         for (_, host) in shard_to_host.iter() {
@@ -1058,7 +2242,9 @@ impl BEImageApp {
                         
                         for backend in sorted_backends {
                             let shard_count = backend_shard_counts.get(&backend).copied().unwrap_or(0);
-                            let backend_http = self.backend_http_info
+                            let status = backend_statuses.as_ref()
+                                .and_then(|statuses| statuses.backends.iter().find(|s| s.backend == backend.to_address()));
+                            let backend_http = self.backend_http_info.read().unwrap()
                                 .get(&backend)
                                 .map(|(_, p)| p.to_string())
                                 .unwrap_or_else(|| "N/A".to_string());
@@ -1082,13 +2268,251 @@ impl BEImageApp {
                             ui.label(&backend.hostname);
                             ui.label(backend.port.to_string());
                             ui.label(backend_http);
-                            ui.label(shard_count.to_string());
+                            match status {
+                                Some(status) => {
+                                    let assigned = status.assigned_shards.len();
+                                    match &status.hosted_shards {
+                                        Some(hosted) if status.has_discrepancies() => {
+                                            ui.colored_label(egui::Color32::YELLOW, format!("{} ({} assigned)", hosted.len(), assigned));
+                                        }
+                                        Some(hosted) => {
+                                            ui.label(hosted.len().to_string());
+                                        }
+                                        None => {
+                                            ui.label(format!("? ({} assigned)", assigned));
+                                        }
+                                    }
+                                    let ticks = match status.tick_range {
+                                        Some((min, max)) if min == max => min.to_string(),
+                                        Some((min, max)) => format!("{}–{}", min, max),
+                                        None => "—".to_string(),
+                                    };
+                                    ui.label(ticks);
+                                    ui.label(status.version.as_deref().unwrap_or("—"));
+                                }
+                                None => {
+                                    ui.label(shard_count.to_string());
+                                    ui.label("—");
+                                    ui.label("—");
+                                }
+                            }
                             ui.label(lat_str);
                             ui.label(err_rate_str);
+                            self.show_node_health(ui, &backend);
+                            self.show_node_actions(ui, &backend);
                             ui.end_row();
                         }
                     });
             });
+            
+            // Assigned-but-not-hosting means a failed init, hosting-but-not-assigned a split brain
+            let discrepancies: Vec<_> = backend_statuses.iter()
+                .flat_map(|statuses| statuses.backends.iter())
+                .filter(|status| status.has_discrepancies())
+                .collect();
+            if !discrepancies.is_empty() {
+                ui.add_space(10.0);
+                ui.group(|ui| {
+                    ui.colored_label(egui::Color32::YELLOW, egui::RichText::new("Shard discrepancies").strong());
+                    for status in discrepancies {
+                        if !status.assigned_not_hosting.is_empty() {
+                            ui.colored_label(egui::Color32::YELLOW, format!(
+                                "{}: assigned but not hosting {}", status.backend, status.assigned_not_hosting.join(", ")));
+                        }
+                        if !status.hosting_not_assigned.is_empty() {
+                            ui.colored_label(egui::Color32::YELLOW, format!(
+                                "{}: hosting but not assigned {}", status.backend, status.hosting_not_assigned.join(", ")));
+                        }
+                        if !status.lease_mismatches.is_empty() {
+                            ui.colored_label(egui::Color32::YELLOW, format!(
+                                "{}: lease epochs differ: {}", status.backend, status.lease_mismatches.join(", ")));
+                        }
+                        if !status.suspended_shards.is_empty() {
+                            ui.colored_label(egui::Color32::YELLOW, format!(
+                                "{}: suspended {}", status.backend, status.suspended_shards.join(", ")));
+                        }
+                    }
+                });
+            }
+            
+            ui.add_space(10.0);
+            self.show_verification(ui);
+            
+            if let Some(status) = self.cluster_action_status.lock().unwrap().as_ref() {
+                ui.add_space(10.0);
+                ui.label(status);
+            }
+        });
+    }
+
+    /// Verify button and the outcome of the last pass started here
+    fn show_verification(&self, ui: &mut egui::Ui) {
+        const MAX_LISTED_PROBLEMS: usize = 20;
+        ui.group(|ui| {
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new("Verification").strong());
+                let running = *self.verification_running.lock().unwrap();
+                // POST /api/verify needs the admin token, hidden for observers
+                if !self.observer_mode {
+                    let label = if running { "Verifying…" } else { "Verify cluster" };
+                    let clicked = ui.add_enabled(!running, egui::Button::new(label))
+                        .on_hover_text("Check every shard: a single host, dimensions, tick drift, image size and rules")
+                        .clicked();
+                    if clicked {
+                        *self.verification_running.lock().unwrap() = true;
+                        let coordinator_http_info = self.coordinator_http_info.clone();
+                        let verification_report = Arc::clone(&self.verification_report);
+                        let verification_running = Arc::clone(&self.verification_running);
+                        let ctx = ui.ctx().clone();
+                        thread::spawn(move || {
+                            let result = call_be::verify_colony(coordinator_http_info.as_ref());
+                            match &result {
+                                Ok(report) => log!("Cluster verification: {}", report.summary()),
+                                Err(e) => log!("Cluster verification failed: {}", e),
+                            }
+                            *verification_report.lock().unwrap() = Some(result);
+                            *verification_running.lock().unwrap() = false;
+                            ctx.request_repaint();
+                        });
+                    }
+                }
+            });
+            
+            match self.verification_report.lock().unwrap().as_ref() {
+                None => {
+                    ui.label(egui::RichText::new("Not run from here yet; passes after colony-start and failover are in the Events tab").weak());
+                }
+                Some(Err(e)) => {
+                    ui.colored_label(egui::Color32::RED, format!("Verification failed: {}", e));
+                }
+                Some(Ok(report)) => {
+                    let color = if report.passed { egui::Color32::GREEN } else { egui::Color32::RED };
+                    ui.colored_label(color, report.summary());
+                    if let Some(max_tick) = report.colony_max_tick {
+                        ui.label(egui::RichText::new(format!("Colony tick {}, drift allowed {}", max_tick, report.max_tick_drift)).weak());
+                    }
+                    let problems: Vec<String> = report.backend_problems.iter().cloned()
+                        .chain(report.shards.iter()
+                            .filter(|shard| !shard.passed)
+                            .map(|shard| format!("{}: {}", shard.shard_id, shard.problems.join("; "))))
+                        .collect();
+                    for problem in problems.iter().take(MAX_LISTED_PROBLEMS) {
+                        ui.colored_label(egui::Color32::YELLOW, problem);
+                    }
+                    if problems.len() > MAX_LISTED_PROBLEMS {
+                        ui.label(format!("…and {} more", problems.len() - MAX_LISTED_PROBLEMS));
+                    }
+                }
+            }
+        });
+    }
+
+    fn show_node_health(&self, ui: &mut egui::Ui, backend: &shared::cluster_topology::HostInfo) {
+        let health = self.node_health.lock().unwrap().get(backend).copied();
+        ui.horizontal(|ui| {
+            match health {
+                Some(NodeHealth { is_up: true, .. }) => {
+                    ui.colored_label(egui::Color32::GREEN, "●");
+                    ui.label("Up");
+                }
+                Some(NodeHealth { is_up: false, .. }) => {
+                    ui.colored_label(egui::Color32::RED, "●");
+                    ui.label("Down");
+                }
+                None => {
+                    ui.colored_label(egui::Color32::GRAY, "●");
+                    ui.label("…");
+                }
+            }
+            let last_seen = health
+                .and_then(|h| h.last_seen)
+                .map(|t| format!("seen {}s ago", t.elapsed().as_secs()))
+                .unwrap_or_else(|| "never seen".to_string());
+            ui.label(egui::RichText::new(last_seen).weak());
+        });
+    }
+
+    fn show_ticker_controls(&self, ui: &mut egui::Ui) {
+        let paused = *self.ticker_paused.lock().unwrap();
+        ui.horizontal(|ui| {
+            let pause_clicked = ui.add_enabled(paused == Some(false), egui::Button::new("Pause")).clicked();
+            let resume_clicked = ui.add_enabled(paused == Some(true), egui::Button::new("Resume")).clicked();
+            let step_clicked = ui.add_enabled(paused == Some(true), egui::Button::new("Step"))
+                .on_hover_text("Advance every shard by one tick")
+                .clicked();
+            
+            if pause_clicked || resume_clicked || step_clicked {
+                let coordinator_http_info = self.coordinator_http_info.clone();
+                let ticker_paused = Arc::clone(&self.ticker_paused);
+                let ticker_action_status = Arc::clone(&self.ticker_action_status);
+                let ctx = ui.ctx().clone();
+                thread::spawn(move || {
+                    let result = if step_clicked {
+                        call_be::step_colony(1, coordinator_http_info.as_ref())
+                    } else {
+                        call_be::set_colony_paused(pause_clicked, coordinator_http_info.as_ref())
+                    };
+                    let status = match result {
+                        Ok(state) => {
+                            *ticker_paused.lock().unwrap() = Some(state.paused);
+                            let verb = if step_clicked { "Stepped" } else if state.paused { "Paused" } else { "Resumed" };
+                            match state.current_tick {
+                                Some(tick) => format!("{} at tick {}", verb, tick),
+                                None => verb.to_string(),
+                            }
+                        }
+                        Err(e) => format!("Ticker action failed: {}", e),
+                    };
+                    log!("{}", status);
+                    *ticker_action_status.lock().unwrap() = Some(status);
+                    ctx.request_repaint();
+                });
+            }
+            
+            if let Some(status) = self.ticker_action_status.lock().unwrap().as_ref() {
+                ui.label(status);
+            }
+        });
+    }
+
+    fn show_node_actions(&self, ui: &mut egui::Ui, backend: &shared::cluster_topology::HostInfo) {
+        ui.horizontal(|ui| {
+            if ui.button("Ping now").clicked() {
+                let backend = backend.clone();
+                let node_health = Arc::clone(&self.node_health);
+                let latency_tracker = Arc::clone(&self.latency_tracker);
+                let backend_http_info = self.backend_http_info.read().unwrap().clone();
+                let ctx = ui.ctx().clone();
+                thread::spawn(move || {
+                    ping_and_record_node_health(&backend, &node_health, &latency_tracker, &backend_http_info);
+                    ctx.request_repaint();
+                });
+            }
+            
+            let metrics_url = call_be::backend_metrics_url(backend, &self.backend_http_info.read().unwrap());
+            if ui.add_enabled(metrics_url.is_some(), egui::Button::new("Open metrics")).clicked() {
+                if let Some(url) = metrics_url {
+                    ui.ctx().copy_text(url.clone());
+                    *self.cluster_action_status.lock().unwrap() = Some(format!("Copied {}", url));
+                }
+            }
+            
+            // Mutating action, hidden for observers
+            if !self.observer_mode && ui.button("Start ticking").clicked() {
+                let backend = backend.clone();
+                let coordinator_http_info = self.coordinator_http_info.clone();
+                let cluster_action_status = Arc::clone(&self.cluster_action_status);
+                let ctx = ui.ctx().clone();
+                thread::spawn(move || {
+                    let status = match call_be::request_backend_start_ticking(&backend, coordinator_http_info.as_ref()) {
+                        Ok(()) => format!("StartTicking re-issued to {}", backend.to_address()),
+                        Err(e) => format!("StartTicking to {} failed: {}", backend.to_address(), e),
+                    };
+                    log!("{}", status);
+                    *cluster_action_status.lock().unwrap() = Some(status);
+                    ctx.request_repaint();
+                });
+            }
         });
     }
 }
@@ -1117,7 +2541,8 @@ fn retrieve_http_ports(
     for backend_addr in backend_addresses {
         // Try to match with coordinator host first (in case coordinator is in backend list)
         let coordinator_host = topology.get_coordinator_host();
-        if (backend_addr.private_ip == coordinator_host.hostname || 
+        if (backend_addr.private_ip == coordinator_host.hostname ||
+            backend_addr.public_ip == coordinator_host.hostname ||
             backend_addr.private_ip == "127.0.0.1" && coordinator_host.hostname == "127.0.0.1" ||
             backend_addr.private_ip == "localhost" && coordinator_host.hostname == "localhost") &&
            backend_addr.internal_port == coordinator_host.port {
@@ -1127,7 +2552,9 @@ fn retrieve_http_ports(
         
         // Match with backend hosts
         for backend_host in topology.get_all_backend_hosts() {
+            // Observer topology reports hosts by public IP instead of private IP
             if (backend_addr.private_ip == backend_host.hostname ||
+                backend_addr.public_ip == backend_host.hostname ||
                 backend_addr.private_ip == "127.0.0.1" && backend_host.hostname == "127.0.0.1" ||
                 backend_addr.private_ip == "localhost" && backend_host.hostname == "localhost") &&
                backend_addr.internal_port == backend_host.port {
@@ -1140,7 +2567,82 @@ fn retrieve_http_ports(
     Ok((coordinator_http_info, backend_http_info))
 }
 
-fn retrieve_topology(mode: &str) -> Result<(Arc<ClusterTopology>, Option<String>), String> {
+fn refresh_events_and_tick_history(
+    colony_events: &Mutex<Option<Vec<ColonyEventDescription>>>,
+    tick_history: &Mutex<Option<Vec<TickSample>>>,
+    coordinator_http_info: Option<&(String, u16)>,
+) {
+    if let Some(events) = call_be::get_colony_events(COLONY_EVENTS_SHOWN, coordinator_http_info) {
+        *colony_events.lock().unwrap() = Some(events);
+    }
+    if let Some(history) = call_be::get_tick_history(TICK_HISTORY_MINUTES, coordinator_http_info) {
+        *tick_history.lock().unwrap() = Some(history.samples);
+    }
+}
+
+/// Applies feed messages until the connection drops. Starts from an HTTP snapshot, and takes
+/// a new one whenever the coordinator reports that messages were dropped.
+fn follow_feed(
+    mut client: FeedClient,
+    colony_events: &Mutex<Option<Vec<ColonyEventDescription>>>,
+    tick_history: &Mutex<Option<Vec<TickSample>>>,
+    coordinator_http_info: Option<&(String, u16)>,
+    ctx: &egui::Context,
+) {
+    refresh_events_and_tick_history(colony_events, tick_history, coordinator_http_info);
+    ctx.request_repaint();
+    while let Ok(message) = client.next_message() {
+        match message {
+            FeedMessage::Event { event } => {
+                // Newest first, like /api/colony-events
+                let mut events = colony_events.lock().unwrap();
+                let events = events.get_or_insert_with(Vec::new);
+                events.insert(0, event);
+                events.truncate(COLONY_EVENTS_SHOWN);
+            }
+            FeedMessage::Tick { timestamp_ms, min_tick, max_tick } => {
+                let mut history = tick_history.lock().unwrap();
+                let samples = history.get_or_insert_with(Vec::new);
+                samples.push(TickSample { timestamp_ms, min_tick, max_tick });
+                let oldest_kept_ms = timestamp_ms.saturating_sub(TICK_HISTORY_MINUTES * 60_000);
+                samples.retain(|sample| sample.timestamp_ms >= oldest_kept_ms);
+            }
+            FeedMessage::Lagged { .. } => refresh_events_and_tick_history(colony_events, tick_history, coordinator_http_info),
+            _ => continue,
+        }
+        ctx.request_repaint();
+    }
+}
+
+/// Attaches through the coordinator's /api/bootstrap, or through /topology and the cluster
+/// registry when the coordinator is too old to serve it
+fn attach_to_cluster(mode: &str, observer_mode: bool) -> Result<ClusterAttachment, String> {
+    let _registry = create_cluster_registry(mode);
+    let rt = tokio::runtime::Runtime::new().map_err(|e| format!("Failed to create tokio runtime: {}", e))?;
+    let coordinator_addr = rt.block_on(ssm::discover_coordinator())
+        .ok_or_else(|| "Failed to discover coordinator".to_string())?;
+    let mut http_port_probe = HttpPortProbe::for_deployment_mode(mode);
+    if let Some(mut attachment) = bootstrap::attach(&coordinator_addr.public_ip, coordinator_addr.http_port, observer_mode)? {
+        bootstrap::probe_missing_http_ports(&mut attachment, &mut http_port_probe, answers_health);
+        return Ok(attachment);
+    }
+
+    eprintln!("Coordinator has no /api/bootstrap, falling back to /topology and the cluster registry");
+    let (topology, colony_instance_id) = retrieve_topology(mode, observer_mode)?;
+    let (coordinator_http_info, backend_http_info) = match retrieve_http_ports(mode, topology.as_ref()) {
+        Ok(info) => info,
+        Err(e) => {
+            eprintln!("Warning: Failed to retrieve HTTP info: {}", e);
+            eprintln!("HTTP info will be shown as N/A in the Cluster tab.");
+            (None, std::collections::HashMap::new())
+        }
+    };
+    let mut attachment = ClusterAttachment { topology, colony_instance_id, coordinator_http_info, backend_http_info };
+    bootstrap::probe_missing_http_ports(&mut attachment, &mut http_port_probe, answers_health);
+    Ok(attachment)
+}
+
+fn retrieve_topology(mode: &str, observer_mode: bool) -> Result<(Arc<ClusterTopology>, Option<String>), String> {
     // Initialize cluster registry
     let _registry = create_cluster_registry(mode);
     
@@ -1156,8 +2658,7 @@ fn retrieve_topology(mode: &str) -> Result<(Arc<ClusterTopology>, Option<String>
     // Make HTTP GET request to /topology
     let url = format!("http://{}:{}/topology", coordinator_ip, http_port);
     let client = reqwest::blocking::Client::new();
-    let response = client
-        .get(&url)
+    let response = call_be::with_auth_blocking(client.get(&url))
         .send()
         .map_err(|e| format!("Failed to connect to coordinator at {}: {}", url, e))?;
     
@@ -1184,8 +2685,7 @@ fn retrieve_topology(mode: &str) -> Result<(Arc<ClusterTopology>, Option<String>
             loop {
                 std::thread::sleep(std::time::Duration::from_millis(500 * (retry_count + 1)));
                 
-                let retry_response = client
-                    .get(&url)
+                let retry_response = call_be::with_auth_blocking(client.get(&url))
                     .send()
                     .map_err(|e| format!("Failed to retry topology request: {}", e))?;
                 
@@ -1225,6 +2725,9 @@ fn retrieve_topology(mode: &str) -> Result<(Arc<ClusterTopology>, Option<String>
                     
                     return Ok((Arc::new(topology), colony_instance_id));
                 } else if retry_status.as_u16() == 404 {
+                    if observer_mode {
+                        return Err(OBSERVER_CANNOT_START_COLONY.to_string());
+                    }
                     // Topology not initialized - automatically initiate colony-start
                     eprintln!("Topology not initialized. Automatically initiating colony-start...");
                     
@@ -1238,8 +2741,7 @@ fn retrieve_topology(mode: &str) -> Result<(Arc<ClusterTopology>, Option<String>
                     // Make POST request to /colony-start
                     let colony_start_url = format!("http://{}:{}/colony-start?idempotency_key={}", 
                         coordinator_ip, http_port, idempotency_key);
-                    let colony_start_response = client
-                        .post(&colony_start_url)
+                    let colony_start_response = call_be::with_auth_blocking(client.post(&colony_start_url))
                         .send()
                         .map_err(|e| format!("Failed to initiate colony-start: {}", e))?;
                     
@@ -1278,6 +2780,9 @@ fn retrieve_topology(mode: &str) -> Result<(Arc<ClusterTopology>, Option<String>
     }
     
     if status.as_u16() == 404 {
+        if observer_mode {
+            return Err(OBSERVER_CANNOT_START_COLONY.to_string());
+        }
         // Topology not initialized - automatically initiate colony-start
         eprintln!("Topology not initialized. Automatically initiating colony-start...");
         
@@ -1291,8 +2796,7 @@ fn retrieve_topology(mode: &str) -> Result<(Arc<ClusterTopology>, Option<String>
         // Make POST request to /colony-start
         let colony_start_url = format!("http://{}:{}/colony-start?idempotency_key={}", 
             coordinator_ip, http_port, idempotency_key);
-        let colony_start_response = client
-            .post(&colony_start_url)
+        let colony_start_response = call_be::with_auth_blocking(client.post(&colony_start_url))
             .send()
             .map_err(|e| format!("Failed to initiate colony-start: {}", e))?;
         
@@ -1310,8 +2814,7 @@ fn retrieve_topology(mode: &str) -> Result<(Arc<ClusterTopology>, Option<String>
         loop {
             std::thread::sleep(std::time::Duration::from_millis(500 * (retry_count + 1)));
             
-            let retry_response = client
-                .get(&url)
+            let retry_response = call_be::with_auth_blocking(client.get(&url))
                 .send()
                 .map_err(|e| format!("Failed to retry topology request: {}", e))?;
             
@@ -1373,28 +2876,46 @@ fn main() -> eframe::Result<()> {
     eprintln!("GUI MAIN ENTERED");
     // Parse command line arguments for mode
     let args: Vec<String> = std::env::args().collect();
-    let mode = args.get(1).map(|s| s.as_str()).unwrap_or("localhost");
+    let observer_mode = args.iter().skip(1).any(|arg| arg == OBSERVER_FLAG);
+    let mode = args.iter().skip(1)
+        .find(|arg| arg.as_str() != OBSERVER_FLAG && !arg.starts_with(VIEW_LINK_PREFIX))
+        .map(|s| s.as_str())
+        .unwrap_or("localhost");
     
     if mode != "localhost" && mode != "aws" {
         eprintln!("Error: Mode must be 'localhost' or 'aws'");
-        eprintln!("Usage: {} [localhost|aws]", args[0]);
+        eprintln!("Usage: {} [localhost|aws] [{}] [{}<tab>?x=..&y=..&zoom=..]", args[0], OBSERVER_FLAG, VIEW_LINK_PREFIX);
         std::process::exit(1);
     }
+
+    // A view link opens the GUI where a teammate was looking, once the topology is loaded
+    let initial_view = match args.iter().skip(1).find(|arg| arg.starts_with(VIEW_LINK_PREFIX)).map(|link| ViewState::parse(link)) {
+        Some(Ok(state)) => Some(state),
+        Some(Err(e)) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        None => None,
+    };
+    
+    // Observers authenticate with the read-only token, everyone else with the admin token
+    let token_env = if observer_mode { OBSERVER_TOKEN_ENV } else { ADMIN_TOKEN_ENV };
+    call_be::set_api_token(std::env::var(token_env).ok().filter(|t| !t.is_empty()));
     
     // Initialize logging
     // GUI always runs locally, but use different log files based on mode for clarity
-    let log_file = if mode == "aws" {
-        "output/logs/gui_aws.log"
-    } else {
-        "output/logs/gui_local.log"
-    };
-    shared::logging::init_logging(log_file);
+    let output_paths = OutputPaths::get_instance();
+    if let Err(e) = output_paths.ensure_writable() {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+    let log_file = if mode == "aws" { "gui_aws" } else { "gui_local" };
+    shared::logging::init_logging(output_paths.log_file(log_file));
     shared::logging::log_startup("GUI");
     shared::logging::set_panic_hook();
     
-    // Retrieve topology from coordinator
-    let (topology, colony_instance_id) = match retrieve_topology(mode) {
-        Ok(result) => result,
+    let ClusterAttachment { topology, colony_instance_id, coordinator_http_info, backend_http_info } = match attach_to_cluster(mode, observer_mode) {
+        Ok(attachment) => attachment,
         Err(e) => {
             eprintln!("Error: Failed to retrieve topology: {}", e);
             eprintln!("Please ensure the coordinator is running and the colony is started.");
@@ -1402,16 +2923,6 @@ fn main() -> eframe::Result<()> {
         }
     };
     
-    // Retrieve HTTP info (public IPs and ports) from ClusterRegistry
-    let (coordinator_http_info, backend_http_info) = match retrieve_http_ports(mode, topology.as_ref()) {
-        Ok(info) => info,
-        Err(e) => {
-            eprintln!("Warning: Failed to retrieve HTTP info: {}", e);
-            eprintln!("HTTP info will be shown as N/A in the Cluster tab.");
-            (None, std::collections::HashMap::new())
-        }
-    };
-    
     let deployment_mode = mode.to_string();
     let topology_clone = Arc::clone(&topology);
     let coordinator_http_info_clone = coordinator_http_info;
@@ -1453,13 +2964,18 @@ fn main() -> eframe::Result<()> {
             // Ensure default fonts are installed
             let fonts = egui::FontDefinitions::default();
             cc.egui_ctx.set_fonts(fonts);
-            Ok(Box::new(BEImageApp::new(
+            let mut app = BEImageApp::new(
                 Arc::clone(&topology_clone),
                 deployment_mode.clone(),
                 coordinator_http_info_clone,
                 backend_http_info_clone.clone(),
                 colony_instance_id_clone.clone(),
-            )))
+                observer_mode,
+            );
+            if let Some(state) = initial_view {
+                app.apply_view_state(state);
+            }
+            Ok(Box::new(app))
         }),
     )
 }
@@ -0,0 +1,268 @@
+use eframe::egui;
+use shared::be_api::StatBucket;
+use shared::coordinator_api::TickSample;
+
+/// Space below the bars for the min/mid/max tick labels
+const AXIS_LABEL_HEIGHT: f32 = 14.0;
+const BAR_GAP: f32 = 1.0;
+
+#[derive(Clone, Copy)]
+pub struct HistogramOptions {
+    /// Size of the plot area, tick labels excluded
+    pub size: egui::Vec2,
+    /// Bar heights by ln(1 + occurrences), so one dominant bucket does not flatten the rest
+    pub log_scale: bool,
+    /// Drawn as a vertical marker line
+    pub avg: Option<f64>,
+    pub bar_color: egui::Color32,
+}
+
+impl Default for HistogramOptions {
+    fn default() -> Self {
+        Self {
+            size: egui::vec2(300.0, 120.0),
+            log_scale: false,
+            avg: None,
+            bar_color: egui::Color32::from_rgb(100, 150, 220),
+        }
+    }
+}
+
+/// Value and height mapping of a histogram, in plot-local coordinates.
+/// Every integer value from min to max gets an equal slot, so gaps between buckets stay visible.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HistogramLayout {
+    pub min_value: i32,
+    pub max_value: i32,
+    pub max_occs: u64,
+    pub width: f32,
+    pub height: f32,
+    pub log_scale: bool,
+}
+
+impl HistogramLayout {
+    /// None when there is nothing to draw
+    pub fn new(buckets: &[StatBucket], width: f32, height: f32, log_scale: bool) -> Option<Self> {
+        let min_value = buckets.iter().map(|b| b.value).min()?;
+        let max_value = buckets.iter().map(|b| b.value).max()?;
+        let max_occs = buckets.iter().map(|b| b.occs).max()?;
+        Some(Self { min_value, max_value, max_occs, width, height, log_scale })
+    }
+
+    pub fn slot_width(&self) -> f32 {
+        self.width / (self.max_value as f64 - self.min_value as f64 + 1.0) as f32
+    }
+
+    /// x of the middle of the value's slot; fractional values (the average) fall in between
+    pub fn value_to_x(&self, value: f64) -> f32 {
+        ((value - self.min_value as f64 + 0.5) * self.slot_width() as f64) as f32
+    }
+
+    /// Value whose slot contains x, None outside the plot
+    pub fn x_to_value(&self, x: f32) -> Option<i32> {
+        if x < 0.0 || x >= self.width {
+            return None;
+        }
+        Some(self.min_value + (x / self.slot_width()).floor() as i32)
+    }
+
+    pub fn bar_height(&self, occs: u64) -> f32 {
+        if self.max_occs == 0 {
+            return 0.0;
+        }
+        let fraction = if self.log_scale {
+            (occs as f64).ln_1p() / (self.max_occs as f64).ln_1p()
+        } else {
+            occs as f64 / self.max_occs as f64
+        };
+        fraction as f32 * self.height
+    }
+
+    /// Tick values at min, mid and max; fewer when the range is too narrow for three
+    pub fn axis_ticks(&self) -> Vec<i32> {
+        let mid = self.min_value + (self.max_value - self.min_value) / 2;
+        let mut ticks = vec![self.min_value, mid, self.max_value];
+        ticks.dedup();
+        ticks
+    }
+}
+
+/// Bar chart of buckets with min/mid/max labels, an avg marker and a (value, occurrences)
+/// tooltip for the bar under the cursor
+pub fn draw_histogram(ui: &mut egui::Ui, buckets: &[StatBucket], options: &HistogramOptions) -> egui::Response {
+    let (rect, response) = ui.allocate_exact_size(
+        options.size + egui::vec2(0.0, AXIS_LABEL_HEIGHT),
+        egui::Sense::hover(),
+    );
+    let plot = egui::Rect::from_min_size(rect.min, options.size);
+    let painter = ui.painter_at(rect);
+    let visuals = ui.visuals();
+    painter.rect_filled(plot, 2.0, visuals.extreme_bg_color);
+
+    let Some(layout) = HistogramLayout::new(buckets, plot.width(), plot.height(), options.log_scale) else {
+        painter.text(plot.center(), egui::Align2::CENTER_CENTER, "No data", egui::FontId::proportional(12.0), visuals.weak_text_color());
+        return response;
+    };
+
+    let bar_width = (layout.slot_width() - BAR_GAP).max(1.0);
+    for bucket in buckets {
+        let center_x = plot.left() + layout.value_to_x(bucket.value as f64);
+        let bar = egui::Rect::from_min_max(
+            egui::pos2(center_x - bar_width / 2.0, plot.bottom() - layout.bar_height(bucket.occs)),
+            egui::pos2(center_x + bar_width / 2.0, plot.bottom()),
+        );
+        painter.rect_filled(bar, 0.0, options.bar_color);
+    }
+
+    if let Some(avg) = options.avg {
+        let x = plot.left() + layout.value_to_x(avg);
+        painter.vline(x, plot.y_range(), egui::Stroke::new(1.5, egui::Color32::from_rgb(230, 80, 60)));
+    }
+
+    let label_font = egui::FontId::proportional(10.0);
+    for tick in layout.axis_ticks() {
+        let x = plot.left() + layout.value_to_x(tick as f64);
+        let align = if tick == layout.min_value {
+            egui::Align2::LEFT_TOP
+        } else if tick == layout.max_value {
+            egui::Align2::RIGHT_TOP
+        } else {
+            egui::Align2::CENTER_TOP
+        };
+        let x = x.clamp(plot.left(), plot.right());
+        painter.text(egui::pos2(x, plot.bottom() + 2.0), align, tick.to_string(), label_font.clone(), visuals.text_color());
+    }
+
+    let hovered = response.hover_pos()
+        .filter(|pos| plot.contains(*pos))
+        .and_then(|pos| layout.x_to_value(pos.x - plot.left()))
+        .and_then(|value| buckets.iter().find(|bucket| bucket.value == value))
+        .cloned();
+    match hovered {
+        Some(bucket) => response.on_hover_ui_at_pointer(|ui| {
+            ui.label(format!("value: {}", bucket.value));
+            ui.label(format!("occurrences: {}", bucket.occs));
+        }),
+        None => response,
+    }
+}
+
+/// Plot-local (x, y of min_tick, y of max_tick) per sample: time on x, tick on y growing upward.
+/// A flat series sits at the vertical middle.
+pub fn sparkline_points(samples: &[TickSample], width: f32, height: f32) -> Vec<(f32, f32, f32)> {
+    let (Some(first), Some(last)) = (samples.first(), samples.last()) else {
+        return Vec::new();
+    };
+    let min_tick = samples.iter().map(|s| s.min_tick).min().unwrap_or(0) as f64;
+    let max_tick = samples.iter().map(|s| s.max_tick).max().unwrap_or(0) as f64;
+    let time_span = (last.timestamp_ms - first.timestamp_ms) as f64;
+    let tick_span = max_tick - min_tick;
+    let y = |tick: u64| {
+        if tick_span == 0.0 {
+            height / 2.0
+        } else {
+            height - ((tick as f64 - min_tick) / tick_span) as f32 * height
+        }
+    };
+    samples.iter()
+        .map(|s| {
+            let x = if time_span == 0.0 { width } else { ((s.timestamp_ms - first.timestamp_ms) as f64 / time_span) as f32 * width };
+            (x, y(s.min_tick), y(s.max_tick))
+        })
+        .collect()
+}
+
+/// Max tick over time as a line over the shaded min-max band, with the first and last max tick as labels
+pub fn draw_tick_sparkline(ui: &mut egui::Ui, samples: &[TickSample], size: egui::Vec2) -> egui::Response {
+    let (rect, response) = ui.allocate_exact_size(size + egui::vec2(0.0, AXIS_LABEL_HEIGHT), egui::Sense::hover());
+    let plot = egui::Rect::from_min_size(rect.min, size);
+    let painter = ui.painter_at(rect);
+    let visuals = ui.visuals();
+    painter.rect_filled(plot, 2.0, visuals.extreme_bg_color);
+
+    let points = sparkline_points(samples, plot.width(), plot.height());
+    if points.is_empty() {
+        painter.text(plot.center(), egui::Align2::CENTER_CENTER, "No data", egui::FontId::proportional(12.0), visuals.weak_text_color());
+        return response;
+    }
+
+    let line_color = egui::Color32::from_rgb(100, 150, 220);
+    for (x, y_min, y_max) in &points {
+        painter.vline(plot.left() + x, (plot.top() + y_max)..=(plot.top() + y_min).max(plot.top() + y_max + 1.0),
+                      egui::Stroke::new(1.0, line_color.gamma_multiply(0.3)));
+    }
+    let max_line: Vec<egui::Pos2> = points.iter().map(|(x, _, y_max)| egui::pos2(plot.left() + x, plot.top() + y_max)).collect();
+    painter.add(egui::Shape::line(max_line, egui::Stroke::new(1.5, line_color)));
+
+    let label_font = egui::FontId::proportional(10.0);
+    if let (Some(first), Some(last)) = (samples.first(), samples.last()) {
+        painter.text(egui::pos2(plot.left(), plot.bottom() + 2.0), egui::Align2::LEFT_TOP, first.max_tick.to_string(), label_font.clone(), visuals.text_color());
+        painter.text(egui::pos2(plot.right(), plot.bottom() + 2.0), egui::Align2::RIGHT_TOP, last.max_tick.to_string(), label_font, visuals.text_color());
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buckets(values: &[(i32, u64)]) -> Vec<StatBucket> {
+        values.iter().map(|&(value, occs)| StatBucket { value, occs }).collect()
+    }
+
+    #[test]
+    fn test_value_to_x_maps_slots_across_width() {
+        let layout = HistogramLayout::new(&buckets(&[(10, 5), (19, 1)]), 100.0, 50.0, false).unwrap();
+        assert_eq!(layout.slot_width(), 10.0);
+        assert_eq!(layout.value_to_x(10.0), 5.0);
+        assert_eq!(layout.value_to_x(19.0), 95.0);
+        assert_eq!(layout.value_to_x(14.5), 50.0);
+
+        assert_eq!(layout.x_to_value(0.0), Some(10));
+        assert_eq!(layout.x_to_value(49.9), Some(14));
+        assert_eq!(layout.x_to_value(99.9), Some(19));
+        assert_eq!(layout.x_to_value(100.0), None);
+        assert_eq!(layout.x_to_value(-1.0), None);
+    }
+
+    #[test]
+    fn test_single_value_fills_plot() {
+        let layout = HistogramLayout::new(&buckets(&[(3, 7)]), 80.0, 40.0, false).unwrap();
+        assert_eq!(layout.value_to_x(3.0), 40.0);
+        assert_eq!(layout.axis_ticks(), vec![3]);
+        assert_eq!(layout.bar_height(7), 40.0);
+        assert!(HistogramLayout::new(&[], 80.0, 40.0, false).is_none());
+    }
+
+    #[test]
+    fn test_log_scale_lifts_small_buckets() {
+        let values = buckets(&[(0, 1000), (1, 10)]);
+        let linear = HistogramLayout::new(&values, 100.0, 100.0, false).unwrap();
+        let log = HistogramLayout::new(&values, 100.0, 100.0, true).unwrap();
+        assert_eq!(linear.bar_height(1000), 100.0);
+        assert_eq!(log.bar_height(1000), 100.0);
+        assert!((linear.bar_height(10) - 1.0).abs() < 1e-4);
+        assert!(log.bar_height(10) > 30.0);
+        assert_eq!(log.bar_height(0), 0.0);
+    }
+
+    #[test]
+    fn test_axis_ticks_min_mid_max() {
+        let layout = HistogramLayout::new(&buckets(&[(0, 1), (100, 1)]), 100.0, 10.0, false).unwrap();
+        assert_eq!(layout.axis_ticks(), vec![0, 50, 100]);
+        let narrow = HistogramLayout::new(&buckets(&[(4, 1), (5, 1)]), 100.0, 10.0, false).unwrap();
+        assert_eq!(narrow.axis_ticks(), vec![4, 5]);
+    }
+
+    fn sample(timestamp_ms: u64, min_tick: u64, max_tick: u64) -> TickSample {
+        TickSample { timestamp_ms, min_tick, max_tick }
+    }
+
+    #[test]
+    fn test_sparkline_points_span_the_plot() {
+        let points = sparkline_points(&[sample(1000, 0, 10), sample(2000, 50, 100)], 200.0, 100.0);
+        assert_eq!(points, vec![(0.0, 100.0, 90.0), (200.0, 50.0, 0.0)]);
+        assert_eq!(sparkline_points(&[sample(1000, 5, 5)], 200.0, 100.0), vec![(200.0, 50.0, 50.0)]);
+        assert!(sparkline_points(&[], 200.0, 100.0).is_empty());
+    }
+}
@@ -0,0 +1,72 @@
+use std::time::{Duration, Instant};
+
+/// How long the "Attached to new colony" note stays up
+pub const INSTANCE_NOTICE_DURATION: Duration = Duration::from_secs(8);
+
+/// What the instance id of a topology refresh means for the colony the GUI shows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InstanceChange {
+    /// Same colony, or the coordinator did not say which one it runs
+    Unchanged,
+    /// The GUI attached without an id; this one names the colony it already shows
+    Identified(String),
+    /// The colony was restarted; everything cached belongs to the previous one
+    Replaced(String),
+}
+
+/// Compares the id the GUI is attached to with the one the coordinator reports.
+/// A missing report is not a change: the coordinator leaves the id out while it restarts.
+pub fn detect_instance_change(attached: Option<&str>, reported: Option<&str>) -> InstanceChange {
+    match (attached, reported) {
+        (_, None) => InstanceChange::Unchanged,
+        (None, Some(reported)) => InstanceChange::Identified(reported.to_string()),
+        (Some(attached), Some(reported)) if attached == reported => InstanceChange::Unchanged,
+        (Some(_), Some(reported)) => InstanceChange::Replaced(reported.to_string()),
+    }
+}
+
+/// One-shot note shown after the GUI switched to a new colony
+#[derive(Debug, Clone)]
+pub struct InstanceNotice {
+    message: String,
+    shown_at: Instant,
+}
+
+impl InstanceNotice {
+    pub fn new(colony_instance_id: &str, now: Instant) -> Self {
+        Self { message: format!("Attached to new colony {}", colony_instance_id), shown_at: now }
+    }
+
+    /// The message until INSTANCE_NOTICE_DURATION has passed
+    pub fn visible(&self, now: Instant) -> Option<&str> {
+        (now.saturating_duration_since(self.shown_at) < INSTANCE_NOTICE_DURATION).then_some(self.message.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restart_is_detected() {
+        assert_eq!(detect_instance_change(Some("run-a"), Some("run-b")), InstanceChange::Replaced("run-b".to_string()));
+        assert_eq!(detect_instance_change(Some("run-a"), Some("run-a")), InstanceChange::Unchanged);
+    }
+
+    #[test]
+    fn test_missing_ids() {
+        // Mid-restart the coordinator has no instance yet; keep showing the old one until it does
+        assert_eq!(detect_instance_change(Some("run-a"), None), InstanceChange::Unchanged);
+        assert_eq!(detect_instance_change(None, None), InstanceChange::Unchanged);
+        assert_eq!(detect_instance_change(None, Some("run-a")), InstanceChange::Identified("run-a".to_string()));
+    }
+
+    #[test]
+    fn test_notice_expires() {
+        let now = Instant::now();
+        let notice = InstanceNotice::new("run-b", now);
+        assert_eq!(notice.visible(now), Some("Attached to new colony run-b"));
+        assert_eq!(notice.visible(now + INSTANCE_NOTICE_DURATION - Duration::from_millis(1)), Some("Attached to new colony run-b"));
+        assert_eq!(notice.visible(now + INSTANCE_NOTICE_DURATION), None);
+    }
+}
@@ -10,6 +10,7 @@ pub enum OperationType {
     GetColonyStats,     // /api/colony-stats (coordinator)
     GetColonyEvents,    // /api/colony-events (coordinator)
     GetTopology,        // /topology (coordinator)
+    HealthCheck,        // /health (backend)
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -75,19 +76,21 @@ impl LatencyTracker {
         let measurements = self.measurements.lock().expect("Failed to lock measurements");
         let errors = self.errors.lock().expect("Failed to lock errors");
 
-        let samples = measurements.get(key)?;
-        if samples.is_empty() {
+        let error_count = errors.get(key).copied().unwrap_or(0);
+        let sample_count = measurements.get(key).map(|samples| samples.len()).unwrap_or(0);
+        // A node that only ever failed still needs to show up in the error columns
+        if sample_count == 0 && error_count == 0 {
             return None;
         }
 
-        let total_ms: f64 = samples.iter().map(|d| d.as_secs_f64() * 1000.0).sum();
-        let avg_ms = total_ms / samples.len() as f64;
-
-        let error_count = errors.get(key).copied().unwrap_or(0);
+        let total_ms: f64 = measurements.get(key)
+            .map(|samples| samples.iter().map(|d| d.as_secs_f64() * 1000.0).sum())
+            .unwrap_or(0.0);
+        let avg_ms = if sample_count > 0 { total_ms / sample_count as f64 } else { 0.0 };
 
         Some(LatencyStats {
             avg_ms,
-            sample_count: samples.len(),
+            sample_count,
             error_count,
         })
     }
@@ -100,6 +103,7 @@ impl LatencyTracker {
             OperationType::GetColonyStats,
             OperationType::GetColonyEvents,
             OperationType::GetTopology,
+            OperationType::HealthCheck,
         ];
 
         let mut total_weighted_latency = 0.0;
@@ -233,6 +237,23 @@ mod tests {
         assert!(tracker.get_stats(&key).is_none());
     }
 
+    #[test]
+    fn test_errors_without_samples_count_towards_node_stats() {
+        let tracker = LatencyTracker::new(100);
+        let host = create_test_host(8080);
+        let key = OperationKey::new(OperationType::HealthCheck, host.clone());
+
+        tracker.record_error(key.clone());
+
+        let stats = tracker.get_stats(&key).expect("Stats should exist");
+        assert_eq!(stats.sample_count, 0);
+        assert_eq!(stats.error_count, 1);
+
+        let node_stats = tracker.get_node_stats(&host);
+        assert!(node_stats.avg_latency_ms.is_none());
+        assert_eq!(node_stats.total_error_rate, 100.0);
+    }
+
     #[test]
     fn test_error_rate_calculation() {
         let tracker = LatencyTracker::new(100);
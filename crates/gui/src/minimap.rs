@@ -0,0 +1,129 @@
+use eframe::egui;
+use shared::colony_model::GlobalPos;
+use crate::{ShardConfig, Tab};
+
+/// Longer side of the minimap, in minimap pixels
+pub const MINIMAP_MAX_SIDE: usize = 160;
+/// Shards without data, on the minimap and in the main image
+pub const MISSING_SHARD_COLOR: egui::Color32 = egui::Color32::from_gray(110);
+const VIEWPORT_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 60, 200);
+
+/// A downsampled colony image, built by the polling thread from the frame it just fetched
+pub struct MinimapFrame {
+    pub tab: Tab,
+    /// Bumped with every frame, so the UI uploads a texture once per poll rather than per egui frame
+    pub generation: u64,
+    pub image: egui::ColorImage,
+}
+
+/// Colony cells per minimap pixel along both axes, so the longer side fits max_side
+pub fn cells_per_pixel(total_width: usize, total_height: usize, max_side: usize) -> usize {
+    total_width.max(total_height).div_ceil(max_side.max(1)).max(1)
+}
+
+/// Samples one cell per minimap pixel from the per-shard data, laid out like the combined image.
+/// cell_color gets a shard's data and the cell index within it; shards without data are gray.
+pub fn downsample<T>(config: &ShardConfig, data: &[Option<T>], max_side: usize, cell_color: impl Fn(&T, usize) -> Option<egui::Color32>) -> egui::ColorImage {
+    let total_width = config.total_width.max(0) as usize;
+    let total_height = config.total_height.max(0) as usize;
+    let step = cells_per_pixel(total_width, total_height, max_side);
+    let size = [total_width.div_ceil(step), total_height.div_ceil(step)];
+    let shard_width = config.shard_width().max(1) as usize;
+    let shard_height = config.shard_height().max(1) as usize;
+    let mut image = egui::ColorImage::new(size, MISSING_SHARD_COLOR);
+    for y in 0..size[1] {
+        for x in 0..size[0] {
+            // The middle cell of the block this pixel stands for
+            let cell_x = (x * step + step / 2).min(total_width - 1);
+            let cell_y = (y * step + step / 2).min(total_height - 1);
+            let col = (cell_x / shard_width).min(config.cols.saturating_sub(1));
+            let row = (cell_y / shard_height).min(config.rows.saturating_sub(1));
+            // The last column and row absorb the remainder, as in the combined image
+            let width = if col == config.cols - 1 { total_width - col * shard_width } else { shard_width };
+            let local_idx = (cell_y - row * shard_height) * width + (cell_x - col * shard_width);
+            let color = data.get(row * config.cols + col)
+                .and_then(Option::as_ref)
+                .and_then(|shard_data| cell_color(shard_data, local_idx));
+            if let Some(color) = color {
+                image.pixels[y * size[0] + x] = color;
+            }
+        }
+    }
+    image
+}
+
+/// Where the visible colony area (in cells) falls on a minimap drawn into minimap_rect
+pub fn viewport_on_minimap(minimap_rect: egui::Rect, colony_size: egui::Vec2, visible: egui::Rect) -> egui::Rect {
+    let to_minimap = |pos: egui::Pos2| minimap_rect.min + (pos.to_vec2() / colony_size) * minimap_rect.size();
+    egui::Rect::from_min_max(to_minimap(visible.min), to_minimap(visible.max)).intersect(minimap_rect)
+}
+
+/// The colony cell under a point of the minimap, clamped to the colony
+pub fn minimap_to_colony(minimap_rect: egui::Rect, colony_size: egui::Vec2, pos: egui::Pos2) -> GlobalPos {
+    let cell = (pos - minimap_rect.min) / minimap_rect.size() * colony_size;
+    GlobalPos::new(
+        (cell.x as i32).clamp(0, (colony_size.x as i32 - 1).max(0)),
+        (cell.y as i32).clamp(0, (colony_size.y as i32 - 1).max(0)),
+    )
+}
+
+/// Draws the minimap with the viewport outline; returns the cell to center on when it was clicked or dragged
+pub fn show_minimap(ui: &egui::Ui, rect: egui::Rect, texture: &egui::TextureHandle, colony_size: egui::Vec2, visible: egui::Rect) -> Option<GlobalPos> {
+    let response = ui.interact(rect, ui.id().with("minimap"), egui::Sense::click_and_drag());
+    let painter = ui.painter_at(rect.expand(2.0));
+    painter.rect_filled(rect.expand(2.0), 2.0, egui::Color32::from_black_alpha(180));
+    painter.image(texture.id(), rect, egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)), egui::Color32::WHITE);
+    painter.rect_stroke(viewport_on_minimap(rect, colony_size, visible), 0.0, egui::Stroke::new(1.5, VIEWPORT_COLOR));
+    if response.clicked() || response.dragged() {
+        return response.interact_pointer_pos().map(|pos| minimap_to_colony(rect, colony_size, pos));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two by two shards of 10x10 cells
+    fn config() -> ShardConfig {
+        ShardConfig { total_width: 20, total_height: 20, cols: 2, rows: 2 }
+    }
+
+    #[test]
+    fn test_downsample_keeps_layout_and_grays_missing_shards() {
+        // Every cell colored by its shard index, shard 3 never arrived
+        let data: Vec<Option<u8>> = vec![Some(0), Some(1), Some(2), None];
+        let image = downsample(&config(), &data, 4, |shard, _| Some(egui::Color32::from_gray(*shard * 10)));
+        assert_eq!(image.size, [4, 4]);
+        assert_eq!(image.pixels[0], egui::Color32::from_gray(0));
+        assert_eq!(image.pixels[3], egui::Color32::from_gray(10));
+        assert_eq!(image.pixels[12], egui::Color32::from_gray(20));
+        assert_eq!(image.pixels[15], MISSING_SHARD_COLOR);
+    }
+
+    #[test]
+    fn test_downsample_samples_the_middle_cell() {
+        let data: Vec<Option<Vec<u8>>> = (0..4).map(|_| Some((0..100).collect())).collect();
+        let image = downsample(&config(), &data, 4, |cells, idx| cells.get(idx).map(|cell| egui::Color32::from_gray(*cell)));
+        // Pixel (1,0) covers cells 5..10 of the first shard row; its middle cell is (7,2)
+        assert_eq!(image.pixels[1], egui::Color32::from_gray(27));
+        // A small colony is not scaled up
+        assert_eq!(cells_per_pixel(20, 20, 160), 1);
+        assert_eq!(cells_per_pixel(1000, 250, 160), 7);
+    }
+
+    #[test]
+    fn test_viewport_and_click_mapping() {
+        let minimap = egui::Rect::from_min_size(egui::pos2(100.0, 50.0), egui::vec2(200.0, 100.0));
+        let colony = egui::vec2(1000.0, 500.0);
+        let visible = egui::Rect::from_min_size(egui::pos2(250.0, 0.0), egui::vec2(500.0, 250.0));
+        let viewport = viewport_on_minimap(minimap, colony, visible);
+        assert_eq!(viewport, egui::Rect::from_min_size(egui::pos2(150.0, 50.0), egui::vec2(100.0, 50.0)));
+        // A viewport larger than the colony stays on the minimap
+        let all = viewport_on_minimap(minimap, colony, egui::Rect::from_min_size(egui::pos2(0.0, 0.0), egui::vec2(3000.0, 900.0)));
+        assert_eq!(all, minimap);
+
+        assert_eq!(minimap_to_colony(minimap, colony, egui::pos2(200.0, 100.0)), GlobalPos::new(500, 250));
+        assert_eq!(minimap_to_colony(minimap, colony, egui::pos2(400.0, 0.0)), GlobalPos::new(999, 0));
+    }
+}
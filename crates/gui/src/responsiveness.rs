@@ -0,0 +1,221 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Number of poll cycles kept for the status indicator
+pub const POLL_WINDOW_CYCLES: usize = 20;
+/// Unresponsive is judged only over cycles that ended within this period
+pub const UNRESPONSIVE_PERIOD: Duration = Duration::from_secs(30);
+/// Below this fraction of successful recent cycles the backend is considered unresponsive
+pub const UNRESPONSIVE_MAX_SUCCESS_RATE: f64 = 0.10;
+/// Above this fraction of failed cycles in the window the backend is considered slow
+pub const SLOW_MIN_FAILURE_RATE: f64 = 0.25;
+/// Mean cycle duration above which the backend is considered slow
+pub const SLOW_CYCLE_DURATION_LOCALHOST: Duration = Duration::from_millis(1000);
+// ~2x the AWS poll interval
+pub const SLOW_CYCLE_DURATION_AWS: Duration = Duration::from_millis(6000);
+/// A new state must be derived this many cycles in a row before it is shown
+pub const HYSTERESIS_CYCLES: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuiResponsivenessState {
+    Healthy,
+    Slow,
+    Unresponsive,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct PollCycle {
+    pub ended_at: Instant,
+    pub success: bool,
+    pub duration: Duration,
+    /// Time since the last successful update when the cycle ended
+    pub data_age: Duration,
+}
+
+pub struct ResponsivenessTracker {
+    cycles: VecDeque<PollCycle>,
+    slow_cycle_duration: Duration,
+    state: GuiResponsivenessState,
+    // Candidate state and how many consecutive cycles derived it
+    pending: Option<(GuiResponsivenessState, usize)>,
+}
+
+impl ResponsivenessTracker {
+    pub fn new(slow_cycle_duration: Duration) -> Self {
+        Self {
+            cycles: VecDeque::with_capacity(POLL_WINDOW_CYCLES),
+            slow_cycle_duration,
+            state: GuiResponsivenessState::Healthy,
+            pending: None,
+        }
+    }
+
+    pub fn for_deployment_mode(deployment_mode: &str) -> Self {
+        if deployment_mode == "aws" {
+            Self::new(SLOW_CYCLE_DURATION_AWS)
+        } else {
+            Self::new(SLOW_CYCLE_DURATION_LOCALHOST)
+        }
+    }
+
+    pub fn state(&self) -> GuiResponsivenessState {
+        self.state
+    }
+
+    pub fn window_len(&self) -> usize {
+        self.cycles.len()
+    }
+
+    pub fn failed_count(&self) -> usize {
+        self.cycles.iter().filter(|cycle| !cycle.success).count()
+    }
+
+    /// Adds a cycle to the window. Returns (previous, current) when the shown state changed.
+    pub fn record(&mut self, cycle: PollCycle) -> Option<(GuiResponsivenessState, GuiResponsivenessState)> {
+        if self.cycles.len() == POLL_WINDOW_CYCLES {
+            self.cycles.pop_front();
+        }
+        self.cycles.push_back(cycle);
+
+        let derived = self.derive_state(cycle.ended_at);
+        if derived == self.state {
+            self.pending = None;
+            return None;
+        }
+        let consecutive = match self.pending {
+            Some((state, count)) if state == derived => count + 1,
+            _ => 1,
+        };
+        if consecutive < HYSTERESIS_CYCLES {
+            self.pending = Some((derived, consecutive));
+            return None;
+        }
+        let previous = self.state;
+        self.state = derived;
+        self.pending = None;
+        Some((previous, derived))
+    }
+
+    fn derive_state(&self, now: Instant) -> GuiResponsivenessState {
+        let recent: Vec<&PollCycle> = self.cycles.iter()
+            .filter(|cycle| now.duration_since(cycle.ended_at) <= UNRESPONSIVE_PERIOD)
+            .collect();
+        let recent_successes = recent.iter().filter(|cycle| cycle.success).count();
+        if (recent_successes as f64) < recent.len() as f64 * UNRESPONSIVE_MAX_SUCCESS_RATE {
+            return GuiResponsivenessState::Unresponsive;
+        }
+
+        let window = self.cycles.len() as f64;
+        let failure_rate = self.failed_count() as f64 / window;
+        let mean_duration = self.cycles.iter().map(|cycle| cycle.duration).sum::<Duration>() / self.cycles.len() as u32;
+        if failure_rate > SLOW_MIN_FAILURE_RATE || mean_duration > self.slow_cycle_duration {
+            GuiResponsivenessState::Slow
+        } else {
+            GuiResponsivenessState::Healthy
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+    /// Feeds one cycle per POLL_INTERVAL; returns every state transition
+    fn replay(tracker: &mut ResponsivenessTracker, start: Instant, history: &[(bool, u64)]) -> Vec<GuiResponsivenessState> {
+        history.iter().enumerate().filter_map(|(i, &(success, duration_ms))| {
+            tracker.record(PollCycle {
+                ended_at: start + POLL_INTERVAL * i as u32,
+                success,
+                duration: Duration::from_millis(duration_ms),
+                data_age: Duration::ZERO,
+            }).map(|(_, current)| current)
+        }).collect()
+    }
+
+    #[test]
+    fn test_single_failure_stays_healthy() {
+        let mut tracker = ResponsivenessTracker::new(SLOW_CYCLE_DURATION_LOCALHOST);
+        let mut history = vec![(true, 50); 10];
+        history.push((false, 50));
+        history.extend(vec![(true, 50); 10]);
+
+        assert!(replay(&mut tracker, Instant::now(), &history).is_empty());
+        assert_eq!(tracker.state(), GuiResponsivenessState::Healthy);
+        assert_eq!(tracker.failed_count(), 1);
+    }
+
+    #[test]
+    fn test_transition_requires_consecutive_cycles() {
+        let mut tracker = ResponsivenessTracker::new(SLOW_CYCLE_DURATION_LOCALHOST);
+        let start = Instant::now();
+        // Every cycle fails from the start: the derived state is Unresponsive immediately,
+        // but it is only shown once it held for HYSTERESIS_CYCLES cycles
+        for i in 1..=HYSTERESIS_CYCLES {
+            let transition = tracker.record(PollCycle {
+                ended_at: start + POLL_INTERVAL * i as u32,
+                success: false,
+                duration: Duration::from_millis(50),
+                data_age: POLL_INTERVAL * i as u32,
+            });
+            if i < HYSTERESIS_CYCLES {
+                assert_eq!(transition, None, "cycle {}", i);
+            } else {
+                assert_eq!(transition, Some((GuiResponsivenessState::Healthy, GuiResponsivenessState::Unresponsive)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_partial_failures_are_slow_not_unresponsive() {
+        let mut tracker = ResponsivenessTracker::new(SLOW_CYCLE_DURATION_LOCALHOST);
+        // Two of every three cycles fail: well above the slow rate, above the unresponsive floor
+        let history: Vec<(bool, u64)> = (0..POLL_WINDOW_CYCLES).map(|i| (i % 3 == 0, 50)).collect();
+
+        let transitions = replay(&mut tracker, Instant::now(), &history);
+        assert_eq!(transitions, vec![GuiResponsivenessState::Slow]);
+        assert_eq!(tracker.window_len(), POLL_WINDOW_CYCLES);
+    }
+
+    #[test]
+    fn test_long_cycles_are_slow() {
+        let mut tracker = ResponsivenessTracker::new(SLOW_CYCLE_DURATION_LOCALHOST);
+        let history = vec![(true, 1500); 5];
+
+        assert_eq!(replay(&mut tracker, Instant::now(), &history), vec![GuiResponsivenessState::Slow]);
+        assert_eq!(tracker.failed_count(), 0);
+    }
+
+    #[test]
+    fn test_recovery_after_outage() {
+        let mut tracker = ResponsivenessTracker::new(SLOW_CYCLE_DURATION_LOCALHOST);
+        let mut history = vec![(false, 50); POLL_WINDOW_CYCLES];
+        history.extend(vec![(true, 50); POLL_WINDOW_CYCLES]);
+
+        let transitions = replay(&mut tracker, Instant::now(), &history);
+        assert_eq!(transitions, vec![
+            GuiResponsivenessState::Unresponsive,
+            GuiResponsivenessState::Slow,
+            GuiResponsivenessState::Healthy,
+        ]);
+        assert_eq!(tracker.failed_count(), 0);
+    }
+
+    #[test]
+    fn test_old_successes_do_not_mask_outage() {
+        let mut tracker = ResponsivenessTracker::new(SLOW_CYCLE_DURATION_AWS);
+        let start = Instant::now();
+        // Sparse polls: successes long ago, then only failures within the unresponsive period
+        for i in 0..10 {
+            tracker.record(PollCycle { ended_at: start + Duration::from_secs(i), success: true, duration: Duration::from_millis(100), data_age: Duration::ZERO });
+        }
+        let outage_start = start + Duration::from_secs(60);
+        for i in 0..HYSTERESIS_CYCLES as u64 {
+            tracker.record(PollCycle { ended_at: outage_start + Duration::from_secs(3 * i), success: false, duration: Duration::from_millis(100), data_age: Duration::from_secs(60 + 3 * i) });
+        }
+
+        assert_eq!(tracker.state(), GuiResponsivenessState::Unresponsive);
+        assert_eq!(tracker.failed_count(), HYSTERESIS_CYCLES);
+    }
+}
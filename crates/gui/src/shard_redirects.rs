@@ -0,0 +1,51 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use shared::be_api::Shard;
+use shared::cluster_topology::HostInfo;
+
+/// Hosts backends named in 421 redirects, which win over the GUI's topology copy for their
+/// shard until the next topology refresh; the app shares one with its polling threads
+#[derive(Debug, Default)]
+pub struct ShardRedirects {
+    hosts: Mutex<HashMap<Shard, HostInfo>>,
+}
+
+impl ShardRedirects {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, shard: Shard, host: HostInfo) {
+        self.hosts.lock().unwrap().insert(shard, host);
+    }
+
+    /// The redirected host of the shard, else the one from the topology
+    pub fn host_for(&self, shard: &Shard, topology_host: &HostInfo) -> HostInfo {
+        self.hosts.lock().unwrap().get(shard).unwrap_or(topology_host).clone()
+    }
+
+    /// A fresh topology already routes the shards where they are
+    pub fn clear(&self) {
+        self.hosts.lock().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redirect_overrides_the_topology_until_cleared() {
+        let redirects = ShardRedirects::new();
+        let shard = Shard { x: 0, y: 0, width: 250, height: 250 };
+        let topology_host = HostInfo::new("10.0.0.2".to_string(), 8084);
+        let moved_to = HostInfo::new("10.0.0.3".to_string(), 8084);
+
+        assert_eq!(redirects.host_for(&shard, &topology_host), topology_host);
+        redirects.record(shard, moved_to.clone());
+        assert_eq!(redirects.host_for(&shard, &topology_host), moved_to);
+        assert_eq!(redirects.host_for(&Shard { x: 250, ..shard }, &topology_host), topology_host);
+        redirects.clear();
+        assert_eq!(redirects.host_for(&shard, &topology_host), topology_host);
+    }
+}
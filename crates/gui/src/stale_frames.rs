@@ -0,0 +1,71 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use shared::be_api::STALE_TICKS_HEADER;
+
+/// The stale-frames note stays up this long after the last stale response,
+/// so it does not blink between polls (~3x the localhost poll interval)
+pub const STALE_NOTE_DURATION: Duration = Duration::from_secs(10);
+
+/// Most recent X-Colony-Stale value seen on a shard image or layer response
+pub struct StaleFrameTracker {
+    last: Mutex<Option<(u64, Instant)>>,
+}
+
+pub static STALE_FRAMES: StaleFrameTracker = StaleFrameTracker::new();
+
+impl StaleFrameTracker {
+    pub const fn new() -> Self {
+        Self { last: Mutex::new(None) }
+    }
+
+    pub fn record(&self, stale_ticks: u64, now: Instant) {
+        *self.last.lock().unwrap() = Some((stale_ticks, now));
+    }
+
+    /// How many ticks stale the frames are, while a stale response was seen recently
+    pub fn stale_ticks(&self, now: Instant) -> Option<u64> {
+        self.last.lock().unwrap()
+            .filter(|(_, seen_at)| now.saturating_duration_since(*seen_at) < STALE_NOTE_DURATION)
+            .map(|(ticks, _)| ticks)
+    }
+
+    /// Records the header if the backend served a cached frame
+    pub fn record_response_headers(&self, headers: &reqwest::header::HeaderMap) {
+        if let Some(stale_ticks) = parse_stale_header(headers) {
+            self.record(stale_ticks, Instant::now());
+        }
+    }
+}
+
+pub fn parse_stale_header(headers: &reqwest::header::HeaderMap) -> Option<u64> {
+    headers.get(STALE_TICKS_HEADER)?.to_str().ok()?.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{HeaderMap, HeaderValue};
+
+    #[test]
+    fn test_parse_stale_header() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(parse_stale_header(&headers), None);
+        headers.insert(STALE_TICKS_HEADER, HeaderValue::from_static("12"));
+        assert_eq!(parse_stale_header(&headers), Some(12));
+        headers.insert(STALE_TICKS_HEADER, HeaderValue::from_static("soon"));
+        assert_eq!(parse_stale_header(&headers), None);
+    }
+
+    #[test]
+    fn test_note_expires() {
+        let tracker = StaleFrameTracker::new();
+        let now = Instant::now();
+        assert_eq!(tracker.stale_ticks(now), None);
+
+        tracker.record(3, now);
+        assert_eq!(tracker.stale_ticks(now + Duration::from_secs(1)), Some(3));
+        tracker.record(5, now + Duration::from_secs(2));
+        assert_eq!(tracker.stale_ticks(now + Duration::from_secs(2) + STALE_NOTE_DURATION - Duration::from_millis(1)), Some(5));
+        assert_eq!(tracker.stale_ticks(now + Duration::from_secs(2) + STALE_NOTE_DURATION), None);
+    }
+}
@@ -0,0 +1,109 @@
+use shared::coordinator_api::{ColonyStatsResponse, TickSample};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use shared::output_paths::OutputPaths;
+
+pub fn export_dir() -> PathBuf {
+    OutputPaths::get_instance().gui_exports_dir()
+}
+
+/// Quotes a CSV field when it holds a comma, quote or line break, doubling inner quotes
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// One row per histogram bucket, then an avg row per metric whose occurrences are the bucket total
+pub fn stats_to_csv(stats: &ColonyStatsResponse) -> String {
+    let mut csv = String::from("metric,row,value,occurrences\n");
+    for metric_stats in &stats.stats {
+        let metric = csv_field(&format!("{:?}", metric_stats.metric));
+        for bucket in &metric_stats.buckets {
+            let _ = writeln!(csv, "{},bucket,{},{}", metric, bucket.value, bucket.occs);
+        }
+        let total: u64 = metric_stats.buckets.iter().map(|bucket| bucket.occs).sum();
+        let _ = writeln!(csv, "{},avg,{},{}", metric, metric_stats.avg, total);
+    }
+    csv
+}
+
+/// Tick history in long format: one row per sample and series
+pub fn tick_history_to_csv(samples: &[TickSample]) -> String {
+    let mut csv = String::from("timestamp_ms,series,value\n");
+    for sample in samples {
+        let _ = writeln!(csv, "{},min_tick,{}", sample.timestamp_ms, sample.min_tick);
+        let _ = writeln!(csv, "{},max_tick,{}", sample.timestamp_ms, sample.max_tick);
+    }
+    csv
+}
+
+/// Writes stats_{tick}.csv and .json, plus tick_history_{tick}.csv when a history is given.
+/// Returns the written paths.
+pub fn export_stats(dir: &Path, stats: &ColonyStatsResponse, tick_history: Option<&[TickSample]>) -> Result<Vec<PathBuf>, String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let json = serde_json::to_string_pretty(stats).map_err(|e| format!("Failed to serialize stats: {}", e))?;
+    let mut files = vec![
+        (dir.join(format!("stats_{}.csv", stats.tick)), stats_to_csv(stats)),
+        (dir.join(format!("stats_{}.json", stats.tick)), json),
+    ];
+    if let Some(samples) = tick_history.filter(|samples| !samples.is_empty()) {
+        files.push((dir.join(format!("tick_history_{}.csv", stats.tick)), tick_history_to_csv(samples)));
+    }
+
+    let mut written = Vec::new();
+    for (path, contents) in files {
+        std::fs::write(&path, contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::be_api::{StatBucket, StatMetric};
+    use shared::coordinator_api::ColonyMetricStats;
+
+    fn stats() -> ColonyStatsResponse {
+        ColonyStatsResponse {
+            tick: 42,
+            age_ms: 0,
+            cached: false,
+            stats: vec![ColonyMetricStats {
+                metric: StatMetric::Health,
+                avg: 12.5,
+                buckets: vec![StatBucket { value: 10, occs: 3 }, StatBucket { value: 15, occs: 1 }],
+            }],
+            species: Vec::new(),
+            region: None,
+            region_cells: None,
+        }
+    }
+
+    #[test]
+    fn test_csv_field_quoting() {
+        assert_eq!(csv_field("Health"), "Health");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+        assert_eq!(csv_field(""), "");
+    }
+
+    #[test]
+    fn test_stats_csv_has_bucket_and_avg_rows() {
+        assert_eq!(
+            stats_to_csv(&stats()),
+            "metric,row,value,occurrences\nHealth,bucket,10,3\nHealth,bucket,15,1\nHealth,avg,12.5,4\n"
+        );
+    }
+
+    #[test]
+    fn test_tick_history_csv_is_long_format() {
+        let samples = [TickSample { timestamp_ms: 1000, min_tick: 5, max_tick: 7 }];
+        assert_eq!(tick_history_to_csv(&samples), "timestamp_ms,series,value\n1000,min_tick,5\n1000,max_tick,7\n");
+    }
+}
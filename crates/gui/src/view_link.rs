@@ -0,0 +1,234 @@
+use shared::colony_model::GlobalPos;
+use crate::Tab;
+
+/// Every view link starts with this, e.g. colony://creatures?x=812&y=344&zoom=4
+pub const VIEW_LINK_PREFIX: &str = "colony://";
+pub const MAX_ZOOM: u32 = 16;
+
+const SANCTUARIES_OVERLAY: &str = "sanctuaries";
+const BIOMES_OVERLAY: &str = "biomes";
+
+const TAB_NAMES: [(Tab, &str); 12] = [
+    (Tab::Creatures, "creatures"),
+    (Tab::ExtraFood, "extra-food"),
+    (Tab::Food, "food"),
+    (Tab::Sizes, "sizes"),
+    (Tab::CanKill, "can-kill"),
+    (Tab::CanMove, "can-move"),
+    (Tab::CostPerTurn, "cost-per-turn"),
+    (Tab::Health, "health"),
+    (Tab::Age, "age"),
+    (Tab::Stats, "stats"),
+    (Tab::Info, "info"),
+    (Tab::Cluster, "cluster"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ViewZoom {
+    /// The whole colony scaled into the window
+    Fit,
+    /// Points per cell
+    Scale(u32),
+}
+
+impl ViewZoom {
+    pub fn label(self) -> String {
+        match self {
+            ViewZoom::Fit => "Fit".to_string(),
+            ViewZoom::Scale(scale) => format!("{}x", scale),
+        }
+    }
+}
+
+/// What a teammate needs to see the same thing: tab, zoom, scroll position, overlays and inspected cell
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViewState {
+    pub tab: Tab,
+    pub zoom: ViewZoom,
+    /// Colony coordinate at the center of the viewport; ignored when zoomed to fit
+    pub center: Option<GlobalPos>,
+    pub show_sanctuaries: bool,
+    pub show_biomes: bool,
+    pub inspected_cell: Option<GlobalPos>,
+}
+
+fn tab_name(tab: Tab) -> &'static str {
+    TAB_NAMES.iter().find(|(named, _)| *named == tab).map(|(_, name)| *name).unwrap_or("creatures")
+}
+
+fn parse_number<T: std::str::FromStr>(key: &str, value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("'{}' is not a valid {} in the view link", value, key))
+}
+
+fn parse_zoom(value: &str) -> Result<ViewZoom, String> {
+    if value == "fit" {
+        return Ok(ViewZoom::Fit);
+    }
+    match parse_number::<u32>("zoom", value)? {
+        scale @ 1..=MAX_ZOOM => Ok(ViewZoom::Scale(scale)),
+        scale => Err(format!("Zoom {} is outside 1..={}", scale, MAX_ZOOM)),
+    }
+}
+
+impl ViewState {
+    pub fn to_link(&self) -> String {
+        let mut params = Vec::new();
+        if let (Some(center), ViewZoom::Scale(_)) = (self.center, self.zoom) {
+            params.push(format!("x={}", center.x));
+            params.push(format!("y={}", center.y));
+        }
+        params.push(match self.zoom {
+            ViewZoom::Fit => "zoom=fit".to_string(),
+            ViewZoom::Scale(scale) => format!("zoom={}", scale),
+        });
+        let overlays: Vec<&str> = [(self.show_sanctuaries, SANCTUARIES_OVERLAY), (self.show_biomes, BIOMES_OVERLAY)].into_iter()
+            .filter_map(|(shown, name)| shown.then_some(name))
+            .collect();
+        if !overlays.is_empty() {
+            params.push(format!("overlays={}", overlays.join(",")));
+        }
+        if let Some(cell) = self.inspected_cell {
+            params.push(format!("cell={},{}", cell.x, cell.y));
+        }
+        format!("{}{}?{}", VIEW_LINK_PREFIX, tab_name(self.tab), params.join("&"))
+    }
+
+    /// Parses a link made by to_link. Unknown parameters and overlays are skipped so links from
+    /// newer GUIs still open; a missing zoom means 1x, like a freshly started GUI.
+    pub fn parse(link: &str) -> Result<Self, String> {
+        let rest = link.trim().strip_prefix(VIEW_LINK_PREFIX)
+            .ok_or_else(|| format!("View links start with {}", VIEW_LINK_PREFIX))?;
+        let (name, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let tab = TAB_NAMES.iter()
+            .find(|(_, tab_name)| tab_name.eq_ignore_ascii_case(name.trim_end_matches('/')))
+            .map(|(tab, _)| *tab)
+            .ok_or_else(|| format!("Unknown tab '{}' in the view link", name))?;
+
+        let mut state = ViewState {
+            tab,
+            zoom: ViewZoom::Scale(1),
+            center: None,
+            show_sanctuaries: false,
+            show_biomes: false,
+            inspected_cell: None,
+        };
+        let (mut x, mut y) = (None, None);
+        for (key, value) in query.split('&').filter_map(|param| param.split_once('=')) {
+            match key {
+                "x" => x = Some(parse_number::<i32>(key, value)?),
+                "y" => y = Some(parse_number::<i32>(key, value)?),
+                "zoom" => state.zoom = parse_zoom(value)?,
+                "overlays" => {
+                    for overlay in value.split(',') {
+                        match overlay {
+                            SANCTUARIES_OVERLAY => state.show_sanctuaries = true,
+                            BIOMES_OVERLAY => state.show_biomes = true,
+                            _ => {}
+                        }
+                    }
+                }
+                "cell" => {
+                    let (cell_x, cell_y) = value.split_once(',')
+                        .ok_or_else(|| format!("Cell '{}' is not shaped like x,y", value))?;
+                    state.inspected_cell = Some(GlobalPos::new(parse_number(key, cell_x)?, parse_number(key, cell_y)?));
+                }
+                _ => {}
+            }
+        }
+        state.center = match (x, y) {
+            (Some(x), Some(y)) => Some(GlobalPos::new(x, y)),
+            (None, None) => None,
+            _ => return Err("The view link needs both x and y".to_string()),
+        };
+        Ok(state)
+    }
+
+    /// Drops coordinates outside a width x height colony and zooms to fit instead, since the link
+    /// was made for another colony; returns the warning to show
+    pub fn fit_to_bounds(&mut self, width: i32, height: i32) -> Option<String> {
+        let inside = |pos: GlobalPos| (0..width).contains(&pos.x) && (0..height).contains(&pos.y);
+        let outside = [self.center, self.inspected_cell].into_iter().flatten().find(|pos| !inside(*pos))?;
+        self.zoom = ViewZoom::Fit;
+        self.center = None;
+        self.inspected_cell = self.inspected_cell.filter(|cell| inside(*cell));
+        Some(format!("The link points at {},{}, outside this {}x{} colony; showing all of it", outside.x, outside.y, width, height))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> ViewState {
+        ViewState {
+            tab: Tab::Creatures,
+            zoom: ViewZoom::Scale(4),
+            center: Some(GlobalPos::new(812, 344)),
+            show_sanctuaries: false,
+            show_biomes: false,
+            inspected_cell: None,
+        }
+    }
+
+    #[test]
+    fn test_compact_link() {
+        assert_eq!(state().to_link(), "colony://creatures?x=812&y=344&zoom=4");
+        assert_eq!(ViewState::parse("colony://creatures?x=812&y=344&zoom=4"), Ok(state()));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let every_field = ViewState {
+            tab: Tab::CostPerTurn,
+            show_sanctuaries: true,
+            show_biomes: true,
+            inspected_cell: Some(GlobalPos::new(800, 350)),
+            ..state()
+        };
+        assert_eq!(every_field.to_link(), "colony://cost-per-turn?x=812&y=344&zoom=4&overlays=sanctuaries,biomes&cell=800,350");
+        let fit = ViewState { tab: Tab::Stats, zoom: ViewZoom::Fit, center: None, ..state() };
+        for (tab, _) in TAB_NAMES {
+            for original in [every_field.clone(), fit.clone()] {
+                let original = ViewState { tab, ..original };
+                assert_eq!(ViewState::parse(&original.to_link()), Ok(original));
+            }
+        }
+        // The center means nothing when zoomed to fit, so it is not written
+        let fit_with_center = ViewState { zoom: ViewZoom::Fit, ..state() };
+        assert_eq!(fit_with_center.to_link(), "colony://creatures?zoom=fit");
+    }
+
+    #[test]
+    fn test_unknown_fields_are_ignored() {
+        let parsed = ViewState::parse(" colony://creatures?x=812&theme=dark&y=344&zoom=4&overlays=heat,biomes&flag ").unwrap();
+        assert_eq!(parsed, ViewState { show_biomes: true, ..state() });
+        assert_eq!(ViewState::parse("colony://Food").unwrap(), ViewState { tab: Tab::Food, zoom: ViewZoom::Scale(1), center: None, ..state() });
+    }
+
+    #[test]
+    fn test_invalid_links() {
+        assert!(ViewState::parse("http://creatures?x=1&y=2").unwrap_err().contains("colony://"));
+        assert!(ViewState::parse("colony://weather").unwrap_err().contains("weather"));
+        assert!(ViewState::parse("colony://creatures?x=abc&y=2").unwrap_err().contains("abc"));
+        assert!(ViewState::parse("colony://creatures?x=5").is_err());
+        assert!(ViewState::parse("colony://creatures?zoom=0").is_err());
+        assert!(ViewState::parse("colony://creatures?zoom=64").is_err());
+        assert!(ViewState::parse("colony://creatures?cell=12").is_err());
+    }
+
+    #[test]
+    fn test_out_of_bounds_falls_back_to_fit() {
+        let mut inside = state();
+        assert_eq!(inside.fit_to_bounds(1000, 500), None);
+        assert_eq!(inside, state());
+
+        let mut outside = ViewState { inspected_cell: Some(GlobalPos::new(10, 20)), ..state() };
+        let warning = outside.fit_to_bounds(500, 500).unwrap();
+        assert!(warning.contains("812,344") && warning.contains("500x500"), "{}", warning);
+        assert_eq!(outside, ViewState { zoom: ViewZoom::Fit, center: None, inspected_cell: Some(GlobalPos::new(10, 20)), ..state() });
+
+        let mut cell_outside = ViewState { inspected_cell: Some(GlobalPos::new(-1, 20)), ..state() };
+        assert!(cell_outside.fit_to_bounds(1000, 500).is_some());
+        assert_eq!((cell_outside.zoom, cell_outside.center, cell_outside.inspected_cell), (ViewZoom::Fit, None, None));
+    }
+}
@@ -0,0 +1,247 @@
+use eframe::egui;
+use shared::be_api::{Shard, ShardLayer};
+use crate::{ShardConfig, Tab};
+
+/// Off-screen shards of the active layer are refetched on every this many poll cycles
+pub const OFFSCREEN_REFRESH_CYCLES: u64 = 10;
+
+/// Shards overlapping the visible colony area (in cells) grown by one shard on every side
+pub fn shards_near_viewport(config: &ShardConfig, visible: egui::Rect) -> Vec<bool> {
+    let area = visible.expand2(egui::vec2(config.shard_width() as f32, config.shard_height() as f32));
+    (0..config.total_shards())
+        .map(|index| {
+            let shard = config.get_shard(index);
+            let (x, y) = (shard.x as f32, shard.y as f32);
+            // Strict, so a shard just touching the margin is not fetched
+            x < area.max.x && area.min.x < x + shard.width as f32 && y < area.max.y && area.min.y < y + shard.height as f32
+        })
+        .collect()
+}
+
+/// Cells of the visible colony area, partly visible ones included; None for an empty area
+pub fn viewport_region(visible: egui::Rect) -> Option<Shard> {
+    let (left, top) = (visible.min.x.floor() as i32, visible.min.y.floor() as i32);
+    let (right, bottom) = (visible.max.x.ceil() as i32, visible.max.y.ceil() as i32);
+    (right > left && bottom > top).then_some(Shard { x: left, y: top, width: right - left, height: bottom - top })
+}
+
+/// Decides which shards of the active layer a poll cycle fetches. A changed viewport is only
+/// followed once it held for a cycle, so rapid panning keeps fetching the last settled area.
+#[derive(Default)]
+pub struct ViewportPoller {
+    cycle: u64,
+    layer: Option<ShardLayer>,
+    /// Shards near the viewport the previous cycle saw
+    pending: Option<Vec<bool>>,
+    settled: Option<Vec<bool>>,
+}
+
+impl ViewportPoller {
+    /// Shards to fetch this cycle; have_data tells which shards of the layer hold data already.
+    /// Everything is fetched every OFFSCREEN_REFRESH_CYCLES cycles, after a layer switch, while no
+    /// viewport settled and when the shard layout changed; shards without data are always fetched.
+    pub fn plan(&mut self, layer: ShardLayer, config: &ShardConfig, viewport: Option<egui::Rect>, have_data: &[bool]) -> Vec<bool> {
+        let total = config.total_shards();
+        let near = viewport.map(|visible| shards_near_viewport(config, visible));
+        if near.is_none() || near == self.pending {
+            self.settled = near.clone();
+        }
+        self.pending = near;
+        let full_refresh = self.cycle.is_multiple_of(OFFSCREEN_REFRESH_CYCLES) || self.layer != Some(layer);
+        self.cycle += 1;
+        self.layer = Some(layer);
+        match &self.settled {
+            Some(near) if !full_refresh && near.len() == total && have_data.len() == total => {
+                near.iter().zip(have_data).map(|(&near, &has_data)| near || !has_data).collect()
+            }
+            _ => vec![true; total],
+        }
+    }
+}
+
+/// Shards of the polled tab that got no data for this many cycles are marked stale on the image
+pub const STALE_SHARD_CYCLES: u64 = 3;
+
+/// Stores the shards fetched this cycle that returned data; skipped shards and failed fetches
+/// keep their older data, so one failing shard does not render black. A changed shard layout
+/// replaces everything. Returns which slots were updated.
+pub fn merge_fetched<T>(stored: &mut Vec<Option<T>>, fetched: Vec<Option<T>>, fetch: &[bool]) -> Vec<bool> {
+    let updated: Vec<bool> = fetched.iter().zip(fetch).map(|(data, &was_fetched)| was_fetched && data.is_some()).collect();
+    if stored.len() != fetched.len() {
+        *stored = fetched;
+        return updated;
+    }
+    for ((slot, data), &was_updated) in stored.iter_mut().zip(fetched).zip(&updated) {
+        if was_updated {
+            *slot = data;
+        }
+    }
+    updated
+}
+
+/// merge_fetched for a cycle that fetched every shard
+pub fn merge_successful<T>(stored: &mut Vec<Option<T>>, fetched: Vec<Option<T>>) -> Vec<bool> {
+    let fetch = vec![true; fetched.len()];
+    merge_fetched(stored, fetched, &fetch)
+}
+
+/// Poll cycle in which each shard of the polled tab last got data, for the stale shard overlay
+#[derive(Debug, Default)]
+pub struct ShardRefresh {
+    tab: Option<Tab>,
+    cycle: u64,
+    /// Cycle the tab was first polled in, standing in for shards that never got data since
+    since: u64,
+    last_updated: Vec<Option<u64>>,
+}
+
+impl ShardRefresh {
+    /// Records a poll cycle of tab; polling another tab or layout starts over
+    pub fn record(&mut self, tab: Tab, cycle: u64, updated: &[bool]) {
+        if self.tab != Some(tab) || self.last_updated.len() != updated.len() {
+            self.tab = Some(tab);
+            self.since = cycle;
+            self.last_updated = vec![None; updated.len()];
+        }
+        self.cycle = cycle;
+        for (last, &was_updated) in self.last_updated.iter_mut().zip(updated) {
+            if was_updated {
+                *last = Some(cycle);
+            }
+        }
+    }
+
+    /// Shards of tab without data for STALE_SHARD_CYCLES cycles or more, with the cycles missed
+    pub fn stale_shards(&self, tab: Tab) -> Vec<(usize, u64)> {
+        if self.tab != Some(tab) {
+            return Vec::new();
+        }
+        self.last_updated.iter()
+            .enumerate()
+            .map(|(index, last)| (index, self.cycle - last.unwrap_or(self.since)))
+            .filter(|(_, missed)| *missed >= STALE_SHARD_CYCLES)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ten by ten shards of 100x100 cells
+    fn config() -> ShardConfig {
+        ShardConfig { total_width: 1000, total_height: 1000, cols: 10, rows: 10 }
+    }
+
+    fn view(x: f32, y: f32, width: f32, height: f32) -> Option<egui::Rect> {
+        Some(egui::Rect::from_min_size(egui::pos2(x, y), egui::vec2(width, height)))
+    }
+
+    fn fetched(plan: &[bool]) -> Vec<usize> {
+        plan.iter().enumerate().filter(|(_, &fetch)| fetch).map(|(index, _)| index).collect()
+    }
+
+    #[test]
+    fn test_viewport_and_margin_select_shards() {
+        // Inside shard (4,4); the margin adds its eight neighbours
+        let near = shards_near_viewport(&config(), view(420.0, 430.0, 50.0, 40.0).unwrap());
+        assert_eq!(fetched(&near), vec![33, 34, 35, 43, 44, 45, 53, 54, 55]);
+        // Exactly one shard: the margin ends on the far shards' edges, so they stay out
+        let near = shards_near_viewport(&config(), view(0.0, 0.0, 100.0, 100.0).unwrap());
+        assert_eq!(fetched(&near), vec![0, 1, 10, 11]);
+    }
+
+    #[test]
+    fn test_viewport_is_followed_after_holding_for_a_cycle() {
+        let mut poller = ViewportPoller::default();
+        let have_data = vec![true; 100];
+        let first = view(0.0, 0.0, 100.0, 100.0);
+        // Full refresh on the first cycle, then the new viewport has not settled yet
+        assert_eq!(fetched(&poller.plan(ShardLayer::Food, &config(), first, &have_data)).len(), 100);
+        assert_eq!(fetched(&poller.plan(ShardLayer::Food, &config(), first, &have_data)), vec![0, 1, 10, 11]);
+
+        // Panning every cycle keeps the settled area
+        let panned = view(850.0, 850.0, 100.0, 100.0);
+        assert_eq!(fetched(&poller.plan(ShardLayer::Food, &config(), panned, &have_data)), vec![0, 1, 10, 11]);
+        assert_eq!(fetched(&poller.plan(ShardLayer::Food, &config(), first, &have_data)), vec![0, 1, 10, 11]);
+        assert_eq!(fetched(&poller.plan(ShardLayer::Food, &config(), panned, &have_data)), vec![0, 1, 10, 11]);
+        assert_eq!(fetched(&poller.plan(ShardLayer::Food, &config(), panned, &have_data)), vec![77, 78, 79, 87, 88, 89, 97, 98, 99]);
+
+        // Scrolling within the same shards is not a change
+        let nudged = view(860.0, 855.0, 100.0, 100.0);
+        assert_eq!(fetched(&poller.plan(ShardLayer::Food, &config(), nudged, &have_data)).len(), 9);
+    }
+
+    #[test]
+    fn test_full_refreshes() {
+        let mut poller = ViewportPoller::default();
+        let mut have_data = vec![true; 100];
+        let corner = view(0.0, 0.0, 100.0, 100.0);
+        for _ in 0..2 {
+            poller.plan(ShardLayer::Food, &config(), corner, &have_data);
+        }
+        for _ in 2..OFFSCREEN_REFRESH_CYCLES {
+            assert_eq!(fetched(&poller.plan(ShardLayer::Food, &config(), corner, &have_data)).len(), 4);
+        }
+        assert_eq!(fetched(&poller.plan(ShardLayer::Food, &config(), corner, &have_data)).len(), 100, "off-screen refresh");
+
+        // Shards without data are fetched wherever they are
+        have_data[99] = false;
+        assert_eq!(fetched(&poller.plan(ShardLayer::Food, &config(), corner, &have_data)), vec![0, 1, 10, 11, 99]);
+        have_data[99] = true;
+        assert_eq!(fetched(&poller.plan(ShardLayer::Health, &config(), corner, &have_data)).len(), 100, "layer switch");
+        assert_eq!(fetched(&poller.plan(ShardLayer::Health, &config(), corner, &have_data[..50])).len(), 100, "layout changed");
+        assert_eq!(fetched(&poller.plan(ShardLayer::Health, &config(), None, &have_data)).len(), 100, "no viewport");
+    }
+
+    #[test]
+    fn test_skipped_shards_keep_their_data() {
+        let mut stored = vec![Some(1), Some(2), None];
+        merge_fetched(&mut stored, vec![Some(10), None, None], &[true, false, true]);
+        assert_eq!(stored, vec![Some(10), Some(2), None]);
+        merge_fetched(&mut stored, vec![Some(5); 4], &[true; 4]);
+        assert_eq!(stored, vec![Some(5); 4]);
+    }
+
+    #[test]
+    fn test_failed_fetches_keep_their_data() {
+        let mut stored = vec![Some(1), Some(2), Some(3)];
+        assert_eq!(merge_fetched(&mut stored, vec![Some(10), None, None], &[true, true, false]), vec![true, false, false]);
+        assert_eq!(stored, vec![Some(10), Some(2), Some(3)]);
+        // Even a cycle where every shard failed leaves the frame as it was
+        assert_eq!(merge_successful(&mut stored, vec![None, None, None]), vec![false; 3]);
+        assert_eq!(stored, vec![Some(10), Some(2), Some(3)]);
+        assert_eq!(merge_successful(&mut stored, vec![None, Some(20), Some(30)]), vec![false, true, true]);
+        assert_eq!(stored, vec![Some(10), Some(20), Some(30)]);
+        // A new layout starts from what this cycle fetched
+        assert_eq!(merge_successful(&mut stored, vec![Some(7), None]), vec![true, false]);
+        assert_eq!(stored, vec![Some(7), None]);
+    }
+
+    #[test]
+    fn test_shards_turn_stale_after_missed_cycles() {
+        let mut refresh = ShardRefresh::default();
+        refresh.record(Tab::Creatures, 1, &[true, true]);
+        for cycle in 2..1 + STALE_SHARD_CYCLES {
+            refresh.record(Tab::Creatures, cycle, &[true, false]);
+        }
+        assert_eq!(refresh.stale_shards(Tab::Creatures), Vec::<(usize, u64)>::new());
+        refresh.record(Tab::Creatures, 1 + STALE_SHARD_CYCLES, &[true, false]);
+        assert_eq!(refresh.stale_shards(Tab::Creatures), vec![(1, STALE_SHARD_CYCLES)]);
+        assert_eq!(refresh.stale_shards(Tab::Food), Vec::<(usize, u64)>::new());
+        refresh.record(Tab::Creatures, 2 + STALE_SHARD_CYCLES, &[true, true]);
+        assert_eq!(refresh.stale_shards(Tab::Creatures), Vec::<(usize, u64)>::new());
+
+        // After a tab switch, a shard that never answered counts from the first poll of the tab
+        refresh.record(Tab::Food, 10, &[false, true]);
+        refresh.record(Tab::Food, 10 + STALE_SHARD_CYCLES, &[false, true]);
+        assert_eq!(refresh.stale_shards(Tab::Food), vec![(0, STALE_SHARD_CYCLES)]);
+    }
+
+    #[test]
+    fn test_viewport_region() {
+        let region = viewport_region(view(10.5, 20.0, 100.0, 50.0).unwrap());
+        assert_eq!(region, Some(Shard { x: 10, y: 20, width: 101, height: 50 }));
+        assert_eq!(viewport_region(view(10.0, 20.0, 0.0, 50.0).unwrap()), None);
+    }
+}
@@ -0,0 +1,123 @@
+use std::sync::OnceLock;
+
+pub const ADMIN_TOKEN_ENV: &str = "COLONY_ADMIN_TOKEN";
+pub const OBSERVER_TOKEN_ENV: &str = "COLONY_OBSERVER_TOKEN";
+
+const BEARER_PREFIX: &str = "Bearer ";
+// Debug pages list private addresses of every node, so they stay admin-only
+const ADMIN_ONLY_GET_PREFIX: &str = "GET /debug-";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiScope {
+    /// Full access to every endpoint
+    Admin,
+    /// Read-only access: GET endpoints only (debug pages excluded), sensitive fields omitted
+    Observer,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthRejection {
+    /// No token, or a token that matches neither scope
+    Unauthorized,
+    /// Valid token whose scope does not allow the request
+    Forbidden,
+}
+
+impl AuthRejection {
+    pub fn to_http_response(&self) -> String {
+        let (status_line, error_json) = match self {
+            AuthRejection::Unauthorized => ("401 Unauthorized", r#"{"error":"Missing or invalid API token"}"#),
+            AuthRejection::Forbidden => ("403 Forbidden", r#"{"error":"Observer token is read-only"}"#),
+        };
+        format!(
+            "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            status_line,
+            error_json.len(),
+            error_json
+        )
+    }
+}
+
+/// Token configuration for the HTTP APIs. When no token is configured auth is
+/// disabled and every request is treated as admin, which keeps local runs unchanged.
+#[derive(Debug, Clone, Default)]
+pub struct ApiAuthConfig {
+    admin_token: Option<String>,
+    observer_token: Option<String>,
+}
+
+static INSTANCE: OnceLock<ApiAuthConfig> = OnceLock::new();
+
+impl ApiAuthConfig {
+    pub fn new(admin_token: Option<String>, observer_token: Option<String>) -> Self {
+        Self {
+            admin_token: admin_token.filter(|t| !t.is_empty()),
+            observer_token: observer_token.filter(|t| !t.is_empty()),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(std::env::var(ADMIN_TOKEN_ENV).ok(), std::env::var(OBSERVER_TOKEN_ENV).ok())
+    }
+
+    /// Process-wide config, read from the environment on first use
+    pub fn get_instance() -> &'static ApiAuthConfig {
+        INSTANCE.get_or_init(Self::from_env)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.admin_token.is_some() || self.observer_token.is_some()
+    }
+
+    pub fn admin_token(&self) -> Option<&str> {
+        self.admin_token.as_deref()
+    }
+
+    pub fn scope_for_token(&self, token: Option<&str>) -> Option<ApiScope> {
+        if !self.is_enabled() {
+            return Some(ApiScope::Admin);
+        }
+        let token = token?;
+        if self.admin_token.as_deref() == Some(token) {
+            Some(ApiScope::Admin)
+        } else if self.observer_token.as_deref() == Some(token) {
+            Some(ApiScope::Observer)
+        } else {
+            None
+        }
+    }
+
+    /// Single entry point used by the HTTP dispatchers before routing a raw request
+    pub fn authorize(&self, request: &str) -> Result<ApiScope, AuthRejection> {
+        let scope = self
+            .scope_for_token(extract_bearer_token(request))
+            .ok_or(AuthRejection::Unauthorized)?;
+        if scope == ApiScope::Observer && !is_read_only_request(request) {
+            return Err(AuthRejection::Forbidden);
+        }
+        Ok(scope)
+    }
+}
+
+pub fn is_read_only_request(request: &str) -> bool {
+    request.starts_with("GET ") && !request.starts_with(ADMIN_ONLY_GET_PREFIX)
+}
+
+pub fn extract_bearer_token(request: &str) -> Option<&str> {
+    request
+        .lines()
+        .skip(1)
+        .take_while(|line| !line.is_empty())
+        .find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            if name.trim().eq_ignore_ascii_case("authorization") {
+                value.trim().strip_prefix(BEARER_PREFIX).map(str::trim)
+            } else {
+                None
+            }
+        })
+}
+
+pub fn bearer_header_value(token: &str) -> String {
+    format!("{}{}", BEARER_PREFIX, token)
+}
@@ -6,6 +6,10 @@ use bincode;
 use tokio::net::TcpStream as TokioTcpStream;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use backoff::{ExponentialBackoff, Error as BackoffError};
+use futures_util::SinkExt;
+use tokio_stream::StreamExt;
+use tokio_util::codec::{Framed, LengthDelimitedCodec};
+use crate::be_api::{WireHello, WireHelloResponse, BUILD_VERSION, PROTOCOL_VERSION, WIRE_HELLO_MAGIC};
 use crate::connection_pool::AsyncConnectionPool;
 use crate::cluster_topology::HostInfo;
 
@@ -88,6 +92,85 @@ pub async fn send_request_and_receive_response_async<Req: serde::Serialize, Resp
     receive_response_async(stream).await
 }
 
+/// Server side: whether a client's Hello may go on to send requests
+pub fn answer_hello(hello: &WireHello) -> WireHelloResponse {
+    let reason = if hello.magic != WIRE_HELLO_MAGIC {
+        Some("Expected a Hello frame before any request; the peer predates protocol versioning".to_string())
+    } else if hello.protocol_version.major != PROTOCOL_VERSION.major {
+        Some(format!("Protocol version {} is incompatible with {}", hello.protocol_version, PROTOCOL_VERSION))
+    } else {
+        None
+    };
+    match reason {
+        Some(reason) => WireHelloResponse::Rejected {
+            protocol_version: PROTOCOL_VERSION,
+            build_version: BUILD_VERSION.to_string(),
+            reason,
+        },
+        None => WireHelloResponse::Accepted { protocol_version: PROTOCOL_VERSION, build_version: BUILD_VERSION.to_string() },
+    }
+}
+
+/// Client side: Ok when the server accepted our Hello
+fn check_hello_response(response: WireHelloResponse) -> Result<(), String> {
+    match response {
+        WireHelloResponse::Accepted { .. } => Ok(()),
+        WireHelloResponse::Rejected { protocol_version, build_version, reason } => Err(format!(
+            "Server at protocol {} (build {}) rejected protocol {} (build {}): {}",
+            protocol_version, build_version, PROTOCOL_VERSION, BUILD_VERSION, reason)),
+    }
+}
+
+pub fn perform_handshake(stream: &mut TcpStream) -> Result<(), Box<dyn std::error::Error>> {
+    send_request(stream, &WireHello::current())?;
+    check_hello_response(receive_response(stream)?)?;
+    Ok(())
+}
+
+pub async fn perform_handshake_async(stream: &mut TokioTcpStream) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    send_request_async(stream, &WireHello::current()).await?;
+    check_hello_response(receive_response_async(stream).await?)?;
+    Ok(())
+}
+
+/// Opens an RPC connection and performs the Hello handshake
+pub fn connect_with_handshake(addr: &str) -> Result<TcpStream, Box<dyn std::error::Error>> {
+    let mut stream = TcpStream::connect(addr)?;
+    perform_handshake(&mut stream)?;
+    Ok(stream)
+}
+
+pub async fn connect_with_handshake_async(addr: &str) -> Result<TokioTcpStream, Box<dyn std::error::Error + Send + Sync>> {
+    let mut stream = TokioTcpStream::connect(addr).await?;
+    perform_handshake_async(&mut stream).await?;
+    Ok(stream)
+}
+
+/// Reads the first frame of an RPC connection and answers it. Returns the client's Hello
+/// when accepted; on None the caller drops the connection.
+pub async fn accept_hello(framed: &mut Framed<TokioTcpStream, LengthDelimitedCodec>, peer: &str) -> Option<WireHello> {
+    let bytes = match framed.next().await {
+        Some(Ok(bytes)) => bytes,
+        _ => return None,
+    };
+    // A request from a pre-handshake peer either fails to decode or decodes with a bad magic
+    let hello = bincode::deserialize::<WireHello>(&bytes).unwrap_or(WireHello {
+        magic: 0,
+        protocol_version: PROTOCOL_VERSION,
+        build_version: "unknown".to_string(),
+    });
+    let response = answer_hello(&hello);
+    if let WireHelloResponse::Rejected { reason, .. } = &response {
+        crate::log_error!("Rejected RPC connection from {} (protocol {}, build {}): {}",
+                          peer, hello.protocol_version, hello.build_version, reason);
+    }
+    let accepted = matches!(response, WireHelloResponse::Accepted { .. });
+    if let Ok(encoded) = bincode::serialize(&response) {
+        let _ = framed.send(encoded.into()).await;
+    }
+    accepted.then_some(hello)
+}
+
 /// Connect to a backend with exponential backoff retry
 async fn connect_with_backoff(addr: &str) -> Result<TokioTcpStream, std::io::Error> {
     // Configure exponential backoff: start with 100ms, max 2s, max 10s total
@@ -100,13 +183,19 @@ async fn connect_with_backoff(addr: &str) -> Result<TokioTcpStream, std::io::Err
     };
     
     let operation = || async {
-        match TokioTcpStream::connect(addr).await {
-            Ok(stream) => Ok(stream),
+        let mut stream = match TokioTcpStream::connect(addr).await {
+            Ok(stream) => stream,
             Err(e) => {
                 crate::log_error!("Failed to connect to backend at {}: {}", addr, e);
-                Err(BackoffError::transient(e))
+                return Err(BackoffError::transient(e));
             }
-        }
+        };
+        // A rejected handshake will not succeed on retry
+        perform_handshake_async(&mut stream).await.map_err(|e| {
+            crate::log_error!("Handshake with backend at {} failed: {}", addr, e);
+            BackoffError::permanent(std::io::Error::other(e.to_string()))
+        })?;
+        Ok(stream)
     };
     
     backoff::future::retry(backoff, operation).await
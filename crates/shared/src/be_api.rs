@@ -1,190 +1,17 @@
-use serde::{Serialize, Deserialize};
-use std::time::{Duration};
-
-pub const BACKEND_PORT: u16 = 8082;
-pub const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+//! Backend RPC protocol. Each message lives in requests or responses next to its enum, the
+//! types they carry in model, and ports, timeouts and versions in constants; everything is
+//! re-exported here, so callers keep importing from shared::be_api.
+pub mod constants;
+pub mod model;
+pub mod requests;
+pub mod responses;
+
+pub use constants::*;
+pub use model::*;
+pub use requests::*;
+pub use responses::*;
 
 // Re-export colony model types for backward compatibility
-pub use crate::colony_model::{Color, Cell, ColonyLifeRules, Shard, ShardLayer, Traits};
+pub use crate::colony_model::{Biome, Color, Cell, ColonyLifeRules, ColonyLifeRuleRange, COLONY_LIFE_INITIAL_RULES, COLONY_LIFE_RULE_RANGES, GlobalPos, SeedingOptions, SeedingPattern, Shard, ShardLayer, Traits};
 pub use crate::colony_events::ColonyEvent;
 pub use crate::cluster_topology::ClusterTopology;
-
-#[derive(Serialize, Deserialize, Debug)]
-pub enum BackendRequest {
-    Ping,
-    InitColony(InitColonyRequest),
-    GetShardStats(GetShardStatsRequest),
-    InitColonyShard(InitColonyShardRequest),
-    GetColonyInfo(GetColonyInfoRequest),
-    UpdatedShardContents(UpdatedShardContentsRequest),
-    InitShardTopography(InitShardTopographyRequest),
-    GetShardCurrentTick(GetShardCurrentTickRequest),
-    ApplyEvent(ApplyEventRequest),
-    StartTicking(StartTickingRequest),
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub enum BackendResponse {
-    Ping,
-    InitColony(InitColonyResponse),
-    GetShardStats(GetShardStatsResponse),
-    InitColonyShard(InitColonyShardResponse),
-    GetColonyInfo(GetColonyInfoResponse),
-    UpdatedShardContents(UpdatedShardContentsResponse),
-    InitShardTopography(InitShardTopographyResponse),
-    GetShardCurrentTick(GetShardCurrentTickResponse),
-    ApplyEvent(ApplyEventResponse),
-    StartTicking(StartTickingResponse),
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct InitColonyRequest {
-    pub width: i32,
-    pub height: i32,
-    pub colony_life_rules: ColonyLifeRules,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct InitColonyShardRequest {
-    pub shard: Shard,
-    pub colony_life_rules: ColonyLifeRules,
-    pub topology: Option<ClusterTopology>,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub enum InitColonyShardResponse {
-    Ok,
-    ShardAlreadyInitialized,
-    ColonyNotInitialized,
-    InvalidShardDimensions,
-    Error,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub enum InitColonyResponse {
-    Ok,
-    ColonyAlreadyInitialized,
-}
-
-// ===== Shard Stats API =====
-#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
-pub enum StatMetric {
-    Health,
-    Size,
-    CanKill,
-    CanMove,
-    Food,
-    Age,
-    OriginalColor,
-}
-
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct StatBucket {
-    pub value: i32,
-    pub occs: u64,
-}
-
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct StringStatBucket {
-    pub value: String,
-    pub occs: u64,
-}
-
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct ShardStatResult {
-    pub shard: Shard,
-    pub metrics: Vec<(StatMetric, Vec<StatBucket>)>,
-    pub string_metrics: Vec<(StatMetric, Vec<StringStatBucket>)>,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct GetShardStatsRequest {
-    pub shard: Shard,
-    pub metrics: Vec<StatMetric>,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub enum GetShardStatsResponse {
-    Ok { stats: Vec<ShardStatResult>, tick_count: u64 },
-    ColonyNotInitialized,
-    ShardNotAvailable,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct GetColonyInfoRequest;
-
-#[derive(Serialize, Deserialize, Debug)]
-pub enum GetColonyInfoResponse {
-    Ok {
-        width: i32,
-        height: i32,
-        shards: Vec<Shard>,
-        colony_life_rules: Option<ColonyLifeRules>,
-        current_tick: Option<u64>,
-    },
-    ColonyNotInitialized,
-}
-
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct UpdatedShardContentsRequest {
-    pub updated_shard: Shard,
-    pub top: Vec<Cell>,
-    pub bottom: Vec<Cell>,
-    pub left: Vec<Cell>,
-    pub right: Vec<Cell>,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct UpdatedShardContentsResponse {
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct InitShardTopographyRequest {
-    pub shard: Shard,
-    pub topography_data: Vec<u8>,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub enum InitShardTopographyResponse {
-    Ok,
-    ShardNotInitialized,
-    InvalidTopographyData,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct GetShardCurrentTickRequest {
-    pub shard: Shard,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub enum GetShardCurrentTickResponse {
-    Ok {
-        current_tick: u64,
-    },
-    ColonyNotInitialized,
-    ShardNotAvailable,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct ApplyEventRequest {
-    pub event: ColonyEvent,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub enum ApplyEventResponse {
-    Ok,
-    ColonyNotInitialized,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct StartTickingRequest {
-    // Empty for now, can be extended with parameters if needed
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub enum StartTickingResponse {
-    Ok,
-    ColonyNotInitialized,
-    TopologyNotInitialized,
-    Error(String),
-}
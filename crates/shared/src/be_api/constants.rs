@@ -0,0 +1,31 @@
+use std::time::Duration;
+use super::model::ProtocolVersion;
+
+pub const BACKEND_PORT: u16 = 8082;
+pub const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+/// Set on shard image/layer HTTP responses served from the backend's frame cache while it is busy;
+/// the value is how many ticks old the frame is
+pub const STALE_TICKS_HEADER: &str = "X-Colony-Stale";
+/// Set on shard image/layer HTTP responses to the tick the body was rendered at
+pub const COLONY_TICK_HEADER: &str = "X-Colony-Tick";
+
+pub const BUILD_VERSION: &str = match option_env!("BUILD_VERSION") {
+    Some(value) => value,
+    None => "unknown",
+};
+
+/// Wire protocol of the RPC connections. Bump major for any change to a bincode-encoded type,
+/// since bincode cannot skip unknown or missing fields; peers with different majors refuse to talk.
+pub const PROTOCOL_VERSION: ProtocolVersion = ProtocolVersion { major: 17, minor: 0 };
+/// A backend that has not renewed a shard's lease for this long stops ticking the shard
+pub const SHARD_LEASE_DURATION: Duration = Duration::from_secs(30);
+/// How often backends renew their leases with the coordinator; a few renewals fit in one lease
+pub const SHARD_LEASE_RENEW_INTERVAL: Duration = Duration::from_secs(10);
+/// A cell counts as covered by food for StatMetric::FoodCoverage above this much food
+pub const FOOD_COVERAGE_MIN_FOOD: u16 = 50;
+/// Leads every Hello, so a first frame from a peer that predates the handshake is recognized
+pub const WIRE_HELLO_MAGIC: u32 = 0x44434F4C;
+
+/// Extra food value of a water cell: nothing grows there, and creatures standing on a cell
+/// when a topography reload turns it to water die. Generated terrain never goes this low.
+pub const WATER_TOPOGRAPHY_VALUE: u8 = 0;
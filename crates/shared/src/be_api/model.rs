@@ -0,0 +1,167 @@
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+use crate::colony_model::Shard;
+use crate::cluster_topology::HostInfo;
+use super::constants::{BUILD_VERSION, PROTOCOL_VERSION, WIRE_HELLO_MAGIC};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct ProtocolVersion {
+    pub major: u16,
+    pub minor: u16,
+}
+
+impl std::fmt::Display for ProtocolVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+/// First frame a client sends on every RPC connection, before any request
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct WireHello {
+    pub magic: u32,
+    pub protocol_version: ProtocolVersion,
+    pub build_version: String,
+}
+
+impl WireHello {
+    pub fn current() -> Self {
+        Self { magic: WIRE_HELLO_MAGIC, protocol_version: PROTOCOL_VERSION, build_version: BUILD_VERSION.to_string() }
+    }
+}
+
+/// Server's answer to a Hello; after Rejected the server closes the connection
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum WireHelloResponse {
+    Accepted { protocol_version: ProtocolVersion, build_version: String },
+    Rejected { protocol_version: ProtocolVersion, build_version: String, reason: String },
+}
+
+// ===== Shard Stats API =====
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub enum StatMetric {
+    Health,
+    Size,
+    CanKill,
+    CanMove,
+    Food,
+    Age,
+    OriginalColor,
+    /// 1 for creatures inside a sanctuary, so the average is the share living in sanctuaries
+    Sanctuary,
+    /// 1 for occupied cells, 0 for empty ones, so the average is the occupied fraction of the world
+    Occupancy,
+    /// 1 for cells holding more than FOOD_COVERAGE_MIN_FOOD food, occupied or not
+    FoodCoverage,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StatBucket {
+    pub value: i32,
+    pub occs: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StringStatBucket {
+    pub value: String,
+    pub occs: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ShardStatResult {
+    pub shard: Shard,
+    pub metrics: Vec<(StatMetric, Vec<StatBucket>)>,
+    pub string_metrics: Vec<(StatMetric, Vec<StringStatBucket>)>,
+}
+
+/// What an event changed on one hosted shard. Only interior cells are counted, so
+/// shadow margins are not counted twice across neighboring shards.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ShardEventEffect {
+    pub shard: Shard,
+    pub cells_affected: u64,
+    /// Affected cells holding a creature, before the event for removals, after it for creations
+    pub creatures_affected: u64,
+    /// Tick of the shard when the event was applied
+    pub tick_applied: u64,
+}
+
+/// One event as applied to one shard, kept in the shard's bounded event log
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ShardEventRecord {
+    pub tick_applied: u64,
+    pub event_id: Uuid,
+    pub event_type: String,
+    pub cells_affected: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ShardEventLog {
+    pub shard: Shard,
+    /// Newest first
+    pub records: Vec<ShardEventRecord>,
+}
+
+/// Hash of a shard's cells at the end of a tick, see shared::state_hash
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TickStateHash {
+    pub tick: u64,
+    pub hash: u64,
+}
+
+/// Body of the 421 a backend answers for a shard its topology assigns to another backend
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ShardRedirect {
+    pub error: String,
+    pub shard_id: String,
+    /// Backend hosting the shard, as the topology names it
+    pub host: HostInfo,
+}
+
+/// How much of a shard's grid a backend generated while initializing it, served by
+/// GET /api/shard/{id}/init-progress and for the shards still initializing by /api/shards
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ShardInitProgress {
+    pub shard_id: String,
+    /// 0.0 to 1.0
+    pub progress: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ShardStateHashes {
+    pub shard: Shard,
+    /// Oldest first
+    pub hashes: Vec<TickStateHash>,
+}
+
+/// Ownership of a shard. The coordinator issues a new epoch whenever it assigns the shard to
+/// another backend, so the copy with the highest epoch is the one that may tick.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShardLease {
+    pub shard: Shard,
+    pub epoch: u64,
+}
+
+// ===== Sanctuary bitset of InitShardTopographyRequest =====
+/// Bytes of the sanctuary bitset of a shard with the given number of interior cells
+pub fn sanctuary_mask_len(cells: usize) -> usize {
+    cells.div_ceil(8)
+}
+
+/// One bit per cell, least significant bit first
+pub fn pack_sanctuary_mask(cells: impl IntoIterator<Item = bool>) -> Vec<u8> {
+    let mut mask = Vec::new();
+    for (idx, inside) in cells.into_iter().enumerate() {
+        if idx % 8 == 0 {
+            mask.push(0);
+        }
+        if inside {
+            *mask.last_mut().unwrap() |= 1 << (idx % 8);
+        }
+    }
+    mask
+}
+
+pub fn sanctuary_bit(mask: &[u8], idx: usize) -> bool {
+    mask.get(idx / 8).is_some_and(|byte| byte & (1 << (idx % 8)) != 0)
+}
@@ -0,0 +1,258 @@
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+use crate::cluster_topology::{ClusterTopology, HostInfo};
+use crate::colony_events::ColonyEvent;
+use crate::colony_model::{Biome, Cell, ColonyLifeRules, SeedingOptions, Shard};
+use super::model::{ShardLease, StatMetric};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum BackendRequest {
+    Ping,
+    InitColony(InitColonyRequest),
+    GetShardStats(GetShardStatsRequest),
+    InitColonyShard(InitColonyShardRequest),
+    GetColonyInfo(GetColonyInfoRequest),
+    UpdatedShardContents(UpdatedShardContentsRequest),
+    InitShardTopography(InitShardTopographyRequest),
+    GetShardCurrentTick(GetShardCurrentTickRequest),
+    ApplyEvent(ApplyEventRequest),
+    StartTicking(StartTickingRequest),
+    UpdateTopology(UpdateTopologyRequest),
+    SetTickerPaused(SetTickerPausedRequest),
+    StepTicks(StepTicksRequest),
+    SetShardFrozen(SetShardFrozenRequest),
+    UpdateBiomes(UpdateBiomesRequest),
+    RefreshTopology(RefreshTopologyRequest),
+    GetEventLog(GetEventLogRequest),
+    GetStateHashes(GetStateHashesRequest),
+    GetShardDensity(GetShardDensityRequest),
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct InitColonyRequest {
+    pub width: i32,
+    pub height: i32,
+    pub colony_life_rules: ColonyLifeRules,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct InitColonyShardRequest {
+    pub shard: Shard,
+    pub colony_life_rules: ColonyLifeRules,
+    pub topology: Option<ClusterTopology>,
+    pub seeding: SeedingOptions,
+    /// Terrain sent along with the shard, same layout as InitShardTopographyRequest
+    pub topography_data: Option<Vec<u8>>,
+    /// Terrain follows in InitShardTopography; the shard holds still until it arrives.
+    /// Ignored when topography_data is set.
+    pub awaiting_topography: bool,
+    /// Colony of the sending coordinator; lets a replacement coordinator take over the backend
+    pub colony_instance_id: Option<String>,
+    /// Lease epoch the coordinator issued with this assignment, see ShardLease
+    pub lease_epoch: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetShardStatsRequest {
+    pub shard: Shard,
+    pub metrics: Vec<StatMetric>,
+    /// Rectangle in colony coordinates the stats are limited to; None for the whole shard
+    pub region: Option<Shard>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetColonyInfoRequest;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UpdatedShardContentsRequest {
+    pub updated_shard: Shard,
+    pub top: Vec<Cell>,
+    pub bottom: Vec<Cell>,
+    pub left: Vec<Cell>,
+    pub right: Vec<Cell>,
+    /// updated_shard is frozen: the borders are empty and neighbors wall off their facing shadow lane
+    pub frozen: bool,
+    /// Lease epoch of updated_shard on the sender; updates older than the newest epoch seen are rejected
+    pub epoch: u64,
+}
+
+/// topography_data holds the extra food of every interior cell in row-major order,
+/// optionally followed by a sanctuary bitset of sanctuary_mask_len bytes, see pack_sanctuary_mask.
+/// A shard that has already ticked queues it and swaps terrain between two ticks.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct InitShardTopographyRequest {
+    pub shard: Shard,
+    pub topography_data: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetShardCurrentTickRequest {
+    pub shard: Shard,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApplyEventRequest {
+    /// Stable across retries so backends can drop duplicates
+    pub event_id: Uuid,
+    pub event: ColonyEvent,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StartTickingRequest {
+    /// The coordinator's lease of every shard; hosted shards holding an older epoch stay suspended
+    pub leases: Vec<ShardLease>,
+    /// Switches fast-forward mode, which renders nothing and keeps no presentation state; None leaves it as is
+    pub fast_forward: Option<bool>,
+}
+
+/// Sent to every backend when the colony grows, so border exchange includes the new shards
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UpdateTopologyRequest {
+    pub topology: ClusterTopology,
+    pub width: i32,
+    pub height: i32,
+}
+
+/// Sent by a coordinator that took over a running colony. The backend replaces its topology
+/// when the coordinator changed and either the colony instance matches or it hosts no shards.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RefreshTopologyRequest {
+    pub topology: ClusterTopology,
+    pub colony_instance_id: Option<String>,
+}
+
+/// Event log records of every hosted shard, optionally only those of one event
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetEventLogRequest {
+    pub event_id: Option<Uuid>,
+    /// Per shard
+    pub limit: usize,
+}
+
+/// Recent state hashes of every hosted shard
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetStateHashesRequest;
+
+/// Creature density of a hosted shard at cells buckets per side
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetShardDensityRequest {
+    pub shard: Shard,
+    pub cells: u32,
+}
+
+/// Pausing returns once the in-flight tick (if any) has finished
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SetTickerPausedRequest {
+    pub paused: bool,
+}
+
+/// Runs count ticks synchronously while the ticker is paused (or was never started)
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StepTicksRequest {
+    /// None steps every hosted shard
+    pub shard: Option<Shard>,
+    pub count: u32,
+}
+
+/// A frozen shard keeps serving images and stats but skips its ticks and is a wall to its neighbors
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SetShardFrozenRequest {
+    pub shard: Shard,
+    pub frozen: bool,
+}
+
+/// Replaces the biomes of every hosted shard; each shard keeps the part of the list that overlaps it
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UpdateBiomesRequest {
+    pub biomes: Vec<Biome>,
+}
+
+/// One request of every BackendRequest variant, for tests that check each variant is handled.
+/// The match below stops compiling when a variant is added, until it is listed here too.
+/// None of them changes a backend that has no colony, except InitColony which creates it.
+pub fn enumerate_all_backend_request_variants() -> Vec<BackendRequest> {
+    let _exhaustive_check = |request: &BackendRequest| match request {
+        BackendRequest::Ping => (),
+        BackendRequest::InitColony(_) => (),
+        BackendRequest::GetShardStats(_) => (),
+        BackendRequest::InitColonyShard(_) => (),
+        BackendRequest::GetColonyInfo(_) => (),
+        BackendRequest::UpdatedShardContents(_) => (),
+        BackendRequest::InitShardTopography(_) => (),
+        BackendRequest::GetShardCurrentTick(_) => (),
+        BackendRequest::ApplyEvent(_) => (),
+        BackendRequest::StartTicking(_) => (),
+        BackendRequest::UpdateTopology(_) => (),
+        BackendRequest::SetTickerPaused(_) => (),
+        BackendRequest::StepTicks(_) => (),
+        BackendRequest::SetShardFrozen(_) => (),
+        BackendRequest::UpdateBiomes(_) => (),
+        BackendRequest::RefreshTopology(_) => (),
+        BackendRequest::GetEventLog(_) => (),
+        BackendRequest::GetStateHashes(_) => (),
+        BackendRequest::GetShardDensity(_) => (),
+    };
+
+    let shard = Shard { x: 0, y: 0, width: 10, height: 10 };
+    // The lowest allowed value of every rule, see COLONY_LIFE_RULE_RANGES
+    let rules = ColonyLifeRules {
+        health_cost_per_size_unit: 1,
+        eat_capacity_per_size_unit: 1,
+        health_cost_if_can_kill: 0,
+        health_cost_if_can_move: 0,
+        mutation_chance: 1,
+        random_death_chance: 1,
+        kill_success_base_chance: 0,
+        kill_size_advantage_percent: 0,
+        kill_counter_damage: 0,
+        reproduction_food_cost: 0,
+        reproduction_min_food: 0,
+        mutation_size_step: 0,
+        mutation_cost_step: 0,
+        boolean_trait_flip_chance: 0,
+        color_drift_per_generation: 0,
+        color_mutation_chance: 0,
+        starvation_grace_ticks: 0,
+        food_sharing_radius: 0,
+        food_sharing_percent: 0,
+    };
+    let topology = || ClusterTopology {
+        coordinator_host: HostInfo::new("127.0.0.1".to_string(), 8083),
+        backend_hosts: Vec::new(),
+        shard_to_host: HashMap::new(),
+    };
+    let no_cells = Vec::<Cell>::new;
+    vec![
+        BackendRequest::Ping,
+        BackendRequest::InitColony(InitColonyRequest { width: 10, height: 10, colony_life_rules: rules }),
+        BackendRequest::GetShardStats(GetShardStatsRequest { shard, metrics: vec![StatMetric::Health], region: None }),
+        BackendRequest::InitColonyShard(InitColonyShardRequest {
+            shard,
+            colony_life_rules: rules,
+            topology: None,
+            seeding: SeedingOptions::default(),
+            topography_data: None,
+            awaiting_topography: false,
+            colony_instance_id: None,
+            lease_epoch: 1,
+        }),
+        BackendRequest::GetColonyInfo(GetColonyInfoRequest),
+        BackendRequest::UpdatedShardContents(UpdatedShardContentsRequest {
+            updated_shard: shard, top: no_cells(), bottom: no_cells(), left: no_cells(), right: no_cells(), frozen: false, epoch: 1,
+        }),
+        BackendRequest::InitShardTopography(InitShardTopographyRequest { shard, topography_data: Vec::new() }),
+        BackendRequest::GetShardCurrentTick(GetShardCurrentTickRequest { shard }),
+        BackendRequest::ApplyEvent(ApplyEventRequest { event_id: Uuid::nil(), event: ColonyEvent::Extinction() }),
+        BackendRequest::StartTicking(StartTickingRequest { leases: vec![ShardLease { shard, epoch: 1 }], fast_forward: Some(true) }),
+        BackendRequest::UpdateTopology(UpdateTopologyRequest { topology: topology(), width: 10, height: 10 }),
+        BackendRequest::SetTickerPaused(SetTickerPausedRequest { paused: true }),
+        BackendRequest::StepTicks(StepTicksRequest { shard: None, count: 1 }),
+        BackendRequest::SetShardFrozen(SetShardFrozenRequest { shard, frozen: false }),
+        BackendRequest::UpdateBiomes(UpdateBiomesRequest { biomes: Vec::<Biome>::new() }),
+        BackendRequest::RefreshTopology(RefreshTopologyRequest { topology: topology(), colony_instance_id: None }),
+        BackendRequest::GetEventLog(GetEventLogRequest { event_id: None, limit: 1 }),
+        BackendRequest::GetStateHashes(GetStateHashesRequest),
+        BackendRequest::GetShardDensity(GetShardDensityRequest { shard, cells: 1 }),
+    ]
+}
@@ -0,0 +1,243 @@
+use serde::{Serialize, Deserialize};
+use crate::colony_model::{ColonyLifeRules, Shard};
+use crate::density::DensityGrid;
+use super::model::{ShardEventEffect, ShardEventLog, ShardLease, ShardStatResult, ShardStateHashes};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum BackendResponse {
+    Ping,
+    InitColony(InitColonyResponse),
+    GetShardStats(GetShardStatsResponse),
+    InitColonyShard(InitColonyShardResponse),
+    GetColonyInfo(GetColonyInfoResponse),
+    UpdatedShardContents(UpdatedShardContentsResponse),
+    InitShardTopography(InitShardTopographyResponse),
+    GetShardCurrentTick(GetShardCurrentTickResponse),
+    ApplyEvent(ApplyEventResponse),
+    StartTicking(StartTickingResponse),
+    UpdateTopology(UpdateTopologyResponse),
+    SetTickerPaused(SetTickerPausedResponse),
+    StepTicks(StepTicksResponse),
+    SetShardFrozen(SetShardFrozenResponse),
+    UpdateBiomes(UpdateBiomesResponse),
+    RefreshTopology(RefreshTopologyResponse),
+    GetEventLog(GetEventLogResponse),
+    GetStateHashes(GetStateHashesResponse),
+    GetShardDensity(GetShardDensityResponse),
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum InitColonyShardResponse {
+    Ok,
+    ShardAlreadyInitialized,
+    ColonyNotInitialized,
+    InvalidShardDimensions,
+    /// The rules failed ColonyLifeRules::validate
+    InvalidRules(String),
+    /// The seeding failed SeedingOptions::validate
+    InvalidSeeding(String),
+    /// topography_data does not match the shard dimensions
+    InvalidTopography(String),
+    /// The topology comes from another coordinator that may not take over, see RefreshTopologyRequest
+    TopologyConflict(String),
+    /// The topology does not tile the colony dimensions given in InitColony
+    DimensionMismatch(String),
+    Error,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum InitColonyResponse {
+    Ok,
+    /// Already initialized with the same dimensions
+    ColonyAlreadyInitialized,
+    /// Already initialized with other dimensions
+    DimensionMismatch(String),
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum GetShardStatsResponse {
+    Ok { stats: Vec<ShardStatResult>, tick_count: u64 },
+    ColonyNotInitialized,
+    ShardNotAvailable,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum GetColonyInfoResponse {
+    Ok {
+        width: i32,
+        height: i32,
+        shards: Vec<Shard>,
+        /// Boxed to keep the enum small; serialized like the plain rules
+        colony_life_rules: Option<Box<ColonyLifeRules>>,
+        current_tick: Option<u64>,
+        /// Lowest and highest tick across the hosted shards
+        tick_range: Option<(u64, u64)>,
+        /// BUILD_VERSION the backend was built with
+        version: String,
+        /// Lease epoch of every hosted shard
+        leases: Vec<ShardLease>,
+        /// Hosted shards that stopped ticking because their lease expired or was superseded
+        suspended_shards: Vec<Shard>,
+    },
+    ColonyNotInitialized,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum UpdatedShardContentsResponse {
+    Ok,
+    /// updated_shard does not border a hosted shard, or the sender is not the host that owns it
+    Rejected(String),
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum InitShardTopographyResponse {
+    Ok,
+    ShardNotInitialized,
+    InvalidTopographyData,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum GetShardCurrentTickResponse {
+    Ok {
+        current_tick: u64,
+    },
+    ColonyNotInitialized,
+    ShardNotAvailable,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum ApplyEventResponse {
+    /// Effects of the shards the event touched; empty when it missed every hosted shard
+    Ok { effects: Vec<ShardEventEffect> },
+    /// Every hosted shard already applied this event_id
+    AlreadyApplied,
+    ColonyNotInitialized,
+    /// A rules change event carried rules that failed ColonyLifeRules::validate
+    InvalidRules(String),
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum StartTickingResponse {
+    Ok,
+    ColonyNotInitialized,
+    TopologyNotInitialized,
+    /// These shards were promised topography that has not arrived yet
+    AwaitingTopography(Vec<Shard>),
+    /// The other shards tick, these hold an older lease than the coordinator's and stay suspended
+    StaleLeases(Vec<Shard>),
+    Error(String),
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum UpdateTopologyResponse {
+    Ok,
+    ColonyNotInitialized,
+    Error(String),
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum RefreshTopologyResponse {
+    /// replaced is false when the topology already named this coordinator
+    Ok { replaced: bool },
+    TopologyNotInitialized,
+    TopologyConflict(String),
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum GetEventLogResponse {
+    /// Only shards with matching records
+    Ok(Vec<ShardEventLog>),
+    ColonyNotInitialized,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum GetStateHashesResponse {
+    Ok(Vec<ShardStateHashes>),
+    /// The backend runs without DETERMINISM_AUDIT_TICKS
+    AuditDisabled,
+    ColonyNotInitialized,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum GetShardDensityResponse {
+    Ok(DensityGrid),
+    ColonyNotInitialized,
+    ShardNotAvailable,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum SetTickerPausedResponse {
+    Ok { current_tick: u64 },
+    ColonyNotInitialized,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum StepTicksResponse {
+    Ok { current_tick: u64 },
+    TickerNotPaused,
+    ColonyNotInitialized,
+    ShardNotAvailable,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum SetShardFrozenResponse {
+    Ok { current_tick: u64 },
+    ColonyNotInitialized,
+    ShardNotAvailable,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum UpdateBiomesResponse {
+    Ok,
+    ColonyNotInitialized,
+    /// The biomes failed validate_biomes against the shards' rules
+    InvalidBiomes(String),
+}
+
+/// One response of every BackendResponse variant; like enumerate_all_backend_request_variants,
+/// the match below stops compiling when a variant is added until it is listed here too
+pub fn enumerate_all_backend_response_variants() -> Vec<BackendResponse> {
+    let _exhaustive_check = |response: &BackendResponse| match response {
+        BackendResponse::Ping => (),
+        BackendResponse::InitColony(_) => (),
+        BackendResponse::GetShardStats(_) => (),
+        BackendResponse::InitColonyShard(_) => (),
+        BackendResponse::GetColonyInfo(_) => (),
+        BackendResponse::UpdatedShardContents(_) => (),
+        BackendResponse::InitShardTopography(_) => (),
+        BackendResponse::GetShardCurrentTick(_) => (),
+        BackendResponse::ApplyEvent(_) => (),
+        BackendResponse::StartTicking(_) => (),
+        BackendResponse::UpdateTopology(_) => (),
+        BackendResponse::SetTickerPaused(_) => (),
+        BackendResponse::StepTicks(_) => (),
+        BackendResponse::SetShardFrozen(_) => (),
+        BackendResponse::UpdateBiomes(_) => (),
+        BackendResponse::RefreshTopology(_) => (),
+        BackendResponse::GetEventLog(_) => (),
+        BackendResponse::GetStateHashes(_) => (),
+        BackendResponse::GetShardDensity(_) => (),
+    };
+
+    vec![
+        BackendResponse::Ping,
+        BackendResponse::InitColony(InitColonyResponse::Ok),
+        BackendResponse::GetShardStats(GetShardStatsResponse::ColonyNotInitialized),
+        BackendResponse::InitColonyShard(InitColonyShardResponse::Ok),
+        BackendResponse::GetColonyInfo(GetColonyInfoResponse::ColonyNotInitialized),
+        BackendResponse::UpdatedShardContents(UpdatedShardContentsResponse::Ok),
+        BackendResponse::InitShardTopography(InitShardTopographyResponse::Ok),
+        BackendResponse::GetShardCurrentTick(GetShardCurrentTickResponse::ColonyNotInitialized),
+        BackendResponse::ApplyEvent(ApplyEventResponse::ColonyNotInitialized),
+        BackendResponse::StartTicking(StartTickingResponse::Ok),
+        BackendResponse::UpdateTopology(UpdateTopologyResponse::Ok),
+        BackendResponse::SetTickerPaused(SetTickerPausedResponse::ColonyNotInitialized),
+        BackendResponse::StepTicks(StepTicksResponse::ColonyNotInitialized),
+        BackendResponse::SetShardFrozen(SetShardFrozenResponse::ColonyNotInitialized),
+        BackendResponse::UpdateBiomes(UpdateBiomesResponse::Ok),
+        BackendResponse::RefreshTopology(RefreshTopologyResponse::TopologyNotInitialized),
+        BackendResponse::GetEventLog(GetEventLogResponse::ColonyNotInitialized),
+        BackendResponse::GetStateHashes(GetStateHashesResponse::AuditDisabled),
+        BackendResponse::GetShardDensity(GetShardDensityResponse::ShardNotAvailable),
+    ]
+}
@@ -1,4 +1,5 @@
 use crate::cluster_topology::NodeAddress;
+use crate::output_paths::OutputPaths;
 use crate::{log, log_error};
 use std::sync::{Arc, OnceLock, RwLock};
 use std::path::PathBuf;
@@ -23,7 +24,10 @@ pub struct FileClusterRegistry {
 
 impl FileClusterRegistry {
     pub fn new() -> Self {
-        let base_path = PathBuf::from("output/ssm");
+        Self::with_base_path(OutputPaths::get_instance().registry_dir())
+    }
+
+    pub fn with_base_path(base_path: PathBuf) -> Self {
         // Create directory structure if it doesn't exist
         if let Err(e) = fs::create_dir_all(&base_path) {
             log_error!("Failed to create ClusterRegistry directory: {}", e);
@@ -41,6 +45,65 @@ impl FileClusterRegistry {
     fn backend_path(&self, instance_id: &str) -> PathBuf {
         self.base_path.join("backends").join(format!("{}.json", instance_id))
     }
+
+    /// Every parseable backend entry with the file it was read from
+    fn backend_entries(&self) -> Vec<(PathBuf, NodeAddress)> {
+        let backends_dir = self.base_path.join("backends");
+        let mut backends = Vec::new();
+        
+        match fs::read_dir(&backends_dir) {
+            Ok(entries) => {
+                for entry in entries {
+                    if let Ok(entry) = entry {
+                        let path = entry.path();
+                        if path.extension().and_then(|s| s.to_str()) == Some("json") {
+                            match fs::read_to_string(&path) {
+                                Ok(content) => {
+                                    match serde_json::from_str::<NodeAddress>(&content) {
+                                        Ok(address) => {
+                                            backends.push((path, address));
+                                        }
+                                        Err(e) => {
+                                            log_error!("Failed to parse backend file {:?}: {}", path, e);
+                                        }
+                                    }
+                                }
+                                Err(e) => {
+                                    log_error!("Failed to read backend file {:?}: {}", path, e);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    log_error!("Failed to read backends directory: {}", e);
+                }
+            }
+        }
+        backends
+    }
+
+    /// Deletes the backend entries registered with this RPC address, e.g. left behind by a crashed
+    /// backend. Returns the instance ids of the removed entries.
+    pub fn remove_backend_entries(&self, address: &NodeAddress) -> Vec<String> {
+        let mut removed = Vec::new();
+        for (path, entry) in self.backend_entries() {
+            if entry.private_ip != address.private_ip || entry.internal_port != address.internal_port {
+                continue;
+            }
+            let instance_id = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default().to_string();
+            match fs::remove_file(&path) {
+                Ok(()) => {
+                    log!("Removed orphaned backend {} from ClusterRegistry: {} is unreachable", instance_id, address.to_internal_address());
+                    removed.push(instance_id);
+                }
+                Err(e) => log_error!("Failed to remove orphaned backend file {:?}: {}", path, e),
+            }
+        }
+        removed
+    }
 }
 
 impl ClusterRegistry for FileClusterRegistry {
@@ -95,40 +158,7 @@ impl ClusterRegistry for FileClusterRegistry {
     }
 
     async fn discover_backends(&self) -> Vec<NodeAddress> {
-        let backends_dir = self.base_path.join("backends");
-        let mut backends = Vec::new();
-        
-        match fs::read_dir(&backends_dir) {
-            Ok(entries) => {
-                for entry in entries {
-                    if let Ok(entry) = entry {
-                        let path = entry.path();
-                        if path.extension().and_then(|s| s.to_str()) == Some("json") {
-                            match fs::read_to_string(&path) {
-                                Ok(content) => {
-                                    match serde_json::from_str::<NodeAddress>(&content) {
-                                        Ok(address) => {
-                                            backends.push(address);
-                                        }
-                                        Err(e) => {
-                                            log_error!("Failed to parse backend file {:?}: {}", path, e);
-                                        }
-                                    }
-                                }
-                                Err(e) => {
-                                    log_error!("Failed to read backend file {:?}: {}", path, e);
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-            Err(e) => {
-                if e.kind() != std::io::ErrorKind::NotFound {
-                    log_error!("Failed to read backends directory: {}", e);
-                }
-            }
-        }
+        let backends: Vec<NodeAddress> = self.backend_entries().into_iter().map(|(_, address)| address).collect();
         
         log!("File ClusterRegistry: discovered {} backend entries", backends.len());
         backends
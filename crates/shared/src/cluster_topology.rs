@@ -1,7 +1,7 @@
 use serde::{Serialize, Deserialize};
 use crate::colony_model::Shard;
 use std::collections::{HashMap, HashSet};
-use std::sync::{Arc, OnceLock, RwLock};
+use std::sync::{Arc, RwLock};
 use crate::log;
 
 const LOCALHOST_WIDTH_IN_SHARDS: i32 = 5;
@@ -10,6 +10,7 @@ const AWS_WIDTH_IN_SHARDS: i32 = 3;
 const AWS_HEIGHT_IN_SHARDS: i32 = 4;
 const SHARD_WIDTH: i32 = 250;
 const SHARD_HEIGHT: i32 = 250;
+const REDACTED_HOSTNAME: &str = "redacted";
 
 /// Configuration for initializing topology
 #[derive(Debug, Clone)]
@@ -182,6 +183,12 @@ impl DiscoveredTopology {
                     status,
                 ));
                 log!("Coordinator changed: {}", coordinator_address.to_address());
+                if let Some(topology) = ClusterTopology::get_instance() {
+                    if !topology.coordinator_host.matches_address(&coordinator_address) {
+                        ClusterTopology::mark_stale(format!("coordinator moved from {} to {}",
+                            topology.coordinator_host.to_address(), coordinator_address.to_internal_address()));
+                    }
+                }
             } else {
                 self.coordinator_info = None;
                 log!("Coordinator removed from topology");
@@ -245,12 +252,16 @@ impl DiscoveredTopology {
         use tokio_stream::StreamExt;
         use crate::be_api::{BackendRequest, BackendResponse};
         use crate::coordinator_api::{CoordinatorRequest, CoordinatorResponse};
+        use crate::backend_communication::perform_handshake_async;
         
         let addr = address.to_address();
         let connect_timeout = Duration::from_secs(2);
         
         match timeout(connect_timeout, TcpStream::connect(&addr)).await {
-            Ok(Ok(stream)) => {
+            Ok(Ok(mut stream)) => {
+                if !matches!(timeout(connect_timeout, perform_handshake_async(&mut stream)).await, Ok(Ok(()))) {
+                    return NodeStatus::Unknown;
+                }
                 let mut framed = Framed::new(stream, LengthDelimitedCodec::new());
                 
                 match node_type {
@@ -302,22 +313,25 @@ impl DiscoveredTopology {
 use tokio::sync::Mutex;
 
 pub fn start_periodic_discovery(topology: Arc<Mutex<DiscoveredTopology>>) {
-    tokio::spawn(async move {
-        use tokio::time::{interval, Duration};
+    crate::supervisor::spawn_supervised("periodic-discovery", move || {
+        let topology = Arc::clone(&topology);
+        async move {
+            use tokio::time::{interval, Duration};
         
-        let mut timer = interval(Duration::from_secs(10));
-        // Skip the first tick which fires immediately
-        timer.tick().await;
+            let mut timer = interval(Duration::from_secs(10));
+            // Skip the first tick which fires immediately
+            timer.tick().await;
         
-        log!("Starting periodic topology discovery (every 10 seconds)");
+            log!("Starting periodic topology discovery (every 10 seconds)");
         
-        loop {
-            timer.tick().await;
+            loop {
+                timer.tick().await;
             
-            // log!("Running periodic topology refresh...");
-            let mut topology_guard = topology.lock().await;
-            topology_guard.refresh_topology().await;
-            drop(topology_guard);
+                // log!("Running periodic topology refresh...");
+                let mut topology_guard = topology.lock().await;
+                topology_guard.refresh_topology().await;
+                drop(topology_guard);
+            }
         }
     });
 }
@@ -336,6 +350,11 @@ impl HostInfo {
     pub fn to_address(&self) -> String {
         format!("{}:{}", self.hostname, self.port)
     }
+
+    /// True when the discovered node is this host, by private IP and RPC port
+    pub fn matches_address(&self, address: &NodeAddress) -> bool {
+        self.hostname == address.private_ip && self.port == address.internal_port
+    }
 }
 
 impl PartialEq for HostInfo {
@@ -353,6 +372,14 @@ impl std::hash::Hash for HostInfo {
     }
 }
 
+/// Side of the colony that receives a new column or row of shards
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExpandDirection {
+    Right,
+    Bottom,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClusterTopology {
     pub coordinator_host: HostInfo,
@@ -381,24 +408,55 @@ where
     Ok(vec.into_iter().collect())
 }
 
-static INSTANCE: OnceLock<RwLock<Option<Arc<ClusterTopology>>>> = OnceLock::new();
+/// The topology readers see and how many times it was swapped
+struct TopologySlot {
+    topology: Option<Arc<ClusterTopology>>,
+    epoch: u64,
+}
 
-impl ClusterTopology {
-    fn topology_lock() -> &'static RwLock<Option<Arc<ClusterTopology>>> {
-        INSTANCE.get_or_init(|| RwLock::new(None))
+impl TopologySlot {
+    /// Swaps in topology, returning the previous one
+    fn swap(&mut self, topology: Option<Arc<ClusterTopology>>) -> Option<Arc<ClusterTopology>> {
+        self.epoch += 1;
+        std::mem::replace(&mut self.topology, topology)
     }
+}
+
+static INSTANCE: RwLock<TopologySlot> = RwLock::new(TopologySlot { topology: None, epoch: 0 });
+/// Why the topology no longer matches the cluster, until a coordinator sends a fresh one
+static STALE_REASON: std::sync::Mutex<Option<String>> = std::sync::Mutex::new(None);
 
+/// The process-wide topology is swapped whole: initialize, initialize_from_topology, replace and
+/// clear exchange the Arc under a short write lock. A reader holding an Arc from get_instance
+/// keeps a consistent snapshot of the table it got, however many swaps follow, and every
+/// get_instance after a swap returns the new value. topology_epoch counts the swaps, so a
+/// component that derives state from the topology can tell cheaply that it must rebuild it.
+impl ClusterTopology {
     /// Get the topology instance if initialized, None otherwise
     pub fn get_instance() -> Option<Arc<ClusterTopology>> {
-        Self::topology_lock().read()
+        INSTANCE.read()
             .expect("ClusterTopology lock poisoned")
+            .topology
             .clone()
     }
+
+    /// The topology together with the epoch it was swapped in at, read at once so state
+    /// derived from it can be tagged with the right epoch
+    pub fn get_instance_with_epoch() -> (Option<Arc<ClusterTopology>>, u64) {
+        let slot = INSTANCE.read().expect("ClusterTopology lock poisoned");
+        (slot.topology.clone(), slot.epoch)
+    }
+
+    /// Incremented by every swap, clear included; 0 until the first one
+    pub fn topology_epoch() -> u64 {
+        INSTANCE.read().expect("ClusterTopology lock poisoned").epoch
+    }
     
     /// Check if ClusterTopology has been initialized
     pub fn is_initialized() -> bool {
-        Self::topology_lock().read()
+        INSTANCE.read()
             .expect("ClusterTopology lock poisoned")
+            .topology
             .is_some()
     }
     
@@ -412,14 +470,14 @@ impl ClusterTopology {
         };
         let topology = Arc::new(topology);
         
-        let mut guard = Self::topology_lock().write()
+        let mut slot = INSTANCE.write()
             .map_err(|_| TopologyError::LockPoisoned)?;
         
-        if guard.is_some() {
+        if slot.topology.is_some() {
             return Err(TopologyError::AlreadyInitialized);
         }
         
-        *guard = Some(topology.clone());
+        slot.swap(Some(topology.clone()));
         Ok(topology)
     }
     
@@ -428,17 +486,76 @@ impl ClusterTopology {
     pub fn initialize_from_topology(topology: ClusterTopology) -> Result<Arc<ClusterTopology>, TopologyError> {
         let topology = Arc::new(topology);
         
-        let mut guard = Self::topology_lock().write()
+        let mut slot = INSTANCE.write()
             .map_err(|_| TopologyError::LockPoisoned)?;
         
-        if guard.is_some() {
+        if slot.topology.is_some() {
             return Err(TopologyError::AlreadyInitialized);
         }
         
-        *guard = Some(topology.clone());
+        slot.swap(Some(topology.clone()));
+        *STALE_REASON.lock().unwrap() = None;
         Ok(topology)
     }
     
+    /// Swap in a new topology, e.g. after the colony was expanded.
+    /// Unlike the initialize methods this succeeds whether or not a topology is already set
+    pub fn replace(topology: ClusterTopology) -> Result<Arc<ClusterTopology>, TopologyError> {
+        let topology = Arc::new(topology);
+        
+        let mut slot = INSTANCE.write()
+            .map_err(|_| TopologyError::LockPoisoned)?;
+        
+        slot.swap(Some(topology.clone()));
+        *STALE_REASON.lock().unwrap() = None;
+        Ok(topology)
+    }
+
+    /// Drops the topology, e.g. when a colony stops or before a new coordinator takes over;
+    /// get_instance returns None and the initialize methods succeed again. Returns the
+    /// dropped topology, which readers that still hold it keep using.
+    pub fn clear() -> Result<Option<Arc<ClusterTopology>>, TopologyError> {
+        let mut slot = INSTANCE.write()
+            .map_err(|_| TopologyError::LockPoisoned)?;
+        
+        let previous = slot.swap(None);
+        *STALE_REASON.lock().unwrap() = None;
+        Ok(previous)
+    }
+
+    /// Flags the stored topology as outdated, e.g. when discovery finds another coordinator
+    pub fn mark_stale(reason: String) {
+        let mut stale_reason = STALE_REASON.lock().unwrap();
+        if stale_reason.as_ref() != Some(&reason) {
+            log!("Topology marked stale: {}", reason);
+            *stale_reason = Some(reason);
+        }
+    }
+
+    /// Set from mark_stale until the next initialize_from_topology or replace
+    pub fn stale_reason() -> Option<String> {
+        STALE_REASON.lock().unwrap().clone()
+    }
+    
+    /// Copy of the topology safe to hand to observers: hosts are reported by their
+    /// public address from the registry, and hosts not found there are masked
+    pub fn to_observer_view(&self, addresses: &[NodeAddress]) -> ClusterTopology {
+        let public_host = |host: &HostInfo| {
+            let hostname = addresses.iter()
+                .find(|addr| addr.private_ip == host.hostname && addr.internal_port == host.port)
+                .map(|addr| addr.public_ip.clone())
+                .unwrap_or_else(|| REDACTED_HOSTNAME.to_string());
+            HostInfo::new(hostname, host.port)
+        };
+        ClusterTopology {
+            coordinator_host: public_host(&self.coordinator_host),
+            backend_hosts: self.backend_hosts.iter().map(public_host).collect(),
+            shard_to_host: self.shard_to_host.iter()
+                .map(|(shard, host)| (*shard, public_host(host)))
+                .collect(),
+        }
+    }
+
     /// Get default width in shards for first initialization
     pub fn default_width_in_shards() -> i32 {
         LOCALHOST_WIDTH_IN_SHARDS
@@ -620,6 +737,41 @@ impl ClusterTopology {
         self.get_shard_height_from_mapping()
     }
     
+    /// Shards that make up a new column (right) or row (bottom), using the existing shard dimensions
+    pub fn new_shards_for_expansion(&self, direction: ExpandDirection) -> Vec<Shard> {
+        let shard_width = self.shard_width();
+        let shard_height = self.shard_height();
+        match direction {
+            ExpandDirection::Right => {
+                let x = self.width_in_shards() * shard_width;
+                (0..self.height_in_shards())
+                    .map(|row| Shard { x, y: row * shard_height, width: shard_width, height: shard_height })
+                    .collect()
+            }
+            ExpandDirection::Bottom => {
+                let y = self.height_in_shards() * shard_height;
+                (0..self.width_in_shards())
+                    .map(|col| Shard { x: col * shard_width, y, width: shard_width, height: shard_height })
+                    .collect()
+            }
+        }
+    }
+    
+    /// Backend hosting the fewest shards; ties go to the first one in backend_hosts
+    pub fn least_loaded_backend(&self) -> Option<&HostInfo> {
+        self.backend_hosts.iter()
+            .min_by_key(|host| self.shard_to_host.values().filter(|h| h == host).count())
+    }
+    
+    /// Copy of the topology with the given shards assigned to host
+    pub fn with_added_shards(&self, shards: &[Shard], host: &HostInfo) -> ClusterTopology {
+        let mut topology = self.clone();
+        for shard in shards {
+            topology.shard_to_host.insert(*shard, host.clone());
+        }
+        topology
+    }
+    
     /// Get all shards that are adjacent to the given shard
     pub fn get_adjacent_shards(&self, shard: &Shard) -> Vec<Shard> {
         let mut adjacent_shards = Vec::new();
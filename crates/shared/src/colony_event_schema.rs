@@ -0,0 +1,240 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::colony_events::{BiomeChange, ColonyEvent, ColonyRuleChange, CreateCreatureParams, Ellipse, Region};
+use crate::colony_model::{Biome, Color, ColonyLifeRules, ColonyLifeRulesOverride, Traits, COLONY_LIFE_INITIAL_RULES, COLONY_LIFE_RULE_RANGES, MAX_BIOMES};
+
+/// Bumped whenever an event type or parameter is added, removed or changes meaning
+pub const EVENT_SCHEMA_VERSION: u32 = 1;
+
+/// Every ColonyEvent variant, in declaration order
+pub const EVENT_TYPE_NAMES: [&str; 6] = ["CreateCreature", "ChangeExtraFoodPerTick", "Extinction", "NewTopography", "ChangeColonyRules", "ChangeBiomes"];
+
+/// One value inside an event's JSON
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EventParameterSchema {
+    pub name: String,
+    /// JSON pointer from the event's root; * stands for every element of an array
+    pub path: String,
+    /// Rust type of the value, e.g. "u32", "bool", "string" or "array"
+    #[serde(rename = "type")]
+    pub param_type: String,
+    /// A value that may be left out or null is not required
+    pub required: bool,
+    /// Inclusive bounds of a number, or of an array's length
+    pub min: Option<i64>,
+    pub max: Option<i64>,
+    pub description: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EventTypeSchema {
+    pub name: String,
+    pub description: String,
+    /// Only the coordinator ticker creates it; POST /api/colony-events refuses it
+    pub generated_only: bool,
+    /// A valid event of this type, in the JSON the endpoint takes
+    pub example: Value,
+    pub parameters: Vec<EventParameterSchema>,
+}
+
+/// Body of GET /api/event-schema
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EventSchemaResponse {
+    pub schema_version: u32,
+    pub event_types: Vec<EventTypeSchema>,
+}
+
+/// Name of the event's variant. Matches exhaustively, so a new variant does not compile
+/// until it has a name here and an entry in the registry.
+pub fn event_type_name(event: &ColonyEvent) -> &'static str {
+    match event {
+        ColonyEvent::CreateCreature(_, _) => "CreateCreature",
+        ColonyEvent::ChangeExtraFoodPerTick(_) => "ChangeExtraFoodPerTick",
+        ColonyEvent::Extinction() => "Extinction",
+        ColonyEvent::NewTopography() => "NewTopography",
+        ColonyEvent::ChangeColonyRules(_) => "ChangeColonyRules",
+        ColonyEvent::ChangeBiomes(_) => "ChangeBiomes",
+    }
+}
+
+fn param(name: &str, path: String, param_type: &str, range: Option<(i64, i64)>, description: &str) -> EventParameterSchema {
+    EventParameterSchema {
+        name: name.to_string(),
+        path,
+        param_type: param_type.to_string(),
+        required: true,
+        min: range.map(|(min, _)| min),
+        max: range.map(|(_, max)| max),
+        description: description.to_string(),
+    }
+}
+
+fn optional(param: EventParameterSchema) -> EventParameterSchema {
+    EventParameterSchema { required: false, ..param }
+}
+
+/// Whether ColonyLifeRules JSON without the field fails to parse, rather than taking a default
+fn is_rule_required(field: &str) -> bool {
+    let mut rules = serde_json::to_value(COLONY_LIFE_INITIAL_RULES).expect("Failed to serialize rules");
+    rules.as_object_mut().expect("rules are an object").remove(field);
+    serde_json::from_value::<ColonyLifeRules>(rules).is_err()
+}
+
+/// A parameter per rule under prefix, with the ranges of COLONY_LIFE_RULE_RANGES
+fn rule_params(prefix: &str, all_optional: bool) -> Vec<EventParameterSchema> {
+    COLONY_LIFE_RULE_RANGES.iter()
+        .map(|range| {
+            let rule = param(range.field, format!("{}/{}", prefix, range.field), "u32",
+                Some((range.min as i64, range.max as i64)), "See ColonyLifeRules");
+            if all_optional || !is_rule_required(range.field) { optional(rule) } else { rule }
+        })
+        .collect()
+}
+
+fn example_event(name: &str) -> ColonyEvent {
+    match name {
+        "CreateCreature" => ColonyEvent::CreateCreature(
+            Region::Ellipse(Ellipse { x: 100, y: 80, radius_x: 20, radius_y: 15 }),
+            CreateCreatureParams {
+                color: Color { red: 200, green: 40, blue: 90 },
+                traits: Traits { size: 10, can_kill: true, can_move: false },
+                starting_health: 600,
+            },
+        ),
+        "ChangeExtraFoodPerTick" => ColonyEvent::ChangeExtraFoodPerTick(2),
+        "Extinction" => ColonyEvent::Extinction(),
+        "NewTopography" => ColonyEvent::NewTopography(),
+        "ChangeColonyRules" => ColonyEvent::ChangeColonyRules(ColonyRuleChange {
+            new_rules: COLONY_LIFE_INITIAL_RULES,
+            description: "Cheaper upkeep".to_string(),
+        }),
+        "ChangeBiomes" => ColonyEvent::ChangeBiomes(BiomeChange {
+            biomes: vec![Biome {
+                name: "north".to_string(),
+                x: 0,
+                y: 0,
+                width: 500,
+                height: 250,
+                overrides: ColonyLifeRulesOverride { random_death_chance: Some(50), ..Default::default() },
+            }],
+            description: "Harsher north".to_string(),
+        }),
+        _ => panic!("No example for event type {}", name),
+    }
+}
+
+fn type_schema(name: &str) -> EventTypeSchema {
+    let (description, generated_only, parameters) = match name {
+        "CreateCreature" => {
+            let region = "/CreateCreature/0/Ellipse";
+            let params = "/CreateCreature/1";
+            ("Places creatures with the given traits on every cell of an ellipse, in colony coordinates", false, vec![
+                param("x", format!("{}/x", region), "i32", None, "Center column"),
+                param("y", format!("{}/y", region), "i32", None, "Center row"),
+                param("radius_x", format!("{}/radius_x", region), "i32", Some((0, 100_000)), "Horizontal radius; 0 is the center cell only"),
+                param("radius_y", format!("{}/radius_y", region), "i32", Some((0, 100_000)), "Vertical radius"),
+                param("red", format!("{}/color/red", params), "u8", Some((0, 255)), "Creature color"),
+                param("green", format!("{}/color/green", params), "u8", Some((0, 255)), "Creature color"),
+                param("blue", format!("{}/color/blue", params), "u8", Some((0, 255)), "Creature color"),
+                param("size", format!("{}/traits/size", params), "u8", Some((0, 255)), "Creature size"),
+                param("can_kill", format!("{}/traits/can_kill", params), "bool", None, "Attacks neighbors of another color"),
+                param("can_move", format!("{}/traits/can_move", params), "bool", None, "Moves to empty neighbors"),
+                param("starting_health", format!("{}/starting_health", params), "u16", Some((0, u16::MAX as i64)), "0 clears the cells instead"),
+            ])
+        }
+        "ChangeExtraFoodPerTick" => ("Adds the amount to every cell's food growth per tick, saturating at 0 and 255", false, vec![
+            param("amount", "/ChangeExtraFoodPerTick".to_string(), "i8", Some((i8::MIN as i64, i8::MAX as i64)), "Negative amounts take food growth away"),
+        ]),
+        "Extinction" => ("Kills every creature", false, Vec::new()),
+        "NewTopography" => ("Regenerates the food growth of the whole colony", true, Vec::new()),
+        "ChangeColonyRules" => {
+            let mut parameters = rule_params("/ChangeColonyRules/new_rules", false);
+            parameters.push(param("description", "/ChangeColonyRules/description".to_string(), "string", None, "Shown in the event log"));
+            ("Replaces the colony life rules; every rule must stay in its range", false, parameters)
+        }
+        "ChangeBiomes" => {
+            let biome = "/ChangeBiomes/biomes/*";
+            let mut parameters = vec![
+                param("biomes", "/ChangeBiomes/biomes".to_string(), "array", Some((0, MAX_BIOMES as i64)), "The full biomes list after the change; later biomes win where they overlap"),
+                param("name", format!("{}/name", biome), "string", None, "Biome name"),
+                param("x", format!("{}/x", biome), "i32", None, "Left column"),
+                param("y", format!("{}/y", biome), "i32", None, "Top row"),
+                param("width", format!("{}/width", biome), "i32", Some((1, i32::MAX as i64)), "Columns"),
+                param("height", format!("{}/height", biome), "i32", Some((1, i32::MAX as i64)), "Rows"),
+            ];
+            // Each override must also keep the combined rules valid, checked against the colony's current rules
+            parameters.extend(rule_params(&format!("{}/overrides", biome), true));
+            parameters.push(param("description", "/ChangeBiomes/description".to_string(), "string", None, "Shown in the event log"));
+            ("Replaces the biomes, rectangles whose cells live by overridden rules", false, parameters)
+        }
+        _ => panic!("No schema for event type {}", name),
+    };
+    EventTypeSchema {
+        name: name.to_string(),
+        description: description.to_string(),
+        generated_only,
+        example: serde_json::to_value(example_event(name)).expect("Failed to serialize event example"),
+        parameters,
+    }
+}
+
+/// The registry: every event type and its parameters
+pub fn event_schema() -> EventSchemaResponse {
+    EventSchemaResponse {
+        schema_version: EVENT_SCHEMA_VERSION,
+        event_types: EVENT_TYPE_NAMES.iter().map(|name| type_schema(name)).collect(),
+    }
+}
+
+pub fn event_type_schema(event: &ColonyEvent) -> EventTypeSchema {
+    type_schema(event_type_name(event))
+}
+
+/// Values at a path, with * expanded over array elements
+fn values_at<'a>(value: &'a Value, path: &str) -> Vec<&'a Value> {
+    let mut values = vec![value];
+    for segment in path.split('/').skip(1) {
+        values = values.into_iter()
+            .flat_map(|value| match (segment, value) {
+                ("*", Value::Array(items)) => items.iter().collect::<Vec<_>>(),
+                (key, Value::Object(fields)) => fields.get(key).into_iter().collect(),
+                (index, Value::Array(items)) => index.parse::<usize>().ok().and_then(|i| items.get(i)).into_iter().collect(),
+                _ => Vec::new(),
+            })
+            .collect();
+    }
+    values
+}
+
+fn check_value(parameter: &EventParameterSchema, value: &Value) -> Option<String> {
+    let measured = match value {
+        Value::Array(items) => Some(items.len() as i64),
+        Value::Number(number) => number.as_i64(),
+        _ => None,
+    };
+    let (measured, min, max) = (measured?, parameter.min?, parameter.max?);
+    if measured < min || measured > max {
+        Some(format!("{}={} not in [{}, {}]", parameter.path, measured, min, max))
+    } else {
+        None
+    }
+}
+
+/// Checks the event against the ranges of its registry entry; the error lists all violations
+pub fn validate_colony_event(event: &ColonyEvent) -> Result<(), String> {
+    let schema = event_type_schema(event);
+    let json = serde_json::to_value(event).map_err(|e| format!("Failed to serialize event: {}", e))?;
+    let mut violations = Vec::new();
+    for parameter in &schema.parameters {
+        let values = values_at(&json, &parameter.path);
+        if parameter.required && !parameter.path.contains('*') && values.iter().all(|value| value.is_null()) {
+            violations.push(format!("{} is missing", parameter.path));
+        }
+        violations.extend(values.into_iter().filter_map(|value| check_value(parameter, value)));
+    }
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Invalid {} event: {}", schema.name, violations.join(", ")))
+    }
+}
@@ -19,6 +19,9 @@ pub fn log_event(event: &ColonyEvent, current_tick: u64) {
         },
         ColonyEvent::ChangeColonyRules(rule_change) => {
             log!("[{}] Event: ChangeColonyRules - {}", current_tick, rule_change.description);
+        },
+        ColonyEvent::ChangeBiomes(biome_change) => {
+            log!("[{}] Event: ChangeBiomes - {}", current_tick, biome_change.description);
         }
     }
 }
@@ -42,33 +45,68 @@ pub fn log_local_event(event: &ColonyEvent, region: &Region, current_tick: u64)
     log!("[{}] Event: {} {}", current_tick, event_details, region_details);
 }
 
+/// Display name of the event's type, as in ColonyEventDescription.event_type
+pub fn event_type_name(event: &ColonyEvent) -> &'static str {
+    match event {
+        ColonyEvent::CreateCreature(..) => "Create Creature",
+        ColonyEvent::ChangeExtraFoodPerTick(amount) => if *amount >= 0 { "More Food" } else { "Less Food" },
+        ColonyEvent::Extinction() => "Extinction",
+        ColonyEvent::NewTopography() => "New Topography",
+        ColonyEvent::ChangeColonyRules(_) => "Colony Rules Change",
+        ColonyEvent::ChangeBiomes(_) => "Biome Shift",
+    }
+}
+
+/// Lifecycle milestones the coordinator records in the colony events, next to the simulation events
+pub const COLONY_STARTED_EVENT: &str = "Colony Started";
+pub const TICKING_STARTED_EVENT: &str = "Ticking Started";
+pub const COLONY_STOPPED_EVENT: &str = "Colony Stopped";
+pub const BACKEND_JOINED_EVENT: &str = "Backend Joined";
+pub const BACKEND_LEFT_EVENT: &str = "Backend Left";
+pub const FAILOVER_EVENT: &str = "Coordinator Failover";
+pub const EXTINCTION_DETECTED_EVENT: &str = "Extinction Detected";
+pub const TARGET_TICK_REACHED_EVENT: &str = "Target Tick Reached";
+pub const WARMUP_ENDED_EVENT: &str = "Warm-up Ended";
+
+pub const LIFECYCLE_EVENT_TYPES: [&str; 9] = [
+    COLONY_STARTED_EVENT,
+    TICKING_STARTED_EVENT,
+    COLONY_STOPPED_EVENT,
+    BACKEND_JOINED_EVENT,
+    BACKEND_LEFT_EVENT,
+    FAILOVER_EVENT,
+    EXTINCTION_DETECTED_EVENT,
+    TARGET_TICK_REACHED_EVENT,
+    WARMUP_ENDED_EVENT,
+];
+
 pub fn create_colony_event_description(event: &ColonyEvent, current_tick: u64) -> ColonyEventDescription {
-    let (event_type, description) = match event {
-        ColonyEvent::CreateCreature(region, _params) => {
-            ("Create Creature".to_string(), format_local_event_description(event, region))
-        },
+    let description = match event {
+        ColonyEvent::CreateCreature(region, _params) => format_local_event_description(event, region),
         ColonyEvent::ChangeExtraFoodPerTick(amount) => {
             if *amount >= 0 {
-                ("More Food".to_string(), format!("Extra food per tick by +{}", amount))
+                format!("Extra food per tick by +{}", amount)
             } else {
-                ("Less Food".to_string(), format!("Extra food per tick by {}", amount))
+                format!("Extra food per tick by {}", amount)
             }
         },
-        ColonyEvent::Extinction() => {
-            ("Extinction".to_string(), "Colony extinction event occurred".to_string())
-        },
-        ColonyEvent::NewTopography() => {
-            ("New Topography".to_string(), "New topography generated".to_string())
-        },
-        ColonyEvent::ChangeColonyRules(rule_change) => {
-            ("Colony Rules Change".to_string(), rule_change.description.clone())
-        }
+        ColonyEvent::Extinction() => "Colony extinction event occurred".to_string(),
+        ColonyEvent::NewTopography() => "New topography generated".to_string(),
+        ColonyEvent::ChangeColonyRules(rule_change) => rule_change.description.clone(),
+        ColonyEvent::ChangeBiomes(biome_change) => biome_change.description.clone(),
     };
 
     ColonyEventDescription {
         tick: current_tick,
-        event_type,
+        intended_tick: None,
+        event_type: event_type_name(event).to_string(),
         description,
+        delivery: None,
+        no_effect: false,
+        region: match event {
+            ColonyEvent::CreateCreature(region, _) => Some(region.clone()),
+            _ => None,
+        },
     }
 }
 
@@ -1,8 +1,8 @@
 use serde::{Serialize, Deserialize};
-use crate::colony_model::{Color, Traits, ColonyLifeRules};
+use crate::colony_model::{Biome, Color, GlobalPos, Traits, ColonyLifeRules};
 
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Ellipse {
     pub x: i32,
     pub y: i32,
@@ -10,7 +10,14 @@ pub struct Ellipse {
     pub radius_y: i32,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+impl Ellipse {
+    /// The center, which x and y give in colony coordinates
+    pub fn center(&self) -> GlobalPos {
+        GlobalPos::new(self.x, self.y)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub enum Region {
     Ellipse(Ellipse),
 }
@@ -29,7 +36,8 @@ pub enum ColonyEvent {
     ChangeExtraFoodPerTick(i8),
     Extinction(),
     NewTopography(),
-    ChangeColonyRules(ColonyRuleChange)
+    ChangeColonyRules(ColonyRuleChange),
+    ChangeBiomes(BiomeChange),
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -37,3 +45,10 @@ pub struct ColonyRuleChange {
     pub new_rules: ColonyLifeRules,
     pub description: String,
 }
+
+/// The full biomes list after the change
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BiomeChange {
+    pub biomes: Vec<Biome>,
+    pub description: String,
+}
@@ -1,4 +1,5 @@
 use serde::{Serialize, Deserialize};
+use crate::utils::StableHasher;
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
 pub struct Color {
@@ -26,6 +27,8 @@ pub struct Cell {
     pub original_color: Color,
     pub health: u16,
     pub age: u16,
+    /// Consecutive ticks the creature found nothing to eat, see starvation_grace_ticks
+    pub hunger: u8,
 
     pub traits: Traits,
 }
@@ -37,7 +40,7 @@ pub struct Traits {
     pub can_move: bool,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
 pub struct ColonyLifeRules {
     pub health_cost_per_size_unit: u32,
     pub eat_capacity_per_size_unit: u32,
@@ -45,6 +48,327 @@ pub struct ColonyLifeRules {
     pub health_cost_if_can_move: u32,
     pub mutation_chance: u32,
     pub random_death_chance: u32,
+    /// Percent chance that an attack on a neighbor of the same size kills it
+    #[serde(default = "legacy_kill_percent")]
+    pub kill_success_base_chance: u32,
+    /// Percentage points added to the kill chance per size unit the attacker is larger,
+    /// and taken off per unit it is smaller
+    #[serde(default = "legacy_kill_percent")]
+    pub kill_size_advantage_percent: u32,
+    /// Health the attacker loses when its attack fails
+    #[serde(default)]
+    pub kill_counter_damage: u32,
+    /// Health a parent pays to reproduce; half of it goes to the offspring
+    #[serde(default)]
+    pub reproduction_food_cost: u32,
+    /// A creature with less health than this does not reproduce
+    #[serde(default)]
+    pub reproduction_min_food: u32,
+    /// Size units a mutation adds to or takes off the offspring's size
+    #[serde(default = "legacy_mutation_size_step")]
+    pub mutation_size_step: u32,
+    /// Most a mutation may change the offspring's health cost per tick; a mutation
+    /// that would change it more keeps the parent's size and abilities
+    #[serde(default = "legacy_mutation_cost_step")]
+    pub mutation_cost_step: u32,
+    /// Percent chance that a mutation flips can_kill, drawn again for can_move
+    #[serde(default = "legacy_boolean_trait_flip_chance")]
+    pub boolean_trait_flip_chance: u32,
+    /// Most each color channel of an offspring moves from its parent's, on every birth
+    #[serde(default)]
+    pub color_drift_per_generation: u32,
+    /// One in this many offspring get a new random color; 0 never. The lineage founder's
+    /// original_color is kept either way.
+    #[serde(default)]
+    pub color_mutation_chance: u32,
+    /// Ticks a creature may go without eating before it pays its health cost again; 0 pays
+    /// on every tick, as before the rule existed
+    #[serde(default)]
+    pub starvation_grace_ticks: u32,
+    /// Cells in each direction within which a creature with food left over after eating
+    /// shares it with the hungriest creature; 0 never shares
+    #[serde(default)]
+    pub food_sharing_radius: u32,
+    /// Percent of the food left over that is shared
+    #[serde(default)]
+    pub food_sharing_percent: u32,
+}
+
+/// Rules stored before the size-based kill chance get the old all-or-nothing combat back:
+/// a creature kills any neighbor up to its own size and never attacks a larger one
+fn legacy_kill_percent() -> u32 {
+    100
+}
+
+/// Rules stored before the mutation magnitude fields mutate as before: size by one unit,
+/// no limit on the cost change and can_kill/can_move flipped 99 times out of 100
+fn legacy_mutation_size_step() -> u32 {
+    1
+}
+
+fn legacy_mutation_cost_step() -> u32 {
+    MAX_MUTATION_COST_STEP
+}
+
+fn legacy_boolean_trait_flip_chance() -> u32 {
+    99
+}
+
+/// Rules a new colony starts with
+pub const COLONY_LIFE_INITIAL_RULES: ColonyLifeRules = ColonyLifeRules {
+    health_cost_per_size_unit: 2,
+    eat_capacity_per_size_unit: 5,
+    health_cost_if_can_kill: 10,
+    health_cost_if_can_move: 5,
+    mutation_chance: 100,
+    random_death_chance: 100,
+    kill_success_base_chance: 60,
+    kill_size_advantage_percent: 10,
+    kill_counter_damage: 20,
+    reproduction_food_cost: 40,
+    reproduction_min_food: 80,
+    mutation_size_step: 1,
+    mutation_cost_step: 20,
+    boolean_trait_flip_chance: 99,
+    color_drift_per_generation: 1,
+    color_mutation_chance: 10_000,
+    starvation_grace_ticks: 0,
+    food_sharing_radius: 0,
+    food_sharing_percent: 0,
+};
+
+/// Above any health cost per tick the rules allow: 255 size units at 100 each plus both abilities
+pub const MAX_MUTATION_COST_STEP: u32 = 30_000;
+/// Each sharing creature looks at every cell of a square this many cells out
+pub const MAX_FOOD_SHARING_RADIUS: u32 = 5;
+
+/// Allowed values for one ColonyLifeRules field, inclusive on both ends
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColonyLifeRuleRange {
+    pub field: &'static str,
+    pub min: u32,
+    pub max: u32,
+}
+
+impl ColonyLifeRuleRange {
+    pub fn contains(&self, value: u32) -> bool {
+        self.min <= value && value <= self.max
+    }
+}
+
+/// Safe range of every rule. The costs are multiplied by creature size (up to 255) into u16
+/// health values, and the chances are "1 in N" draws that cannot take N = 0.
+pub const COLONY_LIFE_RULE_RANGES: [ColonyLifeRuleRange; 19] = [
+    // 0 makes creatures immortal, so they grow until the shard is full and never die
+    ColonyLifeRuleRange { field: "health_cost_per_size_unit", min: 1, max: 100 },
+    // 0 starves every creature on its first tick
+    ColonyLifeRuleRange { field: "eat_capacity_per_size_unit", min: 1, max: 100 },
+    // Health per tick a creature pays for the ability on top of its size cost; 0 makes it free
+    ColonyLifeRuleRange { field: "health_cost_if_can_kill", min: 0, max: 1000 },
+    ColonyLifeRuleRange { field: "health_cost_if_can_move", min: 0, max: 1000 },
+    // One in N births mutates, one in N creatures dies at random each tick; 1 is every time,
+    // and 1_000_000 is as good as never
+    ColonyLifeRuleRange { field: "mutation_chance", min: 1, max: 1_000_000 },
+    ColonyLifeRuleRange { field: "random_death_chance", min: 1, max: 1_000_000 },
+    // Percentages; 0 for both disables killing altogether
+    ColonyLifeRuleRange { field: "kill_success_base_chance", min: 0, max: 100 },
+    ColonyLifeRuleRange { field: "kill_size_advantage_percent", min: 0, max: 100 },
+    ColonyLifeRuleRange { field: "kill_counter_damage", min: 0, max: 1000 },
+    // Health is a u16; 0 for both keeps the plain half-and-half split
+    ColonyLifeRuleRange { field: "reproduction_food_cost", min: 0, max: 1000 },
+    ColonyLifeRuleRange { field: "reproduction_min_food", min: 0, max: 10_000 },
+    // Sizes are a u8; a step of 0 keeps the size, a cost step of 0 only lets the color mutate
+    ColonyLifeRuleRange { field: "mutation_size_step", min: 0, max: 100 },
+    ColonyLifeRuleRange { field: "mutation_cost_step", min: 0, max: MAX_MUTATION_COST_STEP },
+    ColonyLifeRuleRange { field: "boolean_trait_flip_chance", min: 0, max: 100 },
+    // Color channels are a u8; 0 for both keeps the parent's color outside of mutations
+    ColonyLifeRuleRange { field: "color_drift_per_generation", min: 0, max: 64 },
+    ColonyLifeRuleRange { field: "color_mutation_chance", min: 0, max: 1_000_000 },
+    // The hunger counter is a u8
+    ColonyLifeRuleRange { field: "starvation_grace_ticks", min: 0, max: u8::MAX as u32 },
+    // 0 for either turns food sharing off
+    ColonyLifeRuleRange { field: "food_sharing_radius", min: 0, max: MAX_FOOD_SHARING_RADIUS },
+    ColonyLifeRuleRange { field: "food_sharing_percent", min: 0, max: 100 },
+];
+
+impl ColonyLifeRules {
+    /// Range of a field by name, e.g. "mutation_chance"
+    pub fn allowed_range(field: &str) -> Option<ColonyLifeRuleRange> {
+        COLONY_LIFE_RULE_RANGES.iter().find(|range| range.field == field).copied()
+    }
+
+    /// Field values in the same order as COLONY_LIFE_RULE_RANGES
+    pub fn field_values(&self) -> [(&'static str, u32); 19] {
+        [
+            ("health_cost_per_size_unit", self.health_cost_per_size_unit),
+            ("eat_capacity_per_size_unit", self.eat_capacity_per_size_unit),
+            ("health_cost_if_can_kill", self.health_cost_if_can_kill),
+            ("health_cost_if_can_move", self.health_cost_if_can_move),
+            ("mutation_chance", self.mutation_chance),
+            ("random_death_chance", self.random_death_chance),
+            ("kill_success_base_chance", self.kill_success_base_chance),
+            ("kill_size_advantage_percent", self.kill_size_advantage_percent),
+            ("kill_counter_damage", self.kill_counter_damage),
+            ("reproduction_food_cost", self.reproduction_food_cost),
+            ("reproduction_min_food", self.reproduction_min_food),
+            ("mutation_size_step", self.mutation_size_step),
+            ("mutation_cost_step", self.mutation_cost_step),
+            ("boolean_trait_flip_chance", self.boolean_trait_flip_chance),
+            ("color_drift_per_generation", self.color_drift_per_generation),
+            ("color_mutation_chance", self.color_mutation_chance),
+            ("starvation_grace_ticks", self.starvation_grace_ticks),
+            ("food_sharing_radius", self.food_sharing_radius),
+            ("food_sharing_percent", self.food_sharing_percent),
+        ]
+    }
+
+    /// Percent chance, 0 to 100, that an attacker of attacker_size kills a defender of defender_size
+    pub fn kill_success_percent(&self, attacker_size: u8, defender_size: u8) -> u32 {
+        let size_advantage = attacker_size as i64 - defender_size as i64;
+        let percent = self.kill_success_base_chance as i64 + size_advantage * self.kill_size_advantage_percent as i64;
+        percent.clamp(0, 100) as u32
+    }
+
+    /// Checks every field against its range; the error lists all violations
+    pub fn validate(&self) -> Result<(), String> {
+        let violations: Vec<String> = self.field_values().iter()
+            .zip(COLONY_LIFE_RULE_RANGES.iter())
+            .filter(|((_, value), range)| !range.contains(*value))
+            .map(|((field, value), range)| format!("{}={} not in [{}, {}]", field, value, range.min, range.max))
+            .collect();
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("Invalid colony life rules: {}", violations.join(", ")))
+        }
+    }
+}
+
+/// Rule fields a biome changes; None keeps the shard's base value
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct ColonyLifeRulesOverride {
+    pub health_cost_per_size_unit: Option<u32>,
+    pub eat_capacity_per_size_unit: Option<u32>,
+    pub health_cost_if_can_kill: Option<u32>,
+    pub health_cost_if_can_move: Option<u32>,
+    pub mutation_chance: Option<u32>,
+    pub random_death_chance: Option<u32>,
+    pub kill_success_base_chance: Option<u32>,
+    pub kill_size_advantage_percent: Option<u32>,
+    pub kill_counter_damage: Option<u32>,
+    pub reproduction_food_cost: Option<u32>,
+    pub reproduction_min_food: Option<u32>,
+    pub mutation_size_step: Option<u32>,
+    pub mutation_cost_step: Option<u32>,
+    pub boolean_trait_flip_chance: Option<u32>,
+    pub color_drift_per_generation: Option<u32>,
+    pub color_mutation_chance: Option<u32>,
+    pub starvation_grace_ticks: Option<u32>,
+    pub food_sharing_radius: Option<u32>,
+    pub food_sharing_percent: Option<u32>,
+}
+
+impl ColonyLifeRulesOverride {
+    /// The base rules with the overridden fields replaced
+    pub fn apply(&self, base: &ColonyLifeRules) -> ColonyLifeRules {
+        ColonyLifeRules {
+            health_cost_per_size_unit: self.health_cost_per_size_unit.unwrap_or(base.health_cost_per_size_unit),
+            eat_capacity_per_size_unit: self.eat_capacity_per_size_unit.unwrap_or(base.eat_capacity_per_size_unit),
+            health_cost_if_can_kill: self.health_cost_if_can_kill.unwrap_or(base.health_cost_if_can_kill),
+            health_cost_if_can_move: self.health_cost_if_can_move.unwrap_or(base.health_cost_if_can_move),
+            mutation_chance: self.mutation_chance.unwrap_or(base.mutation_chance),
+            random_death_chance: self.random_death_chance.unwrap_or(base.random_death_chance),
+            kill_success_base_chance: self.kill_success_base_chance.unwrap_or(base.kill_success_base_chance),
+            kill_size_advantage_percent: self.kill_size_advantage_percent.unwrap_or(base.kill_size_advantage_percent),
+            kill_counter_damage: self.kill_counter_damage.unwrap_or(base.kill_counter_damage),
+            reproduction_food_cost: self.reproduction_food_cost.unwrap_or(base.reproduction_food_cost),
+            reproduction_min_food: self.reproduction_min_food.unwrap_or(base.reproduction_min_food),
+            mutation_size_step: self.mutation_size_step.unwrap_or(base.mutation_size_step),
+            mutation_cost_step: self.mutation_cost_step.unwrap_or(base.mutation_cost_step),
+            boolean_trait_flip_chance: self.boolean_trait_flip_chance.unwrap_or(base.boolean_trait_flip_chance),
+            color_drift_per_generation: self.color_drift_per_generation.unwrap_or(base.color_drift_per_generation),
+            color_mutation_chance: self.color_mutation_chance.unwrap_or(base.color_mutation_chance),
+            starvation_grace_ticks: self.starvation_grace_ticks.unwrap_or(base.starvation_grace_ticks),
+            food_sharing_radius: self.food_sharing_radius.unwrap_or(base.food_sharing_radius),
+            food_sharing_percent: self.food_sharing_percent.unwrap_or(base.food_sharing_percent),
+        }
+    }
+
+    /// Overridden fields as (name, value), in the order of COLONY_LIFE_RULE_RANGES
+    pub fn overridden_values(&self) -> Vec<(&'static str, u32)> {
+        [
+            ("health_cost_per_size_unit", self.health_cost_per_size_unit),
+            ("eat_capacity_per_size_unit", self.eat_capacity_per_size_unit),
+            ("health_cost_if_can_kill", self.health_cost_if_can_kill),
+            ("health_cost_if_can_move", self.health_cost_if_can_move),
+            ("mutation_chance", self.mutation_chance),
+            ("random_death_chance", self.random_death_chance),
+            ("kill_success_base_chance", self.kill_success_base_chance),
+            ("kill_size_advantage_percent", self.kill_size_advantage_percent),
+            ("kill_counter_damage", self.kill_counter_damage),
+            ("reproduction_food_cost", self.reproduction_food_cost),
+            ("reproduction_min_food", self.reproduction_min_food),
+            ("mutation_size_step", self.mutation_size_step),
+            ("mutation_cost_step", self.mutation_cost_step),
+            ("boolean_trait_flip_chance", self.boolean_trait_flip_chance),
+            ("color_drift_per_generation", self.color_drift_per_generation),
+            ("color_mutation_chance", self.color_mutation_chance),
+            ("starvation_grace_ticks", self.starvation_grace_ticks),
+            ("food_sharing_radius", self.food_sharing_radius),
+            ("food_sharing_percent", self.food_sharing_percent),
+        ]
+        .into_iter()
+        .filter_map(|(field, value)| value.map(|value| (field, value)))
+        .collect()
+    }
+}
+
+/// A biomes list longer than this is rejected; cells store their biome as a u8
+pub const MAX_BIOMES: usize = 255;
+
+/// Rectangle of the colony, in colony coordinates, whose cells live by overridden rules.
+/// Where biomes overlap the later one in the list wins.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Biome {
+    pub name: String,
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+    pub overrides: ColonyLifeRulesOverride,
+}
+
+impl Biome {
+    pub fn contains(&self, pos: GlobalPos) -> bool {
+        pos.x >= self.x && pos.x < self.x + self.width && pos.y >= self.y && pos.y < self.y + self.height
+    }
+
+    /// The part of the biome inside the shard, None when they do not overlap
+    pub fn clip_to(&self, shard: &Shard) -> Option<Biome> {
+        let left = self.x.max(shard.x);
+        let top = self.y.max(shard.y);
+        let right = (self.x + self.width).min(shard.x + shard.width);
+        let bottom = (self.y + self.height).min(shard.y + shard.height);
+        if left >= right || top >= bottom {
+            return None;
+        }
+        Some(Biome { x: left, y: top, width: right - left, height: bottom - top, ..self.clone() })
+    }
+
+    /// Checks the overridden rules on top of the given base rules
+    pub fn validate(&self, base: &ColonyLifeRules) -> Result<(), String> {
+        if self.width <= 0 || self.height <= 0 {
+            return Err(format!("Biome '{}' has an empty rectangle {}x{}", self.name, self.width, self.height));
+        }
+        self.overrides.apply(base).validate().map_err(|e| format!("Biome '{}': {}", self.name, e))
+    }
+}
+
+pub fn validate_biomes(biomes: &[Biome], base: &ColonyLifeRules) -> Result<(), String> {
+    if biomes.len() > MAX_BIOMES {
+        return Err(format!("{} biomes, at most {} are allowed", biomes.len(), MAX_BIOMES));
+    }
+    biomes.iter().try_for_each(|biome| biome.validate(base))
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -78,9 +402,201 @@ impl Shard {
             .map_err(|e| format!("Invalid height '{}': {}", parts[3], e))?;
         Ok(Shard { x, y, width, height })
     }
+
+    /// Whether the cell is one of the shard's own cells, shadow margin excluded
+    pub fn contains(&self, pos: GlobalPos) -> bool {
+        pos.x >= self.x && pos.x < self.x + self.width && pos.y >= self.y && pos.y < self.y + self.height
+    }
+
+    /// The overlap of two rectangles in colony coordinates, None when they do not overlap
+    pub fn intersection(&self, other: &Shard) -> Option<Shard> {
+        let left = self.x.max(other.x);
+        let top = self.y.max(other.y);
+        let right = (self.x + self.width).min(other.x + other.width);
+        let bottom = (self.y + self.height).min(other.y + other.height);
+        if left >= right || top >= bottom {
+            return None;
+        }
+        Some(Shard { x: left, y: top, width: right - left, height: bottom - top })
+    }
+
+    /// Cells in the shard grid: the shard plus a one-cell shadow margin on every side
+    pub fn grid_len(&self) -> usize {
+        (self.width as usize + 2) * (self.height as usize + 2)
+    }
+
+    /// Grid indices of the shard's own cells in row y, 0..height, leaving out the shadow margin
+    pub fn interior_row(&self, y: i32) -> std::ops::Range<usize> {
+        let start = LocalPos::new(0, y).grid_index(self).expect("row outside the shard grid");
+        start..start + self.width as usize
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+/// Cell position in colony coordinates, the space of Shard.x/y, regions and biomes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct GlobalPos {
+    pub x: i32,
+    pub y: i32,
+}
+
+/// Cell position relative to a shard's top-left cell. The shadow margin is at -1 and at
+/// width/height. Only meaningful together with the shard it was taken from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LocalPos {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl GlobalPos {
+    pub const fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    pub fn to_local(self, shard: &Shard) -> LocalPos {
+        LocalPos { x: self.x - shard.x, y: self.y - shard.y }
+    }
+}
+
+impl LocalPos {
+    pub const fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    pub fn to_global(self, shard: &Shard) -> GlobalPos {
+        GlobalPos { x: self.x + shard.x, y: self.y + shard.y }
+    }
+
+    /// Position of a shard grid index
+    pub fn from_grid_index(idx: usize, shard: &Shard) -> Self {
+        let row_size = shard.width as usize + 2;
+        Self { x: (idx % row_size) as i32 - 1, y: (idx / row_size) as i32 - 1 }
+    }
+
+    /// Index into the shard grid, None outside the shard and its shadow margin
+    pub fn grid_index(self, shard: &Shard) -> Option<usize> {
+        if self.x < -1 || self.x > shard.width || self.y < -1 || self.y > shard.height {
+            return None;
+        }
+        Some((self.y + 1) as usize * (shard.width as usize + 2) + (self.x + 1) as usize)
+    }
+
+    /// Whether the cell is one of the shard's own cells rather than the shadow margin
+    pub fn is_interior(self, shard: &Shard) -> bool {
+        self.x >= 0 && self.x < shard.width && self.y >= 0 && self.y < shard.height
+    }
+}
+
+/// A larger radius overflows the squared distance the seeding compares against
+pub const MAX_CLUSTER_RADIUS: u32 = 10_000;
+/// Every cell of a shard is checked against every center while seeding
+pub const MAX_CLUSTERS: u32 = 1_000;
+
+/// Where the initial creatures of a shard are placed. Positions are relative to the shard.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum SeedingPattern {
+    Uniform,
+    /// Creatures only within cluster_radius cells of num_clusters random centers
+    Clustered { num_clusters: u32, cluster_radius: u32 },
+    /// Creatures only in a band along the shard edges
+    EdgeOnly,
+    /// Creatures only in the middle half of the shard in each direction
+    CenterOnly,
+}
+
+/// How a new shard is seeded with creatures. The default is the historic 10% uniform soup.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct SeedingOptions {
+    /// Fraction of the cells in the pattern's region that start with a creature
+    pub density: f64,
+    pub pattern: SeedingPattern,
+    /// Colony-wide seed; each shard derives its own RNG from it. None seeds randomly.
+    pub seed: Option<u64>,
+}
+
+impl Default for SeedingOptions {
+    fn default() -> Self {
+        Self { density: 0.1, pattern: SeedingPattern::Uniform, seed: None }
+    }
+}
+
+impl SeedingOptions {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0.0..=1.0).contains(&self.density) {
+            return Err(format!("Invalid seeding: density={} not in [0, 1]", self.density));
+        }
+        if let SeedingPattern::Clustered { num_clusters, cluster_radius } = self.pattern {
+            if num_clusters == 0 || cluster_radius == 0 {
+                return Err("Invalid seeding: Clustered needs num_clusters and cluster_radius above 0".to_string());
+            }
+            if num_clusters > MAX_CLUSTERS || cluster_radius > MAX_CLUSTER_RADIUS {
+                return Err(format!("Invalid seeding: Clustered allows at most {} clusters of radius {}", MAX_CLUSTERS, MAX_CLUSTER_RADIUS));
+            }
+        }
+        Ok(())
+    }
+
+    /// Seed of the given shard's RNG, so every shard of a seeded colony reproduces on its own
+    pub fn shard_seed(&self, shard: &Shard) -> Option<u64> {
+        self.seed.map(|seed| {
+            let mut hasher = StableHasher::new();
+            hasher.update(&seed.to_le_bytes());
+            hasher.update(shard.to_id().as_bytes());
+            hasher.finish()
+        })
+    }
+}
+
+/// Direction the bands of ExtraFoodPattern::Stripes run in
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StripeOrientation {
+    Horizontal,
+    Vertical,
+}
+
+/// Layout of the initial extra food over the whole colony, drawn instead of the procedural
+/// rivers. Positions and distances are in colony cells, so a pattern runs on across shards.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum ExtraFoodPattern {
+    /// The same extra food everywhere, halfway between the base and the river peak
+    Uniform,
+    /// Peak extra food at center, given as fractions of the colony width and height,
+    /// fading linearly to the base falloff cells away
+    RadialGradient { center: (f32, f32), falloff: f32 },
+    /// Rich and poor bands alternating smoothly, repeating every period cells
+    Stripes { period: u32, orientation: StripeOrientation },
+    /// count circles of radius cells at seeded spots with richness extra food, the base elsewhere
+    Oases { count: u32, radius: u32, richness: u8 },
+}
+
+impl ExtraFoodPattern {
+    pub fn validate(&self) -> Result<(), String> {
+        match *self {
+            ExtraFoodPattern::Uniform => Ok(()),
+            ExtraFoodPattern::RadialGradient { center: (x, y), falloff } => {
+                if !(0.0..=1.0).contains(&x) || !(0.0..=1.0).contains(&y) {
+                    return Err(format!("Invalid extra_food_pattern: RadialGradient center ({}, {}) not within [0, 1]", x, y));
+                }
+                if !falloff.is_finite() || falloff <= 0.0 {
+                    return Err(format!("Invalid extra_food_pattern: RadialGradient falloff={} must be above 0", falloff));
+                }
+                Ok(())
+            }
+            ExtraFoodPattern::Stripes { period, .. } if period < 2 => {
+                Err(format!("Invalid extra_food_pattern: Stripes period={} must be at least 2", period))
+            }
+            ExtraFoodPattern::Stripes { .. } => Ok(()),
+            ExtraFoodPattern::Oases { count, radius, richness } => {
+                if count == 0 || radius == 0 || richness == 0 {
+                    return Err("Invalid extra_food_pattern: Oases needs count, radius and richness above 0".to_string());
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ShardLayer {
     CreatureSize,
     ExtraFood,
@@ -90,4 +606,6 @@ pub enum ShardLayer {
     Food,
     Health,
     Age,
+    /// 1 inside a sanctuary, where random death does not apply
+    Sanctuary,
 } 
\ No newline at end of file
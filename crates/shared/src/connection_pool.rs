@@ -4,6 +4,7 @@ use std::time::{Instant, Duration};
 use tokio::sync::Mutex;
 use tokio::net::TcpStream;
 use backoff::{ExponentialBackoff, Error as BackoffError};
+use crate::backend_communication::perform_handshake_async;
 use crate::cluster_topology::HostInfo;
 
 #[derive(Clone)]
@@ -87,13 +88,19 @@ async fn connect_with_backoff(addr: &str) -> Result<TcpStream, std::io::Error> {
     };
     
     let operation = || async {
-        match TcpStream::connect(addr).await {
-            Ok(stream) => Ok(stream),
+        let mut stream = match TcpStream::connect(addr).await {
+            Ok(stream) => stream,
             Err(e) => {
                 crate::log_error!("Failed to connect to backend at {}: {}", addr, e);
-                Err(BackoffError::transient(e))
+                return Err(BackoffError::transient(e));
             }
-        }
+        };
+        // A rejected handshake will not succeed on retry
+        perform_handshake_async(&mut stream).await.map_err(|e| {
+            crate::log_error!("Handshake with backend at {} failed: {}", addr, e);
+            BackoffError::permanent(std::io::Error::other(e.to_string()))
+        })?;
+        Ok(stream)
     };
     
     backoff::future::retry(backoff, operation).await
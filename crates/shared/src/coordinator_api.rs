@@ -1,17 +1,30 @@
 use serde::{Serialize, Deserialize};
-use crate::colony_model::Shard;
-use crate::be_api::{StatMetric, StatBucket};
+use crate::colony_model::{Color, ExtraFoodPattern, SeedingOptions, Shard};
+use crate::be_api::{ColonyLifeRules, ShardEventEffect, ShardEventLog, ShardLease, StatMetric, StatBucket};
+use crate::cluster_topology::{ClusterTopology, HostInfo};
+use crate::utils::stable_hash_hex;
+use std::collections::BTreeMap;
+use uuid::Uuid;
 
 pub const COORDINATOR_PORT: u16 = 8082;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub enum CoordinatorRequest {
     GetRoutingTable,
+    /// Sent by every backend each SHARD_LEASE_RENEW_INTERVAL for the shards it holds a lease on
+    RenewShardLeases { backend: HostInfo, leases: Vec<ShardLease> },
+    /// Turns the connection into a push channel: the coordinator answers with TopologyUpdate
+    /// right away and again after every change to shard_to_host, until either side closes it
+    SubscribeTopology { backend: HostInfo },
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub enum CoordinatorResponse {
     GetRoutingTableResponse { entries: Vec<RoutingEntry> },
+    /// Leases the coordinator cannot judge yet, e.g. before it has a topology, are left out
+    RenewShardLeasesResponse { renewals: Vec<LeaseRenewal> },
+    /// The whole topology, pushed on a SubscribeTopology connection
+    TopologyUpdate { topology: ClusterTopology },
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -21,6 +34,373 @@ pub struct ColonyMetricStats {
     pub buckets: Vec<StatBucket>,
 }
 
+/// One group of similar original colors in the dominant-species summary
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SpeciesCluster {
+    /// Derived from the representative color, stable across ticks for time series
+    pub cluster_id: String,
+    /// Most common original color in the cluster
+    pub color: Color,
+    pub count: u64,
+    /// Fraction of all creatures, 0..1
+    pub share: f64,
+}
+
+/// Body of GET and POST /api/colony-stats
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ColonyStatsResponse {
+    /// Highest shard tick seen while merging
+    pub tick: u64,
+    /// Time since the stats were merged; non-zero when served from the coordinator cache
+    pub age_ms: u64,
+    pub cached: bool,
+    pub stats: Vec<ColonyMetricStats>,
+    /// Original colors clustered into the dominant species, largest first
+    #[serde(default)]
+    pub species: Vec<SpeciesCluster>,
+    /// The requested region clipped to the shards it intersects; None for the whole colony
+    #[serde(default)]
+    pub region: Option<Shard>,
+    /// Cells of the region in the shards that answered; None for the whole colony
+    #[serde(default)]
+    pub region_cells: Option<u64>,
+}
+
+/// Body of POST /api/colony-stats; missing metrics mean every default metric, no region the whole colony
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ColonyStatsRequest {
+    #[serde(default)]
+    pub metrics: Option<Vec<StatMetric>>,
+    #[serde(default)]
+    pub region: Option<Shard>,
+}
+
+/// Effective configuration a colony was started with. Recorded once at start and never
+/// updated, so later rule changes from events do not show up here.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ColonyRunConfig {
+    pub colony_instance_id: Option<String>,
+    pub deployment_mode: String,
+    pub colony_width: i32,
+    pub colony_height: i32,
+    pub width_in_shards: i32,
+    pub height_in_shards: i32,
+    pub shard_width: i32,
+    pub shard_height: i32,
+    pub backend_count: usize,
+    /// How shards were assigned to backends, e.g. "round-robin"
+    pub assignment_strategy: String,
+    pub topography_seed: u64,
+    pub topography_source: String,
+    /// stable_hash_hex of the generated global elevation image
+    pub topography_hash: String,
+    pub initial_rules: ColonyLifeRules,
+    /// Creature seeding of the initial shards, with the seed that was actually used
+    #[serde(default)]
+    pub seeding: SeedingOptions,
+    /// Laid over the colony instead of the rivers; left out when the rivers were used,
+    /// so older configurations keep their hash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extra_food_pattern: Option<ExtraFoodPattern>,
+}
+
+impl ColonyRunConfig {
+    /// Hash of everything except the instance id, so two runs with the same settings match
+    pub fn config_hash(&self) -> String {
+        let mut config = self.clone();
+        config.colony_instance_id = None;
+        let json = serde_json::to_vec(&config).expect("Failed to serialize run config");
+        stable_hash_hex(&json)
+    }
+}
+
+/// Body of GET /api/colony-config
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ColonyConfigResponse {
+    pub config_hash: String,
+    pub config: ColonyRunConfig,
+}
+
+/// Written as run_summary.json when a run started with a target_tick reaches it; body of GET /api/run-summary
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RunSummary {
+    pub colony_instance_id: String,
+    pub target_tick: u64,
+    /// Highest shard tick once every backend paused, at or past target_tick
+    pub final_tick: u64,
+    /// None when the final stats snapshot failed
+    pub creatures_count: Option<u64>,
+    /// Average of every stats histogram, keyed like the stats JSON
+    pub metric_averages: BTreeMap<String, f64>,
+    /// Generated events that took effect: delivered to a backend, or not broadcast at all like NewTopography
+    pub events_applied: usize,
+    pub started_at_ms: Option<u64>,
+    pub completed_at_ms: u64,
+    /// From the start of ticking to completed_at_ms
+    pub wall_clock_secs: Option<f64>,
+    pub config_hash: Option<String>,
+}
+
+/// Body of GET /api/ticker-state and of POST /api/pause, /api/resume, /api/step and /api/fast-forward
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TickerStateResponse {
+    pub paused: bool,
+    /// Only set by the POST endpoints, which learn it from the backends
+    #[serde(default)]
+    pub current_tick: Option<u64>,
+    /// Set by POST /api/fast-forward, see StartTickingRequest::fast_forward
+    #[serde(default)]
+    pub fast_forward: bool,
+}
+
+/// Body of GET and PUT /api/capture-config
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct CaptureConfig {
+    pub capture_interval_secs: u64,
+    pub stats_interval_secs: u64,
+    pub captures_enabled: bool,
+    pub stats_enabled: bool,
+}
+
+/// One entry of GET /api/shards
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ShardListEntry {
+    pub shard_id: String,
+    pub shard: Shard,
+    /// Backend RPC address (host:port)
+    pub backend: String,
+    pub frozen: bool,
+    /// Created without terrain that is still to be delivered
+    #[serde(default)]
+    pub awaiting_topography: bool,
+}
+
+/// Body of GET /api/shards
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ShardListResponse {
+    pub shards: Vec<ShardListEntry>,
+}
+
+/// One written frame in GET /api/captures
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CaptureFrameEntry {
+    pub tick: u64,
+    /// When the frame was written (RFC 3339, UTC)
+    pub timestamp: String,
+    /// Where GET serves the PNG, e.g. /api/captures/0000042.png
+    pub url: String,
+    pub bytes: u64,
+    /// Shard ids drawn as placeholders, from the frame's .missing.json sidecar
+    pub missing_shards: Vec<String>,
+}
+
+/// Body of GET /api/captures, frames in tick order
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CaptureListResponse {
+    pub colony_instance_id: String,
+    pub frames: Vec<CaptureFrameEntry>,
+}
+
+/// Body of DELETE /api/captures
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CapturePruneResponse {
+    pub frames_deleted: usize,
+}
+
+/// One backend in GET /api/backends: its topology assignment next to what it reports hosting
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BackendStatus {
+    /// Backend RPC address (host:port)
+    pub backend: String,
+    /// Shard ids the topology assigns to this backend
+    pub assigned_shards: Vec<String>,
+    /// Shard ids the backend reports hosting; None when it did not answer
+    pub hosted_shards: Option<Vec<String>>,
+    /// Lowest and highest tick across the hosted shards
+    pub tick_range: Option<(u64, u64)>,
+    pub healthy: bool,
+    pub version: Option<String>,
+    /// Why the backend could not be queried
+    pub error: Option<String>,
+    /// Assigned but not hosted, e.g. after a failed shard init
+    pub assigned_not_hosting: Vec<String>,
+    /// Hosted but not assigned to this backend, a sign of split brain
+    pub hosting_not_assigned: Vec<String>,
+    /// Hosted shards whose lease epoch differs from the one the coordinator issued
+    #[serde(default)]
+    pub lease_mismatches: Vec<String>,
+    /// Hosted shards that stopped ticking because their lease expired or was superseded
+    #[serde(default)]
+    pub suspended_shards: Vec<String>,
+    /// Circuit breaker of the coordinator's calls to this backend
+    #[serde(default)]
+    pub breaker: BreakerState,
+}
+
+/// While Open, coordinator calls to a backend fail fast; HalfOpen lets one probe through
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakerState {
+    #[default]
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+impl BackendStatus {
+    pub fn has_discrepancies(&self) -> bool {
+        !self.assigned_not_hosting.is_empty() || !self.hosting_not_assigned.is_empty()
+            || !self.lease_mismatches.is_empty() || !self.suspended_shards.is_empty()
+    }
+}
+
+/// Body of GET /api/backends
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BackendsResponse {
+    pub backends: Vec<BackendStatus>,
+}
+
+/// Colony size and shard grid, as in ClusterTopology
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct TopologySummary {
+    pub colony_width: i32,
+    pub colony_height: i32,
+    pub width_in_shards: i32,
+    pub height_in_shards: i32,
+    pub shard_width: i32,
+    pub shard_height: i32,
+}
+
+/// One backend in GET /api/bootstrap
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BootstrapBackend {
+    /// The backend as the returned topology names it
+    pub host: HostInfo,
+    /// None when the backend is missing from the cluster registry
+    pub public_ip: Option<String>,
+    /// Left out for observers
+    pub private_ip: Option<String>,
+    pub rpc_port: u16,
+    pub http_port: Option<u16>,
+    pub healthy: bool,
+    pub error: Option<String>,
+}
+
+/// Body of GET /api/bootstrap: everything a GUI or script needs to attach to the cluster
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BootstrapResponse {
+    pub coordinator_version: String,
+    /// Public "ip:port" of the coordinator HTTP server, from the cluster registry
+    pub coordinator_http: Option<String>,
+    /// e.g. "NotInitialized", "Initializing", "TopographyInitialized" or "Completed"
+    pub colony_status: String,
+    pub colony_instance_id: Option<String>,
+    pub summary: Option<TopologySummary>,
+    /// As served by /topology, with the same redaction for observers; None until initialized
+    pub topology: Option<ClusterTopology>,
+    pub backends: Vec<BootstrapBackend>,
+}
+
+/// One shard of POST /api/verify
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ShardVerification {
+    pub shard_id: String,
+    /// Backends (host:port) that report hosting the shard
+    pub hosted_by: Vec<String>,
+    pub tick: Option<u64>,
+    /// How far the shard is behind the colony max tick
+    pub tick_drift: Option<u64>,
+    /// Whether the image endpoint returned width * height * 3 bytes; None when not spot-checked
+    pub image_ok: Option<bool>,
+    pub passed: bool,
+    pub problems: Vec<String>,
+}
+
+/// Body of POST /api/verify: a consistency audit of every shard in the topology
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ColonyVerificationReport {
+    pub passed: bool,
+    /// What started the pass: manual, colony-start or failover
+    pub trigger: String,
+    pub colony_max_tick: Option<u64>,
+    /// Drift above this fails a shard
+    pub max_tick_drift: u64,
+    pub shards_failed: usize,
+    /// Backends that could not be queried or run different rules
+    pub backend_problems: Vec<String>,
+    pub shards: Vec<ShardVerification>,
+}
+
+impl ColonyVerificationReport {
+    /// One line for logs, the colony event and the GUI
+    pub fn summary(&self) -> String {
+        let mut summary = format!("{}: {}/{} shards ok",
+            if self.passed { "PASS" } else { "FAIL" }, self.shards.len() - self.shards_failed, self.shards.len());
+        if !self.backend_problems.is_empty() {
+            summary.push_str(&format!(", backend problems: {}", self.backend_problems.len()));
+        }
+        summary
+    }
+}
+
+/// One shard of GET /api/determinism-check
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ShardDeterminism {
+    pub shard_id: String,
+    /// Ticks both histories recorded; 0 when the shard is missing from either
+    pub compared_ticks: usize,
+    pub first_divergent_tick: Option<u64>,
+}
+
+/// Body of GET /api/determinism-check: per-shard state hashes of two colony instances compared tick by tick
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DeterminismCheckResponse {
+    pub instance_a: String,
+    pub instance_b: String,
+    /// Earlier ticks were not compared
+    pub from_tick: u64,
+    pub diverged: bool,
+    pub shards: Vec<ShardDeterminism>,
+    /// Backends whose current hashes could not be collected
+    pub backend_problems: Vec<String>,
+}
+
+/// Lowest and highest shard tick across the cluster at one moment
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct TickSample {
+    pub timestamp_ms: u64,
+    pub min_tick: u64,
+    pub max_tick: u64,
+}
+
+/// Body of GET /api/tick-history, oldest sample first
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TickHistoryResponse {
+    pub samples: Vec<TickSample>,
+}
+
+/// Body of GET /api/biomes, in colony coordinates; later biomes win where they overlap
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BiomesResponse {
+    pub biomes: Vec<crate::colony_model::Biome>,
+}
+
+/// Body of POST /api/shard/{id}/freeze
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ShardFrozenResponse {
+    pub shard_id: String,
+    pub frozen: bool,
+    pub current_tick: u64,
+}
+
+/// The coordinator's answer for one lease of RenewShardLeases
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum LeaseRenewal {
+    Renewed(Shard),
+    /// The shard was assigned elsewhere under a newer epoch; the backend must stop ticking it
+    Superseded { shard: Shard, epoch: u64, owner: String },
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RoutingEntry {
     pub shard: Shard,
@@ -28,10 +408,77 @@ pub struct RoutingEntry {
     pub port: u16,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct ColonyEventDescription {
+    /// Colony tick (the highest shard tick) when the event was generated
     pub tick: u64,
+    /// Tick the generator scheduled the event for, when it was scheduled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub intended_tick: Option<u64>,
     pub event_type: String,
     pub description: String,
+    /// Per-backend outcome, only for events broadcast to backends
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delivery: Option<EventDelivery>,
+    /// Set when a broadcast event affected zero cells, so the event generator can be tuned
+    #[serde(default)]
+    pub no_effect: bool,
+    /// Where a region-targeted event struck, in colony coordinates
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<crate::colony_events::Region>,
+}
+
+/// Which backends (host:port) applied an event and which are still failing after retries
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct EventDelivery {
+    pub event_id: Uuid,
+    pub applied_to: Vec<String>,
+    pub failed_on: Vec<String>,
+    /// Shards the event touched, as reported by the backends that applied it.
+    /// A backend that only answered AlreadyApplied on a retry contributes nothing.
+    #[serde(default)]
+    pub effects: Vec<ShardEventEffect>,
+}
+
+/// Body of GET /api/colony-events/{event_id}: the event and what each backend logged for it
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ColonyEventDetailResponse {
+    pub event: ColonyEventDescription,
+    pub backends: Vec<BackendEventLog>,
+}
+
+/// Event log records of one backend that applied the event
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BackendEventLog {
+    /// Backend RPC address (host:port)
+    pub backend: String,
+    pub shards: Vec<ShardEventLog>,
+    /// Why the backend could not be queried
+    pub error: Option<String>,
+}
+
+impl EventDelivery {
+    pub fn cells_affected(&self) -> u64 {
+        self.effects.iter().map(|effect| effect.cells_affected).sum()
+    }
+
+    pub fn creatures_affected(&self) -> u64 {
+        self.effects.iter().map(|effect| effect.creatures_affected).sum()
+    }
+
+    /// Applied somewhere but changed nothing, e.g. a region that missed every shard
+    pub fn had_no_effect(&self) -> bool {
+        !self.applied_to.is_empty() && self.cells_affected() == 0
+    }
+
+    /// Lowest and highest tick at which the affected shards applied the event
+    pub fn applied_tick_range(&self) -> Option<(u64, u64)> {
+        let ticks = self.effects.iter().map(|effect| effect.tick_applied);
+        ticks.clone().min().zip(ticks.max())
+    }
+
+    pub fn effect_summary(&self) -> String {
+        format!("{} cells, {} creatures on {} shards", self.cells_affected(), self.creatures_affected(), self.effects.len())
+    }
 }
 
@@ -0,0 +1,140 @@
+//! Creature density at reduced resolution for dashboards, served by the backend per shard at
+//! /api/shard/{id}/density and stitched by the coordinator into /api/density
+use serde::{Deserialize, Serialize};
+use crate::colony_model::Shard;
+
+pub const DEFAULT_SHARD_DENSITY_CELLS: usize = 32;
+pub const DEFAULT_COLONY_DENSITY_CELLS: usize = 128;
+/// Finer than this a layer is the better fit
+pub const MAX_DENSITY_CELLS: usize = 512;
+
+/// The cells query parameter: buckets per side, default when absent
+pub fn parse_density_cells(value: Option<&str>, default: usize) -> Result<usize, String> {
+    let Some(value) = value else {
+        return Ok(default);
+    };
+    match value.parse::<usize>() {
+        Ok(cells) if (1..=MAX_DENSITY_CELLS).contains(&cells) => Ok(cells),
+        _ => Err(format!("cells must be a number from 1 to {}", MAX_DENSITY_CELLS)),
+    }
+}
+
+/// First cell offset of bucket when extent cells are split into buckets. Buckets of an extent
+/// that does not divide evenly differ by one cell.
+pub fn bucket_start(bucket: usize, buckets: usize, extent: usize) -> usize {
+    (bucket * extent).div_ceil(buckets)
+}
+
+/// The bucket holding cell offset pos
+pub fn bucket_of(pos: usize, buckets: usize, extent: usize) -> usize {
+    pos * buckets / extent
+}
+
+/// Creature counts over an area split into cols x rows buckets
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DensityGrid {
+    /// In colony coordinates
+    pub area: Shard,
+    /// Tick the counts were taken at; for a stitched grid, the oldest of its shards
+    pub tick: u64,
+    pub cols: usize,
+    pub rows: usize,
+    /// Creatures per bucket, row by row
+    pub counts: Vec<u32>,
+}
+
+impl DensityGrid {
+    /// cells buckets per side, fewer along a side shorter than that
+    pub fn empty(area: Shard, tick: u64, cells: usize) -> Self {
+        let cols = cells.clamp(1, area.width.max(1) as usize);
+        let rows = cells.clamp(1, area.height.max(1) as usize);
+        Self { area, tick, cols, rows, counts: vec![0; cols * rows] }
+    }
+
+    /// Counts the creatures at positions, in colony coordinates; positions outside area are skipped
+    pub fn from_positions(area: Shard, tick: u64, cells: usize, positions: impl IntoIterator<Item = (i32, i32)>) -> Self {
+        let mut grid = Self::empty(area, tick, cells);
+        for (x, y) in positions {
+            if x < area.x || y < area.y || x >= area.x + area.width || y >= area.y + area.height {
+                continue;
+            }
+            let col = bucket_of((x - area.x) as usize, grid.cols, area.width as usize);
+            let row = bucket_of((y - area.y) as usize, grid.rows, area.height as usize);
+            grid.counts[row * grid.cols + col] += 1;
+        }
+        grid
+    }
+
+    pub fn total(&self) -> u64 {
+        self.counts.iter().map(|count| *count as u64).sum()
+    }
+
+    /// Colony-wide grid over area from per-shard grids. Each shard bucket's count is spread over
+    /// the buckets it overlaps in proportion to the cells they share, then rounded by largest
+    /// remainder so the total stays exact.
+    pub fn stitch(area: Shard, cells: usize, parts: &[DensityGrid]) -> Self {
+        let tick = parts.iter().map(|part| part.tick).min().unwrap_or(0);
+        let mut grid = Self::empty(area, tick, cells);
+        let mut exact = vec![0.0f64; grid.counts.len()];
+        for part in parts {
+            let (part_width, part_height) = (part.area.width as usize, part.area.height as usize);
+            for part_row in 0..part.rows {
+                let y0 = part.area.y + bucket_start(part_row, part.rows, part_height) as i32;
+                let y1 = part.area.y + bucket_start(part_row + 1, part.rows, part_height) as i32;
+                for part_col in 0..part.cols {
+                    let count = part.counts[part_row * part.cols + part_col];
+                    if count == 0 {
+                        continue;
+                    }
+                    let x0 = part.area.x + bucket_start(part_col, part.cols, part_width) as i32;
+                    let x1 = part.area.x + bucket_start(part_col + 1, part.cols, part_width) as i32;
+                    let cells_in_bucket = ((x1 - x0) * (y1 - y0)) as f64;
+                    for (row, overlap_y) in axis_overlaps(y0, y1, area.y, area.height, grid.rows) {
+                        for (col, overlap_x) in axis_overlaps(x0, x1, area.x, area.width, grid.cols) {
+                            exact[row * grid.cols + col] += count as f64 * (overlap_x * overlap_y) as f64 / cells_in_bucket;
+                        }
+                    }
+                }
+            }
+        }
+
+        for (count, value) in grid.counts.iter_mut().zip(&exact) {
+            *count = value.floor() as u32;
+        }
+        let total = exact.iter().sum::<f64>().round() as u64;
+        let mut by_remainder: Vec<usize> = (0..exact.len()).collect();
+        by_remainder.sort_by(|a, b| (exact[*b] - exact[*b].floor()).total_cmp(&(exact[*a] - exact[*a].floor())));
+        let missing = total.saturating_sub(grid.total()) as usize;
+        for idx in by_remainder.into_iter().take(missing) {
+            grid.counts[idx] += 1;
+        }
+        grid
+    }
+}
+
+/// Buckets of an axis (starting at origin, extent cells split into buckets) that the cell range
+/// [from, to) overlaps, with the cells shared
+fn axis_overlaps(from: i32, to: i32, origin: i32, extent: i32, buckets: usize) -> Vec<(usize, i32)> {
+    let from = from.max(origin);
+    let to = to.min(origin + extent);
+    if from >= to {
+        return Vec::new();
+    }
+    let first = bucket_of((from - origin) as usize, buckets, extent as usize);
+    let last = bucket_of((to - 1 - origin) as usize, buckets, extent as usize);
+    (first..=last)
+        .map(|bucket| {
+            let start = origin + bucket_start(bucket, buckets, extent as usize) as i32;
+            let end = origin + bucket_start(bucket + 1, buckets, extent as usize) as i32;
+            (bucket, to.min(end) - from.max(start))
+        })
+        .collect()
+}
+
+/// GET /api/density: the colony grid, and the shards that did not answer and count as empty
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ColonyDensity {
+    #[serde(flatten)]
+    pub grid: DensityGrid,
+    pub missing_shards: Vec<String>,
+}
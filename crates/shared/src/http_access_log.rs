@@ -0,0 +1,265 @@
+//! Access log of the coordinator and backend HTTP servers. Every request gets a line with its
+//! method, path, query (sensitive values redacted), status, response bytes, latency and peer in
+//! a rotating file, and per-path request counts and p95 latency for /metrics. Both servers wrap
+//! each accepted connection in an AccessLoggedStream, which records the request when dropped.
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Write;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use crate::logging::RotatingLogFile;
+
+/// Query parameters whose names end in one of these have their values replaced by REDACTED,
+/// e.g. idempotency_key, token, access_token, api_key
+const SENSITIVE_PARAM_SUFFIXES: [&str; 4] = ["key", "token", "secret", "password"];
+pub const REDACTED: &str = "REDACTED";
+/// Stands in for path segments holding ids, so /api/shard/0_0_250_250/image and the other
+/// shards' images add up under one path
+pub const PATH_ID_SEGMENT: &str = "{id}";
+/// Latencies kept per path for the p95
+pub const ACCESS_LATENCY_WINDOW: usize = 200;
+/// Paths beyond this many are counted under OTHER_PATH, so scanners cannot grow /metrics
+pub const MAX_TRACKED_PATHS: usize = 100;
+pub const OTHER_PATH: &str = "other";
+/// The access log moves to .1 at this size; ACCESS_LOG_KEEP_FILES older files are kept
+pub const ACCESS_LOG_MAX_BYTES: u64 = 10 * 1024 * 1024;
+pub const ACCESS_LOG_KEEP_FILES: usize = 5;
+/// Enough of a response to read the status from its first line
+const RESPONSE_HEAD_BYTES: usize = 16;
+
+fn is_sensitive_param(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    SENSITIVE_PARAM_SUFFIXES.iter().any(|suffix| name.ends_with(suffix))
+}
+
+/// The query with the values of sensitive parameters replaced by REDACTED
+pub fn redact_query(query: &str) -> String {
+    query.split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((name, _)) if is_sensitive_param(name) => format!("{}={}", name, REDACTED),
+            _ => pair.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// The path with every segment holding a digit replaced by PATH_ID_SEGMENT
+pub fn normalize_path(path: &str) -> String {
+    path.split('/')
+        .map(|segment| if segment.bytes().any(|b| b.is_ascii_digit()) { PATH_ID_SEGMENT } else { segment })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// The status code of a response starting with head, e.g. 200 for "HTTP/1.1 200 OK"
+pub fn response_status(head: &[u8]) -> Option<u16> {
+    let head = std::str::from_utf8(head.get(..12)?).ok()?;
+    if !head.starts_with("HTTP/") {
+        return None;
+    }
+    head.split(' ').nth(1)?.parse().ok()
+}
+
+/// One served request
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessRecord {
+    pub method: String,
+    pub path: String,
+    /// Already redacted
+    pub query: Option<String>,
+    /// None when nothing or no HTTP response was written
+    pub status: Option<u16>,
+    pub bytes: u64,
+    pub latency: Duration,
+    pub peer: SocketAddr,
+}
+
+impl AccessRecord {
+    /// Reads method, path and query from the request line of request
+    pub fn new(request: &str, status: Option<u16>, bytes: u64, latency: Duration, peer: SocketAddr) -> Self {
+        let mut request_line = request.lines().next().unwrap_or("").split_whitespace();
+        let method = request_line.next().unwrap_or("-").to_string();
+        let target = request_line.next().unwrap_or("-");
+        let (path, query) = match target.split_once('?') {
+            Some((path, query)) => (path.to_string(), Some(redact_query(query))),
+            None => (target.to_string(), None),
+        };
+        Self { method, path, query, status, bytes, latency, peer }
+    }
+
+    /// `peer "METHOD /path?query" status bytes latency`, with - for a missing status
+    pub fn log_line(&self) -> String {
+        let query = self.query.as_ref().map(|query| format!("?{}", query)).unwrap_or_default();
+        let status = self.status.map(|status| status.to_string()).unwrap_or_else(|| "-".to_string());
+        format!("{} \"{} {}{}\" {} {} {:.1}ms",
+            self.peer, self.method, self.path, query, status, self.bytes, self.latency.as_secs_f64() * 1000.0)
+    }
+}
+
+#[derive(Debug, Default)]
+struct PathStats {
+    count: u64,
+    latencies_ms: VecDeque<f64>,
+}
+
+/// Request counts and recent latencies per normalized path
+#[derive(Debug, Default)]
+pub struct AccessStats {
+    paths: BTreeMap<String, PathStats>,
+}
+
+impl AccessStats {
+    pub fn record(&mut self, path: &str, latency: Duration) {
+        let mut key = normalize_path(path);
+        if !self.paths.contains_key(&key) && self.paths.len() >= MAX_TRACKED_PATHS {
+            key = OTHER_PATH.to_string();
+        }
+        let stats = self.paths.entry(key).or_default();
+        stats.count += 1;
+        if stats.latencies_ms.len() == ACCESS_LATENCY_WINDOW {
+            stats.latencies_ms.pop_front();
+        }
+        stats.latencies_ms.push_back(latency.as_secs_f64() * 1000.0);
+    }
+
+    /// Requests to a normalized path
+    pub fn count(&self, path: &str) -> u64 {
+        self.paths.get(path).map(|stats| stats.count).unwrap_or(0)
+    }
+
+    /// Nearest-rank p95 of the last ACCESS_LATENCY_WINDOW latencies of a normalized path
+    pub fn p95_ms(&self, path: &str) -> Option<f64> {
+        let stats = self.paths.get(path)?;
+        let mut latencies: Vec<f64> = stats.latencies_ms.iter().copied().collect();
+        latencies.sort_by(f64::total_cmp);
+        let rank = (latencies.len() * 95).div_ceil(100);
+        latencies.get(rank.checked_sub(1)?).copied()
+    }
+
+    /// Prometheus text of the per-path counters, with metric names starting with prefix
+    pub fn render_prometheus(&self, prefix: &str) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# TYPE {}_http_requests_total counter", prefix);
+        for (path, stats) in &self.paths {
+            let _ = writeln!(out, "{}_http_requests_total{{path=\"{}\"}} {}", prefix, path, stats.count);
+        }
+        let _ = writeln!(out, "# TYPE {}_http_request_latency_p95_ms gauge", prefix);
+        for path in self.paths.keys() {
+            if let Some(p95) = self.p95_ms(path) {
+                let _ = writeln!(out, "{}_http_request_latency_p95_ms{{path=\"{}\"}} {:.3}", prefix, path, p95);
+            }
+        }
+        out
+    }
+}
+
+/// Where an HTTP server's requests go: its access log file, when it has one, and its stats
+#[derive(Debug)]
+pub struct AccessLog {
+    file: Option<Mutex<RotatingLogFile>>,
+    stats: Mutex<AccessStats>,
+}
+
+impl AccessLog {
+    pub fn new(file: Option<RotatingLogFile>) -> Self {
+        Self { file: file.map(Mutex::new), stats: Mutex::new(AccessStats::default()) }
+    }
+
+    /// Writes to path, rotated at ACCESS_LOG_MAX_BYTES
+    pub fn rotating(path: impl Into<std::path::PathBuf>) -> Self {
+        Self::new(Some(RotatingLogFile::new(path, ACCESS_LOG_MAX_BYTES, ACCESS_LOG_KEEP_FILES)))
+    }
+
+    pub fn record(&self, record: &AccessRecord) {
+        if let Some(file) = &self.file {
+            if let Err(e) = file.lock().unwrap().append_line(&record.log_line()) {
+                crate::log_error!("Failed to write the HTTP access log: {}", e);
+            }
+        }
+        self.stats.lock().unwrap().record(&record.path, record.latency);
+    }
+
+    pub fn count(&self, path: &str) -> u64 {
+        self.stats.lock().unwrap().count(path)
+    }
+
+    pub fn p95_ms(&self, path: &str) -> Option<f64> {
+        self.stats.lock().unwrap().p95_ms(path)
+    }
+
+    pub fn render_prometheus(&self, prefix: &str) -> String {
+        self.stats.lock().unwrap().render_prometheus(prefix)
+    }
+}
+
+/// A connection that counts the response bytes written to it and records its request in an
+/// AccessLog when dropped, so every way a handler returns is logged. Latency runs from
+/// accepting the connection to the drop; for the live feed that is the whole session.
+pub struct AccessLoggedStream<S> {
+    inner: S,
+    log: Arc<AccessLog>,
+    peer: SocketAddr,
+    accepted: Instant,
+    request: Option<String>,
+    head: Vec<u8>,
+    written: u64,
+}
+
+pub type AccessLoggedTcpStream = AccessLoggedStream<tokio::net::TcpStream>;
+
+impl<S> AccessLoggedStream<S> {
+    pub fn new(inner: S, log: Arc<AccessLog>, peer: SocketAddr) -> Self {
+        Self { inner, log, peer, accepted: Instant::now(), request: None, head: Vec::new(), written: 0 }
+    }
+
+    /// The request served on this connection; a connection without one is not logged
+    pub fn set_request(&mut self, request: &str) {
+        self.request = Some(request.lines().next().unwrap_or("").to_string());
+    }
+
+    pub fn status(&self) -> Option<u16> {
+        response_status(&self.head)
+    }
+
+    pub fn bytes_written(&self) -> u64 {
+        self.written
+    }
+}
+
+impl<S> Drop for AccessLoggedStream<S> {
+    fn drop(&mut self) {
+        if let Some(request) = self.request.take() {
+            self.log.record(&AccessRecord::new(&request, self.status(), self.written, self.accepted.elapsed(), self.peer));
+        }
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for AccessLoggedStream<S> {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for AccessLoggedStream<S> {
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<std::io::Result<usize>> {
+        let this = &mut *self;
+        let result = Pin::new(&mut this.inner).poll_write(cx, buf);
+        if let Poll::Ready(Ok(n)) = result {
+            let head_missing = RESPONSE_HEAD_BYTES.saturating_sub(this.head.len()).min(n);
+            this.head.extend_from_slice(&buf[..head_missing]);
+            this.written += n as u64;
+        }
+        result
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
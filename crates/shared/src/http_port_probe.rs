@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+use crate::cluster_topology::HostInfo;
+use crate::log;
+
+/// HTTP port backends listen on unless started with another one
+pub const DEFAULT_BACKEND_HTTP_PORT: u16 = 8085;
+/// A backend is probed at most once per this interval
+pub const HTTP_PORT_PROBE_INTERVAL: Duration = Duration::from_secs(180);
+/// Connect and read timeout of one /health probe
+pub const HTTP_PORT_PROBE_TIMEOUT: Duration = Duration::from_millis(300);
+
+/// Ports a backend's HTTP server is likely on, in probe order: the one after its RPC port,
+/// as the local scripts start them, then the default
+pub fn candidate_http_ports(rpc_port: u16) -> Vec<u16> {
+    let mut ports: Vec<u16> = rpc_port.checked_add(1).into_iter().collect();
+    if !ports.contains(&DEFAULT_BACKEND_HTTP_PORT) && rpc_port != DEFAULT_BACKEND_HTTP_PORT {
+        ports.push(DEFAULT_BACKEND_HTTP_PORT);
+    }
+    ports
+}
+
+/// Whether an HTTP server answers GET /health on the port. 401 and 403 count: with API auth
+/// on, the backend is there even though the probe carries no token.
+pub fn answers_health(hostname: &str, port: u16) -> bool {
+    let Some(addr) = (hostname, port).to_socket_addrs().ok().and_then(|mut addrs| addrs.next()) else {
+        return false;
+    };
+    let Ok(mut stream) = TcpStream::connect_timeout(&addr, HTTP_PORT_PROBE_TIMEOUT) else {
+        return false;
+    };
+    let _ = stream.set_read_timeout(Some(HTTP_PORT_PROBE_TIMEOUT));
+    let request = format!("GET /health HTTP/1.1\r\nHost: {}:{}\r\nConnection: close\r\n\r\n", hostname, port);
+    if stream.write_all(request.as_bytes()).is_err() {
+        return false;
+    }
+    let mut status_line = [0u8; 12];
+    if stream.read_exact(&mut status_line).is_err() {
+        return false;
+    }
+    let status_line = String::from_utf8_lossy(&status_line);
+    status_line.starts_with("HTTP/1.") && matches!(&status_line[9..12], "200" | "401" | "403")
+}
+
+/// Fallback for backends the cluster registry has no HTTP port for, e.g. right after a manual
+/// restart: tries the candidate ports and remembers the one that answered. Each backend is
+/// probed at most once per interval. Disabled in AWS mode, where the ports are always
+/// registered and guessing them across security groups only adds timeouts.
+#[derive(Debug)]
+pub struct HttpPortProbe {
+    enabled: bool,
+    interval: Duration,
+    discovered: HashMap<HostInfo, u16>,
+    last_probe: HashMap<HostInfo, Instant>,
+}
+
+impl HttpPortProbe {
+    pub fn new(enabled: bool, interval: Duration) -> Self {
+        Self { enabled, interval, discovered: HashMap::new(), last_probe: HashMap::new() }
+    }
+
+    pub fn for_deployment_mode(deployment_mode: &str) -> Self {
+        Self::new(deployment_mode != "aws", HTTP_PORT_PROBE_INTERVAL)
+    }
+
+    /// The HTTP port found for the backend earlier, without probing
+    pub fn discovered(&self, host: &HostInfo) -> Option<u16> {
+        self.discovered.get(host).copied()
+    }
+
+    /// The cached port, or the first candidate port answering is_healthy(hostname, port).
+    /// None without probing when disabled or when the backend was probed within the interval.
+    pub fn probe<F>(&mut self, host: &HostInfo, now: Instant, is_healthy: F) -> Option<u16>
+    where
+        F: Fn(&str, u16) -> bool,
+    {
+        if let Some(port) = self.discovered(host) {
+            return Some(port);
+        }
+        if !self.enabled {
+            return None;
+        }
+        if self.last_probe.get(host).is_some_and(|last| now.saturating_duration_since(*last) < self.interval) {
+            return None;
+        }
+        self.last_probe.insert(host.clone(), now);
+        let port = candidate_http_ports(host.port).into_iter().find(|port| is_healthy(&host.hostname, *port))?;
+        log!("Backend {} has no registered HTTP port, found it on {} by probing /health", host.to_address(), port);
+        self.discovered.insert(host.clone(), port);
+        Some(port)
+    }
+
+    /// Drops a cached port that stopped answering, so the next probe looks again
+    pub fn forget(&mut self, host: &HostInfo) {
+        self.discovered.remove(host);
+    }
+}
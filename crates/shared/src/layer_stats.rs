@@ -0,0 +1,128 @@
+use serde::{Serialize, Deserialize};
+
+pub const LAYER_HISTOGRAM_BUCKETS: usize = 32;
+/// Query value (`?version=2`) selecting the binary layer format with a stats header
+pub const LAYER_FORMAT_VERSION_WITH_STATS: u32 = 2;
+
+// min (i32) + max (i32) + mean (f64) + histogram (u32 per bucket)
+const STATS_HEADER_LEN: usize = 4 + 4 + 8 + LAYER_HISTOGRAM_BUCKETS * 4;
+
+/// Summary of one shard layer, computed by the backend while building the response
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LayerStats {
+    pub min: i32,
+    pub max: i32,
+    pub mean: f64,
+    /// Counts over LAYER_HISTOGRAM_BUCKETS equal-width buckets spanning [min, max]
+    pub histogram: Vec<u32>,
+}
+
+/// Layer values together with their stats, as decoded from the version 2 format
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ShardLayerData {
+    pub stats: LayerStats,
+    pub values: Vec<i32>,
+}
+
+impl LayerStats {
+    pub fn compute(values: &[i32]) -> Self {
+        let mut histogram = vec![0u32; LAYER_HISTOGRAM_BUCKETS];
+        if values.is_empty() {
+            return Self { min: 0, max: 0, mean: 0.0, histogram };
+        }
+
+        let mut min = i32::MAX;
+        let mut max = i32::MIN;
+        let mut sum: i64 = 0;
+        for &value in values {
+            min = min.min(value);
+            max = max.max(value);
+            sum += value as i64;
+        }
+
+        let mut stats = Self { min, max, mean: sum as f64 / values.len() as f64, histogram: Vec::new() };
+        for &value in values {
+            histogram[stats.bucket_for(value)] += 1;
+        }
+        stats.histogram = histogram;
+        stats
+    }
+
+    /// Histogram bucket of a value within [min, max]
+    pub fn bucket_for(&self, value: i32) -> usize {
+        let range = self.max as i64 - self.min as i64 + 1;
+        let offset = (value as i64 - self.min as i64).clamp(0, range - 1);
+        (offset * LAYER_HISTOGRAM_BUCKETS as i64 / range) as usize
+    }
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.min.to_le_bytes());
+        out.extend_from_slice(&self.max.to_le_bytes());
+        out.extend_from_slice(&self.mean.to_le_bytes());
+        for &count in &self.histogram {
+            out.extend_from_slice(&count.to_le_bytes());
+        }
+    }
+
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != STATS_HEADER_LEN {
+            return None;
+        }
+        let histogram = bytes[16..]
+            .chunks_exact(4)
+            .map(|chunk| u32::from_le_bytes(chunk.try_into().expect("chunk of 4")))
+            .collect();
+        Some(Self {
+            min: i32::from_le_bytes(bytes[0..4].try_into().ok()?),
+            max: i32::from_le_bytes(bytes[4..8].try_into().ok()?),
+            mean: f64::from_le_bytes(bytes[8..16].try_into().ok()?),
+            histogram,
+        })
+    }
+}
+
+fn encode_values(values: &[i32], out: &mut Vec<u8>) {
+    for &value in values {
+        out.extend_from_slice(&value.to_le_bytes());
+    }
+}
+
+fn decode_values(bytes: &[u8], count: usize) -> Option<Vec<i32>> {
+    if bytes.len() != count * 4 {
+        return None;
+    }
+    Some(bytes.chunks_exact(4).map(|chunk| i32::from_le_bytes(chunk.try_into().expect("chunk of 4"))).collect())
+}
+
+fn decode_count(bytes: &[u8]) -> Option<usize> {
+    Some(u32::from_le_bytes(bytes.get(0..4)?.try_into().ok()?) as usize)
+}
+
+/// Original layer format: count (u32 LE) + i32 values (LE)
+pub fn encode_layer(values: &[i32]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(4 + values.len() * 4);
+    out.extend_from_slice(&(values.len() as u32).to_le_bytes());
+    encode_values(values, &mut out);
+    out
+}
+
+pub fn decode_layer(bytes: &[u8]) -> Option<Vec<i32>> {
+    let count = decode_count(bytes)?;
+    decode_values(&bytes[4..], count)
+}
+
+/// Version 2 layer format: count (u32 LE) + stats header + i32 values (LE)
+pub fn encode_layer_with_stats(values: &[i32], stats: &LayerStats) -> Vec<u8> {
+    let mut out = Vec::with_capacity(4 + STATS_HEADER_LEN + values.len() * 4);
+    out.extend_from_slice(&(values.len() as u32).to_le_bytes());
+    stats.encode(&mut out);
+    encode_values(values, &mut out);
+    out
+}
+
+pub fn decode_layer_with_stats(bytes: &[u8]) -> Option<ShardLayerData> {
+    let count = decode_count(bytes)?;
+    let stats = LayerStats::decode(bytes.get(4..4 + STATS_HEADER_LEN)?)?;
+    let values = decode_values(&bytes[4 + STATS_HEADER_LEN..], count)?;
+    Some(ShardLayerData { stats, values })
+}
@@ -1,13 +1,25 @@
+pub mod api_auth;
 pub mod be_api;
 pub mod backend_communication;
 pub mod colony_events;
 pub mod colony_event_shared;
+pub mod colony_event_schema;
 pub mod colony_model;
+pub mod shard_render;
+pub mod layer_stats;
+pub mod live_feed;
 pub mod coordinator_api;
 pub mod cluster_topology;
 pub mod cluster_registry;
+pub mod density;
 pub mod connection_pool;
+pub mod http_access_log;
+pub mod http_port_probe;
 pub mod logging;
+pub mod output_paths;
+pub mod rpc_decode_failures;
 pub mod ssm;
+pub mod state_hash;
 pub mod storage;
+pub mod supervisor;
 pub mod utils; 
\ No newline at end of file
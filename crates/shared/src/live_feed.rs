@@ -0,0 +1,206 @@
+use crate::api_auth::bearer_header_value;
+use crate::coordinator_api::ColonyEventDescription;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+/// Coordinator HTTP path that upgrades to the live feed WebSocket
+pub const FEED_PATH: &str = "/ws";
+
+/// Frames above this size are refused by FeedClient; the coordinator's downsampled frames stay well below it
+const MAX_FRAME_PAYLOAD: u64 = 16 * 1024 * 1024;
+
+const OPCODE_CONTINUATION: u8 = 0x0;
+const OPCODE_TEXT: u8 = 0x1;
+const OPCODE_CLOSE: u8 = 0x8;
+const OPCODE_PING: u8 = 0x9;
+const OPCODE_PONG: u8 = 0xA;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedTopic {
+    Ticks,
+    Events,
+    Frames,
+    BackendHealth,
+}
+
+/// Sent by the client; each subscription replaces the previous one
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FeedRequest {
+    Subscribe { topics: Vec<FeedTopic> },
+}
+
+/// Pushed by the coordinator as JSON text messages
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FeedMessage {
+    /// Acknowledges a Subscribe
+    Subscribed { topics: Vec<FeedTopic> },
+    /// One tick_monitor sweep over the backends
+    Tick { timestamp_ms: u64, min_tick: u64, max_tick: u64 },
+    /// A colony event, as soon as the coordinator records it
+    Event { event: ColonyEventDescription },
+    /// The stitched colony image, downsampled to at most width x height
+    Frame { tick: u64, width: u32, height: u32, missing_shards: Vec<String>, png_base64: String },
+    /// A backend whose health changed since the previous sweep; observers get its public
+    /// hostname instead of the RPC address
+    BackendHealth { index: usize, backend: String, healthy: bool, error: Option<String> },
+    /// The client read too slowly and this many older messages were dropped
+    Lagged { dropped: u64 },
+}
+
+impl FeedMessage {
+    /// Topic a client must subscribe to for this message; None for messages every client gets
+    pub fn topic(&self) -> Option<FeedTopic> {
+        match self {
+            FeedMessage::Tick { .. } => Some(FeedTopic::Ticks),
+            FeedMessage::Event { .. } => Some(FeedTopic::Events),
+            FeedMessage::Frame { .. } => Some(FeedTopic::Frames),
+            FeedMessage::BackendHealth { .. } => Some(FeedTopic::BackendHealth),
+            FeedMessage::Subscribed { .. } | FeedMessage::Lagged { .. } => None,
+        }
+    }
+}
+
+/// Standard base64 with padding, used for the handshake key and frame images
+pub fn base64_encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let triple = (chunk[0] as u32) << 16
+            | (*chunk.get(1).unwrap_or(&0) as u32) << 8
+            | *chunk.get(2).unwrap_or(&0) as u32;
+        for (i, shift) in [18, 12, 6, 0].into_iter().enumerate() {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(triple >> shift & 0x3F) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Minimal blocking client for the coordinator's live feed: text messages, pings and close
+/// only, which is all the coordinator sends
+pub struct FeedClient {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl FeedClient {
+    /// Connects to address (host:port), upgrades and subscribes to topics. read_timeout bounds
+    /// every next_message call.
+    pub fn connect(address: &str, token: Option<&str>, topics: &[FeedTopic], read_timeout: Duration) -> Result<Self, String> {
+        let writer = TcpStream::connect(address).map_err(|e| format!("Failed to connect to {}: {}", address, e))?;
+        writer.set_read_timeout(Some(read_timeout)).map_err(|e| e.to_string())?;
+        let reader = BufReader::new(writer.try_clone().map_err(|e| e.to_string())?);
+        let mut client = FeedClient { reader, writer };
+
+        let mut key = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut key);
+        let auth = token.map(|token| format!("Authorization: {}\r\n", bearer_header_value(token))).unwrap_or_default();
+        let handshake = format!(
+            "GET {} HTTP/1.1\r\nHost: {}\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Key: {}\r\nSec-WebSocket-Version: 13\r\n{}\r\n",
+            FEED_PATH, address, base64_encode(&key), auth
+        );
+        client.writer.write_all(handshake.as_bytes()).map_err(|e| format!("Failed to send handshake: {}", e))?;
+
+        let mut status_line = String::new();
+        client.reader.read_line(&mut status_line).map_err(|e| format!("Failed to read handshake: {}", e))?;
+        if status_line.split_whitespace().nth(1) != Some("101") {
+            return Err(format!("Upgrade refused: {}", status_line.trim()));
+        }
+        loop {
+            let mut header = String::new();
+            client.reader.read_line(&mut header).map_err(|e| format!("Failed to read handshake: {}", e))?;
+            if header.trim().is_empty() {
+                break;
+            }
+        }
+
+        client.subscribe(topics)?;
+        Ok(client)
+    }
+
+    pub fn subscribe(&mut self, topics: &[FeedTopic]) -> Result<(), String> {
+        let request = serde_json::to_string(&FeedRequest::Subscribe { topics: topics.to_vec() })
+            .map_err(|e| e.to_string())?;
+        self.write_frame(OPCODE_TEXT, request.as_bytes())
+    }
+
+    /// Blocks for the next message, answering pings on the way
+    pub fn next_message(&mut self) -> Result<FeedMessage, String> {
+        let mut message = Vec::new();
+        loop {
+            let (fin, opcode, payload) = self.read_frame()?;
+            match opcode {
+                OPCODE_TEXT | OPCODE_CONTINUATION => {
+                    message.extend_from_slice(&payload);
+                    if fin {
+                        return serde_json::from_slice(&message).map_err(|e| format!("Invalid feed message: {}", e));
+                    }
+                }
+                OPCODE_PING => self.write_frame(OPCODE_PONG, &payload)?,
+                OPCODE_CLOSE => return Err("Feed closed by the coordinator".to_string()),
+                _ => {}
+            }
+        }
+    }
+
+    /// Client frames are always masked
+    fn write_frame(&mut self, opcode: u8, payload: &[u8]) -> Result<(), String> {
+        let mut frame = vec![0x80 | opcode];
+        match payload.len() {
+            len if len < 126 => frame.push(0x80 | len as u8),
+            len if len <= u16::MAX as usize => {
+                frame.push(0x80 | 126);
+                frame.extend_from_slice(&(len as u16).to_be_bytes());
+            }
+            len => {
+                frame.push(0x80 | 127);
+                frame.extend_from_slice(&(len as u64).to_be_bytes());
+            }
+        }
+        let mut mask = [0u8; 4];
+        rand::thread_rng().fill_bytes(&mut mask);
+        frame.extend_from_slice(&mask);
+        frame.extend(payload.iter().enumerate().map(|(i, byte)| byte ^ mask[i % 4]));
+        self.writer.write_all(&frame).map_err(|e| format!("Failed to send frame: {}", e))
+    }
+
+    /// (fin, opcode, payload); server frames are never masked
+    fn read_frame(&mut self) -> Result<(bool, u8, Vec<u8>), String> {
+        let mut header = [0u8; 2];
+        self.read_exact(&mut header)?;
+        let fin = header[0] & 0x80 != 0;
+        let opcode = header[0] & 0x0F;
+        let len = match header[1] & 0x7F {
+            126 => {
+                let mut len = [0u8; 2];
+                self.read_exact(&mut len)?;
+                u16::from_be_bytes(len) as u64
+            }
+            127 => {
+                let mut len = [0u8; 8];
+                self.read_exact(&mut len)?;
+                u64::from_be_bytes(len)
+            }
+            len => len as u64,
+        };
+        if len > MAX_FRAME_PAYLOAD {
+            return Err(format!("Frame of {} bytes exceeds the limit of {}", len, MAX_FRAME_PAYLOAD));
+        }
+        let mut payload = vec![0u8; len as usize];
+        self.read_exact(&mut payload)?;
+        Ok((fin, opcode, payload))
+    }
+
+    fn read_exact(&mut self, buffer: &mut [u8]) -> Result<(), String> {
+        self.reader.read_exact(buffer).map_err(|e| format!("Failed to read feed: {}", e))
+    }
+}
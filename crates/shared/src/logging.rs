@@ -1,5 +1,6 @@
 use std::fs::{OpenOptions, create_dir_all};
 use std::io::Write;
+use std::path::PathBuf;
 use std::sync::Mutex;
 use lazy_static::lazy_static;
 
@@ -9,12 +10,13 @@ lazy_static! {
     static ref LOG_FILE_MUTEX: Mutex<()> = Mutex::new(());
 }
 
-pub fn init_logging(log_file: &str) {
-    if let Some(parent) = std::path::Path::new(log_file).parent() {
+pub fn init_logging(log_file: impl AsRef<std::path::Path>) {
+    let log_file = log_file.as_ref();
+    if let Some(parent) = log_file.parent() {
         let _ = create_dir_all(parent);
     }
     let mut path = LOG_FILE_PATH.lock().unwrap();
-    *path = Some(log_file.to_string());
+    *path = Some(log_file.to_string_lossy().into_owned());
 }
 
 fn get_log_file() -> Option<String> {
@@ -32,6 +34,60 @@ pub fn log_to_file(msg: &str) {
     }
 }
 
+/// A log file that moves to path.1 once it reaches max_bytes, older files moving one number up
+/// and the oldest beyond keep dropped. Callers serialize appends.
+#[derive(Debug, Clone)]
+pub struct RotatingLogFile {
+    path: PathBuf,
+    max_bytes: u64,
+    keep: usize,
+}
+
+impl RotatingLogFile {
+    pub fn new(path: impl Into<PathBuf>, max_bytes: u64, keep: usize) -> Self {
+        let path = path.into();
+        if let Some(parent) = path.parent() {
+            let _ = create_dir_all(parent);
+        }
+        Self { path, max_bytes, keep }
+    }
+
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+
+    /// The n-th older file, path.1 being the latest
+    pub fn rotated_path(&self, n: usize) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{}", n));
+        PathBuf::from(name)
+    }
+
+    /// Appends a timestamped line, rotating first when it would take the file past max_bytes
+    pub fn append_line(&self, msg: &str) -> std::io::Result<()> {
+        let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S");
+        let line = format!("[{}] {}\n", timestamp, msg);
+        let size = std::fs::metadata(&self.path).map(|metadata| metadata.len()).unwrap_or(0);
+        if size > 0 && size + line.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        OpenOptions::new().create(true).append(true).open(&self.path)?.write_all(line.as_bytes())
+    }
+
+    fn rotate(&self) -> std::io::Result<()> {
+        if self.keep == 0 {
+            return std::fs::remove_file(&self.path);
+        }
+        for n in (1..self.keep).rev() {
+            let older = self.rotated_path(n);
+            if older.exists() {
+                std::fs::rename(&older, self.rotated_path(n + 1))?;
+            }
+        }
+        std::fs::rename(&self.path, self.rotated_path(1))
+    }
+}
+
 #[macro_export]
 macro_rules! log {
     ($($arg:tt)*) => {{
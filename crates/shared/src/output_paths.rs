@@ -0,0 +1,100 @@
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Root of everything the processes write; relative paths resolve against the working directory
+pub const OUTPUT_DIR_ENV: &str = "COLONY_OUTPUT_DIR";
+const DEFAULT_OUTPUT_DIR: &str = "output";
+/// Local stand-in for the S3 bucket, laid out like it
+const BUCKET_DIR: &str = "s3/distributed-colony";
+
+static INSTANCE: OnceLock<OutputPaths> = OnceLock::new();
+
+/// Where logs, the file registry, snapshots, captures, stats and exports are written.
+/// Accessors only build paths; writers create the directories they need on first use.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputPaths {
+    root: PathBuf,
+}
+
+impl OutputPaths {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Root from COLONY_OUTPUT_DIR, ./output when unset or empty
+    pub fn from_env() -> Self {
+        let root = std::env::var(OUTPUT_DIR_ENV)
+            .ok()
+            .filter(|dir| !dir.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_OUTPUT_DIR.to_string());
+        Self::new(root)
+    }
+
+    /// Process-wide paths, read from the environment on first use
+    pub fn get_instance() -> &'static OutputPaths {
+        INSTANCE.get_or_init(OutputPaths::from_env)
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn logs_dir(&self) -> PathBuf {
+        self.root.join("logs")
+    }
+
+    /// logs/{component}.log, e.g. component "be_8082"
+    pub fn log_file(&self, component: &str) -> PathBuf {
+        self.logs_dir().join(format!("{}.log", component))
+    }
+
+    /// FileClusterRegistry files, the localhost stand-in for SSM
+    pub fn registry_dir(&self) -> PathBuf {
+        self.root.join("ssm")
+    }
+
+    pub fn storage_dir(&self) -> PathBuf {
+        self.root.join("storage")
+    }
+
+    pub fn shard_snapshots_dir(&self, instance_id: &str) -> PathBuf {
+        self.storage_dir().join(instance_id).join("shards")
+    }
+
+    /// Parent of every instance directory
+    pub fn bucket_dir(&self) -> PathBuf {
+        self.root.join(BUCKET_DIR)
+    }
+
+    pub fn instance_dir(&self, instance_id: &str) -> PathBuf {
+        self.bucket_dir().join(instance_id)
+    }
+
+    pub fn captures_dir(&self, instance_id: &str) -> PathBuf {
+        self.instance_dir(instance_id).join("images_shots")
+    }
+
+    pub fn stats_dir(&self, instance_id: &str) -> PathBuf {
+        self.instance_dir(instance_id).join("stats_shots")
+    }
+
+    pub fn events_dir(&self, instance_id: &str) -> PathBuf {
+        self.instance_dir(instance_id).join("events")
+    }
+
+    pub fn gui_exports_dir(&self) -> PathBuf {
+        self.root.join("gui_exports")
+    }
+
+    /// Creates the root and writes a probe file, so an unwritable root fails at startup
+    /// rather than on the first log line or capture
+    pub fn ensure_writable(&self) -> Result<(), String> {
+        std::fs::create_dir_all(&self.root)
+            .map_err(|e| format!("Cannot create output directory {}: {}", self.root.display(), e))?;
+        let probe = self.root.join(format!(".write_probe_{}", std::process::id()));
+        std::fs::write(&probe, b"")
+            .map_err(|e| format!("Output directory {} is not writable: {}", self.root.display(), e))?;
+        let _ = std::fs::remove_file(&probe);
+        Ok(())
+    }
+}
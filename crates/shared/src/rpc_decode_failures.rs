@@ -0,0 +1,140 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Consecutive undecodable frames after which an RPC listener closes the connection
+pub const DECODE_FAILURE_LIMIT_ENV: &str = "RPC_DECODE_FAILURE_LIMIT";
+/// A client of the same build never sends two bad frames in a row
+pub const DEFAULT_DECODE_FAILURE_LIMIT: u32 = 2;
+/// A peer's failures are logged at most this often, with the running count
+pub const DECODE_FAILURE_LOG_INTERVAL: Duration = Duration::from_secs(60);
+
+pub fn decode_failure_limit() -> u32 {
+    std::env::var(DECODE_FAILURE_LIMIT_ENV)
+        .ok()
+        .and_then(|v| v.parse::<u32>().ok())
+        .filter(|limit| *limit > 0)
+        .unwrap_or(DEFAULT_DECODE_FAILURE_LIMIT)
+}
+
+/// Undecodable requests from one peer address, served by /api/rpc-stats
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct PeerDecodeFailures {
+    pub count: u64,
+    pub first_seen_ms: u64,
+    pub last_seen_ms: u64,
+    /// Connections closed for reaching the consecutive failure limit
+    pub disconnects: u64,
+    #[serde(skip)]
+    last_logged: Option<Instant>,
+}
+
+/// Deserialization failures of an RPC listener, keyed by peer IP so reconnects add up
+#[derive(Debug)]
+pub struct DecodeFailureTracker {
+    peers: BTreeMap<String, PeerDecodeFailures>,
+    log_interval: Duration,
+}
+
+impl DecodeFailureTracker {
+    pub const fn new(log_interval: Duration) -> Self {
+        Self { peers: BTreeMap::new(), log_interval }
+    }
+
+    /// Counts a failure; returns the running count when it is due to be logged, the first
+    /// time for the peer and then once per log_interval
+    pub fn record_failure(&mut self, peer: &str, now: Instant) -> Option<u64> {
+        let now_ms = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0);
+        let failures = self.peers.entry(peer.to_string()).or_insert(PeerDecodeFailures {
+            count: 0,
+            first_seen_ms: now_ms,
+            last_seen_ms: now_ms,
+            disconnects: 0,
+            last_logged: None,
+        });
+        failures.count += 1;
+        failures.last_seen_ms = now_ms;
+        let due = failures.last_logged.is_none_or(|logged| now.duration_since(logged) >= self.log_interval);
+        if !due {
+            return None;
+        }
+        failures.last_logged = Some(now);
+        Some(failures.count)
+    }
+
+    pub fn record_disconnect(&mut self, peer: &str) {
+        if let Some(failures) = self.peers.get_mut(peer) {
+            failures.disconnects += 1;
+        }
+    }
+
+    pub fn peers(&self) -> BTreeMap<String, PeerDecodeFailures> {
+        self.peers.clone()
+    }
+
+    /// Prometheus text of the per-peer counters, with metric names starting with prefix
+    pub fn render_prometheus(&self, prefix: &str) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# TYPE {}_rpc_deserialize_failures_total counter", prefix);
+        for (peer, failures) in &self.peers {
+            let _ = writeln!(out, "{}_rpc_deserialize_failures_total{{peer=\"{}\"}} {}", prefix, peer, failures.count);
+        }
+        let _ = writeln!(out, "# TYPE {}_rpc_deserialize_disconnects_total counter", prefix);
+        for (peer, failures) in &self.peers {
+            let _ = writeln!(out, "{}_rpc_deserialize_disconnects_total{{peer=\"{}\"}} {}", prefix, peer, failures.disconnects);
+        }
+        out
+    }
+}
+
+/// What an RPC listener does with one frame
+#[derive(Debug)]
+pub enum DecodedFrame<T> {
+    Request(T),
+    Rejected {
+        error: String,
+        /// Running count for the peer when this failure should be logged
+        log_count: Option<u64>,
+        /// The connection reached the consecutive failure limit and should be closed
+        disconnect: bool,
+    },
+}
+
+/// Decodes the frames of one connection, counting failures in tracker
+pub struct FrameDecoder {
+    peer: String,
+    consecutive_failures: u32,
+    limit: u32,
+}
+
+impl FrameDecoder {
+    pub fn new(peer: String, limit: u32) -> Self {
+        Self { peer, consecutive_failures: 0, limit }
+    }
+
+    pub fn decode<T: DeserializeOwned>(&mut self, bytes: &[u8], tracker: &Mutex<DecodeFailureTracker>) -> DecodedFrame<T> {
+        self.decode_at(bytes, tracker, Instant::now())
+    }
+
+    pub fn decode_at<T: DeserializeOwned>(&mut self, bytes: &[u8], tracker: &Mutex<DecodeFailureTracker>, now: Instant) -> DecodedFrame<T> {
+        match bincode::deserialize::<T>(bytes) {
+            Ok(request) => {
+                self.consecutive_failures = 0;
+                DecodedFrame::Request(request)
+            }
+            Err(e) => {
+                self.consecutive_failures += 1;
+                let disconnect = self.consecutive_failures >= self.limit;
+                let mut tracker = tracker.lock().unwrap();
+                let log_count = tracker.record_failure(&self.peer, now);
+                if disconnect {
+                    tracker.record_disconnect(&self.peer);
+                }
+                DecodedFrame::Rejected { error: e.to_string(), log_count, disconnect }
+            }
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use crate::be_api::Color;
+use crate::colony_model::Cell;
+
+/// Query parameter of the shard image endpoint that picks the ImageBackground
+pub const BACKGROUND_QUERY_PARAM: &str = "background";
+
+/// Food value at the far end of the palette for a food-tinted background
+pub const FOOD_TINT_MAX: u16 = 255;
+
+/// How far an empty cell moves from white towards its food color
+const FOOD_TINT_STRENGTH: f32 = 0.5;
+
+const WHITE: (u8, u8, u8) = (255, 255, 255);
+
+/// Terrain palette shared by the GUI layer tabs and food-tinted shard images, from low to high
+pub const TERRAIN_PALETTE: [(u8, u8, u8); 7] = [
+    (0, 102, 0),      // Dark Green
+    (0, 204, 0),      // Green
+    (153, 255, 102),  // Light Green
+    (255, 255, 128),  // Yellow
+    (222, 184, 135),  // Tan
+    (204, 51, 0),     // Red
+    (143, 10, 10),    // Dark Red
+];
+
+/// What the empty cells of a shard image show; creatures are drawn in their own color either way
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ImageBackground {
+    /// White, as the image endpoint always rendered
+    #[default]
+    Plain,
+    /// Shaded along TERRAIN_PALETTE by the cell's food
+    FoodTinted,
+}
+
+impl ImageBackground {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ImageBackground::Plain => "plain",
+            ImageBackground::FoodTinted => "food-tinted",
+        }
+    }
+
+    /// The background query value; no value means Plain
+    pub fn from_query(value: Option<&str>) -> Result<Self, String> {
+        match value {
+            None | Some("plain") => Ok(ImageBackground::Plain),
+            Some("food-tinted") => Ok(ImageBackground::FoodTinted),
+            Some(other) => Err(format!("Invalid background: {} (expected plain or food-tinted)", other)),
+        }
+    }
+
+    /// Path of the shard image endpoint; Plain leaves the query out so existing URLs stay as they are
+    pub fn shard_image_path(self, shard_id: &str) -> String {
+        match self {
+            ImageBackground::Plain => format!("/api/shard/{}/image", shard_id),
+            ImageBackground::FoodTinted => format!("/api/shard/{}/image?{}={}", shard_id, BACKGROUND_QUERY_PARAM, self.as_str()),
+        }
+    }
+}
+
+fn lerp(a: u8, b: u8, t: f32) -> u8 {
+    ((1.0 - t) * (a as f32) + t * (b as f32)).round() as u8
+}
+
+pub fn lerp_rgb(a: (u8, u8, u8), b: (u8, u8, u8), t: f32) -> (u8, u8, u8) {
+    (lerp(a.0, b.0, t), lerp(a.1, b.1, t), lerp(a.2, b.2, t))
+}
+
+/// Color along TERRAIN_PALETTE for a value normalized to 0..=1
+pub fn terrain_rgb(normalized: f32) -> (u8, u8, u8) {
+    let clamped = normalized.clamp(0.0, 1.0);
+    let scaled = clamped * (TERRAIN_PALETTE.len() - 1) as f32;
+    let idx = scaled.floor() as usize;
+    if idx >= TERRAIN_PALETTE.len() - 1 {
+        TERRAIN_PALETTE[TERRAIN_PALETTE.len() - 1]
+    } else {
+        lerp_rgb(TERRAIN_PALETTE[idx], TERRAIN_PALETTE[idx + 1], scaled.fract())
+    }
+}
+
+/// The image color of a cell; an empty cell (no health) without food stays white
+pub fn cell_color(cell: &Cell, background: ImageBackground) -> Color {
+    match background {
+        ImageBackground::FoodTinted if cell.health == 0 && cell.food > 0 => {
+            let food_rgb = terrain_rgb(cell.food.min(FOOD_TINT_MAX) as f32 / FOOD_TINT_MAX as f32);
+            let (red, green, blue) = lerp_rgb(WHITE, food_rgb, FOOD_TINT_STRENGTH);
+            Color { red, green, blue }
+        }
+        _ => cell.color,
+    }
+}
@@ -12,24 +12,28 @@ pub struct StorageUtils;
 
 impl StorageUtils {
     pub fn store_with_checksum<T: Serialize>(data: &T, filename: &str) -> Result<(), String> {
+        let serialized = bincode::serialize(data)
+            .map_err(|e| format!("Failed to serialize data: {}", e))?;
+        Self::store_bytes_with_checksum(serialized, filename)
+    }
+
+    /// Writes bytes followed by their checksum, for callers that frame the data themselves
+    pub fn store_bytes_with_checksum(data: Vec<u8>, filename: &str) -> Result<(), String> {
         // Ensure the directory exists
         if let Some(parent) = Path::new(filename).parent() {
             fs::create_dir_all(parent)
                 .map_err(|e| format!("Failed to create directory {}: {}", parent.display(), e))?;
         }
-        
-        let serialized = bincode::serialize(data)
-            .map_err(|e| format!("Failed to serialize data: {}", e))?;
-        
+
         // Calculate checksum
         let mut hasher = DefaultHasher::new();
-        serialized.hash(&mut hasher);
+        data.hash(&mut hasher);
         let checksum = hasher.finish();
-        
+
         // Combine data and checksum
-        let mut data_with_checksum = serialized;
+        let mut data_with_checksum = data;
         data_with_checksum.extend_from_slice(&checksum.to_le_bytes());
-        
+
         fs::write(filename, data_with_checksum)
             .map_err(|e| format!("Failed to write data to file {}: {}", filename, e))
     }
@@ -38,37 +42,45 @@ impl StorageUtils {
         if !Path::new(filename).exists() {
             return None;
         }
-        
-        let content = match fs::read(filename) {
-            Ok(content) => content,
-            Err(_) => return None,
+
+        let data = match Self::retrieve_bytes_with_checksum(filename) {
+            Ok(data) => data,
+            Err(e) => {
+                log_error!("{}", e);
+                return None;
+            }
         };
-        
+
+        match bincode::deserialize(&data) {
+            Ok(data) => Some(data),
+            Err(_) => None,
+        }
+    }
+
+    /// The bytes stored by store_bytes_with_checksum, once their checksum matches
+    pub fn retrieve_bytes_with_checksum(filename: &str) -> Result<Vec<u8>, String> {
+        let mut content = fs::read(filename)
+            .map_err(|e| format!("Failed to read {}: {}", filename, e))?;
+
         if content.len() < CHECKSUM_SIZE {
-            return None;
+            return Err(format!("{} is too short to hold a checksum", filename));
         }
-        
+
         // Split data and checksum
         let data_len = content.len() - CHECKSUM_SIZE;
-        let (data, checksum_bytes) = content.split_at(data_len);
-        let stored_checksum = match checksum_bytes.try_into() {
-            Ok(bytes) => u64::from_le_bytes(bytes),
-            Err(_) => return None,
-        };
-        
+        let checksum_bytes: [u8; CHECKSUM_SIZE] = content[data_len..].try_into()
+            .map_err(|_| format!("{} has a malformed checksum", filename))?;
+        let stored_checksum = u64::from_le_bytes(checksum_bytes);
+        content.truncate(data_len);
+
         // Verify checksum
         let mut hasher = DefaultHasher::new();
-        data.hash(&mut hasher);
+        content.hash(&mut hasher);
         let calculated_checksum = hasher.finish();
-        
+
         if stored_checksum != calculated_checksum {
-            log_error!("Data checksum mismatch for {}. Expected: {}, Got: {}", filename, stored_checksum, calculated_checksum);
-            return None;
-        }
-        
-        match bincode::deserialize(data) {
-            Ok(data) => Some(data),
-            Err(_) => None,
+            return Err(format!("Data checksum mismatch for {}. Expected: {}, Got: {}", filename, stored_checksum, calculated_checksum));
         }
+        Ok(content)
     }
-} 
\ No newline at end of file
+}
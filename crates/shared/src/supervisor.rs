@@ -0,0 +1,157 @@
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+use crate::{log, log_error};
+
+#[derive(Debug, Clone, Copy)]
+pub struct SupervisorConfig {
+    /// Wait before the first restart, doubled on every consecutive panic
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// A run that lasted this long resets the backoff
+    pub stable_after: Duration,
+    /// The task is flapping when it restarted this many times within flapping_window
+    pub flapping_restarts: usize,
+    pub flapping_window: Duration,
+}
+
+impl Default for SupervisorConfig {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            stable_after: Duration::from_secs(60),
+            flapping_restarts: 3,
+            flapping_window: Duration::from_secs(600),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SupervisedTaskHealth {
+    pub name: String,
+    pub running: bool,
+    pub restarts: u64,
+    pub flapping: bool,
+    pub last_panic: Option<String>,
+}
+
+struct TaskState {
+    running: bool,
+    restarts: u64,
+    recent_restarts: VecDeque<Instant>,
+    last_panic: Option<String>,
+    config: SupervisorConfig,
+}
+
+impl TaskState {
+    fn is_flapping(&self) -> bool {
+        self.recent_restarts.iter().filter(|at| at.elapsed() <= self.config.flapping_window).count()
+            >= self.config.flapping_restarts
+    }
+}
+
+static TASKS: OnceLock<Mutex<HashMap<String, TaskState>>> = OnceLock::new();
+
+fn tasks() -> &'static Mutex<HashMap<String, TaskState>> {
+    TASKS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn update_task(name: &str, update: impl FnOnce(&mut TaskState)) {
+    if let Some(state) = tasks().lock().unwrap().get_mut(name) {
+        update(state);
+    }
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    match payload.downcast_ref::<&str>() {
+        Some(s) => s.to_string(),
+        None => payload.downcast_ref::<String>().cloned().unwrap_or_else(|| "<no message>".to_string()),
+    }
+}
+
+/// Spawns a long-lived background task and respawns it with backoff whenever it panics.
+/// `task` builds a fresh future for every run; a run that returns normally ends supervision.
+pub fn spawn_supervised<F, Fut>(name: &str, task: F) -> JoinHandle<()>
+where
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    spawn_supervised_with(name, SupervisorConfig::default(), task)
+}
+
+pub fn spawn_supervised_with<F, Fut>(name: &str, config: SupervisorConfig, task: F) -> JoinHandle<()>
+where
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let name = name.to_string();
+    tasks().lock().unwrap().insert(name.clone(), TaskState {
+        running: true,
+        restarts: 0,
+        recent_restarts: VecDeque::new(),
+        last_panic: None,
+        config,
+    });
+
+    tokio::spawn(async move {
+        let mut backoff = config.initial_backoff;
+        loop {
+            let started_at = Instant::now();
+            let error = match tokio::spawn(task()).await {
+                Ok(()) => {
+                    log!("Supervised task '{}' finished", name);
+                    break;
+                }
+                Err(e) if e.is_panic() => panic_message(e.into_panic().as_ref()),
+                Err(e) => {
+                    log!("Supervised task '{}' was cancelled: {}", name, e);
+                    break;
+                }
+            };
+
+            if started_at.elapsed() >= config.stable_after {
+                backoff = config.initial_backoff;
+            }
+            update_task(&name, |state| {
+                state.restarts += 1;
+                state.recent_restarts.push_back(Instant::now());
+                while state.recent_restarts.len() > config.flapping_restarts {
+                    state.recent_restarts.pop_front();
+                }
+                state.last_panic = Some(error.clone());
+            });
+            log_error!("Supervised task '{}' panicked: {}; restarting in {:?}", name, error, backoff);
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(config.max_backoff);
+        }
+        update_task(&name, |state| state.running = false);
+    })
+}
+
+/// Restart counters of every supervised task, sorted by name
+pub fn supervised_tasks_health() -> Vec<SupervisedTaskHealth> {
+    let mut health: Vec<SupervisedTaskHealth> = tasks().lock().unwrap().iter()
+        .map(|(name, state)| SupervisedTaskHealth {
+            name: name.clone(),
+            running: state.running,
+            restarts: state.restarts,
+            flapping: state.is_flapping(),
+            last_panic: state.last_panic.clone(),
+        })
+        .collect();
+    health.sort_by(|a, b| a.name.cmp(&b.name));
+    health
+}
+
+/// "degraded" when any supervised task is flapping, otherwise "ok"
+pub fn health_status(tasks: &[SupervisedTaskHealth]) -> &'static str {
+    if tasks.iter().any(|task| task.flapping) {
+        "degraded"
+    } else {
+        "ok"
+    }
+}
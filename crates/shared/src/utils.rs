@@ -75,6 +75,51 @@ pub fn new_random_generator() -> SmallRng {
     SmallRng::from_rng(&mut thread_rng()).unwrap()
 }
 
+/// Creates a SmallRng from a fixed seed, for generation that must be reproducible
+pub fn new_seeded_random_generator(seed: u64) -> SmallRng {
+    SmallRng::seed_from_u64(seed)
+}
+
+/// 64-bit FNV-1a as 16 hex digits. Unlike DefaultHasher the result is stable across
+/// Rust releases, so it can be stored and compared between runs.
+pub fn stable_hash_hex(bytes: &[u8]) -> String {
+    let mut hasher = StableHasher::new();
+    hasher.update(bytes);
+    hasher.finish_hex()
+}
+
+/// Incremental form of stable_hash_hex, for data that is produced in chunks
+pub struct StableHasher {
+    hash: u64,
+}
+
+impl StableHasher {
+    pub fn new() -> Self {
+        Self { hash: 0xcbf29ce484222325 }
+    }
+
+    pub fn update(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.hash ^= *byte as u64;
+            self.hash = self.hash.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.hash
+    }
+
+    pub fn finish_hex(&self) -> String {
+        format!("{:016x}", self.finish())
+    }
+}
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub fn random_chance(rng: &mut SmallRng, out_of: u32) -> bool {
     rng.gen_range(1..=out_of) == 1
 }
@@ -109,3 +154,29 @@ pub fn generate_colony_instance_id() -> String {
         .map(|_| (rng.gen_range(b'a'..=b'z') as char))
         .collect()
 }
+
+/// Value of a query string parameter in a raw HTTP request
+pub fn parse_query_param(request: &str, param_name: &str) -> Option<String> {
+    if let Some(query_start) = request.find('?') {
+        let mut query = &request[query_start + 1..];
+        // The query ends at the space before the HTTP version (or the line end)
+        if let Some(query_end) = query.find([' ', '\r', '\n']) {
+            query = &query[..query_end];
+        }
+        for pair in query.split('&') {
+            if let Some(equal_pos) = pair.find('=') {
+                let key = &pair[..equal_pos];
+                let value = &pair[equal_pos + 1..];
+                if key == param_name {
+                    return Some(value.to_string());
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Whether a raw HTTP request is for the root page (GET / with an optional query string)
+pub fn is_root_page_request(request: &str) -> bool {
+    request.starts_with("GET / ") || request.starts_with("GET /?")
+}
@@ -0,0 +1,78 @@
+#[cfg(test)]
+mod tests {
+    use shared::api_auth::{ApiAuthConfig, ApiScope, AuthRejection};
+    use shared::cluster_topology::{ClusterTopology, HostInfo, NodeAddress};
+    use shared::colony_model::Shard;
+    use std::collections::HashMap;
+
+    const ADMIN: &str = "admin-secret";
+    const OBSERVER: &str = "observer-secret";
+
+    fn config() -> ApiAuthConfig {
+        ApiAuthConfig::new(Some(ADMIN.to_string()), Some(OBSERVER.to_string()))
+    }
+
+    fn request(request_line: &str, token: Option<&str>) -> String {
+        let mut request = format!("{} HTTP/1.1\r\nHost: localhost\r\n", request_line);
+        if let Some(token) = token {
+            request.push_str(&format!("Authorization: Bearer {}\r\n", token));
+        }
+        request.push_str("\r\n");
+        request
+    }
+
+    const MUTATING: &str = "POST /colony-start?idempotency_key=abc";
+    const NON_MUTATING: &str = "GET /topology";
+
+    #[test]
+    fn test_admin_scope_allows_all_endpoints() {
+        let config = config();
+        assert_eq!(config.authorize(&request(MUTATING, Some(ADMIN))), Ok(ApiScope::Admin));
+        assert_eq!(config.authorize(&request(NON_MUTATING, Some(ADMIN))), Ok(ApiScope::Admin));
+        assert_eq!(config.authorize(&request("GET /debug-ssm", Some(ADMIN))), Ok(ApiScope::Admin));
+    }
+
+    #[test]
+    fn test_observer_scope_is_read_only() {
+        let config = config();
+        assert_eq!(config.authorize(&request(NON_MUTATING, Some(OBSERVER))), Ok(ApiScope::Observer));
+        assert_eq!(config.authorize(&request(MUTATING, Some(OBSERVER))), Err(AuthRejection::Forbidden));
+        assert_eq!(config.authorize(&request("GET /debug-ssm", Some(OBSERVER))), Err(AuthRejection::Forbidden));
+    }
+
+    #[test]
+    fn test_missing_or_unknown_token_is_unauthorized() {
+        let config = config();
+        assert_eq!(config.authorize(&request(NON_MUTATING, None)), Err(AuthRejection::Unauthorized));
+        assert_eq!(config.authorize(&request(MUTATING, Some("nope"))), Err(AuthRejection::Unauthorized));
+    }
+
+    #[test]
+    fn test_auth_disabled_without_tokens() {
+        let config = ApiAuthConfig::new(None, Some(String::new()));
+        assert!(!config.is_enabled());
+        assert_eq!(config.authorize(&request(MUTATING, None)), Ok(ApiScope::Admin));
+    }
+
+    #[test]
+    fn test_observer_topology_view_hides_private_ips() {
+        let backend = HostInfo::new("10.0.0.5".to_string(), 8082);
+        let unknown = HostInfo::new("10.0.0.9".to_string(), 8082);
+        let shard = Shard { x: 0, y: 0, width: 250, height: 250 };
+        let mut shard_to_host = HashMap::new();
+        shard_to_host.insert(shard, backend.clone());
+        let topology = ClusterTopology {
+            coordinator_host: unknown.clone(),
+            backend_hosts: vec![backend, unknown],
+            shard_to_host,
+        };
+        let addresses = vec![NodeAddress::new("10.0.0.5".to_string(), "54.1.2.3".to_string(), 8082, 8083)];
+
+        let view = topology.to_observer_view(&addresses);
+        let json = serde_json::to_string(&view).expect("Failed to serialize observer view");
+
+        assert!(!json.contains("10.0.0."));
+        assert_eq!(view.backend_hosts[0], HostInfo::new("54.1.2.3".to_string(), 8082));
+        assert_eq!(view.get_host_for_shard(&shard), Some(&view.backend_hosts[0]));
+    }
+}
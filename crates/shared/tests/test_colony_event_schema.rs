@@ -0,0 +1,95 @@
+use shared::be_api::{Color, Traits};
+use shared::colony_event_schema::{event_schema, event_type_name, validate_colony_event, EVENT_SCHEMA_VERSION, EVENT_TYPE_NAMES};
+use shared::colony_events::{BiomeChange, ColonyEvent, CreateCreatureParams, Ellipse, Region};
+
+/// One event of every variant; the match below fails to compile when a variant is missing here
+fn one_of_each_variant() -> Vec<ColonyEvent> {
+    let events = vec![
+        ColonyEvent::CreateCreature(
+            Region::Ellipse(Ellipse { x: 0, y: 0, radius_x: 5, radius_y: 5 }),
+            CreateCreatureParams { color: Color { red: 1, green: 2, blue: 3 }, traits: Traits { size: 5, can_kill: false, can_move: true }, starting_health: 100 },
+        ),
+        ColonyEvent::ChangeExtraFoodPerTick(-3),
+        ColonyEvent::Extinction(),
+        ColonyEvent::NewTopography(),
+        ColonyEvent::ChangeColonyRules(serde_json::from_value(example("ChangeColonyRules")["ChangeColonyRules"].clone()).unwrap()),
+        ColonyEvent::ChangeBiomes(BiomeChange { biomes: Vec::new(), description: "No biomes".to_string() }),
+    ];
+    for event in &events {
+        match event {
+            ColonyEvent::CreateCreature(_, _)
+            | ColonyEvent::ChangeExtraFoodPerTick(_)
+            | ColonyEvent::Extinction()
+            | ColonyEvent::NewTopography()
+            | ColonyEvent::ChangeColonyRules(_)
+            | ColonyEvent::ChangeBiomes(_) => {}
+        }
+    }
+    events
+}
+
+fn example(name: &str) -> serde_json::Value {
+    event_schema().event_types.into_iter().find(|event_type| event_type.name == name).expect("event type").example
+}
+
+/// Parsed from text like the endpoint does; from_value reads an empty tuple variant as a unit variant
+fn event_from(json: serde_json::Value) -> ColonyEvent {
+    serde_json::from_str(&json.to_string()).expect("Failed to parse event")
+}
+
+#[test]
+fn test_every_variant_is_in_the_schema() {
+    let schema = event_schema();
+    assert_eq!(schema.schema_version, EVENT_SCHEMA_VERSION);
+    let names: Vec<&str> = schema.event_types.iter().map(|event_type| event_type.name.as_str()).collect();
+    assert_eq!(names, EVENT_TYPE_NAMES);
+    let variants = one_of_each_variant();
+    assert_eq!(variants.len(), EVENT_TYPE_NAMES.len());
+    for event in &variants {
+        assert!(names.contains(&event_type_name(event)), "{} is not in the schema", event_type_name(event));
+    }
+}
+
+#[test]
+fn test_examples_parse_and_validate() {
+    for event_type in event_schema().event_types {
+        let event = event_from(event_type.example.clone());
+        assert_eq!(event_type_name(&event), event_type.name);
+        assert_eq!(validate_colony_event(&event), Ok(()), "{}", event_type.name);
+        for parameter in &event_type.parameters {
+            assert!(parameter.path.starts_with(&format!("/{}", event_type.name)), "{}", parameter.path);
+        }
+    }
+    assert!(event_schema().event_types.iter().all(|event_type| event_type.generated_only == (event_type.name == "NewTopography")));
+}
+
+#[test]
+fn test_rule_parameters_follow_the_rule_ranges() {
+    let schema = event_schema();
+    let rules = schema.event_types.iter().find(|event_type| event_type.name == "ChangeColonyRules").unwrap();
+    let parameter = |name: &str| rules.parameters.iter().find(|parameter| parameter.name == name).unwrap();
+    assert_eq!((parameter("mutation_chance").min, parameter("mutation_chance").max), (Some(1), Some(1_000_000)));
+    // Rules added later take a default when left out
+    assert!(parameter("mutation_chance").required);
+    assert!(!parameter("color_drift_per_generation").required);
+}
+
+#[test]
+fn test_out_of_range_values_are_rejected() {
+    let mut creature = example("CreateCreature");
+    creature["CreateCreature"][0]["Ellipse"]["radius_x"] = (-1).into();
+    let e = validate_colony_event(&event_from(creature)).unwrap_err();
+    assert!(e.contains("/CreateCreature/0/Ellipse/radius_x=-1 not in [0, 100000]"), "{}", e);
+
+    let mut rules = example("ChangeColonyRules");
+    rules["ChangeColonyRules"]["new_rules"]["mutation_chance"] = 0.into();
+    rules["ChangeColonyRules"]["new_rules"]["kill_success_base_chance"] = 101.into();
+    let e = validate_colony_event(&event_from(rules)).unwrap_err();
+    assert!(e.contains("mutation_chance=0") && e.contains("kill_success_base_chance=101"), "{}", e);
+
+    let mut biomes = example("ChangeBiomes");
+    biomes["ChangeBiomes"]["biomes"][0]["width"] = 0.into();
+    biomes["ChangeBiomes"]["biomes"][0]["overrides"]["mutation_chance"] = 0.into();
+    let e = validate_colony_event(&event_from(biomes)).unwrap_err();
+    assert!(e.contains("/ChangeBiomes/biomes/*/width=0") && e.contains("/ChangeBiomes/biomes/*/overrides/mutation_chance=0"), "{}", e);
+}
@@ -0,0 +1,97 @@
+#[cfg(test)]
+mod tests {
+    use shared::be_api::{ColonyLifeRules, COLONY_LIFE_RULE_RANGES};
+    use shared::colony_model::{COLONY_LIFE_INITIAL_RULES, MAX_MUTATION_COST_STEP};
+
+    fn with_field(field: &str, value: u32) -> ColonyLifeRules {
+        let mut rules = COLONY_LIFE_INITIAL_RULES;
+        match field {
+            "health_cost_per_size_unit" => rules.health_cost_per_size_unit = value,
+            "eat_capacity_per_size_unit" => rules.eat_capacity_per_size_unit = value,
+            "health_cost_if_can_kill" => rules.health_cost_if_can_kill = value,
+            "health_cost_if_can_move" => rules.health_cost_if_can_move = value,
+            "mutation_chance" => rules.mutation_chance = value,
+            "random_death_chance" => rules.random_death_chance = value,
+            "kill_success_base_chance" => rules.kill_success_base_chance = value,
+            "kill_size_advantage_percent" => rules.kill_size_advantage_percent = value,
+            "kill_counter_damage" => rules.kill_counter_damage = value,
+            "reproduction_food_cost" => rules.reproduction_food_cost = value,
+            "reproduction_min_food" => rules.reproduction_min_food = value,
+            "mutation_size_step" => rules.mutation_size_step = value,
+            "mutation_cost_step" => rules.mutation_cost_step = value,
+            "boolean_trait_flip_chance" => rules.boolean_trait_flip_chance = value,
+            "color_drift_per_generation" => rules.color_drift_per_generation = value,
+            "color_mutation_chance" => rules.color_mutation_chance = value,
+            "starvation_grace_ticks" => rules.starvation_grace_ticks = value,
+            "food_sharing_radius" => rules.food_sharing_radius = value,
+            "food_sharing_percent" => rules.food_sharing_percent = value,
+            _ => panic!("Unknown field: {}", field),
+        }
+        rules
+    }
+
+    #[test]
+    fn test_initial_rules_are_valid() {
+        assert_eq!(COLONY_LIFE_INITIAL_RULES.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_boundary_values_for_every_field() {
+        for range in COLONY_LIFE_RULE_RANGES {
+            assert!(with_field(range.field, range.min).validate().is_ok(), "{} min", range.field);
+            assert!(with_field(range.field, range.max).validate().is_ok(), "{} max", range.field);
+
+            let above = with_field(range.field, range.max + 1).validate();
+            assert!(above.unwrap_err().contains(range.field), "{} above max", range.field);
+            if range.min > 0 {
+                let below = with_field(range.field, range.min - 1).validate();
+                assert!(below.unwrap_err().contains(range.field), "{} below min", range.field);
+            }
+        }
+    }
+
+    #[test]
+    fn test_zero_chance_and_free_growth_rejected() {
+        for field in ["mutation_chance", "random_death_chance", "health_cost_per_size_unit"] {
+            assert!(with_field(field, 0).validate().is_err(), "{} = 0", field);
+        }
+    }
+
+    #[test]
+    fn test_kill_success_percent() {
+        let rules = COLONY_LIFE_INITIAL_RULES;
+        assert_eq!(rules.kill_success_percent(10, 10), 60);
+        assert_eq!(rules.kill_success_percent(12, 10), 80);
+        assert_eq!(rules.kill_success_percent(8, 10), 40);
+        // Clamped to a probability
+        assert_eq!(rules.kill_success_percent(20, 10), 100);
+        assert_eq!(rules.kill_success_percent(0, 255), 0);
+    }
+
+    #[test]
+    fn test_rules_without_kill_fields_keep_old_combat() {
+        let json = r#"{"health_cost_per_size_unit":2,"eat_capacity_per_size_unit":5,"health_cost_if_can_kill":10,
+            "health_cost_if_can_move":5,"mutation_chance":100,"random_death_chance":100}"#;
+        let rules: ColonyLifeRules = serde_json::from_str(json).expect("Failed to parse rules");
+        assert_eq!(rules.kill_counter_damage, 0);
+        assert_eq!((rules.reproduction_food_cost, rules.reproduction_min_food), (0, 0));
+        // Mutations as before the magnitude rules: one size unit, no cost limit, abilities mostly flipped
+        assert_eq!((rules.mutation_size_step, rules.mutation_cost_step, rules.boolean_trait_flip_chance), (1, MAX_MUTATION_COST_STEP, 99));
+        // Offspring keep their parent's color outside of mutations
+        assert_eq!((rules.color_drift_per_generation, rules.color_mutation_chance), (0, 0));
+        // Kills anything up to its own size, never anything larger
+        assert_eq!(rules.kill_success_percent(10, 10), 100);
+        assert_eq!(rules.kill_success_percent(11, 10), 100);
+        assert_eq!(rules.kill_success_percent(9, 10), 0);
+    }
+
+    #[test]
+    fn test_ranges_cover_every_field() {
+        let values = COLONY_LIFE_INITIAL_RULES.field_values();
+        assert_eq!(values.len(), COLONY_LIFE_RULE_RANGES.len());
+        for ((field, _), range) in values.iter().zip(COLONY_LIFE_RULE_RANGES.iter()) {
+            assert_eq!(*field, range.field);
+            assert_eq!(ColonyLifeRules::allowed_range(field), Some(*range));
+        }
+    }
+}
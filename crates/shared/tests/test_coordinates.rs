@@ -0,0 +1,63 @@
+#[cfg(test)]
+mod tests {
+    use shared::colony_model::{GlobalPos, LocalPos, Shard};
+
+    const ORIGIN_SHARD: Shard = Shard { x: 0, y: 0, width: 4, height: 3 };
+    const OFFSET_SHARD: Shard = Shard { x: 500, y: 250, width: 4, height: 3 };
+
+    #[test]
+    fn test_global_to_local_at_origin() {
+        let pos = GlobalPos::new(2, 1);
+        assert_eq!(pos.to_local(&ORIGIN_SHARD), LocalPos::new(2, 1));
+        assert_eq!(pos.to_local(&ORIGIN_SHARD).to_global(&ORIGIN_SHARD), pos);
+    }
+
+    #[test]
+    fn test_global_to_local_away_from_origin() {
+        assert_eq!(GlobalPos::new(502, 251).to_local(&OFFSET_SHARD), LocalPos::new(2, 1));
+        assert_eq!(LocalPos::new(2, 1).to_global(&OFFSET_SHARD), GlobalPos::new(502, 251));
+        // The same local position is a different cell in each shard
+        assert_ne!(LocalPos::new(0, 0).to_global(&ORIGIN_SHARD), LocalPos::new(0, 0).to_global(&OFFSET_SHARD));
+        // A cell of another shard maps outside this one
+        assert_eq!(GlobalPos::new(2, 1).to_local(&OFFSET_SHARD), LocalPos::new(-498, -249));
+    }
+
+    #[test]
+    fn test_grid_index_includes_the_shadow_margin() {
+        // Grid rows are width + 2 = 6 cells long, with the margin in row and column 0
+        assert_eq!(LocalPos::new(-1, -1).grid_index(&OFFSET_SHARD), Some(0));
+        assert_eq!(LocalPos::new(0, 0).grid_index(&OFFSET_SHARD), Some(7));
+        assert_eq!(LocalPos::new(3, 2).grid_index(&OFFSET_SHARD), Some(22));
+        assert_eq!(LocalPos::new(4, 3).grid_index(&OFFSET_SHARD), Some(OFFSET_SHARD.grid_len() - 1));
+        assert_eq!(LocalPos::new(5, 0).grid_index(&OFFSET_SHARD), None);
+        assert_eq!(LocalPos::new(0, -2).grid_index(&OFFSET_SHARD), None);
+    }
+
+    #[test]
+    fn test_grid_index_round_trip() {
+        for idx in 0..OFFSET_SHARD.grid_len() {
+            let pos = LocalPos::from_grid_index(idx, &OFFSET_SHARD);
+            assert_eq!(pos.grid_index(&OFFSET_SHARD), Some(idx));
+            assert_eq!(pos.is_interior(&OFFSET_SHARD), OFFSET_SHARD.contains(pos.to_global(&OFFSET_SHARD)));
+        }
+        let interior = (0..OFFSET_SHARD.grid_len())
+            .filter(|&idx| LocalPos::from_grid_index(idx, &OFFSET_SHARD).is_interior(&OFFSET_SHARD))
+            .count();
+        assert_eq!(interior, (OFFSET_SHARD.width * OFFSET_SHARD.height) as usize);
+    }
+
+    #[test]
+    fn test_interior_row_leaves_out_the_shadow_margin() {
+        assert_eq!(OFFSET_SHARD.interior_row(0), 7..11);
+        assert_eq!(OFFSET_SHARD.interior_row(2), 19..23);
+        assert_eq!(OFFSET_SHARD.interior_row(2).start, LocalPos::new(0, 2).grid_index(&OFFSET_SHARD).unwrap());
+    }
+
+    #[test]
+    fn test_shard_contains_global_positions() {
+        assert!(OFFSET_SHARD.contains(GlobalPos::new(500, 250)));
+        assert!(OFFSET_SHARD.contains(GlobalPos::new(503, 252)));
+        assert!(!OFFSET_SHARD.contains(GlobalPos::new(504, 252)));
+        assert!(!OFFSET_SHARD.contains(GlobalPos::new(499, 250)));
+    }
+}
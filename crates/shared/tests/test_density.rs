@@ -0,0 +1,68 @@
+use shared::colony_model::Shard;
+use shared::density::{bucket_of, bucket_start, parse_density_cells, DensityGrid, MAX_DENSITY_CELLS};
+
+fn area(x: i32, y: i32, width: i32, height: i32) -> Shard {
+    Shard { x, y, width, height }
+}
+
+#[test]
+fn test_uneven_buckets_cover_every_cell_once() {
+    // 10 cells in 4 buckets: 3, 2, 3, 2 wide
+    let starts: Vec<usize> = (0..=4).map(|bucket| bucket_start(bucket, 4, 10)).collect();
+    assert_eq!(starts, vec![0, 3, 5, 8, 10]);
+    for pos in 0..10 {
+        let bucket = bucket_of(pos, 4, 10);
+        assert!(bucket_start(bucket, 4, 10) <= pos && pos < bucket_start(bucket + 1, 4, 10), "cell {} in bucket {}", pos, bucket);
+    }
+}
+
+#[test]
+fn test_counts_by_bucket_in_colony_coordinates() {
+    let shard = area(10, 20, 4, 4);
+    // Two per 2x2 bucket corner, one outside the shard
+    let grid = DensityGrid::from_positions(shard, 5, 2, [(10, 20), (11, 21), (13, 23), (12, 21), (14, 20)]);
+    assert_eq!((grid.cols, grid.rows, grid.tick), (2, 2, 5));
+    assert_eq!(grid.counts, vec![2, 1, 0, 1]);
+    assert_eq!(grid.total(), 4);
+
+    // No more buckets than cells along a side
+    let narrow = DensityGrid::empty(area(0, 0, 3, 8), 0, 4);
+    assert_eq!((narrow.cols, narrow.rows), (3, 4));
+}
+
+#[test]
+fn test_stitching_aligned_shards_adds_their_buckets() {
+    let left = DensityGrid { area: area(0, 0, 4, 4), tick: 9, cols: 2, rows: 2, counts: vec![1, 2, 3, 4] };
+    let right = DensityGrid { area: area(4, 0, 4, 4), tick: 7, cols: 2, rows: 2, counts: vec![5, 6, 7, 8] };
+    let colony = DensityGrid::stitch(area(0, 0, 8, 4), 2, &[left, right]);
+    assert_eq!((colony.cols, colony.rows), (2, 2));
+    assert_eq!(colony.counts, vec![3, 11, 7, 15]);
+    // The oldest shard's tick
+    assert_eq!(colony.tick, 7);
+}
+
+#[test]
+fn test_stitching_across_bucket_edges_keeps_the_total() {
+    // Three 5-wide shards into 4 colony buckets, 3 or 4 cells wide: shard buckets straddle the edges
+    let parts: Vec<DensityGrid> = (0..3)
+        .map(|i| DensityGrid { area: area(i * 5, 0, 5, 2), tick: 1, cols: 1, rows: 1, counts: vec![7] })
+        .collect();
+    let colony = DensityGrid::stitch(area(0, 0, 15, 2), 4, &parts);
+    assert_eq!((colony.cols, colony.rows), (4, 2));
+    assert_eq!(colony.total(), 21);
+    // Each colony column gets its share of the 4, 4, 4 and 3 cells it covers, give or take the rounding
+    let exact = [5.6, 5.6, 5.6, 4.2];
+    for (col, exact) in exact.iter().enumerate() {
+        let count = (colony.counts[col] + colony.counts[4 + col]) as f64;
+        assert!((count - exact).abs() < 1.0, "column {} has {}", col, count);
+    }
+}
+
+#[test]
+fn test_cells_parameter() {
+    assert_eq!(parse_density_cells(None, 32), Ok(32));
+    assert_eq!(parse_density_cells(Some("64"), 32), Ok(64));
+    assert!(parse_density_cells(Some("0"), 32).is_err());
+    assert!(parse_density_cells(Some(&(MAX_DENSITY_CELLS + 1).to_string()), 32).is_err());
+    assert!(parse_density_cells(Some("many"), 32).is_err());
+}
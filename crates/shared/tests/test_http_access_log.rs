@@ -0,0 +1,103 @@
+use shared::http_access_log::{normalize_path, redact_query, AccessLog, AccessLoggedStream, AccessRecord, AccessStats, MAX_TRACKED_PATHS, OTHER_PATH};
+use shared::logging::RotatingLogFile;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+
+fn temp_file(name: &str) -> PathBuf {
+    let nanos = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_nanos();
+    std::env::temp_dir().join(format!("{}_{}_{}", name, std::process::id(), nanos)).join("access.log")
+}
+
+fn peer() -> SocketAddr {
+    "10.0.0.7:51234".parse().unwrap()
+}
+
+#[test]
+fn test_sensitive_query_values_are_redacted() {
+    assert_eq!(redact_query("idempotency_key=abc123&seeding=clustered"), "idempotency_key=REDACTED&seeding=clustered");
+    assert_eq!(redact_query("token=t&access_token=a&API_KEY=k&since_tick=40"), "token=REDACTED&access_token=REDACTED&API_KEY=REDACTED&since_tick=40");
+    assert_eq!(redact_query("limit=50&flag"), "limit=50&flag");
+
+    let record = AccessRecord::new("POST /colony-start?idempotency_key=secret-key HTTP/1.1\r\nHost: x\r\n\r\n", Some(200), 35, Duration::from_millis(12), peer());
+    assert_eq!(record.method, "POST");
+    assert_eq!(record.path, "/colony-start");
+    assert_eq!(record.query.as_deref(), Some("idempotency_key=REDACTED"));
+    let line = record.log_line();
+    assert!(!line.contains("secret-key"), "{}", line);
+    assert_eq!(line, "10.0.0.7:51234 \"POST /colony-start?idempotency_key=REDACTED\" 200 35 12.0ms");
+}
+
+#[test]
+fn test_paths_with_ids_share_their_counters() {
+    assert_eq!(normalize_path("/api/shard/0_0_250_250/image"), "/api/shard/{id}/image");
+    assert_eq!(normalize_path("/api/colony-stats"), "/api/colony-stats");
+
+    let mut stats = AccessStats::default();
+    for latency_ms in 1..=100 {
+        stats.record(&format!("/api/shard/{}_0_10_10/image", latency_ms), Duration::from_millis(latency_ms));
+    }
+    stats.record("/api/colony-stats", Duration::from_millis(7));
+    assert_eq!(stats.count("/api/shard/{id}/image"), 100);
+    assert_eq!(stats.p95_ms("/api/shard/{id}/image"), Some(95.0));
+    assert_eq!(stats.count("/api/colony-stats"), 1);
+    assert_eq!(stats.p95_ms("/api/colony-stats"), Some(7.0));
+    assert_eq!(stats.p95_ms("/api/unknown"), None);
+
+    let metrics = stats.render_prometheus("coordinator");
+    assert!(metrics.contains("coordinator_http_requests_total{path=\"/api/shard/{id}/image\"} 100"), "{}", metrics);
+    assert!(metrics.contains("coordinator_http_request_latency_p95_ms{path=\"/api/colony-stats\"} 7.000"), "{}", metrics);
+}
+
+#[test]
+fn test_paths_past_the_limit_are_counted_as_other() {
+    let mut stats = AccessStats::default();
+    for n in 0..MAX_TRACKED_PATHS + 3 {
+        stats.record(&format!("/{}", "a".repeat(n + 1)), Duration::from_millis(1));
+    }
+    assert_eq!(stats.count(OTHER_PATH), 3);
+}
+
+#[test]
+fn test_log_file_rotates_at_max_bytes() {
+    let path = temp_file("access_log_rotation");
+    let file = RotatingLogFile::new(&path, 200, 2);
+    for n in 0..20 {
+        file.append_line(&format!("request {:02} {}", n, "x".repeat(40))).unwrap();
+    }
+    let current = std::fs::read_to_string(&path).unwrap();
+    assert!(current.contains("request 19"));
+    assert!(std::fs::metadata(&path).unwrap().len() <= 200);
+    assert!(file.rotated_path(1).exists());
+    assert!(file.rotated_path(2).exists());
+    assert!(!file.rotated_path(3).exists());
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[tokio::test]
+async fn test_dropped_stream_records_status_bytes_and_redacted_query() {
+    let path = temp_file("access_log_stream");
+    let log = Arc::new(AccessLog::new(Some(RotatingLogFile::new(&path, 1024 * 1024, 1))));
+    let (server, _client) = tokio::io::duplex(4096);
+
+    let mut stream = AccessLoggedStream::new(server, log.clone(), peer());
+    stream.set_request("GET /api/shard/0_0_10_10/event-log?limit=5&token=abc HTTP/1.1\r\n\r\n");
+    let response = "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n";
+    stream.write_all(response.as_bytes()).await.unwrap();
+    assert_eq!(stream.status(), Some(404));
+    assert_eq!(log.count("/api/shard/{id}/event-log"), 0);
+    drop(stream);
+
+    assert_eq!(log.count("/api/shard/{id}/event-log"), 1);
+    assert!(log.p95_ms("/api/shard/{id}/event-log").is_some());
+    let written = std::fs::read_to_string(&path).unwrap();
+    assert!(written.contains(&format!("\"GET /api/shard/0_0_10_10/event-log?limit=5&token=REDACTED\" 404 {} ", response.len())), "{}", written);
+
+    // A connection that never sent a request is not logged
+    let (server, _client) = tokio::io::duplex(64);
+    drop(AccessLoggedStream::new(server, log.clone(), peer()));
+    assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 1);
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+}
@@ -0,0 +1,85 @@
+use shared::cluster_topology::HostInfo;
+use shared::http_port_probe::{candidate_http_ports, HttpPortProbe, DEFAULT_BACKEND_HTTP_PORT};
+use std::cell::RefCell;
+use std::time::{Duration, Instant};
+
+const INTERVAL: Duration = Duration::from_secs(180);
+
+fn backend() -> HostInfo {
+    HostInfo::new("127.0.0.1".to_string(), 8084)
+}
+
+#[test]
+fn test_candidate_ports_start_after_the_rpc_port() {
+    assert_eq!(candidate_http_ports(8090), vec![8091, DEFAULT_BACKEND_HTTP_PORT]);
+    assert_eq!(candidate_http_ports(8084), vec![DEFAULT_BACKEND_HTTP_PORT]);
+    assert_eq!(candidate_http_ports(DEFAULT_BACKEND_HTTP_PORT), vec![8086]);
+    assert_eq!(candidate_http_ports(u16::MAX), vec![DEFAULT_BACKEND_HTTP_PORT]);
+}
+
+#[test]
+fn test_discovered_port_is_cached() {
+    let mut probe = HttpPortProbe::new(true, INTERVAL);
+    let probed = RefCell::new(Vec::new());
+    let fake = |_: &str, port: u16| {
+        probed.borrow_mut().push(port);
+        port == DEFAULT_BACKEND_HTTP_PORT
+    };
+    let now = Instant::now();
+
+    assert_eq!(probe.probe(&backend(), now, fake), Some(DEFAULT_BACKEND_HTTP_PORT));
+    assert_eq!(probe.probe(&backend(), now, fake), Some(DEFAULT_BACKEND_HTTP_PORT));
+    assert_eq!(probe.discovered(&backend()), Some(DEFAULT_BACKEND_HTTP_PORT));
+    assert_eq!(*probed.borrow(), vec![DEFAULT_BACKEND_HTTP_PORT]);
+}
+
+#[test]
+fn test_failed_probe_waits_for_the_interval() {
+    let mut probe = HttpPortProbe::new(true, INTERVAL);
+    let calls = RefCell::new(0);
+    let fake = |_: &str, _: u16| {
+        *calls.borrow_mut() += 1;
+        false
+    };
+    let start = Instant::now();
+
+    assert_eq!(probe.probe(&backend(), start, fake), None);
+    assert_eq!(*calls.borrow(), 1);
+    assert_eq!(probe.probe(&backend(), start + INTERVAL - Duration::from_secs(1), fake), None);
+    assert_eq!(*calls.borrow(), 1);
+    assert_eq!(probe.probe(&backend(), start + INTERVAL, fake), None);
+    assert_eq!(*calls.borrow(), 2);
+}
+
+#[test]
+fn test_backends_are_rate_limited_separately() {
+    let mut probe = HttpPortProbe::new(true, INTERVAL);
+    let other = HostInfo::new("127.0.0.1".to_string(), 8090);
+    let now = Instant::now();
+
+    assert_eq!(probe.probe(&backend(), now, |_, _| false), None);
+    assert_eq!(probe.probe(&other, now, |_, port| port == 8091), Some(8091));
+}
+
+#[test]
+fn test_forgotten_port_is_probed_again_after_the_interval() {
+    let mut probe = HttpPortProbe::new(true, INTERVAL);
+    let start = Instant::now();
+    assert_eq!(probe.probe(&backend(), start, |_, _| true), Some(DEFAULT_BACKEND_HTTP_PORT));
+
+    probe.forget(&backend());
+    assert_eq!(probe.probe(&backend(), start, |_, _| true), None);
+    assert_eq!(probe.probe(&backend(), start + INTERVAL, |_, _| true), Some(DEFAULT_BACKEND_HTTP_PORT));
+}
+
+#[test]
+fn test_aws_mode_never_probes() {
+    let mut probe = HttpPortProbe::for_deployment_mode("aws");
+    let probed = RefCell::new(false);
+    let result = probe.probe(&backend(), Instant::now(), |_, _| {
+        *probed.borrow_mut() = true;
+        true
+    });
+    assert_eq!(result, None);
+    assert!(!*probed.borrow());
+}
@@ -0,0 +1,62 @@
+#[cfg(test)]
+mod tests {
+    use shared::layer_stats::{
+        decode_layer, decode_layer_with_stats, encode_layer, encode_layer_with_stats, LayerStats, LAYER_HISTOGRAM_BUCKETS
+    };
+    use shared::utils::new_random_generator;
+    use rand::Rng;
+
+    fn brute_force_histogram(values: &[i32], min: i32, max: i32) -> Vec<u32> {
+        let mut histogram = vec![0u32; LAYER_HISTOGRAM_BUCKETS];
+        let range = (max - min + 1) as f64;
+        for &value in values {
+            let bucket = ((value - min) as f64 / range * LAYER_HISTOGRAM_BUCKETS as f64).floor() as usize;
+            histogram[bucket] += 1;
+        }
+        histogram
+    }
+
+    #[test]
+    fn test_header_stats_match_brute_force() {
+        let mut rng = new_random_generator();
+        for max_value in [1, 2, 31, 255, 10_000] {
+            let values: Vec<i32> = (0..250 * 250).map(|_| rng.gen_range(0..=max_value)).collect();
+            let stats = LayerStats::compute(&values);
+
+            let min = *values.iter().min().unwrap();
+            let max = *values.iter().max().unwrap();
+            let mean = values.iter().map(|&v| v as f64).sum::<f64>() / values.len() as f64;
+            assert_eq!(stats.min, min);
+            assert_eq!(stats.max, max);
+            assert!((stats.mean - mean).abs() < 1e-9);
+            assert_eq!(stats.histogram, brute_force_histogram(&values, min, max));
+            assert_eq!(stats.histogram.iter().sum::<u32>() as usize, values.len());
+        }
+    }
+
+    #[test]
+    fn test_stats_for_empty_and_constant_layers() {
+        let empty = LayerStats::compute(&[]);
+        assert_eq!((empty.min, empty.max, empty.mean), (0, 0, 0.0));
+        assert!(empty.histogram.iter().all(|&count| count == 0));
+
+        let constant = LayerStats::compute(&[7; 10]);
+        assert_eq!((constant.min, constant.max), (7, 7));
+        assert_eq!(constant.histogram[0], 10);
+    }
+
+    #[test]
+    fn test_layer_formats_roundtrip() {
+        let values = vec![0, 5, -3, 42, 255];
+        let stats = LayerStats::compute(&values);
+
+        assert_eq!(decode_layer(&encode_layer(&values)), Some(values.clone()));
+
+        let decoded = decode_layer_with_stats(&encode_layer_with_stats(&values, &stats)).expect("decode failed");
+        assert_eq!(decoded.values, values);
+        assert_eq!(decoded.stats, stats);
+
+        // The two formats are not interchangeable
+        assert!(decode_layer_with_stats(&encode_layer(&values)).is_none());
+    }
+}
@@ -0,0 +1,62 @@
+use shared::colony_event_shared::create_colony_event_description;
+use shared::colony_events::{ColonyEvent, CreateCreatureParams, Ellipse, Region};
+use shared::colony_model::{Color, Traits};
+use shared::coordinator_api::ColonyEventDescription;
+use shared::live_feed::{base64_encode, FeedMessage, FeedRequest, FeedTopic};
+
+#[test]
+fn test_base64_encode_matches_rfc4648_vectors() {
+    let vectors = [("", ""), ("f", "Zg=="), ("fo", "Zm8="), ("foo", "Zm9v"), ("foob", "Zm9vYg=="), ("fooba", "Zm9vYmE="), ("foobar", "Zm9vYmFy")];
+    for (input, expected) in vectors {
+        assert_eq!(base64_encode(input.as_bytes()), expected);
+    }
+    assert_eq!(base64_encode(&[0xFB, 0xFF]), "+/8=");
+}
+
+#[test]
+fn test_feed_messages_are_tagged_json() {
+    let request = serde_json::to_string(&FeedRequest::Subscribe { topics: vec![FeedTopic::Ticks, FeedTopic::BackendHealth] }).unwrap();
+    assert_eq!(request, r#"{"type":"subscribe","topics":["ticks","backend_health"]}"#);
+
+    let tick = FeedMessage::Tick { timestamp_ms: 5, min_tick: 10, max_tick: 12 };
+    assert_eq!(serde_json::to_string(&tick).unwrap(), r#"{"type":"tick","timestamp_ms":5,"min_tick":10,"max_tick":12}"#);
+    assert_eq!(tick.topic(), Some(FeedTopic::Ticks));
+    assert_eq!(FeedMessage::Lagged { dropped: 3 }.topic(), None);
+
+    let event = FeedMessage::Event {
+        event: ColonyEventDescription {
+            tick: 7,
+            intended_tick: None,
+            event_type: "Drought".to_string(),
+            description: "Dry".to_string(),
+            delivery: None,
+            no_effect: false,
+            region: None,
+        },
+    };
+    let json = serde_json::to_string(&event).unwrap();
+    match serde_json::from_str::<FeedMessage>(&json).unwrap() {
+        FeedMessage::Event { event } => assert_eq!((event.tick, event.event_type.as_str()), (7, "Drought")),
+        other => panic!("unexpected message {:?}", other),
+    }
+}
+
+#[test]
+fn test_region_events_carry_their_region() {
+    let region = Region::Ellipse(Ellipse { x: 40, y: 25, radius_x: 6, radius_y: 3 });
+    let params = CreateCreatureParams { color: Color { red: 1, green: 2, blue: 3 }, traits: Traits { size: 4, can_kill: false, can_move: true }, starting_health: 90 };
+    let event = create_colony_event_description(&ColonyEvent::CreateCreature(region, params), 300);
+    let json = serde_json::to_string(&FeedMessage::Event { event }).unwrap();
+    match serde_json::from_str::<FeedMessage>(&json).unwrap() {
+        FeedMessage::Event { event } => match event.region {
+            Some(Region::Ellipse(ellipse)) => assert_eq!((ellipse.x, ellipse.y, ellipse.radius_x, ellipse.radius_y), (40, 25, 6, 3)),
+            None => panic!("region lost in {}", json),
+        },
+        other => panic!("unexpected message {:?}", other),
+    }
+
+    // Events without a region keep their old JSON
+    let event = create_colony_event_description(&ColonyEvent::ChangeExtraFoodPerTick(2), 300);
+    assert!(event.region.is_none());
+    assert!(!serde_json::to_string(&event).unwrap().contains("region"));
+}
@@ -0,0 +1,48 @@
+use shared::output_paths::{OutputPaths, OUTPUT_DIR_ENV};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn temp_root(name: &str) -> PathBuf {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).expect("Clock before epoch").as_nanos();
+    std::env::temp_dir().join(format!("{}_{}_{}", name, std::process::id(), nanos))
+}
+
+#[test]
+fn test_accessors_resolve_under_the_root() {
+    let root = Path::new("/tmp/colony_out");
+    let paths = OutputPaths::new(root);
+    assert_eq!(paths.root(), root);
+    assert_eq!(paths.log_file("be_8082"), root.join("logs/be_8082.log"));
+    assert_eq!(paths.registry_dir(), root.join("ssm"));
+    assert_eq!(paths.shard_snapshots_dir("abc"), root.join("storage/abc/shards"));
+    assert_eq!(paths.captures_dir("abc"), root.join("s3/distributed-colony/abc/images_shots"));
+    assert_eq!(paths.stats_dir("abc"), root.join("s3/distributed-colony/abc/stats_shots"));
+    assert_eq!(paths.events_dir("abc"), root.join("s3/distributed-colony/abc/events"));
+    assert_eq!(paths.gui_exports_dir(), root.join("gui_exports"));
+}
+
+#[test]
+fn test_ensure_writable_creates_the_root_and_fails_on_a_file() {
+    let root = temp_root("output_paths");
+    let paths = OutputPaths::new(root.join("nested"));
+    paths.ensure_writable().expect("Temp root should be writable");
+    assert!(paths.root().is_dir());
+    // The probe file is cleaned up
+    assert_eq!(std::fs::read_dir(paths.root()).unwrap().count(), 0);
+
+    let file = root.join("not_a_dir");
+    std::fs::write(&file, b"").unwrap();
+    let error = OutputPaths::new(&file).ensure_writable().unwrap_err();
+    assert!(error.contains("not_a_dir"), "{}", error);
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn test_from_env_honours_the_output_dir() {
+    let root = temp_root("output_paths_env");
+    std::env::set_var(OUTPUT_DIR_ENV, &root);
+    assert_eq!(OutputPaths::from_env().root(), root.as_path());
+    std::env::set_var(OUTPUT_DIR_ENV, " ");
+    assert_eq!(OutputPaths::from_env().root(), Path::new("output"));
+    std::env::remove_var(OUTPUT_DIR_ENV);
+}
@@ -0,0 +1,92 @@
+use shared::be_api::BackendRequest;
+use shared::rpc_decode_failures::{DecodeFailureTracker, DecodedFrame, FrameDecoder, DECODE_FAILURE_LOG_INTERVAL};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const PEER: &str = "10.0.0.7";
+
+fn garbage() -> Vec<u8> {
+    vec![0xff; 7]
+}
+
+fn ping() -> Vec<u8> {
+    bincode::serialize(&BackendRequest::Ping).unwrap()
+}
+
+fn tracker() -> Mutex<DecodeFailureTracker> {
+    Mutex::new(DecodeFailureTracker::new(DECODE_FAILURE_LOG_INTERVAL))
+}
+
+/// (log_count, disconnect) of a frame that must be rejected
+fn rejected(frame: DecodedFrame<BackendRequest>) -> (Option<u64>, bool) {
+    match frame {
+        DecodedFrame::Rejected { error, log_count, disconnect } => {
+            assert!(!error.is_empty());
+            (log_count, disconnect)
+        }
+        DecodedFrame::Request(request) => panic!("garbage decoded as {:?}", request),
+    }
+}
+
+#[test]
+fn test_valid_frames_decode() {
+    let tracker = tracker();
+    let mut decoder = FrameDecoder::new(PEER.to_string(), 2);
+    assert!(matches!(decoder.decode::<BackendRequest>(&ping(), &tracker), DecodedFrame::Request(BackendRequest::Ping)));
+    assert!(tracker.lock().unwrap().peers().is_empty());
+}
+
+#[test]
+fn test_failures_are_logged_once_per_interval_with_the_running_count() {
+    let tracker = tracker();
+    let start = Instant::now();
+    // A fresh connection per failure, like a client of another build reconnecting
+    let fail_at = |now| rejected(FrameDecoder::new(PEER.to_string(), 5).decode_at(&garbage(), &tracker, now)).0;
+
+    assert_eq!(fail_at(start), Some(1));
+    for seconds in 1..30 {
+        assert_eq!(fail_at(start + Duration::from_secs(seconds)), None);
+    }
+    assert_eq!(fail_at(start + DECODE_FAILURE_LOG_INTERVAL - Duration::from_millis(1)), None);
+    assert_eq!(fail_at(start + DECODE_FAILURE_LOG_INTERVAL), Some(32));
+    assert_eq!(fail_at(start + DECODE_FAILURE_LOG_INTERVAL + Duration::from_secs(1)), None);
+
+    let peers = tracker.lock().unwrap().peers();
+    let failures = &peers[PEER];
+    assert_eq!(failures.count, 33);
+    assert!(failures.first_seen_ms <= failures.last_seen_ms);
+    assert_eq!(failures.disconnects, 0);
+}
+
+#[test]
+fn test_peers_are_throttled_separately() {
+    let tracker = tracker();
+    let now = Instant::now();
+    let mut first = FrameDecoder::new(PEER.to_string(), 5);
+    let mut second = FrameDecoder::new("10.0.0.8".to_string(), 5);
+    assert_eq!(rejected(first.decode_at(&garbage(), &tracker, now)).0, Some(1));
+    assert_eq!(rejected(second.decode_at(&garbage(), &tracker, now)).0, Some(1));
+    assert_eq!(rejected(first.decode_at(&garbage(), &tracker, now)).0, None);
+}
+
+#[test]
+fn test_connection_closes_after_consecutive_failures_only() {
+    let tracker = tracker();
+    let now = Instant::now();
+    let mut decoder = FrameDecoder::new(PEER.to_string(), 3);
+
+    assert!(!rejected(decoder.decode_at(&garbage(), &tracker, now)).1);
+    assert!(!rejected(decoder.decode_at(&garbage(), &tracker, now)).1);
+    // A good frame in between resets the streak
+    assert!(matches!(decoder.decode_at::<BackendRequest>(&ping(), &tracker, now), DecodedFrame::Request(_)));
+    assert!(!rejected(decoder.decode_at(&garbage(), &tracker, now)).1);
+    assert!(!rejected(decoder.decode_at(&garbage(), &tracker, now)).1);
+    assert!(rejected(decoder.decode_at(&garbage(), &tracker, now)).1);
+
+    let tracker = tracker.lock().unwrap();
+    assert_eq!(tracker.peers()[PEER].count, 5);
+    assert_eq!(tracker.peers()[PEER].disconnects, 1);
+    let metrics = tracker.render_prometheus("backend");
+    assert!(metrics.contains("backend_rpc_deserialize_failures_total{peer=\"10.0.0.7\"} 5"));
+    assert!(metrics.contains("backend_rpc_deserialize_disconnects_total{peer=\"10.0.0.7\"} 1"));
+}
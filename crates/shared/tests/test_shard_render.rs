@@ -0,0 +1,65 @@
+use shared::colony_model::{Cell, Color, Traits};
+use shared::shard_render::{cell_color, terrain_rgb, ImageBackground, FOOD_TINT_MAX, TERRAIN_PALETTE};
+
+const WHITE: Color = Color { red: 255, green: 255, blue: 255 };
+const CREATURE: Color = Color { red: 10, green: 20, blue: 30 };
+
+fn cell(health: u16, food: u16, color: Color) -> Cell {
+    Cell {
+        tick_bit: false,
+        food,
+        extra_food_per_tick: 0,
+        color,
+        original_color: color,
+        health,
+        age: 0,
+        hunger: 0,
+        traits: Traits { size: 1, can_kill: false, can_move: false },
+    }
+}
+
+fn rgb(color: Color) -> (u8, u8, u8) {
+    (color.red, color.green, color.blue)
+}
+
+#[test]
+fn test_plain_background_keeps_cell_colors() {
+    for food in [0, 100, FOOD_TINT_MAX] {
+        assert_eq!(rgb(cell_color(&cell(0, food, WHITE), ImageBackground::Plain)), rgb(WHITE));
+        assert_eq!(rgb(cell_color(&cell(5, food, CREATURE), ImageBackground::Plain)), rgb(CREATURE));
+    }
+}
+
+#[test]
+fn test_food_tinted_background_shades_only_empty_cells() {
+    let tinted = |food| rgb(cell_color(&cell(0, food, WHITE), ImageBackground::FoodTinted));
+    // No food stays white, more food moves further along the palette
+    assert_eq!(tinted(0), rgb(WHITE));
+    assert_ne!(tinted(50), tinted(200));
+    // Halfway between white and the last palette color, also for food above FOOD_TINT_MAX
+    let (r, g, b) = TERRAIN_PALETTE[TERRAIN_PALETTE.len() - 1];
+    let expected = ((255 + r as u16).div_ceil(2) as u8, (255 + g as u16).div_ceil(2) as u8, (255 + b as u16).div_ceil(2) as u8);
+    assert_eq!(tinted(FOOD_TINT_MAX), expected);
+    assert_eq!(tinted(FOOD_TINT_MAX * 4), expected);
+
+    // Creatures are drawn in their own color whatever their cell's food
+    assert_eq!(rgb(cell_color(&cell(5, 200, CREATURE), ImageBackground::FoodTinted)), rgb(CREATURE));
+}
+
+#[test]
+fn test_terrain_palette_ends() {
+    assert_eq!(terrain_rgb(0.0), TERRAIN_PALETTE[0]);
+    assert_eq!(terrain_rgb(-1.0), TERRAIN_PALETTE[0]);
+    assert_eq!(terrain_rgb(1.0), TERRAIN_PALETTE[TERRAIN_PALETTE.len() - 1]);
+}
+
+#[test]
+fn test_background_query() {
+    assert_eq!(ImageBackground::from_query(None), Ok(ImageBackground::Plain));
+    assert_eq!(ImageBackground::from_query(Some("plain")), Ok(ImageBackground::Plain));
+    assert_eq!(ImageBackground::from_query(Some("food-tinted")), Ok(ImageBackground::FoodTinted));
+    assert!(ImageBackground::from_query(Some("food")).is_err());
+
+    assert_eq!(ImageBackground::Plain.shard_image_path("0_0_10_10"), "/api/shard/0_0_10_10/image");
+    assert_eq!(ImageBackground::FoodTinted.shard_image_path("0_0_10_10"), "/api/shard/0_0_10_10/image?background=food-tinted");
+}
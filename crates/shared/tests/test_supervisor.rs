@@ -0,0 +1,71 @@
+use shared::supervisor::{health_status, spawn_supervised_with, supervised_tasks_health, SupervisedTaskHealth, SupervisorConfig};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+const FAST_RESTARTS: SupervisorConfig = SupervisorConfig {
+    initial_backoff: Duration::from_millis(5),
+    max_backoff: Duration::from_millis(20),
+    stable_after: Duration::from_secs(60),
+    flapping_restarts: 3,
+    flapping_window: Duration::from_secs(600),
+};
+
+fn task_health(name: &str) -> SupervisedTaskHealth {
+    supervised_tasks_health().into_iter().find(|task| task.name == name).expect("task registered")
+}
+
+#[tokio::test]
+async fn test_task_panicking_three_times_runs_on_the_fourth() {
+    let runs = Arc::new(AtomicUsize::new(0));
+    let completed = Arc::new(AtomicUsize::new(0));
+    let supervisor = {
+        let (runs, completed) = (Arc::clone(&runs), Arc::clone(&completed));
+        spawn_supervised_with("panics-three-times", FAST_RESTARTS, move || {
+            let (runs, completed) = (Arc::clone(&runs), Arc::clone(&completed));
+            async move {
+                let run = runs.fetch_add(1, Ordering::SeqCst) + 1;
+                if run <= 3 {
+                    panic!("run {} failed", run);
+                }
+                completed.fetch_add(1, Ordering::SeqCst);
+            }
+        })
+    };
+
+    tokio::time::timeout(Duration::from_secs(5), supervisor).await
+        .expect("supervisor did not finish")
+        .expect("supervisor panicked");
+    assert_eq!(runs.load(Ordering::SeqCst), 4);
+    assert_eq!(completed.load(Ordering::SeqCst), 1);
+
+    let health = task_health("panics-three-times");
+    assert_eq!(health.restarts, 3);
+    assert!(!health.running);
+    assert_eq!(health.last_panic.as_deref(), Some("run 3 failed"));
+    // Three restarts inside the window count as flapping
+    assert!(health.flapping);
+    assert_eq!(health_status(&[health]), "degraded");
+}
+
+#[tokio::test]
+async fn test_single_restart_is_not_flapping() {
+    let runs = Arc::new(AtomicUsize::new(0));
+    let supervisor = {
+        let runs = Arc::clone(&runs);
+        spawn_supervised_with("panics-once", FAST_RESTARTS, move || {
+            let runs = Arc::clone(&runs);
+            async move {
+                if runs.fetch_add(1, Ordering::SeqCst) == 0 {
+                    panic!("first run failed");
+                }
+            }
+        })
+    };
+
+    tokio::time::timeout(Duration::from_secs(5), supervisor).await.unwrap().unwrap();
+    let health = task_health("panics-once");
+    assert_eq!(health.restarts, 1);
+    assert!(!health.flapping);
+    assert_eq!(health_status(&[health]), "ok");
+}
@@ -0,0 +1,95 @@
+use shared::cluster_topology::{ClusterTopology, HostInfo, TopologyConfig};
+use shared::colony_model::Shard;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// The topology is process-wide; the tests of this file take turns swapping it
+static SWAPS: Mutex<()> = Mutex::new(());
+
+const SHARDS: i32 = 16;
+
+/// Every shard on the backend at port, which is also the only backend host
+fn topology(port: u16) -> ClusterTopology {
+    let backend = HostInfo::new("127.0.0.1".to_string(), port);
+    ClusterTopology {
+        coordinator_host: HostInfo::new("127.0.0.1".to_string(), 8090),
+        backend_hosts: vec![backend.clone()],
+        shard_to_host: (0..SHARDS).map(|i| (Shard { x: i * 10, y: 0, width: 10, height: 10 }, backend.clone())).collect(),
+    }
+}
+
+/// A table half from one swap and half from another would mix ports
+fn assert_consistent(topology: &ClusterTopology) {
+    let port = topology.backend_hosts[0].port;
+    assert_eq!(topology.shard_to_host.len(), SHARDS as usize);
+    assert!(topology.shard_to_host.values().all(|host| host.port == port), "torn topology");
+}
+
+#[test]
+fn test_readers_racing_swaps_see_whole_tables_and_rising_epochs() {
+    let _swaps = SWAPS.lock().unwrap_or_else(|e| e.into_inner());
+    ClusterTopology::replace(topology(9000)).unwrap();
+    let done = Arc::new(AtomicBool::new(false));
+    let readers: Vec<_> = (0..4).map(|_| {
+        let done = done.clone();
+        thread::spawn(move || {
+            let mut last_epoch = 0;
+            let mut reads = 0u64;
+            while !done.load(Ordering::Relaxed) {
+                let (topology, epoch) = ClusterTopology::get_instance_with_epoch();
+                assert!(epoch >= last_epoch, "epoch went back from {} to {}", last_epoch, epoch);
+                last_epoch = epoch;
+                if let Some(topology) = topology {
+                    assert_consistent(&topology);
+                }
+                reads += 1;
+            }
+            reads
+        })
+    }).collect();
+
+    // A snapshot taken before the swaps stays as it was
+    let held = ClusterTopology::get_instance().unwrap();
+    let first_epoch = ClusterTopology::topology_epoch();
+    for round in 0..500u16 {
+        if round % 50 == 0 {
+            ClusterTopology::clear().unwrap();
+        } else {
+            ClusterTopology::replace(topology(9001 + round)).unwrap();
+        }
+    }
+    done.store(true, Ordering::Relaxed);
+    for reader in readers {
+        assert!(reader.join().unwrap() > 0);
+    }
+
+    assert_eq!(held.backend_hosts[0].port, 9000);
+    assert_consistent(&held);
+    assert_eq!(ClusterTopology::topology_epoch(), first_epoch + 500);
+    assert_eq!(ClusterTopology::get_instance().unwrap().backend_hosts[0].port, 9001 + 499);
+}
+
+#[test]
+fn test_clear_lets_initialize_run_again() {
+    let _swaps = SWAPS.lock().unwrap_or_else(|e| e.into_inner());
+    ClusterTopology::replace(topology(9100)).unwrap();
+    let config = |port: u16| {
+        let topology = topology(port);
+        TopologyConfig::new(topology.coordinator_host, topology.backend_hosts, topology.shard_to_host)
+    };
+    assert!(ClusterTopology::initialize(config(9101)).is_err());
+
+    let epoch = ClusterTopology::topology_epoch();
+    let cleared = ClusterTopology::clear().unwrap();
+    assert_eq!(cleared.unwrap().backend_hosts[0].port, 9100);
+    assert!(ClusterTopology::get_instance().is_none());
+    assert_eq!(ClusterTopology::topology_epoch(), epoch + 1);
+
+    ClusterTopology::initialize(config(9102)).unwrap();
+    assert_eq!(ClusterTopology::get_instance().unwrap().backend_hosts[0].port, 9102);
+    assert_eq!(ClusterTopology::topology_epoch(), epoch + 2);
+    // Clearing again returns what it dropped, and twice in a row nothing
+    assert!(ClusterTopology::clear().unwrap().is_some());
+    assert!(ClusterTopology::clear().unwrap().is_none());
+}
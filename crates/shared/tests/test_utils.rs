@@ -5,3 +5,19 @@ fn test_generate_colony_instance_id() {
     assert!(id.chars().all(|c| c.is_ascii_lowercase()));
 }
 
+
+#[test]
+fn test_parse_query_param_stops_at_http_version() {
+    let request = "GET /api/colony-events?limit=30&host=a HTTP/1.1\r\nHost: localhost\r\n\r\n";
+    assert_eq!(shared::utils::parse_query_param(request, "limit"), Some("30".to_string()));
+    assert_eq!(shared::utils::parse_query_param(request, "host"), Some("a".to_string()));
+    assert_eq!(shared::utils::parse_query_param(request, "port"), None);
+}
+
+#[test]
+fn test_is_root_page_request() {
+    assert!(shared::utils::is_root_page_request("GET / HTTP/1.1\r\nHost: localhost\r\n\r\n"));
+    assert!(shared::utils::is_root_page_request("GET /?refresh=2 HTTP/1.1\r\n\r\n"));
+    assert!(!shared::utils::is_root_page_request("GET /api/shards HTTP/1.1\r\n\r\n"));
+    assert!(!shared::utils::is_root_page_request("POST / HTTP/1.1\r\n\r\n"));
+}
@@ -0,0 +1,80 @@
+use futures_util::SinkExt;
+use shared::backend_communication::{accept_hello, connect_with_handshake_async, receive_response_async, send_request_and_receive_response_async, send_request_async};
+use shared::be_api::{BackendRequest, BackendResponse, ProtocolVersion, WireHello, WireHelloResponse, PROTOCOL_VERSION};
+use tokio::net::TcpListener;
+use tokio_stream::StreamExt;
+use tokio_util::codec::{Framed, LengthDelimitedCodec};
+
+/// Accepts one connection, runs the handshake and answers Ping requests like a backend
+async fn start_ping_server() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    tokio::spawn(async move {
+        let (socket, peer) = listener.accept().await.unwrap();
+        let mut framed = Framed::new(socket, LengthDelimitedCodec::new());
+        if accept_hello(&mut framed, &peer.to_string()).await.is_none() {
+            return;
+        }
+        while let Some(Ok(bytes)) = framed.next().await {
+            if let Ok(BackendRequest::Ping) = bincode::deserialize(&bytes) {
+                let encoded = bincode::serialize(&BackendResponse::Ping).unwrap();
+                let _ = framed.send(encoded.into()).await;
+            }
+        }
+    });
+    addr
+}
+
+#[tokio::test]
+async fn test_matched_version_handshake_then_requests() {
+    let addr = start_ping_server().await;
+    let mut stream = connect_with_handshake_async(&addr).await.expect("handshake failed");
+    for _ in 0..2 {
+        let response: BackendResponse = send_request_and_receive_response_async(&mut stream, &BackendRequest::Ping).await.unwrap();
+        assert!(matches!(response, BackendResponse::Ping));
+    }
+}
+
+#[tokio::test]
+async fn test_mismatched_major_is_rejected_before_any_request() {
+    let addr = start_ping_server().await;
+    let mut stream = tokio::net::TcpStream::connect(&addr).await.unwrap();
+    let hello = WireHello {
+        protocol_version: ProtocolVersion { major: PROTOCOL_VERSION.major + 1, minor: 0 },
+        build_version: "future".to_string(),
+        ..WireHello::current()
+    };
+    send_request_async(&mut stream, &hello).await.unwrap();
+    match receive_response_async::<WireHelloResponse>(&mut stream).await.unwrap() {
+        WireHelloResponse::Rejected { protocol_version, reason, .. } => {
+            assert_eq!(protocol_version, PROTOCOL_VERSION);
+            assert!(reason.contains("incompatible"), "{}", reason);
+        }
+        other => panic!("Expected rejection, got {:?}", other),
+    }
+    // The server closed the connection, so a request gets no answer
+    let _ = send_request_async(&mut stream, &BackendRequest::Ping).await;
+    assert!(receive_response_async::<BackendResponse>(&mut stream).await.is_err());
+}
+
+#[tokio::test]
+async fn test_request_without_hello_is_rejected() {
+    let addr = start_ping_server().await;
+    let mut stream = tokio::net::TcpStream::connect(&addr).await.unwrap();
+    send_request_async(&mut stream, &BackendRequest::Ping).await.unwrap();
+    let response = receive_response_async::<WireHelloResponse>(&mut stream).await.unwrap();
+    assert!(matches!(response, WireHelloResponse::Rejected { .. }), "{:?}", response);
+}
+
+#[tokio::test]
+async fn test_newer_minor_is_accepted() {
+    let addr = start_ping_server().await;
+    let mut stream = tokio::net::TcpStream::connect(&addr).await.unwrap();
+    let hello = WireHello {
+        protocol_version: ProtocolVersion { minor: PROTOCOL_VERSION.minor + 1, ..PROTOCOL_VERSION },
+        ..WireHello::current()
+    };
+    send_request_async(&mut stream, &hello).await.unwrap();
+    let response = receive_response_async::<WireHelloResponse>(&mut stream).await.unwrap();
+    assert!(matches!(response, WireHelloResponse::Accepted { .. }), "{:?}", response);
+}
@@ -410,7 +410,7 @@ print_status "Testing coordinator HTTP endpoint (health check)..."
 COORDINATOR_URL="http://${COORDINATOR_IP}:${COORDINATOR_HTTP_PORT}/colony-start"
 
 # Try to curl the endpoint with GET (don't fail script on HTTP errors)
-# GET /colony-start returns "Colony-start API" without triggering the actual colony-start process
+# GET /colony-start returns the colony-start status as JSON without triggering the actual colony-start process
 RESPONSE_FILE="/tmp/coordinator_curl_response.txt"
 log_output "Running: curl -X GET $COORDINATOR_URL"
 
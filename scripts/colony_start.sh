@@ -10,6 +10,12 @@ set -e
 AWS_REGION=${AWS_REGION:-"eu-west-1"}
 HTTP_PORT=${HTTP_PORT:-8084}
 LOG_FILE=${LOG_FILE:-""}  # Optional: if provided, will append to this log file
+COLONY_ADMIN_TOKEN=${COLONY_ADMIN_TOKEN:-""}  # Optional: required when the coordinator has auth enabled
+
+AUTH_HEADER_ARGS=()
+if [ -n "$COLONY_ADMIN_TOKEN" ]; then
+    AUTH_HEADER_ARGS=(-H "Authorization: Bearer ${COLONY_ADMIN_TOKEN}")
+fi
 
 # Colors for output
 RED='\033[0;31m'
@@ -79,7 +85,7 @@ call_debug_ssm() {
     TEMP_RESPONSE=$(mktemp)
     TEMP_STDERR=$(mktemp)
     
-    HTTP_CODE=$(curl -s -S -o "$TEMP_RESPONSE" -w "%{http_code}" --max-time 10 -X GET "$DEBUG_URL" 2>"$TEMP_STDERR")
+    HTTP_CODE=$(curl -s -S -o "$TEMP_RESPONSE" -w "%{http_code}" --max-time 10 "${AUTH_HEADER_ARGS[@]}" -X GET "$DEBUG_URL" 2>"$TEMP_STDERR")
     CURL_EXIT_CODE=$?
     
     if [ "$CURL_EXIT_CODE" -eq 0 ] && [ "$HTTP_CODE" -ge 200 ] && [ "$HTTP_CODE" -lt 300 ] && [ -f "$TEMP_RESPONSE" ] && [ -s "$TEMP_RESPONSE" ]; then
@@ -190,7 +196,7 @@ main() {
     TEMP_RESPONSE=$(mktemp)
     TEMP_STDERR=$(mktemp)
     
-    HTTP_CODE=$(curl -s -S -o "$TEMP_RESPONSE" -w "%{http_code}" --max-time 30 -X POST "$COLONY_START_URL" 2>"$TEMP_STDERR")
+    HTTP_CODE=$(curl -s -S -o "$TEMP_RESPONSE" -w "%{http_code}" --max-time 30 "${AUTH_HEADER_ARGS[@]}" -X POST "$COLONY_START_URL" 2>"$TEMP_STDERR")
     CURL_EXIT_CODE=$?
     
     log_output "HTTP Status Code: $HTTP_CODE"